@@ -19,6 +19,7 @@
 //! cargo run --example advanced_queries
 //! ```
 
+use reso_examples::error::ExampleError;
 use reso_examples::{
     load_env, create_client, build_query_by_key, build_query_with_expand,
     build_query_with_select, execute_query,
@@ -90,8 +91,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("{}", serde_json::to_string_pretty(&response)?);
             }
             Err(e) => {
-                println!("Note: Key-based lookup failed: {}", e);
-                println!("This is normal if your RESO server doesn't support direct key access.");
+                let error = ExampleError::from(e);
+                println!("Note: Key-based lookup failed: {}", error);
+                println!("Hint: {}", error.remediation());
             }
         }
     } else {
@@ -123,11 +125,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
-        Err(e) => {
-            println!("\nNote: Expand query failed: {}", e);
-            println!("This is normal if your RESO server doesn't support $expand.");
-            println!("Many RESO servers, including the reference server, don't support expansion.");
-        }
+        Err(e) => match ExampleError::from(e) {
+            ExampleError::Unsupported { feature, .. } => {
+                println!("\nNote: This server doesn't support {}.", feature);
+                println!("Many RESO servers, including the reference server, don't support expansion.");
+            }
+            error => {
+                println!("\nNote: Expand query failed: {}", error);
+                println!("Hint: {}", error.remediation());
+            }
+        },
     }
 
     // Example 3: Complex query combining multiple features
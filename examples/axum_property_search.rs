@@ -18,22 +18,113 @@
 //! ```
 //!
 //! Then open your browser to: http://localhost:3000
+//!
+//! Without credentials, set `RESO_DEMO=1` to serve the bundled sample
+//! dataset of Austin-area listings instead of a RESO feed:
+//!
+//! ```bash
+//! RESO_DEMO=1 cargo run --example axum_property_search
+//! ```
+//!
+//! Results are paged on the server: `page` selects which `limit` results
+//! to fetch with `$skip` and `$top`, in `ListingKey` order unless a sort is
+//! chosen, and the page shows the total number of matches (`$count`) with
+//! previous and next links.
+//!
+//! `sort_by` (price, beds, or date) and `sort_dir` (asc or desc) sort the
+//! results; only those fields are accepted, and the form offers them as
+//! dropdowns.
+//!
+//! Result cards show each listing's first photo as a lazily loaded
+//! thumbnail, found for the whole page with one Media query.
+//!
+//! Each search result links to a detail page at `/property/{ListingKey}` with
+//! the listing's full details, its listing agent, and its photos as a
+//! gallery. The page looks the listing up by key with `Media` and
+//! `ListAgent` expanded, falling back to a plain lookup and a separate Media
+//! query on servers without `$expand`. Clicking a photo opens it in a
+//! lightbox that steps through the rest with the arrow keys. Detail pages
+//! embed schema.org `RealEstateListing` JSON-LD so search engines can show
+//! the price, address, and photos in rich results.
+//!
+//! Every feed request costs quota, so the pages are guarded before they reach
+//! the RESO server: each client IP may make [`REQUESTS_PER_MINUTE`] requests,
+//! overlong URLs are refused, and searches must stay within
+//! [`SEARCH_LIMITS`]. Search results are cached, and a background task
+//! keeps the popular searches ([`POPULAR_CITIES`] by [`PRICE_BANDS`]) fresh
+//! so most visitors are answered without waiting on the feed.
+//!
+//! For orchestration, `/healthz` reports whether the RESO server answers a
+//! probe query (503 when it does not), and `/metrics` exposes the client's
+//! request counts and latencies in the Prometheus text format.
+//!
+//! One deployment can front several MLS feeds: list their dataset ids in
+//! `RESO_DATASETS` (e.g. `actris_ref,har`) and each is searchable at
+//! `/mls/{dataset}/search`, with detail pages under `/mls/{dataset}/property/`.
+//! Every dataset gets its own client from a `ClientPool` and its own result
+//! cache; `RESO_DATASET_<NAME>_BASE_URL` and `RESO_DATASET_<NAME>_TOKEN`
+//! point a dataset at another vendor or credentials.
+//!
+//! For single-page apps, `/api/search` takes the same parameters as
+//! `/search` and answers with JSON: the matches as typed `Property` records
+//! under `data`, and the total, count, and limit under `pagination`. Matches
+//! that are not valid `Property` records are listed under `skipped` with the
+//! reason, so `data` and `total` can be reconciled.
+//! `/api/property/{ListingKey}` returns one listing. Failures come back as
+//! `{"error": {"code": ..., "message": ...}}` with a matching status, and
+//! both routes send CORS headers so pages on other origins can call them.
+//!
+//! The home page shows the listings the visitor opened most recently, from
+//! any dataset. The list lives in a session cookie, so the server keeps no
+//! per-visitor state. Each entry is a key lookup served through the result
+//! cache, and the detail page fills the cache, so the strip rarely costs a
+//! feed request.
+//!
+//! `/map` lets visitors outline an area by clicking points on a map. The
+//! browser posts the points to `/map/search`, which sends the outline's
+//! bounding box to the feed and returns the listings inside the outline
+//! itself, since few RESO servers support geospatial filters.
+//!
+//! Listings link to readable URLs such as
+//! `/homes/123-main-st-austin-tx-78701-ACT1234`; only the `ListingKey` at
+//! the end is used to load the page, so links survive address corrections.
+//! Detail pages name that URL as canonical, including when reached at
+//! `/property/{key}`.
+//! `/sitemap.xml` lists these URLs for the most recently updated active
+//! listings so search engines can find them.
 
 use axum::{
-    extract::{Query, State},
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
     response::{Html, IntoResponse, Response},
-    routing::get,
-    Router,
+    routing::{get, post},
+    Json, Router,
 };
-use reso_client::ResoClient;
-use serde::Deserialize;
+use reso_client::{ClientConfig, QueryBuilder};
 use serde_json::Value as JsonValue;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
-use utoipa::{
-    IntoParams, ToSchema,
-    openapi,
-};
+use reso_examples::filter::Operator;
+use reso_examples::geo::{find_in_polygon, record_coordinates, Polygon};
+use reso_examples::cancel::CancellationToken;
+use reso_examples::client::{HttpClient, ResoApi};
+use reso_examples::demo::DemoClient;
+use reso_examples::error::ExampleError;
+use reso_examples::health::HealthChecker;
+use reso_examples::media::{expanded_media, first_photos, first_photos_query, listing_media, ListingMedia};
+use reso_examples::metrics::RequestMetrics;
+use reso_examples::models::Property;
+use reso_examples::pool::ClientPool;
+use reso_examples::result_cache::{execute_cached, run_cache_warmer, ResultCache};
+use reso_examples::schema_org::{json_ld_script, real_estate_listing};
+use reso_examples::search::{stable_order_by, SearchLimits, SearchParams, SORT_FIELDS, TIEBREAK_FIELD};
+use reso_examples::slug::{listing_slug, parse_listing_slug};
+use utoipa::openapi;
 use utoipa_swagger_ui::SwaggerUi;
 
 // Fields we want to query and display
@@ -46,9 +137,13 @@ const PROPERTY_FIELDS: &[&str] = &[
     "UnparsedAddress",
     "StreetNumber",
     "StreetName",
+    "StreetSuffix",
+    "UnitNumber",
     "City",
     "StateOrProvince",
     "PostalCode",
+    "Latitude",
+    "Longitude",
     "PropertyType",
     "PropertySubType",
     "BedroomsTotal",
@@ -63,44 +158,124 @@ const PROPERTY_FIELDS: &[&str] = &[
     "PublicRemarks",
 ];
 
+/// Fields the detail page shows beyond [`PROPERTY_FIELDS`].
+const DETAIL_FIELDS: &[&str] = &[
+    "OriginalListPrice",
+    "DaysOnMarket",
+    "CountyOrParish",
+    "StoriesTotal",
+    "GarageSpaces",
+    "PoolPrivateYN",
+    "WaterfrontYN",
+    "NewConstructionYN",
+    "ElementarySchool",
+    "MiddleOrJuniorSchool",
+    "HighSchool",
+    "Zoning",
+    "ListAgentKey",
+    "ListAgentFullName",
+    "ListOfficeKey",
+    "ListOfficeName",
+];
+
+/// Related records the detail page expands, where the server supports `$expand`.
+const DETAIL_EXPAND: &[&str] = &["Media", "ListAgent"];
+
+/// Origin of absolute URLs (structured data, canonical links, the sitemap)
+/// when neither `RESO_PUBLIC_ORIGIN` nor the request's headers name one.
+const DEFAULT_ORIGIN: &str = "http://127.0.0.1:3030";
+
+/// Page requests allowed per client IP per minute, with bursts up to the
+/// same number.
+const REQUESTS_PER_MINUTE: u32 = 30;
+
+/// Longest request URI served; longer ones get 414 URI Too Long.
+const MAX_URI_LENGTH: usize = 2048;
+
+/// Caps on searches submitted through the form or query string.
+const SEARCH_LIMITS: SearchLimits = SearchLimits {
+    max_limit: 50,
+    max_filter_terms: 8,
+    max_value_length: 100,
+    max_offset: 1_000,
+};
+
+/// Choices of the sort dropdown, by `sort_by` value (see `SORT_FIELDS`).
+const SORT_OPTIONS: &[(&str, &str)] = &[
+    ("", "Default"),
+    ("price", "Price"),
+    ("beds", "Bedrooms"),
+    ("date", "Listing date"),
+];
+
+/// Choices of the sort direction dropdown.
+const SORT_DIRECTIONS: &[(&str, &str)] = &[("asc", "Ascending"), ("desc", "Descending")];
+
+/// Session cookie listing the properties a visitor opened, most recent first.
+const RECENT_COOKIE: &str = "recently_viewed";
+
+/// Listings shown in the recently viewed strip.
+const RECENT_LIMIT: usize = 6;
+
+/// How long a `/healthz` result is reused before the server is probed again.
+const HEALTH_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// How long search results are served from the cache.
+const CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// How often the popular searches are refreshed; shorter than [`CACHE_TTL`]
+/// so they never expire.
+const WARM_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Pause between the warmer's requests, leaving the feed's rate limit to
+/// interactive searches.
+const WARM_SPACING: Duration = Duration::from_secs(2);
+
+/// Cities whose searches are kept warm; replace with the busiest cities in your feed.
+const POPULAR_CITIES: &[&str] = &["Austin", "Round Rock", "Cedar Park", "Georgetown"];
+
+/// Most points a map search outline may have.
+const MAX_MAP_POINTS: usize = 50;
+
+/// Listings in `/sitemap.xml`, most recently modified first.
+const SITEMAP_LIMIT: u32 = 1000;
+
+/// Fields a sitemap entry is built from.
+const SITEMAP_FIELDS: &[&str] = &[
+    "ListingKey",
+    "UnparsedAddress",
+    "StreetNumber",
+    "StreetName",
+    "StreetSuffix",
+    "UnitNumber",
+    "City",
+    "StateOrProvince",
+    "PostalCode",
+    "ModificationTimestamp",
+];
+
+/// Price bands (min, max) warmed for each popular city, besides the city alone.
+const PRICE_BANDS: &[(&str, &str)] = &[("", "300000"), ("300000", "600000"), ("600000", "")];
+
+/// Facets counted beside search results: a label, the field, the search
+/// parameter that filters on it, and the values the form offers.
+const FACETS: &[(&str, &str, &str, &[&str])] = &[
+    ("Status", "StandardStatus", "status", &["Active", "Pending", "Closed", "Expired"]),
+    ("Property Type", "PropertyType", "property_type", &["Residential", "Commercial", "Land", "Multi-Family"]),
+];
+
 #[derive(Clone)]
 struct AppState {
-    client: Arc<ResoClient>,
-}
-
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
-#[into_params(parameter_in = Query)]
-struct SearchParams {
-    /// City name to filter properties
-    #[serde(default)]
-    city: String,
-    /// State or Province code (e.g., TX, CA)
-    #[serde(default)]
-    state: String,
-    /// Property status (Active, Pending, Closed, Expired)
-    #[serde(default)]
-    status: String,
-    /// Minimum listing price
-    #[serde(default)]
-    min_price: String,
-    /// Maximum listing price
-    #[serde(default)]
-    max_price: String,
-    /// Minimum number of bedrooms
-    #[serde(default)]
-    min_beds: String,
-    /// Maximum number of bedrooms
-    #[serde(default)]
-    max_beds: String,
-    /// Minimum number of bathrooms
-    #[serde(default)]
-    min_baths: String,
-    /// Property type (Residential, Commercial, Land, Multi-Family)
-    #[serde(default)]
-    property_type: String,
-    /// Maximum number of results to return (default: 10, max: 100)
-    #[serde(default)]
-    limit: String,
+    client: Arc<dyn ResoApi>,
+    cache: Arc<ResultCache>,
+    health: Arc<HealthChecker>,
+    metrics: RequestMetrics,
+    /// Clients for the datasets served under `/mls/{dataset}`
+    pool: Arc<ClientPool>,
+    /// A result cache per pooled dataset, since queries are the same across datasets
+    dataset_caches: Arc<HashMap<String, ResultCache>>,
+    /// The site's public address from `RESO_PUBLIC_ORIGIN`, e.g. `https://homes.example.com`
+    public_origin: Option<Arc<str>>,
 }
 
 /// Creates the OpenAPI specification for the API
@@ -167,7 +342,14 @@ fn create_openapi_spec() -> openapi::OpenApi {
         ("max_beds", "Maximum number of bedrooms"),
         ("min_baths", "Minimum number of bathrooms"),
         ("property_type", "Property type: Residential, Commercial, Land, or Multi-Family"),
-        ("limit", "Maximum number of results to return (default: 10, max: 100)"),
+        ("elementary_school", "Assigned elementary school (e.g., Barton Hills)"),
+        ("middle_school", "Assigned middle or junior high school"),
+        ("high_school", "Assigned high school"),
+        ("zoning", "Zoning code (e.g., SF-3)"),
+        ("limit", "Maximum number of results to return (default: 10, max: 50)"),
+        ("sort_by", "Sort field: price, beds, or date (listing date); the server's order when omitted"),
+        ("sort_dir", "Sort direction: asc (default) or desc"),
+        ("page", "Page of results, counting from 1, each `limit` results long (up to the first 1,000 results)"),
     ] {
         search_op = search_op.parameter(
             ParameterBuilder::new()
@@ -191,7 +373,230 @@ fn create_openapi_spec() -> openapi::OpenApi {
             .build()
     );
 
-    paths = paths.path("/search", PathItem::new(HttpMethod::Get, search_op.build()));
+    let search_op = search_op.build();
+    paths = paths.path("/search", PathItem::new(HttpMethod::Get, search_op.clone()));
+
+    // GET /property/{key} - Property detail page
+    let detail_op = OperationBuilder::new()
+        .tag("Properties")
+        .summary(Some("Property Details".to_string()))
+        .description(Some(
+            "Returns an HTML page with a property's details, a photo gallery built from its Media records, and its listing agent. \
+            Media and ListAgent are expanded in the same request where the server supports $expand".to_string()
+        ))
+        .parameter(
+            ParameterBuilder::new()
+                .name("key")
+                .parameter_in(ParameterIn::Path)
+                .description(Some("ListingKey of the property".to_string()))
+                .required(Required::True)
+                .build()
+        )
+        .response(
+            "200",
+            ResponseBuilder::new()
+                .description("HTML property detail page")
+                .content(
+                    "text/html",
+                    ContentBuilder::new()
+                        .build()
+                )
+                .build()
+        )
+        .build();
+    paths = paths.path("/property/{key}", PathItem::new(HttpMethod::Get, detail_op.clone()));
+
+    // GET /homes/{slug} - The detail page at its readable URL
+    let mut slug_op = detail_op.clone();
+    slug_op.summary = Some("Property Details by Slug".to_string());
+    slug_op.parameters = Some(vec![ParameterBuilder::new()
+        .name("slug")
+        .parameter_in(ParameterIn::Path)
+        .description(Some(
+            "Address words followed by the ListingKey, e.g. 123-main-st-austin-tx-78701-ACT1234; only the key is used".to_string()
+        ))
+        .required(Required::True)
+        .build()]);
+    paths = paths.path("/homes/{slug}", PathItem::new(HttpMethod::Get, slug_op.clone()));
+
+    // GET /sitemap.xml - Listing pages for search engines
+    paths = paths.path(
+        "/sitemap.xml",
+        PathItem::new(
+            HttpMethod::Get,
+            OperationBuilder::new()
+                .tag("UI")
+                .summary(Some("Sitemap".to_string()))
+                .description(Some(format!(
+                    "Lists the readable URLs of the {} most recently modified active listings",
+                    SITEMAP_LIMIT
+                )))
+                .response(
+                    "200",
+                    ResponseBuilder::new()
+                        .description("XML sitemap")
+                        .content("application/xml", ContentBuilder::new().build())
+                        .build()
+                )
+                .build()
+        )
+    );
+
+    // GET /map and POST /map/search - Search inside an outline drawn on a map
+    paths = paths.path(
+        "/map",
+        PathItem::new(
+            HttpMethod::Get,
+            OperationBuilder::new()
+                .tag("UI")
+                .summary(Some("Map Search".to_string()))
+                .description(Some("Returns an HTML map on which visitors outline an area to search".to_string()))
+                .response(
+                    "200",
+                    ResponseBuilder::new()
+                        .description("HTML map page")
+                        .content("text/html", ContentBuilder::new().build())
+                        .build()
+                )
+                .build()
+        )
+    );
+    paths = paths.path(
+        "/map/search",
+        PathItem::new(
+            HttpMethod::Post,
+            OperationBuilder::new()
+                .tag("Properties")
+                .summary(Some("Search Inside an Area".to_string()))
+                .description(Some(format!(
+                    "Finds listings inside a polygon. The body is `{{\"points\": [[lat, lon], ...]}}` with 3 to {} points; \
+                    the polygon's bounding box is sent to the RESO server and listings outside the outline are dropped",
+                    MAX_MAP_POINTS
+                )))
+                .request_body(Some(
+                    request_body::RequestBodyBuilder::new()
+                        .content("application/json", ContentBuilder::new().build())
+                        .required(Some(Required::True))
+                        .build()
+                ))
+                .response(
+                    "200",
+                    ResponseBuilder::new()
+                        .description("`listings`: key, address, price, coordinates, and detail page link of each listing")
+                        .content("application/json", ContentBuilder::new().build())
+                        .build()
+                )
+                .response(
+                    "400",
+                    ResponseBuilder::new()
+                        .description("The outline is invalid; `error` says why")
+                        .content("application/json", ContentBuilder::new().build())
+                        .build()
+                )
+                .build()
+        )
+    );
+
+    // GET /api/search and /api/property/{key} - The JSON API
+    let json_response = |description: &str| {
+        ResponseBuilder::new()
+            .description(description)
+            .content("application/json", ContentBuilder::new().build())
+            .build()
+    };
+    let mut api_search_op = OperationBuilder::new()
+        .tag("API")
+        .summary(Some("Search Properties as JSON".to_string()))
+        .description(Some(
+            "Takes the same parameters as /search. Responds with `data`, the matching Property records, and \
+            `pagination`: `total` matches, the `count` returned, the `limit` applied, and the `page` and its `offset`. \
+            Matches that are not valid Property records are left out of `data` and listed in `skipped` as \
+            `{\"ListingKey\": ..., \"error\": ...}`".to_string()
+        ))
+        .response("200", json_response("`data` and `pagination`"))
+        .response("400", json_response("The search is invalid; `error` has a `code` and `message`"))
+        .response("502", json_response("The RESO server failed; `error` has a `code` and `message`"))
+        .build();
+    api_search_op.parameters = search_op.parameters.clone();
+    paths = paths.path("/api/search", PathItem::new(HttpMethod::Get, api_search_op));
+
+    let mut api_detail_op = OperationBuilder::new()
+        .tag("API")
+        .summary(Some("Property as JSON".to_string()))
+        .description(Some("Responds with `data`, the Property record".to_string()))
+        .response("200", json_response("`data`, the Property record"))
+        .response("404", json_response("No listing has this key"))
+        .build();
+    api_detail_op.parameters = detail_op.parameters.clone();
+    paths = paths.path("/api/property/{key}", PathItem::new(HttpMethod::Get, api_detail_op));
+
+    // GET /mls/{dataset}/... - The same pages for each dataset in RESO_DATASETS
+    for (path, mut operation) in [
+        ("/mls/{dataset}/search", search_op),
+        ("/mls/{dataset}/property/{key}", detail_op),
+        ("/mls/{dataset}/homes/{slug}", slug_op),
+    ] {
+        operation.tags = Some(vec!["Datasets".to_string()]);
+        operation.parameters.get_or_insert_with(Vec::new).insert(
+            0,
+            ParameterBuilder::new()
+                .name("dataset")
+                .parameter_in(ParameterIn::Path)
+                .description(Some("Dataset id from RESO_DATASETS (e.g., actris_ref); unknown datasets return 404".to_string()))
+                .required(Required::True)
+                .build(),
+        );
+        paths = paths.path(path, PathItem::new(HttpMethod::Get, operation));
+    }
+
+    // GET /healthz and /metrics - Operations
+    paths = paths.path(
+        "/healthz",
+        PathItem::new(
+            HttpMethod::Get,
+            OperationBuilder::new()
+                .tag("Operations")
+                .summary(Some("Health Check".to_string()))
+                .description(Some(
+                    "Probes the RESO server with a one-record query; results are reused for 30 seconds".to_string()
+                ))
+                .response(
+                    "200",
+                    ResponseBuilder::new()
+                        .description("The RESO server is reachable and accepts the token")
+                        .content("application/json", ContentBuilder::new().build())
+                        .build()
+                )
+                .response(
+                    "503",
+                    ResponseBuilder::new()
+                        .description("The probe query failed; `error` says why")
+                        .content("application/json", ContentBuilder::new().build())
+                        .build()
+                )
+                .build()
+        )
+    );
+    paths = paths.path(
+        "/metrics",
+        PathItem::new(
+            HttpMethod::Get,
+            OperationBuilder::new()
+                .tag("Operations")
+                .summary(Some("Prometheus Metrics".to_string()))
+                .description(Some(
+                    "RESO request counts by resource and status, and request latency histogram".to_string()
+                ))
+                .response(
+                    "200",
+                    ResponseBuilder::new()
+                        .description("Prometheus text exposition format")
+                        .content("text/plain", ContentBuilder::new().build())
+                        .build()
+                )
+                .build()
+        )
+    );
 
     // GET /openapi.json - OpenAPI spec
     paths = paths.path(
@@ -269,6 +674,9 @@ fn create_openapi_spec() -> openapi::OpenApi {
     openapi.tags = Some(vec![
         Tag::new("UI"),
         Tag::new("Properties"),
+        Tag::new("API"),
+        Tag::new("Datasets"),
+        Tag::new("Operations"),
         Tag::new("Documentation"),
     ]);
 
@@ -293,23 +701,77 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("=== RESO Property Search Web Service ===\n");
 
-    // Create RESO client
-    println!("Creating RESO client from environment...");
-    let client = reso_examples::create_client()?;
-    println!("✓ Client created successfully\n");
+    // Create RESO client, recording metrics for every response
+    let metrics = RequestMetrics::default();
+    let (client, pool): (Arc<dyn ResoApi>, ClientPool) = if std::env::var("RESO_DEMO").is_ok_and(|v| v == "1") {
+        println!("Serving the bundled demo dataset (RESO_DEMO=1)...");
+        (Arc::new(DemoClient::new()), ClientPool::new())
+    } else {
+        println!("Creating RESO client from environment...");
+        let client = HttpClient::builder(ClientConfig::from_env()?)
+            .tls_from_env()?
+            .dns_from_env()?
+            .response_hook(metrics.clone())
+            .build()?;
+        let pool = ClientPool::from_env_with(|builder| Ok(builder.response_hook(metrics.clone())))?;
+        (Arc::new(client), pool)
+    };
+    println!("✓ Client created successfully");
+    for dataset in pool.datasets() {
+        println!("  • Dataset {} at /mls/{}/search", dataset, dataset);
+    }
+    println!();
 
     // Build OpenAPI spec
     let openapi = create_openapi_spec();
 
     // Create shared state
     let state = AppState {
-        client: Arc::new(client),
+        client,
+        cache: Arc::new(ResultCache::new(CACHE_TTL, 1000)),
+        health: Arc::new(HealthChecker::new("Property", HEALTH_MAX_AGE)),
+        metrics,
+        dataset_caches: Arc::new(
+            pool.datasets()
+                .map(|dataset| (dataset.to_string(), ResultCache::new(CACHE_TTL, 1000)))
+                .collect(),
+        ),
+        pool: Arc::new(pool),
+        public_origin: std::env::var("RESO_PUBLIC_ORIGIN")
+            .ok()
+            .map(|origin| origin.trim().trim_end_matches('/').to_string())
+            .filter(|origin| !origin.is_empty())
+            .map(Arc::from),
     };
 
+    // Keep popular searches in the cache until shutdown
+    let shutdown = CancellationToken::new();
+    let popular = popular_searches()?;
+    let (client, cache, warmer) = (state.client.clone(), state.cache.clone(), shutdown.clone());
+    tokio::spawn(async move {
+        run_cache_warmer(&client, &cache, &popular, WARM_INTERVAL, WARM_SPACING, &warmer).await;
+    });
+
+    // Per-IP limits cover the pages that query the feed
+    let limiter = Arc::new(IpRateLimiter::new(REQUESTS_PER_MINUTE));
+
     // Build the router
     let app = Router::new()
         .route("/", get(home_page))
         .route("/search", get(search_handler))
+        .route("/property/:key", get(property_detail))
+        .route("/mls/:dataset/search", get(dataset_search))
+        .route("/mls/:dataset/property/:key", get(dataset_property_detail))
+        .route("/homes/:slug", get(property_by_slug))
+        .route("/mls/:dataset/homes/:slug", get(dataset_property_by_slug))
+        .route("/sitemap.xml", get(sitemap))
+        .route("/map", get(map_page))
+        .route("/map/search", post(map_search))
+        .merge(api_routes())
+        .route_layer(middleware::from_fn_with_state(limiter, guard_requests))
+        // Probes are not rate limited
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(metrics_handler))
         .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", openapi.clone()))
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -318,125 +780,789 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3030").await?;
     println!("🚀 Server running at http://127.0.0.1:3030");
     println!("   • Web UI: http://127.0.0.1:3030");
+    println!("   • Map search: http://127.0.0.1:3030/map");
+    println!("   • JSON API: http://127.0.0.1:3030/api/search?city=Austin");
+    println!("   • Sitemap: http://127.0.0.1:3030/sitemap.xml");
     println!("   • Swagger UI: http://127.0.0.1:3030/swagger-ui");
     println!("   • OpenAPI Spec: http://127.0.0.1:3030/openapi.json");
+    println!("   • Health: http://127.0.0.1:3030/healthz");
+    println!("   • Metrics: http://127.0.0.1:3030/metrics");
     println!("   Press Ctrl+C to stop\n");
 
-    axum::serve(listener, app).await?;
+    // Connection info gives the middleware each client's IP address
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            shutdown.cancel();
+        })
+        .await?;
 
     Ok(())
 }
 
-async fn home_page() -> Html<String> {
-    Html(render_search_form(None, None))
+/// The JSON API, readable from pages on any origin.
+fn api_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/search", get(api_search))
+        .route("/api/property/:key", get(api_property_detail))
+        .layer(CorsLayer::new().allow_origin(Any).allow_methods([axum::http::Method::GET]))
+}
+
+/// The queries the search form sends for each popular city and price band.
+fn popular_searches() -> Result<Vec<reso_client::Query>, reso_client::ResoError> {
+    let mut queries = Vec::new();
+    for city in POPULAR_CITIES {
+        for (min_price, max_price) in std::iter::once(&("", "")).chain(PRICE_BANDS) {
+            let params = SearchParams {
+                city: city.to_string(),
+                min_price: min_price.to_string(),
+                max_price: max_price.to_string(),
+                ..Default::default()
+            };
+            // Built exactly as `search_handler` builds them, so the cache keys match
+            queries.push(search_query(params.to_filter()?.as_deref(), None, 0, params.limit()?)?);
+        }
+    }
+    Ok(queries)
+}
+
+/// The query behind a search form submission: the page of `limit` results
+/// after the first `skip`, sorted by `order` when one was chosen and by
+/// `ListingKey` otherwise, with the total in `@odata.count`.
+///
+/// Paging with `$skip` needs a stable order; without one the server may
+/// return a listing on two pages and never on a third. A chosen sort is
+/// therefore followed by `ListingKey`, which breaks ties between equal
+/// prices or dates.
+fn search_query(
+    filter: Option<&str>,
+    order: Option<(&str, &str)>,
+    skip: u32,
+    limit: u32,
+) -> Result<reso_client::Query, reso_client::ResoError> {
+    let (field, direction) = order.unwrap_or((TIEBREAK_FIELD, "asc"));
+    let mut builder = stable_order_by(QueryBuilder::new("Property"), field, direction)
+        .select(PROPERTY_FIELDS)
+        .skip(skip)
+        .top(limit)
+        .with_count();
+    if let Some(filter) = filter {
+        builder = builder.filter(filter);
+    }
+    builder.build()
+}
+
+/// Per-IP token buckets refilling at a fixed rate.
+///
+/// Behind a reverse proxy every request comes from the proxy's address;
+/// key the buckets on `X-Forwarded-For` set by a trusted proxy instead.
+struct IpRateLimiter {
+    per_second: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<IpAddr, (f64, Instant)>>,
+}
+
+impl IpRateLimiter {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            per_second: f64::from(per_minute) / 60.0,
+            burst: f64::from(per_minute),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes one request from `ip`'s bucket, or returns how long until one is available.
+    fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        // Buckets that have refilled completely carry no state worth keeping
+        if buckets.len() > 10_000 {
+            let (per_second, burst) = (self.per_second, self.burst);
+            buckets.retain(|_, (tokens, last)| *tokens + now.duration_since(*last).as_secs_f64() * per_second < burst);
+        }
+
+        let (tokens, last) = buckets.entry(ip).or_insert((self.burst, now));
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.per_second).min(self.burst);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - *tokens) / self.per_second))
+        }
+    }
+}
+
+/// Refuses overlong URLs and clients over their rate limit before any
+/// handler runs.
+async fn guard_requests(
+    State(limiter): State<Arc<IpRateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().to_string().len() > MAX_URI_LENGTH {
+        return (StatusCode::URI_TOO_LONG, "Request URL is too long").into_response();
+    }
+    if let Err(wait) = limiter.check(addr.ip()) {
+        let retry_after = wait.as_secs() + 1;
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            format!("Too many requests; try again in {} seconds", retry_after),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+async fn healthz(State(state): State<AppState>) -> Response {
+    let report = state.health.check(&state.client).await;
+    let status = if report.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, axum::Json(report)).into_response()
+}
+
+async fn metrics_handler(State(state): State<AppState>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+        .into_response()
+}
+
+async fn home_page(State(state): State<AppState>, headers: HeaderMap) -> Html<String> {
+    // Look the listings up concurrently; ones that fail to load are left out
+    let lookups = read_recent(&headers).into_iter().filter_map(|recent| {
+        let (client, cache) = feed(&state, &recent.dataset)?;
+        let query = reso_examples::build_query_by_key("Property", &recent.key, Some(PROPERTY_FIELDS)).ok()?;
+        Some(async move {
+            let property = execute_cached(&client, cache, &query).await.ok()?;
+            Some(RecentView {
+                href: format!("{}/property/{}", recent.base(), percent_encode(&recent.key)),
+                property,
+            })
+        })
+    });
+    let recent: Vec<RecentView> = futures::future::join_all(lookups).await.into_iter().flatten().collect();
+    Html(render_search_form("", &recent, None, None))
 }
 
 async fn search_handler(
     State(state): State<AppState>,
     Query(params): Query<SearchParams>,
 ) -> Response {
-    // Build filter expression from search parameters
-    let mut filters = Vec::new();
+    run_search(&state.client, &state.cache, "", params).await
+}
 
-    if !params.city.is_empty() {
-        filters.push(format!("City eq '{}'", params.city));
+async fn dataset_search(
+    State(state): State<AppState>,
+    Path(dataset): Path<String>,
+    Query(params): Query<SearchParams>,
+) -> Response {
+    match (state.pool.get(&dataset), state.dataset_caches.get(&dataset)) {
+        (Some(client), Some(cache)) => run_search(&client, cache, &dataset_base(&dataset), params).await,
+        _ => unknown_dataset(&dataset),
     }
+}
+
+async fn dataset_property_detail(
+    State(state): State<AppState>,
+    Path((dataset, key)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    show_property(&state, RecentListing { dataset, key }, &headers).await
+}
 
-    if !params.state.is_empty() {
-        filters.push(format!("StateOrProvince eq '{}'", params.state));
+async fn property_by_slug(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    show_property_by_slug(&state, String::new(), &slug, &headers).await
+}
+
+async fn dataset_property_by_slug(
+    State(state): State<AppState>,
+    Path((dataset, slug)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    show_property_by_slug(&state, dataset, &slug, &headers).await
+}
+
+async fn show_property_by_slug(state: &AppState, dataset: String, slug: &str, headers: &HeaderMap) -> Response {
+    match parse_listing_slug(slug) {
+        Some(key) => show_property(state, RecentListing { dataset, key }, headers).await,
+        None => (
+            StatusCode::NOT_FOUND,
+            Html(render_detail_page(
+                "",
+                &public_origin(state, headers),
+                None,
+                &[],
+                Some(&format!("No listing at {}", slug)),
+            )),
+        )
+            .into_response(),
     }
+}
 
-    if !params.status.is_empty() {
-        filters.push(format!("StandardStatus eq '{}'", params.status));
+/// The origin of absolute URLs in pages: `RESO_PUBLIC_ORIGIN` when set,
+/// otherwise the host the request was sent to, as reported by a reverse
+/// proxy's `X-Forwarded-Proto`/`X-Forwarded-Host` or the `Host` header.
+fn public_origin(state: &AppState, headers: &HeaderMap) -> String {
+    if let Some(origin) = &state.public_origin {
+        return origin.to_string();
     }
+    // Proxies list one value per hop; the first is the client's
+    let first = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+    };
+    let host = first("x-forwarded-host")
+        .or_else(|| first("host"))
+        .filter(|host| !host.is_empty() && host.chars().all(|c| c.is_ascii_alphanumeric() || ".-:[]".contains(c)));
+    let Some(host) = host else {
+        return DEFAULT_ORIGIN.to_string();
+    };
+    let scheme = match first("x-forwarded-proto") {
+        Some("https") => "https",
+        _ => "http",
+    };
+    format!("{}://{}", scheme, host)
+}
+
+/// The readable URL path of a listing's detail page, under `base`.
+fn listing_path(base: &str, property: &JsonValue) -> Option<String> {
+    Some(format!("{}/homes/{}", base, percent_encode(&listing_slug(property)?)))
+}
 
-    if !params.min_price.is_empty() {
-        if let Ok(price) = params.min_price.parse::<i64>() {
-            filters.push(format!("ListPrice ge {}", price));
+async fn sitemap(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let query = QueryBuilder::new("Property")
+        .filter("StandardStatus eq 'Active'")
+        .select(SITEMAP_FIELDS)
+        .order_by("ModificationTimestamp", "desc")
+        .top(SITEMAP_LIMIT)
+        .build();
+    let response = match query {
+        Ok(query) => execute_cached(&state.client, &state.cache, &query).await,
+        Err(e) => Err(e),
+    };
+    match response {
+        Ok(response) => {
+            let records = response["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+            (
+                [(header::CONTENT_TYPE, "application/xml")],
+                render_sitemap(&public_origin(&state, &headers), records),
+            )
+                .into_response()
         }
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Error building sitemap: {}", e)).into_response(),
     }
+}
 
-    if !params.max_price.is_empty() {
-        if let Ok(price) = params.max_price.parse::<i64>() {
-            filters.push(format!("ListPrice le {}", price));
+/// The client and result cache serving `dataset`; `""` is the default feed.
+fn feed<'a>(state: &'a AppState, dataset: &str) -> Option<(Arc<dyn ResoApi>, &'a ResultCache)> {
+    if dataset.is_empty() {
+        return Some((state.client.clone(), &state.cache));
+    }
+    let client: Arc<dyn ResoApi> = state.pool.get(dataset)?;
+    Some((client, state.dataset_caches.get(dataset)?))
+}
+
+/// The path prefix of a pooled dataset's pages.
+fn dataset_base(dataset: &str) -> String {
+    format!("/mls/{}", percent_encode(dataset))
+}
+
+fn unknown_dataset(dataset: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Html(render_search_form("", &[], None, Some(&format!("Unknown dataset: {}", dataset)))),
+    )
+        .into_response()
+}
+
+/// Runs a search against `client` and renders the results, with the form
+/// and links pointing under `base` (`""` or `/mls/{dataset}`).
+async fn run_search(client: &impl ResoApi, cache: &ResultCache, base: &str, params: SearchParams) -> Response {
+    // When the browser disconnects, axum drops this handler's future, and
+    // with it the in-flight RESO request, so no upstream work is wasted.
+    // Work spawned onto other tasks would need a `cancel::CancellationToken`.
+    match execute_search(client, cache, &params).await {
+        Ok(response) => {
+            let (facets, photos) = tokio::join!(
+                search_facets(client, cache, &params),
+                search_photos(client, cache, &response)
+            );
+            let results = SearchResults {
+                response,
+                params,
+                facets,
+                photos,
+            };
+            Html(render_search_form(base, &[], Some(&results), None)).into_response()
+        }
+        Err(SearchError::Invalid(e)) => {
+            Html(render_search_form(base, &[], None, Some(&format!("Invalid search: {}", e)))).into_response()
+        }
+        Err(SearchError::Upstream(e)) => {
+            Html(render_search_form(
+                base,
+                &[],
+                None,
+                Some(&format!("Error executing query: {}", e)),
+            ))
+            .into_response()
         }
     }
+}
 
-    if !params.min_beds.is_empty() {
-        if let Ok(beds) = params.min_beds.parse::<i64>() {
-            filters.push(format!("BedroomsTotal ge {}", beds));
+/// Why a search has no results to show.
+enum SearchError {
+    /// The parameters are malformed or exceed [`SEARCH_LIMITS`]
+    Invalid(reso_client::ResoError),
+    /// The RESO server failed to answer
+    Upstream(reso_client::ResoError),
+}
+
+/// Checks a search, runs it through the result cache, and returns the
+/// response with the total number of matches in `@odata.count`.
+async fn execute_search(
+    client: &impl ResoApi,
+    cache: &ResultCache,
+    params: &SearchParams,
+) -> Result<JsonValue, SearchError> {
+    // Check the search against the caps, then translate it into an OData filter
+    let (filter_str, query) = params
+        .check_limits(&SEARCH_LIMITS)
+        .and_then(|_| params.to_filter())
+        .and_then(|f| {
+            let query = search_query(f.as_deref(), params.order_by()?, params.offset()?, params.limit()?)?;
+            Ok((f, query))
+        })
+        .map_err(SearchError::Invalid)?;
+
+    let mut response = execute_cached(client, cache, &query).await.map_err(SearchError::Upstream)?;
+    // The total, needed for the page links, comes with the page; servers
+    // that ignore `$count` get it counted once and cached with the page
+    if response["@odata.count"].as_u64().is_none() {
+        if let Ok(total) = reso_examples::count_records(client, "Property", filter_str.as_deref()).await {
+            response["@odata.count"] = total.into();
+            cache.insert(&query, response.clone());
         }
     }
+    Ok(response)
+}
+
+/// A search's response, with what the results page shows around it.
+struct SearchResults {
+    response: JsonValue,
+    params: SearchParams,
+    facets: Vec<FacetView>,
+    /// Thumbnail of each listing, by `ListingKey`
+    photos: HashMap<String, ListingMedia>,
+}
+
+/// Result counts for each value of one of the [`FACETS`].
+struct FacetView {
+    label: &'static str,
+    param: &'static str,
+    counts: Vec<(&'static str, u64)>,
+}
+
+/// Counts the matches for each facet value. Each facet is counted with the
+/// rest of the search but not its own parameter, so every value shows what
+/// choosing it would find. Facets the feed fails to count are left out.
+async fn search_facets(client: &impl ResoApi, cache: &ResultCache, params: &SearchParams) -> Vec<FacetView> {
+    let lookups = FACETS.iter().map(|&(label, field, param, values)| async move {
+        let mut others = params.clone();
+        *search_param(&mut others, param)? = String::new();
+        let filter = others.to_filter().ok()?;
+        let counts = values.iter().map(|&value| {
+            let clause = Operator::Eq.render(field, value);
+            let filter = match &filter {
+                Some(expr) => format!("({}) and {}", expr, clause),
+                None => clause,
+            };
+            async move { Ok::<_, reso_client::ResoError>((value, cached_count(client, cache, &filter).await?)) }
+        });
+        Some(FacetView {
+            label,
+            param,
+            counts: futures::future::try_join_all(counts).await.ok()?,
+        })
+    });
+    futures::future::join_all(lookups).await.into_iter().flatten().collect()
+}
+
+/// Counts the listings matching `filter` through the result cache, as an
+/// empty page with `$count`. Servers that ignore `$count` get the listings
+/// counted once and cached with the page, as [`execute_search`] does.
+async fn cached_count(client: &impl ResoApi, cache: &ResultCache, filter: &str) -> Result<u64, reso_client::ResoError> {
+    let query = QueryBuilder::new("Property").filter(filter).top(0).with_count().build()?;
+    let mut response = execute_cached(client, cache, &query).await?;
+    if let Some(total) = response["@odata.count"].as_u64() {
+        return Ok(total);
+    }
+    let total = reso_examples::count_records(client, "Property", Some(filter)).await?;
+    response["@odata.count"] = total.into();
+    cache.insert(&query, response);
+    Ok(total)
+}
+
+/// The first photo of each listing in a search response, from one cached
+/// Media query. Feeds without Media access get cards without thumbnails.
+async fn search_photos(client: &impl ResoApi, cache: &ResultCache, response: &JsonValue) -> HashMap<String, ListingMedia> {
+    let keys: Vec<&str> = response["value"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|record| record["ListingKey"].as_str())
+        .collect();
+    if keys.is_empty() {
+        return HashMap::new();
+    }
+    let media = match first_photos_query(&keys) {
+        Ok(query) => execute_cached(client, cache, &query).await,
+        Err(e) => Err(e),
+    };
+    match media {
+        Ok(media) => first_photos(media["value"].as_array().map(Vec::as_slice).unwrap_or_default()),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// The search parameter named `param`, for the ones [`FACETS`] use.
+fn search_param<'a>(params: &'a mut SearchParams, param: &str) -> Option<&'a mut String> {
+    match param {
+        "status" => Some(&mut params.status),
+        "property_type" => Some(&mut params.property_type),
+        _ => None,
+    }
+}
 
-    if !params.max_beds.is_empty() {
-        if let Ok(beds) = params.max_beds.parse::<i64>() {
-            filters.push(format!("BedroomsTotal le {}", beds));
+/// The URL of the search page for `params`, under `base`.
+fn search_url(base: &str, params: &SearchParams) -> String {
+    let fields = match serde_json::to_value(params) {
+        Ok(JsonValue::Object(fields)) => fields,
+        _ => Default::default(),
+    };
+    let query: Vec<String> = fields
+        .iter()
+        .filter_map(|(name, value)| Some((name, value.as_str().filter(|v| !v.is_empty())?)))
+        .map(|(name, value)| format!("{}={}", name, percent_encode(value)))
+        .collect();
+    format!("{}/search?{}", base, query.join("&"))
+}
+
+/// `/api/search`: the search as JSON, for single-page apps.
+///
+/// Responds with `{"data": [Property, ...], "pagination": {...}}`, plus
+/// `skipped` when some matches are not valid `Property` records, or an
+/// [`api_error`] envelope.
+async fn api_search(State(state): State<AppState>, Query(params): Query<SearchParams>) -> Response {
+    match execute_search(&state.client, &state.cache, &params).await {
+        Ok(response) => {
+            let records = response["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+            let mut data = Vec::with_capacity(records.len());
+            let mut skipped = Vec::new();
+            for record in records {
+                match api_property(record) {
+                    Ok(property) => data.push(property),
+                    Err(e) => skipped.push(serde_json::json!({
+                        "ListingKey": record["ListingKey"],
+                        "error": e.to_string(),
+                    })),
+                }
+            }
+            let mut body = serde_json::json!({
+                "data": data,
+                "pagination": {
+                    "total": response["@odata.count"].as_u64().unwrap_or(data.len() as u64),
+                    "count": data.len(),
+                    "limit": params.limit().unwrap_or_default(),
+                    "offset": params.offset().unwrap_or_default(),
+                    "page": params.page().unwrap_or(1),
+                },
+            });
+            if !skipped.is_empty() {
+                body["skipped"] = skipped.into();
+            }
+            Json(body).into_response()
         }
+        Err(SearchError::Invalid(e)) => api_error(StatusCode::BAD_REQUEST, "invalid_search", &e.to_string()),
+        Err(SearchError::Upstream(e)) => upstream_error(e),
     }
+}
 
-    if !params.min_baths.is_empty() {
-        if let Ok(baths) = params.min_baths.parse::<i64>() {
-            filters.push(format!("BathroomsTotalInteger ge {}", baths));
+/// `/api/property/{key}`: one listing as JSON.
+async fn api_property_detail(State(state): State<AppState>, Path(key): Path<String>) -> Response {
+    let property = match reso_examples::build_query_by_key("Property", &key, Some(PROPERTY_FIELDS)) {
+        Ok(query) => execute_cached(&state.client, &state.cache, &query).await,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, "invalid_key", &e.to_string()),
+    };
+    match property {
+        Ok(record) => match api_property(&record) {
+            Ok(property) => Json(serde_json::json!({ "data": property })).into_response(),
+            Err(e) => {
+                upstream_error(reso_client::ResoError::Parse(format!("Listing {} is not a valid Property: {}", key, e)))
+            }
+        },
+        Err(reso_client::ResoError::NotFound { .. }) => {
+            api_error(StatusCode::NOT_FOUND, "not_found", &format!("No listing with key {}", key))
         }
+        Err(e) => upstream_error(e),
     }
+}
+
+/// A record as a typed [`Property`], without OData annotations.
+fn api_property(record: &JsonValue) -> Result<Property, serde_json::Error> {
+    let mut property: Property = serde_json::from_value(record.clone())?;
+    property.other.retain(|name, _| !name.starts_with('@'));
+    Ok(property)
+}
+
+/// The JSON error envelope: `{"error": {"code": ..., "message": ...}}`.
+fn api_error(status: StatusCode, code: &str, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": { "code": code, "message": message } }))).into_response()
+}
 
-    if !params.property_type.is_empty() {
-        filters.push(format!("PropertyType eq '{}'", params.property_type));
+/// An error envelope for a failed RESO request, coded by its cause.
+fn upstream_error(error: reso_client::ResoError) -> Response {
+    let error = ExampleError::from(error);
+    let (status, code) = match &error {
+        ExampleError::QuotaExhausted(_) => (StatusCode::SERVICE_UNAVAILABLE, "quota_exhausted"),
+        ExampleError::Network(_) => (StatusCode::GATEWAY_TIMEOUT, "upstream_unreachable"),
+        ExampleError::MalformedFilter(_) => (StatusCode::BAD_REQUEST, "invalid_search"),
+        _ => (StatusCode::BAD_GATEWAY, "upstream_error"),
+    };
+    api_error(status, code, &error.reso_error().to_string())
+}
+
+async fn map_page() -> Html<String> {
+    Html(render_map_page())
+}
+
+/// The body `/map` posts: the outline's points as `[lat, lon]` pairs.
+#[derive(serde::Deserialize)]
+struct MapSearch {
+    points: Vec<(f64, f64)>,
+}
+
+/// Finds the listings inside the outline and returns them as JSON for the
+/// map to plot.
+async fn map_search(State(state): State<AppState>, Json(search): Json<MapSearch>) -> Response {
+    let bad_request = |message: String| {
+        (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": message }))).into_response()
+    };
+    if search.points.len() > MAX_MAP_POINTS {
+        return bad_request(format!("An outline may have at most {} points", MAX_MAP_POINTS));
     }
+    let polygon = match Polygon::new(search.points) {
+        Ok(polygon) => polygon,
+        Err(e) => return bad_request(e.to_string()),
+    };
 
-    let filter_str = if filters.is_empty() {
-        None
-    } else {
-        Some(filters.join(" and "))
+    match find_in_polygon(&state.client, &polygon, None).await {
+        Ok(records) => {
+            let listings: Vec<JsonValue> = records
+                .iter()
+                .filter_map(|record| {
+                    let key = record["ListingKey"].as_str()?;
+                    let (lat, lon) = record_coordinates(record)?;
+                    Some(serde_json::json!({
+                        "key": key,
+                        "address": record["UnparsedAddress"].as_str().or(record["City"].as_str()).unwrap_or(key),
+                        "price": record["ListPrice"],
+                        "lat": lat,
+                        "lon": lon,
+                        "href": listing_path("", record).unwrap_or_else(|| format!("/property/{}", percent_encode(key))),
+                    }))
+                })
+                .collect();
+            Json(serde_json::json!({ "listings": listings })).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({ "error": format!("Error executing query: {}", e) })),
+        )
+            .into_response(),
+    }
+}
+
+async fn property_detail(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let listing = RecentListing {
+        dataset: String::new(),
+        key,
     };
+    show_property(&state, listing, &headers).await
+}
 
-    // Parse limit or default to 10
-    let limit = params
-        .limit
-        .parse::<u32>()
-        .unwrap_or(10)
-        .min(100); // Cap at 100 results
-
-    // Build and execute query
-    let query = match reso_examples::build_query_with_select(
-        "Property",
-        filter_str.as_deref(),
-        PROPERTY_FIELDS,
-        Some(limit),
-    ) {
-        Ok(q) => q,
-        Err(e) => {
-            return Html(render_search_form(
-                None,
-                Some(&format!("Error building query: {}", e)),
-            ))
-            .into_response();
+/// Loads a property and its photos and renders the detail page, adding the
+/// property to the visitor's recently viewed listings.
+async fn show_property(state: &AppState, listing: RecentListing, headers: &HeaderMap) -> Response {
+    let base = listing.base();
+    let Some((client, cache)) = feed(state, &listing.dataset) else {
+        return unknown_dataset(&listing.dataset);
+    };
+    let key = listing.key.as_str();
+    // Photos and the listing agent come along in one request where the
+    // server supports $expand. Servers that reject it, or ignore it and send
+    // no Media, get the plain lookup and a separate Media query
+    let expanded = match detail_query(key, true) {
+        Ok(query) => execute_cached(&client, cache, &query).await.ok(),
+        Err(_) => None,
+    };
+    let (property, media) = match expanded {
+        Some(property) if property["Media"].is_array() => {
+            let media = expanded_media(&property);
+            (property, media)
         }
+        _ => {
+            let property = match detail_query(key, false) {
+                Ok(query) => execute_cached(&client, cache, &query).await,
+                Err(e) => Err(e),
+            };
+            let property = match property {
+                Ok(property) => property,
+                Err(e) => {
+                    return Html(render_detail_page(
+                        &base,
+                        &public_origin(state, headers),
+                        None,
+                        &[],
+                        Some(&format!("Error loading property {}: {}", key, e)),
+                    ))
+                    .into_response();
+                }
+            };
+            let media = listing_photos(&client, key).await;
+            (property, media)
+        }
+    };
+    let photos: Vec<ListingMedia> = media.into_iter().filter(ListingMedia::is_photo).collect();
+
+    // The home page's recently viewed strip looks listings up with the card
+    // fields; caching those from this record saves it a request
+    if let Ok(query) = reso_examples::build_query_by_key("Property", key, Some(PROPERTY_FIELDS)) {
+        let mut summary = property.clone();
+        if let Some(fields) = summary.as_object_mut() {
+            fields.retain(|name, _| PROPERTY_FIELDS.contains(&name.as_str()));
+        }
+        cache.insert(&query, summary);
+    }
+
+    let mut recent = read_recent(headers);
+    recent.retain(|viewed| *viewed != listing);
+    recent.insert(0, listing);
+    recent.truncate(RECENT_LIMIT);
+    (
+        [(header::SET_COOKIE, recent_cookie(&recent))],
+        Html(render_detail_page(&base, &public_origin(state, headers), Some(&property), &photos, None)),
+    )
+        .into_response()
+}
+
+/// The detail page's lookup of `key`: every field the page shows, with
+/// [`DETAIL_EXPAND`] expanded when `expand` is set.
+fn detail_query(key: &str, expand: bool) -> Result<reso_client::Query, reso_client::ResoError> {
+    let fields: Vec<&str> = PROPERTY_FIELDS.iter().chain(DETAIL_FIELDS).copied().collect();
+    if !expand {
+        return reso_examples::build_query_by_key("Property", key, Some(&fields));
+    }
+    QueryBuilder::by_key("Property", key).select(&fields).expand(DETAIL_EXPAND).build()
+}
+
+/// A listing's media from the Media resource, in display order. A feed
+/// without Media access still shows the listing, just without a gallery.
+async fn listing_photos(client: &impl ResoApi, key: &str) -> Vec<ListingMedia> {
+    let filter = Operator::Eq.render("ResourceRecordKey", key);
+    let media = match reso_examples::build_query_with_order("Media", Some(&filter), "Order", "asc", Some(100)) {
+        Ok(query) => reso_examples::execute_query(client, &query).await,
+        Err(e) => Err(e),
     };
+    match media {
+        Ok(response) => response["value"].as_array().map(|records| listing_media(records)).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
 
-    match reso_examples::execute_query(&state.client, &query).await {
-        Ok(response) => {
-            Html(render_search_form(Some(&response), None)).into_response()
+/// A listing the visitor opened: its dataset (`""` for the default feed) and key.
+#[derive(Debug, Clone, PartialEq)]
+struct RecentListing {
+    dataset: String,
+    key: String,
+}
+
+impl RecentListing {
+    /// The path prefix of the listing's pages.
+    fn base(&self) -> String {
+        if self.dataset.is_empty() {
+            String::new()
+        } else {
+            dataset_base(&self.dataset)
         }
-        Err(e) => {
-            Html(render_search_form(
-                None,
-                Some(&format!("Error executing query: {}", e)),
-            ))
-            .into_response()
+    }
+}
+
+/// A recently viewed listing, loaded for the home page.
+struct RecentView {
+    href: String,
+    property: JsonValue,
+}
+
+/// Reads the recently viewed listings from the request's cookies.
+///
+/// The cookie holds `dataset:key` pairs separated by commas, each part
+/// percent-encoded. Malformed entries are ignored.
+fn read_recent(headers: &HeaderMap) -> Vec<RecentListing> {
+    let value = headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|cookie| cookie.trim().strip_prefix(RECENT_COOKIE)?.strip_prefix('='))
+        .unwrap_or_default();
+    let mut recent = Vec::new();
+    for entry in value.split(',') {
+        let Some((dataset, key)) = entry.split_once(':') else {
+            continue;
+        };
+        if let (Some(dataset), Some(key)) = (percent_decode(dataset), percent_decode(key)) {
+            let listing = RecentListing { dataset, key };
+            if !listing.key.is_empty() && !recent.contains(&listing) {
+                recent.push(listing);
+            }
         }
     }
+    recent.truncate(RECENT_LIMIT);
+    recent
 }
 
-fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> String {
-    let mut html = String::from(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>RESO Property Search</title>
-    <style>
+/// A `Set-Cookie` value storing `recent`. Without `Max-Age` or `Expires`
+/// the cookie ends with the browser session.
+fn recent_cookie(recent: &[RecentListing]) -> String {
+    let value = recent
+        .iter()
+        .map(|listing| format!("{}:{}", percent_encode(&listing.dataset), percent_encode(&listing.key)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{}={}; Path=/; HttpOnly; SameSite=Lax", RECENT_COOKIE, value)
+}
+
+/// Styles shared by every page.
+const PAGE_STYLE: &str = r#"
         * {
             margin: 0;
             padding: 0;
@@ -515,6 +1641,43 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
             margin-bottom: 20px;
             color: #333;
         }
+        .pagination {
+            display: flex;
+            justify-content: center;
+            align-items: center;
+            gap: 20px;
+            margin-top: 20px;
+        }
+        .pagination a {
+            color: #667eea;
+            font-weight: 600;
+            text-decoration: none;
+        }
+        .page-number {
+            color: #555;
+        }
+        .facets {
+            display: flex;
+            flex-wrap: wrap;
+            gap: 30px;
+            margin-bottom: 20px;
+        }
+        .facet h3 {
+            font-size: 14px;
+            margin: 0 0 6px;
+            color: #555;
+        }
+        .facet ul {
+            list-style: none;
+            margin: 0;
+            padding: 0;
+        }
+        .facet li.selected a {
+            font-weight: 600;
+        }
+        .facet-count {
+            color: #888;
+        }
         .property-card {
             border: 1px solid #e0e0e0;
             border-radius: 6px;
@@ -523,6 +1686,35 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
             background: #fafafa;
             transition: box-shadow 0.2s;
         }
+        .listing-facts h2,
+        .listing-agent h2 {
+            font-size: 18px;
+            margin: 20px 0 10px;
+            color: #333;
+        }
+        .listing-agent {
+            padding: 15px 20px;
+            border: 1px solid #e0e0e0;
+            border-radius: 6px;
+            background: #fafafa;
+        }
+        .agent-name {
+            font-size: 16px;
+            font-weight: 600;
+        }
+        .agent-office {
+            color: #666;
+            margin-bottom: 6px;
+        }
+        .property-thumbnail {
+            display: block;
+            width: 100%;
+            height: 240px;
+            object-fit: cover;
+            border-radius: 4px;
+            margin-bottom: 15px;
+            background: #e0e0e0;
+        }
         .property-card:hover {
             box-shadow: 0 4px 8px rgba(0,0,0,0.1);
         }
@@ -608,13 +1800,174 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
             color: #666;
             font-size: 18px;
         }
-    </style>
+        .recent {
+            margin-top: 30px;
+        }
+        .recent h2 {
+            font-size: 18px;
+            margin-bottom: 10px;
+        }
+        .recent-list {
+            display: flex;
+            gap: 10px;
+            overflow-x: auto;
+        }
+        .recent-item {
+            flex: 0 0 200px;
+            padding: 10px 12px;
+            border: 1px solid #ddd;
+            border-radius: 6px;
+            color: inherit;
+            text-decoration: none;
+        }
+        .recent-item:hover {
+            border-color: #007bff;
+        }
+        .recent-address {
+            display: block;
+            font-size: 14px;
+        }
+        .recent-price {
+            font-weight: 600;
+            color: #28a745;
+        }
+        .map-link {
+            margin-bottom: 20px;
+        }
+        .map-link a, .leaflet-popup-content a {
+            color: #007bff;
+        }
+        #map {
+            height: 480px;
+            border-radius: 6px;
+            margin: 15px 0;
+        }
+        .map-actions {
+            display: flex;
+            gap: 10px;
+            align-items: center;
+        }
+        button.secondary {
+            background: #6c757d;
+        }
+        .back-link {
+            display: inline-block;
+            margin-bottom: 20px;
+            color: #007bff;
+            text-decoration: none;
+        }
+        .property-address a {
+            color: inherit;
+            text-decoration: none;
+        }
+        .property-address a:hover {
+            color: #007bff;
+        }
+        .gallery {
+            display: grid;
+            grid-template-columns: repeat(auto-fill, minmax(180px, 1fr));
+            gap: 10px;
+            margin-bottom: 30px;
+        }
+        .gallery figure {
+            margin: 0;
+        }
+        .gallery button {
+            padding: 0;
+            background: none;
+            cursor: zoom-in;
+            width: 100%;
+        }
+        .gallery img {
+            width: 100%;
+            aspect-ratio: 4 / 3;
+            object-fit: cover;
+            border-radius: 4px;
+            display: block;
+        }
+        .gallery figcaption {
+            font-size: 12px;
+            color: #666;
+            margin-top: 4px;
+        }
+        .lightbox {
+            display: none;
+            position: fixed;
+            inset: 0;
+            background: rgba(0,0,0,0.9);
+            z-index: 10;
+            align-items: center;
+            justify-content: center;
+            flex-direction: column;
+        }
+        .lightbox.open {
+            display: flex;
+        }
+        .lightbox img {
+            max-width: 90vw;
+            max-height: 80vh;
+        }
+        .lightbox-caption {
+            color: white;
+            margin-top: 12px;
+            max-width: 90vw;
+            text-align: center;
+        }
+        .lightbox button {
+            position: absolute;
+            background: rgba(255,255,255,0.15);
+            padding: 10px 16px;
+        }
+        .lightbox .close { top: 20px; right: 20px; }
+        .lightbox .prev { left: 20px; top: 50%; }
+        .lightbox .next { right: 20px; top: 50%; }
+    "#;
+
+/// Opens a page: the document head with [`PAGE_STYLE`] and the container div.
+fn page_start(title: &str) -> String {
+    page_start_with(title, "")
+}
+
+/// Like [`page_start`], adding `head` (already escaped markup) to the
+/// document head.
+fn page_start_with(title: &str, head: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{}</title>
+    {}
+    <style>{}</style>
 </head>
 <body>
     <div class="container">
-        <h1>🏠 RESO Property Search</h1>
+"#,
+        html_escape(title),
+        head,
+        PAGE_STYLE
+    )
+}
 
-        <form class="search-form" method="GET" action="/search">
+fn render_search_form(
+    base: &str,
+    recent: &[RecentView],
+    results: Option<&SearchResults>,
+    error: Option<&str>,
+) -> String {
+    let mut html = page_start("RESO Property Search");
+    html.push_str(&format!(
+        r#"
+        <h1>🏠 RESO Property Search</h1>
+        {}
+        <form class="search-form" method="GET" action="{}/search">"#,
+        // The map searches the default feed only
+        if base.is_empty() { r#"<p class="map-link"><a href="/map">🗺️ Or draw an area on a map</a></p>"# } else { "" },
+        html_escape(base)
+    ));
+    html.push_str(
+        r#"
             <div class="form-grid">
                 <div class="form-group">
                     <label for="city">City</label>
@@ -675,8 +2028,13 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
 
                 <div class="form-group">
                     <label for="limit">Results Limit</label>
-                    <input type="number" id="limit" name="limit" value="10" min="1" max="100">
+                    <input type="number" id="limit" name="limit" value="10" min="1" max="50">
                 </div>
+"#,
+    );
+    html.push_str(&render_sort_controls(results.map(|r| &r.params)));
+    html.push_str(
+        r#"
             </div>
 
             <button type="submit">🔍 Search Properties</button>
@@ -684,6 +2042,10 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
 "#,
     );
 
+    if !recent.is_empty() {
+        html.push_str(&render_recent(recent));
+    }
+
     // Add error message if present
     if let Some(err_msg) = error {
         html.push_str(&format!(
@@ -693,14 +2055,27 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
     }
 
     // Add results if present
-    if let Some(response) = results {
+    if let Some(results) = results {
+        let response = &results.response;
         if let Some(records) = response["value"].as_array() {
+            let offset = results.params.offset().unwrap_or(0) as u64;
+            let total = response["@odata.count"].as_u64().unwrap_or(offset + records.len() as u64);
+            let count = if !records.is_empty() && total > records.len() as u64 {
+                format!(
+                    "Showing {}–{} of {} properties",
+                    offset + 1,
+                    offset + records.len() as u64,
+                    total
+                )
+            } else {
+                format!("Found {} propert{}", total, if total == 1 { "y" } else { "ies" })
+            };
             html.push_str(&format!(
                 r#"<div class="results">
-                    <div class="result-count">Found {} propert{}</div>"#,
-                records.len(),
-                if records.len() == 1 { "y" } else { "ies" }
+                    <div class="result-count">{}</div>"#,
+                count
             ));
+            html.push_str(&render_facets(base, results));
 
             if records.is_empty() {
                 html.push_str(
@@ -708,9 +2083,11 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
                 );
             } else {
                 for record in records {
-                    html.push_str(&render_property_card(record));
+                    let photo = record["ListingKey"].as_str().and_then(|key| results.photos.get(key));
+                    html.push_str(&render_property_card(base, record, photo));
                 }
             }
+            html.push_str(&render_pagination(base, &results.params, total));
 
             html.push_str("</div>");
         }
@@ -726,9 +2103,414 @@ fn render_search_form(results: Option<&JsonValue>, error: Option<&str>) -> Strin
     html
 }
 
-fn render_property_card(property: &JsonValue) -> String {
+/// Renders the facet counts as links that narrow the search to one value,
+/// or, for the value already chosen, widen it again.
+fn render_facets(base: &str, results: &SearchResults) -> String {
+    if results.facets.is_empty() {
+        return String::new();
+    }
+    let mut html = String::from(r#"<div class="facets">"#);
+    for facet in &results.facets {
+        html.push_str(&format!(r#"<div class="facet"><h3>{}</h3><ul>"#, html_escape(facet.label)));
+        for &(value, count) in &facet.counts {
+            let mut params = results.params.clone();
+            let Some(current) = search_param(&mut params, facet.param) else {
+                continue;
+            };
+            let selected = current.as_str() == value;
+            *current = if selected { String::new() } else { value.to_string() };
+            // The narrowed search starts over at its first page
+            params.page = String::new();
+            html.push_str(&format!(
+                r#"<li{}><a href="{}">{}</a> <span class="facet-count">({})</span></li>"#,
+                if selected { r#" class="selected""# } else { "" },
+                html_escape(&search_url(base, &params)),
+                html_escape(value),
+                count
+            ));
+        }
+        html.push_str("</ul></div>");
+    }
+    html.push_str("</div>");
+    html
+}
+
+/// Renders the sort dropdowns, with the current search's order selected.
+fn render_sort_controls(params: Option<&SearchParams>) -> String {
+    let (sort_by, sort_dir) = match params.map(SearchParams::order_by) {
+        Some(Ok(Some((field, direction)))) => {
+            let key = SORT_FIELDS.iter().find(|(_, f)| *f == field).map_or("", |(key, _)| *key);
+            (key, direction)
+        }
+        _ => ("", "asc"),
+    };
+    let options = |choices: &[(&str, &str)], current: &str| {
+        choices
+            .iter()
+            .map(|&(value, label)| {
+                format!(
+                    r#"<option value="{}"{}>{}</option>"#,
+                    value,
+                    if value == current { " selected" } else { "" },
+                    label
+                )
+            })
+            .collect::<String>()
+    };
+    format!(
+        r#"
+                <div class="form-group">
+                    <label for="sort_by">Sort By</label>
+                    <select id="sort_by" name="sort_by">{}</select>
+                </div>
+
+                <div class="form-group">
+                    <label for="sort_dir">Order</label>
+                    <select id="sort_dir" name="sort_dir">{}</select>
+                </div>
+"#,
+        options(SORT_OPTIONS, sort_by),
+        options(SORT_DIRECTIONS, sort_dir)
+    )
+}
+
+/// Renders previous and next links around "Page 2 of 25". Pages past
+/// [`SEARCH_LIMITS`]'s `max_offset` are not linked, since they are refused.
+fn render_pagination(base: &str, params: &SearchParams, total: u64) -> String {
+    let (Ok(page), Ok(limit)) = (params.page(), params.limit()) else {
+        return String::new();
+    };
+    let pages = total.div_ceil(limit as u64).max(1);
+    if pages == 1 && page == 1 {
+        return String::new();
+    }
+    let link = |page: u32, label: &str| {
+        let params = SearchParams {
+            page: page.to_string(),
+            ..params.clone()
+        };
+        format!(r#"<a href="{}">{}</a>"#, html_escape(&search_url(base, &params)), label)
+    };
+
+    let mut html = String::from(r#"<div class="pagination">"#);
+    if page > 1 {
+        html.push_str(&link(page - 1, "← Previous"));
+    }
+    html.push_str(&format!(r#"<span class="page-number">Page {} of {}</span>"#, page, pages));
+    let next_offset = page as u64 * limit as u64;
+    if next_offset < total && next_offset <= SEARCH_LIMITS.max_offset as u64 {
+        html.push_str(&link(page + 1, "Next →"));
+    }
+    html.push_str("</div>");
+    html
+}
+
+/// Renders the recently viewed listings as a row of compact links.
+fn render_recent(recent: &[RecentView]) -> String {
+    let mut html = String::from(r#"<div class="recent"><h2>Recently viewed</h2><div class="recent-list">"#);
+    for view in recent {
+        let property = &view.property;
+        let address = property["UnparsedAddress"]
+            .as_str()
+            .or(property["ListingKey"].as_str())
+            .unwrap_or("Listing");
+        html.push_str(&format!(
+            r#"<a class="recent-item" href="{}"><span class="recent-address">{}</span>"#,
+            html_escape(&view.href),
+            html_escape(address)
+        ));
+        if let Some(price) = property["ListPrice"].as_f64() {
+            html.push_str(&format!(r#"<span class="recent-price">${:.0}</span>"#, price));
+        }
+        html.push_str("</a>");
+    }
+    html.push_str("</div></div>");
+    html
+}
+
+/// The map page: visitors click points to outline an area, and the
+/// listings `/map/search` finds inside it are plotted as markers.
+fn render_map_page() -> String {
+    let mut html = page_start("RESO Map Search");
+    html.push_str(
+        r#"<link rel="stylesheet" href="https://unpkg.com/leaflet@1.9.4/dist/leaflet.css">
+        <script src="https://unpkg.com/leaflet@1.9.4/dist/leaflet.js"></script>
+        <a href="/" class="back-link">← Back to Search</a>
+        <h1>🗺️ Search an Area</h1>
+        <p>Click the map to outline an area, then search it.</p>
+        <div id="map"></div>
+        <div class="map-actions">
+            <button type="button" onclick="searchArea()">🔍 Search This Area</button>
+            <button type="button" class="secondary" onclick="clearArea()">Clear</button>
+            <span id="map-status" role="status"></span>
+        </div>
+        <script>
+            const maxPoints = MAX_POINTS;
+            const map = L.map('map').setView([30.2672, -97.7431], 12);
+            L.tileLayer('https://tile.openstreetmap.org/{z}/{x}/{y}.png', {
+                maxZoom: 19,
+                attribution: '&copy; OpenStreetMap contributors'
+            }).addTo(map);
+            const outline = L.polygon([], { color: '#007bff' }).addTo(map);
+            const markers = L.layerGroup().addTo(map);
+            let points = [];
+
+            function showStatus(text) { document.getElementById('map-status').textContent = text; }
+            map.on('click', e => {
+                if (points.length >= maxPoints) {
+                    showStatus(`An outline may have at most ${maxPoints} points`);
+                    return;
+                }
+                points.push([e.latlng.lat, e.latlng.lng]);
+                outline.setLatLngs(points);
+            });
+            function clearArea() {
+                points = [];
+                outline.setLatLngs([]);
+                markers.clearLayers();
+                showStatus('');
+            }
+            async function searchArea() {
+                if (points.length < 3) {
+                    showStatus('Click at least three points to outline an area');
+                    return;
+                }
+                showStatus('Searching…');
+                const response = await fetch('/map/search', {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({ points })
+                });
+                const body = await response.json().catch(() => ({ error: `Search failed (${response.status})` }));
+                markers.clearLayers();
+                if (!response.ok) {
+                    showStatus(body.error);
+                    return;
+                }
+                for (const listing of body.listings) {
+                    const link = document.createElement('a');
+                    link.href = listing.href;
+                    link.textContent = listing.price == null
+                        ? listing.address
+                        : `${listing.address} – $${Math.round(listing.price).toLocaleString()}`;
+                    L.marker([listing.lat, listing.lon]).bindPopup(link).addTo(markers);
+                }
+                showStatus(`Found ${body.listings.length} propert${body.listings.length === 1 ? 'y' : 'ies'} in the area`);
+            }
+        </script>"#
+            .replace("MAX_POINTS", &MAX_MAP_POINTS.to_string())
+            .as_str(),
+    );
+    html.push_str(
+        r#"
+    </div>
+</body>
+</html>"#,
+    );
+    html
+}
+
+fn render_detail_page(
+    base: &str,
+    origin: &str,
+    property: Option<&JsonValue>,
+    photos: &[ListingMedia],
+    error: Option<&str>,
+) -> String {
+    let title = property
+        .and_then(|p| p["UnparsedAddress"].as_str())
+        .unwrap_or("Property Details");
+    let url = property.map(|property| {
+        let key = property["ListingKey"].as_str().unwrap_or_default();
+        let path = listing_path(base, property).unwrap_or_else(|| format!("{}/property/{}", base, percent_encode(key)));
+        format!("{}{}", origin, path)
+    });
+    // The page is served at both /property/{key} and /homes/{slug}; search
+    // engines should index only the readable URL
+    let canonical = url
+        .as_deref()
+        .map(|url| format!(r#"<link rel="canonical" href="{}">"#, html_escape(url)))
+        .unwrap_or_default();
+    let mut html = page_start_with(title, &canonical);
+    html.push_str(r#"<a class="back-link" href="javascript:history.back()">← Back to results</a>"#);
+
+    if let Some(err_msg) = error {
+        html.push_str(&format!(
+            r#"<div class="error">⚠️ <strong>Error:</strong> {}</div>"#,
+            html_escape(err_msg)
+        ));
+    }
+    if let (Some(property), Some(url)) = (property, &url) {
+        html.push_str(&json_ld_script(&real_estate_listing(property, url, photos)));
+    }
+    if !photos.is_empty() {
+        html.push_str(&render_gallery(photos));
+    }
+    if let Some(property) = property {
+        html.push_str(&render_property_card(base, property, None));
+        html.push_str(&render_listing_facts(property));
+        html.push_str(&render_listing_agent(property));
+    }
+
+    html.push_str(
+        r#"
+    </div>
+</body>
+</html>"#,
+    );
+    html
+}
+
+/// Renders the detail page's fields beyond the card's, in the card's grid.
+fn render_listing_facts(property: &JsonValue) -> String {
+    let text = |field: &str| property[field].as_str().filter(|v| !v.is_empty()).map(str::to_string);
+    let number = |field: &str| property[field].as_f64().map(|v| format!("{}", v));
+    let flag = |field: &str| property[field].as_bool().map(|v| if v { "Yes" } else { "No" }.to_string());
+    let facts: Vec<(&str, Option<String>)> = vec![
+        ("Original Price", property["OriginalListPrice"].as_f64().map(|v| format!("${:.0}", v))),
+        ("Days on Market", number("DaysOnMarket")),
+        ("County", text("CountyOrParish")),
+        ("Stories", number("StoriesTotal")),
+        ("Garage Spaces", number("GarageSpaces")),
+        ("Private Pool", flag("PoolPrivateYN")),
+        ("Waterfront", flag("WaterfrontYN")),
+        ("New Construction", flag("NewConstructionYN")),
+        ("Elementary School", text("ElementarySchool")),
+        ("Middle School", text("MiddleOrJuniorSchool")),
+        ("High School", text("HighSchool")),
+        ("Zoning", text("Zoning")),
+    ];
+    let items: String = facts
+        .into_iter()
+        .filter_map(|(label, value)| {
+            Some(format!(
+                r#"<div class="detail-item"><div class="detail-label">{}</div><div class="detail-value">{}</div></div>"#,
+                label,
+                html_escape(&value?)
+            ))
+        })
+        .collect();
+    if items.is_empty() {
+        return String::new();
+    }
+    format!(r#"<div class="listing-facts"><h2>Property Facts</h2><div class="property-details">{}</div></div>"#, items)
+}
+
+/// Renders the listing agent from the expanded `ListAgent` Member record,
+/// or from the listing's own `ListAgentFullName` when it was not expanded.
+fn render_listing_agent(property: &JsonValue) -> String {
+    let agent = &property["ListAgent"];
+    let Some(name) = agent["MemberFullName"]
+        .as_str()
+        .or(property["ListAgentFullName"].as_str())
+        .filter(|name| !name.is_empty())
+    else {
+        return String::new();
+    };
+    let mut html = format!(
+        r#"<div class="listing-agent"><h2>Listed by</h2><div class="agent-name">{}</div>"#,
+        html_escape(name)
+    );
+    if let Some(office) = agent["OfficeName"].as_str().or(property["ListOfficeName"].as_str()) {
+        html.push_str(&format!(r#"<div class="agent-office">{}</div>"#, html_escape(office)));
+    }
+    if let Some(phone) = agent["MemberPreferredPhone"].as_str() {
+        html.push_str(&format!(
+            r#"<div><a href="tel:{}">{}</a></div>"#,
+            html_escape(phone),
+            html_escape(phone)
+        ));
+    }
+    if let Some(email) = agent["MemberEmail"].as_str() {
+        html.push_str(&format!(
+            r#"<div><a href="mailto:{}">{}</a></div>"#,
+            html_escape(email),
+            html_escape(email)
+        ));
+    }
+    html.push_str("</div>");
+    html
+}
+
+/// Renders photos in the agent's order as a grid of captioned thumbnails,
+/// plus a lightbox that shows them full size.
+fn render_gallery(photos: &[ListingMedia]) -> String {
+    let mut html = String::from(r#"<div class="gallery">"#);
+    for (i, photo) in photos.iter().enumerate() {
+        let alt = photo.alt_text(&format!("Photo {} of {}", i + 1, photos.len()));
+        let caption = photo.caption.as_deref().or(photo.label.as_deref());
+        html.push_str(&format!(
+            r#"<figure>
+                <button type="button" onclick="openLightbox({})" aria-label="View {}">
+                    <img src="{}" alt="{}" loading="lazy">
+                </button>
+                {}
+            </figure>"#,
+            i,
+            html_escape(&alt),
+            html_escape(&photo.url),
+            html_escape(&alt),
+            caption
+                .map(|c| format!("<figcaption>{}</figcaption>", html_escape(c)))
+                .unwrap_or_default()
+        ));
+    }
+    html.push_str("</div>");
+
+    // The lightbox reads each photo's URL and alt text from the thumbnails
+    html.push_str(
+        r#"<div class="lightbox" id="lightbox" role="dialog" aria-modal="true" aria-label="Photo viewer">
+            <button type="button" class="close" onclick="closeLightbox()" aria-label="Close">✕</button>
+            <button type="button" class="prev" onclick="stepLightbox(-1)" aria-label="Previous photo">‹</button>
+            <img id="lightbox-image" src="" alt="">
+            <div class="lightbox-caption" id="lightbox-caption"></div>
+            <button type="button" class="next" onclick="stepLightbox(1)" aria-label="Next photo">›</button>
+        </div>
+        <script>
+            const photos = Array.from(document.querySelectorAll('.gallery img'));
+            const lightbox = document.getElementById('lightbox');
+            let current = 0;
+            function openLightbox(i) {
+                current = (i + photos.length) % photos.length;
+                const image = document.getElementById('lightbox-image');
+                image.src = photos[current].src;
+                image.alt = photos[current].alt;
+                document.getElementById('lightbox-caption').textContent =
+                    `${photos[current].alt} (${current + 1} / ${photos.length})`;
+                lightbox.classList.add('open');
+            }
+            function stepLightbox(delta) { openLightbox(current + delta); }
+            function closeLightbox() { lightbox.classList.remove('open'); }
+            lightbox.addEventListener('click', e => { if (e.target === lightbox) closeLightbox(); });
+            document.addEventListener('keydown', e => {
+                if (!lightbox.classList.contains('open')) return;
+                if (e.key === 'Escape') closeLightbox();
+                if (e.key === 'ArrowLeft') stepLightbox(-1);
+                if (e.key === 'ArrowRight') stepLightbox(1);
+            });
+        </script>"#,
+    );
+    html
+}
+
+fn render_property_card(base: &str, property: &JsonValue, photo: Option<&ListingMedia>) -> String {
     let mut card = String::from(r#"<div class="property-card">"#);
 
+    // Thumbnails load as they scroll into view, so a long page of results
+    // doesn't fetch every photo up front
+    if let Some(photo) = photo {
+        let alt = photo.alt_text(property["UnparsedAddress"].as_str().unwrap_or("Listing photo"));
+        let img = format!(
+            r#"<img class="property-thumbnail" src="{}" alt="{}" loading="lazy" decoding="async">"#,
+            html_escape(&photo.url),
+            html_escape(&alt)
+        );
+        card.push_str(&match listing_path(base, property) {
+            Some(path) => format!(r#"<a href="{}">{}</a>"#, html_escape(&path), img),
+            None => img,
+        });
+    }
+
     // Header with address and price
     card.push_str(r#"<div class="property-header">"#);
 
@@ -763,9 +2545,14 @@ fn render_property_card(property: &JsonValue) -> String {
         address.to_string()
     };
 
+    // Link the address to the detail page
+    let address_html = match listing_path(base, property) {
+        Some(path) => format!(r#"<a href="{}">{}</a>"#, html_escape(&path), html_escape(&full_address)),
+        None => html_escape(&full_address),
+    };
     card.push_str(&format!(
         r#"<div class="property-address">{}</div>"#,
-        html_escape(&full_address)
+        address_html
     ));
 
     if let Some(price) = property["ListPrice"].as_f64() {
@@ -868,10 +2655,55 @@ fn render_property_card(property: &JsonValue) -> String {
     card
 }
 
+/// Renders an XML sitemap of the listings' readable URLs under `origin`,
+/// with each listing's `ModificationTimestamp` as its last modification.
+fn render_sitemap(origin: &str, records: &[JsonValue]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for record in records {
+        let Some(path) = listing_path("", record) else { continue };
+        xml.push_str(&format!("  <url><loc>{}</loc>", html_escape(&format!("{}{}", origin, path))));
+        if let Some(modified) = record["ModificationTimestamp"].as_str() {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", html_escape(modified)));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
         .replace('"', "&quot;")
         .replace('\'', "&#x27;")
-}
\ No newline at end of file
+}
+
+/// Percent-encodes a path segment.
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Reverses [`percent_encode`], or `None` if `s` is not valid percent-encoded UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
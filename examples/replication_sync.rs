@@ -20,6 +20,9 @@
 //! cargo run --example replication_sync
 //! ```
 //!
+//! Set `NOMINATIM_USER_AGENT` to fill in missing coordinates on the first
+//! batch using the OpenStreetMap Nominatim geocoder.
+//!
 //! ## Note
 //!
 //! Not all RESO servers support replication endpoints. If your server doesn't
@@ -28,6 +31,8 @@
 use reso_examples::{
     load_env, create_client, build_replication_query, execute_replication_query,
 };
+use reso_examples::error::ExampleError;
+use reso_examples::geocode::{fill_missing_coordinates, NominatimGeocoder};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -54,10 +59,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
 
     match execute_replication_query(&client, &replication_query).await {
-        Ok(response) => {
+        Ok(mut response) => {
             println!("✓ Replication query executed successfully!");
             println!("  Records in this batch: {}", response.records.len());
 
+            // Optional pipeline step: geocode records the feed left without coordinates
+            if let Ok(user_agent) = std::env::var("NOMINATIM_USER_AGENT") {
+                let geocoder = NominatimGeocoder::new(user_agent);
+                match fill_missing_coordinates(&geocoder, &mut response.records).await {
+                    Ok(stats) => println!(
+                        "  Geocoded {} records ({} already had coordinates, {} unmatched)",
+                        stats.geocoded, stats.skipped, stats.unmatched
+                    ),
+                    Err(e) => println!("  Geocoding failed: {}", e),
+                }
+            }
+
             if let Some(link) = &response.next_link {
                 println!("  Next link available: {}", link);
                 println!("  More records are available - use link for next batch");
@@ -94,13 +111,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         Err(e) => {
-            println!("\n❌ Replication query failed: {}", e);
-            println!("\nThis is normal if:");
-            println!("  - Your RESO server doesn't support replication endpoints");
-            println!("  - Your credentials don't have replication permissions");
-            println!("  - The server requires additional authorization for replication");
-            println!("\nAlternative: Use standard queries with pagination:");
-            println!("  cargo run --example query_properties");
+            let error = ExampleError::from(e);
+            println!("\n❌ Replication query failed: {}", error);
+            if let Some(odata) = error.odata() {
+                println!("  Server error code: {}", odata.code.as_deref().unwrap_or("(none)"));
+            }
+            println!("\nHint: {}", error.remediation());
+            if matches!(error, ExampleError::Unsupported { .. } | ExampleError::Auth(_)) {
+                println!("\nAlternative: Use standard queries with pagination:");
+                println!("  cargo run --example query_properties");
+            }
         }
     }
 
@@ -123,7 +143,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         Err(e) => {
-            println!("Note: Full replication query failed: {}", e);
+            println!("Note: Full replication query failed: {}", ExampleError::from(e));
         }
     }
 
@@ -0,0 +1,108 @@
+//! Example: Scheduled Sync Daemon
+//!
+//! This example keeps local JSONL copies of several RESO resources up to
+//! date, replicating each one on its own schedule until interrupted.
+//!
+//! Each resource is written to `<dir>/<Resource>.jsonl` with a checkpoint in
+//! `<dir>/<Resource>.checkpoint.json`. The first run is a full load; later
+//! runs only fetch records modified since the last one. Ctrl-C stops the
+//! running sync between batches with its checkpoint saved, so restarting the
+//! daemon picks up where it stopped.
+//!
+//! ## Setup
+//!
+//! 1. Copy `.env.example` to `.env`
+//! 2. Fill in your RESO credentials:
+//!    - RESO_BASE_URL: Your RESO API base URL
+//!    - RESO_TOKEN: Your bearer authentication token
+//!    - RESO_DATASET_ID: (optional) Dataset identifier
+//!
+//! Optional settings:
+//!    - SYNC_DIR: Output directory (default `sync`)
+//!    - SLACK_WEBHOOK_URL: Post alerts for failed or unusual runs to Slack
+//!
+//! ## Usage
+//!
+//! ```bash
+//! cargo run --example sync_daemon
+//! ```
+//!
+//! ## Note
+//!
+//! The server must support replication endpoints for each resource synced.
+
+use async_trait::async_trait;
+use reso_client::ResoError;
+use reso_examples::cancel::{cancel_on_ctrl_c, CancellationToken};
+use reso_examples::export::JsonlSink;
+use reso_examples::schedule::{Alert, ReplicationJob, Scheduler, SlackNotifier, SyncJob};
+use reso_examples::sync::{Checkpoint, ReplicationStats};
+use reso_examples::{create_client, load_env};
+use std::path::Path;
+use std::time::Duration;
+
+/// Resources to sync and how often, in minutes.
+const SCHEDULES: &[(&str, u64)] = &[("Property", 15), ("Member", 24 * 60), ("Office", 24 * 60)];
+
+/// Prints the outcome of every run of the job it wraps.
+struct Logged<J> {
+    name: String,
+    job: J,
+}
+
+#[async_trait]
+impl<J: SyncJob> SyncJob for Logged<J> {
+    async fn run(&mut self, token: &CancellationToken) -> Result<ReplicationStats, ResoError> {
+        println!("[{}] syncing...", self.name);
+        let result = self.job.run(token).await;
+        match &result {
+            Ok(stats) => println!("[{}] {}", self.name, stats),
+            Err(e) => println!("[{}] stopped: {}", self.name, e),
+        }
+        result
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load environment variables from .env file
+    load_env()?;
+
+    println!("=== RESO Sync Daemon Example ===\n");
+
+    let dir = std::env::var("SYNC_DIR").unwrap_or_else(|_| "sync".to_string());
+    std::fs::create_dir_all(&dir)?;
+
+    let mut scheduler = Scheduler::new(Duration::from_secs(3600))
+        .on_alert(|alert: &Alert| eprintln!("ALERT: {}", alert));
+    if let Ok(webhook) = std::env::var("SLACK_WEBHOOK_URL") {
+        scheduler = scheduler.on_alert(SlackNotifier::new(webhook));
+    }
+
+    for &(resource, minutes) in SCHEDULES {
+        let dir = Path::new(&dir);
+        let job = ReplicationJob::new(
+            create_client()?,
+            resource,
+            None,
+            JsonlSink::append(dir.join(format!("{}.jsonl", resource)))?,
+            Checkpoint::load(dir.join(format!("{}.checkpoint.json", resource)), resource)?,
+        );
+        println!("✓ {} every {} minutes", resource, minutes);
+        scheduler = scheduler.job_every(
+            resource,
+            Duration::from_secs(minutes * 60),
+            Logged {
+                name: resource.to_string(),
+                job,
+            },
+        );
+    }
+
+    println!("\nSyncing into {}/ (Ctrl-C to stop)\n", dir);
+    let token = cancel_on_ctrl_c();
+    scheduler.run(&token).await;
+
+    println!("\nStopped. Checkpoints are saved; run again to resume.");
+    Ok(())
+}
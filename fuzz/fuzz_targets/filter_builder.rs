@@ -0,0 +1,55 @@
+//! Builds filters from arbitrary field names and values and runs them
+//! against the demo server, whose parser must accept every filter the
+//! builder lets through.
+//!
+//! ```bash
+//! cargo +nightly fuzz run filter_builder
+//! ```
+
+#![no_main]
+
+use futures::executor::block_on;
+use libfuzzer_sys::fuzz_target;
+use reso_client::ResoError;
+use reso_examples::demo::DemoClient;
+use reso_examples::filter::{FilterBuilder, Operator};
+use reso_examples::{build_query, execute_query};
+use std::sync::OnceLock;
+
+static CLIENT: OnceLock<DemoClient> = OnceLock::new();
+
+fuzz_target!(|input: (String, Vec<(u8, u8, bool, String, f64)>)| {
+    let (field, terms) = input;
+    let mut filter: Option<FilterBuilder> = None;
+    for (kind, operator, or, value, number) in terms.into_iter().take(16) {
+        let term = match kind % 6 {
+            0 => FilterBuilder::eq(&field, value.as_str()),
+            1 => FilterBuilder::starts_with("City", &value),
+            2 => FilterBuilder::in_list("PostalCode", &[value.as_str(), "78701"]),
+            3 => {
+                // eq through le; the string and list operators do not take a number
+                let comparisons = &Operator::ALL[..6];
+                let operator = comparisons[operator as usize % comparisons.len()];
+                FilterBuilder::compare("ListPrice", operator, &number)
+            }
+            4 => !FilterBuilder::contains("ListOffice/OfficeName", &value),
+            _ => FilterBuilder::ne("StandardStatus", value.as_str()),
+        };
+        filter = Some(match filter {
+            None => term,
+            Some(f) if or => f.or(term),
+            Some(f) => f.and(term),
+        });
+    }
+    let Some(Ok(filter)) = filter.map(|f| f.build()) else {
+        return;
+    };
+
+    let query = build_query("Property", Some(&filter), Some(1)).expect("a built filter makes a valid query");
+    match block_on(execute_query(CLIENT.get_or_init(DemoClient::new), &query)) {
+        Err(ResoError::ODataError { message, .. }) if message.starts_with("Invalid $filter") => {
+            panic!("builder emitted an unparseable filter {:?}: {}", filter, message)
+        }
+        _ => {}
+    }
+});
@@ -0,0 +1,128 @@
+//! `#[derive(ResoFilter)]` for `reso_examples`.
+//!
+//! Generates an implementation of `reso_examples::filter::ResoFilter` that
+//! combines the annotated fields of a struct into an OData filter. See the
+//! `reso_examples::filter` module for usage; this crate is re-exported there.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+/// Operator names accepted in `op = "..."` and the `Operator` variants they map to.
+const OPERATORS: &[(&str, &str)] = &[
+    ("eq", "Eq"),
+    ("ne", "Ne"),
+    ("gt", "Gt"),
+    ("ge", "Ge"),
+    ("lt", "Lt"),
+    ("le", "Le"),
+    ("contains", "Contains"),
+    ("startswith", "StartsWith"),
+    ("in", "In"),
+];
+
+#[proc_macro_derive(ResoFilter, attributes(filter))]
+pub fn derive_reso_filter(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "ResoFilter requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ResoFilter can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut conditions = Vec::new();
+    for field in fields {
+        let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("filter")) else {
+            continue;
+        };
+        let ident = field.ident.as_ref().expect("named field");
+
+        let mut odata_field = None;
+        let mut op = Ident::new("Eq", ident.span());
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("field") {
+                odata_field = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("op") {
+                let value = meta.value()?.parse::<LitStr>()?;
+                let name = value.value().to_ascii_lowercase();
+                let Some((_, variant)) = OPERATORS.iter().find(|(op, _)| *op == name) else {
+                    let names: Vec<&str> = OPERATORS.iter().map(|(op, _)| *op).collect();
+                    return Err(syn::Error::new_spanned(
+                        &value,
+                        format!("unknown operator; expected one of {}", names.join(", ")),
+                    ));
+                };
+                op = Ident::new(variant, value.span());
+                Ok(())
+            } else {
+                Err(meta.error("expected `field = \"...\"` or `op = \"...\"`"))
+            }
+        })?;
+        let odata_field = odata_field
+            .ok_or_else(|| syn::Error::new_spanned(attr, "missing `field = \"...\"`"))?;
+
+        let push = quote! {
+            conditions.push(::reso_examples::filter::Operator::#op.render(#odata_field, value));
+        };
+        conditions.push(if is_option(&field.ty) {
+            quote! {
+                if let ::std::option::Option::Some(value) = &self.#ident {
+                    #push
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#ident;
+                #push
+            }
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::reso_examples::filter::ResoFilter for #name #ty_generics #where_clause {
+            fn to_filter(&self) -> ::std::option::Option<::std::string::String> {
+                let mut conditions: ::std::vec::Vec<::std::string::String> = ::std::vec::Vec::new();
+                #(#conditions)*
+                if conditions.is_empty() {
+                    ::std::option::Option::None
+                } else {
+                    ::std::option::Option::Some(conditions.join(" and "))
+                }
+            }
+        }
+    })
+}
+
+/// Whether a field's type is spelled `Option<...>`; unset options are skipped.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
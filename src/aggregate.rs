@@ -0,0 +1,499 @@
+//! Server-side and client-side aggregation helpers.
+//!
+//! Aggregations are sent as OData `$apply` expressions when the server
+//! supports them. Many RESO servers do not, so each helper falls back to
+//! paging through the matching records and aggregating client-side.
+//!
+//! [`facet_counts`] counts matches for a fixed list of values instead, for
+//! the facet lists beside search results.
+
+use crate::client::ResoApi;
+use crate::error::is_rejected_query;
+use crate::filter::Operator;
+use crate::MAX_PAGE_SIZE;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use reso_client::{JsonValue, QueryBuilder, ResoError};
+use std::collections::HashMap;
+
+/// Most records [`count_by`] and [`aggregate`] page through when the server
+/// cannot aggregate; more matches fail with `ResoError::InvalidQuery`
+/// rather than paging through a whole feed.
+pub const MAX_PAGED_RECORDS: u32 = 10_000;
+
+/// Count requests [`facet_counts`] keeps in flight at once.
+pub const FACET_CONCURRENCY: usize = 4;
+
+/// The number of records sharing one value of a field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupCount {
+    /// The field value, `Null` for records without one
+    pub value: JsonValue,
+    pub count: u64,
+}
+
+/// Counts matching records grouped by the value of a field.
+///
+/// Uses `$apply=groupby(...)` when the server supports it, otherwise pages
+/// through at most [`MAX_PAGED_RECORDS`] matching records selecting only
+/// `field`. Errors other than the server turning down `$apply` are
+/// returned.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression
+/// * `field` - The field to group by (e.g., "City", "StandardStatus")
+///
+/// # Returns
+///
+/// Returns one entry per distinct value, largest count first.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, aggregate::count_by};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let groups = count_by(&client, "Property", Some("StandardStatus eq 'Active'"), "City").await?;
+///
+///     for group in groups {
+///         println!("{}: {}", group.value, group.count);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn count_by(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    field: &str,
+) -> Result<Vec<GroupCount>, ResoError> {
+    let query = QueryBuilder::new(resource)
+        .apply(groupby_count_expression(filter, field))
+        .build()?;
+
+    // Servers without $apply support either reject the query or ignore the
+    // option; both fall back to paging
+    let groups = match client.execute(&query).await {
+        Ok(response) => parse_groupby_counts(&response, field),
+        Err(e) if is_rejected_query(&e) => None,
+        Err(e) => return Err(e),
+    };
+
+    let mut groups = match groups {
+        Some(groups) => groups,
+        None => count_by_paging(client, resource, filter, field).await?,
+    };
+    groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+    Ok(groups)
+}
+
+/// A statistic computed over one numeric field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Min,
+    Max,
+    Average,
+    Sum,
+}
+
+impl Aggregation {
+    /// The OData aggregation method, as used in `$apply`.
+    pub fn method(self) -> &'static str {
+        match self {
+            Aggregation::Min => "min",
+            Aggregation::Max => "max",
+            Aggregation::Average => "average",
+            Aggregation::Sum => "sum",
+        }
+    }
+}
+
+/// Computes the minimum, maximum, average, or sum of a numeric field over
+/// the matching records.
+///
+/// Uses `$apply=aggregate(...)` when the server supports it, otherwise pages
+/// through at most [`MAX_PAGED_RECORDS`] matching records selecting only
+/// `field`, as [`count_by`] does. Records without a numeric value for the
+/// field are left out.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression
+/// * `field` - The numeric field to aggregate (e.g., "ListPrice")
+/// * `agg` - The statistic to compute
+///
+/// # Returns
+///
+/// Returns the statistic, or `None` if no matching record has a value.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::aggregate::{aggregate, Aggregation};
+/// use reso_examples::create_client;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let filter = "City eq 'Austin' and StandardStatus eq 'Active'";
+///     let average = aggregate(&client, "Property", Some(filter), "ListPrice", Aggregation::Average).await?;
+///
+///     match average {
+///         Some(price) => println!("Average list price in Austin: ${:.0}", price),
+///         None => println!("No active listings in Austin"),
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn aggregate(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    field: &str,
+    agg: Aggregation,
+) -> Result<Option<f64>, ResoError> {
+    let query = QueryBuilder::new(resource)
+        .apply(aggregate_expression(filter, field, agg))
+        .build()?;
+
+    // As in `count_by`, a rejected or ignored $apply falls back to paging
+    match client.execute(&query).await {
+        Ok(response) => {
+            if let Some(value) = parse_aggregate(&response) {
+                return Ok(value);
+            }
+        }
+        Err(e) if is_rejected_query(&e) => {}
+        Err(e) => return Err(e),
+    }
+
+    let mut stats = FieldStats::default();
+    for_each_page(client, resource, filter, field, |records| {
+        records.iter().filter_map(|r| r[field].as_f64()).for_each(|v| stats.add(v));
+    })
+    .await?;
+    Ok(stats.get(agg))
+}
+
+/// Builds the `$apply` expression aggregating `field` with `agg`.
+pub fn aggregate_expression(filter: Option<&str>, field: &str, agg: Aggregation) -> String {
+    let aggregate = format!("aggregate({} with {} as Value)", field, agg.method());
+    match filter {
+        Some(expr) => format!("filter({})/{}", expr, aggregate),
+        None => aggregate,
+    }
+}
+
+/// Reads an `aggregate` result: `Some(None)` when the server aggregated no
+/// values, or `None` if the response has a different shape.
+fn parse_aggregate(response: &JsonValue) -> Option<Option<f64>> {
+    match response["value"].as_array()?.as_slice() {
+        [row] => match row.get("Value")? {
+            JsonValue::Null => Some(None),
+            value => Some(Some(value.as_f64()?)),
+        },
+        _ => None,
+    }
+}
+
+/// Running statistics of the values seen while paging.
+#[derive(Debug, Default)]
+struct FieldStats {
+    count: u64,
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl FieldStats {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+
+    fn get(&self, agg: Aggregation) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        match agg {
+            Aggregation::Min => self.min,
+            Aggregation::Max => self.max,
+            Aggregation::Average => Some(self.sum / self.count as f64),
+            Aggregation::Sum => Some(self.sum),
+        }
+    }
+}
+
+/// Counts the records matching `filter` for each candidate value of a facet
+/// field, e.g. how many results are Residential, Land, and so on.
+///
+/// Sends one count request per value, at most [`FACET_CONCURRENCY`] at a
+/// time. Unlike [`count_by`], only the given values are counted, so a search
+/// form gets a count for each of its options, including zeros, without
+/// reading every record.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression the counts are narrowed by
+/// * `facet_field` - The field to facet on (e.g., "PropertyType")
+/// * `values` - The values to count
+///
+/// # Returns
+///
+/// Returns a map from each value to its count.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, aggregate::facet_counts};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let types = ["Residential", "Land", "Commercial"];
+///     let counts = facet_counts(&client, "Property", Some("City eq 'Austin'"), "PropertyType", &types).await?;
+///
+///     for value in types {
+///         println!("{} ({})", value, counts[value]);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn facet_counts(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    facet_field: &str,
+    values: &[&str],
+) -> Result<HashMap<String, u64>, ResoError> {
+    stream::iter(values)
+        .map(|value| async move {
+            let clause = Operator::Eq.render(facet_field, *value);
+            let filter = match filter {
+                Some(expr) => format!("({}) and {}", expr, clause),
+                None => clause,
+            };
+            let count = crate::count_records(client, resource, Some(&filter)).await?;
+            Ok::<_, ResoError>((value.to_string(), count))
+        })
+        .buffer_unordered(FACET_CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+/// Builds the `$apply` expression counting records per value of `field`.
+///
+/// The filter is part of the transformation because `$filter` is evaluated
+/// after `$apply`, i.e. against the aggregated rows.
+pub fn groupby_count_expression(filter: Option<&str>, field: &str) -> String {
+    let groupby = format!("groupby(({}),aggregate($count as Count))", field);
+    match filter {
+        Some(expr) => format!("filter({})/{}", expr, groupby),
+        None => groupby,
+    }
+}
+
+/// Reads `groupby` results, or `None` if the response has a different shape.
+pub(crate) fn parse_groupby_counts(response: &JsonValue, field: &str) -> Option<Vec<GroupCount>> {
+    response["value"]
+        .as_array()?
+        .iter()
+        .map(|row| {
+            Some(GroupCount {
+                value: row.get(field).cloned().unwrap_or(JsonValue::Null),
+                count: row["Count"].as_u64()?,
+            })
+        })
+        .collect()
+}
+
+async fn count_by_paging(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    field: &str,
+) -> Result<Vec<GroupCount>, ResoError> {
+    let mut counts: HashMap<String, GroupCount> = HashMap::new();
+    for_each_page(client, resource, filter, field, |records| {
+        for record in records {
+            let value = record.get(field).cloned().unwrap_or(JsonValue::Null);
+            counts
+                .entry(value.to_string())
+                .or_insert(GroupCount { value, count: 0 })
+                .count += 1;
+        }
+    })
+    .await?;
+    Ok(counts.into_values().collect())
+}
+
+/// Pages through the records matching `filter`, selecting only `field`,
+/// and passes each page to `on_page`. Fails once [`MAX_PAGED_RECORDS`] have
+/// been read and more remain, which also stops servers that ignore `$skip`.
+async fn for_each_page(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    field: &str,
+    mut on_page: impl FnMut(&[JsonValue]),
+) -> Result<(), ResoError> {
+    let mut skip = 0;
+    loop {
+        let mut builder = QueryBuilder::new(resource)
+            .select(&[field])
+            .top(MAX_PAGE_SIZE)
+            .skip(skip);
+        if let Some(expr) = filter {
+            builder = builder.filter(expr);
+        }
+        let response = client.execute(&builder.build()?).await?;
+        let records = response["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+        on_page(records);
+
+        if records.len() < MAX_PAGE_SIZE as usize {
+            return Ok(());
+        }
+        skip += MAX_PAGE_SIZE;
+        if skip >= MAX_PAGED_RECORDS {
+            return Err(ResoError::InvalidQuery(format!(
+                "More than {} {} records match and the server cannot aggregate them; narrow the filter",
+                MAX_PAGED_RECORDS, resource
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_groupby_count_expression() {
+        assert_eq!(
+            groupby_count_expression(Some("StandardStatus eq 'Active'"), "City"),
+            "filter(StandardStatus eq 'Active')/groupby((City),aggregate($count as Count))"
+        );
+    }
+
+    #[test]
+    fn test_parse_groupby_counts() {
+        let response = json!({"value": [{"City": "Austin", "Count": 12}, {"City": null, "Count": 3}]});
+        let groups = parse_groupby_counts(&response, "City").unwrap();
+        assert_eq!(groups[0], GroupCount { value: json!("Austin"), count: 12 });
+        assert_eq!(groups[1].value, JsonValue::Null);
+
+        // Servers that ignore $apply return plain records
+        let plain = json!({"value": [{"ListingKey": "A", "City": "Austin"}]});
+        assert!(parse_groupby_counts(&plain, "City").is_none());
+    }
+
+    #[test]
+    fn test_aggregate_expression_and_response() {
+        assert_eq!(
+            aggregate_expression(Some("City eq 'Austin'"), "ListPrice", Aggregation::Average),
+            "filter(City eq 'Austin')/aggregate(ListPrice with average as Value)"
+        );
+        assert_eq!(parse_aggregate(&json!({"value": [{"Value": 412500.5}]})), Some(Some(412500.5)));
+        assert_eq!(parse_aggregate(&json!({"value": [{"Value": null}]})), Some(None));
+        assert_eq!(parse_aggregate(&json!({"value": [{"ListingKey": "A", "ListPrice": 1}]})), None);
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_falls_back_to_paging() {
+        // The demo client refuses $apply
+        let client = crate::demo::DemoClient::new();
+        let prices: Vec<f64> = client
+            .records("Property")
+            .iter()
+            .filter(|r| r["City"] == "Austin")
+            .filter_map(|r| r["ListPrice"].as_f64())
+            .collect();
+        assert!(!prices.is_empty());
+
+        let filter = Some("City eq 'Austin'");
+        let sum = aggregate(&client, "Property", filter, "ListPrice", Aggregation::Sum).await.unwrap();
+        assert_eq!(sum, Some(prices.iter().sum()));
+        let average = aggregate(&client, "Property", filter, "ListPrice", Aggregation::Average).await.unwrap();
+        assert_eq!(average, Some(prices.iter().sum::<f64>() / prices.len() as f64));
+        let max = aggregate(&client, "Property", filter, "ListPrice", Aggregation::Max).await.unwrap();
+        assert_eq!(max, prices.iter().copied().reduce(f64::max));
+
+        let none = aggregate(&client, "Property", Some("City eq 'Nowhere'"), "ListPrice", Aggregation::Min).await;
+        assert_eq!(none.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_paging_fallback_is_bounded_and_other_errors_are_returned() {
+        use reso_client::ClientConfig;
+        use wiremock::matchers::{path, query_param_is_missing};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param_is_missing("$apply"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"value": vec![json!({"City": "Austin"}); 200]})),
+            )
+            .expect((MAX_PAGED_RECORDS / MAX_PAGE_SIZE) as u64)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("$apply is not supported"))
+            .mount(&server)
+            .await;
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let unbounded = count_by(&client, "Property", None, "City").await;
+        assert!(matches!(unbounded, Err(ResoError::InvalidQuery(_))));
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        assert!(matches!(
+            count_by(&client, "Property", None, "City").await,
+            Err(ResoError::Unauthorized { .. })
+        ));
+        assert!(matches!(
+            aggregate(&client, "Property", None, "ListPrice", Aggregation::Max).await,
+            Err(ResoError::Unauthorized { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_facet_counts_count_each_value() {
+        let client = crate::demo::DemoClient::new();
+        let statuses = ["Active", "Pending", "Closed", "Withdrawn"];
+        let counts = facet_counts(&client, "Property", Some("City eq 'Austin'"), "StandardStatus", &statuses)
+            .await
+            .unwrap();
+
+        assert_eq!(counts.len(), statuses.len());
+        for status in statuses {
+            let expected = client
+                .records("Property")
+                .iter()
+                .filter(|r| r["City"] == "Austin" && r["StandardStatus"] == status)
+                .count();
+            assert_eq!(counts[status], expected as u64, "{}", status);
+        }
+        assert!(counts["Active"] > 0);
+    }
+}
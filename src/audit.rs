@@ -0,0 +1,260 @@
+//! Spot-checking a mirrored dataset against the server.
+//!
+//! [`reconcile`](crate::reconcile) compares counts; an audit compares
+//! content. A random sample of local records is fetched again by key and
+//! compared field by field, which surfaces updates the replication missed
+//! and records the server has since deleted.
+
+use crate::client::ResoApi;
+use crate::compact::LatestRecords;
+use crate::provenance::PROVENANCE_FIELD;
+use reso_client::{JsonValue, QueryBuilder, ResoError};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+
+/// A field whose local value differs from the server's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDrift {
+    pub field: String,
+    pub local: JsonValue,
+    pub server: JsonValue,
+}
+
+/// A sampled record that differs from the server's copy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordDrift {
+    pub key: String,
+    /// True if the server's `ModificationTimestamp` is newer than the local
+    /// one, i.e. the next incremental run should pick the change up
+    pub server_newer: bool,
+    pub fields: Vec<FieldDrift>,
+}
+
+/// The result of [`audit_records`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditReport {
+    /// Records checked
+    pub sampled: usize,
+    /// Records identical to the server's copy
+    pub matching: usize,
+    pub drifted: Vec<RecordDrift>,
+    /// Keys stored locally that the server no longer returns
+    pub missing_on_server: Vec<String>,
+}
+
+impl AuditReport {
+    /// Returns true if every sampled record matched the server.
+    pub fn is_clean(&self) -> bool {
+        self.drifted.is_empty() && self.missing_on_server.is_empty()
+    }
+}
+
+impl std::fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Sampled {} records: {} matching, {} drifted, {} missing on server",
+            self.sampled,
+            self.matching,
+            self.drifted.len(),
+            self.missing_on_server.len()
+        )?;
+        for record in &self.drifted {
+            let note = if record.server_newer { " (server copy is newer)" } else { "" };
+            write!(f, "\n  {}{}", record.key, note)?;
+            for drift in &record.fields {
+                write!(f, "\n    {}: local {}, server {}", drift.field, drift.local, drift.server)?;
+            }
+        }
+        for key in &self.missing_on_server {
+            write!(f, "\n  {}: not found on server", key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Re-fetches local records by key and compares them with the server's copies.
+///
+/// Only fields present in the local record are compared, so records
+/// replicated with `$select` are not reported for the fields they left out.
+/// OData annotations such as `@odata.etag` and provenance stamps are ignored.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `key_field` - Field holding each record's key, e.g. "ListingKey"
+/// * `records` - The local records to check, e.g. from [`sample_jsonl`]
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::audit::{audit_records, sample_jsonl};
+/// use reso_examples::create_client;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let sample = sample_jsonl("property.jsonl", "ListingKey", 50)?;
+///
+///     let report = audit_records(&client, "Property", "ListingKey", &sample).await?;
+///     println!("{}", report);
+///     Ok(())
+/// }
+/// ```
+pub async fn audit_records(
+    client: &impl ResoApi,
+    resource: &str,
+    key_field: &str,
+    records: &[JsonValue],
+) -> Result<AuditReport, ResoError> {
+    let mut report = AuditReport::default();
+
+    for local in records {
+        let key = match &local[key_field] {
+            JsonValue::String(s) => s.clone(),
+            JsonValue::Null => {
+                return Err(ResoError::Parse(format!("Record without key field '{}'", key_field)))
+            }
+            other => other.to_string(),
+        };
+        report.sampled += 1;
+
+        let query = QueryBuilder::by_key(resource, &key).build()?;
+        let response = match client.execute_by_key(&query).await {
+            Ok(response) => response,
+            Err(ResoError::NotFound { .. }) => {
+                report.missing_on_server.push(key);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        // Some servers wrap key lookups in a `value` array like collection queries
+        let server = match response["value"].as_array() {
+            Some(records) => match records.first() {
+                Some(record) => record.clone(),
+                None => {
+                    report.missing_on_server.push(key);
+                    continue;
+                }
+            },
+            None => response,
+        };
+
+        let fields = compare_fields(local, &server);
+        if fields.is_empty() {
+            report.matching += 1;
+        } else {
+            let server_newer = matches!(
+                (local["ModificationTimestamp"].as_str(), server["ModificationTimestamp"].as_str()),
+                (Some(l), Some(s)) if s > l
+            );
+            report.drifted.push(RecordDrift {
+                key,
+                server_newer,
+                fields,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+fn compare_fields(local: &JsonValue, server: &JsonValue) -> Vec<FieldDrift> {
+    let Some(fields) = local.as_object() else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .filter(|(field, _)| !field.contains('@') && field.as_str() != PROVENANCE_FIELD)
+        .filter(|(field, value)| &server[field.as_str()] != *value)
+        .map(|(field, value)| FieldDrift {
+            field: field.clone(),
+            local: value.clone(),
+            server: server[field.as_str()].clone(),
+        })
+        .collect()
+}
+
+/// Reads up to `n` records chosen at random from a JSONL store.
+///
+/// Each key is sampled at most once, using its newest version, so
+/// append-only stores holding several versions of a record are audited
+/// against their latest copy.
+pub fn sample_jsonl(path: impl AsRef<Path>, key_field: &str, n: usize) -> Result<Vec<JsonValue>, ResoError> {
+    let mut latest = LatestRecords::default();
+    latest.read_file(path.as_ref(), key_field)?;
+
+    // Reservoir sampling with a randomly seeded xorshift generator
+    let mut state = RandomState::new().build_hasher().finish() | 1;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut sample: Vec<JsonValue> = Vec::with_capacity(n);
+    for (i, record) in latest.records().enumerate() {
+        if sample.len() < n {
+            sample.push(record.clone());
+        } else {
+            let j = (next() % (i as u64 + 1)) as usize;
+            if j < n {
+                sample[j] = record.clone();
+            }
+        }
+    }
+    Ok(sample)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reso_client::ClientConfig;
+    use serde_json::json;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_audit_reports_drift_and_missing_keys() {
+        let server = MockServer::start().await;
+        Mock::given(path("/Property('A')"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "@odata.etag": "W/\"1\"",
+                "ListingKey": "A",
+                "ListPrice": 100
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property('B')"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ListingKey": "B",
+                "ListPrice": 250,
+                "ModificationTimestamp": "2025-01-02T00:00:00Z"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property('C')"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let local = [
+            json!({"ListingKey": "A", "ListPrice": 100}),
+            json!({"ListingKey": "B", "ListPrice": 200, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            json!({"ListingKey": "C", "ListPrice": 300}),
+        ];
+        let report = audit_records(&client, "Property", "ListingKey", &local).await.unwrap();
+
+        assert_eq!((report.sampled, report.matching), (3, 1));
+        assert_eq!(report.missing_on_server, ["C"]);
+        assert_eq!(report.drifted.len(), 1);
+        assert!(report.drifted[0].server_newer);
+        let fields: Vec<&str> = report.drifted[0].fields.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, ["ListPrice", "ModificationTimestamp"]);
+    }
+}
@@ -0,0 +1,396 @@
+//! OAuth2 client-credentials authentication.
+//!
+//! Some vendors hand out long-lived bearer tokens, which go in `RESO_TOKEN`.
+//! Trestle and several MLS vendors instead issue short-lived tokens from an
+//! OAuth2 token endpoint in exchange for a client id and secret.
+//! [`OAuthTokens`] runs that client-credentials grant, caches the token, and
+//! requests a new one shortly before it expires.
+//! [`HttpClientBuilder::oauth`](crate::client::HttpClientBuilder::oauth)
+//! attaches it to a client, and [`create_client_oauth`](crate::create_client_oauth)
+//! builds such a client from the environment. Other schemes, such as a
+//! token store shared between processes, implement [`TokenProvider`].
+
+use async_trait::async_trait;
+use reqwest::header::ACCEPT;
+use reso_client::{JsonValue, ResoError};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Tokens are replaced this long before they expire, so a request never
+/// carries a token that runs out in flight.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Lifetime assumed when the token response has no `expires_in`.
+const DEFAULT_LIFETIME: Duration = Duration::from_secs(3600);
+
+/// Supplies the bearer token for each request.
+///
+/// [`HttpClientBuilder::token_provider`](crate::client::HttpClientBuilder::token_provider)
+/// uses one instead of the configured token, e.g. to give each tenant of a
+/// multi-tenant server its own credentials.
+///
+/// # Example
+///
+/// ```
+/// use async_trait::async_trait;
+/// use reso_client::ResoError;
+/// use reso_examples::auth::TokenProvider;
+///
+/// /// Reads the token on every request, so rotating it needs no restart.
+/// struct TokenFile(std::path::PathBuf);
+///
+/// #[async_trait]
+/// impl TokenProvider for TokenFile {
+///     async fn token(&self) -> Result<String, ResoError> {
+///         let token = tokio::fs::read_to_string(&self.0)
+///             .await
+///             .map_err(|e| ResoError::Config(format!("Cannot read token file: {}", e)))?;
+///         Ok(token.trim().to_string())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    /// A current bearer token.
+    async fn token(&self) -> Result<String, ResoError>;
+
+    /// Called when the server answers 401, so a cached token can be
+    /// dropped before the next request.
+    async fn invalidate(&self) {}
+}
+
+/// Credentials for the OAuth2 client-credentials grant.
+#[derive(Clone)]
+pub struct ClientCredentials {
+    /// The vendor's token endpoint
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Scope to request, e.g. `api` for Trestle; omitted if `None`
+    pub scope: Option<String>,
+}
+
+impl ClientCredentials {
+    pub fn new(token_url: impl Into<String>, client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        Self {
+            token_url: token_url.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scope: None,
+        }
+    }
+
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Reads `RESO_TOKEN_URL`, `RESO_CLIENT_ID`, `RESO_CLIENT_SECRET`, and
+    /// the optional `RESO_SCOPE`.
+    pub fn from_env() -> Result<Self, ResoError> {
+        let required = |var: &str| match std::env::var(var) {
+            Ok(value) if !value.trim().is_empty() => Ok(value.trim().to_string()),
+            _ => Err(ResoError::Config(format!("{} is not set", var))),
+        };
+        let mut credentials = Self::new(
+            required("RESO_TOKEN_URL")?,
+            required("RESO_CLIENT_ID")?,
+            required("RESO_CLIENT_SECRET")?,
+        );
+        if let Ok(scope) = std::env::var("RESO_SCOPE") {
+            if !scope.trim().is_empty() {
+                credentials = credentials.with_scope(scope.trim());
+            }
+        }
+        Ok(credentials)
+    }
+}
+
+/// Keeps the secret out of logs.
+impl fmt::Debug for ClientCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientCredentials")
+            .field("token_url", &self.token_url)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"[redacted]")
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+/// Access tokens from a client-credentials grant, cached until shortly
+/// before they expire.
+///
+/// Clones share the cached token, so every clone of a client refreshes it
+/// once, and concurrent requests wait for the same refresh.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_client::ClientConfig;
+/// use reso_examples::auth::{ClientCredentials, OAuthTokens};
+/// use reso_examples::client::HttpClient;
+/// use reso_examples::count_records;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let credentials = ClientCredentials::new(
+///         "https://api-trestle.corelogic.com/trestle/oidc/connect/token",
+///         "client_id",
+///         "client_secret",
+///     )
+///     .with_scope("api");
+///     // The configured token is unused; requests carry the OAuth token
+///     let config = ClientConfig::new("https://api-trestle.corelogic.com/trestle/odata", "");
+///     let client = HttpClient::builder(config)
+///         .oauth(OAuthTokens::new(credentials)?)
+///         .build()?;
+///
+///     println!("{} listings", count_records(&client, "Property", None).await?);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct OAuthTokens {
+    credentials: Arc<ClientCredentials>,
+    http: reqwest::Client,
+    cached: Arc<Mutex<Option<CachedToken>>>,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+impl OAuthTokens {
+    pub fn new(credentials: ClientCredentials) -> Result<Self, ResoError> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| ResoError::Config(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            credentials: Arc::new(credentials),
+            http,
+            cached: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// A current access token, requesting a new one if the cached token is
+    /// missing or about to expire.
+    pub async fn access_token(&self) -> Result<String, ResoError> {
+        // Held across the request, so concurrent callers share one refresh
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > Instant::now() + REFRESH_MARGIN {
+                return Ok(token.access_token.clone());
+            }
+        }
+        let token = self.request_token().await?;
+        let access_token = token.access_token.clone();
+        *cached = Some(token);
+        Ok(access_token)
+    }
+
+    /// Discards the cached token, so the next request fetches a new one.
+    ///
+    /// [`HttpClient`](crate::client::HttpClient) calls this when the server
+    /// answers 401, in case the token was revoked before it expired.
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    async fn request_token(&self) -> Result<CachedToken, ResoError> {
+        let credentials = &self.credentials;
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", credentials.client_id.as_str()),
+            ("client_secret", credentials.client_secret.as_str()),
+        ];
+        if let Some(scope) = &credentials.scope {
+            form.push(("scope", scope.as_str()));
+        }
+
+        let requested = Instant::now();
+        let response = self
+            .http
+            .post(&credentials.token_url)
+            .header(ACCEPT, "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| ResoError::Network(format!("Token request to {} failed: {}", credentials.token_url, e)))?;
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ResoError::Network(format!("Failed to read token response: {}", e)))?;
+        let json: Option<JsonValue> = serde_json::from_str(&body).ok();
+
+        if !(200..300).contains(&status) {
+            // OAuth errors carry `error` and `error_description`, not an OData error
+            let message = json
+                .as_ref()
+                .and_then(|json| {
+                    let error = json["error"].as_str()?;
+                    Some(match json["error_description"].as_str() {
+                        Some(description) => format!("{}: {}", error, description),
+                        None => error.to_string(),
+                    })
+                })
+                .unwrap_or_else(|| body.chars().take(500).collect());
+            return Err(match status {
+                400 | 401 => ResoError::Unauthorized {
+                    message: format!("Token endpoint rejected the client credentials ({})", message),
+                    status_code: status,
+                },
+                _ => crate::client::error_from_status(status, &body),
+            });
+        }
+
+        let json = json.ok_or_else(|| ResoError::Parse("Token response is not JSON".to_string()))?;
+        let access_token = json["access_token"]
+            .as_str()
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| ResoError::Parse("Token response has no access_token".to_string()))?;
+        // Some vendors send expires_in as a string
+        let lifetime = json["expires_in"]
+            .as_u64()
+            .or_else(|| json["expires_in"].as_str()?.parse().ok())
+            .map_or(DEFAULT_LIFETIME, Duration::from_secs);
+        Ok(CachedToken {
+            access_token: access_token.to_string(),
+            expires_at: requested + lifetime,
+        })
+    }
+}
+
+#[async_trait]
+impl TokenProvider for OAuthTokens {
+    async fn token(&self) -> Result<String, ResoError> {
+        self.access_token().await
+    }
+
+    async fn invalidate(&self) {
+        OAuthTokens::invalidate(self).await
+    }
+}
+
+impl fmt::Debug for OAuthTokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuthTokens")
+            .field("credentials", &self.credentials)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpClient;
+    use reso_client::ClientConfig;
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn credentials(server: &MockServer) -> ClientCredentials {
+        ClientCredentials::new(format!("{}/token", server.uri()), "id", "secret").with_scope("api")
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached_until_it_nears_expiry() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .and(body_string_contains("scope=api"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"access_token": "abc", "expires_in": 3600})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/$count"))
+            .and(header("Authorization", "Bearer abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("42"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), ""))
+            .oauth(OAuthTokens::new(credentials(&server)).unwrap())
+            .build()
+            .unwrap();
+        for _ in 0..2 {
+            assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 42);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revoked_token_is_replaced_and_request_repeated() {
+        let server = MockServer::start().await;
+        Mock::given(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"access_token": "revoked", "expires_in": 3600})))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"access_token": "fresh", "expires_in": 3600})))
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/$count"))
+            .and(header("Authorization", "Bearer revoked"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/$count"))
+            .and(header("Authorization", "Bearer fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("42"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), ""))
+            .oauth(OAuthTokens::new(credentials(&server)).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_expiring_tokens_are_refreshed() {
+        let server = MockServer::start().await;
+        // Inside the refresh margin, so every request fetches a new token
+        Mock::given(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"access_token": "short", "expires_in": "30"})))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let tokens = OAuthTokens::new(credentials(&server)).unwrap();
+        assert_eq!(tokens.access_token().await.unwrap(), "short");
+        assert_eq!(tokens.access_token().await.unwrap(), "short");
+    }
+
+    #[tokio::test]
+    async fn test_rejected_credentials_are_unauthorized() {
+        let server = MockServer::start().await;
+        Mock::given(path("/token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "invalid_client",
+                "error_description": "Unknown client"
+            })))
+            .mount(&server)
+            .await;
+
+        let tokens = OAuthTokens::new(credentials(&server)).unwrap();
+        match tokens.access_token().await {
+            Err(ResoError::Unauthorized { message, .. }) => {
+                assert!(message.contains("invalid_client: Unknown client"));
+                assert!(!message.contains("secret"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(!format!("{:?}", tokens).contains("\"secret\""));
+    }
+}
@@ -0,0 +1,33 @@
+//! `reso audit <resource>`: compare a sample of local records with the server.
+
+use reso_examples::audit::{audit_records, sample_jsonl};
+use reso_examples::client::ResoApi;
+use reso_examples::export::{default_key_field, SqliteSink};
+use std::path::PathBuf;
+
+/// Where the local copy is stored.
+pub enum Source {
+    Jsonl(PathBuf),
+    Sqlite(PathBuf),
+}
+
+pub async fn run(
+    client: &impl ResoApi,
+    resource: &str,
+    source: Source,
+    sample: usize,
+    key_field: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let key_field = key_field.unwrap_or_else(|| default_key_field(resource));
+    let records = match source {
+        Source::Jsonl(path) => sample_jsonl(path, &key_field, sample)?,
+        Source::Sqlite(path) => SqliteSink::open(path, resource, &key_field)?.sample(sample)?,
+    };
+
+    let report = audit_records(client, resource, &key_field, &records).await?;
+    println!("{}", report);
+    if !report.is_clean() {
+        return Err("Local records differ from the server".into());
+    }
+    Ok(())
+}
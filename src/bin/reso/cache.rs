@@ -0,0 +1,30 @@
+//! On-disk cache of the server's metadata document.
+//!
+//! Commands that fetch metadata store the XML here so later invocations,
+//! such as completion script generation, can use it without credentials
+//! or a network round trip.
+
+use reso_examples::metadata::{parse_metadata, ResoSchema};
+use std::path::PathBuf;
+
+/// Location of the cached metadata: `$RESO_CACHE_DIR/metadata.xml`,
+/// defaulting to `.reso_cache/metadata.xml`.
+pub fn metadata_path() -> PathBuf {
+    let dir = std::env::var("RESO_CACHE_DIR").unwrap_or_else(|_| ".reso_cache".to_string());
+    PathBuf::from(dir).join("metadata.xml")
+}
+
+/// Stores the metadata XML, ignoring failures since the cache is optional.
+pub fn store_metadata(xml: &str) {
+    let path = metadata_path();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(path, xml);
+}
+
+/// Parses the cached metadata, if present and valid.
+pub fn cached_schema() -> Option<ResoSchema> {
+    let xml = std::fs::read_to_string(metadata_path()).ok()?;
+    parse_metadata(&xml).ok()
+}
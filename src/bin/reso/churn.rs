@@ -0,0 +1,15 @@
+//! `reso churn`: report how often each field changes across a sync history.
+
+use reso_examples::churn::churn_jsonl;
+use std::path::PathBuf;
+
+pub fn run(files: &[PathBuf], key_field: &str, top: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let mut report = churn_jsonl(files, key_field)?;
+    let hidden = report.fields.len().saturating_sub(top);
+    report.fields.truncate(top);
+    println!("{}", report);
+    if hidden > 0 {
+        println!("  ... {} more fields (--top)", hidden);
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+//! `reso codegen`: generate field name constants or record structs from
+//! server metadata.
+
+use reso_examples::codegen::{schema_field_constants, schema_structs};
+use reso_examples::metadata::ResoSchema;
+use std::path::Path;
+
+pub fn run(schema: &ResoSchema, out: Option<&Path>, structs: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let (source, kind) = if structs {
+        (schema_structs(schema), "structs")
+    } else {
+        (schema_field_constants(schema), "constants")
+    };
+    match out {
+        Some(path) => {
+            std::fs::write(path, source)?;
+            println!(
+                "Wrote {} for {} resources to {}",
+                kind,
+                schema.resources().len(),
+                path.display()
+            );
+        }
+        None => print!("{}", source),
+    }
+    Ok(())
+}
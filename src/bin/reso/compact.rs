@@ -0,0 +1,66 @@
+//! `reso compact`: deduplicate a JSONL store, merging in delta files.
+
+use reso_examples::compact::{compact_jsonl, merge_snapshot, CompactStats};
+use std::path::{Path, PathBuf};
+
+pub fn run(
+    base: &Path,
+    deltas: &[PathBuf],
+    out: Option<&Path>,
+    key_field: &str,
+    max_file_size: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let max_file_bytes = max_file_size.map(parse_size).transpose()?;
+    let out = out.unwrap_or(base);
+
+    let stats = if deltas.is_empty() && out == base {
+        compact_jsonl(base, key_field, max_file_bytes)?
+    } else {
+        let merged = merge_snapshot(base, deltas, out, key_field)?;
+        match max_file_bytes {
+            // Splitting reads the merged file back; it holds no duplicates
+            Some(max) => CompactStats {
+                outputs: compact_jsonl(out, key_field, Some(max))?.outputs,
+                ..merged
+            },
+            None => merged,
+        }
+    };
+
+    println!(
+        "Read {} records from {} files; wrote {} records, removing {} duplicates",
+        stats.records_in, stats.files, stats.records_out, stats.duplicates_removed
+    );
+    for path in &stats.outputs {
+        println!("  {}", path.display());
+    }
+    Ok(())
+}
+
+/// Parses a size such as `500000`, `64K`, `256M`, or `1G`.
+fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let (digits, multiplier) = match size.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&size[..i], 1 << 10),
+        Some((i, 'M' | 'm')) => (&size[..i], 1 << 20),
+        Some((i, 'G' | 'g')) => (&size[..i], 1 << 30),
+        _ => (size, 1),
+    };
+    match digits.parse::<u64>().ok().and_then(|n| n.checked_mul(multiplier)) {
+        Some(bytes) if bytes > 0 => Ok(bytes),
+        _ => Err(format!("Invalid size '{}' (expected e.g. 500000, 64K, 256M)", size)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size_rejects_overflow() {
+        assert_eq!(parse_size("256M"), Ok(256 << 20));
+        assert_eq!(parse_size(" 500000 "), Ok(500000));
+        assert!(parse_size("0K").is_err());
+        assert!(parse_size("18446744073709551615G").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! `reso completions <shell>`: print a shell completion script.
+
+use crate::cache;
+use clap::builder::PossibleValuesParser;
+use clap::Command;
+use clap_complete::{generate, Shell};
+
+/// Subcommands whose `resource` argument names a server resource.
+const RESOURCE_COMMANDS: &[&str] = &["fields", "query", "replicate", "retry-failed", "audit", "pii-scan", "count"];
+
+pub fn run(mut command: Command, shell: Shell) {
+    if let Some(schema) = cache::cached_schema() {
+        let names: Vec<String> = schema
+            .resources()
+            .iter()
+            .map(|r| r.name.to_string())
+            .collect();
+        command = with_resource_names(command, &names);
+    } else {
+        eprintln!(
+            "No cached metadata at {}; run `reso resources` first to enable resource-name completion",
+            cache::metadata_path().display()
+        );
+    }
+
+    let name = command.get_name().to_string();
+    generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+/// Offers `names` as the possible values of every `resource` argument.
+fn with_resource_names(mut command: Command, names: &[String]) -> Command {
+    for subcommand in RESOURCE_COMMANDS {
+        command = command.mut_subcommand(*subcommand, |sub| {
+            sub.mut_arg("resource", |arg| {
+                arg.value_parser(PossibleValuesParser::new(names.to_vec()))
+            })
+        });
+    }
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_resource_names_become_possible_values() {
+        let command = with_resource_names(Cli::command(), &["Property".into(), "Member".into()]);
+        let count = command.find_subcommand("count").unwrap();
+        let resource = count
+            .get_arguments()
+            .find(|a| a.get_id() == "resource")
+            .unwrap();
+        let values: Vec<_> = resource
+            .get_possible_values()
+            .iter()
+            .map(|v| v.get_name().to_string())
+            .collect();
+        assert_eq!(values, vec!["Property", "Member"]);
+    }
+}
@@ -0,0 +1,35 @@
+//! `reso count <resource>`: count records, optionally grouped by a field.
+
+use crate::table::Table;
+use reso_client::JsonValue;
+use reso_examples::aggregate::count_by;
+use reso_examples::client::ResoApi;
+use reso_examples::count_records;
+
+pub async fn run(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    by: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(field) = by else {
+        println!("{}", count_records(client, resource, filter).await?);
+        return Ok(());
+    };
+
+    let groups = count_by(client, resource, filter, field).await?;
+    let total: u64 = groups.iter().map(|g| g.count).sum();
+
+    let mut table = Table::new([field, "Count"]);
+    for group in &groups {
+        let value = match &group.value {
+            JsonValue::String(s) => s.clone(),
+            JsonValue::Null => "(none)".to_string(),
+            other => other.to_string(),
+        };
+        table.add_row([value, group.count.to_string()]);
+    }
+    table.add_row(["Total".to_string(), total.to_string()]);
+    print!("{}", table.render());
+    Ok(())
+}
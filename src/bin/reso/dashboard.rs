@@ -0,0 +1,164 @@
+//! `reso dashboard`: live view of sync progress from the status file.
+//!
+//! Also `reso runs`, which prints the ledger of finished runs.
+
+use crate::table::Table as TextTable;
+use chrono::Utc;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+use ratatui::Frame;
+use reso_examples::status::{ResourceStatus, StatusFile, SyncState};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How often the status file is re-read.
+const REFRESH: Duration = Duration::from_secs(1);
+
+pub fn run(status: StatusFile) -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = ratatui::init();
+    let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            // A read error is shown rather than ending the session, since the
+            // file may be mid-replacement or not yet created
+            let snapshot = status.read().map_err(|e| e.to_string());
+            terminal.draw(|frame| draw(frame, &status, &snapshot))?;
+
+            if event::poll(REFRESH)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press
+                        && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                    {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
+
+/// Prints up to `limit` finished runs, most recent first.
+pub fn list_runs(status: &StatusFile, resource: Option<&str>, limit: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let runs: Vec<_> = status
+        .list_runs()?
+        .into_iter()
+        .filter(|run| resource.is_none_or(|resource| run.resource.eq_ignore_ascii_case(resource)))
+        .take(limit)
+        .collect();
+    if runs.is_empty() {
+        println!("No finished runs in {}", status.path().display());
+        return Ok(());
+    }
+
+    let mut table = TextTable::new(["Resource", "Result", "Started", "Finished", "Records", "Checkpoint", "Error"]);
+    for run in runs {
+        let checkpoint = format!(
+            "{} -> {}",
+            run.checkpoint_before.as_deref().unwrap_or("-"),
+            run.checkpoint_after.as_deref().unwrap_or("-")
+        );
+        table.add_row([
+            run.resource,
+            format!("{:?}", run.state),
+            run.started_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default(),
+            run.finished_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            run.records.to_string(),
+            checkpoint,
+            run.error.unwrap_or_default(),
+        ]);
+    }
+    print!("{}", table.render());
+    Ok(())
+}
+
+fn draw(
+    frame: &mut Frame,
+    status: &StatusFile,
+    snapshot: &Result<BTreeMap<String, ResourceStatus>, String>,
+) {
+    let [table_area, detail_area] =
+        Layout::vertical([Constraint::Min(5), Constraint::Length(5)]).areas(frame.area());
+
+    let title = format!(" RESO sync - {} (q to quit) ", status.path().display());
+    let statuses = match snapshot {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            frame.render_widget(
+                Paragraph::new(e.as_str()).block(Block::default().borders(Borders::ALL).title(title)),
+                frame.area(),
+            );
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    let rows = statuses.iter().map(|(resource, s)| {
+        let (state, color) = match s.state {
+            SyncState::Idle => ("idle", Color::Gray),
+            SyncState::Running => ("running", Color::Cyan),
+            SyncState::Completed => ("completed", Color::Green),
+            SyncState::Failed => ("failed", Color::Red),
+        };
+        let rate_limit = match s.rate_limited_until {
+            Some(until) if s.is_rate_limited(now) => {
+                format!("paused {}s", (until - now).num_seconds())
+            }
+            _ => "ok".to_string(),
+        };
+        let updated = s
+            .updated_at
+            .map(|t| format!("{}s ago", (now - t).num_seconds()))
+            .unwrap_or_default();
+
+        Row::new(vec![
+            resource.clone(),
+            state.to_string(),
+            s.records.to_string(),
+            s.batches.to_string(),
+            format!("{:.1}", s.records_per_sec()),
+            s.errors.to_string(),
+            rate_limit,
+            updated,
+        ])
+        .style(Style::default().fg(color))
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "Resource", "State", "Records", "Batches", "Rec/s", "Errors", "Rate limit", "Updated",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(table, table_area);
+
+    let last_errors: Vec<String> = statuses
+        .iter()
+        .filter_map(|(resource, s)| s.last_error.as_ref().map(|e| format!("{}: {}", resource, e)))
+        .collect();
+    let detail = if last_errors.is_empty() {
+        "No errors recorded".to_string()
+    } else {
+        last_errors.join("\n")
+    };
+    frame.render_widget(
+        Paragraph::new(detail).block(Block::default().borders(Borders::ALL).title(" Last errors ")),
+        detail_area,
+    );
+}
@@ -0,0 +1,195 @@
+//! `reso doctor`: check configuration and server readiness.
+
+use crate::cache;
+use reso_client::ResoError;
+use reso_examples::capabilities::{detect_capabilities, remediation_hint, Vendor};
+use reso_examples::client::ResoApi;
+use reso_examples::metadata::parse_metadata;
+use reso_examples::fetch_metadata;
+
+struct Report {
+    failures: usize,
+}
+
+impl Report {
+    fn pass(&self, message: &str) {
+        println!("  ✓ {}", message);
+    }
+
+    fn warn(&self, message: &str, hint: &str) {
+        println!("  ! {}", message);
+        println!("    → {}", hint);
+    }
+
+    fn fail(&mut self, message: &str, hint: &str) {
+        self.failures += 1;
+        println!("  ✗ {}", message);
+        println!("    → {}", hint);
+    }
+
+    fn error(&mut self, what: &str, error: &ResoError) {
+        self.fail(&format!("{}: {}", what, error), remediation_hint(error));
+    }
+}
+
+pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut report = Report { failures: 0 };
+
+    println!("Configuration");
+    let base_url = std::env::var("RESO_BASE_URL").ok();
+    match &base_url {
+        Some(url) if url.starts_with("https://") => report.pass(&format!("RESO_BASE_URL = {}", url)),
+        Some(url) if url.starts_with("http://") => report.warn(
+            &format!("RESO_BASE_URL = {} uses plain HTTP", url),
+            "Use https:// so the bearer token is not sent in clear text",
+        ),
+        Some(url) => report.fail(
+            &format!("RESO_BASE_URL = {} is not a URL", url),
+            "Set it to the full OData endpoint, e.g. https://api.bridgedataoutput.com/api/v2/OData",
+        ),
+        None => report.fail("RESO_BASE_URL is not set", "Copy .env.example to .env and fill it in"),
+    }
+    let is_set = |var: &str| std::env::var(var).is_ok_and(|value| !value.trim().is_empty());
+    if is_set("RESO_TOKEN_URL") {
+        report.pass("RESO_TOKEN_URL is set; using OAuth2 client credentials");
+        for var in ["RESO_CLIENT_ID", "RESO_CLIENT_SECRET"] {
+            if is_set(var) {
+                report.pass(&format!("{} is set", var));
+            } else {
+                report.fail(
+                    &format!("{} is not set", var),
+                    "Add the OAuth client id and secret from your MLS or vendor to .env",
+                );
+            }
+        }
+    } else if is_set("RESO_TOKEN") {
+        report.pass("RESO_TOKEN is set");
+    } else {
+        report.fail(
+            "RESO_TOKEN is not set",
+            "Add the bearer token from your MLS or vendor to .env, or RESO_TOKEN_URL, RESO_CLIENT_ID, and RESO_CLIENT_SECRET for OAuth",
+        );
+    }
+    match std::env::var("RESO_DATASET_ID") {
+        Ok(dataset) => report.pass(&format!("RESO_DATASET_ID = {}", dataset)),
+        Err(_) => println!("  - RESO_DATASET_ID not set (only required by some vendors)"),
+    }
+    if let Ok(timeout) = std::env::var("RESO_TIMEOUT") {
+        match timeout.parse::<u64>() {
+            Ok(secs) => report.pass(&format!("RESO_TIMEOUT = {}s", secs)),
+            Err(_) => report.fail(
+                &format!("RESO_TIMEOUT = {} is not a number of seconds", timeout),
+                "Set RESO_TIMEOUT to a whole number, e.g. 30",
+            ),
+        }
+    }
+
+    let vendor = base_url.as_deref().map(Vendor::detect).unwrap_or(Vendor::Unknown);
+    if vendor != Vendor::Unknown {
+        println!("\nVendor: {}", vendor.name());
+        for quirk in vendor.quirks() {
+            println!("  • {}", quirk);
+        }
+    }
+
+    if report.failures > 0 {
+        return finish(report);
+    }
+
+    let client = match crate::server_client() {
+        Ok(client) => client,
+        Err(e) => {
+            report.error("Cannot create client", &e);
+            return finish(report);
+        }
+    };
+
+    println!("\nConnectivity & metadata");
+    let Some(resource) = check_metadata(&client, &mut report).await else {
+        return finish(report);
+    };
+
+    println!("\nCapabilities ({})", resource);
+    match detect_capabilities(&client, &resource).await {
+        Ok(caps) => {
+            report.pass(&format!("Basic queries on {} work", resource));
+            let feature = |supported: bool, name: &str, hint: &str| {
+                if supported {
+                    report.pass(&format!("{} supported", name));
+                } else {
+                    report.warn(&format!("{} not supported", name), hint);
+                }
+            };
+            feature(caps.count, "$count", "Use fetch-and-count fallbacks");
+            feature(caps.orderby, "$orderby", "Sort results client-side");
+            feature(caps.apply, "$apply", "Aggregations fall back to paging client-side");
+            if let Some(expand) = caps.expand_media {
+                feature(expand, "$expand=Media", "Fetch Media with a separate query by ResourceRecordKey");
+            }
+            if let Some(geo) = caps.geo_distance {
+                feature(geo, "geo.distance", "Radius searches use a Latitude/Longitude bounding box");
+            }
+            feature(
+                caps.replication,
+                "Replication endpoint",
+                "Use $top/$skip paging for syncs, or ask your vendor for replication access",
+            );
+            for error in &caps.errors {
+                println!("    {}", error);
+            }
+        }
+        Err(e) => report.error(&format!("Cannot query {}", resource), &e),
+    }
+
+    finish(report)
+}
+
+/// Fetches and parses metadata, returning the resource to probe.
+async fn check_metadata(client: &impl ResoApi, report: &mut Report) -> Option<String> {
+    let xml = match fetch_metadata(client).await {
+        Ok(xml) => xml,
+        Err(e) => {
+            report.error("Metadata request failed", &e);
+            return None;
+        }
+    };
+    report.pass(&format!("Connected and authenticated ({} KB of metadata)", xml.len() / 1024));
+
+    let schema = match parse_metadata(&xml) {
+        Ok(schema) => schema,
+        Err(e) => {
+            report.error("Metadata could not be parsed", &e);
+            return None;
+        }
+    };
+    cache::store_metadata(&xml);
+
+    let resources = schema.resources();
+    if resources.is_empty() {
+        report.fail(
+            "Metadata lists no resources",
+            "Check that RESO_BASE_URL points at the OData service root",
+        );
+        return None;
+    }
+    report.pass(&format!("{} resources available", resources.len()));
+
+    let property = resources.iter().find(|r| r.name == "Property");
+    if property.is_none() {
+        report.warn(
+            "No Property resource",
+            "Listing helpers in this crate assume a Property resource",
+        );
+    }
+    Some(property.unwrap_or(&resources[0]).name.to_string())
+}
+
+fn finish(report: Report) -> Result<(), Box<dyn std::error::Error>> {
+    println!();
+    if report.failures == 0 {
+        println!("Ready: all checks passed");
+        Ok(())
+    } else {
+        Err(format!("{} check(s) failed", report.failures).into())
+    }
+}
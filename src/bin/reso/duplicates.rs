@@ -0,0 +1,61 @@
+//! `reso duplicates`: cross-reference listings that appear in several datasets.
+
+use reso_examples::duplicates::{find_duplicates, Dataset};
+use std::path::{Path, PathBuf};
+
+/// Parses a `name=path` dataset argument; a bare path is named after its file stem.
+pub fn parse_dataset(arg: &str) -> Result<(String, PathBuf), String> {
+    match arg.split_once('=') {
+        Some((name, path)) if !name.is_empty() && !path.is_empty() => {
+            Ok((name.to_string(), PathBuf::from(path)))
+        }
+        Some(_) => Err(format!("Invalid dataset '{}' (expected name=path.jsonl)", arg)),
+        None => {
+            let path = PathBuf::from(arg);
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .ok_or_else(|| format!("Invalid dataset path '{}'", arg))?;
+            Ok((name, path))
+        }
+    }
+}
+
+pub fn run(
+    datasets: &[(String, PathBuf)],
+    key_field: &str,
+    out: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let datasets = datasets
+        .iter()
+        .map(|(name, path)| Dataset::from_jsonl(name, path, key_field))
+        .collect::<Result<Vec<_>, _>>()?;
+    let matches = find_duplicates(&datasets);
+
+    let writer: Box<dyn std::io::Write> = match out {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["dataset_a", "key_a", "dataset_b", "key_b", "same_parcel", "same_address"])?;
+    for m in &matches {
+        writer.write_record([
+            m.dataset_a.as_str(),
+            m.key_a.as_str(),
+            m.dataset_b.as_str(),
+            m.key_b.as_str(),
+            if m.same_parcel { "true" } else { "false" },
+            if m.same_address { "true" } else { "false" },
+        ])?;
+    }
+    writer.flush()?;
+
+    let records: usize = datasets.iter().map(|d| d.records.len()).sum();
+    eprintln!(
+        "{} probable duplicates among {} listings in {} datasets",
+        matches.len(),
+        records,
+        datasets.len()
+    );
+    Ok(())
+}
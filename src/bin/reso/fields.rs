@@ -0,0 +1,52 @@
+//! `reso fields <resource>`: describe the fields of a resource.
+
+use crate::table::Table;
+use reso_examples::dictionary::is_standard_field;
+use reso_examples::metadata::ResoSchema;
+
+pub fn run(
+    schema: &ResoSchema,
+    resource: &str,
+    standard_only: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entity_type = schema
+        .resource(resource)
+        .ok_or_else(|| format!("Resource '{}' not found in metadata", resource))?;
+
+    let mut table = Table::new(["Field", "Type", "Nullable", "Standard"]);
+    for field in &entity_type.fields {
+        let standard = is_standard_field(resource, &field.name);
+        if standard_only && !standard {
+            continue;
+        }
+        table.add_row([
+            field.name.as_str(),
+            field.edm_type.as_str(),
+            yes_no(field.nullable),
+            yes_no(standard),
+        ]);
+    }
+    print!("{}", table.render());
+
+    if !entity_type.navigation.is_empty() {
+        let mut table = Table::new(["Navigation", "Target", "Collection"]);
+        for navigation in &entity_type.navigation {
+            table.add_row([
+                navigation.name.as_str(),
+                navigation.target_type.as_str(),
+                yes_no(navigation.collection),
+            ]);
+        }
+        println!();
+        print!("{}", table.render());
+    }
+    Ok(())
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
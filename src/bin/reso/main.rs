@@ -0,0 +1,661 @@
+//! `reso`: a command-line tool for exploring RESO Web API servers.
+//!
+//! Credentials are read from the environment (or a `.env` file) exactly as
+//! in the examples: `RESO_BASE_URL`, `RESO_TOKEN`, and optionally
+//! `RESO_DATASET_ID` and `RESO_TIMEOUT`. With `--demo`, commands answer from
+//! the bundled sample dataset instead and need no credentials.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! cargo run --bin reso -- resources
+//! cargo run --bin reso -- --demo query Property --filter "City eq 'Austin'" --top 5
+//! cargo run --bin reso -- fields Property --standard-only
+//! cargo run --bin reso -- query Property --filter "City eq 'Austin'" --select ListingKey,ListPrice --top 5
+//! cargo run --bin reso -- replicate Property --out property.jsonl
+//! cargo run --bin reso --features db-postgres -- replicate Property --postgres "host=localhost dbname=mls" --pg-table mls.property
+//! cargo run --bin reso -- retry-failed Property --out property.jsonl
+//! cargo run --bin reso -- compact property.jsonl property.delta-1.jsonl property.delta-2.jsonl
+//! cargo run --bin reso -- audit Property --sqlite mls.sqlite --sample 50
+//! cargo run --bin reso -- pii-scan Property --out property.jsonl
+//! cargo run --bin reso -- churn property.jsonl --top 10
+//! cargo run --bin reso -- duplicates --dataset actris=actris.jsonl --dataset har=har.jsonl
+//! cargo run --bin reso -- count Property --filter "StandardStatus eq 'Active'" --by City
+//! cargo run --bin reso -- shell
+//! cargo run --bin reso -- dashboard
+//! cargo run --bin reso -- doctor
+//! cargo run --bin reso -- codegen --out src/fields.rs
+//! cargo run --bin reso -- codegen --structs --out src/records.rs
+//! cargo run --bin reso -- migrations --dialect postgres --resource Property
+//! cargo run --bin reso -- completions bash > /etc/bash_completion.d/reso
+//! ```
+
+mod audit;
+mod cache;
+mod churn;
+mod codegen;
+mod compact;
+mod completions;
+mod count;
+mod dashboard;
+mod doctor;
+mod duplicates;
+mod fields;
+mod migrations;
+mod pii;
+mod query;
+mod replicate;
+mod resources;
+mod shell;
+mod table;
+
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use reso_client::{ClientConfig, ResoError};
+use reso_examples::client::{debug_wire_from_env, HttpClient, ResoApi};
+use reso_examples::demo::{DemoClient, DEMO_BASE_URL};
+use reso_examples::export::{ConflictPolicy, Partition};
+use reso_examples::metadata::{parse_metadata, ResoSchema, UnknownFieldPolicy};
+use reso_examples::migrations::{MigrationFormat, SqlDialect};
+use reso_examples::rate_limit::{RateLimit, RateLimitedClient};
+use reso_examples::retry_queue::DEFAULT_RETRY_QUEUE;
+use reso_examples::status::{StatusFile, DEFAULT_STATUS_FILE};
+use reso_examples::validate::validate_query;
+use reso_examples::{create_client, fetch_metadata, load_env};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "reso", about = "Explore and query RESO Web API servers")]
+pub(crate) struct Cli {
+    /// Answer from the bundled sample dataset instead of a RESO server
+    #[arg(long, global = true)]
+    demo: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List resources with their key field and field count
+    Resources,
+    /// Describe the fields of a resource
+    Fields {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Only show RESO Data Dictionary standard fields
+        #[arg(long)]
+        standard_only: bool,
+    },
+    /// Run a query and print the results
+    Query {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// OData filter expression
+        #[arg(long)]
+        filter: Option<String>,
+        /// Comma-separated fields to select
+        #[arg(long, value_delimiter = ',')]
+        select: Vec<String>,
+        /// Maximum number of records to return
+        #[arg(long, default_value_t = 10)]
+        top: u32,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = query::Format::Table)]
+        format: query::Format,
+        /// Check --select against the (cached) metadata first and `drop` or `error` on
+        /// fields the server does not define
+        #[arg(long)]
+        unknown_fields: Option<UnknownFieldPolicy>,
+        /// Check the query's fields and filter values against the (cached) metadata
+        /// before sending it
+        #[arg(long)]
+        validate: bool,
+        /// Print the request URL and headers (token redacted) instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Replicate a resource into a JSONL file, SQLite database, or Parquet directory
+    #[command(group(ArgGroup::new("destination").required(true).args(["out", "csv", "sqlite", "parquet", "postgres"])))]
+    Replicate {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Append records to a newline-delimited JSON file
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Append records to a CSV file, keeping the columns of an existing header
+        #[arg(long)]
+        csv: Option<PathBuf>,
+        /// Upsert records into a SQLite database
+        #[arg(long)]
+        sqlite: Option<PathBuf>,
+        /// Write Parquet files into a directory (requires the export-parquet feature)
+        #[arg(long)]
+        parquet: Option<PathBuf>,
+        /// Upsert records into PostgreSQL, given a connection string such as
+        /// "host=localhost user=postgres dbname=mls" (requires the db-postgres feature)
+        #[arg(long, value_name = "CONNECTION")]
+        postgres: Option<String>,
+        /// Table for --postgres, optionally schema-qualified as schema.table (default: the resource name)
+        #[arg(long, conflicts_with_all = ["out", "csv", "sqlite", "parquet"])]
+        pg_table: Option<String>,
+        /// Only replicate records modified after this timestamp, e.g. 2025-01-01T00:00:00Z
+        #[arg(long)]
+        since: Option<String>,
+        /// OData filter expression
+        #[arg(long)]
+        filter: Option<String>,
+        /// Checkpoint file (default: <resource>.checkpoint.json)
+        #[arg(long)]
+        checkpoint: Option<PathBuf>,
+        /// Primary key field for database output (default: the resource's key)
+        #[arg(long)]
+        key_field: Option<String>,
+        /// How database output treats records already stored: newest-wins, overwrite, or skip
+        #[arg(long, default_value = "newest-wins")]
+        on_conflict: ConflictPolicy,
+        /// Split --out or --parquet output into Hive-style directories by a field,
+        /// or by `date` (the ModificationTimestamp date); repeat for nested partitions
+        #[arg(long)]
+        partition_by: Vec<Partition>,
+        /// Add SQLite columns for fields the server's metadata gained since the table was created
+        #[arg(long)]
+        evolve_schema: bool,
+        /// Status file read by `reso dashboard`
+        #[arg(long, default_value = DEFAULT_STATUS_FILE)]
+        status: PathBuf,
+        /// Where runs that fail part-way are queued for `reso retry-failed`
+        #[arg(long, default_value = DEFAULT_RETRY_QUEUE)]
+        retry_queue: PathBuf,
+        /// After the run, compare the number of stored records with the server's count
+        #[arg(long)]
+        verify: bool,
+        /// Also compare counts grouped by a field, e.g. City (implies --verify)
+        #[arg(long, value_name = "FIELD")]
+        verify_by: Option<String>,
+        /// Stamp each record with a `_provenance` object: dataset id, originating system,
+        /// fetch time, and a hash of the resource and filter
+        #[arg(long)]
+        provenance: bool,
+        /// Fill in missing Latitude and Longitude from the address with OpenStreetMap
+        /// Nominatim (one request per second), identifying as USER_AGENT
+        #[arg(long, value_name = "USER_AGENT")]
+        geocode: Option<String>,
+        /// Write a manifest of the exported files, with record counts and SHA-256
+        /// checksums: <file>.manifest.json, or manifest.json in an output directory
+        #[arg(long, conflicts_with_all = ["sqlite", "postgres"])]
+        manifest: bool,
+        /// Leave out owner names, private remarks, showing details, and agent contact
+        /// fields, for exports published outside the brokerage
+        #[arg(long)]
+        redact_sensitive: bool,
+        /// Leave a field out of the output; repeat for more fields
+        #[arg(long = "drop-field", value_name = "FIELD")]
+        drop_fields: Vec<String>,
+        /// Replace a field with a keyed hash (RESO_REDACTION_KEY); repeat for more fields
+        #[arg(long = "hash-field", value_name = "FIELD")]
+        hash_fields: Vec<String>,
+    },
+    /// Fetch the records of failed replication runs recorded in the retry queue
+    #[command(group(ArgGroup::new("destination").required(true).args(["out", "csv", "sqlite", "parquet", "postgres"])))]
+    RetryFailed {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Append records to a newline-delimited JSON file
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Append records to a CSV file, keeping the columns of an existing header
+        #[arg(long)]
+        csv: Option<PathBuf>,
+        /// Upsert records into a SQLite database
+        #[arg(long)]
+        sqlite: Option<PathBuf>,
+        /// Write Parquet files into a directory (requires the export-parquet feature)
+        #[arg(long)]
+        parquet: Option<PathBuf>,
+        /// Upsert records into PostgreSQL, given a connection string such as
+        /// "host=localhost user=postgres dbname=mls" (requires the db-postgres feature)
+        #[arg(long, value_name = "CONNECTION")]
+        postgres: Option<String>,
+        /// Table for --postgres, optionally schema-qualified as schema.table (default: the resource name)
+        #[arg(long, conflicts_with_all = ["out", "csv", "sqlite", "parquet"])]
+        pg_table: Option<String>,
+        /// Primary key field for database output (default: the resource's key)
+        #[arg(long)]
+        key_field: Option<String>,
+        /// How database output treats records already stored: newest-wins, overwrite, or skip
+        #[arg(long, default_value = "newest-wins")]
+        on_conflict: ConflictPolicy,
+        /// Split --out or --parquet output into Hive-style directories by a field,
+        /// or by `date` (the ModificationTimestamp date); repeat for nested partitions
+        #[arg(long)]
+        partition_by: Vec<Partition>,
+        /// Leave out owner names, private remarks, showing details, and agent contact
+        /// fields, for exports published outside the brokerage
+        #[arg(long)]
+        redact_sensitive: bool,
+        /// Leave a field out of the output; repeat for more fields
+        #[arg(long = "drop-field", value_name = "FIELD")]
+        drop_fields: Vec<String>,
+        /// Replace a field with a keyed hash (RESO_REDACTION_KEY); repeat for more fields
+        #[arg(long = "hash-field", value_name = "FIELD")]
+        hash_fields: Vec<String>,
+        /// Retry queue written by `reso replicate`
+        #[arg(long, default_value = DEFAULT_RETRY_QUEUE)]
+        retry_queue: PathBuf,
+        /// Stamp each record with a `_provenance` object as `replicate --provenance` does;
+        /// the hash covers the resource only, since the queued runs had different filters
+        #[arg(long)]
+        provenance: bool,
+    },
+    /// Re-fetch a random sample of local records and report differences from the server
+    #[command(group(ArgGroup::new("source").required(true).args(["out", "sqlite"])))]
+    Audit {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Newline-delimited JSON store written by `reso replicate --out`
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// SQLite database written by `reso replicate --sqlite`
+        #[arg(long)]
+        sqlite: Option<PathBuf>,
+        /// Number of records to check
+        #[arg(long, default_value_t = 100)]
+        sample: usize,
+        /// Key field of the records (default: the resource's key)
+        #[arg(long)]
+        key_field: Option<String>,
+    },
+    /// Report email addresses and phone numbers found in free-text fields of local records
+    #[command(group(ArgGroup::new("source").required(true).args(["out", "sqlite"])))]
+    PiiScan {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// Newline-delimited JSON store written by `reso replicate --out`
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// SQLite database written by `reso replicate --sqlite`
+        #[arg(long)]
+        sqlite: Option<PathBuf>,
+        /// Text field to scan; repeat for more (default: PublicRemarks, SyndicationRemarks, Directions)
+        #[arg(long = "field", value_name = "FIELD")]
+        fields: Vec<String>,
+        /// Key field of the records (default: the resource's key)
+        #[arg(long)]
+        key_field: Option<String>,
+    },
+    /// Count records, optionally grouped by a field
+    Count {
+        /// Resource name, e.g. Property
+        resource: String,
+        /// OData filter expression
+        #[arg(long)]
+        filter: Option<String>,
+        /// Group counts by the values of this field
+        #[arg(long)]
+        by: Option<String>,
+    },
+    /// Interactive prompt for refining and running queries
+    Shell {
+        /// File where saved query templates are stored
+        #[arg(long, default_value = "reso_templates.json")]
+        templates: PathBuf,
+    },
+    /// Live view of sync progress, throughput, errors, and rate limiting
+    Dashboard {
+        /// Status file written by sync runs
+        #[arg(long, default_value = DEFAULT_STATUS_FILE)]
+        status: PathBuf,
+    },
+    /// List past sync runs from the status file, most recent first
+    Runs {
+        /// Only show runs of this resource
+        resource: Option<String>,
+        /// Status file written by sync runs
+        #[arg(long, default_value = DEFAULT_STATUS_FILE)]
+        status: PathBuf,
+        /// Maximum number of runs to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Check configuration, connectivity, and server capabilities
+    Doctor,
+    /// Generate Rust field name constants, or record structs, from the server's metadata
+    Codegen {
+        /// File to write; prints to stdout when omitted
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Generate a serde struct per resource instead of field name constants
+        #[arg(long)]
+        structs: bool,
+    },
+    /// Deduplicate a JSONL store, optionally merging in delta files, keeping one record per key
+    Compact {
+        /// The store or base snapshot
+        base: PathBuf,
+        /// Delta files to apply, oldest first
+        deltas: Vec<PathBuf>,
+        /// Where to write the merged snapshot (default: replace the base)
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Field identifying a record
+        #[arg(long, default_value = "ListingKey")]
+        key_field: String,
+        /// Split the output into numbered files of at most this size, e.g. 256M
+        #[arg(long)]
+        max_file_size: Option<String>,
+    },
+    /// Report how often each field changes between successive versions of records
+    Churn {
+        /// JSONL stores or delta files written by `reso replicate --out`, oldest first
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Field identifying a record
+        #[arg(long, default_value = "ListingKey")]
+        key_field: String,
+        /// Fields to show, most often changed first
+        #[arg(long, default_value_t = 25)]
+        top: usize,
+    },
+    /// Cross-reference listings that appear in more than one MLS dataset, as CSV
+    Duplicates {
+        /// A JSONL store per dataset, as name=path (repeat for each dataset)
+        #[arg(long = "dataset", required = true, num_args = 1, value_parser = duplicates::parse_dataset)]
+        datasets: Vec<(String, PathBuf)>,
+        /// Field identifying a record
+        #[arg(long, default_value = "ListingKey")]
+        key_field: String,
+        /// Write the cross-reference table to a file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Generate SQL migrations for the database sinks from the server's metadata
+    Migrations {
+        /// Directory holding the migrations
+        #[arg(long, default_value = "migrations")]
+        dir: PathBuf,
+        /// Only generate tables for these resources (default: all)
+        #[arg(long = "resource")]
+        resources: Vec<String>,
+        /// Target database: sqlite or postgres
+        #[arg(long, default_value = "sqlite")]
+        dialect: SqlDialect,
+        /// File naming: sqlx or refinery
+        #[arg(long, default_value = "sqlx")]
+        format: MigrationFormat,
+    },
+    /// Print a shell completion script, completing resource names from cached metadata
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    load_env()?;
+    let cli = Cli::parse();
+
+    // Commands that work offline from local files
+    match &cli.command {
+        Command::Completions { shell } => {
+            completions::run(Cli::command(), *shell);
+            return Ok(());
+        }
+        Command::Dashboard { status } => return dashboard::run(StatusFile::new(status)),
+        Command::Runs {
+            resource,
+            status,
+            limit,
+        } => return dashboard::list_runs(&StatusFile::new(status), resource.as_deref(), *limit),
+        // Reports missing configuration instead of failing on it
+        Command::Doctor => return doctor::run().await,
+        Command::Duplicates {
+            datasets,
+            key_field,
+            out,
+        } => return duplicates::run(datasets, key_field, out.as_deref()),
+        Command::PiiScan {
+            resource,
+            out,
+            sqlite,
+            fields,
+            key_field,
+        } => {
+            let source = match (out, sqlite) {
+                (Some(path), _) => pii::Source::Jsonl(path.clone()),
+                (_, Some(path)) => pii::Source::Sqlite(path.clone()),
+                _ => unreachable!("clap requires one source"),
+            };
+            return pii::run(resource, source, fields, key_field.clone());
+        }
+        Command::Compact {
+            base,
+            deltas,
+            out,
+            key_field,
+            max_file_size,
+        } => {
+            return compact::run(base, deltas, out.as_deref(), key_field, max_file_size.as_deref())
+        }
+        Command::Churn { files, key_field, top } => return churn::run(files, key_field, *top),
+        _ => {}
+    }
+
+    // Paced so long replications stay within vendor quotas; a 429 pauses
+    // every request for the server's Retry-After
+    let client: Box<dyn ResoApi> = if cli.demo {
+        Box::new(DemoClient::new())
+    } else {
+        Box::new(RateLimitedClient::new(server_client()?, RateLimit::from_env()?))
+    };
+
+    match cli.command {
+        Command::Resources => {
+            let schema = load_schema(&client).await?;
+            resources::run(&schema);
+        }
+        Command::Fields {
+            resource,
+            standard_only,
+        } => {
+            let schema = load_schema(&client).await?;
+            fields::run(&schema, &resource, standard_only)?;
+        }
+        Command::Query {
+            resource,
+            filter,
+            select,
+            top,
+            format,
+            unknown_fields,
+            validate,
+            dry_run,
+        } => {
+            let schema = if validate || (unknown_fields.is_some() && !select.is_empty()) {
+                Some(match cache::cached_schema().filter(|_| !cli.demo) {
+                    Some(schema) => schema,
+                    None => load_schema(&client).await?,
+                })
+            } else {
+                None
+            };
+            let select = match (unknown_fields, &schema) {
+                (Some(policy), Some(schema)) if !select.is_empty() => {
+                    query::check_select(schema, &resource, select, policy)?
+                }
+                _ => select,
+            };
+            if let (true, Some(schema)) = (validate, &schema) {
+                let built = query::build(&resource, filter.as_deref(), &select, Some(top))?;
+                validate_query(schema, &built)?;
+            }
+            if dry_run {
+                let query = query::build(&resource, filter.as_deref(), &select, Some(top))?;
+                let renderer = match cli.demo {
+                    true => HttpClient::builder(ClientConfig::new(DEMO_BASE_URL, "")).build()?,
+                    false => HttpClient::from_env()?,
+                };
+                println!("{}", renderer.dry_run(&query)?);
+                return Ok(());
+            }
+            query::run(&client, &resource, filter.as_deref(), &select, Some(top), format).await?;
+        }
+        Command::Replicate {
+            resource,
+            out,
+            csv,
+            sqlite,
+            parquet,
+            postgres,
+            pg_table,
+            since,
+            filter,
+            checkpoint,
+            key_field,
+            on_conflict,
+            partition_by,
+            evolve_schema,
+            status,
+            retry_queue,
+            verify,
+            verify_by,
+            provenance,
+            geocode,
+            manifest,
+            redact_sensitive,
+            drop_fields,
+            hash_fields,
+        } => {
+            let args = replicate::ReplicateArgs {
+                resource,
+                destination: replicate::Destination::from_args(out, csv, sqlite, parquet, postgres, pg_table),
+                filter,
+                since,
+                checkpoint,
+                sink: replicate::SinkOptions {
+                    key_field,
+                    on_conflict,
+                    partition_by,
+                    redaction: replicate::SinkOptions::redaction(redact_sensitive, &drop_fields, &hash_fields)?,
+                },
+                evolve_schema,
+                status,
+                retry_queue,
+                verify,
+                verify_by,
+                provenance,
+                geocode,
+                manifest,
+            };
+            replicate::run(&client, args).await?;
+        }
+        Command::RetryFailed {
+            resource,
+            out,
+            csv,
+            sqlite,
+            parquet,
+            postgres,
+            pg_table,
+            key_field,
+            on_conflict,
+            partition_by,
+            redact_sensitive,
+            drop_fields,
+            hash_fields,
+            retry_queue,
+            provenance,
+        } => {
+            let args = replicate::RetryArgs {
+                resource,
+                destination: replicate::Destination::from_args(out, csv, sqlite, parquet, postgres, pg_table),
+                sink: replicate::SinkOptions {
+                    key_field,
+                    on_conflict,
+                    partition_by,
+                    redaction: replicate::SinkOptions::redaction(redact_sensitive, &drop_fields, &hash_fields)?,
+                },
+                retry_queue,
+                provenance,
+            };
+            replicate::retry(&client, args).await?;
+        }
+        Command::Audit {
+            resource,
+            out,
+            sqlite,
+            sample,
+            key_field,
+        } => {
+            let source = match (out, sqlite) {
+                (Some(path), _) => audit::Source::Jsonl(path),
+                (_, Some(path)) => audit::Source::Sqlite(path),
+                _ => unreachable!("clap requires one source"),
+            };
+            audit::run(&client, &resource, source, sample, key_field).await?;
+        }
+        Command::Count {
+            resource,
+            filter,
+            by,
+        } => {
+            count::run(&client, &resource, filter.as_deref(), by.as_deref()).await?;
+        }
+        Command::Shell { templates } => {
+            shell::run(&client, templates).await?;
+        }
+        Command::Codegen { out, structs } => {
+            let schema = load_schema(&client).await?;
+            codegen::run(&schema, out.as_deref(), structs)?;
+        }
+        Command::Migrations {
+            dir,
+            resources,
+            dialect,
+            format,
+        } => {
+            let xml = fetch_metadata(&client).await?;
+            if client.base_url() != DEMO_BASE_URL {
+                cache::store_metadata(&xml);
+            }
+            migrations::run(&xml, &dir, &resources, dialect, format)?;
+        }
+        Command::Completions { .. }
+        | Command::Dashboard { .. }
+        | Command::Runs { .. }
+        | Command::Doctor
+        | Command::Compact { .. }
+        | Command::Churn { .. }
+        | Command::Duplicates { .. }
+        | Command::PiiScan { .. } => {
+            unreachable!("handled before connecting")
+        }
+    }
+
+    Ok(())
+}
+
+/// The client for the configured server: `ResoClient`, or `HttpClient`
+/// when `RESO_DEBUG_WIRE` asks for the request and response dump or
+/// `RESO_TOKEN_URL` for OAuth, which only it supports.
+pub(crate) fn server_client() -> Result<Box<dyn ResoApi>, ResoError> {
+    let oauth = std::env::var("RESO_TOKEN_URL").is_ok_and(|url| !url.trim().is_empty());
+    if debug_wire_from_env() || oauth {
+        Ok(Box::new(HttpClient::from_env()?))
+    } else {
+        Ok(Box::new(create_client()?))
+    }
+}
+
+/// Fetches and parses the server's metadata document, refreshing the cache.
+///
+/// The demo dataset's metadata is not cached, so it never stands in for a
+/// real server's.
+async fn load_schema(client: &impl ResoApi) -> Result<ResoSchema, Box<dyn std::error::Error>> {
+    let xml = fetch_metadata(client).await?;
+    let schema = parse_metadata(&xml)?;
+    if client.base_url() != DEMO_BASE_URL {
+        cache::store_metadata(&xml);
+    }
+    Ok(schema)
+}
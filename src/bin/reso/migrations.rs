@@ -0,0 +1,71 @@
+//! `reso migrations`: generate SQL migrations for the database sinks.
+//!
+//! The metadata each resource's migrations were generated from is kept in
+//! the migrations directory, per dialect, so the next run only emits
+//! `ALTER TABLE` migrations for what changed, and a run narrowed with
+//! `--resource` leaves the other resources' snapshots alone.
+
+use chrono::Utc;
+use reso_examples::metadata::parse_metadata;
+use reso_examples::migrations::{generate_migrations, write_migrations, MigrationFormat, SqlDialect};
+use std::path::{Path, PathBuf};
+
+/// Directory of metadata snapshots, one per dialect and resource, that the
+/// existing migrations were generated from. Migration tools ignore it.
+const SNAPSHOT_DIR: &str = ".reso_metadata";
+
+pub fn run(
+    xml: &str,
+    dir: &Path,
+    resources: &[String],
+    dialect: SqlDialect,
+    format: MigrationFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let current = parse_metadata(xml)?;
+    let version: u64 = Utc::now().format("%Y%m%d%H%M%S").to_string().parse()?;
+
+    let mut migrations = Vec::new();
+    let mut diffed = Vec::new();
+    for resource in current.resources() {
+        if !resources.is_empty() && !resources.iter().any(|r| r == resource.name) {
+            continue;
+        }
+        let snapshot = snapshot_path(dir, dialect, resource.name);
+        let previous = match std::fs::read_to_string(&snapshot) {
+            Ok(previous) => Some(parse_metadata(&previous)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("Cannot read {}: {}", snapshot.display(), e).into()),
+        };
+        let next_version = version + migrations.len() as u64;
+        let generated = generate_migrations(previous.as_ref(), &current, &[resource.name], dialect, next_version);
+        if !generated.is_empty() {
+            migrations.extend(generated);
+            diffed.push(snapshot);
+        }
+    }
+    if migrations.is_empty() {
+        println!("Schema unchanged; no migrations to write");
+        return Ok(());
+    }
+
+    for path in write_migrations(dir, &migrations, format)? {
+        println!("Wrote {}", path.display());
+    }
+    // Only the resources migrated here move on to the new schema
+    for snapshot in diffed {
+        if let Some(parent) = snapshot.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&snapshot, xml)?;
+    }
+    Ok(())
+}
+
+/// Where the schema `resource`'s `dialect` migrations were generated from is kept.
+fn snapshot_path(dir: &Path, dialect: SqlDialect, resource: &str) -> PathBuf {
+    let dialect = match dialect {
+        SqlDialect::Sqlite => "sqlite",
+        SqlDialect::Postgres => "postgres",
+    };
+    dir.join(SNAPSHOT_DIR).join(dialect).join(format!("{}.xml", resource))
+}
@@ -0,0 +1,38 @@
+//! `reso pii-scan <resource>`: report likely personal information in free-text fields.
+
+use reso_examples::export::{default_key_field, SqliteSink};
+use reso_examples::pii::{scan_jsonl, scan_records, PUBLIC_TEXT_FIELDS};
+use std::path::PathBuf;
+
+/// Where the replicated records are stored.
+pub enum Source {
+    Jsonl(PathBuf),
+    Sqlite(PathBuf),
+}
+
+pub fn run(
+    resource: &str,
+    source: Source,
+    fields: &[String],
+    key_field: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let key_field = key_field.unwrap_or_else(|| default_key_field(resource));
+    let fields: Vec<&str> = if fields.is_empty() {
+        PUBLIC_TEXT_FIELDS.to_vec()
+    } else {
+        fields.iter().map(String::as_str).collect()
+    };
+
+    let report = match source {
+        Source::Jsonl(path) => scan_jsonl(path, &key_field, &fields)?,
+        Source::Sqlite(path) => {
+            let records = SqliteSink::open(path, resource, &key_field)?.records()?;
+            scan_records(&records, &key_field, &fields)
+        }
+    };
+    println!("{}", report);
+    if !report.is_clean() {
+        return Err("Found likely personal information in free-text fields".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! `reso query <resource>`: run an ad-hoc query and render the results.
+
+use crate::table::Table;
+use clap::ValueEnum;
+use reso_client::{JsonValue, Query, ResoError};
+use reso_examples::client::ResoApi;
+use reso_examples::metadata::{ResoSchema, UnknownFieldPolicy};
+use reso_examples::retry::RetryPolicy;
+use reso_examples::{build_query, build_query_with_select, execute_query_with_retry};
+
+/// Widest cell rendered in table output before truncation.
+const MAX_CELL_WIDTH: usize = 40;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Csv,
+    Table,
+}
+
+pub async fn run(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    select: &[String],
+    top: Option<u32>,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let query = build(resource, filter, select, top)?;
+    let response = execute_query_with_retry(client, &query, RetryPolicy::default()).await?;
+    let records = response["value"].as_array().cloned().unwrap_or_default();
+    render(&records, select, format)
+}
+
+/// Applies the unknown-field policy to the select list, warning about
+/// dropped fields.
+pub fn check_select(
+    schema: &ResoSchema,
+    resource: &str,
+    select: Vec<String>,
+    policy: UnknownFieldPolicy,
+) -> Result<Vec<String>, ResoError> {
+    let fields: Vec<&str> = select.iter().map(String::as_str).collect();
+    let checked = schema.check_select(resource, &fields, policy)?;
+    if !checked.dropped.is_empty() {
+        eprintln!("Not selecting fields {} does not define: {}", resource, checked.dropped.join(", "));
+    }
+    Ok(checked.fields.into_iter().map(str::to_string).collect())
+}
+
+/// Builds the query, selecting fields only when some were given.
+pub fn build(
+    resource: &str,
+    filter: Option<&str>,
+    select: &[String],
+    top: Option<u32>,
+) -> Result<Query, ResoError> {
+    if select.is_empty() {
+        build_query(resource, filter, top)
+    } else {
+        let fields: Vec<&str> = select.iter().map(String::as_str).collect();
+        build_query_with_select(resource, filter, &fields, top)
+    }
+}
+
+/// Prints records in the given format.
+///
+/// CSV and table columns follow `select` when given, otherwise every field
+/// present in the records.
+pub fn render(
+    records: &[JsonValue],
+    select: &[String],
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(records)?),
+        Format::Csv => {
+            let columns = columns(records, select);
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            writer.write_record(&columns)?;
+            for record in records {
+                writer.write_record(columns.iter().map(|c| cell(&record[c.as_str()])))?;
+            }
+            writer.flush()?;
+        }
+        Format::Table => {
+            let columns = columns(records, select);
+            let mut table = Table::new(columns.clone());
+            for record in records {
+                table.add_row(columns.iter().map(|c| truncate(cell(&record[c.as_str()]))));
+            }
+            print!("{}", table.render());
+            eprintln!("{} records", records.len());
+        }
+    }
+    Ok(())
+}
+
+/// The selected fields, or every field seen across the records in
+/// first-seen order, without OData annotations.
+fn columns(records: &[JsonValue], select: &[String]) -> Vec<String> {
+    if !select.is_empty() {
+        return select.to_vec();
+    }
+
+    let mut columns: Vec<String> = Vec::new();
+    for record in records {
+        if let Some(object) = record.as_object() {
+            for key in object.keys() {
+                if !key.starts_with('@') && !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+    columns
+}
+
+fn cell(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn truncate(value: String) -> String {
+    if value.chars().count() <= MAX_CELL_WIDTH {
+        return value;
+    }
+    let mut truncated: String = value.chars().take(MAX_CELL_WIDTH - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_columns_skip_annotations_and_keep_order() {
+        let records = vec![
+            json!({"ListingKey": "A", "@odata.id": "x", "City": "Austin"}),
+            json!({"ListingKey": "B", "ListPrice": 1}),
+        ];
+        let all = columns(&records, &[]);
+        assert_eq!(all.len(), 3);
+        assert!(!all.iter().any(|c| c.starts_with('@')));
+        assert_eq!(all.last().unwrap(), "ListPrice");
+        assert_eq!(columns(&records, &["ListPrice".to_string()]), vec!["ListPrice"]);
+        assert_eq!(cell(&json!(null)), "");
+        assert_eq!(truncate("x".repeat(50)).chars().count(), MAX_CELL_WIDTH);
+    }
+}
@@ -0,0 +1,394 @@
+//! `reso replicate <resource>`: replicate a resource into a file or database.
+//!
+//! Also `reso retry-failed <resource>`, which recovers the runs that
+//! `replicate` recorded in the retry queue.
+
+use crate::cache;
+use reso_examples::cancel::{cancel_on_ctrl_c, is_cancelled};
+use reso_examples::client::ResoApi;
+use reso_examples::enrich::{CoordinateEnricher, EnrichingSink, EnrichmentPipeline};
+use reso_examples::export::{
+    default_key_field, ConflictPolicy, CsvSink, JsonlSink, Partition, PartitionedSink, RecordSink, SqliteSink,
+};
+use reso_examples::fetch_metadata;
+use reso_examples::geocode::NominatimGeocoder;
+use reso_examples::manifest::ManifestSink;
+use reso_examples::metadata::parse_metadata;
+use reso_examples::provenance::{Provenance, ProvenanceSink};
+use reso_examples::reconcile::reconcile_counts;
+use reso_examples::redact::{RedactingSink, Redaction};
+use reso_examples::retry_queue::{is_upstream_failure, retry_failed, FailedBatch, RetryQueue};
+use reso_examples::status::{StatusFile, StatusSink};
+use reso_examples::sync::{replicate_all_cancellable, Checkpoint};
+use std::path::PathBuf;
+
+/// Where replicated records are written.
+pub enum Destination {
+    Jsonl(PathBuf),
+    Csv(PathBuf),
+    Sqlite(PathBuf),
+    Parquet(PathBuf),
+    /// A connection string, and the table if not named after the resource
+    Postgres { params: String, table: Option<String> },
+}
+
+/// Options shared by every destination.
+pub struct SinkOptions {
+    pub key_field: Option<String>,
+    pub on_conflict: ConflictPolicy,
+    pub partition_by: Vec<Partition>,
+    pub redaction: Option<Redaction>,
+}
+
+impl SinkOptions {
+    /// Builds the redaction from `--redact-sensitive`, `--drop-field`, and
+    /// `--hash-field`; hashing is keyed by `RESO_REDACTION_KEY`.
+    pub fn redaction(
+        sensitive: bool,
+        drop: &[String],
+        hash: &[String],
+    ) -> Result<Option<Redaction>, Box<dyn std::error::Error>> {
+        if !sensitive && drop.is_empty() && hash.is_empty() {
+            return Ok(None);
+        }
+        let key = match std::env::var("RESO_REDACTION_KEY") {
+            Ok(key) if !key.is_empty() => key,
+            _ if hash.is_empty() => String::new(),
+            _ => return Err("--hash-field requires RESO_REDACTION_KEY to be set".into()),
+        };
+
+        let mut redaction = if sensitive {
+            Redaction::sensitive_fields(key.as_bytes())
+        } else {
+            Redaction::new(key.as_bytes())
+        };
+        for field in drop {
+            redaction = redaction.drop(field);
+        }
+        for field in hash {
+            redaction = redaction.hash(field);
+        }
+        Ok(Some(redaction))
+    }
+}
+
+impl Destination {
+    /// Picks the destination from the mutually exclusive command-line flags.
+    pub fn from_args(
+        out: Option<PathBuf>,
+        csv: Option<PathBuf>,
+        sqlite: Option<PathBuf>,
+        parquet: Option<PathBuf>,
+        postgres: Option<String>,
+        pg_table: Option<String>,
+    ) -> Self {
+        match (out, csv, sqlite, parquet, postgres) {
+            (Some(path), _, _, _, _) => Destination::Jsonl(path),
+            (_, Some(path), _, _, _) => Destination::Csv(path),
+            (_, _, Some(path), _, _) => Destination::Sqlite(path),
+            (_, _, _, Some(dir), _) => Destination::Parquet(dir),
+            (_, _, _, _, Some(params)) => Destination::Postgres { params, table: pg_table },
+            _ => unreachable!("clap requires one destination"),
+        }
+    }
+}
+
+pub struct ReplicateArgs {
+    pub resource: String,
+    pub destination: Destination,
+    pub filter: Option<String>,
+    pub since: Option<String>,
+    pub checkpoint: Option<PathBuf>,
+    pub sink: SinkOptions,
+    pub evolve_schema: bool,
+    pub status: PathBuf,
+    pub retry_queue: PathBuf,
+    /// Compare stored counts with the server's after the run
+    pub verify: bool,
+    /// Also compare counts grouped by this field
+    pub verify_by: Option<String>,
+    /// Stamp records with where and when they were fetched
+    pub provenance: bool,
+    /// Geocode records without coordinates through Nominatim, with this user agent
+    pub geocode: Option<String>,
+    /// Write a manifest of the exported files when the run finishes
+    pub manifest: bool,
+}
+
+async fn open_sink(
+    destination: &Destination,
+    resource: &str,
+    options: &SinkOptions,
+) -> Result<Box<dyn RecordSink>, Box<dyn std::error::Error>> {
+    let partitions = options.partition_by.clone();
+    let sink: Box<dyn RecordSink> = match destination {
+        // With partitions, --out names the root directory of the tree
+        Destination::Jsonl(path) if !partitions.is_empty() => {
+            Box::new(PartitionedSink::jsonl(path, partitions))
+        }
+        Destination::Jsonl(path) => Box::new(JsonlSink::append(path)?),
+        Destination::Csv(_) | Destination::Sqlite(_) | Destination::Postgres { .. } if !partitions.is_empty() => {
+            return Err("--partition-by applies to --out and --parquet only".into())
+        }
+        Destination::Csv(path) => Box::new(CsvSink::append(path)?),
+        Destination::Sqlite(path) => {
+            let key_field = options
+                .key_field
+                .clone()
+                .unwrap_or_else(|| default_key_field(resource));
+            let sink = SqliteSink::open(path, resource, &key_field)?;
+            Box::new(sink.with_conflict_policy(options.on_conflict))
+        }
+        Destination::Parquet(dir) => parquet_sink(dir, partitions)?,
+        Destination::Postgres { params, table } => {
+            let key_field = options
+                .key_field
+                .clone()
+                .unwrap_or_else(|| default_key_field(resource));
+            let table = table.as_deref().unwrap_or(resource);
+            postgres_sink(params, table, &key_field, options.on_conflict).await?
+        }
+    };
+    Ok(match &options.redaction {
+        Some(redaction) => Box::new(RedactingSink::new(sink, redaction.clone())),
+        None => sink,
+    })
+}
+
+/// Where `--manifest` writes: inside an output directory, or next to a single file.
+fn manifest_path(destination: &Destination, options: &SinkOptions) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let next_to = |path: &PathBuf| {
+        let mut name = path.clone().into_os_string();
+        name.push(".manifest.json");
+        Ok(PathBuf::from(name))
+    };
+    match destination {
+        Destination::Jsonl(path) if options.partition_by.is_empty() => next_to(path),
+        Destination::Csv(path) => next_to(path),
+        Destination::Jsonl(dir) | Destination::Parquet(dir) => Ok(dir.join("manifest.json")),
+        Destination::Sqlite(_) | Destination::Postgres { .. } => {
+            Err("--manifest applies to --out, --csv, and --parquet only".into())
+        }
+    }
+}
+
+pub async fn run(client: &impl ResoApi, args: ReplicateArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut sink = open_sink(&args.destination, &args.resource, &args.sink).await?;
+    if args.provenance {
+        sink = with_provenance(sink, &args.resource, args.filter.as_deref());
+    }
+    if let Some(user_agent) = &args.geocode {
+        // Outside the redaction, which may drop the address fields
+        let geocoder = NominatimGeocoder::new(user_agent.as_str());
+        let pipeline = EnrichmentPipeline::new()
+            .with_enricher(CoordinateEnricher::new(geocoder))
+            .with_concurrency(1);
+        sink = Box::new(EnrichingSink::new(sink, pipeline));
+    }
+    if args.manifest {
+        let path = manifest_path(&args.destination, &args.sink)?;
+        sink = Box::new(ManifestSink::new(sink, path, &args.resource, args.filter.as_deref()));
+    }
+
+    if args.evolve_schema {
+        let xml = fetch_metadata(client).await?;
+        cache::store_metadata(&xml);
+        let schema = parse_metadata(&xml)?;
+        let entity_type = schema
+            .resource(&args.resource)
+            .ok_or_else(|| format!("Resource '{}' is not in the server's metadata", args.resource))?;
+        eprintln!("{}", sink.evolve_schema(entity_type).await?);
+    }
+
+    let checkpoint_path = args
+        .checkpoint
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.checkpoint.json", args.resource.to_lowercase())));
+    let mut checkpoint = Checkpoint::load(&checkpoint_path, &args.resource)?;
+
+    // An explicit --since starts a fresh run from that timestamp
+    if let Some(since) = args.since {
+        checkpoint.since = Some(since);
+        checkpoint.next_link = None;
+        checkpoint.high_water = None;
+        checkpoint.records = 0;
+    }
+
+    if checkpoint.in_progress() {
+        eprintln!(
+            "Resuming interrupted run ({} records already written)",
+            checkpoint.records
+        );
+    } else if let Some(since) = &checkpoint.since {
+        eprintln!("Replicating {} modified after {}", args.resource, since);
+    }
+
+    // Progress is published for `reso dashboard`
+    let status = StatusFile::new(&args.status);
+    status.start(&args.resource, checkpoint.since.as_deref())?;
+    let mut sink = StatusSink::new(sink, status.clone(), &args.resource);
+
+    // Ctrl-C stops between batches, leaving the checkpoint resumable
+    let token = cancel_on_ctrl_c();
+
+    let result = replicate_all_cancellable(
+        client,
+        &args.resource,
+        args.filter.as_deref(),
+        &mut sink,
+        &mut checkpoint,
+        &token,
+    )
+    .await;
+    let stats = match result {
+        Ok(stats) => {
+            status.complete(&args.resource, checkpoint.since.as_deref())?;
+            stats
+        }
+        Err(e) if is_cancelled(&e) => {
+            status.fail(&args.resource, &e, checkpoint.since.as_deref())?;
+            eprintln!(
+                "Interrupted after {} records; rerun the same command to resume",
+                checkpoint.records
+            );
+            return Ok(());
+        }
+        Err(e) => {
+            status.fail(&args.resource, &e, checkpoint.since.as_deref())?;
+            if checkpoint.in_progress() && is_upstream_failure(&e) {
+                // Queue the unfetched pages and start the window over, so the
+                // next run is not blocked on a failing next link
+                let queue = RetryQueue::new(&args.retry_queue);
+                queue.push(&FailedBatch::from_checkpoint(&checkpoint, args.filter.as_deref(), &e))?;
+                let written = checkpoint.records;
+                checkpoint.restart();
+                checkpoint.save()?;
+                eprintln!(
+                    "Run failed after {} records; the rest is queued in {}. \
+                     Run `reso retry-failed {}` with the same destination to fetch it; \
+                     the next run starts from {} again.",
+                    written,
+                    queue.path().display(),
+                    args.resource,
+                    checkpoint.since.as_deref().unwrap_or("the beginning")
+                );
+            } else if checkpoint.in_progress() {
+                eprintln!(
+                    "Run failed after {} records; rerun the same command to resume",
+                    checkpoint.records
+                );
+            }
+            return Err(e.into());
+        }
+    };
+
+    println!("Replicated {}", stats);
+    if let Some(since) = &checkpoint.since {
+        println!("Checkpoint {} now at {}", checkpoint_path.display(), since);
+    }
+
+    if args.verify || args.verify_by.is_some() {
+        let group_by = args.verify_by.as_deref();
+        match sink.count_stored(group_by).await? {
+            Some(stored) => {
+                let report =
+                    reconcile_counts(client, &args.resource, args.filter.as_deref(), &stored, group_by).await?;
+                println!("{}", report);
+                if !report.is_consistent() {
+                    return Err("Stored records do not match the server's counts".into());
+                }
+            }
+            None => eprintln!("This destination cannot count its records; skipping verification"),
+        }
+    }
+    Ok(())
+}
+
+pub struct RetryArgs {
+    pub resource: String,
+    pub destination: Destination,
+    pub sink: SinkOptions,
+    pub retry_queue: PathBuf,
+    /// Stamp records with where and when they were fetched
+    pub provenance: bool,
+}
+
+pub async fn retry(client: &impl ResoApi, args: RetryArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let queue = RetryQueue::new(&args.retry_queue);
+    let queued = queue
+        .entries()?
+        .iter()
+        .filter(|entry| entry.resource == args.resource)
+        .count();
+    if queued == 0 {
+        println!("No failed {} runs queued in {}", args.resource, queue.path().display());
+        return Ok(());
+    }
+
+    let mut sink = open_sink(&args.destination, &args.resource, &args.sink).await?;
+    if args.provenance {
+        // The queued runs each had their own filter
+        sink = with_provenance(sink, &args.resource, None);
+    }
+    let stats = retry_failed(client, &queue, &args.resource, &mut sink).await?;
+    println!(
+        "Recovered {} of {} failed runs ({} records)",
+        stats.recovered, queued, stats.records
+    );
+    if stats.still_failing > 0 {
+        return Err(format!(
+            "{} runs still failing; they remain queued in {}",
+            stats.still_failing,
+            queue.path().display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Stamps records written to `sink` with the dataset and a hash of the
+/// resource and filter.
+fn with_provenance(sink: Box<dyn RecordSink>, resource: &str, filter: Option<&str>) -> Box<dyn RecordSink> {
+    let dataset_id = std::env::var("RESO_DATASET_ID").ok();
+    Box::new(ProvenanceSink::new(sink, Provenance::new(dataset_id.as_deref(), resource, filter)))
+}
+
+#[cfg(feature = "export-parquet")]
+fn parquet_sink(
+    dir: &std::path::Path,
+    partitions: Vec<Partition>,
+) -> Result<Box<dyn RecordSink>, Box<dyn std::error::Error>> {
+    if !partitions.is_empty() {
+        return Ok(Box::new(PartitionedSink::parquet(dir, partitions)));
+    }
+    Ok(Box::new(reso_examples::export::ParquetSink::create(dir)?))
+}
+
+#[cfg(not(feature = "export-parquet"))]
+fn parquet_sink(
+    _dir: &std::path::Path,
+    _partitions: Vec<Partition>,
+) -> Result<Box<dyn RecordSink>, Box<dyn std::error::Error>> {
+    Err("Parquet output requires building with `--features export-parquet`".into())
+}
+
+#[cfg(feature = "db-postgres")]
+async fn postgres_sink(
+    params: &str,
+    table: &str,
+    key_field: &str,
+    on_conflict: ConflictPolicy,
+) -> Result<Box<dyn RecordSink>, Box<dyn std::error::Error>> {
+    let sink = reso_examples::export::PostgresSink::connect(params, table, key_field).await?;
+    Ok(Box::new(sink.with_conflict_policy(on_conflict)))
+}
+
+#[cfg(not(feature = "db-postgres"))]
+async fn postgres_sink(
+    _params: &str,
+    _table: &str,
+    _key_field: &str,
+    _on_conflict: ConflictPolicy,
+) -> Result<Box<dyn RecordSink>, Box<dyn std::error::Error>> {
+    Err("PostgreSQL output requires building with `--features db-postgres`".into())
+}
@@ -0,0 +1,22 @@
+//! `reso resources`: list the resources a server exposes.
+
+use crate::table::Table;
+use reso_examples::metadata::ResoSchema;
+
+pub fn run(schema: &ResoSchema) {
+    let resources = schema.resources();
+    if resources.is_empty() {
+        println!("No resources found in metadata");
+        return;
+    }
+
+    let mut table = Table::new(["Resource", "Key", "Fields"]);
+    for resource in resources {
+        table.add_row([
+            resource.name.to_string(),
+            resource.entity_type.key.join(", "),
+            resource.entity_type.fields.len().to_string(),
+        ]);
+    }
+    print!("{}", table.render());
+}
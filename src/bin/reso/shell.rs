@@ -0,0 +1,274 @@
+//! `reso shell`: an interactive prompt for refining queries.
+//!
+//! The shell keeps a working query (resource, filter clauses, selected
+//! fields, and limit) that is refined one command at a time and run on
+//! demand. Working queries can be saved as named templates in a JSON file
+//! and loaded again in later sessions.
+
+use crate::query::{self, Format};
+use reso_examples::client::ResoApi;
+use reso_examples::execute_query_with_retry;
+use reso_examples::retry::RetryPolicy;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = ".reso_history";
+
+const HELP: &str = "\
+Commands:
+  use <resource>        Set the resource to query (default: Property)
+  filter <expr>         Replace the filter with a single clause
+  and <expr>            Add a clause, combined with 'and'
+  clear                 Remove all filter clauses
+  select <a,b,c>        Select fields (no argument selects all)
+  top <n>               Limit the number of records
+  show                  Print the working query
+  run [json|csv|table]  Execute the working query (default: table)
+  save <name>           Save the working query as a template
+  load <name>           Replace the working query with a template
+  templates             List saved templates
+  help                  Show this help
+  quit                  Exit the shell";
+
+/// The query being refined in the shell.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkingQuery {
+    pub resource: String,
+    pub filters: Vec<String>,
+    pub select: Vec<String>,
+    pub top: u32,
+}
+
+impl Default for WorkingQuery {
+    fn default() -> Self {
+        Self {
+            resource: "Property".to_string(),
+            filters: Vec::new(),
+            select: Vec::new(),
+            top: 10,
+        }
+    }
+}
+
+impl WorkingQuery {
+    /// The filter clauses joined with `and`, each parenthesized when there
+    /// is more than one so `or` clauses keep their meaning.
+    pub fn filter(&self) -> Option<String> {
+        match self.filters.as_slice() {
+            [] => None,
+            [single] => Some(single.clone()),
+            clauses => Some(
+                clauses
+                    .iter()
+                    .map(|c| format!("({})", c))
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+            ),
+        }
+    }
+}
+
+/// What the shell should do after a command.
+#[derive(Debug, PartialEq)]
+enum Action {
+    Continue,
+    Run(Format),
+    Save(String),
+    Load(String),
+    ListTemplates,
+    Quit,
+}
+
+/// Applies a command line to the working query.
+fn apply(query: &mut WorkingQuery, line: &str) -> Result<Action, String> {
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+    let require = |what: &str| {
+        if arg.is_empty() {
+            Err(format!("Usage: {} {}", command, what))
+        } else {
+            Ok(arg.to_string())
+        }
+    };
+
+    match command {
+        "use" => query.resource = require("<resource>")?,
+        "filter" => query.filters = vec![require("<expr>")?],
+        "and" => query.filters.push(require("<expr>")?),
+        "clear" => query.filters.clear(),
+        "select" => {
+            query.select = arg
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect()
+        }
+        "top" => {
+            query.top = require("<n>")?
+                .parse()
+                .map_err(|_| format!("Invalid limit '{}'", arg))?
+        }
+        "show" => {}
+        "run" => {
+            let format = match arg {
+                "" | "table" => Format::Table,
+                "json" => Format::Json,
+                "csv" => Format::Csv,
+                other => return Err(format!("Unknown format '{}'", other)),
+            };
+            return Ok(Action::Run(format));
+        }
+        "save" => return Ok(Action::Save(require("<name>")?)),
+        "load" => return Ok(Action::Load(require("<name>")?)),
+        "templates" => return Ok(Action::ListTemplates),
+        "help" => println!("{}", HELP),
+        "quit" | "exit" => return Ok(Action::Quit),
+        other => return Err(format!("Unknown command '{}' (try 'help')", other)),
+    }
+    Ok(Action::Continue)
+}
+
+fn load_templates(path: &Path) -> Result<BTreeMap<String, WorkingQuery>, Box<dyn std::error::Error>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_templates(
+    path: &Path,
+    templates: &BTreeMap<String, WorkingQuery>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(templates)?)?;
+    Ok(())
+}
+
+pub async fn run(client: &impl ResoApi, templates_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let mut editor = DefaultEditor::new()?;
+    let _ = editor.load_history(HISTORY_FILE);
+    let mut templates = load_templates(&templates_path)?;
+    let mut query = WorkingQuery::default();
+
+    println!("RESO shell - type 'help' for commands, 'quit' to exit");
+
+    loop {
+        let line = match editor.readline(&format!("reso:{}> ", query.resource)) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let action = match apply(&mut query, line) {
+            Ok(action) => action,
+            Err(message) => {
+                eprintln!("{}", message);
+                continue;
+            }
+        };
+
+        match action {
+            Action::Continue => {}
+            Action::Quit => break,
+            Action::Run(format) => {
+                let result = async {
+                    let built = query::build(
+                        &query.resource,
+                        query.filter().as_deref(),
+                        &query.select,
+                        Some(query.top),
+                    )?;
+                    let response = execute_query_with_retry(client, &built, RetryPolicy::default()).await?;
+                    let records = response["value"].as_array().cloned().unwrap_or_default();
+                    query::render(&records, &query.select, format)
+                }
+                .await;
+                if let Err(e) = result {
+                    eprintln!("Error: {}", e);
+                }
+                continue;
+            }
+            Action::Save(name) => {
+                templates.insert(name.clone(), query.clone());
+                match save_templates(&templates_path, &templates) {
+                    Ok(()) => println!("Saved template '{}'", name),
+                    Err(e) => eprintln!("Error: {}", e),
+                }
+            }
+            Action::Load(name) => match templates.get(&name) {
+                Some(template) => query = template.clone(),
+                None => {
+                    eprintln!("No template named '{}'", name);
+                    continue;
+                }
+            },
+            Action::ListTemplates => {
+                for (name, template) in &templates {
+                    println!(
+                        "{:<20} {} {}",
+                        name,
+                        template.resource,
+                        template.filter().unwrap_or_default()
+                    );
+                }
+                continue;
+            }
+        }
+
+        match query::build(
+            &query.resource,
+            query.filter().as_deref(),
+            &query.select,
+            Some(query.top),
+        ) {
+            Ok(built) => println!("{}", built.to_odata_string()),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+
+    let _ = editor.save_history(HISTORY_FILE);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_refines_working_query() {
+        let mut query = WorkingQuery::default();
+
+        apply(&mut query, "use Member").unwrap();
+        apply(&mut query, "filter MemberCity eq 'Austin'").unwrap();
+        apply(&mut query, "and MemberStatus eq 'Active' or MemberType eq 'Broker'").unwrap();
+        apply(&mut query, "select MemberKey, MemberFullName").unwrap();
+        apply(&mut query, "top 25").unwrap();
+
+        assert_eq!(query.resource, "Member");
+        assert_eq!(
+            query.filter().unwrap(),
+            "(MemberCity eq 'Austin') and (MemberStatus eq 'Active' or MemberType eq 'Broker')"
+        );
+        assert_eq!(query.select, vec!["MemberKey", "MemberFullName"]);
+        assert_eq!(query.top, 25);
+
+        assert_eq!(apply(&mut query, "run csv").unwrap(), Action::Run(Format::Csv));
+        assert_eq!(apply(&mut query, "save members").unwrap(), Action::Save("members".into()));
+        assert!(apply(&mut query, "top many").is_err());
+        assert!(apply(&mut query, "frobnicate").is_err());
+
+        apply(&mut query, "clear").unwrap();
+        assert_eq!(query.filter(), None);
+    }
+}
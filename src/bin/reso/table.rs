@@ -0,0 +1,74 @@
+//! Plain-text table rendering for CLI output.
+
+/// A simple left-aligned text table.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row<S: Into<String>>(&mut self, row: impl IntoIterator<Item = S>) {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    /// Renders the table with a separator line under the headers.
+    pub fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                let len = cell.chars().count();
+                match widths.get_mut(i) {
+                    Some(width) => *width = (*width).max(len),
+                    None => widths.push(len),
+                }
+            }
+        }
+
+        let format_row = |cells: &[String]| {
+            let line: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let cell = cells.get(i).map(String::as_str).unwrap_or("");
+                    format!("{:<width$}", cell, width = width)
+                })
+                .collect();
+            line.join("  ").trim_end().to_string()
+        };
+
+        let mut out = format_row(&self.headers);
+        out.push('\n');
+        let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        out.push_str(&separator.join("  "));
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&format_row(row));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_pads_columns() {
+        let mut table = Table::new(["Resource", "Fields"]);
+        table.add_row(["Property", "512"]);
+        table.add_row(["Member", "40"]);
+
+        assert_eq!(
+            table.render(),
+            "Resource  Fields\n--------  ------\nProperty  512\nMember    40\n"
+        );
+    }
+}
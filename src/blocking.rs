@@ -0,0 +1,116 @@
+//! Synchronous versions of the query helpers.
+//!
+//! Scripts and simple command-line tools often have no other use for an
+//! async runtime. Like `reqwest::blocking`, these functions run the async
+//! helpers to completion on an internal single-threaded tokio runtime,
+//! created on first use and shared by all calls.
+//!
+//! They must not be called from within an async runtime; use the async
+//! functions there instead. Doing so returns a `ResoError::Config` rather
+//! than blocking the runtime's thread.
+//!
+//! Requires the `blocking` feature.
+
+use reso_client::{JsonValue, Query, ReplicationQuery, ReplicationResponse, ResoError};
+use crate::client::ResoApi;
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fn runtime() -> Result<&'static Runtime, ResoError> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| ResoError::Config(format!("Cannot start blocking runtime: {}", e)))?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Runs any async helper of this crate to completion.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{blocking, create_client};
+/// use reso_examples::aggregate::count_by;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let groups = blocking::block_on(count_by(&client, "Property", None, "City"))?;
+///     println!("{} cities", groups.len());
+///     Ok(())
+/// }
+/// ```
+pub fn block_on<T>(future: impl Future<Output = Result<T, ResoError>>) -> Result<T, ResoError> {
+    if Handle::try_current().is_ok() {
+        return Err(ResoError::Config(
+            "The blocking API cannot be used from within an async runtime".to_string(),
+        ));
+    }
+    runtime()?.block_on(future)
+}
+
+/// Blocking version of [`execute_query`](crate::execute_query).
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{blocking, build_query, create_client};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_query("Property", Some("City eq 'Austin'"), Some(10))?;
+///     let response = blocking::execute_query(&client, &query)?;
+///     println!("{}", response["value"]);
+///     Ok(())
+/// }
+/// ```
+pub fn execute_query(client: &impl ResoApi, query: &Query) -> Result<JsonValue, ResoError> {
+    block_on(crate::execute_query(client, query))
+}
+
+/// Blocking version of [`count_records`](crate::count_records).
+pub fn count_records(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+) -> Result<u64, ResoError> {
+    block_on(crate::count_records(client, resource, filter))
+}
+
+/// Blocking version of [`fetch_metadata`](crate::fetch_metadata).
+pub fn fetch_metadata(client: &impl ResoApi) -> Result<String, ResoError> {
+    block_on(crate::fetch_metadata(client))
+}
+
+/// Blocking version of [`execute_replication_query`](crate::execute_replication_query).
+pub fn execute_replication_query(
+    client: &impl ResoApi,
+    query: &ReplicationQuery,
+) -> Result<ReplicationResponse, ResoError> {
+    block_on(crate::execute_replication_query(client, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reso_client::{ClientConfig, ResoClient};
+
+    #[test]
+    fn test_blocking_call_reports_errors() {
+        // Nothing listens on the discard port, so the request fails fast
+        let client = ResoClient::with_config(ClientConfig::new("http://127.0.0.1:9", "token")).unwrap();
+        let query = crate::build_query("Property", None, Some(1)).unwrap();
+        assert!(matches!(execute_query(&client, &query), Err(ResoError::Network(_))));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_call_inside_runtime_is_rejected() {
+        let result = block_on(async { Ok(()) });
+        assert!(matches!(result, Err(ResoError::Config(_))));
+    }
+}
@@ -0,0 +1,103 @@
+//! Cancelling in-flight RESO requests.
+//!
+//! Dropping a request future aborts the underlying HTTP request, so a web
+//! handler that awaits a query directly already stops when its client
+//! disconnects. A [`CancellationToken`] makes the same thing explicit and
+//! reaches work that is not owned by the dropped future: requests running in
+//! spawned tasks, or long replication runs that should stop on shutdown.
+//!
+//! Cancelled operations fail with a `ResoError::Network` that
+//! [`is_cancelled`] recognizes.
+
+use crate::client::ResoApi;
+use reso_client::{JsonValue, Query, ResoError};
+use std::future::Future;
+
+pub use tokio_util::sync::CancellationToken;
+
+const CANCELLED: &str = "Request cancelled";
+
+/// Runs a request until it completes or the token is cancelled.
+///
+/// A token that is already cancelled stops the request before it starts.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::cancel::{cancellable, CancellationToken};
+/// use reso_examples::{count_records, create_client};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let token = CancellationToken::new();
+///
+///     // Cancel from elsewhere, e.g. a shutdown signal handler
+///     let shutdown = token.clone();
+///     tokio::spawn(async move {
+///         let _ = tokio::signal::ctrl_c().await;
+///         shutdown.cancel();
+///     });
+///
+///     let count = cancellable(&token, count_records(&client, "Property", None)).await?;
+///     println!("{} listings", count);
+///     Ok(())
+/// }
+/// ```
+pub async fn cancellable<T>(
+    token: &CancellationToken,
+    request: impl Future<Output = Result<T, ResoError>>,
+) -> Result<T, ResoError> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(ResoError::Network(CANCELLED.to_string())),
+        result = request => result,
+    }
+}
+
+/// Cancellable version of [`execute_query`](crate::execute_query).
+pub async fn execute_query_cancellable(
+    client: &impl ResoApi,
+    query: &Query,
+    token: &CancellationToken,
+) -> Result<JsonValue, ResoError> {
+    cancellable(token, client.execute(query)).await
+}
+
+/// A token cancelled when the process receives Ctrl-C (SIGINT), for
+/// shutting down replication runs and schedulers gracefully.
+///
+/// Must be called from within a Tokio runtime.
+pub fn cancel_on_ctrl_c() -> CancellationToken {
+    let token = CancellationToken::new();
+    let interrupt = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            interrupt.cancel();
+        }
+    });
+    token
+}
+
+/// Returns true if an error means the operation was cancelled.
+pub fn is_cancelled(error: &ResoError) -> bool {
+    matches!(error, ResoError::Network(message) if message == CANCELLED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancelled_token_stops_request() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let result: Result<(), ResoError> = cancellable(&token, std::future::pending()).await;
+        assert!(is_cancelled(&result.unwrap_err()));
+
+        let uncancelled = CancellationToken::new();
+        assert_eq!(cancellable(&uncancelled, async { Ok(7) }).await.unwrap(), 7);
+        assert!(!is_cancelled(&ResoError::Network("timed out".into())));
+    }
+}
@@ -0,0 +1,225 @@
+//! Detection of server capabilities and vendor quirks.
+//!
+//! RESO servers differ in which OData features they implement and in
+//! vendor-specific requirements. This module probes a server with small
+//! queries to find out what it supports, identifies well-known vendors from
+//! the base URL, and suggests remediation for common errors.
+
+use crate::aggregate::{groupby_count_expression, parse_groupby_counts};
+use crate::client::ResoApi;
+use crate::geo::build_query_near_geo;
+use reso_client::{QueryBuilder, ReplicationQueryBuilder, ResoError};
+
+/// OData features supported by a server, as observed by probing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Capabilities {
+    /// `/$count` requests
+    pub count: bool,
+    /// `$orderby` on `ModificationTimestamp`
+    pub orderby: bool,
+    /// `$apply` aggregation
+    pub apply: bool,
+    /// `$expand=Media`, or `None` when the resource was not `Property`
+    pub expand_media: Option<bool>,
+    /// `geo.distance` on `Coordinates`, or `None` when the resource was not
+    /// `Property`
+    pub geo_distance: Option<bool>,
+    /// The replication endpoint
+    pub replication: bool,
+    /// Error messages from failed probes, prefixed with the probe name
+    pub errors: Vec<String>,
+}
+
+/// Probes a resource for supported query features.
+///
+/// Each probe requests at most one record. Probe failures are recorded in
+/// [`Capabilities::errors`]; only a failure of the basic `$top=1` query,
+/// which means the resource cannot be queried at all, is returned as an error.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, capabilities::detect_capabilities};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let caps = detect_capabilities(&client, "Property").await?;
+///     println!("$apply supported: {}", caps.apply);
+///     Ok(())
+/// }
+/// ```
+pub async fn detect_capabilities(
+    client: &impl ResoApi,
+    resource: &str,
+) -> Result<Capabilities, ResoError> {
+    let basic = QueryBuilder::new(resource).top(1).build()?;
+    client.execute(&basic).await?;
+
+    let mut caps = Capabilities::default();
+
+    let count = QueryBuilder::new(resource).count().build()?;
+    caps.count = caps.probe("$count", client.execute_count(&count).await);
+
+    let ordered = QueryBuilder::new(resource)
+        .order_by("ModificationTimestamp", "desc")
+        .top(1)
+        .build()?;
+    caps.orderby = caps.probe("$orderby", client.execute(&ordered).await);
+
+    let apply = QueryBuilder::new(resource)
+        .apply(groupby_count_expression(None, "ModificationTimestamp"))
+        .top(1)
+        .build()?;
+    caps.apply = match client.execute(&apply).await {
+        // Servers that ignore $apply answer with plain records
+        Ok(response) => parse_groupby_counts(&response, "ModificationTimestamp").is_some(),
+        Err(e) => caps.probe::<()>("$apply", Err(e)),
+    };
+
+    if resource == "Property" {
+        let expand = QueryBuilder::new(resource).expand(&["Media"]).top(1).build()?;
+        caps.expand_media = Some(caps.probe("$expand", client.execute(&expand).await));
+
+        let near = build_query_near_geo("Coordinates", 0.0, 0.0, 1.0, None, Some(1))?;
+        caps.geo_distance = Some(caps.probe("geo.distance", client.execute(&near).await));
+    }
+
+    let replication = ReplicationQueryBuilder::new(resource).top(1).build()?;
+    caps.replication = caps.probe("replication", client.execute_replication(&replication).await);
+
+    Ok(caps)
+}
+
+impl Capabilities {
+    fn probe<T>(&mut self, name: &str, result: Result<T, ResoError>) -> bool {
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                self.errors.push(format!("{}: {}", name, e));
+                false
+            }
+        }
+    }
+}
+
+/// Well-known RESO Web API vendors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vendor {
+    Bridge,
+    Trestle,
+    Spark,
+    MlsGrid,
+    ResoReference,
+    Unknown,
+}
+
+impl Vendor {
+    /// Identifies the vendor from the API base URL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::capabilities::Vendor;
+    ///
+    /// let vendor = Vendor::detect("https://api.bridgedataoutput.com/api/v2/OData");
+    /// assert_eq!(vendor, Vendor::Bridge);
+    /// ```
+    pub fn detect(base_url: &str) -> Self {
+        let url = base_url.to_ascii_lowercase();
+        if url.contains("bridgedataoutput.com") {
+            Vendor::Bridge
+        } else if url.contains("trestle") {
+            Vendor::Trestle
+        } else if url.contains("sparkapi.com") || url.contains("flexmls") {
+            Vendor::Spark
+        } else if url.contains("mlsgrid.com") {
+            Vendor::MlsGrid
+        } else if url.contains("reso.org") {
+            Vendor::ResoReference
+        } else {
+            Vendor::Unknown
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Vendor::Bridge => "Bridge Interactive",
+            Vendor::Trestle => "Trestle",
+            Vendor::Spark => "Spark (FBS/Flexmls)",
+            Vendor::MlsGrid => "MLS Grid",
+            Vendor::ResoReference => "RESO reference server",
+            Vendor::Unknown => "Unknown vendor",
+        }
+    }
+
+    /// Known vendor-specific behaviour worth checking when queries fail.
+    pub fn quirks(&self) -> &'static [&'static str] {
+        match self {
+            Vendor::Bridge => &[
+                "Bridge scopes requests to a dataset; set RESO_DATASET_ID (e.g. actris_ref)",
+                "Standard queries return at most 200 records per page",
+            ],
+            Vendor::Trestle => &[
+                "Trestle issues short-lived OAuth2 tokens; refresh RESO_TOKEN when requests start returning 401",
+                "Use the replication endpoint for full syncs instead of deep $skip paging",
+            ],
+            Vendor::Spark => &["Spark limits page sizes and request rates per API key; keep $top small"],
+            Vendor::MlsGrid => &[
+                "MLS Grid requires filtering on OriginatingSystemName for every query",
+                "MLS Grid enforces strict hourly and daily request quotas",
+            ],
+            Vendor::ResoReference => &["The reference server requires RESO_DATASET_ID"],
+            Vendor::Unknown => &[],
+        }
+    }
+}
+
+/// Suggests how to fix a failed request.
+pub fn remediation_hint(error: &ResoError) -> &'static str {
+    match error {
+        ResoError::Config(_) => "Check RESO_BASE_URL and RESO_TOKEN in your .env file (see .env.example)",
+        ResoError::Network(_) => {
+            "Check network access to RESO_BASE_URL, or raise RESO_TIMEOUT for slow servers"
+        }
+        ResoError::Unauthorized { .. } => {
+            "The token was rejected; obtain a fresh RESO_TOKEN from your MLS or vendor"
+        }
+        ResoError::Forbidden { .. } => {
+            "The token is valid but lacks access; ask your vendor to enable this resource or feed"
+        }
+        ResoError::NotFound { .. } => {
+            "Check RESO_BASE_URL (it usually ends in /OData) and RESO_DATASET_ID"
+        }
+        ResoError::RateLimited { .. } => "Slow down: wait before retrying and reduce request rates",
+        ResoError::ServerError { .. } => "The server failed; retry later or contact the vendor",
+        ResoError::ODataError { .. } | ResoError::InvalidQuery(_) => {
+            "The server rejected the query; check field names against `reso fields`"
+        }
+        ResoError::Parse(_) => {
+            "The response was not valid JSON/XML; check that RESO_BASE_URL points at the OData endpoint"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_detection() {
+        assert_eq!(Vendor::detect("https://api-trestle.corelogic.com/trestle/odata"), Vendor::Trestle);
+        assert_eq!(Vendor::detect("https://api.mlsgrid.com/v2"), Vendor::MlsGrid);
+        assert_eq!(Vendor::detect("https://example.com/odata"), Vendor::Unknown);
+        assert!(Vendor::Unknown.quirks().is_empty());
+    }
+
+    #[test]
+    fn test_remediation_hint() {
+        let error = ResoError::Unauthorized {
+            message: "bad token".into(),
+            status_code: 401,
+        };
+        assert!(remediation_hint(&error).contains("RESO_TOKEN"));
+    }
+}
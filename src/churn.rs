@@ -0,0 +1,244 @@
+//! How often each field changes across a sync history.
+//!
+//! An append-only JSONL store, or a snapshot plus its delta files, holds
+//! every version of a record that replication fetched. Comparing each
+//! version with the one before it shows which fields actually move:
+//! `ListPrice` and `StandardStatus` change on a handful of updates, while
+//! fields like `PhotosCount` or `DaysOnMarket` may churn on nearly every
+//! one. The report helps choose which fields deserve delta notifications
+//! or an index, and which are noise.
+
+use crate::compact::read_keyed_records;
+use crate::provenance::PROVENANCE_FIELD;
+use reso_client::{JsonValue, ResoError};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// How often one field changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChurn {
+    pub field: String,
+    /// Updates in which the field's value changed
+    pub changes: u64,
+    /// Share of all updates that changed the field, from 0 to 1
+    pub rate: f64,
+}
+
+/// The result of [`ChurnTracker::report`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChurnReport {
+    /// Distinct record keys seen
+    pub records: u64,
+    /// Versions read, including repeats and stale versions
+    pub versions: u64,
+    /// Versions that changed at least one field of the newest version before them
+    pub updates: u64,
+    /// Fields that changed, most often first
+    pub fields: Vec<FieldChurn>,
+}
+
+impl std::fmt::Display for ChurnReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} records, {} versions, {} updates",
+            self.records, self.versions, self.updates
+        )?;
+        for field in &self.fields {
+            write!(
+                f,
+                "\n  {}: {} changes ({:.1}% of updates)",
+                field.field,
+                field.changes,
+                field.rate * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts field changes between successive versions of records.
+///
+/// Versions are compared with the newest version of the same key seen so
+/// far. A version whose `ModificationTimestamp` is older than that one (a
+/// retried page, an overlapping rerun) is skipped, as is one identical to
+/// it. `ModificationTimestamp` itself, OData annotations, and the
+/// [`provenance`](crate::provenance) stamp are not counted as changes. A
+/// field that is missing counts as `null`.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::churn::ChurnTracker;
+/// use serde_json::json;
+///
+/// let mut tracker = ChurnTracker::new("ListingKey");
+/// tracker.observe(&json!({"ListingKey": "A", "ListPrice": 500000, "PhotosCount": 10}));
+/// tracker.observe(&json!({"ListingKey": "A", "ListPrice": 500000, "PhotosCount": 12}));
+/// tracker.observe(&json!({"ListingKey": "A", "ListPrice": 489000, "PhotosCount": 14}));
+///
+/// let report = tracker.report();
+/// assert_eq!(report.updates, 2);
+/// assert_eq!(report.fields[0].field, "PhotosCount");
+/// assert_eq!(report.fields[1].rate, 0.5);
+/// ```
+pub struct ChurnTracker {
+    key_field: String,
+    latest: HashMap<String, JsonValue>,
+    versions: u64,
+    updates: u64,
+    changes: BTreeMap<String, u64>,
+}
+
+impl ChurnTracker {
+    pub fn new(key_field: &str) -> Self {
+        Self {
+            key_field: key_field.to_string(),
+            latest: HashMap::new(),
+            versions: 0,
+            updates: 0,
+            changes: BTreeMap::new(),
+        }
+    }
+
+    /// Compares a version of a record with the newest one seen for its key.
+    /// Records without the key field are ignored.
+    pub fn observe(&mut self, record: &JsonValue) {
+        let key = match &record[self.key_field.as_str()] {
+            JsonValue::Null => return,
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        self.observe_keyed(key, record.clone());
+    }
+
+    fn observe_keyed(&mut self, key: String, record: JsonValue) {
+        self.versions += 1;
+        let Some(previous) = self.latest.get(&key) else {
+            self.latest.insert(key, record);
+            return;
+        };
+
+        let timestamp = |r: &JsonValue| r["ModificationTimestamp"].as_str().map(str::to_string);
+        if let (Some(previous), Some(current)) = (timestamp(previous), timestamp(&record)) {
+            if current < previous {
+                return;
+            }
+        }
+
+        let changed = changed_fields(previous, &record);
+        if changed.is_empty() {
+            return;
+        }
+        self.updates += 1;
+        for field in changed {
+            *self.changes.entry(field).or_default() += 1;
+        }
+        self.latest.insert(key, record);
+    }
+
+    /// The fields changed so far, most often first.
+    pub fn report(&self) -> ChurnReport {
+        let mut fields: Vec<FieldChurn> = self
+            .changes
+            .iter()
+            .map(|(field, &changes)| FieldChurn {
+                field: field.clone(),
+                changes,
+                rate: changes as f64 / self.updates as f64,
+            })
+            .collect();
+        // Stable, so ties stay in field name order
+        fields.sort_by_key(|field| std::cmp::Reverse(field.changes));
+        ChurnReport {
+            records: self.latest.len() as u64,
+            versions: self.versions,
+            updates: self.updates,
+            fields,
+        }
+    }
+}
+
+/// Reports field churn across JSONL files written by replication, read in
+/// the order given (e.g. a snapshot, then its delta files oldest first).
+///
+/// # Arguments
+///
+/// * `paths` - The files to read
+/// * `key_field` - Field identifying a record, e.g. "ListingKey"
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::churn::churn_jsonl;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let report = churn_jsonl(&["property.jsonl"], "ListingKey")?;
+/// for field in report.fields.iter().take(10) {
+///     println!("{}: {:.0}% of updates", field.field, field.rate * 100.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn churn_jsonl(paths: &[impl AsRef<Path>], key_field: &str) -> Result<ChurnReport, ResoError> {
+    let mut tracker = ChurnTracker::new(key_field);
+    for path in paths {
+        read_keyed_records(path.as_ref(), key_field, |key, record| {
+            tracker.observe_keyed(key, record)
+        })?;
+    }
+    Ok(tracker.report())
+}
+
+/// Fields whose values differ between two versions of a record.
+fn changed_fields(previous: &JsonValue, current: &JsonValue) -> Vec<String> {
+    let mut fields: Vec<&String> = Vec::new();
+    for object in [previous.as_object(), current.as_object()].into_iter().flatten() {
+        for field in object.keys() {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+    }
+    fields
+        .into_iter()
+        .filter(|field| {
+            field.as_str() != "ModificationTimestamp"
+                && field.as_str() != PROVENANCE_FIELD
+                && !field.contains('@')
+        })
+        .filter(|field| previous[field.as_str()] != current[field.as_str()])
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_churn_skips_stale_and_repeated_versions() {
+        let mut tracker = ChurnTracker::new("ListingKey");
+        for record in [
+            json!({"ListingKey": "A", "ListPrice": 500000, "PhotosCount": 10, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            json!({"ListingKey": "B", "ListPrice": 300000, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            json!({"ListingKey": "A", "ListPrice": 500000, "PhotosCount": 12, "ModificationTimestamp": "2025-01-02T00:00:00Z",
+                   "_provenance": {"fetched_at": "2025-01-02T00:05:00Z"}}),
+            // A retried page delivers the first version again
+            json!({"ListingKey": "A", "ListPrice": 500000, "PhotosCount": 10, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            // A resumed run writes the same version twice
+            json!({"ListingKey": "B", "ListPrice": 300000, "ModificationTimestamp": "2025-01-01T00:00:00Z",
+                   "@odata.etag": "W/\"2\""}),
+            json!({"ListingKey": "B", "ListPrice": 289000, "StandardStatus": "Active", "ModificationTimestamp": "2025-01-03T00:00:00Z"}),
+        ] {
+            tracker.observe(&record);
+        }
+
+        let report = tracker.report();
+        assert_eq!((report.records, report.versions, report.updates), (2, 6, 2));
+        let fields: Vec<(&str, u64)> = report.fields.iter().map(|f| (f.field.as_str(), f.changes)).collect();
+        assert_eq!(fields, [("ListPrice", 1), ("PhotosCount", 1), ("StandardStatus", 1)]);
+        assert_eq!(report.fields[0].rate, 0.5);
+    }
+}
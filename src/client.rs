@@ -0,0 +1,1155 @@
+//! Client abstraction and an HTTP client with request middleware.
+//!
+//! The helpers in this crate accept any [`ResoApi`] implementation, so the
+//! same code runs against `reso_client::ResoClient` or against
+//! [`HttpClient`], which sends the requests itself and lets applications
+//! hook into them: request hooks can rewrite the URL or headers (custom
+//! signing, extra headers), and response hooks observe every response
+//! (logging, metrics, quota tracking).
+
+use crate::auth::{OAuthTokens, TokenProvider};
+use crate::format::{ODataJson, ResponseFormat};
+use crate::rate_limit::parse_retry_after;
+use crate::retry::note_retry_after;
+use crate::streaming::RecordStream;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::dns::Resolve;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use reso_client::{
+    ClientConfig, JsonValue, Query, ReplicationQuery, ReplicationResponse, ResoClient, ResoError,
+};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Operations a RESO Web API client provides.
+///
+/// Mirrors the methods of `ResoClient`, which implements this trait.
+#[async_trait]
+pub trait ResoApi: Send + Sync {
+    /// The base URL of the server.
+    fn base_url(&self) -> &str;
+
+    /// Executes a query and returns the raw JSON response.
+    async fn execute(&self, query: &Query) -> Result<JsonValue, ResoError>;
+
+    /// Executes a key lookup such as `Property('ABC123')`.
+    async fn execute_by_key(&self, query: &Query) -> Result<JsonValue, ResoError>;
+
+    /// Executes a `/$count` query.
+    async fn execute_count(&self, query: &Query) -> Result<u64, ResoError>;
+
+    /// Fetches the `$metadata` XML document.
+    async fn fetch_metadata(&self) -> Result<String, ResoError>;
+
+    /// Executes the first request of a replication run.
+    async fn execute_replication(
+        &self,
+        query: &ReplicationQuery,
+    ) -> Result<ReplicationResponse, ResoError>;
+
+    /// Follows a replication next link.
+    async fn execute_next_link(&self, next_link: &str) -> Result<ReplicationResponse, ResoError>;
+}
+
+#[async_trait]
+impl ResoApi for ResoClient {
+    fn base_url(&self) -> &str {
+        ResoClient::base_url(self)
+    }
+
+    async fn execute(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        ResoClient::execute(self, query).await
+    }
+
+    async fn execute_by_key(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        ResoClient::execute_by_key(self, query).await
+    }
+
+    async fn execute_count(&self, query: &Query) -> Result<u64, ResoError> {
+        ResoClient::execute_count(self, query).await
+    }
+
+    async fn fetch_metadata(&self) -> Result<String, ResoError> {
+        ResoClient::fetch_metadata(self).await
+    }
+
+    async fn execute_replication(
+        &self,
+        query: &ReplicationQuery,
+    ) -> Result<ReplicationResponse, ResoError> {
+        ResoClient::execute_replication(self, query).await
+    }
+
+    async fn execute_next_link(&self, next_link: &str) -> Result<ReplicationResponse, ResoError> {
+        ResoClient::execute_next_link(self, next_link).await
+    }
+}
+
+/// Lets shared (`Arc<ResoClient>`) and runtime-chosen (`Box<dyn ResoApi>`)
+/// clients be passed to the helpers directly.
+macro_rules! forward_reso_api {
+    ($($wrapper:ident),*) => {
+        $(
+            #[async_trait]
+            impl<T: ResoApi + ?Sized> ResoApi for $wrapper<T> {
+                fn base_url(&self) -> &str {
+                    (**self).base_url()
+                }
+
+                async fn execute(&self, query: &Query) -> Result<JsonValue, ResoError> {
+                    (**self).execute(query).await
+                }
+
+                async fn execute_by_key(&self, query: &Query) -> Result<JsonValue, ResoError> {
+                    (**self).execute_by_key(query).await
+                }
+
+                async fn execute_count(&self, query: &Query) -> Result<u64, ResoError> {
+                    (**self).execute_count(query).await
+                }
+
+                async fn fetch_metadata(&self) -> Result<String, ResoError> {
+                    (**self).fetch_metadata().await
+                }
+
+                async fn execute_replication(
+                    &self,
+                    query: &ReplicationQuery,
+                ) -> Result<ReplicationResponse, ResoError> {
+                    (**self).execute_replication(query).await
+                }
+
+                async fn execute_next_link(
+                    &self,
+                    next_link: &str,
+                ) -> Result<ReplicationResponse, ResoError> {
+                    (**self).execute_next_link(next_link).await
+                }
+            }
+        )*
+    };
+}
+
+forward_reso_api!(Arc, Box);
+
+/// An outgoing request, as seen and modified by request hooks.
+#[derive(Debug, Clone)]
+pub struct RequestParts {
+    pub method: Method,
+    pub url: String,
+    /// Includes the `Authorization` and `Accept` headers set by the client
+    pub headers: HeaderMap,
+}
+
+/// Prints the request line and headers, e.g. for a
+/// [`dry_run`](HttpClient::dry_run).
+impl fmt::Display for RequestParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        for (name, value) in &self.headers {
+            write!(f, "\n{}: {}", name, value.to_str().unwrap_or("<binary>"))?;
+        }
+        Ok(())
+    }
+}
+
+/// A received response, as seen by response hooks.
+#[derive(Debug, Clone)]
+pub struct ResponseParts {
+    /// The URL the request was sent to, after request hooks ran
+    pub url: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// Time from sending the request until the response headers arrived
+    pub elapsed: Duration,
+}
+
+/// Observes every response received by an [`HttpClient`].
+///
+/// # Example
+///
+/// ```
+/// use async_trait::async_trait;
+/// use reso_examples::client::{ResponseHook, ResponseParts};
+///
+/// struct LogSlowRequests;
+///
+/// #[async_trait]
+/// impl ResponseHook for LogSlowRequests {
+///     async fn on_response(&self, response: &ResponseParts) {
+///         if response.elapsed.as_secs() >= 5 {
+///             eprintln!("slow request ({:?}): {}", response.elapsed, response.url);
+///         }
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait ResponseHook: Send + Sync {
+    async fn on_response(&self, response: &ResponseParts);
+}
+
+type RequestHook = Arc<dyn Fn(RequestParts) -> RequestParts + Send + Sync>;
+
+/// A RESO client that sends requests itself, with request and response hooks.
+///
+/// Behaves like `ResoClient` (same URLs, headers, and error mapping) and is
+/// configured from the same `ClientConfig`.
+///
+/// # Example
+///
+/// ```no_run
+/// use reqwest::header::HeaderValue;
+/// use reso_client::ClientConfig;
+/// use reso_examples::client::HttpClient;
+/// use reso_examples::count_records;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = HttpClient::builder(ClientConfig::from_env()?)
+///         .request_hook(|mut request| {
+///             request
+///                 .headers
+///                 .insert("X-Request-Source", HeaderValue::from_static("nightly-sync"));
+///             request
+///         })
+///         .build()?;
+///
+///     let count = count_records(&client, "Property", None).await?;
+///     println!("{} listings", count);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct HttpClient {
+    config: ClientConfig,
+    http: reqwest::Client,
+    request_hooks: Vec<RequestHook>,
+    response_hooks: Vec<Arc<dyn ResponseHook>>,
+    tokens: Option<Arc<dyn TokenProvider>>,
+    format: Arc<dyn ResponseFormat>,
+    debug_wire: bool,
+}
+
+/// Builder for [`HttpClient`].
+pub struct HttpClientBuilder {
+    config: ClientConfig,
+    request_hooks: Vec<RequestHook>,
+    response_hooks: Vec<Arc<dyn ResponseHook>>,
+    ca_bundles: Vec<Vec<u8>>,
+    identity: Option<Vec<u8>>,
+    built_in_roots: bool,
+    dns_overrides: Vec<(String, Vec<IpAddr>)>,
+    pin_base_host: Option<Vec<IpAddr>>,
+    dns_resolver: Option<Arc<dyn Resolve>>,
+    tokens: Option<Arc<dyn TokenProvider>>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    format: Arc<dyn ResponseFormat>,
+    debug_wire: bool,
+}
+
+impl HttpClientBuilder {
+    /// Adds a hook that may rewrite each request before it is sent.
+    ///
+    /// Hooks run in the order they were added.
+    pub fn request_hook(
+        mut self,
+        hook: impl Fn(RequestParts) -> RequestParts + Send + Sync + 'static,
+    ) -> Self {
+        self.request_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Adds a hook that is awaited for each response, successful or not.
+    pub fn response_hook(mut self, hook: impl ResponseHook + 'static) -> Self {
+        self.response_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Authenticates with access tokens from an OAuth2 client-credentials
+    /// grant instead of the configured bearer token.
+    pub fn oauth(self, tokens: OAuthTokens) -> Self {
+        self.token_provider(tokens)
+    }
+
+    /// Takes the bearer token for each request from `provider` instead of
+    /// the configured token.
+    pub fn token_provider(mut self, provider: impl TokenProvider + 'static) -> Self {
+        self.tokens = Some(Arc::new(provider));
+        self
+    }
+
+    /// Sends `User-Agent: <user_agent>`, which some vendors ask
+    /// integrations to set so they can be told apart in their logs.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sends every request through the proxy at `url`, e.g.
+    /// `http://proxy.internal:3128`, with credentials in the URL if needed.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Parses data responses with `format`, for servers that answer in
+    /// Atom/XML or a nonstandard JSON envelope (see [`format`](crate::format)).
+    pub fn response_format(mut self, format: impl ResponseFormat + 'static) -> Self {
+        self.format = Arc::new(format);
+        self
+    }
+
+    /// Trusts the certificates in a PEM bundle, for servers using a private CA.
+    ///
+    /// May be called more than once. The system roots stay trusted unless
+    /// [`only_custom_roots`](Self::only_custom_roots) is also set.
+    pub fn ca_bundle_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.ca_bundles.push(pem.into());
+        self
+    }
+
+    /// Presents a client certificate, for endpoints that require mutual TLS.
+    ///
+    /// The PEM must contain the certificate chain followed by its private key
+    /// (RSA, SEC1, or PKCS#8).
+    pub fn client_identity_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.identity = Some(pem.into());
+        self
+    }
+
+    /// Trusts only the certificates added with [`ca_bundle_pem`](Self::ca_bundle_pem).
+    pub fn only_custom_roots(mut self) -> Self {
+        self.built_in_roots = false;
+        self
+    }
+
+    /// Applies the TLS settings named by `RESO_CA_BUNDLE` and
+    /// `RESO_CLIENT_IDENTITY`, each a path to a PEM file, when set.
+    pub fn tls_from_env(mut self) -> Result<Self, ResoError> {
+        if let Some(pem) = read_pem_env("RESO_CA_BUNDLE")? {
+            self = self.ca_bundle_pem(pem);
+        }
+        if let Some(pem) = read_pem_env("RESO_CLIENT_IDENTITY")? {
+            self = self.client_identity_pem(pem);
+        }
+        Ok(self)
+    }
+
+    /// Resolves `host` to the given addresses instead of using DNS.
+    ///
+    /// The port still comes from the URL, so this only changes which
+    /// machine is contacted; TLS is verified against the host name as usual.
+    pub fn resolve(mut self, host: &str, addrs: &[IpAddr]) -> Self {
+        self.dns_overrides.push((host.to_string(), addrs.to_vec()));
+        self
+    }
+
+    /// Resolves the host of the configured base URL to the given addresses,
+    /// for split-horizon DNS or vendors that whitelist egress by IP.
+    pub fn pin_ip(mut self, addrs: &[IpAddr]) -> Self {
+        self.pin_base_host = Some(addrs.to_vec());
+        self
+    }
+
+    /// Uses a custom resolver for hosts without a [`resolve`](Self::resolve)
+    /// or [`pin_ip`](Self::pin_ip) override.
+    pub fn dns_resolver(mut self, resolver: impl Resolve + 'static) -> Self {
+        self.dns_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Pins the base URL host to the comma-separated addresses in
+    /// `RESO_RESOLVE_IP`, when set.
+    pub fn dns_from_env(self) -> Result<Self, ResoError> {
+        match std::env::var("RESO_RESOLVE_IP") {
+            Ok(value) if !value.trim().is_empty() => {
+                let addrs = value
+                    .split(',')
+                    .map(|ip| {
+                        ip.trim().parse().map_err(|_| {
+                            ResoError::Config(format!("Invalid IP in RESO_RESOLVE_IP: '{}'", ip.trim()))
+                        })
+                    })
+                    .collect::<Result<Vec<IpAddr>, _>>()?;
+                Ok(self.pin_ip(&addrs))
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// Prints every request and response to stderr: headers, and bodies
+    /// pretty-printed and capped at 8 KiB, with credentials redacted.
+    ///
+    /// Defaults to on when `RESO_DEBUG_WIRE=1`.
+    pub fn debug_wire(mut self, enabled: bool) -> Self {
+        self.debug_wire = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<HttpClient, ResoError> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.config.timeout)
+            .tls_built_in_root_certs(self.built_in_roots);
+
+        let mut dns_overrides = self.dns_overrides.clone();
+        if let Some(addrs) = &self.pin_base_host {
+            let host = reqwest::Url::parse(&self.config.base_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .ok_or_else(|| {
+                    ResoError::Config(format!("Cannot pin IP: no host in '{}'", self.config.base_url))
+                })?;
+            dns_overrides.push((host, addrs.clone()));
+        }
+        for (host, addrs) in &dns_overrides {
+            // Port 0 keeps the port from the URL
+            let addrs: Vec<SocketAddr> = addrs.iter().map(|ip| SocketAddr::new(*ip, 0)).collect();
+            builder = builder.resolve_to_addrs(host, &addrs);
+        }
+        if let Some(resolver) = &self.dns_resolver {
+            builder = builder.dns_resolver2(resolver.clone());
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent.as_str());
+        }
+        if let Some(url) = &self.proxy {
+            let proxy = reqwest::Proxy::all(url.as_str())
+                .map_err(|e| ResoError::Config(format!("Invalid proxy URL '{}': {}", url, e)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        for bundle in &self.ca_bundles {
+            let certificates = reqwest::Certificate::from_pem_bundle(bundle)
+                .map_err(|e| ResoError::Config(format!("Invalid CA bundle: {}", e)))?;
+            if certificates.is_empty() {
+                return Err(ResoError::Config(
+                    "CA bundle contains no PEM certificates".to_string(),
+                ));
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(pem) = &self.identity {
+            let identity = reqwest::Identity::from_pem(pem)
+                .map_err(|e| ResoError::Config(format!("Invalid client certificate: {}", e)))?;
+            builder = builder.identity(identity);
+        }
+
+        let http = builder
+            .build()
+            .map_err(|e| ResoError::Config(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(HttpClient {
+            config: self.config,
+            http,
+            request_hooks: self.request_hooks,
+            response_hooks: self.response_hooks,
+            tokens: self.tokens,
+            format: self.format,
+            debug_wire: self.debug_wire,
+        })
+    }
+}
+
+impl HttpClient {
+    pub fn builder(config: ClientConfig) -> HttpClientBuilder {
+        HttpClientBuilder {
+            config,
+            request_hooks: Vec::new(),
+            response_hooks: Vec::new(),
+            ca_bundles: Vec::new(),
+            identity: None,
+            built_in_roots: true,
+            dns_overrides: Vec::new(),
+            pin_base_host: None,
+            dns_resolver: None,
+            tokens: None,
+            user_agent: None,
+            proxy: None,
+            format: Arc::new(ODataJson),
+            debug_wire: debug_wire_from_env(),
+        }
+    }
+
+    /// Creates a client without hooks from the `RESO_*` environment variables,
+    /// including the settings read by [`HttpClientBuilder::tls_from_env`] and
+    /// [`HttpClientBuilder::dns_from_env`].
+    ///
+    /// When `RESO_TOKEN_URL` is set, the client authenticates with the OAuth2
+    /// client-credentials grant, as with [`create_client_oauth`](crate::create_client_oauth).
+    pub fn from_env() -> Result<Self, ResoError> {
+        if std::env::var("RESO_TOKEN_URL").is_ok_and(|url| !url.trim().is_empty()) {
+            return crate::create_client_oauth();
+        }
+        Self::builder(ClientConfig::from_env()?)
+            .tls_from_env()?
+            .dns_from_env()?
+            .build()
+    }
+
+    fn build_url(&self, path: &str) -> String {
+        match &self.config.dataset_id {
+            Some(dataset_id) => format!("{}/{}/{}", self.config.base_url, dataset_id, path),
+            None => format!("{}/{}", self.config.base_url, path),
+        }
+    }
+
+    /// Sends a GET request and returns the response headers and body.
+    async fn send(&self, url: String, accept: &'static str) -> Result<(HeaderMap, String), ResoError> {
+        let (response, elapsed) = self.start(url, accept).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await;
+        if self.debug_wire {
+            let text = body.as_deref().unwrap_or("<unreadable body>");
+            eprintln!("{}", format_wire_response(status, &headers, elapsed, text));
+        }
+
+        if !status.is_success() {
+            return Err(error_from_response(status, &headers, &body.unwrap_or_default()));
+        }
+        let body = body.map_err(|e| match e.is_timeout() {
+            true => network_error(e),
+            false => ResoError::Parse(format!("Failed to read response: {}", e)),
+        })?;
+        Ok((headers, body))
+    }
+
+    /// Sends a GET request through the hooks and returns the response
+    /// before its body is read, with the time it took to arrive.
+    ///
+    /// A 401 with a token provider drops the provider's token and repeats
+    /// the request once with a new one, in case the old one was revoked
+    /// before it expired.
+    async fn start(&self, url: String, accept: &'static str) -> Result<(reqwest::Response, Duration), ResoError> {
+        let (response, elapsed) = self.attempt(url.clone(), accept).await?;
+        match (response.status(), &self.tokens) {
+            (StatusCode::UNAUTHORIZED, Some(tokens)) => {
+                tokens.invalidate().await;
+                self.attempt(url, accept).await
+            }
+            _ => Ok((response, elapsed)),
+        }
+    }
+
+    /// One request for [`start`](Self::start).
+    async fn attempt(&self, url: String, accept: &'static str) -> Result<(reqwest::Response, Duration), ResoError> {
+        let bearer = match &self.tokens {
+            Some(tokens) => HeaderValue::from_str(&format!("Bearer {}", tokens.token().await?))
+                .map_err(|e| ResoError::Parse(format!("Invalid access token: {}", e)))?,
+            None => HeaderValue::from_str(&format!("Bearer {}", self.config.token))
+                .map_err(|e| ResoError::Config(format!("Invalid RESO_TOKEN: {}", e)))?,
+        };
+        let request = self.request_parts(url, accept, bearer);
+        if self.debug_wire {
+            eprintln!("{}", format_wire_request(&request));
+        }
+
+        let started = Instant::now();
+        let response = self
+            .http
+            .request(request.method, &request.url)
+            .headers(request.headers)
+            .send()
+            .await
+            .map_err(network_error)?;
+
+        let status = response.status();
+        let elapsed = started.elapsed();
+
+        if !self.response_hooks.is_empty() {
+            let parts = ResponseParts {
+                url: request.url,
+                status,
+                headers: response.headers().clone(),
+                elapsed,
+            };
+            for hook in &self.response_hooks {
+                hook.on_response(&parts).await;
+            }
+        }
+        Ok((response, elapsed))
+    }
+
+    /// A GET request with the client's headers, after the request hooks.
+    fn request_parts(&self, url: String, accept: &'static str, bearer: HeaderValue) -> RequestParts {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, bearer);
+        headers.insert(ACCEPT, HeaderValue::from_static(accept));
+
+        let mut request = RequestParts {
+            method: Method::GET,
+            url,
+            headers,
+        };
+        for hook in &self.request_hooks {
+            request = hook(request);
+        }
+        request
+    }
+
+    /// The request [`execute`](ResoApi::execute) (or, for a `$count` query,
+    /// [`execute_count`](ResoApi::execute_count)) would send for `query`,
+    /// without sending it.
+    ///
+    /// The URL is percent-encoded exactly as it goes on the wire, and
+    /// request hooks have run. The token is never fetched or shown: the
+    /// `Authorization` header and other secret headers and URL parameters
+    /// read `[redacted]`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use reso_examples::build_query;
+    /// use reso_examples::client::HttpClient;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = HttpClient::from_env()?;
+    /// let query = build_query("Property", Some("City eq 'Coeur d'' Alene'"), Some(5))?;
+    /// // The method and encoded URL, then one header per line
+    /// println!("{}", client.dry_run(&query)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dry_run(&self, query: &Query) -> Result<RequestParts, ResoError> {
+        let path = query.to_odata_string();
+        let accept = match path.contains("/$count") {
+            true => "text/plain",
+            false => self.format.accept(),
+        };
+        self.dry_run_url(self.build_url(&path), accept)
+    }
+
+    /// Like [`dry_run`](Self::dry_run), for a replication query.
+    pub fn dry_run_replication(&self, query: &ReplicationQuery) -> Result<RequestParts, ResoError> {
+        self.dry_run_url(self.build_url(&query.to_odata_string()), self.format.accept())
+    }
+
+    fn dry_run_url(&self, url: String, accept: &'static str) -> Result<RequestParts, ResoError> {
+        let mut request = self.request_parts(url, accept, HeaderValue::from_static("Bearer [redacted]"));
+        let encoded = reqwest::Url::parse(&request.url)
+            .map_err(|e| ResoError::InvalidQuery(format!("Invalid request URL '{}': {}", request.url, e)))?;
+        request.url = redact_url(encoded.as_str());
+        for name in SECRET_HEADERS {
+            if let Some(value) = request.headers.get_mut(*name) {
+                *value = HeaderValue::from_static("[redacted]");
+            }
+        }
+        request.headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer [redacted]"));
+        Ok(request)
+    }
+
+    async fn json(&self, url: String) -> Result<JsonValue, ResoError> {
+        let (_, body) = self.send(url, self.format.accept()).await?;
+        self.format.parse(&body)
+    }
+
+    async fn text(&self, url: String, accept: &'static str) -> Result<String, ResoError> {
+        Ok(self.send(url, accept).await?.1)
+    }
+
+    async fn replication_page(&self, url: String) -> Result<ReplicationResponse, ResoError> {
+        let (headers, body) = self.send(url, self.format.accept()).await?;
+        let mut json = self.format.parse(&body)?;
+        // Formats that carry the next link in the body report it as `@odata.nextLink`
+        let next_link = json["@odata.nextLink"]
+            .as_str()
+            .map(str::to_string)
+            .or_else(|| header_next_link(&headers));
+        let records = match json["value"].take() {
+            JsonValue::Array(records) => records,
+            _ => Vec::new(),
+        };
+        Ok(ReplicationResponse::new(records, next_link))
+    }
+
+    /// Starts a replication request whose records are parsed as the body
+    /// arrives instead of all at once (see [`streaming`](crate::streaming)).
+    pub async fn stream_replication(&self, query: &ReplicationQuery) -> Result<RecordStream, ResoError> {
+        self.stream_page(self.build_url(&query.to_odata_string())).await
+    }
+
+    /// Like [`stream_replication`](Self::stream_replication), for a next link.
+    pub async fn stream_next_link(&self, next_link: &str) -> Result<RecordStream, ResoError> {
+        self.stream_page(next_link.to_string()).await
+    }
+
+    async fn stream_page(&self, url: String) -> Result<RecordStream, ResoError> {
+        let (response, elapsed) = self.start(url, "application/json").await?;
+        let status = response.status();
+        if self.debug_wire {
+            eprintln!("{}", format_wire_response(status, response.headers(), elapsed, "<streamed body>"));
+        }
+        if !status.is_success() {
+            let headers = response.headers().clone();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error_from_response(status, &headers, &body));
+        }
+        let next_link = header_next_link(response.headers());
+        Ok(RecordStream::new(response, next_link))
+    }
+}
+
+#[async_trait]
+impl ResoApi for HttpClient {
+    fn base_url(&self) -> &str {
+        &self.config.base_url
+    }
+
+    async fn execute(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        self.json(self.build_url(&query.to_odata_string())).await
+    }
+
+    async fn execute_by_key(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        self.json(self.build_url(&query.to_odata_string())).await
+    }
+
+    async fn execute_count(&self, query: &Query) -> Result<u64, ResoError> {
+        let text = self
+            .text(self.build_url(&query.to_odata_string()), "text/plain")
+            .await?;
+        text.trim()
+            .parse()
+            .map_err(|e| ResoError::Parse(format!("Failed to parse count '{}': {}", text, e)))
+    }
+
+    async fn fetch_metadata(&self) -> Result<String, ResoError> {
+        self.text(self.build_url("$metadata"), "application/xml").await
+    }
+
+    async fn execute_replication(
+        &self,
+        query: &ReplicationQuery,
+    ) -> Result<ReplicationResponse, ResoError> {
+        self.replication_page(self.build_url(&query.to_odata_string()))
+            .await
+    }
+
+    async fn execute_next_link(&self, next_link: &str) -> Result<ReplicationResponse, ResoError> {
+        self.replication_page(next_link.to_string()).await
+    }
+}
+
+/// Largest part of a body printed in wire-debug mode.
+const WIRE_BODY_LIMIT: usize = 8 * 1024;
+
+const SECRET_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "set-cookie", "x-api-key"];
+
+const SECRET_FIELDS: &[&str] = &["access_token", "refresh_token", "id_token", "token", "client_secret", "password"];
+
+/// The next page's URL from a response's headers: a bare URL in `next`, or
+/// the `rel="next"` target of an RFC 5988 `Link` header
+/// (`<https://...>; rel="next"`).
+pub(crate) fn header_next_link(headers: &HeaderMap) -> Option<String> {
+    if let Some(next) = headers.get("next").and_then(|v| v.to_str().ok()) {
+        let next = next.trim();
+        let url = next.strip_prefix('<').and_then(|rest| rest.split_once('>')).map_or(next, |(url, _)| url);
+        return (!url.is_empty()).then(|| url.to_string());
+    }
+    let link = headers.get("link").and_then(|v| v.to_str().ok())?;
+    link.split(',').find_map(|entry| {
+        let (target, params) = entry.trim().strip_prefix('<')?.split_once('>')?;
+        params
+            .split(';')
+            .filter_map(|param| param.trim().strip_prefix("rel="))
+            .any(|rel| rel.trim_matches('"').split_whitespace().any(|r| r.eq_ignore_ascii_case("next")))
+            .then(|| target.to_string())
+    })
+}
+
+fn format_wire_request(request: &RequestParts) -> String {
+    format!(
+        "> {} {}\n{}",
+        request.method,
+        redact_url(&request.url),
+        format_wire_headers('>', &request.headers)
+    )
+}
+
+fn format_wire_response(status: StatusCode, headers: &HeaderMap, elapsed: Duration, body: &str) -> String {
+    format!(
+        "< {} ({} ms)\n{}{}\n",
+        status,
+        elapsed.as_millis(),
+        format_wire_headers('<', headers),
+        format_wire_body(body)
+    )
+}
+
+fn format_wire_headers(prefix: char, headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                "[redacted]"
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            format!("{} {}: {}\n", prefix, name, value)
+        })
+        .collect()
+}
+
+/// Pretty-prints JSON bodies with secret-looking fields redacted, then caps
+/// the output at [`WIRE_BODY_LIMIT`] bytes.
+fn format_wire_body(body: &str) -> String {
+    let mut text = match serde_json::from_str::<JsonValue>(body) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            serde_json::to_string_pretty(&json).unwrap_or_else(|_| body.to_string())
+        }
+        Err(_) => body.to_string(),
+    };
+
+    if text.len() > WIRE_BODY_LIMIT {
+        let total = text.len();
+        let mut cut = WIRE_BODY_LIMIT;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        text.truncate(cut);
+        text.push_str(&format!("\n... ({} of {} bytes shown)", cut, total));
+    }
+    text
+}
+
+fn redact_json(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SECRET_FIELDS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *value = JsonValue::String("[redacted]".to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Hides token-like query parameters, which some vendors accept instead of
+/// an `Authorization` header.
+fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let params: Vec<String> = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((name, _)) if SECRET_FIELDS.contains(&name.to_ascii_lowercase().as_str()) => {
+                format!("{}=[redacted]", name)
+            }
+            _ => param.to_string(),
+        })
+        .collect();
+    format!("{}?{}", base, params.join("&"))
+}
+
+fn read_pem_env(var: &str) -> Result<Option<Vec<u8>>, ResoError> {
+    match std::env::var(var) {
+        Ok(path) if !path.is_empty() => std::fs::read(&path)
+            .map(Some)
+            .map_err(|e| ResoError::Config(format!("Cannot read {} ({}): {}", var, path, e))),
+        _ => Ok(None),
+    }
+}
+
+/// Maps a transport error, spelling out timeouts so callers such as the
+/// adaptive page size in [`sync`](crate::sync) can tell them apart.
+/// Whether `RESO_DEBUG_WIRE` asks for requests and responses to be
+/// printed (see [`HttpClientBuilder::debug_wire`]).
+pub fn debug_wire_from_env() -> bool {
+    std::env::var("RESO_DEBUG_WIRE").is_ok_and(|v| v == "1" || v == "true")
+}
+
+/// Start of the message of a request that timed out.
+pub(crate) const TIMED_OUT: &str = "Request timed out";
+
+/// Returns true if `error` is a request that [`HttpClient`] or a
+/// [`Downloader`](crate::download::Downloader) gave up waiting for.
+pub fn is_timeout(error: &ResoError) -> bool {
+    matches!(error, ResoError::Network(message) if message.starts_with(TIMED_OUT))
+}
+
+fn network_error(e: reqwest::Error) -> ResoError {
+    if e.is_timeout() {
+        ResoError::Network(format!("{}: {}", TIMED_OUT, e))
+    } else {
+        ResoError::Network(e.to_string())
+    }
+}
+
+/// Like [`error_from_status`], noting the response's `Retry-After` on a 429
+/// or 503 so retries can wait as long as the server asked (see
+/// [`retry_after`](crate::retry::retry_after)).
+fn error_from_response(status: StatusCode, headers: &HeaderMap, body: &str) -> ResoError {
+    let error = error_from_status(status.as_u16(), body);
+    let wait = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, Utc::now()));
+    match wait {
+        Some(wait) => note_retry_after(error, wait),
+        None => error,
+    }
+}
+
+/// Maps an HTTP error status to a `ResoError` the way `ResoClient` does,
+/// using the OData error message from the body when there is one.
+pub(crate) fn error_from_status(status_code: u16, body: &str) -> ResoError {
+    let odata = serde_json::from_str::<JsonValue>(body).ok();
+    let message = match odata.as_ref().map(|json| &json["error"]) {
+        Some(error) if error["message"].is_string() => {
+            let message = error["message"].as_str().unwrap_or_default();
+            match error["code"].as_str().filter(|code| !code.is_empty()) {
+                Some(code) => format!("{} (code: {})", message, code),
+                None => message.to_string(),
+            }
+        }
+        _ => body.chars().take(500).collect(),
+    };
+
+    match status_code {
+        401 => ResoError::Unauthorized { message, status_code },
+        403 => ResoError::Forbidden { message, status_code },
+        404 => ResoError::NotFound { message, status_code },
+        429 => ResoError::RateLimited { message, status_code },
+        500..=599 => ResoError::ServerError { message, status_code },
+        _ => ResoError::ODataError { message, status_code },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct RecordStatuses(Arc<Mutex<Vec<u16>>>);
+
+    #[async_trait]
+    impl ResponseHook for RecordStatuses {
+        async fn on_response(&self, response: &ResponseParts) {
+            self.0.lock().unwrap().push(response.status.as_u16());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks_modify_requests_and_observe_responses() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/Property/$count"))
+            .and(header("Authorization", "Bearer secret"))
+            .and(header("X-Signature", "signed"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("42"))
+            .mount(&server)
+            .await;
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "secret"))
+            .request_hook(|mut request| {
+                request
+                    .headers
+                    .insert("X-Signature", HeaderValue::from_static("signed"));
+                request
+            })
+            .response_hook(RecordStatuses(statuses.clone()))
+            .build()
+            .unwrap();
+
+        assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 42);
+        assert!(client.fetch_metadata().await.is_err());
+        assert_eq!(*statuses.lock().unwrap(), vec![200, 404]);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_ip_bypasses_dns() {
+        let server = MockServer::start().await;
+        Mock::given(path("/Property/$count"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("7"))
+            .mount(&server)
+            .await;
+
+        // `.invalid` names never resolve, so this only succeeds if pinned
+        let base_url = format!("http://reso.invalid:{}", server.address().port());
+        let client = HttpClient::builder(ClientConfig::new(base_url, "token"))
+            .pin_ip(&[server.address().ip()])
+            .build()
+            .unwrap();
+
+        assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 7);
+    }
+
+    struct TenantToken(&'static str);
+
+    #[async_trait]
+    impl TokenProvider for TenantToken {
+        async fn token(&self) -> Result<String, ResoError> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_provider_and_user_agent() {
+        let server = MockServer::start().await;
+        Mock::given(path("/Property/$count"))
+            .and(header("Authorization", "Bearer tenant-a"))
+            .and(header("User-Agent", "listing-portal/2.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("9"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The configured token is ignored in favor of the provider's
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "unused"))
+            .token_provider(TenantToken("tenant-a"))
+            .user_agent("listing-portal/2.1")
+            .build()
+            .unwrap();
+        assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 9);
+
+        let bad_proxy = HttpClient::builder(ClientConfig::new(server.uri(), "token")).proxy("not a url").build();
+        assert!(matches!(bad_proxy, Err(ResoError::Config(_))));
+    }
+
+    #[test]
+    fn test_invalid_tls_material_is_a_config_error() {
+        let config = || ClientConfig::new("https://example.com", "token");
+        let no_certs = HttpClient::builder(config()).ca_bundle_pem("not a certificate").build();
+        assert!(matches!(no_certs, Err(ResoError::Config(_))));
+
+        let no_key = HttpClient::builder(config()).client_identity_pem("not a key").build();
+        assert!(matches!(no_key, Err(ResoError::Config(_))));
+    }
+
+    #[test]
+    fn test_wire_debug_redacts_secrets() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        let request = RequestParts {
+            method: Method::GET,
+            url: "https://x.test/Property?$top=1&access_token=secret".to_string(),
+            headers,
+        };
+        let dump = format_wire_request(&request);
+        assert!(!dump.contains("secret"));
+        assert!(dump.contains("$top=1"));
+        assert!(dump.contains("accept: application/json"));
+
+        let body = format_wire_body(r#"{"value": [{"ListPrice": 1}], "Token": "secret"}"#);
+        assert!(body.contains("\"ListPrice\": 1"));
+        assert!(!body.contains("secret"));
+
+        let long = format_wire_body(&"é".repeat(WIRE_BODY_LIMIT));
+        assert!(long.ends_with(&format!("({} of {} bytes shown)", WIRE_BODY_LIMIT, 2 * WIRE_BODY_LIMIT)));
+    }
+
+    #[test]
+    fn test_dry_run_encodes_url_and_redacts_secrets() {
+        let client = HttpClient::builder(ClientConfig::new("https://api.example.com/odata", "secret-token"))
+            .request_hook(|mut request: RequestParts| {
+                request.headers.insert("x-api-key", HeaderValue::from_static("key-123"));
+                request.url.push_str("&access_token=secret-token");
+                request
+            })
+            .build()
+            .unwrap();
+        let query = crate::build_query("Property", Some("City eq 'Round Rock'"), Some(5)).unwrap();
+
+        let request = client.dry_run(&query).unwrap();
+        assert!(request.url.starts_with("https://api.example.com/odata/Property?"));
+        assert!(request.url.contains("Round") && !request.url.contains(' '));
+        assert!(request.url.ends_with("access_token=[redacted]"));
+
+        let printed = request.to_string();
+        assert!(printed.starts_with("GET https://api.example.com/odata/Property?"));
+        assert!(printed.contains("authorization: Bearer [redacted]"));
+        assert!(printed.contains("accept: application/json"));
+        assert!(printed.contains("x-api-key: [redacted]"));
+        assert!(!printed.contains("secret-token") && !printed.contains("key-123"));
+    }
+
+    #[test]
+    fn test_header_next_link_parses_link_targets() {
+        let headers = |name: &'static str, value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, HeaderValue::from_static(value));
+            headers
+        };
+        assert_eq!(
+            header_next_link(&headers("link", r#"<https://x.test/Property/replication?next=a>; rel="next""#)).as_deref(),
+            Some("https://x.test/Property/replication?next=a")
+        );
+        assert_eq!(
+            header_next_link(&headers("link", r#"<https://x.test/p1>; rel="prev", <https://x.test/p3>; rel=next"#)).as_deref(),
+            Some("https://x.test/p3")
+        );
+        assert_eq!(header_next_link(&headers("link", r#"<https://x.test/p1>; rel="prev""#)), None);
+        assert_eq!(
+            header_next_link(&headers("next", "https://x.test/Property/replication?next=b")).as_deref(),
+            Some("https://x.test/Property/replication?next=b")
+        );
+        assert_eq!(header_next_link(&HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn test_replication_prefers_the_body_next_link() {
+        let server = MockServer::start().await;
+        Mock::given(path("/Property/replication"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", r#"<https://x.test/from-header>; rel="next""#)
+                    .set_body_json(serde_json::json!({"value": [], "@odata.nextLink": "https://x.test/from-body"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/next"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", r#"<https://x.test/from-header>; rel="next""#)
+                    .set_body_json(serde_json::json!({"value": []})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "token")).build().unwrap();
+        let query = reso_client::ReplicationQueryBuilder::new("Property").build().unwrap();
+        let page = client.execute_replication(&query).await.unwrap();
+        assert_eq!(page.next_link(), Some("https://x.test/from-body"));
+        let page = client.execute_next_link(&format!("{}/Property/next", server.uri())).await.unwrap();
+        assert_eq!(page.next_link(), Some("https://x.test/from-header"));
+    }
+
+    #[test]
+    fn test_error_from_status_uses_odata_message() {
+        let body = r#"{"error": {"code": "BadField", "message": "Unknown field Foo"}}"#;
+        match error_from_status(400, body) {
+            ResoError::ODataError { message, status_code } => {
+                assert_eq!(message, "Unknown field Foo (code: BadField)");
+                assert_eq!(status_code, 400);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(matches!(error_from_status(429, ""), ResoError::RateLimited { .. }));
+    }
+
+    #[test]
+    fn test_error_from_response_notes_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        let error = error_from_response(StatusCode::TOO_MANY_REQUESTS, &headers, "");
+        assert_eq!(crate::retry::retry_after(&error), Some(Duration::from_secs(7)));
+        let error = error_from_response(StatusCode::BAD_REQUEST, &headers, "");
+        assert_eq!(crate::retry::retry_after(&error), None);
+    }
+}
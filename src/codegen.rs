@@ -0,0 +1,317 @@
+//! Rust source generation from RESO metadata.
+//!
+//! Field names are plain strings on the wire, so a typo in a `$select` or
+//! filter only shows up as a server error at runtime. The generator here
+//! emits a module of string constants per resource
+//! (`fields::property::LIST_PRICE`), turning those typos into compile errors.
+//! The crate ships [`fields`](crate::fields) generated from the Data
+//! Dictionary; `reso codegen` generates the same module from a server's
+//! metadata, including its local fields.
+//!
+//! Which fields a server exposes, and their types, vary from one MLS to the
+//! next, so a fixed set of record structs such as
+//! [`models::Property`](crate::models::Property) never quite matches.
+//! [`schema_structs`] (`reso codegen --structs`) generates serde structs for
+//! every resource a particular server publishes.
+
+use crate::dictionary::{standard_fields, STANDARD_RESOURCES};
+use crate::metadata::{EntityType, ResoSchema};
+
+/// Generates the field constants module for every resource in a schema.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, fetch_metadata};
+/// use reso_examples::{codegen::schema_field_constants, metadata::parse_metadata};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let schema = parse_metadata(&fetch_metadata(&client).await?)?;
+///     std::fs::write("src/fields.rs", schema_field_constants(&schema))?;
+///     Ok(())
+/// }
+/// ```
+pub fn schema_field_constants(schema: &ResoSchema) -> String {
+    generate_field_constants(
+        "Field name constants generated from server metadata by `reso codegen`.",
+        schema.resources().iter().map(|r| {
+            let fields = r.entity_type.fields.iter().map(|f| f.name.as_str()).collect();
+            (r.name, fields)
+        }),
+    )
+}
+
+/// Generates the field constants module for the Data Dictionary resources.
+pub fn dictionary_field_constants() -> String {
+    generate_field_constants(
+        "RESO Data Dictionary field name constants.\n//!\n\
+         //! Generated by `codegen::dictionary_field_constants`; do not edit by hand.",
+        STANDARD_RESOURCES
+            .iter()
+            .map(|r| (*r, standard_fields(r).unwrap_or_default().to_vec())),
+    )
+}
+
+/// Generates a module with one submodule of constants per resource.
+///
+/// Each submodule also lists every constant in `ALL_FIELDS`. Fields whose
+/// constant name collides with an earlier field are left out.
+pub fn generate_field_constants<'a>(
+    doc: &str,
+    resources: impl IntoIterator<Item = (&'a str, Vec<&'a str>)>,
+) -> String {
+    let mut out = format!("//! {}\n", doc);
+
+    for (resource, fields) in resources {
+        out.push_str(&format!(
+            "\npub mod {} {{\n",
+            rust_ident(&to_snake_case(resource))
+        ));
+
+        let mut names: Vec<String> = Vec::new();
+        for field in fields {
+            let name = const_name(field);
+            if names.contains(&name) {
+                continue;
+            }
+            out.push_str(&format!("    pub const {}: &str = {:?};\n", name, field));
+            names.push(name);
+        }
+
+        out.push_str("\n    pub const ALL_FIELDS: &[&str] = &[\n");
+        for name in &names {
+            out.push_str(&format!("        {},\n", name));
+        }
+        out.push_str("    ];\n}\n");
+    }
+
+    out
+}
+
+/// Generates a module with a serde struct for every resource in a schema.
+///
+/// Each structural field becomes a snake_case struct field renamed to its
+/// wire name. Key fields are required and the rest are `Option`s, since
+/// servers leave out empty fields and fields not in `$select`. Edm types map
+/// onto Rust types (`Edm.Decimal` to `f64`, `Edm.DateTimeOffset` to
+/// `chrono::DateTime<Utc>`, collections to `Vec`); enumerations are read as
+/// their `String` names, and types without a Rust counterpart, such as
+/// geography points, as `serde_json::Value`. Expanded navigation properties
+/// and fields whose names collide land in a flattened `other` map.
+///
+/// The generated code needs `serde`, `serde_json`, and `chrono` with its
+/// `serde` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, fetch_metadata};
+/// use reso_examples::{codegen::schema_structs, metadata::parse_metadata};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let schema = parse_metadata(&fetch_metadata(&client).await?)?;
+///     std::fs::write("src/records.rs", schema_structs(&schema))?;
+///     Ok(())
+/// }
+/// ```
+pub fn schema_structs(schema: &ResoSchema) -> String {
+    let mut out = String::from(
+        "//! Record types generated from server metadata by `reso codegen --structs`.\n\n\
+         use serde::{Deserialize, Serialize};\n",
+    );
+    for resource in schema.resources() {
+        out.push('\n');
+        out.push_str(&record_struct(resource.name, resource.entity_type));
+    }
+    out
+}
+
+/// One struct of [`schema_structs`].
+fn record_struct(resource: &str, entity_type: &EntityType) -> String {
+    let name: String = resource.chars().filter(char::is_ascii_alphanumeric).collect();
+    let mut out = format!(
+        "/// A `{}` record.\n\
+         #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]\n\
+         pub struct {} {{\n",
+        resource,
+        rust_ident(&name)
+    );
+
+    // `other` is taken by the map of remaining fields
+    let mut names = vec!["other".to_string()];
+    for field in &entity_type.fields {
+        let ident = rust_ident(&to_snake_case(&field.name));
+        if names.contains(&ident) {
+            continue;
+        }
+        let rust_type = rust_type(&field.edm_type);
+        if entity_type.key.contains(&field.name) {
+            out.push_str(&format!("    #[serde(rename = {:?})]\n", field.name));
+            out.push_str(&format!("    pub {}: {},\n", ident, rust_type));
+        } else {
+            out.push_str(&format!(
+                "    #[serde(rename = {:?}, default, skip_serializing_if = \"Option::is_none\")]\n",
+                field.name
+            ));
+            out.push_str(&format!("    pub {}: Option<{}>,\n", ident, rust_type));
+        }
+        names.push(ident);
+    }
+
+    out.push_str(
+        "    /// Expanded navigation properties and fields not covered above\n\
+         \x20   #[serde(flatten)]\n\
+         \x20   pub other: serde_json::Map<String, serde_json::Value>,\n}\n",
+    );
+    out
+}
+
+/// The Rust type a value of an Edm type is deserialized into.
+fn rust_type(edm_type: &str) -> String {
+    if let Some(inner) = edm_type.strip_prefix("Collection(").and_then(|t| t.strip_suffix(')')) {
+        return format!("Vec<{}>", rust_type(inner));
+    }
+    match edm_type {
+        "Edm.String" | "Edm.Guid" => "String",
+        "Edm.Boolean" => "bool",
+        "Edm.Byte" => "u8",
+        "Edm.SByte" => "i8",
+        "Edm.Int16" => "i16",
+        "Edm.Int32" => "i32",
+        "Edm.Int64" => "i64",
+        "Edm.Decimal" | "Edm.Double" => "f64",
+        "Edm.Single" => "f32",
+        "Edm.Date" => "chrono::NaiveDate",
+        "Edm.DateTimeOffset" => "chrono::DateTime<chrono::Utc>",
+        "Edm.TimeOfDay" => "chrono::NaiveTime",
+        // Enumerations are declared in the server's own namespace and arrive
+        // as their member names
+        other if !other.starts_with("Edm.") => "String",
+        _ => "serde_json::Value",
+    }
+    .to_string()
+}
+
+/// Converts a PascalCase name to snake_case, keeping acronyms together
+/// (`BuyerAgentMlsId` → `buyer_agent_mls_id`, `ListingURL` → `listing_url`).
+pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                out.push('_');
+            }
+        }
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+
+    out
+}
+
+fn const_name(field: &str) -> String {
+    rust_ident(&to_snake_case(field).to_ascii_uppercase())
+}
+
+/// Makes a generated name a valid identifier.
+fn rust_ident(name: &str) -> String {
+    // Strict and reserved keywords of the 2024 edition.
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+        "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+        "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+        "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    // Keywords that cannot be raw identifiers.
+    const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+    let name = name.trim_matches('_');
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else if PATH_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("ListPrice"), "list_price");
+        assert_eq!(to_snake_case("ListingURL"), "listing_url");
+        assert_eq!(to_snake_case("BuyerAgentMlsId"), "buyer_agent_mls_id");
+        assert_eq!(to_snake_case("X_ABOR_Flag"), "x_abor_flag");
+        assert_eq!(to_snake_case("Stories2"), "stories2");
+        assert_eq!(const_name("Type"), "TYPE");
+        assert_eq!(rust_ident("type"), "r#type");
+        assert_eq!(rust_ident("try"), "r#try");
+        assert_eq!(rust_ident("yield"), "r#yield");
+        assert_eq!(rust_ident("unsafe"), "r#unsafe");
+        assert_eq!(rust_ident("self"), "self_");
+    }
+
+    #[test]
+    fn test_schema_structs() {
+        use crate::metadata::Field;
+
+        let field = |name: &str, edm_type: &str| Field {
+            name: name.to_string(),
+            edm_type: edm_type.to_string(),
+            nullable: true,
+        };
+        let schema = ResoSchema {
+            entity_types: vec![EntityType {
+                name: "Property".to_string(),
+                namespace: "org.reso.metadata".to_string(),
+                key: vec!["ListingKey".to_string()],
+                fields: vec![
+                    field("ListingKey", "Edm.String"),
+                    field("ListPrice", "Edm.Decimal"),
+                    field("Type", "org.reso.metadata.enums.Type"),
+                    field("Appliances", "Collection(org.reso.metadata.enums.Appliances)"),
+                    field("Coordinates", "Edm.GeographyPoint"),
+                    field("Other", "Edm.String"),
+                ],
+                navigation: Vec::new(),
+            }],
+            entity_sets: Vec::new(),
+        };
+
+        let source = schema_structs(&schema);
+        assert!(source.contains("pub struct Property {\n    #[serde(rename = \"ListingKey\")]\n    pub listing_key: String,\n"));
+        assert!(source.contains("    pub list_price: Option<f64>,\n"));
+        assert!(source.contains("    #[serde(rename = \"Type\", default, skip_serializing_if = \"Option::is_none\")]\n    pub r#type: Option<String>,\n"));
+        assert!(source.contains("    pub appliances: Option<Vec<String>>,\n"));
+        assert!(source.contains("    pub coordinates: Option<serde_json::Value>,\n"));
+        assert!(!source.contains("\"Other\""));
+        assert!(source.ends_with("    pub other: serde_json::Map<String, serde_json::Value>,\n}\n"));
+    }
+
+    #[test]
+    fn test_checked_in_fields_module_is_current() {
+        // Regenerate with `codegen::dictionary_field_constants` after editing
+        // the dictionary lists
+        assert_eq!(include_str!("fields.rs"), dictionary_field_constants());
+    }
+}
@@ -0,0 +1,324 @@
+//! Merging and deduplicating newline-delimited JSON stores.
+//!
+//! A long-running sync into [`JsonlSink`](crate::export::JsonlSink) files
+//! accumulates a full snapshot plus a growing series of incremental delta
+//! files, each holding new versions of records already seen. The functions
+//! here fold them into a single file with one line per record key, or into
+//! a series of files of bounded size.
+
+use reso_client::{JsonValue, ResoError};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Counts reported by [`merge_snapshot`] and [`compact_jsonl`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompactStats {
+    /// Input files read
+    pub files: usize,
+    /// Records read across all inputs
+    pub records_in: u64,
+    /// Records written, one per key
+    pub records_out: u64,
+    /// Older versions dropped in favour of a newer one
+    pub duplicates_removed: u64,
+    /// Files written
+    pub outputs: Vec<PathBuf>,
+}
+
+/// The newest version of each record, by key.
+///
+/// A record replaces the stored version of its key unless the stored one
+/// has a newer `ModificationTimestamp`; with equal or missing timestamps the
+/// record read last wins.
+#[derive(Default)]
+pub(crate) struct LatestRecords {
+    records: BTreeMap<String, JsonValue>,
+    stats: CompactStats,
+}
+
+impl LatestRecords {
+    pub(crate) fn read_file(&mut self, path: &Path, key_field: &str) -> Result<(), ResoError> {
+        read_keyed_records(path, key_field, |key, record| self.insert(key, record))?;
+        self.stats.files += 1;
+        Ok(())
+    }
+
+    fn insert(&mut self, key: String, record: JsonValue) {
+        self.stats.records_in += 1;
+        match self.records.get(&key) {
+            None => {
+                self.records.insert(key, record);
+            }
+            Some(stored) => {
+                self.stats.duplicates_removed += 1;
+                let stored_ts = stored["ModificationTimestamp"].as_str();
+                let new_ts = record["ModificationTimestamp"].as_str();
+                let stored_is_newer = matches!((stored_ts, new_ts), (Some(s), Some(n)) if s > n);
+                if !stored_is_newer {
+                    self.records.insert(key, record);
+                }
+            }
+        }
+    }
+
+    /// Iterates the records in key order.
+    pub(crate) fn records(&self) -> impl Iterator<Item = &JsonValue> {
+        self.records.values()
+    }
+}
+
+/// Reads a JSONL file in order, passing each record with its key to `f`.
+///
+/// Blank lines are skipped; a record without the key field is an error.
+pub(crate) fn read_keyed_records(
+    path: &Path,
+    key_field: &str,
+    mut f: impl FnMut(String, JsonValue),
+) -> Result<(), ResoError> {
+    let file = File::open(path).map_err(|e| io_error("open", path, e))?;
+    for (line_number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| io_error("read", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: JsonValue = serde_json::from_str(&line).map_err(|e| {
+            ResoError::Parse(format!("{} line {}: {}", path.display(), line_number + 1, e))
+        })?;
+        let key = match &record[key_field] {
+            JsonValue::String(s) => s.clone(),
+            JsonValue::Null => {
+                return Err(ResoError::Parse(format!(
+                    "{} line {}: missing key field '{}'",
+                    path.display(),
+                    line_number + 1,
+                    key_field
+                )))
+            }
+            other => other.to_string(),
+        };
+        f(key, record);
+    }
+    Ok(())
+}
+
+/// Merges a base snapshot with later delta files into a new snapshot.
+///
+/// Every input is a JSONL file; deltas are applied in the order given. The
+/// output holds the newest version of each record, sorted by key, and is
+/// written to a temporary file first so a failed merge never leaves a
+/// truncated snapshot behind.
+///
+/// # Arguments
+///
+/// * `base` - The previous full snapshot
+/// * `deltas` - Incremental files written since, oldest first
+/// * `out` - Where to write the merged snapshot; may be the same as `base`
+/// * `key_field` - Field identifying a record, e.g. "ListingKey"
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::compact::merge_snapshot;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let stats = merge_snapshot(
+///     "property.snapshot.jsonl",
+///     &["property.2025-01-02.jsonl", "property.2025-01-03.jsonl"],
+///     "property.snapshot.jsonl",
+///     "ListingKey",
+/// )?;
+/// println!("{} records, {} superseded versions dropped", stats.records_out, stats.duplicates_removed);
+/// # Ok(())
+/// # }
+/// ```
+pub fn merge_snapshot(
+    base: impl AsRef<Path>,
+    deltas: &[impl AsRef<Path>],
+    out: impl AsRef<Path>,
+    key_field: &str,
+) -> Result<CompactStats, ResoError> {
+    let mut latest = LatestRecords::default();
+    latest.read_file(base.as_ref(), key_field)?;
+    for delta in deltas {
+        latest.read_file(delta.as_ref(), key_field)?;
+    }
+
+    let out = out.as_ref();
+    let records_out = write_atomically(out, latest.records())?;
+    Ok(CompactStats {
+        records_out,
+        outputs: vec![out.to_path_buf()],
+        ..latest.stats
+    })
+}
+
+/// Rewrites an append-only JSONL store keeping only the newest version of
+/// each record.
+///
+/// Without `max_file_bytes` the store is replaced in place. With it, the
+/// records are split into files of at most that size (a single larger
+/// record gets a file of its own) named after the store:
+/// `property.jsonl` becomes `property-00000.jsonl`, `property-00001.jsonl`,
+/// and so on, and the original file is removed once they are written.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::compact::compact_jsonl;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let stats = compact_jsonl("property.jsonl", "ListingKey", Some(256 * 1024 * 1024))?;
+/// println!(
+///     "Removed {} duplicates; wrote {} records to {} files",
+///     stats.duplicates_removed,
+///     stats.records_out,
+///     stats.outputs.len()
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn compact_jsonl(
+    path: impl AsRef<Path>,
+    key_field: &str,
+    max_file_bytes: Option<u64>,
+) -> Result<CompactStats, ResoError> {
+    let path = path.as_ref();
+    let mut latest = LatestRecords::default();
+    latest.read_file(path, key_field)?;
+
+    let Some(max_file_bytes) = max_file_bytes else {
+        let records_out = write_atomically(path, latest.records())?;
+        return Ok(CompactStats {
+            records_out,
+            outputs: vec![path.to_path_buf()],
+            ..latest.stats
+        });
+    };
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let part_path = |n: usize| path.with_file_name(format!("{}-{:05}.jsonl", stem, n));
+
+    let mut outputs = Vec::new();
+    let mut part: Vec<&JsonValue> = Vec::new();
+    let mut part_bytes = 0;
+    let mut records_out = 0;
+    for record in latest.records() {
+        let size = record.to_string().len() as u64 + 1;
+        if !part.is_empty() && part_bytes + size > max_file_bytes {
+            let out = part_path(outputs.len());
+            records_out += write_atomically(&out, part.drain(..))?;
+            outputs.push(out);
+            part_bytes = 0;
+        }
+        part.push(record);
+        part_bytes += size;
+    }
+    if !part.is_empty() || outputs.is_empty() {
+        let out = part_path(outputs.len());
+        records_out += write_atomically(&out, part.drain(..))?;
+        outputs.push(out);
+    }
+
+    if !outputs.iter().any(|out| out == path) {
+        std::fs::remove_file(path).map_err(|e| io_error("remove", path, e))?;
+    }
+    Ok(CompactStats {
+        records_out,
+        outputs,
+        ..latest.stats
+    })
+}
+
+/// Writes records as JSONL through a temporary file renamed over `path`.
+pub(crate) fn write_atomically<'a>(
+    path: &Path,
+    records: impl Iterator<Item = &'a JsonValue>,
+) -> Result<u64, ResoError> {
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    let file = File::create(&tmp).map_err(|e| io_error("create", &tmp, e))?;
+    let mut writer = BufWriter::new(file);
+    let mut count = 0;
+    for record in records {
+        serde_json::to_writer(&mut writer, record).map_err(|e| ResoError::Parse(e.to_string()))?;
+        writer.write_all(b"\n").map_err(|e| io_error("write", &tmp, e))?;
+        count += 1;
+    }
+    writer.flush().map_err(|e| io_error("write", &tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| io_error("replace", path, e))?;
+    Ok(count)
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> ResoError {
+    ResoError::Config(format!("Cannot {} {}: {}", action, path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_newest_version() {
+        let dir = std::env::temp_dir().join(format!("reso-compact-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("base.jsonl");
+        let delta = dir.join("delta.jsonl");
+        std::fs::write(
+            &base,
+            "{\"ListingKey\":\"B\",\"ListPrice\":1,\"ModificationTimestamp\":\"2025-01-01T00:00:00Z\"}\n\
+             {\"ListingKey\":\"A\",\"ListPrice\":1,\"ModificationTimestamp\":\"2025-01-03T00:00:00Z\"}\n",
+        )
+        .unwrap();
+        // B is updated; the delta's copy of A is older than the base's
+        std::fs::write(
+            &delta,
+            "{\"ListingKey\":\"B\",\"ListPrice\":2,\"ModificationTimestamp\":\"2025-01-02T00:00:00Z\"}\n\
+             {\"ListingKey\":\"A\",\"ListPrice\":0,\"ModificationTimestamp\":\"2025-01-02T00:00:00Z\"}\n",
+        )
+        .unwrap();
+
+        let stats = merge_snapshot(&base, &[&delta], &base, "ListingKey").unwrap();
+        assert_eq!(
+            stats,
+            CompactStats {
+                files: 2,
+                records_in: 4,
+                records_out: 2,
+                duplicates_removed: 2,
+                outputs: vec![base.clone()],
+            }
+        );
+
+        let merged: Vec<JsonValue> = std::fs::read_to_string(&base)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!((merged[0]["ListingKey"].as_str(), merged[0]["ListPrice"].as_i64()), (Some("A"), Some(1)));
+        assert_eq!((merged[1]["ListingKey"].as_str(), merged[1]["ListPrice"].as_i64()), (Some("B"), Some(2)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact_jsonl_splits_by_size() {
+        let dir = std::env::temp_dir().join(format!("reso-compact-split-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = dir.join("property.jsonl");
+        // Each line is 17 bytes including the newline
+        std::fs::write(
+            &store,
+            "{\"ListingKey\":\"A\"}\n{\"ListingKey\":\"B\"}\n{\"ListingKey\":\"A\"}\n{\"ListingKey\":\"C\"}\n",
+        )
+        .unwrap();
+
+        let stats = compact_jsonl(&store, "ListingKey", Some(40)).unwrap();
+        assert_eq!((stats.records_in, stats.records_out, stats.duplicates_removed), (4, 3, 1));
+        assert_eq!(stats.outputs, [dir.join("property-00000.jsonl"), dir.join("property-00001.jsonl")]);
+        assert_eq!(std::fs::read_to_string(&stats.outputs[1]).unwrap(), "{\"ListingKey\":\"C\"}\n");
+        assert!(!store.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+//! Comparable-property ("comps") search.
+//!
+//! A comparative market analysis starts from a subject listing and looks for
+//! recently closed listings nearby with a similar size and layout. This module
+//! fetches the subject, queries for candidate sales, and ranks them by how
+//! closely they match.
+
+use crate::geo::{haversine_miles, record_coordinates, BoundingBox};
+use chrono::{Duration, NaiveDate, Utc};
+use crate::client::ResoApi;
+use crate::MAX_PAGE_SIZE;
+use reso_client::{JsonValue, QueryBuilder, ResoError};
+
+/// Fields requested for both the subject and candidate listings.
+pub const COMPS_FIELDS: &[&str] = &[
+    "ListingKey",
+    "ListingId",
+    "StandardStatus",
+    "UnparsedAddress",
+    "City",
+    "StateOrProvince",
+    "PostalCode",
+    "PropertyType",
+    "BedroomsTotal",
+    "BathroomsTotalInteger",
+    "LivingArea",
+    "ListPrice",
+    "ClosePrice",
+    "CloseDate",
+    "Latitude",
+    "Longitude",
+];
+
+/// Options controlling how comparable listings are selected and ranked.
+#[derive(Debug, Clone)]
+pub struct CompsOptions {
+    /// Search radius around the subject, in miles
+    pub radius_miles: f64,
+    /// Only consider listings closed within this many days
+    pub closed_within_days: i64,
+    /// Allowed difference in `BedroomsTotal`
+    pub bedroom_tolerance: i64,
+    /// Allowed difference in `BathroomsTotalInteger`
+    pub bathroom_tolerance: i64,
+    /// Allowed relative difference in `LivingArea` (0.2 = ±20%)
+    pub living_area_tolerance: f64,
+    /// Only consider listings with the same `PropertyType` as the subject
+    pub same_property_type: bool,
+    /// Maximum number of comparables to return
+    pub max_results: usize,
+}
+
+impl Default for CompsOptions {
+    fn default() -> Self {
+        Self {
+            radius_miles: 1.0,
+            closed_within_days: 180,
+            bedroom_tolerance: 1,
+            bathroom_tolerance: 1,
+            living_area_tolerance: 0.2,
+            same_property_type: true,
+            max_results: 10,
+        }
+    }
+}
+
+/// A closed listing ranked against the subject property.
+#[derive(Debug, Clone)]
+pub struct Comparable {
+    /// The candidate listing as returned by the server
+    pub record: JsonValue,
+    /// Distance from the subject in miles, when both have coordinates
+    pub distance_miles: Option<f64>,
+    /// Dissimilarity score; lower values are better matches
+    pub score: f64,
+}
+
+/// Finds recently closed listings comparable to the subject listing.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `subject_key` - The `ListingKey` of the subject property
+/// * `opts` - Selection and ranking options
+///
+/// # Returns
+///
+/// Returns comparables ordered from best to worst match.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, comps::{find_comps, CompsOptions}};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let comps = find_comps(&client, "12345", &CompsOptions::default()).await?;
+///
+///     for comp in comps {
+///         println!("{} (score {:.2})", comp.record["ListingKey"], comp.score);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn find_comps(
+    client: &impl ResoApi,
+    subject_key: &str,
+    opts: &CompsOptions,
+) -> Result<Vec<Comparable>, ResoError> {
+    let subject_query = QueryBuilder::by_key("Property", subject_key)
+        .select(COMPS_FIELDS)
+        .build()?;
+    let response = client.execute_by_key(&subject_query).await?;
+
+    // Some servers wrap key lookups in a `value` array like collection queries
+    let subject = match response["value"].as_array() {
+        Some(records) => records
+            .first()
+            .cloned()
+            .ok_or_else(|| ResoError::NotFound {
+                message: format!("Property '{}' not found", subject_key),
+                status_code: 404,
+            })?,
+        None => response,
+    };
+
+    let today = Utc::now().date_naive();
+    let filter = build_comps_filter(&subject, opts, today);
+
+    let query = QueryBuilder::new("Property")
+        .filter(filter)
+        .select(COMPS_FIELDS)
+        .top(MAX_PAGE_SIZE)
+        .build()?;
+    let response = client.execute(&query).await?;
+
+    let candidates = response["value"].as_array().cloned().unwrap_or_default();
+    Ok(rank_comps(&subject, candidates, opts, today))
+}
+
+/// Builds the OData filter selecting candidate comparables for a subject.
+///
+/// Criteria for which the subject has no value (e.g. missing coordinates)
+/// are left out rather than excluding every candidate.
+pub fn build_comps_filter(subject: &JsonValue, opts: &CompsOptions, today: NaiveDate) -> String {
+    let since = today - Duration::days(opts.closed_within_days);
+    let mut filters = vec![
+        "StandardStatus eq 'Closed'".to_string(),
+        format!("CloseDate ge {}", since.format("%Y-%m-%d")),
+    ];
+
+    if let Some(key) = subject["ListingKey"].as_str() {
+        filters.push(format!("ListingKey ne '{}'", key.replace('\'', "''")));
+    }
+
+    if opts.same_property_type {
+        if let Some(property_type) = subject["PropertyType"].as_str() {
+            filters.push(format!(
+                "PropertyType eq '{}'",
+                property_type.replace('\'', "''")
+            ));
+        }
+    }
+
+    if let Some(beds) = subject["BedroomsTotal"].as_i64() {
+        filters.push(format!(
+            "BedroomsTotal ge {} and BedroomsTotal le {}",
+            beds - opts.bedroom_tolerance,
+            beds + opts.bedroom_tolerance
+        ));
+    }
+
+    if let Some(baths) = subject["BathroomsTotalInteger"].as_i64() {
+        filters.push(format!(
+            "BathroomsTotalInteger ge {} and BathroomsTotalInteger le {}",
+            baths - opts.bathroom_tolerance,
+            baths + opts.bathroom_tolerance
+        ));
+    }
+
+    if let Some(area) = subject["LivingArea"].as_f64() {
+        filters.push(format!(
+            "LivingArea ge {:.0} and LivingArea le {:.0}",
+            area * (1.0 - opts.living_area_tolerance),
+            area * (1.0 + opts.living_area_tolerance)
+        ));
+    }
+
+    if let Some((lat, lon)) = record_coordinates(subject) {
+        filters.push(BoundingBox::around(lat, lon, opts.radius_miles).to_filter());
+    }
+
+    filters.join(" and ")
+}
+
+/// Drops candidates outside the search radius and orders the rest by score.
+///
+/// The score sums the normalized differences in distance, bedrooms,
+/// bathrooms, living area, and time since closing, so each criterion
+/// contributes roughly 0–1 for a candidate within the configured tolerances.
+pub fn rank_comps(
+    subject: &JsonValue,
+    candidates: Vec<JsonValue>,
+    opts: &CompsOptions,
+    today: NaiveDate,
+) -> Vec<Comparable> {
+    let subject_coords = record_coordinates(subject);
+
+    let mut comps: Vec<Comparable> = candidates
+        .into_iter()
+        .filter_map(|record| {
+            let distance_miles = match (subject_coords, record_coordinates(&record)) {
+                (Some((lat1, lon1)), Some((lat2, lon2))) => {
+                    Some(haversine_miles(lat1, lon1, lat2, lon2))
+                }
+                _ => None,
+            };
+
+            // The bounding box admits corners outside the circle
+            if distance_miles.is_some_and(|d| d > opts.radius_miles) {
+                return None;
+            }
+
+            let score = comp_score(subject, &record, distance_miles, opts, today);
+            Some(Comparable {
+                record,
+                distance_miles,
+                score,
+            })
+        })
+        .collect();
+
+    comps.sort_by(|a, b| a.score.total_cmp(&b.score));
+    comps.truncate(opts.max_results);
+    comps
+}
+
+fn comp_score(
+    subject: &JsonValue,
+    candidate: &JsonValue,
+    distance_miles: Option<f64>,
+    opts: &CompsOptions,
+    today: NaiveDate,
+) -> f64 {
+    let mut score = 0.0;
+
+    if let Some(distance) = distance_miles {
+        score += distance / opts.radius_miles.max(f64::EPSILON);
+    }
+
+    let int_diff = |field: &str, tolerance: i64| -> f64 {
+        match (subject[field].as_i64(), candidate[field].as_i64()) {
+            (Some(a), Some(b)) => (a - b).abs() as f64 / (tolerance + 1) as f64,
+            _ => 0.0,
+        }
+    };
+    score += int_diff("BedroomsTotal", opts.bedroom_tolerance);
+    score += int_diff("BathroomsTotalInteger", opts.bathroom_tolerance);
+
+    if let (Some(a), Some(b)) = (
+        subject["LivingArea"].as_f64(),
+        candidate["LivingArea"].as_f64(),
+    ) {
+        if a > 0.0 && opts.living_area_tolerance > 0.0 {
+            score += ((a - b).abs() / a) / opts.living_area_tolerance;
+        }
+    }
+
+    let closed = candidate["CloseDate"]
+        .as_str()
+        .and_then(|s| s.get(..10))
+        .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+    if let Some(closed) = closed {
+        let age = (today - closed).num_days().max(0) as f64;
+        score += age / opts.closed_within_days.max(1) as f64;
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()
+    }
+
+    #[test]
+    fn test_build_comps_filter() {
+        let subject = json!({
+            "ListingKey": "ABC",
+            "PropertyType": "Residential",
+            "BedroomsTotal": 3,
+            "LivingArea": 2000.0
+        });
+        let filter = build_comps_filter(&subject, &CompsOptions::default(), today());
+
+        assert!(filter.starts_with("StandardStatus eq 'Closed' and CloseDate ge 2025-01-01"));
+        assert!(filter.contains("ListingKey ne 'ABC'"));
+        assert!(filter.contains("BedroomsTotal ge 2 and BedroomsTotal le 4"));
+        assert!(filter.contains("LivingArea ge 1600 and LivingArea le 2400"));
+        assert!(!filter.contains("Bathrooms"));
+        assert!(!filter.contains("Latitude"));
+    }
+
+    #[test]
+    fn test_rank_comps_orders_and_drops_outside_radius() {
+        let subject = json!({
+            "BedroomsTotal": 3,
+            "LivingArea": 2000.0,
+            "Latitude": 30.0,
+            "Longitude": -97.0
+        });
+        let candidates = vec![
+            json!({"ListingKey": "near-diff", "BedroomsTotal": 4, "LivingArea": 2300.0,
+                   "Latitude": 30.001, "Longitude": -97.0, "CloseDate": "2025-06-01"}),
+            json!({"ListingKey": "near-same", "BedroomsTotal": 3, "LivingArea": 2000.0,
+                   "Latitude": 30.001, "Longitude": -97.0, "CloseDate": "2025-06-01"}),
+            json!({"ListingKey": "far", "BedroomsTotal": 3, "LivingArea": 2000.0,
+                   "Latitude": 30.5, "Longitude": -97.0, "CloseDate": "2025-06-01"}),
+        ];
+
+        let comps = rank_comps(&subject, candidates, &CompsOptions::default(), today());
+        let keys: Vec<_> = comps.iter().map(|c| c.record["ListingKey"].as_str().unwrap()).collect();
+        assert_eq!(keys, vec!["near-same", "near-diff"]);
+        assert!(comps[0].distance_miles.unwrap() < 0.1);
+    }
+}
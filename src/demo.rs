@@ -0,0 +1,686 @@
+//! An offline client serving a bundled sample dataset.
+//!
+//! [`DemoClient`] answers the same [`ResoApi`] calls as a real server from
+//! an anonymized set of Austin-area listings (with their photos, agents, and
+//! offices) compiled into the crate, so the examples, the `reso` CLI
+//! (`--demo`), and the axum example (`RESO_DEMO=1`) can be tried before
+//! RESO credentials arrive.
+//!
+//! Queries are evaluated locally: `$filter` (comparisons, `in`, `and`/`or`/
+//! `not`, and the string functions `contains`, `startswith`, `endswith`,
+//! `tolower`, `toupper`, and `trim`), `$select`, `$orderby`, `$top`,
+//! `$skip`, `$count`, `$expand` of `Media`, `ListAgent`, and `ListOffice`,
+//! key lookups, `/$count`, and replication paging. Anything else, such as
+//! `$apply` or geospatial functions, is rejected with a 400 the way a
+//! server without that feature would.
+
+use crate::client::ResoApi;
+use crate::metadata::{parse_metadata, ResoSchema};
+use crate::query_string::ODataRequest;
+use crate::validate::{tokenize, Literal, Token};
+use async_trait::async_trait;
+use reso_client::{JsonValue, Query, ReplicationQuery, ReplicationResponse, ResoError};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Base URL reported by [`DemoClient`]; next links use it too.
+pub const DEMO_BASE_URL: &str = "demo://sample";
+
+const SAMPLE_DATA: &str = include_str!("../data/demo/sample.json");
+const SAMPLE_METADATA: &str = include_str!("../data/demo/metadata.xml");
+
+/// Largest replication page, as on most servers.
+const MAX_REPLICATION_TOP: usize = 2000;
+
+/// Navigation properties that `$expand` resolves: (name, target resource,
+/// key field on the record, matching field on the target, is a collection).
+const EXPANSIONS: &[(&str, &str, &str, &str, bool)] = &[
+    ("Media", "Media", "ListingKey", "ResourceRecordKey", true),
+    ("ListAgent", "Member", "ListAgentKey", "MemberKey", false),
+    ("ListOffice", "Office", "ListOfficeKey", "OfficeKey", false),
+];
+
+/// A client that answers from the bundled sample dataset.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::demo::DemoClient;
+/// use reso_examples::search::PropertySearch;
+/// use reso_examples::execute_query;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = DemoClient::new();
+///     let query = PropertySearch::new().city("Austin").active().max_price(600_000).build()?;
+///     let response = execute_query(&client, &query).await?;
+///
+///     for listing in response["value"].as_array().into_iter().flatten() {
+///         println!("{} {}", listing["UnparsedAddress"], listing["ListPrice"]);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DemoClient {
+    resources: HashMap<String, Vec<JsonValue>>,
+    schema: ResoSchema,
+}
+
+impl Default for DemoClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DemoClient {
+    pub fn new() -> Self {
+        let data: HashMap<String, Vec<JsonValue>> =
+            serde_json::from_str(SAMPLE_DATA).expect("bundled sample data is valid JSON");
+        let schema = parse_metadata(SAMPLE_METADATA).expect("bundled sample metadata is valid");
+        Self {
+            resources: data,
+            schema,
+        }
+    }
+
+    /// The records of a resource, in key order.
+    pub fn records(&self, resource: &str) -> &[JsonValue] {
+        self.resources.get(resource).map_or(&[], Vec::as_slice)
+    }
+
+    fn table(&self, resource: &str) -> Result<&[JsonValue], ResoError> {
+        match self.resources.get(resource) {
+            Some(records) => Ok(records),
+            None => Err(ResoError::NotFound {
+                message: format!("Resource '{}' is not in the demo dataset", resource),
+                status_code: 404,
+            }),
+        }
+    }
+
+    /// Records of `resource` matching the request's `$filter`.
+    fn matching(&self, request: &Request) -> Result<Vec<JsonValue>, ResoError> {
+        let records = self.table(&request.resource)?;
+        let filter = match request.option("$filter") {
+            Some(filter) => Some(Parser::parse(filter)?),
+            None => None,
+        };
+        Ok(records
+            .iter()
+            .filter(|record| match &filter {
+                Some(filter) => filter.eval(record) == JsonValue::Bool(true),
+                None => true,
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn respond(&self, request: &Request) -> Result<JsonValue, ResoError> {
+        if let Some(option) = ["$apply", "$search", "$compute"].iter().find(|o| request.option(o).is_some()) {
+            return Err(unsupported(option));
+        }
+        let mut records = match &request.key {
+            Some(key) => {
+                let key_field = self.key_field(&request.resource);
+                let record = self
+                    .table(&request.resource)?
+                    .iter()
+                    .find(|record| record[key_field.as_str()].as_str() == Some(key))
+                    .ok_or_else(|| ResoError::NotFound {
+                        message: format!("No {} with key '{}'", request.resource, key),
+                        status_code: 404,
+                    })?;
+                vec![record.clone()]
+            }
+            None => self.matching(request)?,
+        };
+
+        if let Some(order_by) = request.option("$orderby") {
+            sort_records(&mut records, order_by);
+        }
+        let total = records.len();
+        let skip = request.number("$skip")?.unwrap_or(0);
+        let top = request.number("$top")?.unwrap_or(usize::MAX);
+        let mut page: Vec<JsonValue> = records.into_iter().skip(skip).take(top).collect();
+        for record in &mut page {
+            self.shape(record, request);
+        }
+
+        if request.key.is_some() {
+            return Ok(page.pop().unwrap_or_default());
+        }
+        let mut response = serde_json::json!({
+            "@odata.context": format!("{}/$metadata#{}", DEMO_BASE_URL, request.resource),
+            "value": page,
+        });
+        if request.option("$count") == Some("true") {
+            response["@odata.count"] = total.into();
+        }
+        Ok(response)
+    }
+
+    /// Applies `$expand` and `$select` to a record.
+    fn shape(&self, record: &mut JsonValue, request: &Request) {
+        let mut expanded = Vec::new();
+        for item in request.option("$expand").map(list_items).unwrap_or_default() {
+            // Nested options such as `Media($select=MediaURL)` are ignored
+            let name = item.split('(').next().unwrap_or(item).trim();
+            let Some(&(_, target, local, remote, collection)) = EXPANSIONS.iter().find(|e| e.0 == name) else {
+                continue;
+            };
+            let related: Vec<JsonValue> = self
+                .records(target)
+                .iter()
+                .filter(|other| !record[local].is_null() && other[remote] == record[local])
+                .cloned()
+                .collect();
+            record[name] = match collection {
+                true => JsonValue::Array(related),
+                false => related.into_iter().next().unwrap_or_default(),
+            };
+            expanded.push(name);
+        }
+
+        let Some(select) = request.option("$select") else { return };
+        let fields = list_items(select);
+        if let JsonValue::Object(map) = record {
+            map.retain(|field, _| fields.contains(&field.as_str()) || expanded.contains(&field.as_str()));
+        }
+    }
+
+    fn replicate(&self, request: &Request) -> Result<ReplicationResponse, ResoError> {
+        let records = self.matching(request)?;
+        let offset = request.number("$skiptoken")?.unwrap_or(0);
+        let top = request.number("$top")?.unwrap_or(MAX_REPLICATION_TOP).clamp(1, MAX_REPLICATION_TOP);
+        let mut page: Vec<JsonValue> = records.iter().skip(offset).take(top).cloned().collect();
+        for record in &mut page {
+            self.shape(record, request);
+        }
+
+        let next = offset + page.len();
+        let next_link = (next < records.len()).then(|| {
+            let mut options: Vec<(String, String)> =
+                request.options.iter().filter(|(name, _)| name != "$skiptoken").cloned().collect();
+            options.push(("$skiptoken".to_string(), next.to_string()));
+            let link = ODataRequest {
+                path: format!("{}/replication", request.resource),
+                options,
+            };
+            format!("{}/{}", DEMO_BASE_URL, link.to_odata_string())
+        });
+        Ok(ReplicationResponse::new(page, next_link))
+    }
+
+    fn key_field(&self, resource: &str) -> String {
+        self.schema
+            .resource(resource)
+            .and_then(|entity_type| entity_type.key.first().cloned())
+            .unwrap_or_else(|| format!("{}Key", resource))
+    }
+}
+
+#[async_trait]
+impl ResoApi for DemoClient {
+    fn base_url(&self) -> &str {
+        DEMO_BASE_URL
+    }
+
+    async fn execute(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        self.respond(&Request::parse(&query.to_odata_string())?)
+    }
+
+    async fn execute_by_key(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        self.respond(&Request::parse(&query.to_odata_string())?)
+    }
+
+    async fn execute_count(&self, query: &Query) -> Result<u64, ResoError> {
+        let request = Request::parse(&query.to_odata_string())?;
+        Ok(self.matching(&request)?.len() as u64)
+    }
+
+    async fn fetch_metadata(&self) -> Result<String, ResoError> {
+        Ok(SAMPLE_METADATA.to_string())
+    }
+
+    async fn execute_replication(
+        &self,
+        query: &ReplicationQuery,
+    ) -> Result<ReplicationResponse, ResoError> {
+        self.replicate(&Request::parse(&query.to_odata_string())?)
+    }
+
+    async fn execute_next_link(&self, next_link: &str) -> Result<ReplicationResponse, ResoError> {
+        let path = next_link.strip_prefix(DEMO_BASE_URL).ok_or_else(|| {
+            ResoError::InvalidQuery(format!("'{}' is not a demo next link", next_link))
+        })?;
+        self.replicate(&Request::parse(path.trim_start_matches('/'))?)
+    }
+}
+
+/// A request path and its query options.
+struct Request {
+    resource: String,
+    /// Key of a lookup such as `Property('DEMO1001')`
+    key: Option<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Request {
+    fn parse(odata: &str) -> Result<Self, ResoError> {
+        let request = ODataRequest::parse(odata)?;
+        let resource = request.resource().to_string();
+        let key = request
+            .path
+            .strip_prefix(resource.as_str())
+            .and_then(|rest| rest.strip_prefix("('"))
+            .and_then(|rest| rest.split_once("')"))
+            .map(|(key, _)| key.replace("''", "'"));
+        Ok(Self {
+            resource,
+            key,
+            options: request.options,
+        })
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn number(&self, name: &str) -> Result<Option<usize>, ResoError> {
+        self.option(name)
+            .map(|value| {
+                value.parse().map_err(|_| ResoError::ODataError {
+                    message: format!("Invalid {} value '{}'", name, value),
+                    status_code: 400,
+                })
+            })
+            .transpose()
+    }
+}
+
+fn list_items(value: &str) -> Vec<&str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty()).collect()
+}
+
+fn unsupported(feature: &str) -> ResoError {
+    ResoError::ODataError {
+        message: format!("The demo dataset does not support {}", feature),
+        status_code: 400,
+    }
+}
+
+/// Sorts by a `$orderby` list such as `ListPrice desc,ListingKey`.
+fn sort_records(records: &mut [JsonValue], order_by: &str) {
+    let keys: Vec<(&str, bool)> = list_items(order_by)
+        .into_iter()
+        .map(|item| {
+            let mut words = item.split_whitespace();
+            let field = words.next().unwrap_or_default();
+            (field, words.next() == Some("desc"))
+        })
+        .collect();
+    records.sort_by(|a, b| {
+        keys.iter()
+            .map(|(field, descending)| {
+                let ordering = sort_order(&a[*field], &b[*field]);
+                if *descending { ordering.reverse() } else { ordering }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Orders values for sorting, with nulls first as in OData.
+fn sort_order(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (a.is_null(), b.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => compare(a, b).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// Compares two values of the same kind; numbers of any representation
+/// compare by value.
+fn compare(a: &JsonValue, b: &JsonValue) -> Option<Ordering> {
+    match (a, b) {
+        (JsonValue::Number(x), JsonValue::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (JsonValue::String(x), JsonValue::String(y)) => Some(x.cmp(y)),
+        (JsonValue::Bool(x), JsonValue::Bool(y)) => Some(x.cmp(y)),
+        (JsonValue::Null, JsonValue::Null) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+/// A parsed `$filter` expression.
+#[derive(Debug)]
+pub(crate) enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(String, Box<Expr>, Box<Expr>),
+    In(Box<Expr>, Vec<Expr>),
+    Call(String, Vec<Expr>),
+    Field(String),
+    Value(JsonValue),
+}
+
+impl Expr {
+    pub(crate) fn eval(&self, record: &JsonValue) -> JsonValue {
+        match self {
+            Expr::Or(a, b) => (a.eval(record) == JsonValue::Bool(true) || b.eval(record) == JsonValue::Bool(true)).into(),
+            Expr::And(a, b) => (a.eval(record) == JsonValue::Bool(true) && b.eval(record) == JsonValue::Bool(true)).into(),
+            Expr::Not(a) => (a.eval(record) != JsonValue::Bool(true)).into(),
+            Expr::Compare(op, a, b) => {
+                let (a, b) = (a.eval(record), b.eval(record));
+                let ordering = compare(&a, &b);
+                match op.as_str() {
+                    "eq" => ordering == Some(Ordering::Equal),
+                    "ne" => ordering != Some(Ordering::Equal),
+                    // Comparisons with null are false, as in OData
+                    _ if a.is_null() || b.is_null() => false,
+                    "gt" => ordering == Some(Ordering::Greater),
+                    "ge" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    "lt" => ordering == Some(Ordering::Less),
+                    _ => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                }
+                .into()
+            }
+            Expr::In(a, values) => {
+                let a = a.eval(record);
+                values
+                    .iter()
+                    .any(|value| compare(&a, &value.eval(record)) == Some(Ordering::Equal))
+                    .into()
+            }
+            Expr::Call(name, args) => {
+                let args: Vec<JsonValue> = args.iter().map(|arg| arg.eval(record)).collect();
+                let text = |i: usize| args.get(i).and_then(JsonValue::as_str);
+                match (name.as_str(), text(0), text(1)) {
+                    ("contains", Some(a), Some(b)) => a.contains(b).into(),
+                    ("startswith", Some(a), Some(b)) => a.starts_with(b).into(),
+                    ("endswith", Some(a), Some(b)) => a.ends_with(b).into(),
+                    ("tolower", Some(a), _) => a.to_lowercase().into(),
+                    ("toupper", Some(a), _) => a.to_uppercase().into(),
+                    ("trim", Some(a), _) => a.trim().into(),
+                    ("contains" | "startswith" | "endswith", _, _) => false.into(),
+                    _ => JsonValue::Null,
+                }
+            }
+            Expr::Field(path) => path
+                .split('/')
+                .try_fold(record, |value, segment| value.get(segment))
+                .cloned()
+                .unwrap_or_default(),
+            Expr::Value(value) => value.clone(),
+        }
+    }
+}
+
+/// Recursive-descent parser over the tokens of a `$filter`.
+pub(crate) struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    pub(crate) fn parse(filter: &str) -> Result<Expr, ResoError> {
+        let mut parser = Parser {
+            tokens: tokenize(filter),
+            pos: 0,
+        };
+        let expr = parser.or().and_then(|expr| match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(syntax_error(format!("unexpected {:?}", token))),
+        });
+        expr.map_err(|e| match e {
+            ResoError::ODataError { message, status_code } => ResoError::ODataError {
+                message: format!("Invalid $filter '{}': {}", filter, message),
+                status_code,
+            },
+            e => e,
+        })
+    }
+
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Path(w)) if w == word)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ResoError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(syntax_error(format!("expected {:?}, found {:?}", expected, other))),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ResoError> {
+        let mut expr = self.and()?;
+        while self.peek_word("or") {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ResoError> {
+        let mut expr = self.unary()?;
+        while self.peek_word("and") {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ResoError> {
+        if self.peek_word("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ResoError> {
+        let left = self.primary()?;
+        for op in ["eq", "ne", "gt", "ge", "lt", "le"] {
+            if self.peek_word(op) {
+                self.pos += 1;
+                return Ok(Expr::Compare(op.to_string(), Box::new(left), Box::new(self.primary()?)));
+            }
+        }
+        if self.peek_word("in") {
+            self.pos += 1;
+            return Ok(Expr::In(Box::new(left), self.arguments()?));
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ResoError> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            Some(Token::Literal(literal)) => literal_value(literal).map(Expr::Value),
+            Some(Token::Path(path)) if self.tokens.get(self.pos) == Some(&Token::Open) => {
+                if !matches!(path.as_str(), "contains" | "startswith" | "endswith" | "tolower" | "toupper" | "trim") {
+                    return Err(unsupported(&format!("'{}'", path)));
+                }
+                Ok(Expr::Call(path, self.arguments()?))
+            }
+            Some(Token::Path(path)) => Ok(Expr::Field(path)),
+            other => Err(syntax_error(format!("unexpected {:?}", other))),
+        }
+    }
+
+    /// A parenthesized, comma-separated list of expressions.
+    fn arguments(&mut self) -> Result<Vec<Expr>, ResoError> {
+        self.expect(Token::Open)?;
+        let mut items = Vec::new();
+        if self.tokens.get(self.pos) == Some(&Token::Close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.or()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::Close) => return Ok(items),
+                other => return Err(syntax_error(format!("expected ',' or ')', found {:?}", other))),
+            }
+        }
+    }
+}
+
+fn literal_value(literal: Literal) -> Result<JsonValue, ResoError> {
+    Ok(match literal {
+        Literal::String(s) | Literal::Date(s) | Literal::DateTimeOffset(s) => JsonValue::String(s),
+        Literal::Typed(text) => {
+            // Enumeration literals compare by member name: `Ns.Enum'Value'`
+            let (prefix, quoted) = text.split_once('\'').unwrap_or((&text, ""));
+            if prefix.starts_with("geography") || prefix.starts_with("geometry") {
+                return Err(unsupported("geospatial literals"));
+            }
+            JsonValue::String(quoted.trim_end_matches('\'').to_string())
+        }
+        Literal::Integer(n) | Literal::Decimal(n) => n
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(JsonValue::Number)
+            .ok_or_else(|| unsupported(&format!("the number {}", n)))?,
+        Literal::Boolean(b) => JsonValue::Bool(b == "true"),
+        Literal::Null => JsonValue::Null,
+    })
+}
+
+fn syntax_error(problem: String) -> ResoError {
+    ResoError::ODataError {
+        message: problem,
+        status_code: 400,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::RecordSink;
+    use crate::search::PropertySearch;
+    use crate::sync::{replicate_all, Checkpoint};
+    use futures::TryStreamExt;
+
+    #[derive(Default)]
+    struct VecSink(Vec<JsonValue>);
+
+    #[async_trait]
+    impl RecordSink for VecSink {
+        async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+            self.0.extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filters_sorts_and_pages() {
+        let client = DemoClient::new();
+        let query = PropertySearch::new()
+            .city("Austin")
+            .min_price(300_000)
+            .remarks_contain("kitchen")
+            .order_by("ListPrice", "desc")
+            .top(100)
+            .build()
+            .unwrap();
+        let response = crate::execute_query(&client, &query).await.unwrap();
+        let listings = response["value"].as_array().unwrap();
+
+        let expected = client
+            .records("Property")
+            .iter()
+            .filter(|r| r["City"] == "Austin" && r["ListPrice"].as_f64().unwrap() >= 300_000.0)
+            .filter(|r| r["PublicRemarks"].as_str().unwrap().contains("kitchen"))
+            .count();
+        assert!(expected > 0);
+        assert_eq!(listings.len(), expected);
+        let prices: Vec<f64> = listings.iter().map(|r| r["ListPrice"].as_f64().unwrap()).collect();
+        assert!(prices.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        let count = crate::count_records(&client, "Property", Some("City in ('Austin','Georgetown') and not (BedroomsTotal ge 3)"))
+            .await
+            .unwrap();
+        let in_cities = |r: &&JsonValue| matches!(r["City"].as_str(), Some("Austin" | "Georgetown"));
+        let expected = client
+            .records("Property")
+            .iter()
+            .filter(in_cities)
+            .filter(|r| r["BedroomsTotal"].as_u64().unwrap_or(0) < 3)
+            .count();
+        assert_eq!(count, expected as u64);
+
+        let paged: Vec<JsonValue> = crate::paginate_query(&client, "Office", None, &["OfficeKey"], 2)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(paged.len(), client.records("Office").len());
+        assert_eq!(paged[0].as_object().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_lookup_expand_and_unsupported_options() {
+        let client = DemoClient::new();
+        let query = crate::build_query_with_expand("Property", None, &["ListingKey"], &["Media", "ListAgent"], Some(1))
+            .unwrap();
+        let response = crate::execute_query(&client, &query).await.unwrap();
+        let listing = &response["value"][0];
+        let key = listing["ListingKey"].as_str().unwrap();
+        assert!(listing["Media"].as_array().unwrap().iter().all(|m| m["ResourceRecordKey"] == key));
+        assert!(listing["ListAgent"]["MemberFullName"].is_string());
+
+        let by_key = crate::build_query_by_key("Property", key, Some(&["ListingKey", "City"])).unwrap();
+        let record = client.execute_by_key(&by_key).await.unwrap();
+        assert_eq!(record["ListingKey"], key);
+        let missing = crate::build_query_by_key("Property", "NOPE", None).unwrap();
+        assert!(matches!(client.execute_by_key(&missing).await, Err(ResoError::NotFound { .. })));
+
+        // $apply is refused, so count_by falls back to paging
+        let groups = crate::aggregate::count_by(&client, "Property", None, "City").await.unwrap();
+        let total: u64 = groups.iter().map(|g| g.count).sum();
+        assert_eq!(total, client.records("Property").len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_replication_follows_next_links() {
+        let client = DemoClient::new();
+        let mut sink = VecSink::default();
+        let mut checkpoint = Checkpoint::new("Property");
+        let mut page_size = crate::sync::AdaptivePageSize::new(25, 25);
+        let never = crate::cancel::CancellationToken::new();
+
+        let stats = crate::sync::replicate_all_paged(
+            &client,
+            "Property",
+            Some("StandardStatus eq 'Active'"),
+            &mut sink,
+            &mut checkpoint,
+            &never,
+            &mut page_size,
+        )
+        .await
+        .unwrap();
+        let active = client.records("Property").iter().filter(|r| r["StandardStatus"] == "Active").count();
+        assert_eq!(stats.records, active as u64);
+        assert_eq!(sink.0.len(), active);
+        assert!(stats.batches > 1);
+
+        let mut members = VecSink::default();
+        replicate_all(&client, "Member", None, &mut members, &mut Checkpoint::new("Member")).await.unwrap();
+        assert_eq!(members.0.len(), client.records("Member").len());
+    }
+}
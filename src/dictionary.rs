@@ -0,0 +1,710 @@
+//! RESO Data Dictionary standard field names.
+//!
+//! Servers certified against the RESO Data Dictionary expose standard fields
+//! under fixed names, alongside local fields added by the MLS or vendor
+//! (often prefixed, e.g. `X_` or `ABOR_`). These lists let tools tell the two
+//! apart. They cover the Data Dictionary 2.0 fields of the core resources.
+
+/// Resources covered by this module.
+pub const STANDARD_RESOURCES: &[&str] = &["Property", "Member", "Office", "Media", "OpenHouse"];
+
+/// Standard fields of the `Property` resource.
+pub const PROPERTY_FIELDS: &[&str] = &[
+    "AboveGradeFinishedArea",
+    "AboveGradeFinishedAreaSource",
+    "AboveGradeFinishedAreaUnits",
+    "AccessCode",
+    "AccessibilityFeatures",
+    "AdditionalParcelsDescription",
+    "AdditionalParcelsYN",
+    "AnchorsCoTenants",
+    "Appliances",
+    "ArchitecturalStyle",
+    "AssociationAmenities",
+    "AssociationFee",
+    "AssociationFee2",
+    "AssociationFee2Frequency",
+    "AssociationFeeFrequency",
+    "AssociationFeeIncludes",
+    "AssociationName",
+    "AssociationName2",
+    "AssociationPhone",
+    "AssociationPhone2",
+    "AssociationYN",
+    "AttachedGarageYN",
+    "AvailabilityDate",
+    "Basement",
+    "BasementYN",
+    "BathroomsFull",
+    "BathroomsHalf",
+    "BathroomsOneQuarter",
+    "BathroomsPartial",
+    "BathroomsThreeQuarter",
+    "BathroomsTotalInteger",
+    "BedroomsPossible",
+    "BedroomsTotal",
+    "BelowGradeFinishedArea",
+    "BelowGradeFinishedAreaSource",
+    "BelowGradeFinishedAreaUnits",
+    "BodyType",
+    "BuilderModel",
+    "BuilderName",
+    "BuildingAreaSource",
+    "BuildingAreaTotal",
+    "BuildingAreaUnits",
+    "BuildingFeatures",
+    "BuildingName",
+    "BusinessName",
+    "BusinessType",
+    "BuyerAgencyCompensation",
+    "BuyerAgencyCompensationType",
+    "BuyerAgentAOR",
+    "BuyerAgentDesignation",
+    "BuyerAgentDirectPhone",
+    "BuyerAgentEmail",
+    "BuyerAgentFirstName",
+    "BuyerAgentFullName",
+    "BuyerAgentKey",
+    "BuyerAgentLastName",
+    "BuyerAgentMlsId",
+    "BuyerAgentStateLicense",
+    "BuyerFinancing",
+    "BuyerOfficeAOR",
+    "BuyerOfficeKey",
+    "BuyerOfficeMlsId",
+    "BuyerOfficeName",
+    "BuyerOfficePhone",
+    "CableTvExpense",
+    "CancellationDate",
+    "CapRate",
+    "CarportSpaces",
+    "CarportYN",
+    "CarrierRoute",
+    "City",
+    "CityRegion",
+    "CloseDate",
+    "ClosePrice",
+    "CoBuyerAgentFullName",
+    "CoBuyerAgentKey",
+    "CoBuyerAgentMlsId",
+    "CoBuyerOfficeKey",
+    "CoBuyerOfficeMlsId",
+    "CoBuyerOfficeName",
+    "CoListAgentFullName",
+    "CoListAgentKey",
+    "CoListAgentMlsId",
+    "CoListOfficeKey",
+    "CoListOfficeMlsId",
+    "CoListOfficeName",
+    "CommonInterest",
+    "CommonWalls",
+    "CommunityFeatures",
+    "Concessions",
+    "ConcessionsAmount",
+    "ConcessionsComments",
+    "ConstructionMaterials",
+    "ContinentRegion",
+    "Contingency",
+    "ContingentDate",
+    "ContractStatusChangeDate",
+    "Cooling",
+    "CoolingYN",
+    "CopyrightNotice",
+    "Country",
+    "CountryRegion",
+    "CountyOrParish",
+    "CoveredSpaces",
+    "CropsIncludedYN",
+    "CrossStreet",
+    "CultivatedArea",
+    "CumulativeDaysOnMarket",
+    "CurrentFinancing",
+    "CurrentUse",
+    "DOH1",
+    "DOH2",
+    "DOH3",
+    "DaysOnMarket",
+    "DevelopmentStatus",
+    "DirectionFaces",
+    "Directions",
+    "Disclaimer",
+    "Disclosures",
+    "DistanceToBusComments",
+    "DistanceToElectricComments",
+    "DistanceToSchoolsComments",
+    "DistanceToSewerComments",
+    "DistanceToWaterComments",
+    "DocumentsAvailable",
+    "DocumentsChangeTimestamp",
+    "DocumentsCount",
+    "DoorFeatures",
+    "DualVariableCompensationYN",
+    "Electric",
+    "ElectricExpense",
+    "ElectricOnPropertyYN",
+    "ElementarySchool",
+    "ElementarySchoolDistrict",
+    "Elevation",
+    "ElevationUnits",
+    "EntryLevel",
+    "EntryLocation",
+    "Exclusions",
+    "ExistingLeaseType",
+    "ExpirationDate",
+    "ExteriorFeatures",
+    "FarmCreditServiceInclYN",
+    "FarmLandAreaSource",
+    "FarmLandAreaUnits",
+    "Fencing",
+    "FinancialDataSource",
+    "FireplaceFeatures",
+    "FireplaceYN",
+    "FireplacesTotal",
+    "Flooring",
+    "FoundationArea",
+    "FoundationDetails",
+    "FrontageLength",
+    "FrontageType",
+    "FuelExpense",
+    "Furnished",
+    "FurnitureReplacementExpense",
+    "GarageSpaces",
+    "GarageYN",
+    "GardenerExpense",
+    "GrazingPermitsBlmYN",
+    "GrazingPermitsForestServiceYN",
+    "GrazingPermitsPrivateYN",
+    "GreenBuildingVerificationType",
+    "GreenEnergyEfficient",
+    "GreenEnergyGeneration",
+    "GreenIndoorAirQuality",
+    "GreenLocation",
+    "GreenSustainability",
+    "GreenWaterConservation",
+    "GrossIncome",
+    "GrossScheduledIncome",
+    "HabitableResidenceYN",
+    "Heating",
+    "HeatingYN",
+    "HighSchool",
+    "HighSchoolDistrict",
+    "HomeWarrantyYN",
+    "HorseAmenities",
+    "HorseYN",
+    "HoursDaysOfOperation",
+    "HoursDaysOfOperationDescription",
+    "Inclusions",
+    "IncomeIncludes",
+    "InsuranceExpense",
+    "InteriorFeatures",
+    "InternetAddressDisplayYN",
+    "InternetAutomatedValuationDisplayYN",
+    "InternetConsumerCommentYN",
+    "InternetEntireListingDisplayYN",
+    "IrrigationSource",
+    "IrrigationWaterRightsAcres",
+    "IrrigationWaterRightsYN",
+    "LaborInformation",
+    "LandLeaseAmount",
+    "LandLeaseAmountFrequency",
+    "LandLeaseExpirationDate",
+    "LandLeaseYN",
+    "Latitude",
+    "LaundryFeatures",
+    "LeasableArea",
+    "LeasableAreaUnits",
+    "LeaseAmount",
+    "LeaseAmountFrequency",
+    "LeaseAssignableYN",
+    "LeaseConsideredYN",
+    "LeaseExpiration",
+    "LeaseRenewalCompensation",
+    "LeaseRenewalOptionYN",
+    "LeaseTerm",
+    "Levels",
+    "License1",
+    "License2",
+    "License3",
+    "LicensesExpense",
+    "ListAOR",
+    "ListAgentAOR",
+    "ListAgentDesignation",
+    "ListAgentDirectPhone",
+    "ListAgentEmail",
+    "ListAgentFirstName",
+    "ListAgentFullName",
+    "ListAgentKey",
+    "ListAgentLastName",
+    "ListAgentMlsId",
+    "ListAgentStateLicense",
+    "ListOfficeAOR",
+    "ListOfficeKey",
+    "ListOfficeMlsId",
+    "ListOfficeName",
+    "ListOfficePhone",
+    "ListPrice",
+    "ListPriceLow",
+    "ListingAgreement",
+    "ListingContractDate",
+    "ListingId",
+    "ListingKey",
+    "ListingService",
+    "ListingTerms",
+    "ListingURL",
+    "LivingArea",
+    "LivingAreaSource",
+    "LivingAreaUnits",
+    "LockBoxLocation",
+    "LockBoxSerialNumber",
+    "LockBoxType",
+    "Longitude",
+    "LotDimensionsSource",
+    "LotFeatures",
+    "LotSizeAcres",
+    "LotSizeArea",
+    "LotSizeDimensions",
+    "LotSizeSource",
+    "LotSizeSquareFeet",
+    "LotSizeUnits",
+    "MLSAreaMajor",
+    "MLSAreaMinor",
+    "MainLevelBathrooms",
+    "MainLevelBedrooms",
+    "MaintenanceExpense",
+    "MajorChangeTimestamp",
+    "MajorChangeType",
+    "Make",
+    "ManagerExpense",
+    "MapCoordinate",
+    "MapCoordinateSource",
+    "MapURL",
+    "MiddleOrJuniorSchool",
+    "MiddleOrJuniorSchoolDistrict",
+    "MlsStatus",
+    "MobileDimUnits",
+    "MobileHomeRemainsYN",
+    "MobileLength",
+    "MobileWidth",
+    "Model",
+    "ModificationTimestamp",
+    "NetOperatingIncome",
+    "NewConstructionYN",
+    "NewTaxesExpense",
+    "NumberOfBuildings",
+    "NumberOfFullTimeEmployees",
+    "NumberOfLots",
+    "NumberOfPads",
+    "NumberOfPartTimeEmployees",
+    "NumberOfSeparateElectricMeters",
+    "NumberOfSeparateGasMeters",
+    "NumberOfSeparateWaterMeters",
+    "NumberOfUnitsInCommunity",
+    "NumberOfUnitsLeased",
+    "NumberOfUnitsMoMo",
+    "NumberOfUnitsTotal",
+    "NumberOfUnitsVacant",
+    "OccupantName",
+    "OccupantPhone",
+    "OccupantType",
+    "OffMarketDate",
+    "OffMarketTimestamp",
+    "OnMarketDate",
+    "OnMarketTimestamp",
+    "OpenParkingSpaces",
+    "OpenParkingYN",
+    "OperatingExpense",
+    "OperatingExpenseIncludes",
+    "OriginalEntryTimestamp",
+    "OriginalListPrice",
+    "OriginatingSystemID",
+    "OriginatingSystemKey",
+    "OriginatingSystemName",
+    "OtherEquipment",
+    "OtherExpense",
+    "OtherParking",
+    "OtherStructures",
+    "OwnerName",
+    "OwnerPays",
+    "OwnerPhone",
+    "Ownership",
+    "OwnershipType",
+    "ParcelNumber",
+    "ParkManagerName",
+    "ParkManagerPhone",
+    "ParkName",
+    "ParkingFeatures",
+    "ParkingTotal",
+    "PastureArea",
+    "PatioAndPorchFeatures",
+    "PendingTimestamp",
+    "PestControlExpense",
+    "PetsAllowed",
+    "PhotosChangeTimestamp",
+    "PhotosCount",
+    "PoolExpense",
+    "PoolFeatures",
+    "PoolPrivateYN",
+    "Possession",
+    "PossibleUse",
+    "PostalCity",
+    "PostalCode",
+    "PostalCodePlus4",
+    "PowerProductionType",
+    "PreviousListPrice",
+    "PriceChangeTimestamp",
+    "PrivateOfficeRemarks",
+    "PrivateRemarks",
+    "ProfessionalManagementExpense",
+    "PropertyAttachedYN",
+    "PropertyCondition",
+    "PropertySubType",
+    "PropertyType",
+    "PublicRemarks",
+    "PublicSurveyRange",
+    "PublicSurveySection",
+    "PublicSurveyTownship",
+    "PurchaseContractDate",
+    "RVParkingDimensions",
+    "RangeArea",
+    "RentControlYN",
+    "RentIncludes",
+    "RoadFrontageType",
+    "RoadResponsibility",
+    "RoadSurfaceType",
+    "Roof",
+    "RoomType",
+    "RoomsTotal",
+    "SeatingCapacity",
+    "SecurityFeatures",
+    "SeniorCommunityYN",
+    "SerialU",
+    "SerialX",
+    "SerialXX",
+    "Sewer",
+    "ShowingAdvanceNotice",
+    "ShowingAttendedYN",
+    "ShowingContactName",
+    "ShowingContactPhone",
+    "ShowingContactPhoneExt",
+    "ShowingContactType",
+    "ShowingDays",
+    "ShowingEndTime",
+    "ShowingInstructions",
+    "ShowingRequirements",
+    "ShowingStartTime",
+    "SignOnPropertyYN",
+    "Skirt",
+    "SourceSystemID",
+    "SourceSystemKey",
+    "SourceSystemName",
+    "SpaFeatures",
+    "SpaYN",
+    "SpecialLicenses",
+    "SpecialListingConditions",
+    "StandardStatus",
+    "StartShowingDate",
+    "StateOrProvince",
+    "StateRegion",
+    "StatusChangeTimestamp",
+    "Stories",
+    "StoriesTotal",
+    "StreetAdditionalInfo",
+    "StreetDirPrefix",
+    "StreetDirSuffix",
+    "StreetName",
+    "StreetNumber",
+    "StreetNumberNumeric",
+    "StreetSuffix",
+    "StreetSuffixModifier",
+    "StructureType",
+    "SubAgencyCompensation",
+    "SubAgencyCompensationType",
+    "SubdivisionName",
+    "SuppliesExpense",
+    "SyndicateTo",
+    "SyndicationRemarks",
+    "TaxAnnualAmount",
+    "TaxAssessedValue",
+    "TaxBlock",
+    "TaxBookNumber",
+    "TaxLegalDescription",
+    "TaxLot",
+    "TaxMapNumber",
+    "TaxOtherAnnualAssessmentAmount",
+    "TaxParcelLetter",
+    "TaxStatusCurrent",
+    "TaxTract",
+    "TaxYear",
+    "TenantPays",
+    "Topography",
+    "TotalActualRent",
+    "Township",
+    "TransactionBrokerCompensation",
+    "TransactionBrokerCompensationType",
+    "TrashExpense",
+    "UnitNumber",
+    "UnitTypeType",
+    "UnitsFurnished",
+    "UniversalPropertyId",
+    "UniversalPropertySubId",
+    "UnparsedAddress",
+    "Utilities",
+    "VacancyAllowance",
+    "VacancyAllowanceRate",
+    "Vegetation",
+    "VideosChangeTimestamp",
+    "VideosCount",
+    "View",
+    "ViewYN",
+    "VirtualTourURLBranded",
+    "VirtualTourURLUnbranded",
+    "WalkScore",
+    "WaterBodyName",
+    "WaterSewerExpense",
+    "WaterSource",
+    "WaterfrontFeatures",
+    "WaterfrontYN",
+    "WindowFeatures",
+    "WithdrawnDate",
+    "WoodedArea",
+    "WorkmansCompensationExpense",
+    "YearBuilt",
+    "YearBuiltDetails",
+    "YearBuiltEffective",
+    "YearBuiltSource",
+    "YearEstablished",
+    "YearsCurrentOwner",
+    "Zoning",
+    "ZoningDescription",
+];
+
+/// Standard fields of the `Member` resource.
+pub const MEMBER_FIELDS: &[&str] = &[
+    "JobTitle",
+    "LastLoginTimestamp",
+    "MemberAOR",
+    "MemberAORMlsId",
+    "MemberAORkey",
+    "MemberAddress1",
+    "MemberAddress2",
+    "MemberAssociationComments",
+    "MemberCarrierRoute",
+    "MemberCity",
+    "MemberCountry",
+    "MemberCountyOrParish",
+    "MemberDesignation",
+    "MemberDirectPhone",
+    "MemberEmail",
+    "MemberFax",
+    "MemberFirstName",
+    "MemberFullName",
+    "MemberHomePhone",
+    "MemberIsAssistantTo",
+    "MemberKey",
+    "MemberLanguages",
+    "MemberLastName",
+    "MemberLoginId",
+    "MemberMiddleName",
+    "MemberMlsAccessYN",
+    "MemberMlsId",
+    "MemberMlsSecurityClass",
+    "MemberMobilePhone",
+    "MemberNamePrefix",
+    "MemberNameSuffix",
+    "MemberNationalAssociationId",
+    "MemberNickname",
+    "MemberOfficePhone",
+    "MemberOfficePhoneExt",
+    "MemberOtherPhoneType",
+    "MemberPager",
+    "MemberPassword",
+    "MemberPhoneTTYTDD",
+    "MemberPostalCode",
+    "MemberPostalCodePlus4",
+    "MemberPreferredPhone",
+    "MemberPreferredPhoneExt",
+    "MemberStateLicense",
+    "MemberStateLicenseState",
+    "MemberStateOrProvince",
+    "MemberStatus",
+    "MemberTollFreePhone",
+    "MemberType",
+    "MemberVoiceMail",
+    "MemberVoiceMailExt",
+    "ModificationTimestamp",
+    "OfficeKey",
+    "OfficeMlsId",
+    "OfficeName",
+    "OriginalEntryTimestamp",
+    "OriginatingSystemID",
+    "OriginatingSystemMemberKey",
+    "OriginatingSystemName",
+    "SocialMediaType",
+    "SourceSystemID",
+    "SourceSystemMemberKey",
+    "SourceSystemName",
+    "SyndicateTo",
+];
+
+/// Standard fields of the `Office` resource.
+pub const OFFICE_FIELDS: &[&str] = &[
+    "FranchiseAffiliation",
+    "IDXOfficeParticipationYN",
+    "MainOfficeKey",
+    "MainOfficeMlsId",
+    "ModificationTimestamp",
+    "OfficeAOR",
+    "OfficeAORMlsId",
+    "OfficeAORkey",
+    "OfficeAddress1",
+    "OfficeAddress2",
+    "OfficeAssociationComments",
+    "OfficeBranchType",
+    "OfficeBrokerKey",
+    "OfficeBrokerMlsId",
+    "OfficeCity",
+    "OfficeCorporateLicense",
+    "OfficeCountyOrParish",
+    "OfficeEmail",
+    "OfficeFax",
+    "OfficeKey",
+    "OfficeManagerKey",
+    "OfficeManagerMlsId",
+    "OfficeMlsId",
+    "OfficeName",
+    "OfficeNationalAssociationId",
+    "OfficePhone",
+    "OfficePhoneExt",
+    "OfficePostalCode",
+    "OfficePostalCodePlus4",
+    "OfficeStateOrProvince",
+    "OfficeStatus",
+    "OfficeType",
+    "OriginalEntryTimestamp",
+    "OriginatingSystemID",
+    "OriginatingSystemName",
+    "OriginatingSystemOfficeKey",
+    "SocialMediaType",
+    "SourceSystemID",
+    "SourceSystemName",
+    "SourceSystemOfficeKey",
+    "SyndicateAgentOption",
+    "SyndicateTo",
+];
+
+/// Standard fields of the `Media` resource.
+pub const MEDIA_FIELDS: &[&str] = &[
+    "ChangedByMemberID",
+    "ChangedByMemberKey",
+    "ClassName",
+    "ImageHeight",
+    "ImageOf",
+    "ImageSizeDescription",
+    "ImageWidth",
+    "LongDescription",
+    "MediaCategory",
+    "MediaHTML",
+    "MediaKey",
+    "MediaModificationTimestamp",
+    "MediaObjectID",
+    "MediaStatus",
+    "MediaType",
+    "MediaURL",
+    "ModificationTimestamp",
+    "Order",
+    "OriginatingSystemID",
+    "OriginatingSystemMediaKey",
+    "OriginatingSystemName",
+    "Permission",
+    "PreferredPhotoYN",
+    "ResourceName",
+    "ResourceRecordID",
+    "ResourceRecordKey",
+    "ShortDescription",
+    "SourceSystemID",
+    "SourceSystemMediaKey",
+    "SourceSystemName",
+];
+
+/// Standard fields of the `OpenHouse` resource.
+pub const OPEN_HOUSE_FIELDS: &[&str] = &[
+    "AppointmentRequiredYN",
+    "ListingId",
+    "ListingKey",
+    "ModificationTimestamp",
+    "OpenHouseAttendedBy",
+    "OpenHouseDate",
+    "OpenHouseEndTime",
+    "OpenHouseId",
+    "OpenHouseKey",
+    "OpenHouseRemarks",
+    "OpenHouseStartTime",
+    "OpenHouseStatus",
+    "OpenHouseType",
+    "OriginalEntryTimestamp",
+    "OriginatingSystemID",
+    "OriginatingSystemKey",
+    "OriginatingSystemName",
+    "Refreshments",
+    "ShowingAgentFirstName",
+    "ShowingAgentKey",
+    "ShowingAgentLastName",
+    "ShowingAgentMlsID",
+    "SourceSystemID",
+    "SourceSystemKey",
+    "SourceSystemName",
+];
+
+/// Returns the standard field list for a resource, if it is one of the
+/// resources covered by this module.
+pub fn standard_fields(resource: &str) -> Option<&'static [&'static str]> {
+    match resource {
+        "Property" => Some(PROPERTY_FIELDS),
+        "Member" => Some(MEMBER_FIELDS),
+        "Office" => Some(OFFICE_FIELDS),
+        "Media" => Some(MEDIA_FIELDS),
+        "OpenHouse" => Some(OPEN_HOUSE_FIELDS),
+        _ => None,
+    }
+}
+
+/// Returns true if `field` is a Data Dictionary standard field of `resource`.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::dictionary::is_standard_field;
+///
+/// assert!(is_standard_field("Property", "ListPrice"));
+/// assert!(!is_standard_field("Property", "X_VendorFlag"));
+/// ```
+pub fn is_standard_field(resource: &str, field: &str) -> bool {
+    standard_fields(resource).is_some_and(|fields| fields.binary_search(&field).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_lists_are_sorted() {
+        // `is_standard_field` relies on binary search
+        for fields in [
+            PROPERTY_FIELDS,
+            MEMBER_FIELDS,
+            OFFICE_FIELDS,
+            MEDIA_FIELDS,
+            OPEN_HOUSE_FIELDS,
+        ] {
+            let unsorted = fields.windows(2).find(|w| w[0] >= w[1]);
+            assert!(unsorted.is_none(), "out of order: {:?}", unsorted);
+        }
+    }
+
+    #[test]
+    fn test_is_standard_field() {
+        assert!(is_standard_field("Member", "MemberKey"));
+        assert!(!is_standard_field("Member", "ListPrice"));
+        assert!(!is_standard_field("CustomResource", "ListPrice"));
+    }
+}
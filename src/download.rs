@@ -0,0 +1,264 @@
+//! Throttled downloads of listing photos and other media files.
+//!
+//! A photo sync fetches tens of thousands of files from the vendor's CDN.
+//! Left unbounded it saturates an office uplink and can trip the CDN's abuse
+//! detection, so a [`Downloader`] caps both the number of files in flight and
+//! the combined transfer rate. Clones share the same limits, letting every
+//! task of a sync draw from one budget.
+
+use crate::client::TIMED_OUT;
+use reso_client::ResoError;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+
+/// Limits applied by a [`Downloader`].
+#[derive(Debug, Clone)]
+pub struct DownloadLimits {
+    /// Combined transfer rate of all downloads, unlimited if `None`
+    pub bytes_per_second: Option<u64>,
+    /// Downloads allowed in flight at once
+    pub max_concurrent: usize,
+}
+
+impl Default for DownloadLimits {
+    fn default() -> Self {
+        Self {
+            bytes_per_second: None,
+            max_concurrent: 4,
+        }
+    }
+}
+
+/// Downloads files over HTTP within shared [`DownloadLimits`].
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::download::{DownloadLimits, Downloader};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // 2 MB/s across at most 4 concurrent downloads
+///     let downloader = Downloader::new(DownloadLimits {
+///         bytes_per_second: Some(2_000_000),
+///         max_concurrent: 4,
+///     });
+///     let bytes = downloader
+///         .download("https://cdn.example.com/photos/1.jpg", "photos/1.jpg")
+///         .await?;
+///     println!("Downloaded {} bytes", bytes);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    http: reqwest::Client,
+    permits: Arc<Semaphore>,
+    rate: Option<Arc<TokenBucket>>,
+}
+
+impl Downloader {
+    pub fn new(limits: DownloadLimits) -> Self {
+        Self::with_http_client(reqwest::Client::new(), limits)
+    }
+
+    /// Uses an existing HTTP client, e.g. one with a custom User-Agent.
+    pub fn with_http_client(http: reqwest::Client, limits: DownloadLimits) -> Self {
+        Self {
+            http,
+            permits: Arc::new(Semaphore::new(limits.max_concurrent.max(1))),
+            rate: limits
+                .bytes_per_second
+                .filter(|&rate| rate > 0)
+                .map(|rate| Arc::new(TokenBucket::new(rate))),
+        }
+    }
+
+    /// Downloads `url` to `dest`, returning the number of bytes written.
+    ///
+    /// Waits for a free download slot first, then paces the transfer to the
+    /// shared rate. The file is written to `<dest>.part` and renamed once
+    /// complete, so an interrupted download never leaves a truncated file at
+    /// `dest`; the partial file is removed when the download fails. A server
+    /// that stops sending for [`STALL_TIMEOUT`] fails the download.
+    pub async fn download(&self, url: &str, dest: impl AsRef<Path>) -> Result<u64, ResoError> {
+        let dest = dest.as_ref();
+        let Some(name) = dest.file_name() else {
+            return Err(ResoError::Config(format!("Cannot download to {}: not a file path", dest.display())));
+        };
+        let mut part_name = name.to_os_string();
+        part_name.push(".part");
+        let tmp = dest.with_file_name(part_name);
+
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|e| ResoError::Network(e.to_string()))?;
+
+        let response = stalled(url, self.http.get(url).send()).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(crate::client::error_from_status(status.as_u16(), &body));
+        }
+
+        let written = match self.write_body(url, response, &tmp).await {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&tmp).await;
+                return Err(e);
+            }
+        };
+        tokio::fs::rename(&tmp, dest)
+            .await
+            .map_err(|e| io_error("replace", dest, e))?;
+        Ok(written)
+    }
+
+    async fn write_body(&self, url: &str, mut response: reqwest::Response, tmp: &Path) -> Result<u64, ResoError> {
+        let mut file = tokio::fs::File::create(tmp)
+            .await
+            .map_err(|e| io_error("create", tmp, e))?;
+        let mut written = 0;
+        while let Some(chunk) = stalled(url, response.chunk()).await? {
+            if let Some(rate) = &self.rate {
+                rate.consume(chunk.len() as u64).await;
+            }
+            file.write_all(&chunk).await.map_err(|e| io_error("write", tmp, e))?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await.map_err(|e| io_error("write", tmp, e))?;
+        Ok(written)
+    }
+}
+
+/// Longest wait for a server to answer, or for the next chunk of a body
+/// being downloaded. Bounds stalls rather than whole transfers, which may
+/// be slowed on purpose by the rate limit.
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs one step of a download, failing it after [`STALL_TIMEOUT`].
+async fn stalled<T>(
+    url: &str,
+    step: impl std::future::Future<Output = Result<T, reqwest::Error>>,
+) -> Result<T, ResoError> {
+    match tokio::time::timeout(STALL_TIMEOUT, step).await {
+        Ok(result) => result.map_err(|e| ResoError::Network(e.to_string())),
+        Err(_) => Err(ResoError::Network(format!(
+            "{}: no data from {} for {}s",
+            TIMED_OUT,
+            url,
+            STALL_TIMEOUT.as_secs()
+        ))),
+    }
+}
+
+/// Paces transfers to a byte rate, allowing bursts of up to one second's
+/// worth of bytes.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: u64) -> Self {
+        let rate = bytes_per_second as f64;
+        Self {
+            rate,
+            state: Mutex::new((rate, Instant::now())),
+        }
+    }
+
+    /// Takes `bytes` from the bucket, sleeping off any shortfall. The
+    /// balance may go negative, so chunks larger than the bucket still pass
+    /// and concurrent callers queue behind each other's debt.
+    async fn consume(&self, bytes: u64) {
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let (available, last) = &mut *state;
+            let now = Instant::now();
+            *available = (*available + now.duration_since(*last).as_secs_f64() * self.rate).min(self.rate);
+            *last = now;
+            *available -= bytes as f64;
+            if *available < 0.0 {
+                Duration::from_secs_f64(-*available / self.rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> ResoError {
+    ResoError::Config(format!("Cannot {} {}: {}", action, path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_download_is_paced_to_the_rate_limit() {
+        let server = MockServer::start().await;
+        Mock::given(path("/photo.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![7u8; 3000]))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("reso-download-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("photo.jpg");
+
+        // The first 2000 bytes fit the initial burst; the rest waits ~0.5s
+        let downloader = Downloader::new(DownloadLimits {
+            bytes_per_second: Some(2000),
+            max_concurrent: 1,
+        });
+        let started = Instant::now();
+        let bytes = downloader
+            .download(&format!("{}/photo.jpg", server.uri()), &dest)
+            .await
+            .unwrap();
+
+        assert_eq!(bytes, 3000);
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        assert_eq!(std::fs::read(&dest).unwrap().len(), 3000);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_temporary_file_keeps_the_extension() {
+        let server = MockServer::start().await;
+        Mock::given(path("/photo.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![1u8; 10]))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("reso-download-part-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // photo.jpg and photo.png once shared photo.part
+        std::fs::write(dir.join("photo.part"), b"another download").unwrap();
+
+        let downloader = Downloader::new(DownloadLimits::default());
+        let bytes = downloader
+            .download(&format!("{}/photo.png", server.uri()), dir.join("photo.png"))
+            .await
+            .unwrap();
+
+        assert_eq!(bytes, 10);
+        assert_eq!(std::fs::read(dir.join("photo.part")).unwrap(), b"another download");
+        assert!(!dir.join("photo.png.part").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
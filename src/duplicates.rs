@@ -0,0 +1,284 @@
+//! Detecting the same listing in several MLS datasets.
+//!
+//! MLS coverage overlaps: a house on the border of two markets is often
+//! listed in both, under different listing keys. [`find_duplicates`] flags
+//! listings from different datasets that share a parcel number or a
+//! normalized street address and were on the market at the same time, and
+//! returns a cross-reference between their keys.
+
+use crate::compact::LatestRecords;
+use chrono::NaiveDate;
+use reso_client::{JsonValue, ResoError};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// The records of one MLS feed.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    /// Name identifying the feed, e.g. its dataset id
+    pub name: String,
+    /// Field holding each record's key, e.g. "ListingKey"
+    pub key_field: String,
+    pub records: Vec<JsonValue>,
+}
+
+impl Dataset {
+    /// Reads a JSONL store, keeping the newest version of each record.
+    pub fn from_jsonl(name: &str, path: impl AsRef<Path>, key_field: &str) -> Result<Self, ResoError> {
+        let mut latest = LatestRecords::default();
+        latest.read_file(path.as_ref(), key_field)?;
+        Ok(Self {
+            name: name.to_string(),
+            key_field: key_field.to_string(),
+            records: latest.records().cloned().collect(),
+        })
+    }
+}
+
+/// A pair of listings from different datasets that are probably the same property.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateMatch {
+    pub dataset_a: String,
+    pub key_a: String,
+    pub dataset_b: String,
+    pub key_b: String,
+    /// Both listings carry the same `ParcelNumber`
+    pub same_parcel: bool,
+    /// Both listings have the same normalized address
+    pub same_address: bool,
+}
+
+/// A record's position: (dataset, record) indices.
+type RecordIndex = (usize, usize);
+
+/// Flags probable duplicate listings across datasets.
+///
+/// Two listings match when they come from different datasets, share a
+/// `ParcelNumber` or a normalized address (street number, name, suffix,
+/// and unit, plus postal code or city), and their market periods overlap.
+/// A listing's period runs from `ListingContractDate` (or `OnMarketDate`)
+/// to `CloseDate`, `OffMarketDate`, or `ExpirationDate`, and is open-ended
+/// when none is known. Listings without a start date are not ruled out.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::duplicates::{find_duplicates, Dataset};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let datasets = [
+///     Dataset::from_jsonl("actris", "actris.jsonl", "ListingKey")?,
+///     Dataset::from_jsonl("har", "har.jsonl", "ListingKey")?,
+/// ];
+/// for m in find_duplicates(&datasets) {
+///     println!("{}:{} = {}:{}", m.dataset_a, m.key_a, m.dataset_b, m.key_b);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_duplicates(datasets: &[Dataset]) -> Vec<DuplicateMatch> {
+    // Candidate pairs by shared parcel or address
+    let mut by_parcel: HashMap<String, Vec<RecordIndex>> = HashMap::new();
+    let mut by_address: HashMap<String, Vec<RecordIndex>> = HashMap::new();
+    for (d, dataset) in datasets.iter().enumerate() {
+        for (r, record) in dataset.records.iter().enumerate() {
+            if let Some(parcel) = parcel_key(record) {
+                by_parcel.entry(parcel).or_default().push((d, r));
+            }
+            if let Some(address) = address_key(record) {
+                by_address.entry(address).or_default().push((d, r));
+            }
+        }
+    }
+
+    // Matched pairs with whether they share a parcel and an address
+    let mut pairs: BTreeMap<(RecordIndex, RecordIndex), (bool, bool)> = BTreeMap::new();
+    for (groups, parcel) in [(&by_parcel, true), (&by_address, false)] {
+        for members in groups.values() {
+            for (i, &a) in members.iter().enumerate() {
+                for &b in &members[i + 1..] {
+                    if a.0 != b.0 {
+                        let matched = pairs.entry((a.min(b), a.max(b))).or_default();
+                        matched.0 |= parcel;
+                        matched.1 |= !parcel;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut matches = Vec::new();
+    for ((a, b), (same_parcel, same_address)) in pairs {
+        let (record_a, record_b) = (&datasets[a.0].records[a.1], &datasets[b.0].records[b.1]);
+        if periods_overlap(record_a, record_b) {
+            matches.push(DuplicateMatch {
+                dataset_a: datasets[a.0].name.clone(),
+                key_a: record_key(record_a, &datasets[a.0].key_field),
+                dataset_b: datasets[b.0].name.clone(),
+                key_b: record_key(record_b, &datasets[b.0].key_field),
+                same_parcel,
+                same_address,
+            });
+        }
+    }
+    matches
+}
+
+fn record_key(record: &JsonValue, key_field: &str) -> String {
+    match &record[key_field] {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The parcel number without punctuation, since feeds format APNs differently.
+fn parcel_key(record: &JsonValue) -> Option<String> {
+    let parcel: String = record["ParcelNumber"]
+        .as_str()?
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    (!parcel.is_empty()).then_some(parcel)
+}
+
+/// Street suffixes and directions in their USPS abbreviations.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("street", "st"),
+    ("avenue", "ave"),
+    ("road", "rd"),
+    ("drive", "dr"),
+    ("boulevard", "blvd"),
+    ("lane", "ln"),
+    ("court", "ct"),
+    ("circle", "cir"),
+    ("place", "pl"),
+    ("parkway", "pkwy"),
+    ("highway", "hwy"),
+    ("terrace", "ter"),
+    ("trail", "trl"),
+    ("cove", "cv"),
+    ("north", "n"),
+    ("south", "s"),
+    ("east", "e"),
+    ("west", "w"),
+    ("apartment", "unit"),
+    ("apt", "unit"),
+    ("suite", "unit"),
+    ("ste", "unit"),
+];
+
+/// A normalized address plus postal code (or city), built from the
+/// structured street fields or, failing that, `UnparsedAddress`.
+fn address_key(record: &JsonValue) -> Option<String> {
+    let text = |field: &str| record[field].as_str().unwrap_or_default();
+    let street = if !text("StreetNumber").is_empty() && !text("StreetName").is_empty() {
+        [
+            "StreetNumber",
+            "StreetDirPrefix",
+            "StreetName",
+            "StreetSuffix",
+            "StreetDirSuffix",
+            "UnitNumber",
+        ]
+        .map(text)
+        .join(" ")
+    } else {
+        // Unparsed addresses often end with ", City, ST 78701"
+        text("UnparsedAddress").split(',').next().unwrap_or_default().to_string()
+    };
+    let street = normalize(&street);
+    if street.is_empty() {
+        return None;
+    }
+
+    let postal: String = text("PostalCode").chars().take(5).collect();
+    let area = if postal.is_empty() { normalize(text("City")) } else { postal };
+    (!area.is_empty()).then(|| format!("{}|{}", street, area))
+}
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(long, _)| *long == word)
+                .map_or(word, |(_, short)| *short)
+        })
+        .filter(|word| *word != "unit")
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn periods_overlap(a: &JsonValue, b: &JsonValue) -> bool {
+    let (Some(start_a), Some(start_b)) = (start_date(a), start_date(b)) else {
+        return true;
+    };
+    let ends_before = |end: Option<NaiveDate>, start: NaiveDate| end.is_some_and(|end| end < start);
+    !ends_before(end_date(a), start_b) && !ends_before(end_date(b), start_a)
+}
+
+fn start_date(record: &JsonValue) -> Option<NaiveDate> {
+    ["ListingContractDate", "OnMarketDate"].iter().find_map(|field| date(record, field))
+}
+
+fn end_date(record: &JsonValue) -> Option<NaiveDate> {
+    ["CloseDate", "OffMarketDate", "ExpirationDate"]
+        .iter()
+        .find_map(|field| date(record, field))
+}
+
+fn date(record: &JsonValue, field: &str) -> Option<NaiveDate> {
+    let value = record[field].as_str()?;
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dataset(name: &str, records: Vec<JsonValue>) -> Dataset {
+        Dataset {
+            name: name.to_string(),
+            key_field: "ListingKey".to_string(),
+            records,
+        }
+    }
+
+    #[test]
+    fn test_find_duplicates_by_address_and_parcel() {
+        let a = dataset(
+            "actris",
+            vec![
+                json!({"ListingKey": "A1", "StreetNumber": "100", "StreetName": "Main", "StreetSuffix": "Street",
+                       "PostalCode": "78701-1234", "ListingContractDate": "2025-01-10"}),
+                json!({"ListingKey": "A2", "ParcelNumber": "01-234-56", "ListingContractDate": "2025-02-01",
+                       "CloseDate": "2025-03-01"}),
+                json!({"ListingKey": "A3", "UnparsedAddress": "9 Oak Lane, Austin, TX 78702", "PostalCode": "78702",
+                       "ListingContractDate": "2024-01-01", "CloseDate": "2024-02-01"}),
+            ],
+        );
+        let b = dataset(
+            "har",
+            vec![
+                json!({"ListingKey": "B1", "UnparsedAddress": "100 MAIN ST, Austin, TX", "PostalCode": "78701",
+                       "ListingContractDate": "2025-01-15"}),
+                json!({"ListingKey": "B2", "ParcelNumber": "0123456", "OnMarketDate": "2025-02-20"}),
+                // Same address as A3, but listed after A3 closed
+                json!({"ListingKey": "B3", "StreetNumber": "9", "StreetName": "Oak", "StreetSuffix": "Ln",
+                       "PostalCode": "78702", "ListingContractDate": "2025-05-01"}),
+            ],
+        );
+
+        let mut matches = find_duplicates(&[a, b]);
+        matches.sort_by(|x, y| x.key_a.cmp(&y.key_a));
+        let pairs: Vec<(&str, &str, bool, bool)> = matches
+            .iter()
+            .map(|m| (m.key_a.as_str(), m.key_b.as_str(), m.same_parcel, m.same_address))
+            .collect();
+        assert_eq!(pairs, [("A1", "B1", false, true), ("A2", "B2", true, false)]);
+    }
+}
@@ -0,0 +1,439 @@
+//! Pluggable per-record enrichment for sync pipelines.
+//!
+//! An [`Enricher`] computes extra fields for a record — a school district,
+//! walk score, flood zone, and so on — typically by calling an external
+//! service. An [`EnrichmentPipeline`] runs one or more enrichers over each
+//! replicated batch with bounded concurrency, caching lookups so records that
+//! share a cache key (e.g. the same address or parcel) are only looked up once.
+//! An [`EnrichingSink`] plugs a pipeline into replication, enriching each
+//! batch before it is written.
+
+use crate::export::{OutputFile, RecordSink, StoredCounts};
+use crate::geocode::{record_address, Geocoder};
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use reso_client::{JsonValue, ResoError};
+use serde_json::Map;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Fields produced by an enricher, merged into the record.
+pub type Enrichment = Map<String, JsonValue>;
+
+/// A per-record enrichment step.
+///
+/// # Example
+///
+/// ```
+/// use async_trait::async_trait;
+/// use reso_examples::enrich::{Enricher, Enrichment};
+/// use reso_client::{JsonValue, ResoError};
+///
+/// struct FloodZone;
+///
+/// #[async_trait]
+/// impl Enricher for FloodZone {
+///     fn name(&self) -> &str {
+///         "flood_zone"
+///     }
+///
+///     fn cache_key(&self, record: &JsonValue) -> Option<String> {
+///         record["ParcelNumber"].as_str().map(|s| s.to_string())
+///     }
+///
+///     async fn enrich(&self, _record: &JsonValue) -> Result<Enrichment, ResoError> {
+///         let mut fields = Enrichment::new();
+///         fields.insert("X_FloodZone".to_string(), "AE".into());
+///         Ok(fields)
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Enricher: Send + Sync {
+    /// A short name identifying the enricher in caches and statistics.
+    fn name(&self) -> &str;
+
+    /// The key under which results for this record are cached.
+    ///
+    /// Records for which this returns `None` are skipped.
+    fn cache_key(&self, record: &JsonValue) -> Option<String>;
+
+    /// Computes the fields to add to a record.
+    async fn enrich(&self, record: &JsonValue) -> Result<Enrichment, ResoError>;
+}
+
+/// Counts reported by [`EnrichmentPipeline::run`].
+#[derive(Debug, Clone, Default)]
+pub struct EnrichStats {
+    /// Enrichments computed by calling an enricher
+    pub enriched: usize,
+    /// Enrichments served from the cache
+    pub cache_hits: usize,
+    /// Records an enricher skipped because they had no cache key
+    pub skipped: usize,
+    /// Error messages from failed lookups, prefixed with the enricher name
+    pub errors: Vec<String>,
+}
+
+/// Runs enrichers over batches of records with bounded concurrency and caching.
+///
+/// Enrichment fields are only written to fields that are missing or null, so
+/// values supplied by the feed always win. The cache lives as long as the
+/// pipeline, so reusing one pipeline across batches of a sync avoids repeat
+/// lookups. Failed lookups are reported in [`EnrichStats::errors`] rather
+/// than aborting the batch.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::enrich::{EnrichmentPipeline, CoordinateEnricher};
+/// use reso_examples::geocode::NominatimGeocoder;
+///
+/// # async fn example(records: &mut [serde_json::Value]) {
+/// let pipeline = EnrichmentPipeline::new()
+///     .with_enricher(CoordinateEnricher::new(NominatimGeocoder::new("my-app/1.0")))
+///     .with_concurrency(1);
+///
+/// let stats = pipeline.run(records).await;
+/// println!("{} enriched, {} cache hits", stats.enriched, stats.cache_hits);
+/// # }
+/// ```
+pub struct EnrichmentPipeline {
+    enrichers: Vec<Arc<dyn Enricher>>,
+    concurrency: usize,
+    cache: Mutex<HashMap<(String, String), Enrichment>>,
+}
+
+impl Default for EnrichmentPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnrichmentPipeline {
+    /// Creates an empty pipeline with a concurrency limit of 4.
+    pub fn new() -> Self {
+        Self {
+            enrichers: Vec::new(),
+            concurrency: 4,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds an enricher. Enrichers run in the order they were added.
+    pub fn with_enricher(mut self, enricher: impl Enricher + 'static) -> Self {
+        self.enrichers.push(Arc::new(enricher));
+        self
+    }
+
+    /// Sets the maximum number of records enriched concurrently.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Number of cached enrichment results.
+    pub fn cache_len(&self) -> usize {
+        self.cache.lock().unwrap().len()
+    }
+
+    /// Enriches a batch of records in place.
+    pub async fn run(&self, records: &mut [JsonValue]) -> EnrichStats {
+        let mut stats = EnrichStats::default();
+
+        for enricher in &self.enrichers {
+            // Built up front rather than in a stream closure, which would
+            // keep the future from being Send inside async sinks
+            let lookups: Vec<_> = records
+                .iter()
+                .enumerate()
+                .map(|(index, record)| async move { (index, self.enrich_one(enricher.as_ref(), record).await) })
+                .collect();
+            let results: Vec<(usize, Outcome)> =
+                stream::iter(lookups).buffer_unordered(self.concurrency).collect().await;
+
+            for (index, outcome) in results {
+                match outcome {
+                    Outcome::Computed(fields) => {
+                        stats.enriched += 1;
+                        merge_missing(&mut records[index], fields);
+                    }
+                    Outcome::Cached(fields) => {
+                        stats.cache_hits += 1;
+                        merge_missing(&mut records[index], fields);
+                    }
+                    Outcome::Skipped => stats.skipped += 1,
+                    Outcome::Failed(e) => stats.errors.push(format!("{}: {}", enricher.name(), e)),
+                }
+            }
+        }
+
+        stats
+    }
+
+    async fn enrich_one(&self, enricher: &dyn Enricher, record: &JsonValue) -> Outcome {
+        let Some(key) = enricher.cache_key(record) else {
+            return Outcome::Skipped;
+        };
+        let cache_key = (enricher.name().to_string(), key);
+
+        if let Some(fields) = self.cache.lock().unwrap().get(&cache_key) {
+            return Outcome::Cached(fields.clone());
+        }
+
+        match enricher.enrich(record).await {
+            Ok(fields) => {
+                self.cache.lock().unwrap().insert(cache_key, fields.clone());
+                Outcome::Computed(fields)
+            }
+            Err(e) => Outcome::Failed(e),
+        }
+    }
+}
+
+/// Wraps a sink, running an [`EnrichmentPipeline`] over every batch before
+/// writing it.
+///
+/// As with [`EnrichmentPipeline::run`], failed lookups do not fail the
+/// batch; they are added up in [`stats`](Self::stats), and the sink lists
+/// how many there were on stderr when it is finalized.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::enrich::{CoordinateEnricher, EnrichingSink, EnrichmentPipeline};
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::geocode::NominatimGeocoder;
+/// use reso_examples::sync::{replicate_all, Checkpoint};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let pipeline = EnrichmentPipeline::new()
+///         .with_enricher(CoordinateEnricher::new(NominatimGeocoder::new("my-app/1.0")));
+///     let mut sink = EnrichingSink::new(JsonlSink::append("property.jsonl")?, pipeline);
+///     let mut checkpoint = Checkpoint::load("property.checkpoint.json", "Property")?;
+///
+///     replicate_all(&client, "Property", None, &mut sink, &mut checkpoint).await?;
+///     println!("{} records enriched", sink.stats().enriched);
+///     Ok(())
+/// }
+/// ```
+pub struct EnrichingSink<S> {
+    inner: S,
+    pipeline: EnrichmentPipeline,
+    stats: EnrichStats,
+}
+
+impl<S: RecordSink> EnrichingSink<S> {
+    pub fn new(inner: S, pipeline: EnrichmentPipeline) -> Self {
+        Self {
+            inner,
+            pipeline,
+            stats: EnrichStats::default(),
+        }
+    }
+
+    /// Counts over every batch written so far.
+    pub fn stats(&self) -> &EnrichStats {
+        &self.stats
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink> RecordSink for EnrichingSink<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let mut batch = records.to_vec();
+        let stats = self.pipeline.run(&mut batch).await;
+        self.stats.enriched += stats.enriched;
+        self.stats.cache_hits += stats.cache_hits;
+        self.stats.skipped += stats.skipped;
+        self.stats.errors.extend(stats.errors);
+        self.inner.write_batch(&batch).await
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.inner.flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        if let Some(first) = self.stats.errors.first() {
+            eprintln!(
+                "{} enrichment lookups failed; the first: {}",
+                self.stats.errors.len(),
+                first
+            );
+        }
+        self.inner.finalize().await
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        self.inner.evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        self.inner.count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.inner.output_files()
+    }
+}
+
+enum Outcome {
+    Computed(Enrichment),
+    Cached(Enrichment),
+    Skipped,
+    Failed(ResoError),
+}
+
+fn merge_missing(record: &mut JsonValue, fields: Enrichment) {
+    if let Some(object) = record.as_object_mut() {
+        for (field, value) in fields {
+            let slot = object.entry(field).or_insert(JsonValue::Null);
+            if slot.is_null() {
+                *slot = value;
+            }
+        }
+    }
+}
+
+/// Enricher that fills `Latitude`/`Longitude` using a [`Geocoder`].
+///
+/// Records that already have coordinates are skipped; others are cached by
+/// their address.
+pub struct CoordinateEnricher<G> {
+    geocoder: G,
+}
+
+impl<G: Geocoder> CoordinateEnricher<G> {
+    pub fn new(geocoder: G) -> Self {
+        Self { geocoder }
+    }
+}
+
+#[async_trait]
+impl<G: Geocoder> Enricher for CoordinateEnricher<G> {
+    fn name(&self) -> &str {
+        "coordinates"
+    }
+
+    fn cache_key(&self, record: &JsonValue) -> Option<String> {
+        if crate::geo::record_coordinates(record).is_some() {
+            return None;
+        }
+        record_address(record)
+    }
+
+    async fn enrich(&self, record: &JsonValue) -> Result<Enrichment, ResoError> {
+        let mut fields = Enrichment::new();
+        if let Some(address) = record_address(record) {
+            if let Some(coords) = self.geocoder.geocode(&address).await? {
+                fields.insert("Latitude".to_string(), coords.latitude.into());
+                fields.insert("Longitude".to_string(), coords.longitude.into());
+            }
+        }
+        Ok(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct SchoolDistrict {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Enricher for SchoolDistrict {
+        fn name(&self) -> &str {
+            "school_district"
+        }
+
+        fn cache_key(&self, record: &JsonValue) -> Option<String> {
+            record["PostalCode"].as_str().map(|s| s.to_string())
+        }
+
+        async fn enrich(&self, record: &JsonValue) -> Result<Enrichment, ResoError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if record["PostalCode"] == "00000" {
+                return Err(ResoError::Network("lookup failed".into()));
+            }
+            let mut fields = Enrichment::new();
+            fields.insert("SchoolDistrict".to_string(), "Austin ISD".into());
+            Ok(fields)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_caches_and_keeps_existing_values() {
+        let pipeline = EnrichmentPipeline::new().with_enricher(SchoolDistrict {
+            calls: AtomicUsize::new(0),
+        });
+
+        let mut records = vec![
+            json!({"PostalCode": "78701"}),
+            json!({"PostalCode": "78701", "SchoolDistrict": "Eanes ISD"}),
+            json!({"City": "Austin"}),
+            json!({"PostalCode": "00000"}),
+        ];
+
+        let stats = pipeline.with_concurrency(1).run(&mut records).await;
+
+        assert_eq!(stats.enriched, 1);
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.errors.len(), 1);
+        assert!(stats.errors[0].starts_with("school_district:"));
+        assert_eq!(records[0]["SchoolDistrict"], "Austin ISD");
+        assert_eq!(records[1]["SchoolDistrict"], "Eanes ISD");
+        assert!(records[2].get("SchoolDistrict").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replication_through_enriching_sink() {
+        use crate::demo::DemoClient;
+        use crate::sync::{replicate_all, Checkpoint};
+
+        #[derive(Default)]
+        struct VecSink(Vec<JsonValue>);
+
+        #[async_trait]
+        impl RecordSink for VecSink {
+            async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+                self.0.extend_from_slice(records);
+                Ok(())
+            }
+        }
+
+        let client = DemoClient::new();
+        let pipeline = EnrichmentPipeline::new().with_enricher(SchoolDistrict {
+            calls: AtomicUsize::new(0),
+        });
+        let mut sink = EnrichingSink::new(VecSink::default(), pipeline);
+        replicate_all(&client, "Property", None, &mut sink, &mut Checkpoint::new("Property"))
+            .await
+            .unwrap();
+
+        let records = &sink.inner.0;
+        assert_eq!(records.len(), client.records("Property").len());
+        assert!(records
+            .iter()
+            .filter(|record| record["PostalCode"].is_string())
+            .all(|record| record["SchoolDistrict"] == "Austin ISD"));
+
+        // One lookup per postal code, however many batches it spans
+        let stats = sink.stats();
+        let postal_codes: HashSet<&str> = records.iter().filter_map(|r| r["PostalCode"].as_str()).collect();
+        assert_eq!(stats.enriched, postal_codes.len());
+        assert_eq!(stats.enriched + stats.cache_hits + stats.skipped, records.len());
+        assert!(stats.errors.is_empty());
+    }
+}
@@ -0,0 +1,316 @@
+//! Classifying failed requests by cause, with hints for fixing them.
+//!
+//! `ResoError` follows HTTP status codes, so a 400 for a misspelled filter
+//! field and a 400 for an unsupported `$expand` look the same. An
+//! [`ExampleError`] sorts errors into the causes users act on differently
+//! (rejected credentials, exhausted quotas, features the server lacks,
+//! malformed filters, and network trouble), keeps the server's OData error
+//! payload, and suggests a fix through [`ExampleError::remediation`].
+
+use crate::capabilities::remediation_hint;
+use crate::retry::split_retry_after;
+use reso_client::{JsonValue, ResoError};
+use std::fmt;
+
+/// Query options and endpoints named in "not supported" messages, in the
+/// order they are looked for.
+const FEATURES: &[&str] = &[
+    "$expand",
+    "$apply",
+    "$count",
+    "$orderby",
+    "$select",
+    "$search",
+    "$skip",
+    "$top",
+    "geo.distance",
+    "geo.intersects",
+    "replication",
+];
+
+/// Phrases servers use when a query uses something they do not implement.
+const UNSUPPORTED_PHRASES: &[&str] = &["not supported", "unsupported", "not implemented", "not allowed"];
+
+/// Phrases servers use for quota exhaustion sent as 401 or 403 rather
+/// than 429.
+const QUOTA_PHRASES: &[&str] = &["quota", "limit exceeded", "too many requests"];
+
+/// A failed request, classified by cause.
+///
+/// Every variant keeps the original `ResoError`, available as
+/// [`reso_error`](Self::reso_error) and as the error's `source()`.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::error::ExampleError;
+/// use reso_examples::{build_query_with_expand, create_client, execute_query};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_query_with_expand("Property", None, &["ListingKey"], &["Media"], Some(5))?;
+///
+///     match execute_query(&client, &query).await.map_err(ExampleError::from) {
+///         Ok(response) => println!("{}", response),
+///         Err(ExampleError::Unsupported { feature, .. }) => println!("The server doesn't support {}", feature),
+///         Err(e) => println!("{}\nHint: {}", e, e.remediation()),
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub enum ExampleError {
+    /// The token was missing, expired, or lacks access (401 or 403)
+    Auth(ResoError),
+    /// A rate limit or request quota was reached
+    QuotaExhausted(ResoError),
+    /// The query used a feature the server does not implement, such as
+    /// `$expand` or the replication endpoint
+    Unsupported { feature: String, source: ResoError },
+    /// The server could not parse or apply the `$filter`
+    MalformedFilter(ResoError),
+    /// The server could not be reached or did not answer in time
+    Network(ResoError),
+    /// Any other failure: configuration, server errors, unexpected responses
+    Other(ResoError),
+}
+
+/// The `error` object of an OData error response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ODataErrorPayload {
+    /// Service-defined error code, e.g. "BadRequest"
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl ExampleError {
+    /// The error being classified.
+    pub fn reso_error(&self) -> &ResoError {
+        match self {
+            ExampleError::Auth(e)
+            | ExampleError::QuotaExhausted(e)
+            | ExampleError::MalformedFilter(e)
+            | ExampleError::Network(e)
+            | ExampleError::Other(e)
+            | ExampleError::Unsupported { source: e, .. } => e,
+        }
+    }
+
+    /// The server's OData error code and message, when the response had
+    /// an OData error body.
+    ///
+    /// Clients keep only the message of a body without a `code`, so for
+    /// OData and server errors that message is the payload, with no code.
+    pub fn odata(&self) -> Option<ODataErrorPayload> {
+        match self.reso_error() {
+            ResoError::Unauthorized { message, .. }
+            | ResoError::Forbidden { message, .. }
+            | ResoError::NotFound { message, .. }
+            | ResoError::RateLimited { message, .. } => parse_payload(message),
+            ResoError::ServerError { message, .. } | ResoError::ODataError { message, .. } => {
+                parse_payload(message).or_else(|| {
+                    let message = without_retry_after(message);
+                    (!message.is_empty()).then(|| ODataErrorPayload {
+                        code: None,
+                        message: message.to_string(),
+                    })
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The HTTP status of the failed response, if there was one.
+    pub fn status_code(&self) -> Option<u16> {
+        match self.reso_error() {
+            ResoError::Unauthorized { status_code, .. }
+            | ResoError::Forbidden { status_code, .. }
+            | ResoError::NotFound { status_code, .. }
+            | ResoError::RateLimited { status_code, .. }
+            | ResoError::ServerError { status_code, .. }
+            | ResoError::ODataError { status_code, .. } => Some(*status_code),
+            _ => None,
+        }
+    }
+
+    /// Suggests how to fix the failure.
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            ExampleError::QuotaExhausted(_) => {
+                "The request quota is used up; wait for it to reset, lower request rates (see `rate_limit`), or fetch fewer pages"
+            }
+            ExampleError::Unsupported { feature, .. } => match feature.as_str() {
+                "$expand" => "The server does not support $expand; fetch related records with separate queries",
+                "$apply" => "The server does not support $apply; aggregate pages client-side instead",
+                "replication" => "The server has no replication endpoint; page through standard queries instead",
+                _ => "The server does not support part of this query; check `reso doctor` for supported features",
+            },
+            ExampleError::MalformedFilter(_) => {
+                "Check the $filter syntax: quote strings in single quotes, and check field names against `reso fields`"
+            }
+            other => remediation_hint(other.reso_error()),
+        }
+    }
+
+    fn category(&self) -> &'static str {
+        match self {
+            ExampleError::Auth(_) => "Authentication failed",
+            ExampleError::QuotaExhausted(_) => "Quota exhausted",
+            ExampleError::Unsupported { .. } => "Unsupported feature",
+            ExampleError::MalformedFilter(_) => "Malformed filter",
+            ExampleError::Network(_) => "Network error",
+            ExampleError::Other(_) => "Request failed",
+        }
+    }
+}
+
+impl From<ResoError> for ExampleError {
+    fn from(error: ResoError) -> Self {
+        match &error {
+            ResoError::Unauthorized { message, .. } | ResoError::Forbidden { message, .. } => {
+                if mentions(message, QUOTA_PHRASES) {
+                    ExampleError::QuotaExhausted(error)
+                } else {
+                    ExampleError::Auth(error)
+                }
+            }
+            ResoError::RateLimited { .. } => ExampleError::QuotaExhausted(error),
+            ResoError::Network(_) => ExampleError::Network(error),
+            ResoError::ServerError { message, .. } | ResoError::ODataError { message, .. } if is_unsupported(&error) => {
+                let feature = feature_named(message);
+                ExampleError::Unsupported { feature, source: error }
+            }
+            ResoError::ServerError { .. } => ExampleError::Other(error),
+            ResoError::ODataError { message, .. } => {
+                if mentions(message, &["filter", "syntax", "parse"]) {
+                    ExampleError::MalformedFilter(error)
+                } else {
+                    ExampleError::Other(error)
+                }
+            }
+            ResoError::InvalidQuery(message) if mentions(message, &["filter"]) => ExampleError::MalformedFilter(error),
+            _ => ExampleError::Other(error),
+        }
+    }
+}
+
+impl fmt::Display for ExampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExampleError::Unsupported { feature, source } => {
+                write!(f, "{} ({}): {}", self.category(), feature, source)
+            }
+            other => write!(f, "{}: {}", other.category(), other.reso_error()),
+        }
+    }
+}
+
+impl std::error::Error for ExampleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.reso_error())
+    }
+}
+
+/// Returns true if an error says the server does not implement part of the
+/// query, as classified by [`ExampleError::Unsupported`].
+pub fn is_unsupported(error: &ResoError) -> bool {
+    match error {
+        ResoError::ServerError { message, status_code } => *status_code == 501 || mentions(message, UNSUPPORTED_PHRASES),
+        ResoError::ODataError { message, .. } => mentions(message, UNSUPPORTED_PHRASES),
+        _ => false,
+    }
+}
+
+/// Returns true if the server turned the query down, as a bad request or
+/// an unsupported feature, rather than failing to answer it. Such a query
+/// may succeed in another form, e.g. without the option the server rejected.
+pub fn is_rejected_query(error: &ResoError) -> bool {
+    matches!(error, ResoError::ODataError { .. }) || is_unsupported(error)
+}
+
+fn mentions(message: &str, phrases: &[&str]) -> bool {
+    let message = message.to_ascii_lowercase();
+    phrases.iter().any(|phrase| message.contains(phrase))
+}
+
+/// The feature a "not supported" message names, or "unknown".
+fn feature_named(message: &str) -> String {
+    let lower = message.to_ascii_lowercase();
+    FEATURES
+        .iter()
+        .find(|feature| lower.contains(*feature))
+        .map_or_else(|| "unknown".to_string(), |feature| feature.to_string())
+}
+
+/// Recovers the OData payload from an error message: either the
+/// "message (code: X)" form clients produce from an OData body, or a raw
+/// body that is itself an OData error.
+fn parse_payload(message: &str) -> Option<ODataErrorPayload> {
+    let message = without_retry_after(message);
+    if let Ok(body) = serde_json::from_str::<JsonValue>(message) {
+        let error = &body["error"];
+        return error["message"].as_str().map(|text| ODataErrorPayload {
+            code: error["code"].as_str().filter(|code| !code.is_empty()).map(str::to_string),
+            message: text.to_string(),
+        });
+    }
+    let (text, code) = message.strip_suffix(')')?.rsplit_once(" (code: ")?;
+    Some(ODataErrorPayload {
+        code: Some(code.to_string()),
+        message: text.to_string(),
+    })
+}
+
+/// An error message without the `Retry-After` note `HttpClient` adds.
+fn without_retry_after(message: &str) -> &str {
+    split_retry_after(message).map_or(message, |(text, _)| text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::error_from_status;
+
+    #[test]
+    fn test_classifies_errors_and_keeps_odata_payload() {
+        let expand = error_from_status(
+            400,
+            r#"{"error": {"code": "NotSupported", "message": "Query option $expand is not supported"}}"#,
+        );
+        let error = ExampleError::from(expand);
+        assert!(matches!(&error, ExampleError::Unsupported { feature, .. } if feature == "$expand"));
+        assert_eq!(
+            error.odata(),
+            Some(ODataErrorPayload {
+                code: Some("NotSupported".into()),
+                message: "Query option $expand is not supported".into(),
+            })
+        );
+        assert!(error.remediation().contains("separate queries"));
+        assert_eq!(error.status_code(), Some(400));
+
+        let filter = ExampleError::from(error_from_status(400, r#"{"error": {"message": "Syntax error at position 12 in $filter"}}"#));
+        assert!(matches!(filter, ExampleError::MalformedFilter(_)));
+        assert_eq!(
+            filter.odata(),
+            Some(ODataErrorPayload {
+                code: None,
+                message: "Syntax error at position 12 in $filter".into(),
+            })
+        );
+
+        let quota = ExampleError::from(error_from_status(403, "Daily request quota exceeded"));
+        assert!(matches!(quota, ExampleError::QuotaExhausted(_)));
+        assert!(quota.odata().is_none());
+        assert!(matches!(ExampleError::from(error_from_status(401, "")), ExampleError::Auth(_)));
+        assert!(matches!(
+            ExampleError::from(error_from_status(501, "Replication is not implemented")),
+            ExampleError::Unsupported { feature, .. } if feature == "replication"
+        ));
+
+        let network = ExampleError::from(ResoError::Network("Request timed out".into()));
+        assert!(network.to_string().starts_with("Network error: "));
+        assert!(network.remediation().contains("RESO_TIMEOUT"));
+    }
+}
@@ -0,0 +1,1558 @@
+//! Destinations for replicated records.
+//!
+//! A [`RecordSink`] receives records batch by batch from the replication
+//! engine in [`sync`](crate::sync). Sinks are provided for newline-delimited
+//! JSON files and, behind features, CSV files (`export-csv`), SQLite
+//! databases (`db-sqlite`, on by default), PostgreSQL tables
+//! (`db-postgres`), and directories of Parquet files (`export-parquet`).
+//! [`PartitionedSink`] spreads the file sinks over a Hive-style directory
+//! tree.
+//!
+//! The replication helpers take any `RecordSink`, so a destination of your
+//! own only needs `write_batch`; the other methods have defaults.
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use reso_client::{JsonValue, ResoError};
+//! use reso_examples::export::RecordSink;
+//!
+//! /// Counts records instead of storing them, e.g. for a dry run.
+//! #[derive(Default)]
+//! struct CountingSink {
+//!     records: usize,
+//! }
+//!
+//! #[async_trait]
+//! impl RecordSink for CountingSink {
+//!     async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+//!         self.records += records.len();
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use crate::aggregate::GroupCount;
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use async_trait::async_trait;
+use reso_client::{JsonValue, ResoError};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A destination that replicated records are written to.
+#[async_trait]
+pub trait RecordSink: Send {
+    /// Writes one batch of records.
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError>;
+
+    /// Makes previously written batches durable.
+    ///
+    /// Called after each batch, before the checkpoint is advanced.
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        Ok(())
+    }
+
+    /// Completes the export. No batches are written afterwards.
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.flush().await
+    }
+
+    /// Brings the destination's schema up to date with the resource's
+    /// metadata before a run, for sinks that have one.
+    ///
+    /// The database sinks add a column for each field that appeared since
+    /// their table was created (see [`migrations`](crate::migrations)).
+    /// Schemaless sinks report no changes.
+    async fn evolve_schema(&mut self, _entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        Ok(SchemaChanges::default())
+    }
+
+    /// Counts the distinct records stored, optionally grouped by a field,
+    /// for sinks that can (see [`reconcile`](crate::reconcile)).
+    ///
+    /// Returns `None` for sinks that cannot count, such as append-only files
+    /// where a record may appear more than once.
+    async fn count_stored(&mut self, _group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        Ok(None)
+    }
+
+    /// The files this sink has written to, for [`manifest`](crate::manifest).
+    ///
+    /// Database sinks write no files and return an empty list.
+    fn output_files(&self) -> Vec<OutputFile> {
+        Vec::new()
+    }
+}
+
+/// A file written by a sink, reported by [`RecordSink::output_files`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFile {
+    pub path: PathBuf,
+    /// Records in the file, or `None` for newline-delimited files, whose
+    /// records are counted from their lines
+    pub records: Option<u64>,
+}
+
+/// Record counts reported by [`RecordSink::count_stored`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoredCounts {
+    pub total: u64,
+    /// Counts per value of the grouping field, if one was requested
+    pub groups: Vec<GroupCount>,
+}
+
+/// Lets sinks chosen at runtime (`Box<dyn RecordSink>`) be wrapped and driven
+/// like concrete ones.
+#[async_trait]
+impl<S: RecordSink + ?Sized> RecordSink for Box<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        (**self).write_batch(records).await
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        (**self).flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        (**self).finalize().await
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        (**self).evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        (**self).count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        (**self).output_files()
+    }
+}
+
+/// Returns the conventional key field of a resource.
+///
+/// The Data Dictionary names keys `<Resource>Key`, except for `Property`
+/// whose key is `ListingKey`.
+pub fn default_key_field(resource: &str) -> String {
+    match resource {
+        "Property" => "ListingKey".to_string(),
+        _ => format!("{}Key", resource),
+    }
+}
+
+pub(crate) fn sink_error(context: &str, e: impl std::fmt::Display) -> ResoError {
+    ResoError::Config(format!("{}: {}", context, e))
+}
+
+/// What the database sinks do when a record's key is already stored.
+///
+/// Policies are applied row by row inside each batch's transaction, so a
+/// batch that arrives late (a retried page, an overlapping rerun) cannot
+/// replace data written by a newer one under [`NewestWins`](Self::NewestWins).
+/// Records repeated within a batch are resolved by the same rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Replace the stored record unless its `ModificationTimestamp` is newer
+    /// than the incoming one. Timestamps are compared as text, which orders
+    /// correctly for the UTC ISO 8601 values RESO servers send.
+    #[default]
+    NewestWins,
+    /// Always replace the stored record.
+    Overwrite,
+    /// Keep the stored record.
+    Skip,
+}
+
+impl ConflictPolicy {
+    /// The `ON CONFLICT` clause of the upsert into `table`.
+    #[cfg(any(feature = "db-sqlite", feature = "db-postgres"))]
+    fn on_conflict_sql(&self, table: &str) -> String {
+        let update = "ON CONFLICT (key) DO UPDATE SET
+                modification_timestamp = excluded.modification_timestamp,
+                data = excluded.data";
+        match self {
+            ConflictPolicy::NewestWins => format!(
+                "{} WHERE {}.modification_timestamp IS NULL
+                    OR excluded.modification_timestamp >= {}.modification_timestamp",
+                update,
+                quote_identifier(table),
+                quote_identifier(table)
+            ),
+            ConflictPolicy::Overwrite => update.to_string(),
+            ConflictPolicy::Skip => "ON CONFLICT (key) DO NOTHING".to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for ConflictPolicy {
+    type Err = ResoError;
+
+    /// Parses `newest-wins`, `overwrite`, or `skip`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest-wins" => Ok(ConflictPolicy::NewestWins),
+            "overwrite" => Ok(ConflictPolicy::Overwrite),
+            "skip" => Ok(ConflictPolicy::Skip),
+            other => Err(ResoError::Config(format!(
+                "Unknown conflict policy '{}' (expected newest-wins, overwrite, or skip)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Appends records to a newline-delimited JSON file, one record per line.
+pub struct JsonlSink {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl JsonlSink {
+    /// Opens `path` for appending, creating it if needed.
+    ///
+    /// Appending lets a resumed replication run continue the same file.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| sink_error(&format!("Cannot open {}", path.display()), e))?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl RecordSink for JsonlSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        for record in records {
+            serde_json::to_writer(&mut self.writer, record)
+                .map_err(|e| ResoError::Parse(e.to_string()))?;
+            self.writer
+                .write_all(b"\n")
+                .map_err(|e| sink_error(&format!("Cannot write {}", self.path.display()), e))?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.writer
+            .flush()
+            .map_err(|e| sink_error(&format!("Cannot write {}", self.path.display()), e))
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        vec![OutputFile {
+            path: self.path.clone(),
+            records: None,
+        }]
+    }
+}
+
+/// Appends records to a CSV file, one row per record.
+///
+/// The columns are the existing file's header when appending to one, else
+/// those given to [`CsvSink::with_columns`], else every field of the first
+/// batch sorted by name, without OData annotations. Strings are written
+/// as-is, `null` as an empty cell, and other values as JSON.
+///
+/// The header cannot change once written, so fields outside the columns,
+/// such as ones that first appear in a later batch, are left out. They are
+/// listed by [`CsvSink::dropped_fields`] and reported on stderr by
+/// `finalize`.
+#[cfg(feature = "export-csv")]
+pub struct CsvSink {
+    path: PathBuf,
+    writer: csv::Writer<File>,
+    /// Empty until the first batch when not known in advance
+    columns: Vec<String>,
+    header_written: bool,
+    /// Rows in the file, including those already there when it was opened
+    rows: u64,
+    /// Fields seen in records but not in the columns
+    dropped: std::collections::BTreeSet<String>,
+}
+
+#[cfg(feature = "export-csv")]
+impl CsvSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let path = path.as_ref().to_path_buf();
+        let read_error = |e: csv::Error| ResoError::Parse(format!("Cannot read {}: {}", path.display(), e));
+        let (columns, rows) = match csv::Reader::from_path(&path) {
+            Ok(mut reader) => {
+                let header = reader.headers().map_err(read_error)?.iter().map(str::to_string).collect();
+                (header, reader.records().count() as u64)
+            }
+            Err(_) => (Vec::new(), 0),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| sink_error(&format!("Cannot open {}", path.display()), e))?;
+        Ok(Self {
+            path,
+            writer: csv::Writer::from_writer(file),
+            header_written: !columns.is_empty(),
+            columns,
+            rows,
+            dropped: Default::default(),
+        })
+    }
+
+    /// Writes these columns to a new file. A file that already has a header
+    /// keeps its columns.
+    pub fn with_columns(mut self, columns: &[&str]) -> Self {
+        if !self.header_written {
+            self.columns = columns.iter().map(|c| c.to_string()).collect();
+        }
+        self
+    }
+
+    /// Fields left out because they are not in the columns, sorted by name.
+    pub fn dropped_fields(&self) -> Vec<&str> {
+        self.dropped.iter().map(String::as_str).collect()
+    }
+
+    fn write_error(&self, e: csv::Error) -> ResoError {
+        sink_error(&format!("Cannot write {}", self.path.display()), e)
+    }
+}
+
+#[cfg(feature = "export-csv")]
+#[async_trait]
+impl RecordSink for CsvSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        if self.columns.is_empty() {
+            for key in records.iter().filter_map(JsonValue::as_object).flat_map(|object| object.keys()) {
+                if !key.starts_with('@') && !self.columns.contains(key) {
+                    self.columns.push(key.clone());
+                }
+            }
+        }
+        if !self.header_written && !self.columns.is_empty() {
+            self.writer.write_record(&self.columns).map_err(|e| self.write_error(e))?;
+            self.header_written = true;
+        }
+        for record in records {
+            if let Some(object) = record.as_object() {
+                for key in object.keys() {
+                    if !key.starts_with('@') && !self.columns.contains(key) && !self.dropped.contains(key) {
+                        self.dropped.insert(key.clone());
+                    }
+                }
+            }
+            let row = self.columns.iter().map(|column| match &record[column.as_str()] {
+                JsonValue::Null => String::new(),
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+            self.writer.write_record(row).map_err(|e| self.write_error(e))?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.writer
+            .flush()
+            .map_err(|e| sink_error(&format!("Cannot write {}", self.path.display()), e))
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        if !self.dropped.is_empty() {
+            eprintln!(
+                "Not in the columns of {}, left out: {}",
+                self.path.display(),
+                self.dropped_fields().join(", ")
+            );
+        }
+        self.flush().await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        vec![OutputFile {
+            path: self.path.clone(),
+            records: Some(self.rows),
+        }]
+    }
+}
+
+/// Upserts records into a SQLite table keyed by the resource key.
+///
+/// Each record is stored as JSON alongside its key and
+/// `ModificationTimestamp`, so re-replicated records replace older copies
+/// according to the sink's [`ConflictPolicy`]:
+///
+/// ```sql
+/// CREATE TABLE "Property" (
+///     key TEXT PRIMARY KEY,
+///     modification_timestamp TEXT,
+///     data TEXT NOT NULL
+/// )
+/// ```
+#[cfg(feature = "db-sqlite")]
+pub struct SqliteSink {
+    conn: rusqlite::Connection,
+    table: String,
+    key_field: String,
+    policy: ConflictPolicy,
+}
+
+#[cfg(feature = "db-sqlite")]
+impl SqliteSink {
+    /// Opens (or creates) the database at `path` and ensures the table exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the SQLite database file
+    /// * `table` - Table name, usually the resource name
+    /// * `key_field` - Record field used as the primary key, e.g. "ListingKey"
+    pub fn open(
+        path: impl AsRef<Path>,
+        table: &str,
+        key_field: &str,
+    ) -> Result<Self, ResoError> {
+        let conn = rusqlite::Connection::open(path).map_err(|e| sink_error("SQLite", e))?;
+        Self::with_connection(conn, table, key_field)
+    }
+
+    /// Uses an existing connection, e.g. an in-memory database.
+    pub fn with_connection(
+        conn: rusqlite::Connection,
+        table: &str,
+        key_field: &str,
+    ) -> Result<Self, ResoError> {
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                key TEXT PRIMARY KEY,
+                modification_timestamp TEXT,
+                data TEXT NOT NULL
+            )",
+            quote_identifier(table)
+        ))
+        .map_err(|e| sink_error("SQLite", e))?;
+
+        Ok(Self {
+            conn,
+            table: table.to_string(),
+            key_field: key_field.to_string(),
+            policy: ConflictPolicy::default(),
+        })
+    }
+
+    /// Sets how records whose key is already stored are handled.
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The underlying connection.
+    pub fn connection(&self) -> &rusqlite::Connection {
+        &self.conn
+    }
+
+    /// Reads up to `n` stored records chosen at random.
+    pub fn sample(&self, n: usize) -> Result<Vec<JsonValue>, ResoError> {
+        self.read_records("ORDER BY RANDOM() LIMIT ?1", [n as i64])
+    }
+
+    /// Reads every stored record.
+    pub fn records(&self) -> Result<Vec<JsonValue>, ResoError> {
+        self.read_records("", [])
+    }
+
+    fn read_records(&self, clauses: &str, params: impl rusqlite::Params) -> Result<Vec<JsonValue>, ResoError> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT data FROM {} {}", quote_identifier(&self.table), clauses))
+            .map_err(|e| sink_error("SQLite", e))?;
+        let rows = stmt
+            .query_map(params, |row| row.get::<_, String>(0))
+            .map_err(|e| sink_error("SQLite", e))?;
+        rows.map(|data| {
+            let data = data.map_err(|e| sink_error("SQLite", e))?;
+            serde_json::from_str(&data).map_err(|e| ResoError::Parse(e.to_string()))
+        })
+        .collect()
+    }
+}
+
+#[cfg(feature = "db-sqlite")]
+#[async_trait]
+impl RecordSink for SqliteSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let tx = self.conn.transaction().map_err(|e| sink_error("SQLite", e))?;
+        {
+            let mut stmt = tx
+                .prepare(&format!(
+                    "INSERT INTO {} (key, modification_timestamp, data) VALUES (?1, ?2, ?3) {}",
+                    quote_identifier(&self.table),
+                    self.policy.on_conflict_sql(&self.table)
+                ))
+                .map_err(|e| sink_error("SQLite", e))?;
+
+            for record in records {
+                let key = record_key(record, &self.key_field)?;
+                let modified = record["ModificationTimestamp"].as_str();
+                stmt.execute(rusqlite::params![key, modified, record.to_string()])
+                    .map_err(|e| sink_error("SQLite", e))?;
+            }
+        }
+        tx.commit().map_err(|e| sink_error("SQLite", e))
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        // table_xinfo, unlike table_info, lists generated columns
+        let columns = {
+            let mut stmt = self
+                .conn
+                .prepare(&format!("PRAGMA table_xinfo({})", quote_identifier(&self.table)))
+                .map_err(|e| sink_error("SQLite", e))?;
+            let names = stmt
+                .query_map([], |row| row.get::<_, String>(1))
+                .map_err(|e| sink_error("SQLite", e))?;
+            names
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| sink_error("SQLite", e))?
+        };
+
+        let (changes, statements) =
+            plan_schema_changes(&self.table, &columns, entity_type, SqlDialect::Sqlite);
+        let tx = self.conn.transaction().map_err(|e| sink_error("SQLite", e))?;
+        for statement in &statements {
+            tx.execute_batch(statement).map_err(|e| sink_error("SQLite", e))?;
+        }
+        tx.commit().map_err(|e| sink_error("SQLite", e))?;
+        Ok(changes)
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        let table = quote_identifier(&self.table);
+        let total: i64 = self
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .map_err(|e| sink_error("SQLite", e))?;
+
+        let mut groups = Vec::new();
+        if let Some(field) = group_by {
+            let mut stmt = self
+                .conn
+                .prepare(&format!(
+                    "SELECT json_extract(data, ?1) AS value, COUNT(*) FROM {} GROUP BY value",
+                    table
+                ))
+                .map_err(|e| sink_error("SQLite", e))?;
+            let rows = stmt
+                .query_map([format!("$.\"{}\"", field)], |row| {
+                    let value = match row.get::<_, rusqlite::types::Value>(0)? {
+                        rusqlite::types::Value::Integer(n) => JsonValue::from(n),
+                        rusqlite::types::Value::Real(n) => JsonValue::from(n),
+                        rusqlite::types::Value::Text(s) => JsonValue::from(s),
+                        _ => JsonValue::Null,
+                    };
+                    Ok(GroupCount {
+                        value,
+                        count: row.get::<_, i64>(1)? as u64,
+                    })
+                })
+                .map_err(|e| sink_error("SQLite", e))?;
+            groups = rows
+                .collect::<Result<_, _>>()
+                .map_err(|e| sink_error("SQLite", e))?;
+        }
+
+        Ok(Some(StoredCounts {
+            total: total as u64,
+            groups,
+        }))
+    }
+}
+
+/// Reads the key of a record as text.
+#[cfg(any(feature = "db-sqlite", feature = "db-postgres"))]
+fn record_key(record: &JsonValue, key_field: &str) -> Result<String, ResoError> {
+    match &record[key_field] {
+        JsonValue::String(s) => Ok(s.clone()),
+        JsonValue::Null => Err(ResoError::Parse(format!(
+            "Record is missing key field '{}'",
+            key_field
+        ))),
+        other => Ok(other.to_string()),
+    }
+}
+
+#[cfg(any(feature = "db-sqlite", feature = "db-postgres"))]
+use crate::migrations::{plan_schema_changes, SqlDialect};
+
+#[cfg(any(feature = "db-sqlite", feature = "db-postgres"))]
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Rows per `INSERT` statement; each row takes three of the 65535 bind
+/// parameters PostgreSQL allows.
+#[cfg(feature = "db-postgres")]
+const POSTGRES_INSERT_ROWS: usize = 1000;
+
+/// Upserts records into a PostgreSQL table keyed by the resource key.
+///
+/// The table mirrors the `SqliteSink` layout, including its
+/// [`ConflictPolicy`] handling, with the record stored as `JSONB` so it can
+/// be queried in place:
+///
+/// ```sql
+/// CREATE TABLE "mls"."Property" (
+///     key TEXT PRIMARY KEY,
+///     modification_timestamp TEXT,
+///     data JSONB NOT NULL
+/// )
+/// ```
+///
+/// Each batch is written in one transaction with multi-row
+/// `INSERT ... ON CONFLICT (key) DO UPDATE` statements of up to 1000 rows.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::export::{ConflictPolicy, PostgresSink};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let sink = PostgresSink::connect("host=localhost user=postgres dbname=warehouse", "mls.Property", "ListingKey")
+///     .await?
+///     .with_conflict_policy(ConflictPolicy::Overwrite);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "db-postgres")]
+pub struct PostgresSink {
+    client: tokio_postgres::Client,
+    /// Schema, or `None` for the connection's current schema
+    schema: Option<String>,
+    table: String,
+    key_field: String,
+    policy: ConflictPolicy,
+}
+
+#[cfg(feature = "db-postgres")]
+impl PostgresSink {
+    /// Connects without TLS and ensures the table exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Connection string, e.g. "host=localhost user=postgres dbname=mls"
+    /// * `table` - Table name, usually the resource name, optionally
+    ///   schema-qualified as `schema.table`; a missing schema is created
+    /// * `key_field` - Record field used as the primary key, e.g. "ListingKey"
+    pub async fn connect(params: &str, table: &str, key_field: &str) -> Result<Self, ResoError> {
+        let (client, connection) = tokio_postgres::connect(params, tokio_postgres::NoTls)
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("PostgreSQL connection error: {}", e);
+            }
+        });
+        Self::with_client(client, table, key_field).await
+    }
+
+    /// Uses an existing client, e.g. one connected with TLS.
+    pub async fn with_client(
+        client: tokio_postgres::Client,
+        table: &str,
+        key_field: &str,
+    ) -> Result<Self, ResoError> {
+        let (schema, table) = match table.split_once('.') {
+            Some((schema, table)) => (Some(schema.to_string()), table.to_string()),
+            None => (None, table.to_string()),
+        };
+        let sink = Self {
+            client,
+            schema,
+            table,
+            key_field: key_field.to_string(),
+            policy: ConflictPolicy::default(),
+        };
+
+        if let Some(schema) = &sink.schema {
+            sink.client
+                .batch_execute(&format!("CREATE SCHEMA IF NOT EXISTS {}", quote_identifier(schema)))
+                .await
+                .map_err(|e| sink_error("PostgreSQL", e))?;
+        }
+        sink.client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    key TEXT PRIMARY KEY,
+                    modification_timestamp TEXT,
+                    data JSONB NOT NULL
+                )",
+                sink.qualified_table()
+            ))
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?;
+        Ok(sink)
+    }
+
+    /// The quoted table name, schema-qualified when a schema was given.
+    fn qualified_table(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote_identifier(schema), quote_identifier(&self.table)),
+            None => quote_identifier(&self.table),
+        }
+    }
+
+    /// Sets how records whose key is already stored are handled.
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+#[cfg(feature = "db-postgres")]
+#[async_trait]
+impl RecordSink for PostgresSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let rows = collapse_duplicate_keys(records, &self.key_field, self.policy)?;
+        let table = self.qualified_table();
+        let on_conflict = self.policy.on_conflict_sql(&self.table);
+        let tx = self
+            .client
+            .transaction()
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?;
+
+        for chunk in rows.chunks(POSTGRES_INSERT_ROWS) {
+            let values: Vec<String> = (0..chunk.len())
+                .map(|i| format!("(${}, ${}, ${})", 3 * i + 1, 3 * i + 2, 3 * i + 3))
+                .collect();
+            let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = chunk
+                .iter()
+                .flat_map(|row| {
+                    [
+                        &row.key as &(dyn tokio_postgres::types::ToSql + Sync),
+                        &row.modified,
+                        row.record,
+                    ]
+                })
+                .collect();
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (key, modification_timestamp, data) VALUES {} {}",
+                    table,
+                    values.join(", "),
+                    on_conflict
+                ),
+                &params,
+            )
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?;
+        }
+        tx.commit().await.map_err(|e| sink_error("PostgreSQL", e))
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        let columns: Vec<String> = self
+            .client
+            .query(
+                "SELECT column_name::text FROM information_schema.columns
+                 WHERE table_name = $1 AND table_schema = COALESCE($2, current_schema())",
+                &[&self.table, &self.schema],
+            )
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+
+        let (changes, statements) =
+            plan_schema_changes(&self.table, &columns, entity_type, SqlDialect::Postgres);
+        let tx = self
+            .client
+            .transaction()
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?;
+        // The statements name the table unqualified
+        if let Some(schema) = &self.schema {
+            tx.batch_execute(&format!("SET LOCAL search_path TO {}", quote_identifier(schema)))
+                .await
+                .map_err(|e| sink_error("PostgreSQL", e))?;
+        }
+        for statement in &statements {
+            tx.batch_execute(statement)
+                .await
+                .map_err(|e| sink_error("PostgreSQL", e))?;
+        }
+        tx.commit().await.map_err(|e| sink_error("PostgreSQL", e))?;
+        Ok(changes)
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        let table = self.qualified_table();
+        let total: i64 = self
+            .client
+            .query_one(&format!("SELECT COUNT(*) FROM {}", table), &[])
+            .await
+            .map_err(|e| sink_error("PostgreSQL", e))?
+            .get(0);
+
+        let mut groups = Vec::new();
+        if let Some(field) = group_by {
+            let rows = self
+                .client
+                .query(
+                    &format!("SELECT data -> $1, COUNT(*) FROM {} GROUP BY 1", table),
+                    &[&field],
+                )
+                .await
+                .map_err(|e| sink_error("PostgreSQL", e))?;
+            groups = rows
+                .iter()
+                .map(|row| GroupCount {
+                    value: row.get::<_, Option<JsonValue>>(0).unwrap_or(JsonValue::Null),
+                    count: row.get::<_, i64>(1) as u64,
+                })
+                .collect();
+        }
+
+        Ok(Some(StoredCounts {
+            total: total as u64,
+            groups,
+        }))
+    }
+}
+
+/// A record to upsert, with its key and `ModificationTimestamp`.
+#[cfg(feature = "db-postgres")]
+#[derive(Debug)]
+struct UpsertRow<'a> {
+    key: String,
+    modified: Option<&'a str>,
+    record: &'a JsonValue,
+}
+
+/// Collapses records that share a key into the one `policy` would leave
+/// stored, keeping the order in which keys first appear.
+///
+/// One `INSERT ... ON CONFLICT DO UPDATE` may not change a row twice, so a
+/// batch holding a listing twice (a page boundary shifting mid-run) has to
+/// be resolved before it is sent.
+#[cfg(feature = "db-postgres")]
+fn collapse_duplicate_keys<'a>(
+    records: &'a [JsonValue],
+    key_field: &str,
+    policy: ConflictPolicy,
+) -> Result<Vec<UpsertRow<'a>>, ResoError> {
+    let mut rows: Vec<UpsertRow<'a>> = Vec::with_capacity(records.len());
+    let mut positions = std::collections::HashMap::new();
+    for record in records {
+        let row = UpsertRow {
+            key: record_key(record, key_field)?,
+            modified: record["ModificationTimestamp"].as_str(),
+            record,
+        };
+        match positions.get(&row.key) {
+            Some(&i) => {
+                let stored: &UpsertRow = &rows[i];
+                let replace = match policy {
+                    // As in SQL, a stored row without a timestamp is always replaced
+                    ConflictPolicy::NewestWins => row.modified >= stored.modified,
+                    ConflictPolicy::Overwrite => true,
+                    ConflictPolicy::Skip => false,
+                };
+                if replace {
+                    rows[i] = row;
+                }
+            }
+            None => {
+                positions.insert(row.key.clone(), rows.len());
+                rows.push(row);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Writes each batch as a Parquet file in a directory.
+///
+/// Files are named `part-00000.parquet`, `part-00001.parquet`, and so on,
+/// continuing after any parts already present. The column schema is inferred
+/// from the records of each batch.
+#[cfg(feature = "export-parquet")]
+pub struct ParquetSink {
+    dir: PathBuf,
+    next_part: usize,
+    /// Parts written by this sink
+    written: Vec<OutputFile>,
+}
+
+#[cfg(feature = "export-parquet")]
+impl ParquetSink {
+    /// Creates the output directory if needed.
+    pub fn create(dir: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| sink_error(&format!("Cannot create {}", dir.display()), e))?;
+
+        let next_part = std::fs::read_dir(&dir)
+            .map_err(|e| sink_error(&format!("Cannot read {}", dir.display()), e))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with("part-") && name.ends_with(".parquet")
+            })
+            .count();
+
+        Ok(Self {
+            dir,
+            next_part,
+            written: Vec::new(),
+        })
+    }
+}
+
+#[cfg(feature = "export-parquet")]
+#[async_trait]
+impl RecordSink for ParquetSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        use std::sync::Arc;
+
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let schema = arrow_json::reader::infer_json_schema_from_iterator(
+            records.iter().map(|r| Ok(r.clone())),
+        )
+        .map_err(|e| sink_error("Parquet schema inference", e))?;
+        let schema = Arc::new(schema);
+
+        let mut decoder = arrow_json::ReaderBuilder::new(schema.clone())
+            .build_decoder()
+            .map_err(|e| sink_error("Parquet", e))?;
+        decoder.serialize(records).map_err(|e| sink_error("Parquet", e))?;
+        let Some(batch) = decoder.flush().map_err(|e| sink_error("Parquet", e))? else {
+            return Ok(());
+        };
+
+        let path = self.dir.join(format!("part-{:05}.parquet", self.next_part));
+        let file = File::create(&path)
+            .map_err(|e| sink_error(&format!("Cannot create {}", path.display()), e))?;
+        let mut writer = parquet::arrow::ArrowWriter::try_new(file, schema, None)
+            .map_err(|e| sink_error("Parquet", e))?;
+        writer.write(&batch).map_err(|e| sink_error("Parquet", e))?;
+        writer.close().map_err(|e| sink_error("Parquet", e))?;
+
+        self.written.push(OutputFile {
+            path,
+            records: Some(batch.num_rows() as u64),
+        });
+        self.next_part += 1;
+        Ok(())
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.written.clone()
+    }
+}
+
+/// A value that file sinks can partition records by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Partition {
+    /// The value of a field, e.g. `City=Austin`
+    Field(String),
+    /// The date part of a timestamp field, e.g. `ModificationTimestamp_date=2025-01-02`
+    Date(String),
+}
+
+impl Partition {
+    /// The Hive-style directory name for a record, `<column>=<value>`.
+    ///
+    /// Missing values use Hive's `__HIVE_DEFAULT_PARTITION__`; characters
+    /// that are unsafe in paths are percent-encoded as Hive does.
+    pub fn directory(&self, record: &JsonValue) -> String {
+        let (column, value) = match self {
+            Partition::Field(field) => (field.clone(), &record[field]),
+            Partition::Date(field) => (format!("{}_date", field), &record[field]),
+        };
+        let value = match value {
+            JsonValue::Null => None,
+            JsonValue::String(s) if s.is_empty() => None,
+            JsonValue::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        };
+        let value = match (self, value) {
+            (_, None) => "__HIVE_DEFAULT_PARTITION__".to_string(),
+            (Partition::Date(_), Some(ts)) => escape_partition_value(ts.get(..10).unwrap_or(&ts)),
+            (Partition::Field(_), Some(v)) => escape_partition_value(&v),
+        };
+        format!("{}={}", column, value)
+    }
+}
+
+impl std::str::FromStr for Partition {
+    type Err = ResoError;
+
+    /// Parses `date` (the `ModificationTimestamp` date), `date:<Field>`, or a
+    /// field name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "date" => Ok(Partition::Date("ModificationTimestamp".to_string())),
+            Some(("date", field)) if !field.is_empty() => Ok(Partition::Date(field.to_string())),
+            None if !s.is_empty() => Ok(Partition::Field(s.to_string())),
+            _ => Err(ResoError::Config(format!("Invalid partition '{}'", s))),
+        }
+    }
+}
+
+fn escape_partition_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_control() || "\"#%'*/:=?\\{[]^".contains(c) {
+            for byte in c.to_string().bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+type SinkFactory = Box<dyn FnMut(&Path) -> Result<Box<dyn RecordSink>, ResoError> + Send>;
+
+/// Partition sinks a [`PartitionedSink`] keeps open by default.
+pub const MAX_OPEN_PARTITIONS: usize = 64;
+
+/// Splits records into a Hive-style directory tree, one sink per partition.
+///
+/// With `[Field("City"), Date("ModificationTimestamp")]` a record lands in
+/// `<root>/City=Austin/ModificationTimestamp_date=2025-01-02/`, which query
+/// engines such as DuckDB, Spark, and Athena use to skip partitions that a
+/// query filters out. Sinks are opened as partitions are first seen. At most
+/// [`max_open`](Self::max_open) stay open; opening another finalizes the
+/// least recently written one, which is reopened if its partition comes up
+/// again.
+pub struct PartitionedSink {
+    root: PathBuf,
+    partitions: Vec<Partition>,
+    open: SinkFactory,
+    /// Open partition sinks, with the write that last used each
+    sinks: std::collections::BTreeMap<PathBuf, (Box<dyn RecordSink>, u64)>,
+    max_open: usize,
+    writes: u64,
+    /// Files of the partition sinks closed by eviction or `finalize`
+    finished: Vec<OutputFile>,
+}
+
+impl PartitionedSink {
+    /// Partitions into sinks created by `open` for each partition directory.
+    pub fn new(
+        root: impl AsRef<Path>,
+        partitions: Vec<Partition>,
+        open: impl FnMut(&Path) -> Result<Box<dyn RecordSink>, ResoError> + Send + 'static,
+    ) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            partitions,
+            open: Box::new(open),
+            sinks: Default::default(),
+            max_open: MAX_OPEN_PARTITIONS,
+            writes: 0,
+            finished: Vec::new(),
+        }
+    }
+
+    /// Appends to `part.jsonl` in each partition directory.
+    pub fn jsonl(root: impl AsRef<Path>, partitions: Vec<Partition>) -> Self {
+        Self::new(root, partitions, |dir| {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| sink_error(&format!("Cannot create {}", dir.display()), e))?;
+            Ok(Box::new(JsonlSink::append(dir.join("part.jsonl"))?))
+        })
+    }
+
+    /// Writes numbered Parquet parts into each partition directory.
+    ///
+    /// A partition receives only a slice of each batch, so its rows are
+    /// collected until [`ROWS_PER_PART`] are pending, the sink is finalized,
+    /// or the partition is closed to make room for another. Pending rows are
+    /// kept in `_pending.jsonl` in the partition directory, which query
+    /// engines skip, and picked up again by the next run.
+    #[cfg(feature = "export-parquet")]
+    pub fn parquet(root: impl AsRef<Path>, partitions: Vec<Partition>) -> Self {
+        Self::new(root, partitions, |dir| Ok(Box::new(SpooledParquetSink::open(dir)?)))
+    }
+
+    /// Keeps at most `max_open` partition sinks open (at least one), bounding
+    /// open files and buffered rows when a field has many values.
+    pub fn max_open(mut self, max_open: usize) -> Self {
+        self.max_open = max_open.max(1);
+        self
+    }
+
+    fn partition_dir(&self, record: &JsonValue) -> PathBuf {
+        self.partitions
+            .iter()
+            .fold(self.root.clone(), |dir, p| dir.join(p.directory(record)))
+    }
+
+    /// Finalizes the least recently written sink and closes it.
+    async fn evict(&mut self) -> Result<(), ResoError> {
+        let Some(dir) = self
+            .sinks
+            .iter()
+            .min_by_key(|(_, (_, last_write))| *last_write)
+            .map(|(dir, _)| dir.clone())
+        else {
+            return Ok(());
+        };
+        if let Some((mut sink, _)) = self.sinks.remove(&dir) {
+            sink.finalize().await?;
+            self.retire(sink.output_files());
+        }
+        Ok(())
+    }
+
+    /// Records the files of a closed sink, replacing earlier entries for
+    /// files a reopened partition appended to.
+    fn retire(&mut self, files: Vec<OutputFile>) {
+        for file in files {
+            self.finished.retain(|finished| finished.path != file.path);
+            self.finished.push(file);
+        }
+    }
+}
+
+#[async_trait]
+impl RecordSink for PartitionedSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let mut groups: std::collections::BTreeMap<PathBuf, Vec<JsonValue>> = Default::default();
+        for record in records {
+            groups
+                .entry(self.partition_dir(record))
+                .or_default()
+                .push(record.clone());
+        }
+
+        for (dir, records) in groups {
+            self.writes += 1;
+            if !self.sinks.contains_key(&dir) {
+                if self.sinks.len() >= self.max_open {
+                    self.evict().await?;
+                }
+                let sink = (self.open)(&dir)?;
+                self.sinks.insert(dir.clone(), (sink, self.writes));
+            }
+            if let Some((sink, last_write)) = self.sinks.get_mut(&dir) {
+                *last_write = self.writes;
+                sink.write_batch(&records).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        for (sink, _) in self.sinks.values_mut() {
+            sink.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        let mut files = Vec::new();
+        for (sink, _) in self.sinks.values_mut() {
+            sink.finalize().await?;
+            files.extend(sink.output_files());
+        }
+        self.retire(files);
+        self.sinks.clear();
+        Ok(())
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        // A partition reopened after it was closed appends to the same file
+        let open: Vec<OutputFile> = self.sinks.values().flat_map(|(sink, _)| sink.output_files()).collect();
+        let mut files: Vec<OutputFile> = self
+            .finished
+            .iter()
+            .filter(|file| !open.iter().any(|o| o.path == file.path))
+            .cloned()
+            .collect();
+        files.extend(open);
+        files
+    }
+}
+
+/// Rows a partition of [`PartitionedSink::parquet`] collects per Parquet part.
+#[cfg(feature = "export-parquet")]
+pub const ROWS_PER_PART: usize = 20_000;
+
+/// A [`ParquetSink`] that spools rows to `_pending.jsonl` until a part of
+/// [`ROWS_PER_PART`] rows is ready.
+///
+/// Spooled rows are flushed like any JSONL file, so a checkpoint never
+/// covers rows that exist only in memory, and they are read back only to
+/// write a part.
+#[cfg(feature = "export-parquet")]
+struct SpooledParquetSink {
+    parts: ParquetSink,
+    spool_path: PathBuf,
+    /// `None` once finalized
+    spool: Option<JsonlSink>,
+    pending: usize,
+}
+
+#[cfg(feature = "export-parquet")]
+impl SpooledParquetSink {
+    fn open(dir: &Path) -> Result<Self, ResoError> {
+        let parts = ParquetSink::create(dir)?;
+        let spool_path = dir.join("_pending.jsonl");
+        let pending = read_spool(&spool_path)?.len();
+        Ok(Self {
+            parts,
+            spool: Some(JsonlSink::append(&spool_path)?),
+            spool_path,
+            pending,
+        })
+    }
+
+    /// Writes the spooled rows as one part and empties the spool.
+    async fn write_part(&mut self) -> Result<(), ResoError> {
+        if let Some(mut spool) = self.spool.take() {
+            spool.flush().await?;
+        }
+        if self.pending > 0 {
+            self.parts.write_batch(&read_spool(&self.spool_path)?).await?;
+            self.pending = 0;
+        }
+        match std::fs::remove_file(&self.spool_path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(sink_error(&format!("Cannot remove {}", self.spool_path.display()), e)),
+        }
+    }
+}
+
+/// Reads the records of a spool file; a missing file holds none.
+///
+/// A torn last line was never flushed, so no checkpoint covers it and it is
+/// dropped.
+#[cfg(feature = "export-parquet")]
+fn read_spool(path: &Path) -> Result<Vec<JsonValue>, ResoError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(sink_error(&format!("Cannot read {}", path.display()), e)),
+    };
+    let lines: Vec<&str> = contents.lines().filter(|line| !line.trim().is_empty()).collect();
+    let mut records = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(_) if i + 1 == lines.len() => {}
+            Err(e) => return Err(ResoError::Parse(format!("Invalid record in {}: {}", path.display(), e))),
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(feature = "export-parquet")]
+#[async_trait]
+impl RecordSink for SpooledParquetSink {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let spool = self
+            .spool
+            .as_mut()
+            .ok_or_else(|| ResoError::Config(format!("{} was already finalized", self.spool_path.display())))?;
+        spool.write_batch(records).await?;
+        self.pending += records.len();
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        if let Some(spool) = &mut self.spool {
+            spool.flush().await?;
+        }
+        if self.pending >= ROWS_PER_PART {
+            self.write_part().await?;
+            self.spool = Some(JsonlSink::append(&self.spool_path)?);
+        }
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.write_part().await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.parts.output_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[cfg(feature = "db-postgres")]
+    #[test]
+    fn test_collapse_duplicate_keys_follows_policy() {
+        let batch = [
+            json!({"ListingKey": "A", "ListPrice": 1, "ModificationTimestamp": "2025-01-02T00:00:00Z"}),
+            json!({"ListingKey": "B", "ListPrice": 2, "ModificationTimestamp": null}),
+            json!({"ListingKey": "A", "ListPrice": 3, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            json!({"ListingKey": "B", "ListPrice": 4, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+        ];
+        let prices = |policy| {
+            collapse_duplicate_keys(&batch, "ListingKey", policy)
+                .unwrap()
+                .iter()
+                .map(|row| (row.key.clone(), row.record["ListPrice"].as_i64().unwrap()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(prices(ConflictPolicy::NewestWins), [("A".to_string(), 1), ("B".to_string(), 4)]);
+        assert_eq!(prices(ConflictPolicy::Overwrite), [("A".to_string(), 3), ("B".to_string(), 4)]);
+        assert_eq!(prices(ConflictPolicy::Skip), [("A".to_string(), 1), ("B".to_string(), 2)]);
+        assert!(collapse_duplicate_keys(&[json!({"ListPrice": 1})], "ListingKey", ConflictPolicy::Skip).is_err());
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_sink_upserts_by_key() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let mut sink = SqliteSink::with_connection(conn, "Property", "ListingKey").unwrap();
+
+        sink.write_batch(&[
+            json!({"ListingKey": "A", "ListPrice": 100, "ModificationTimestamp": "2025-01-01T00:00:00Z"}),
+            json!({"ListingKey": "B", "ListPrice": 200}),
+        ])
+        .await
+        .unwrap();
+        sink.write_batch(&[
+            json!({"ListingKey": "A", "ListPrice": 150, "ModificationTimestamp": "2025-01-02T00:00:00Z"}),
+        ])
+        .await
+        .unwrap();
+
+        let count: i64 = sink
+            .connection()
+            .query_row("SELECT COUNT(*) FROM \"Property\"", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let (modified, data): (String, String) = sink
+            .connection()
+            .query_row(
+                "SELECT modification_timestamp, data FROM \"Property\" WHERE key = 'A'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(modified, "2025-01-02T00:00:00Z");
+        assert_eq!(serde_json::from_str::<JsonValue>(&data).unwrap()["ListPrice"], 150);
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_conflict_policies() {
+        let newer = json!({"ListingKey": "A", "ListPrice": 2, "ModificationTimestamp": "2025-01-02T00:00:00Z"});
+        let late = json!({"ListingKey": "A", "ListPrice": 1, "ModificationTimestamp": "2025-01-01T00:00:00Z"});
+
+        for (policy, expected) in [
+            (ConflictPolicy::NewestWins, 2),
+            (ConflictPolicy::Overwrite, 1),
+            (ConflictPolicy::Skip, 2),
+        ] {
+            let conn = rusqlite::Connection::open_in_memory().unwrap();
+            let mut sink = SqliteSink::with_connection(conn, "Property", "ListingKey")
+                .unwrap()
+                .with_conflict_policy(policy);
+            sink.write_batch(std::slice::from_ref(&newer)).await.unwrap();
+            sink.write_batch(std::slice::from_ref(&late)).await.unwrap();
+
+            let data: String = sink
+                .connection()
+                .query_row("SELECT data FROM \"Property\" WHERE key = 'A'", [], |row| row.get(0))
+                .unwrap();
+            let price = serde_json::from_str::<JsonValue>(&data).unwrap()["ListPrice"].clone();
+            assert_eq!(price, expected, "{:?}", policy);
+        }
+        assert_eq!("skip".parse::<ConflictPolicy>().unwrap(), ConflictPolicy::Skip);
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_count_stored_by_field() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let mut sink = SqliteSink::with_connection(conn, "Property", "ListingKey").unwrap();
+        sink.write_batch(&[
+            json!({"ListingKey": "A", "City": "Austin"}),
+            json!({"ListingKey": "B", "City": "Austin"}),
+            json!({"ListingKey": "C"}),
+        ])
+        .await
+        .unwrap();
+
+        let mut counts = sink.count_stored(Some("City")).await.unwrap().unwrap();
+        counts.groups.sort_by_key(|g| g.count);
+        assert_eq!(counts.total, 3);
+        assert_eq!(
+            counts.groups,
+            vec![
+                GroupCount { value: JsonValue::Null, count: 1 },
+                GroupCount { value: json!("Austin"), count: 2 },
+            ]
+        );
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_evolve_schema_adds_new_fields() {
+        use crate::metadata::Field;
+
+        let field = |name: &str, edm_type: &str| Field {
+            name: name.to_string(),
+            edm_type: edm_type.to_string(),
+            nullable: true,
+        };
+        let mut property = EntityType {
+            name: "Property".to_string(),
+            fields: vec![field("ListingKey", "Edm.String")],
+            ..Default::default()
+        };
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let mut sink = SqliteSink::with_connection(conn, "Property", "ListingKey").unwrap();
+        assert_eq!(sink.evolve_schema(&property).await.unwrap().added, ["ListingKey"]);
+
+        property.fields.push(field("ListPrice", "Edm.Decimal"));
+        let changes = sink.evolve_schema(&property).await.unwrap();
+        assert_eq!(changes.added, ["ListPrice"]);
+        assert!(changes.removed.is_empty());
+
+        sink.write_batch(&[json!({"ListingKey": "A", "ListPrice": 10.5})]).await.unwrap();
+        let price: f64 = sink
+            .connection()
+            .query_row("SELECT \"ListPrice\" FROM \"Property\"", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(price, 10.5);
+        assert!(sink.evolve_schema(&property).await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "export-parquet")]
+    #[tokio::test]
+    async fn test_parquet_sink_writes_numbered_parts() {
+        let dir = std::env::temp_dir().join(format!("reso-parquet-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut sink = ParquetSink::create(&dir).unwrap();
+        let batch = [json!({"ListingKey": "A", "ListPrice": 100.5, "City": "Austin"})];
+        sink.write_batch(&batch).await.unwrap();
+        sink.write_batch(&batch).await.unwrap();
+
+        assert!(dir.join("part-00000.parquet").exists());
+        assert!(dir.join("part-00001.parquet").exists());
+        assert_eq!(ParquetSink::create(&dir).unwrap().next_part, 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_sink_appends_lines() {
+        let path = std::env::temp_dir().join(format!("reso-jsonl-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        for key in ["A", "B"] {
+            let mut sink = JsonlSink::append(&path).unwrap();
+            sink.write_batch(&[json!({"ListingKey": key})]).await.unwrap();
+            sink.finalize().await.unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "{\"ListingKey\":\"A\"}\n{\"ListingKey\":\"B\"}\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "export-csv")]
+    #[tokio::test]
+    async fn test_csv_sink_keeps_existing_header() {
+        let path = std::env::temp_dir().join(format!("reso-csv-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut sink = CsvSink::append(&path).unwrap();
+        sink.write_batch(&[json!({"@odata.id": "x", "City": "Austin, TX", "ListPrice": 500000, "ListingKey": "A"})])
+            .await
+            .unwrap();
+        sink.finalize().await.unwrap();
+
+        // Reopening appends under the existing columns, whatever the batch holds
+        let mut sink = CsvSink::append(&path).unwrap().with_columns(&["Ignored"]);
+        sink.write_batch(&[json!({"City": "Dallas", "ListingKey": "B", "Extra": 1, "ListPrice": null})])
+            .await
+            .unwrap();
+        sink.finalize().await.unwrap();
+        assert_eq!(sink.output_files()[0].records, Some(2));
+        assert_eq!(sink.dropped_fields(), vec!["Extra"]);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "City,ListPrice,ListingKey\n\"Austin, TX\",500000,A\nDallas,,B\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_partitioned_sink_writes_hive_layout() {
+        let root = std::env::temp_dir().join(format!("reso-partitioned-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let partitions = vec!["City".parse().unwrap(), "date".parse().unwrap()];
+        let mut sink = PartitionedSink::jsonl(&root, partitions);
+        sink.write_batch(&[
+            json!({"ListingKey": "A", "City": "Austin", "ModificationTimestamp": "2025-01-02T10:00:00Z"}),
+            json!({"ListingKey": "B", "City": "Austin", "ModificationTimestamp": "2025-01-02T11:00:00Z"}),
+            json!({"ListingKey": "C", "City": "A/B", "ModificationTimestamp": null}),
+        ])
+        .await
+        .unwrap();
+        sink.finalize().await.unwrap();
+
+        let austin = root.join("City=Austin/ModificationTimestamp_date=2025-01-02/part.jsonl");
+        assert_eq!(std::fs::read_to_string(austin).unwrap().lines().count(), 2);
+        assert!(root
+            .join("City=A%2FB/ModificationTimestamp_date=__HIVE_DEFAULT_PARTITION__/part.jsonl")
+            .exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_partitioned_sink_closes_least_recent_partition() {
+        let root = std::env::temp_dir().join(format!("reso-partitioned-lru-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let mut sink = PartitionedSink::jsonl(&root, vec!["City".parse().unwrap()]).max_open(1);
+        for city in ["Austin", "Dallas", "Austin"] {
+            sink.write_batch(&[json!({"ListingKey": city, "City": city})]).await.unwrap();
+            assert_eq!(sink.sinks.len(), 1);
+        }
+        sink.finalize().await.unwrap();
+
+        let austin = root.join("City=Austin/part.jsonl");
+        assert_eq!(std::fs::read_to_string(&austin).unwrap().lines().count(), 2);
+        let mut paths: Vec<PathBuf> = sink.output_files().into_iter().map(|file| file.path).collect();
+        paths.sort();
+        assert_eq!(paths, [austin, root.join("City=Dallas/part.jsonl")]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_default_key_field() {
+        assert_eq!(default_key_field("Property"), "ListingKey");
+        assert_eq!(default_key_field("Member"), "MemberKey");
+    }
+}
@@ -0,0 +1,240 @@
+//! One listing search across several MLS datasets.
+//!
+//! Portals covering several MLSs show one result list, not one per feed.
+//! [`federated_search`] sends a [`PropertySearch`] to every dataset in a
+//! [`ClientPool`] at once, normalizes the results so feeds with different
+//! vocabularies compare alike (`StandardStatus` through a [`StatusMapping`],
+//! and a category through a [`Taxonomy`]), merges them in the search's sort
+//! order, and marks each record with the dataset it came from.
+//!
+//! Listings that appear in more than one MLS are returned once per feed;
+//! [`duplicates`](crate::duplicates) can flag them.
+
+use crate::client::ResoApi;
+use crate::listing_status::StatusMapping;
+use crate::pool::ClientPool;
+use crate::search::{PropertySearch, DEFAULT_LIMIT};
+use crate::taxonomy::Taxonomy;
+use futures::future::join_all;
+use reso_client::{JsonValue, ResoError};
+use std::cmp::Ordering;
+
+/// Field holding the dataset a federated result came from.
+pub const SOURCE_FIELD: &str = "X_SourceDataset";
+
+/// Merged results of a [`federated_search`].
+#[derive(Debug, Default)]
+pub struct FederatedResults {
+    /// Records from every dataset that answered, in the search's order
+    pub records: Vec<JsonValue>,
+    /// Datasets whose search failed, with the error
+    pub failures: Vec<(String, ResoError)>,
+}
+
+/// Runs `search` against every dataset in `pool` concurrently and merges
+/// the results, normalizing them with the built-in status and property
+/// type tables.
+///
+/// Results are sorted by the search's order (nulls last), or interleaved
+/// one dataset at a time when it has none, and `skip` and `top` apply to
+/// the merged list: each dataset is asked for its first `skip + top`
+/// records. A dataset that fails is reported in
+/// [`failures`](FederatedResults::failures) and the others are still
+/// returned.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::federated::{federated_search, SOURCE_FIELD};
+/// use reso_examples::pool::ClientPool;
+/// use reso_examples::search::PropertySearch;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // RESO_DATASETS=actris_ref,har
+///     let pool = ClientPool::from_env_with(Ok)?;
+///     let search = PropertySearch::new().min_beds(3).active().cheapest_first().top(20);
+///
+///     let results = federated_search(&pool, &search).await?;
+///     for listing in &results.records {
+///         println!("{} {} {}", listing[SOURCE_FIELD], listing["ListingKey"], listing["ListPrice"]);
+///     }
+///     for (dataset, error) in &results.failures {
+///         eprintln!("{} unavailable: {}", dataset, error);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn federated_search<C: ResoApi>(
+    pool: &ClientPool<C>,
+    search: &PropertySearch,
+) -> Result<FederatedResults, ResoError> {
+    federated_search_with(pool, search, &StatusMapping::default(), &Taxonomy::default()).await
+}
+
+/// Like [`federated_search`], normalizing with the given tables, e.g. ones
+/// extended with each MLS's own status values.
+pub async fn federated_search_with<C: ResoApi>(
+    pool: &ClientPool<C>,
+    search: &PropertySearch,
+    statuses: &StatusMapping,
+    taxonomy: &Taxonomy,
+) -> Result<FederatedResults, ResoError> {
+    let skip = search.skip.unwrap_or(0);
+    let top = search.top.unwrap_or(DEFAULT_LIMIT);
+    let mut per_dataset = search.clone();
+    per_dataset.skip = None;
+    per_dataset.top = Some(skip.saturating_add(top));
+    let query = per_dataset.build()?;
+
+    let datasets: Vec<&str> = pool.datasets().collect();
+    let responses = join_all(datasets.iter().map(|dataset| {
+        let client = pool.get(dataset);
+        let query = &query;
+        async move {
+            match client {
+                Some(client) => client.execute(query).await,
+                None => Err(ResoError::Config(format!("Unknown dataset '{}'", dataset))),
+            }
+        }
+    }))
+    .await;
+
+    let mut results = FederatedResults::default();
+    let mut lists = Vec::new();
+    for (dataset, response) in datasets.into_iter().zip(responses) {
+        let mut response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                results.failures.push((dataset.to_string(), e));
+                continue;
+            }
+        };
+        let mut records = match response["value"].take() {
+            JsonValue::Array(records) => records,
+            _ => Vec::new(),
+        };
+        for record in &mut records {
+            statuses.apply(record);
+            taxonomy.apply(record);
+            if let Some(fields) = record.as_object_mut() {
+                fields.insert(SOURCE_FIELD.to_string(), dataset.into());
+            }
+        }
+        lists.push(records);
+    }
+
+    let merged = match &search.order_by {
+        Some((field, direction)) => {
+            let descending = direction.eq_ignore_ascii_case("desc");
+            let mut merged: Vec<JsonValue> = lists.into_iter().flatten().collect();
+            // Stable, so ties keep dataset order
+            merged.sort_by(|a, b| compare_field(field_value(a, field), field_value(b, field), descending));
+            merged
+        }
+        None => interleave(lists),
+    };
+    results.records = merged.into_iter().skip(skip as usize).take(top as usize).collect();
+    Ok(results)
+}
+
+/// A field of a record by property path, e.g. `ListOffice/OfficeName`.
+fn field_value<'a>(record: &'a JsonValue, path: &str) -> &'a JsonValue {
+    path.split('/').fold(record, |value, segment| &value[segment])
+}
+
+/// Orders two field values, with nulls (and missing fields) last in either
+/// direction.
+fn compare_field(a: &JsonValue, b: &JsonValue, descending: bool) -> Ordering {
+    let ordering = match (a, b) {
+        (JsonValue::Null, JsonValue::Null) => return Ordering::Equal,
+        (JsonValue::Null, _) => return Ordering::Greater,
+        (_, JsonValue::Null) => return Ordering::Less,
+        (JsonValue::Number(x), JsonValue::Number(y)) => {
+            x.as_f64().partial_cmp(&y.as_f64()).unwrap_or(Ordering::Equal)
+        }
+        (JsonValue::String(x), JsonValue::String(y)) => x.cmp(y),
+        (JsonValue::Bool(x), JsonValue::Bool(y)) => x.cmp(y),
+        _ => Ordering::Equal,
+    };
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// Takes one record from each list in turn.
+fn interleave(lists: Vec<Vec<JsonValue>>) -> Vec<JsonValue> {
+    let mut iters: Vec<_> = lists.into_iter().map(Vec::into_iter).collect();
+    let mut merged = Vec::new();
+    loop {
+        let before = merged.len();
+        merged.extend(iters.iter_mut().filter_map(Iterator::next));
+        if merged.len() == before {
+            return merged;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reso_client::ClientConfig;
+    use serde_json::json;
+    use wiremock::matchers::{path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_merges_normalizes_and_reports_failures() {
+        let server = MockServer::start().await;
+        let listings = [
+            ("actris_ref", json!([
+                {"ListingKey": "A1", "ListPrice": 300000, "MlsStatus": "Active Option Contract", "PropertyType": "Residential", "PropertySubType": "Condominium"},
+                {"ListingKey": "A2", "ListPrice": 500000, "StandardStatus": "Active"}
+            ])),
+            ("har", json!([
+                {"ListingKey": "H1", "ListPrice": 250000, "StandardStatus": "Active"},
+                {"ListingKey": "H2", "ListPrice": 400000, "StandardStatus": "Active"}
+            ])),
+        ];
+        for (dataset, records) in listings {
+            // Each dataset is asked for skip + top records, unskipped
+            Mock::given(path(format!("/{}/Property", dataset)))
+                .and(query_param("$top", "3"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": records})))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(path("/crmls/Property"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let pool = ClientPool::from_config(&ClientConfig::new(server.uri(), "token"), &["actris_ref", "crmls", "har"], Ok)
+            .unwrap();
+        let search = PropertySearch::new().cheapest_first().skip(1).top(2);
+        let results = federated_search(&pool, &search).await.unwrap();
+
+        let keys: Vec<&str> = results.records.iter().map(|r| r["ListingKey"].as_str().unwrap()).collect();
+        assert_eq!(keys, ["A1", "H2"]);
+        assert_eq!(results.records[0][SOURCE_FIELD], "actris_ref");
+        assert_eq!(results.records[0]["StandardStatus"], "Active Under Contract");
+        assert_eq!(results.records[0][crate::taxonomy::CATEGORY_FIELD], "condo");
+        assert_eq!(results.records[1][SOURCE_FIELD], "har");
+        assert_eq!(results.failures.len(), 1);
+        assert_eq!(results.failures[0].0, "crmls");
+    }
+
+    #[test]
+    fn test_ordering_puts_nulls_last_and_interleaves_unordered() {
+        let values = [json!(2), JsonValue::Null, json!(1)];
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| compare_field(a, b, true));
+        assert_eq!(sorted, [json!(2), json!(1), JsonValue::Null]);
+
+        let merged = interleave(vec![vec![json!("a1"), json!("a2"), json!("a3")], vec![json!("b1")]]);
+        assert_eq!(merged, [json!("a1"), json!("b1"), json!("a2"), json!("a3")]);
+    }
+}
@@ -0,0 +1,1287 @@
+//! RESO Data Dictionary field name constants.
+//!
+//! Generated by `codegen::dictionary_field_constants`; do not edit by hand.
+
+pub mod property {
+    pub const ABOVE_GRADE_FINISHED_AREA: &str = "AboveGradeFinishedArea";
+    pub const ABOVE_GRADE_FINISHED_AREA_SOURCE: &str = "AboveGradeFinishedAreaSource";
+    pub const ABOVE_GRADE_FINISHED_AREA_UNITS: &str = "AboveGradeFinishedAreaUnits";
+    pub const ACCESS_CODE: &str = "AccessCode";
+    pub const ACCESSIBILITY_FEATURES: &str = "AccessibilityFeatures";
+    pub const ADDITIONAL_PARCELS_DESCRIPTION: &str = "AdditionalParcelsDescription";
+    pub const ADDITIONAL_PARCELS_YN: &str = "AdditionalParcelsYN";
+    pub const ANCHORS_CO_TENANTS: &str = "AnchorsCoTenants";
+    pub const APPLIANCES: &str = "Appliances";
+    pub const ARCHITECTURAL_STYLE: &str = "ArchitecturalStyle";
+    pub const ASSOCIATION_AMENITIES: &str = "AssociationAmenities";
+    pub const ASSOCIATION_FEE: &str = "AssociationFee";
+    pub const ASSOCIATION_FEE2: &str = "AssociationFee2";
+    pub const ASSOCIATION_FEE2_FREQUENCY: &str = "AssociationFee2Frequency";
+    pub const ASSOCIATION_FEE_FREQUENCY: &str = "AssociationFeeFrequency";
+    pub const ASSOCIATION_FEE_INCLUDES: &str = "AssociationFeeIncludes";
+    pub const ASSOCIATION_NAME: &str = "AssociationName";
+    pub const ASSOCIATION_NAME2: &str = "AssociationName2";
+    pub const ASSOCIATION_PHONE: &str = "AssociationPhone";
+    pub const ASSOCIATION_PHONE2: &str = "AssociationPhone2";
+    pub const ASSOCIATION_YN: &str = "AssociationYN";
+    pub const ATTACHED_GARAGE_YN: &str = "AttachedGarageYN";
+    pub const AVAILABILITY_DATE: &str = "AvailabilityDate";
+    pub const BASEMENT: &str = "Basement";
+    pub const BASEMENT_YN: &str = "BasementYN";
+    pub const BATHROOMS_FULL: &str = "BathroomsFull";
+    pub const BATHROOMS_HALF: &str = "BathroomsHalf";
+    pub const BATHROOMS_ONE_QUARTER: &str = "BathroomsOneQuarter";
+    pub const BATHROOMS_PARTIAL: &str = "BathroomsPartial";
+    pub const BATHROOMS_THREE_QUARTER: &str = "BathroomsThreeQuarter";
+    pub const BATHROOMS_TOTAL_INTEGER: &str = "BathroomsTotalInteger";
+    pub const BEDROOMS_POSSIBLE: &str = "BedroomsPossible";
+    pub const BEDROOMS_TOTAL: &str = "BedroomsTotal";
+    pub const BELOW_GRADE_FINISHED_AREA: &str = "BelowGradeFinishedArea";
+    pub const BELOW_GRADE_FINISHED_AREA_SOURCE: &str = "BelowGradeFinishedAreaSource";
+    pub const BELOW_GRADE_FINISHED_AREA_UNITS: &str = "BelowGradeFinishedAreaUnits";
+    pub const BODY_TYPE: &str = "BodyType";
+    pub const BUILDER_MODEL: &str = "BuilderModel";
+    pub const BUILDER_NAME: &str = "BuilderName";
+    pub const BUILDING_AREA_SOURCE: &str = "BuildingAreaSource";
+    pub const BUILDING_AREA_TOTAL: &str = "BuildingAreaTotal";
+    pub const BUILDING_AREA_UNITS: &str = "BuildingAreaUnits";
+    pub const BUILDING_FEATURES: &str = "BuildingFeatures";
+    pub const BUILDING_NAME: &str = "BuildingName";
+    pub const BUSINESS_NAME: &str = "BusinessName";
+    pub const BUSINESS_TYPE: &str = "BusinessType";
+    pub const BUYER_AGENCY_COMPENSATION: &str = "BuyerAgencyCompensation";
+    pub const BUYER_AGENCY_COMPENSATION_TYPE: &str = "BuyerAgencyCompensationType";
+    pub const BUYER_AGENT_AOR: &str = "BuyerAgentAOR";
+    pub const BUYER_AGENT_DESIGNATION: &str = "BuyerAgentDesignation";
+    pub const BUYER_AGENT_DIRECT_PHONE: &str = "BuyerAgentDirectPhone";
+    pub const BUYER_AGENT_EMAIL: &str = "BuyerAgentEmail";
+    pub const BUYER_AGENT_FIRST_NAME: &str = "BuyerAgentFirstName";
+    pub const BUYER_AGENT_FULL_NAME: &str = "BuyerAgentFullName";
+    pub const BUYER_AGENT_KEY: &str = "BuyerAgentKey";
+    pub const BUYER_AGENT_LAST_NAME: &str = "BuyerAgentLastName";
+    pub const BUYER_AGENT_MLS_ID: &str = "BuyerAgentMlsId";
+    pub const BUYER_AGENT_STATE_LICENSE: &str = "BuyerAgentStateLicense";
+    pub const BUYER_FINANCING: &str = "BuyerFinancing";
+    pub const BUYER_OFFICE_AOR: &str = "BuyerOfficeAOR";
+    pub const BUYER_OFFICE_KEY: &str = "BuyerOfficeKey";
+    pub const BUYER_OFFICE_MLS_ID: &str = "BuyerOfficeMlsId";
+    pub const BUYER_OFFICE_NAME: &str = "BuyerOfficeName";
+    pub const BUYER_OFFICE_PHONE: &str = "BuyerOfficePhone";
+    pub const CABLE_TV_EXPENSE: &str = "CableTvExpense";
+    pub const CANCELLATION_DATE: &str = "CancellationDate";
+    pub const CAP_RATE: &str = "CapRate";
+    pub const CARPORT_SPACES: &str = "CarportSpaces";
+    pub const CARPORT_YN: &str = "CarportYN";
+    pub const CARRIER_ROUTE: &str = "CarrierRoute";
+    pub const CITY: &str = "City";
+    pub const CITY_REGION: &str = "CityRegion";
+    pub const CLOSE_DATE: &str = "CloseDate";
+    pub const CLOSE_PRICE: &str = "ClosePrice";
+    pub const CO_BUYER_AGENT_FULL_NAME: &str = "CoBuyerAgentFullName";
+    pub const CO_BUYER_AGENT_KEY: &str = "CoBuyerAgentKey";
+    pub const CO_BUYER_AGENT_MLS_ID: &str = "CoBuyerAgentMlsId";
+    pub const CO_BUYER_OFFICE_KEY: &str = "CoBuyerOfficeKey";
+    pub const CO_BUYER_OFFICE_MLS_ID: &str = "CoBuyerOfficeMlsId";
+    pub const CO_BUYER_OFFICE_NAME: &str = "CoBuyerOfficeName";
+    pub const CO_LIST_AGENT_FULL_NAME: &str = "CoListAgentFullName";
+    pub const CO_LIST_AGENT_KEY: &str = "CoListAgentKey";
+    pub const CO_LIST_AGENT_MLS_ID: &str = "CoListAgentMlsId";
+    pub const CO_LIST_OFFICE_KEY: &str = "CoListOfficeKey";
+    pub const CO_LIST_OFFICE_MLS_ID: &str = "CoListOfficeMlsId";
+    pub const CO_LIST_OFFICE_NAME: &str = "CoListOfficeName";
+    pub const COMMON_INTEREST: &str = "CommonInterest";
+    pub const COMMON_WALLS: &str = "CommonWalls";
+    pub const COMMUNITY_FEATURES: &str = "CommunityFeatures";
+    pub const CONCESSIONS: &str = "Concessions";
+    pub const CONCESSIONS_AMOUNT: &str = "ConcessionsAmount";
+    pub const CONCESSIONS_COMMENTS: &str = "ConcessionsComments";
+    pub const CONSTRUCTION_MATERIALS: &str = "ConstructionMaterials";
+    pub const CONTINENT_REGION: &str = "ContinentRegion";
+    pub const CONTINGENCY: &str = "Contingency";
+    pub const CONTINGENT_DATE: &str = "ContingentDate";
+    pub const CONTRACT_STATUS_CHANGE_DATE: &str = "ContractStatusChangeDate";
+    pub const COOLING: &str = "Cooling";
+    pub const COOLING_YN: &str = "CoolingYN";
+    pub const COPYRIGHT_NOTICE: &str = "CopyrightNotice";
+    pub const COUNTRY: &str = "Country";
+    pub const COUNTRY_REGION: &str = "CountryRegion";
+    pub const COUNTY_OR_PARISH: &str = "CountyOrParish";
+    pub const COVERED_SPACES: &str = "CoveredSpaces";
+    pub const CROPS_INCLUDED_YN: &str = "CropsIncludedYN";
+    pub const CROSS_STREET: &str = "CrossStreet";
+    pub const CULTIVATED_AREA: &str = "CultivatedArea";
+    pub const CUMULATIVE_DAYS_ON_MARKET: &str = "CumulativeDaysOnMarket";
+    pub const CURRENT_FINANCING: &str = "CurrentFinancing";
+    pub const CURRENT_USE: &str = "CurrentUse";
+    pub const DOH1: &str = "DOH1";
+    pub const DOH2: &str = "DOH2";
+    pub const DOH3: &str = "DOH3";
+    pub const DAYS_ON_MARKET: &str = "DaysOnMarket";
+    pub const DEVELOPMENT_STATUS: &str = "DevelopmentStatus";
+    pub const DIRECTION_FACES: &str = "DirectionFaces";
+    pub const DIRECTIONS: &str = "Directions";
+    pub const DISCLAIMER: &str = "Disclaimer";
+    pub const DISCLOSURES: &str = "Disclosures";
+    pub const DISTANCE_TO_BUS_COMMENTS: &str = "DistanceToBusComments";
+    pub const DISTANCE_TO_ELECTRIC_COMMENTS: &str = "DistanceToElectricComments";
+    pub const DISTANCE_TO_SCHOOLS_COMMENTS: &str = "DistanceToSchoolsComments";
+    pub const DISTANCE_TO_SEWER_COMMENTS: &str = "DistanceToSewerComments";
+    pub const DISTANCE_TO_WATER_COMMENTS: &str = "DistanceToWaterComments";
+    pub const DOCUMENTS_AVAILABLE: &str = "DocumentsAvailable";
+    pub const DOCUMENTS_CHANGE_TIMESTAMP: &str = "DocumentsChangeTimestamp";
+    pub const DOCUMENTS_COUNT: &str = "DocumentsCount";
+    pub const DOOR_FEATURES: &str = "DoorFeatures";
+    pub const DUAL_VARIABLE_COMPENSATION_YN: &str = "DualVariableCompensationYN";
+    pub const ELECTRIC: &str = "Electric";
+    pub const ELECTRIC_EXPENSE: &str = "ElectricExpense";
+    pub const ELECTRIC_ON_PROPERTY_YN: &str = "ElectricOnPropertyYN";
+    pub const ELEMENTARY_SCHOOL: &str = "ElementarySchool";
+    pub const ELEMENTARY_SCHOOL_DISTRICT: &str = "ElementarySchoolDistrict";
+    pub const ELEVATION: &str = "Elevation";
+    pub const ELEVATION_UNITS: &str = "ElevationUnits";
+    pub const ENTRY_LEVEL: &str = "EntryLevel";
+    pub const ENTRY_LOCATION: &str = "EntryLocation";
+    pub const EXCLUSIONS: &str = "Exclusions";
+    pub const EXISTING_LEASE_TYPE: &str = "ExistingLeaseType";
+    pub const EXPIRATION_DATE: &str = "ExpirationDate";
+    pub const EXTERIOR_FEATURES: &str = "ExteriorFeatures";
+    pub const FARM_CREDIT_SERVICE_INCL_YN: &str = "FarmCreditServiceInclYN";
+    pub const FARM_LAND_AREA_SOURCE: &str = "FarmLandAreaSource";
+    pub const FARM_LAND_AREA_UNITS: &str = "FarmLandAreaUnits";
+    pub const FENCING: &str = "Fencing";
+    pub const FINANCIAL_DATA_SOURCE: &str = "FinancialDataSource";
+    pub const FIREPLACE_FEATURES: &str = "FireplaceFeatures";
+    pub const FIREPLACE_YN: &str = "FireplaceYN";
+    pub const FIREPLACES_TOTAL: &str = "FireplacesTotal";
+    pub const FLOORING: &str = "Flooring";
+    pub const FOUNDATION_AREA: &str = "FoundationArea";
+    pub const FOUNDATION_DETAILS: &str = "FoundationDetails";
+    pub const FRONTAGE_LENGTH: &str = "FrontageLength";
+    pub const FRONTAGE_TYPE: &str = "FrontageType";
+    pub const FUEL_EXPENSE: &str = "FuelExpense";
+    pub const FURNISHED: &str = "Furnished";
+    pub const FURNITURE_REPLACEMENT_EXPENSE: &str = "FurnitureReplacementExpense";
+    pub const GARAGE_SPACES: &str = "GarageSpaces";
+    pub const GARAGE_YN: &str = "GarageYN";
+    pub const GARDENER_EXPENSE: &str = "GardenerExpense";
+    pub const GRAZING_PERMITS_BLM_YN: &str = "GrazingPermitsBlmYN";
+    pub const GRAZING_PERMITS_FOREST_SERVICE_YN: &str = "GrazingPermitsForestServiceYN";
+    pub const GRAZING_PERMITS_PRIVATE_YN: &str = "GrazingPermitsPrivateYN";
+    pub const GREEN_BUILDING_VERIFICATION_TYPE: &str = "GreenBuildingVerificationType";
+    pub const GREEN_ENERGY_EFFICIENT: &str = "GreenEnergyEfficient";
+    pub const GREEN_ENERGY_GENERATION: &str = "GreenEnergyGeneration";
+    pub const GREEN_INDOOR_AIR_QUALITY: &str = "GreenIndoorAirQuality";
+    pub const GREEN_LOCATION: &str = "GreenLocation";
+    pub const GREEN_SUSTAINABILITY: &str = "GreenSustainability";
+    pub const GREEN_WATER_CONSERVATION: &str = "GreenWaterConservation";
+    pub const GROSS_INCOME: &str = "GrossIncome";
+    pub const GROSS_SCHEDULED_INCOME: &str = "GrossScheduledIncome";
+    pub const HABITABLE_RESIDENCE_YN: &str = "HabitableResidenceYN";
+    pub const HEATING: &str = "Heating";
+    pub const HEATING_YN: &str = "HeatingYN";
+    pub const HIGH_SCHOOL: &str = "HighSchool";
+    pub const HIGH_SCHOOL_DISTRICT: &str = "HighSchoolDistrict";
+    pub const HOME_WARRANTY_YN: &str = "HomeWarrantyYN";
+    pub const HORSE_AMENITIES: &str = "HorseAmenities";
+    pub const HORSE_YN: &str = "HorseYN";
+    pub const HOURS_DAYS_OF_OPERATION: &str = "HoursDaysOfOperation";
+    pub const HOURS_DAYS_OF_OPERATION_DESCRIPTION: &str = "HoursDaysOfOperationDescription";
+    pub const INCLUSIONS: &str = "Inclusions";
+    pub const INCOME_INCLUDES: &str = "IncomeIncludes";
+    pub const INSURANCE_EXPENSE: &str = "InsuranceExpense";
+    pub const INTERIOR_FEATURES: &str = "InteriorFeatures";
+    pub const INTERNET_ADDRESS_DISPLAY_YN: &str = "InternetAddressDisplayYN";
+    pub const INTERNET_AUTOMATED_VALUATION_DISPLAY_YN: &str = "InternetAutomatedValuationDisplayYN";
+    pub const INTERNET_CONSUMER_COMMENT_YN: &str = "InternetConsumerCommentYN";
+    pub const INTERNET_ENTIRE_LISTING_DISPLAY_YN: &str = "InternetEntireListingDisplayYN";
+    pub const IRRIGATION_SOURCE: &str = "IrrigationSource";
+    pub const IRRIGATION_WATER_RIGHTS_ACRES: &str = "IrrigationWaterRightsAcres";
+    pub const IRRIGATION_WATER_RIGHTS_YN: &str = "IrrigationWaterRightsYN";
+    pub const LABOR_INFORMATION: &str = "LaborInformation";
+    pub const LAND_LEASE_AMOUNT: &str = "LandLeaseAmount";
+    pub const LAND_LEASE_AMOUNT_FREQUENCY: &str = "LandLeaseAmountFrequency";
+    pub const LAND_LEASE_EXPIRATION_DATE: &str = "LandLeaseExpirationDate";
+    pub const LAND_LEASE_YN: &str = "LandLeaseYN";
+    pub const LATITUDE: &str = "Latitude";
+    pub const LAUNDRY_FEATURES: &str = "LaundryFeatures";
+    pub const LEASABLE_AREA: &str = "LeasableArea";
+    pub const LEASABLE_AREA_UNITS: &str = "LeasableAreaUnits";
+    pub const LEASE_AMOUNT: &str = "LeaseAmount";
+    pub const LEASE_AMOUNT_FREQUENCY: &str = "LeaseAmountFrequency";
+    pub const LEASE_ASSIGNABLE_YN: &str = "LeaseAssignableYN";
+    pub const LEASE_CONSIDERED_YN: &str = "LeaseConsideredYN";
+    pub const LEASE_EXPIRATION: &str = "LeaseExpiration";
+    pub const LEASE_RENEWAL_COMPENSATION: &str = "LeaseRenewalCompensation";
+    pub const LEASE_RENEWAL_OPTION_YN: &str = "LeaseRenewalOptionYN";
+    pub const LEASE_TERM: &str = "LeaseTerm";
+    pub const LEVELS: &str = "Levels";
+    pub const LICENSE1: &str = "License1";
+    pub const LICENSE2: &str = "License2";
+    pub const LICENSE3: &str = "License3";
+    pub const LICENSES_EXPENSE: &str = "LicensesExpense";
+    pub const LIST_AOR: &str = "ListAOR";
+    pub const LIST_AGENT_AOR: &str = "ListAgentAOR";
+    pub const LIST_AGENT_DESIGNATION: &str = "ListAgentDesignation";
+    pub const LIST_AGENT_DIRECT_PHONE: &str = "ListAgentDirectPhone";
+    pub const LIST_AGENT_EMAIL: &str = "ListAgentEmail";
+    pub const LIST_AGENT_FIRST_NAME: &str = "ListAgentFirstName";
+    pub const LIST_AGENT_FULL_NAME: &str = "ListAgentFullName";
+    pub const LIST_AGENT_KEY: &str = "ListAgentKey";
+    pub const LIST_AGENT_LAST_NAME: &str = "ListAgentLastName";
+    pub const LIST_AGENT_MLS_ID: &str = "ListAgentMlsId";
+    pub const LIST_AGENT_STATE_LICENSE: &str = "ListAgentStateLicense";
+    pub const LIST_OFFICE_AOR: &str = "ListOfficeAOR";
+    pub const LIST_OFFICE_KEY: &str = "ListOfficeKey";
+    pub const LIST_OFFICE_MLS_ID: &str = "ListOfficeMlsId";
+    pub const LIST_OFFICE_NAME: &str = "ListOfficeName";
+    pub const LIST_OFFICE_PHONE: &str = "ListOfficePhone";
+    pub const LIST_PRICE: &str = "ListPrice";
+    pub const LIST_PRICE_LOW: &str = "ListPriceLow";
+    pub const LISTING_AGREEMENT: &str = "ListingAgreement";
+    pub const LISTING_CONTRACT_DATE: &str = "ListingContractDate";
+    pub const LISTING_ID: &str = "ListingId";
+    pub const LISTING_KEY: &str = "ListingKey";
+    pub const LISTING_SERVICE: &str = "ListingService";
+    pub const LISTING_TERMS: &str = "ListingTerms";
+    pub const LISTING_URL: &str = "ListingURL";
+    pub const LIVING_AREA: &str = "LivingArea";
+    pub const LIVING_AREA_SOURCE: &str = "LivingAreaSource";
+    pub const LIVING_AREA_UNITS: &str = "LivingAreaUnits";
+    pub const LOCK_BOX_LOCATION: &str = "LockBoxLocation";
+    pub const LOCK_BOX_SERIAL_NUMBER: &str = "LockBoxSerialNumber";
+    pub const LOCK_BOX_TYPE: &str = "LockBoxType";
+    pub const LONGITUDE: &str = "Longitude";
+    pub const LOT_DIMENSIONS_SOURCE: &str = "LotDimensionsSource";
+    pub const LOT_FEATURES: &str = "LotFeatures";
+    pub const LOT_SIZE_ACRES: &str = "LotSizeAcres";
+    pub const LOT_SIZE_AREA: &str = "LotSizeArea";
+    pub const LOT_SIZE_DIMENSIONS: &str = "LotSizeDimensions";
+    pub const LOT_SIZE_SOURCE: &str = "LotSizeSource";
+    pub const LOT_SIZE_SQUARE_FEET: &str = "LotSizeSquareFeet";
+    pub const LOT_SIZE_UNITS: &str = "LotSizeUnits";
+    pub const MLS_AREA_MAJOR: &str = "MLSAreaMajor";
+    pub const MLS_AREA_MINOR: &str = "MLSAreaMinor";
+    pub const MAIN_LEVEL_BATHROOMS: &str = "MainLevelBathrooms";
+    pub const MAIN_LEVEL_BEDROOMS: &str = "MainLevelBedrooms";
+    pub const MAINTENANCE_EXPENSE: &str = "MaintenanceExpense";
+    pub const MAJOR_CHANGE_TIMESTAMP: &str = "MajorChangeTimestamp";
+    pub const MAJOR_CHANGE_TYPE: &str = "MajorChangeType";
+    pub const MAKE: &str = "Make";
+    pub const MANAGER_EXPENSE: &str = "ManagerExpense";
+    pub const MAP_COORDINATE: &str = "MapCoordinate";
+    pub const MAP_COORDINATE_SOURCE: &str = "MapCoordinateSource";
+    pub const MAP_URL: &str = "MapURL";
+    pub const MIDDLE_OR_JUNIOR_SCHOOL: &str = "MiddleOrJuniorSchool";
+    pub const MIDDLE_OR_JUNIOR_SCHOOL_DISTRICT: &str = "MiddleOrJuniorSchoolDistrict";
+    pub const MLS_STATUS: &str = "MlsStatus";
+    pub const MOBILE_DIM_UNITS: &str = "MobileDimUnits";
+    pub const MOBILE_HOME_REMAINS_YN: &str = "MobileHomeRemainsYN";
+    pub const MOBILE_LENGTH: &str = "MobileLength";
+    pub const MOBILE_WIDTH: &str = "MobileWidth";
+    pub const MODEL: &str = "Model";
+    pub const MODIFICATION_TIMESTAMP: &str = "ModificationTimestamp";
+    pub const NET_OPERATING_INCOME: &str = "NetOperatingIncome";
+    pub const NEW_CONSTRUCTION_YN: &str = "NewConstructionYN";
+    pub const NEW_TAXES_EXPENSE: &str = "NewTaxesExpense";
+    pub const NUMBER_OF_BUILDINGS: &str = "NumberOfBuildings";
+    pub const NUMBER_OF_FULL_TIME_EMPLOYEES: &str = "NumberOfFullTimeEmployees";
+    pub const NUMBER_OF_LOTS: &str = "NumberOfLots";
+    pub const NUMBER_OF_PADS: &str = "NumberOfPads";
+    pub const NUMBER_OF_PART_TIME_EMPLOYEES: &str = "NumberOfPartTimeEmployees";
+    pub const NUMBER_OF_SEPARATE_ELECTRIC_METERS: &str = "NumberOfSeparateElectricMeters";
+    pub const NUMBER_OF_SEPARATE_GAS_METERS: &str = "NumberOfSeparateGasMeters";
+    pub const NUMBER_OF_SEPARATE_WATER_METERS: &str = "NumberOfSeparateWaterMeters";
+    pub const NUMBER_OF_UNITS_IN_COMMUNITY: &str = "NumberOfUnitsInCommunity";
+    pub const NUMBER_OF_UNITS_LEASED: &str = "NumberOfUnitsLeased";
+    pub const NUMBER_OF_UNITS_MO_MO: &str = "NumberOfUnitsMoMo";
+    pub const NUMBER_OF_UNITS_TOTAL: &str = "NumberOfUnitsTotal";
+    pub const NUMBER_OF_UNITS_VACANT: &str = "NumberOfUnitsVacant";
+    pub const OCCUPANT_NAME: &str = "OccupantName";
+    pub const OCCUPANT_PHONE: &str = "OccupantPhone";
+    pub const OCCUPANT_TYPE: &str = "OccupantType";
+    pub const OFF_MARKET_DATE: &str = "OffMarketDate";
+    pub const OFF_MARKET_TIMESTAMP: &str = "OffMarketTimestamp";
+    pub const ON_MARKET_DATE: &str = "OnMarketDate";
+    pub const ON_MARKET_TIMESTAMP: &str = "OnMarketTimestamp";
+    pub const OPEN_PARKING_SPACES: &str = "OpenParkingSpaces";
+    pub const OPEN_PARKING_YN: &str = "OpenParkingYN";
+    pub const OPERATING_EXPENSE: &str = "OperatingExpense";
+    pub const OPERATING_EXPENSE_INCLUDES: &str = "OperatingExpenseIncludes";
+    pub const ORIGINAL_ENTRY_TIMESTAMP: &str = "OriginalEntryTimestamp";
+    pub const ORIGINAL_LIST_PRICE: &str = "OriginalListPrice";
+    pub const ORIGINATING_SYSTEM_ID: &str = "OriginatingSystemID";
+    pub const ORIGINATING_SYSTEM_KEY: &str = "OriginatingSystemKey";
+    pub const ORIGINATING_SYSTEM_NAME: &str = "OriginatingSystemName";
+    pub const OTHER_EQUIPMENT: &str = "OtherEquipment";
+    pub const OTHER_EXPENSE: &str = "OtherExpense";
+    pub const OTHER_PARKING: &str = "OtherParking";
+    pub const OTHER_STRUCTURES: &str = "OtherStructures";
+    pub const OWNER_NAME: &str = "OwnerName";
+    pub const OWNER_PAYS: &str = "OwnerPays";
+    pub const OWNER_PHONE: &str = "OwnerPhone";
+    pub const OWNERSHIP: &str = "Ownership";
+    pub const OWNERSHIP_TYPE: &str = "OwnershipType";
+    pub const PARCEL_NUMBER: &str = "ParcelNumber";
+    pub const PARK_MANAGER_NAME: &str = "ParkManagerName";
+    pub const PARK_MANAGER_PHONE: &str = "ParkManagerPhone";
+    pub const PARK_NAME: &str = "ParkName";
+    pub const PARKING_FEATURES: &str = "ParkingFeatures";
+    pub const PARKING_TOTAL: &str = "ParkingTotal";
+    pub const PASTURE_AREA: &str = "PastureArea";
+    pub const PATIO_AND_PORCH_FEATURES: &str = "PatioAndPorchFeatures";
+    pub const PENDING_TIMESTAMP: &str = "PendingTimestamp";
+    pub const PEST_CONTROL_EXPENSE: &str = "PestControlExpense";
+    pub const PETS_ALLOWED: &str = "PetsAllowed";
+    pub const PHOTOS_CHANGE_TIMESTAMP: &str = "PhotosChangeTimestamp";
+    pub const PHOTOS_COUNT: &str = "PhotosCount";
+    pub const POOL_EXPENSE: &str = "PoolExpense";
+    pub const POOL_FEATURES: &str = "PoolFeatures";
+    pub const POOL_PRIVATE_YN: &str = "PoolPrivateYN";
+    pub const POSSESSION: &str = "Possession";
+    pub const POSSIBLE_USE: &str = "PossibleUse";
+    pub const POSTAL_CITY: &str = "PostalCity";
+    pub const POSTAL_CODE: &str = "PostalCode";
+    pub const POSTAL_CODE_PLUS4: &str = "PostalCodePlus4";
+    pub const POWER_PRODUCTION_TYPE: &str = "PowerProductionType";
+    pub const PREVIOUS_LIST_PRICE: &str = "PreviousListPrice";
+    pub const PRICE_CHANGE_TIMESTAMP: &str = "PriceChangeTimestamp";
+    pub const PRIVATE_OFFICE_REMARKS: &str = "PrivateOfficeRemarks";
+    pub const PRIVATE_REMARKS: &str = "PrivateRemarks";
+    pub const PROFESSIONAL_MANAGEMENT_EXPENSE: &str = "ProfessionalManagementExpense";
+    pub const PROPERTY_ATTACHED_YN: &str = "PropertyAttachedYN";
+    pub const PROPERTY_CONDITION: &str = "PropertyCondition";
+    pub const PROPERTY_SUB_TYPE: &str = "PropertySubType";
+    pub const PROPERTY_TYPE: &str = "PropertyType";
+    pub const PUBLIC_REMARKS: &str = "PublicRemarks";
+    pub const PUBLIC_SURVEY_RANGE: &str = "PublicSurveyRange";
+    pub const PUBLIC_SURVEY_SECTION: &str = "PublicSurveySection";
+    pub const PUBLIC_SURVEY_TOWNSHIP: &str = "PublicSurveyTownship";
+    pub const PURCHASE_CONTRACT_DATE: &str = "PurchaseContractDate";
+    pub const RV_PARKING_DIMENSIONS: &str = "RVParkingDimensions";
+    pub const RANGE_AREA: &str = "RangeArea";
+    pub const RENT_CONTROL_YN: &str = "RentControlYN";
+    pub const RENT_INCLUDES: &str = "RentIncludes";
+    pub const ROAD_FRONTAGE_TYPE: &str = "RoadFrontageType";
+    pub const ROAD_RESPONSIBILITY: &str = "RoadResponsibility";
+    pub const ROAD_SURFACE_TYPE: &str = "RoadSurfaceType";
+    pub const ROOF: &str = "Roof";
+    pub const ROOM_TYPE: &str = "RoomType";
+    pub const ROOMS_TOTAL: &str = "RoomsTotal";
+    pub const SEATING_CAPACITY: &str = "SeatingCapacity";
+    pub const SECURITY_FEATURES: &str = "SecurityFeatures";
+    pub const SENIOR_COMMUNITY_YN: &str = "SeniorCommunityYN";
+    pub const SERIAL_U: &str = "SerialU";
+    pub const SERIAL_X: &str = "SerialX";
+    pub const SERIAL_XX: &str = "SerialXX";
+    pub const SEWER: &str = "Sewer";
+    pub const SHOWING_ADVANCE_NOTICE: &str = "ShowingAdvanceNotice";
+    pub const SHOWING_ATTENDED_YN: &str = "ShowingAttendedYN";
+    pub const SHOWING_CONTACT_NAME: &str = "ShowingContactName";
+    pub const SHOWING_CONTACT_PHONE: &str = "ShowingContactPhone";
+    pub const SHOWING_CONTACT_PHONE_EXT: &str = "ShowingContactPhoneExt";
+    pub const SHOWING_CONTACT_TYPE: &str = "ShowingContactType";
+    pub const SHOWING_DAYS: &str = "ShowingDays";
+    pub const SHOWING_END_TIME: &str = "ShowingEndTime";
+    pub const SHOWING_INSTRUCTIONS: &str = "ShowingInstructions";
+    pub const SHOWING_REQUIREMENTS: &str = "ShowingRequirements";
+    pub const SHOWING_START_TIME: &str = "ShowingStartTime";
+    pub const SIGN_ON_PROPERTY_YN: &str = "SignOnPropertyYN";
+    pub const SKIRT: &str = "Skirt";
+    pub const SOURCE_SYSTEM_ID: &str = "SourceSystemID";
+    pub const SOURCE_SYSTEM_KEY: &str = "SourceSystemKey";
+    pub const SOURCE_SYSTEM_NAME: &str = "SourceSystemName";
+    pub const SPA_FEATURES: &str = "SpaFeatures";
+    pub const SPA_YN: &str = "SpaYN";
+    pub const SPECIAL_LICENSES: &str = "SpecialLicenses";
+    pub const SPECIAL_LISTING_CONDITIONS: &str = "SpecialListingConditions";
+    pub const STANDARD_STATUS: &str = "StandardStatus";
+    pub const START_SHOWING_DATE: &str = "StartShowingDate";
+    pub const STATE_OR_PROVINCE: &str = "StateOrProvince";
+    pub const STATE_REGION: &str = "StateRegion";
+    pub const STATUS_CHANGE_TIMESTAMP: &str = "StatusChangeTimestamp";
+    pub const STORIES: &str = "Stories";
+    pub const STORIES_TOTAL: &str = "StoriesTotal";
+    pub const STREET_ADDITIONAL_INFO: &str = "StreetAdditionalInfo";
+    pub const STREET_DIR_PREFIX: &str = "StreetDirPrefix";
+    pub const STREET_DIR_SUFFIX: &str = "StreetDirSuffix";
+    pub const STREET_NAME: &str = "StreetName";
+    pub const STREET_NUMBER: &str = "StreetNumber";
+    pub const STREET_NUMBER_NUMERIC: &str = "StreetNumberNumeric";
+    pub const STREET_SUFFIX: &str = "StreetSuffix";
+    pub const STREET_SUFFIX_MODIFIER: &str = "StreetSuffixModifier";
+    pub const STRUCTURE_TYPE: &str = "StructureType";
+    pub const SUB_AGENCY_COMPENSATION: &str = "SubAgencyCompensation";
+    pub const SUB_AGENCY_COMPENSATION_TYPE: &str = "SubAgencyCompensationType";
+    pub const SUBDIVISION_NAME: &str = "SubdivisionName";
+    pub const SUPPLIES_EXPENSE: &str = "SuppliesExpense";
+    pub const SYNDICATE_TO: &str = "SyndicateTo";
+    pub const SYNDICATION_REMARKS: &str = "SyndicationRemarks";
+    pub const TAX_ANNUAL_AMOUNT: &str = "TaxAnnualAmount";
+    pub const TAX_ASSESSED_VALUE: &str = "TaxAssessedValue";
+    pub const TAX_BLOCK: &str = "TaxBlock";
+    pub const TAX_BOOK_NUMBER: &str = "TaxBookNumber";
+    pub const TAX_LEGAL_DESCRIPTION: &str = "TaxLegalDescription";
+    pub const TAX_LOT: &str = "TaxLot";
+    pub const TAX_MAP_NUMBER: &str = "TaxMapNumber";
+    pub const TAX_OTHER_ANNUAL_ASSESSMENT_AMOUNT: &str = "TaxOtherAnnualAssessmentAmount";
+    pub const TAX_PARCEL_LETTER: &str = "TaxParcelLetter";
+    pub const TAX_STATUS_CURRENT: &str = "TaxStatusCurrent";
+    pub const TAX_TRACT: &str = "TaxTract";
+    pub const TAX_YEAR: &str = "TaxYear";
+    pub const TENANT_PAYS: &str = "TenantPays";
+    pub const TOPOGRAPHY: &str = "Topography";
+    pub const TOTAL_ACTUAL_RENT: &str = "TotalActualRent";
+    pub const TOWNSHIP: &str = "Township";
+    pub const TRANSACTION_BROKER_COMPENSATION: &str = "TransactionBrokerCompensation";
+    pub const TRANSACTION_BROKER_COMPENSATION_TYPE: &str = "TransactionBrokerCompensationType";
+    pub const TRASH_EXPENSE: &str = "TrashExpense";
+    pub const UNIT_NUMBER: &str = "UnitNumber";
+    pub const UNIT_TYPE_TYPE: &str = "UnitTypeType";
+    pub const UNITS_FURNISHED: &str = "UnitsFurnished";
+    pub const UNIVERSAL_PROPERTY_ID: &str = "UniversalPropertyId";
+    pub const UNIVERSAL_PROPERTY_SUB_ID: &str = "UniversalPropertySubId";
+    pub const UNPARSED_ADDRESS: &str = "UnparsedAddress";
+    pub const UTILITIES: &str = "Utilities";
+    pub const VACANCY_ALLOWANCE: &str = "VacancyAllowance";
+    pub const VACANCY_ALLOWANCE_RATE: &str = "VacancyAllowanceRate";
+    pub const VEGETATION: &str = "Vegetation";
+    pub const VIDEOS_CHANGE_TIMESTAMP: &str = "VideosChangeTimestamp";
+    pub const VIDEOS_COUNT: &str = "VideosCount";
+    pub const VIEW: &str = "View";
+    pub const VIEW_YN: &str = "ViewYN";
+    pub const VIRTUAL_TOUR_URL_BRANDED: &str = "VirtualTourURLBranded";
+    pub const VIRTUAL_TOUR_URL_UNBRANDED: &str = "VirtualTourURLUnbranded";
+    pub const WALK_SCORE: &str = "WalkScore";
+    pub const WATER_BODY_NAME: &str = "WaterBodyName";
+    pub const WATER_SEWER_EXPENSE: &str = "WaterSewerExpense";
+    pub const WATER_SOURCE: &str = "WaterSource";
+    pub const WATERFRONT_FEATURES: &str = "WaterfrontFeatures";
+    pub const WATERFRONT_YN: &str = "WaterfrontYN";
+    pub const WINDOW_FEATURES: &str = "WindowFeatures";
+    pub const WITHDRAWN_DATE: &str = "WithdrawnDate";
+    pub const WOODED_AREA: &str = "WoodedArea";
+    pub const WORKMANS_COMPENSATION_EXPENSE: &str = "WorkmansCompensationExpense";
+    pub const YEAR_BUILT: &str = "YearBuilt";
+    pub const YEAR_BUILT_DETAILS: &str = "YearBuiltDetails";
+    pub const YEAR_BUILT_EFFECTIVE: &str = "YearBuiltEffective";
+    pub const YEAR_BUILT_SOURCE: &str = "YearBuiltSource";
+    pub const YEAR_ESTABLISHED: &str = "YearEstablished";
+    pub const YEARS_CURRENT_OWNER: &str = "YearsCurrentOwner";
+    pub const ZONING: &str = "Zoning";
+    pub const ZONING_DESCRIPTION: &str = "ZoningDescription";
+
+    pub const ALL_FIELDS: &[&str] = &[
+        ABOVE_GRADE_FINISHED_AREA,
+        ABOVE_GRADE_FINISHED_AREA_SOURCE,
+        ABOVE_GRADE_FINISHED_AREA_UNITS,
+        ACCESS_CODE,
+        ACCESSIBILITY_FEATURES,
+        ADDITIONAL_PARCELS_DESCRIPTION,
+        ADDITIONAL_PARCELS_YN,
+        ANCHORS_CO_TENANTS,
+        APPLIANCES,
+        ARCHITECTURAL_STYLE,
+        ASSOCIATION_AMENITIES,
+        ASSOCIATION_FEE,
+        ASSOCIATION_FEE2,
+        ASSOCIATION_FEE2_FREQUENCY,
+        ASSOCIATION_FEE_FREQUENCY,
+        ASSOCIATION_FEE_INCLUDES,
+        ASSOCIATION_NAME,
+        ASSOCIATION_NAME2,
+        ASSOCIATION_PHONE,
+        ASSOCIATION_PHONE2,
+        ASSOCIATION_YN,
+        ATTACHED_GARAGE_YN,
+        AVAILABILITY_DATE,
+        BASEMENT,
+        BASEMENT_YN,
+        BATHROOMS_FULL,
+        BATHROOMS_HALF,
+        BATHROOMS_ONE_QUARTER,
+        BATHROOMS_PARTIAL,
+        BATHROOMS_THREE_QUARTER,
+        BATHROOMS_TOTAL_INTEGER,
+        BEDROOMS_POSSIBLE,
+        BEDROOMS_TOTAL,
+        BELOW_GRADE_FINISHED_AREA,
+        BELOW_GRADE_FINISHED_AREA_SOURCE,
+        BELOW_GRADE_FINISHED_AREA_UNITS,
+        BODY_TYPE,
+        BUILDER_MODEL,
+        BUILDER_NAME,
+        BUILDING_AREA_SOURCE,
+        BUILDING_AREA_TOTAL,
+        BUILDING_AREA_UNITS,
+        BUILDING_FEATURES,
+        BUILDING_NAME,
+        BUSINESS_NAME,
+        BUSINESS_TYPE,
+        BUYER_AGENCY_COMPENSATION,
+        BUYER_AGENCY_COMPENSATION_TYPE,
+        BUYER_AGENT_AOR,
+        BUYER_AGENT_DESIGNATION,
+        BUYER_AGENT_DIRECT_PHONE,
+        BUYER_AGENT_EMAIL,
+        BUYER_AGENT_FIRST_NAME,
+        BUYER_AGENT_FULL_NAME,
+        BUYER_AGENT_KEY,
+        BUYER_AGENT_LAST_NAME,
+        BUYER_AGENT_MLS_ID,
+        BUYER_AGENT_STATE_LICENSE,
+        BUYER_FINANCING,
+        BUYER_OFFICE_AOR,
+        BUYER_OFFICE_KEY,
+        BUYER_OFFICE_MLS_ID,
+        BUYER_OFFICE_NAME,
+        BUYER_OFFICE_PHONE,
+        CABLE_TV_EXPENSE,
+        CANCELLATION_DATE,
+        CAP_RATE,
+        CARPORT_SPACES,
+        CARPORT_YN,
+        CARRIER_ROUTE,
+        CITY,
+        CITY_REGION,
+        CLOSE_DATE,
+        CLOSE_PRICE,
+        CO_BUYER_AGENT_FULL_NAME,
+        CO_BUYER_AGENT_KEY,
+        CO_BUYER_AGENT_MLS_ID,
+        CO_BUYER_OFFICE_KEY,
+        CO_BUYER_OFFICE_MLS_ID,
+        CO_BUYER_OFFICE_NAME,
+        CO_LIST_AGENT_FULL_NAME,
+        CO_LIST_AGENT_KEY,
+        CO_LIST_AGENT_MLS_ID,
+        CO_LIST_OFFICE_KEY,
+        CO_LIST_OFFICE_MLS_ID,
+        CO_LIST_OFFICE_NAME,
+        COMMON_INTEREST,
+        COMMON_WALLS,
+        COMMUNITY_FEATURES,
+        CONCESSIONS,
+        CONCESSIONS_AMOUNT,
+        CONCESSIONS_COMMENTS,
+        CONSTRUCTION_MATERIALS,
+        CONTINENT_REGION,
+        CONTINGENCY,
+        CONTINGENT_DATE,
+        CONTRACT_STATUS_CHANGE_DATE,
+        COOLING,
+        COOLING_YN,
+        COPYRIGHT_NOTICE,
+        COUNTRY,
+        COUNTRY_REGION,
+        COUNTY_OR_PARISH,
+        COVERED_SPACES,
+        CROPS_INCLUDED_YN,
+        CROSS_STREET,
+        CULTIVATED_AREA,
+        CUMULATIVE_DAYS_ON_MARKET,
+        CURRENT_FINANCING,
+        CURRENT_USE,
+        DOH1,
+        DOH2,
+        DOH3,
+        DAYS_ON_MARKET,
+        DEVELOPMENT_STATUS,
+        DIRECTION_FACES,
+        DIRECTIONS,
+        DISCLAIMER,
+        DISCLOSURES,
+        DISTANCE_TO_BUS_COMMENTS,
+        DISTANCE_TO_ELECTRIC_COMMENTS,
+        DISTANCE_TO_SCHOOLS_COMMENTS,
+        DISTANCE_TO_SEWER_COMMENTS,
+        DISTANCE_TO_WATER_COMMENTS,
+        DOCUMENTS_AVAILABLE,
+        DOCUMENTS_CHANGE_TIMESTAMP,
+        DOCUMENTS_COUNT,
+        DOOR_FEATURES,
+        DUAL_VARIABLE_COMPENSATION_YN,
+        ELECTRIC,
+        ELECTRIC_EXPENSE,
+        ELECTRIC_ON_PROPERTY_YN,
+        ELEMENTARY_SCHOOL,
+        ELEMENTARY_SCHOOL_DISTRICT,
+        ELEVATION,
+        ELEVATION_UNITS,
+        ENTRY_LEVEL,
+        ENTRY_LOCATION,
+        EXCLUSIONS,
+        EXISTING_LEASE_TYPE,
+        EXPIRATION_DATE,
+        EXTERIOR_FEATURES,
+        FARM_CREDIT_SERVICE_INCL_YN,
+        FARM_LAND_AREA_SOURCE,
+        FARM_LAND_AREA_UNITS,
+        FENCING,
+        FINANCIAL_DATA_SOURCE,
+        FIREPLACE_FEATURES,
+        FIREPLACE_YN,
+        FIREPLACES_TOTAL,
+        FLOORING,
+        FOUNDATION_AREA,
+        FOUNDATION_DETAILS,
+        FRONTAGE_LENGTH,
+        FRONTAGE_TYPE,
+        FUEL_EXPENSE,
+        FURNISHED,
+        FURNITURE_REPLACEMENT_EXPENSE,
+        GARAGE_SPACES,
+        GARAGE_YN,
+        GARDENER_EXPENSE,
+        GRAZING_PERMITS_BLM_YN,
+        GRAZING_PERMITS_FOREST_SERVICE_YN,
+        GRAZING_PERMITS_PRIVATE_YN,
+        GREEN_BUILDING_VERIFICATION_TYPE,
+        GREEN_ENERGY_EFFICIENT,
+        GREEN_ENERGY_GENERATION,
+        GREEN_INDOOR_AIR_QUALITY,
+        GREEN_LOCATION,
+        GREEN_SUSTAINABILITY,
+        GREEN_WATER_CONSERVATION,
+        GROSS_INCOME,
+        GROSS_SCHEDULED_INCOME,
+        HABITABLE_RESIDENCE_YN,
+        HEATING,
+        HEATING_YN,
+        HIGH_SCHOOL,
+        HIGH_SCHOOL_DISTRICT,
+        HOME_WARRANTY_YN,
+        HORSE_AMENITIES,
+        HORSE_YN,
+        HOURS_DAYS_OF_OPERATION,
+        HOURS_DAYS_OF_OPERATION_DESCRIPTION,
+        INCLUSIONS,
+        INCOME_INCLUDES,
+        INSURANCE_EXPENSE,
+        INTERIOR_FEATURES,
+        INTERNET_ADDRESS_DISPLAY_YN,
+        INTERNET_AUTOMATED_VALUATION_DISPLAY_YN,
+        INTERNET_CONSUMER_COMMENT_YN,
+        INTERNET_ENTIRE_LISTING_DISPLAY_YN,
+        IRRIGATION_SOURCE,
+        IRRIGATION_WATER_RIGHTS_ACRES,
+        IRRIGATION_WATER_RIGHTS_YN,
+        LABOR_INFORMATION,
+        LAND_LEASE_AMOUNT,
+        LAND_LEASE_AMOUNT_FREQUENCY,
+        LAND_LEASE_EXPIRATION_DATE,
+        LAND_LEASE_YN,
+        LATITUDE,
+        LAUNDRY_FEATURES,
+        LEASABLE_AREA,
+        LEASABLE_AREA_UNITS,
+        LEASE_AMOUNT,
+        LEASE_AMOUNT_FREQUENCY,
+        LEASE_ASSIGNABLE_YN,
+        LEASE_CONSIDERED_YN,
+        LEASE_EXPIRATION,
+        LEASE_RENEWAL_COMPENSATION,
+        LEASE_RENEWAL_OPTION_YN,
+        LEASE_TERM,
+        LEVELS,
+        LICENSE1,
+        LICENSE2,
+        LICENSE3,
+        LICENSES_EXPENSE,
+        LIST_AOR,
+        LIST_AGENT_AOR,
+        LIST_AGENT_DESIGNATION,
+        LIST_AGENT_DIRECT_PHONE,
+        LIST_AGENT_EMAIL,
+        LIST_AGENT_FIRST_NAME,
+        LIST_AGENT_FULL_NAME,
+        LIST_AGENT_KEY,
+        LIST_AGENT_LAST_NAME,
+        LIST_AGENT_MLS_ID,
+        LIST_AGENT_STATE_LICENSE,
+        LIST_OFFICE_AOR,
+        LIST_OFFICE_KEY,
+        LIST_OFFICE_MLS_ID,
+        LIST_OFFICE_NAME,
+        LIST_OFFICE_PHONE,
+        LIST_PRICE,
+        LIST_PRICE_LOW,
+        LISTING_AGREEMENT,
+        LISTING_CONTRACT_DATE,
+        LISTING_ID,
+        LISTING_KEY,
+        LISTING_SERVICE,
+        LISTING_TERMS,
+        LISTING_URL,
+        LIVING_AREA,
+        LIVING_AREA_SOURCE,
+        LIVING_AREA_UNITS,
+        LOCK_BOX_LOCATION,
+        LOCK_BOX_SERIAL_NUMBER,
+        LOCK_BOX_TYPE,
+        LONGITUDE,
+        LOT_DIMENSIONS_SOURCE,
+        LOT_FEATURES,
+        LOT_SIZE_ACRES,
+        LOT_SIZE_AREA,
+        LOT_SIZE_DIMENSIONS,
+        LOT_SIZE_SOURCE,
+        LOT_SIZE_SQUARE_FEET,
+        LOT_SIZE_UNITS,
+        MLS_AREA_MAJOR,
+        MLS_AREA_MINOR,
+        MAIN_LEVEL_BATHROOMS,
+        MAIN_LEVEL_BEDROOMS,
+        MAINTENANCE_EXPENSE,
+        MAJOR_CHANGE_TIMESTAMP,
+        MAJOR_CHANGE_TYPE,
+        MAKE,
+        MANAGER_EXPENSE,
+        MAP_COORDINATE,
+        MAP_COORDINATE_SOURCE,
+        MAP_URL,
+        MIDDLE_OR_JUNIOR_SCHOOL,
+        MIDDLE_OR_JUNIOR_SCHOOL_DISTRICT,
+        MLS_STATUS,
+        MOBILE_DIM_UNITS,
+        MOBILE_HOME_REMAINS_YN,
+        MOBILE_LENGTH,
+        MOBILE_WIDTH,
+        MODEL,
+        MODIFICATION_TIMESTAMP,
+        NET_OPERATING_INCOME,
+        NEW_CONSTRUCTION_YN,
+        NEW_TAXES_EXPENSE,
+        NUMBER_OF_BUILDINGS,
+        NUMBER_OF_FULL_TIME_EMPLOYEES,
+        NUMBER_OF_LOTS,
+        NUMBER_OF_PADS,
+        NUMBER_OF_PART_TIME_EMPLOYEES,
+        NUMBER_OF_SEPARATE_ELECTRIC_METERS,
+        NUMBER_OF_SEPARATE_GAS_METERS,
+        NUMBER_OF_SEPARATE_WATER_METERS,
+        NUMBER_OF_UNITS_IN_COMMUNITY,
+        NUMBER_OF_UNITS_LEASED,
+        NUMBER_OF_UNITS_MO_MO,
+        NUMBER_OF_UNITS_TOTAL,
+        NUMBER_OF_UNITS_VACANT,
+        OCCUPANT_NAME,
+        OCCUPANT_PHONE,
+        OCCUPANT_TYPE,
+        OFF_MARKET_DATE,
+        OFF_MARKET_TIMESTAMP,
+        ON_MARKET_DATE,
+        ON_MARKET_TIMESTAMP,
+        OPEN_PARKING_SPACES,
+        OPEN_PARKING_YN,
+        OPERATING_EXPENSE,
+        OPERATING_EXPENSE_INCLUDES,
+        ORIGINAL_ENTRY_TIMESTAMP,
+        ORIGINAL_LIST_PRICE,
+        ORIGINATING_SYSTEM_ID,
+        ORIGINATING_SYSTEM_KEY,
+        ORIGINATING_SYSTEM_NAME,
+        OTHER_EQUIPMENT,
+        OTHER_EXPENSE,
+        OTHER_PARKING,
+        OTHER_STRUCTURES,
+        OWNER_NAME,
+        OWNER_PAYS,
+        OWNER_PHONE,
+        OWNERSHIP,
+        OWNERSHIP_TYPE,
+        PARCEL_NUMBER,
+        PARK_MANAGER_NAME,
+        PARK_MANAGER_PHONE,
+        PARK_NAME,
+        PARKING_FEATURES,
+        PARKING_TOTAL,
+        PASTURE_AREA,
+        PATIO_AND_PORCH_FEATURES,
+        PENDING_TIMESTAMP,
+        PEST_CONTROL_EXPENSE,
+        PETS_ALLOWED,
+        PHOTOS_CHANGE_TIMESTAMP,
+        PHOTOS_COUNT,
+        POOL_EXPENSE,
+        POOL_FEATURES,
+        POOL_PRIVATE_YN,
+        POSSESSION,
+        POSSIBLE_USE,
+        POSTAL_CITY,
+        POSTAL_CODE,
+        POSTAL_CODE_PLUS4,
+        POWER_PRODUCTION_TYPE,
+        PREVIOUS_LIST_PRICE,
+        PRICE_CHANGE_TIMESTAMP,
+        PRIVATE_OFFICE_REMARKS,
+        PRIVATE_REMARKS,
+        PROFESSIONAL_MANAGEMENT_EXPENSE,
+        PROPERTY_ATTACHED_YN,
+        PROPERTY_CONDITION,
+        PROPERTY_SUB_TYPE,
+        PROPERTY_TYPE,
+        PUBLIC_REMARKS,
+        PUBLIC_SURVEY_RANGE,
+        PUBLIC_SURVEY_SECTION,
+        PUBLIC_SURVEY_TOWNSHIP,
+        PURCHASE_CONTRACT_DATE,
+        RV_PARKING_DIMENSIONS,
+        RANGE_AREA,
+        RENT_CONTROL_YN,
+        RENT_INCLUDES,
+        ROAD_FRONTAGE_TYPE,
+        ROAD_RESPONSIBILITY,
+        ROAD_SURFACE_TYPE,
+        ROOF,
+        ROOM_TYPE,
+        ROOMS_TOTAL,
+        SEATING_CAPACITY,
+        SECURITY_FEATURES,
+        SENIOR_COMMUNITY_YN,
+        SERIAL_U,
+        SERIAL_X,
+        SERIAL_XX,
+        SEWER,
+        SHOWING_ADVANCE_NOTICE,
+        SHOWING_ATTENDED_YN,
+        SHOWING_CONTACT_NAME,
+        SHOWING_CONTACT_PHONE,
+        SHOWING_CONTACT_PHONE_EXT,
+        SHOWING_CONTACT_TYPE,
+        SHOWING_DAYS,
+        SHOWING_END_TIME,
+        SHOWING_INSTRUCTIONS,
+        SHOWING_REQUIREMENTS,
+        SHOWING_START_TIME,
+        SIGN_ON_PROPERTY_YN,
+        SKIRT,
+        SOURCE_SYSTEM_ID,
+        SOURCE_SYSTEM_KEY,
+        SOURCE_SYSTEM_NAME,
+        SPA_FEATURES,
+        SPA_YN,
+        SPECIAL_LICENSES,
+        SPECIAL_LISTING_CONDITIONS,
+        STANDARD_STATUS,
+        START_SHOWING_DATE,
+        STATE_OR_PROVINCE,
+        STATE_REGION,
+        STATUS_CHANGE_TIMESTAMP,
+        STORIES,
+        STORIES_TOTAL,
+        STREET_ADDITIONAL_INFO,
+        STREET_DIR_PREFIX,
+        STREET_DIR_SUFFIX,
+        STREET_NAME,
+        STREET_NUMBER,
+        STREET_NUMBER_NUMERIC,
+        STREET_SUFFIX,
+        STREET_SUFFIX_MODIFIER,
+        STRUCTURE_TYPE,
+        SUB_AGENCY_COMPENSATION,
+        SUB_AGENCY_COMPENSATION_TYPE,
+        SUBDIVISION_NAME,
+        SUPPLIES_EXPENSE,
+        SYNDICATE_TO,
+        SYNDICATION_REMARKS,
+        TAX_ANNUAL_AMOUNT,
+        TAX_ASSESSED_VALUE,
+        TAX_BLOCK,
+        TAX_BOOK_NUMBER,
+        TAX_LEGAL_DESCRIPTION,
+        TAX_LOT,
+        TAX_MAP_NUMBER,
+        TAX_OTHER_ANNUAL_ASSESSMENT_AMOUNT,
+        TAX_PARCEL_LETTER,
+        TAX_STATUS_CURRENT,
+        TAX_TRACT,
+        TAX_YEAR,
+        TENANT_PAYS,
+        TOPOGRAPHY,
+        TOTAL_ACTUAL_RENT,
+        TOWNSHIP,
+        TRANSACTION_BROKER_COMPENSATION,
+        TRANSACTION_BROKER_COMPENSATION_TYPE,
+        TRASH_EXPENSE,
+        UNIT_NUMBER,
+        UNIT_TYPE_TYPE,
+        UNITS_FURNISHED,
+        UNIVERSAL_PROPERTY_ID,
+        UNIVERSAL_PROPERTY_SUB_ID,
+        UNPARSED_ADDRESS,
+        UTILITIES,
+        VACANCY_ALLOWANCE,
+        VACANCY_ALLOWANCE_RATE,
+        VEGETATION,
+        VIDEOS_CHANGE_TIMESTAMP,
+        VIDEOS_COUNT,
+        VIEW,
+        VIEW_YN,
+        VIRTUAL_TOUR_URL_BRANDED,
+        VIRTUAL_TOUR_URL_UNBRANDED,
+        WALK_SCORE,
+        WATER_BODY_NAME,
+        WATER_SEWER_EXPENSE,
+        WATER_SOURCE,
+        WATERFRONT_FEATURES,
+        WATERFRONT_YN,
+        WINDOW_FEATURES,
+        WITHDRAWN_DATE,
+        WOODED_AREA,
+        WORKMANS_COMPENSATION_EXPENSE,
+        YEAR_BUILT,
+        YEAR_BUILT_DETAILS,
+        YEAR_BUILT_EFFECTIVE,
+        YEAR_BUILT_SOURCE,
+        YEAR_ESTABLISHED,
+        YEARS_CURRENT_OWNER,
+        ZONING,
+        ZONING_DESCRIPTION,
+    ];
+}
+
+pub mod member {
+    pub const JOB_TITLE: &str = "JobTitle";
+    pub const LAST_LOGIN_TIMESTAMP: &str = "LastLoginTimestamp";
+    pub const MEMBER_AOR: &str = "MemberAOR";
+    pub const MEMBER_AOR_MLS_ID: &str = "MemberAORMlsId";
+    pub const MEMBER_AO_RKEY: &str = "MemberAORkey";
+    pub const MEMBER_ADDRESS1: &str = "MemberAddress1";
+    pub const MEMBER_ADDRESS2: &str = "MemberAddress2";
+    pub const MEMBER_ASSOCIATION_COMMENTS: &str = "MemberAssociationComments";
+    pub const MEMBER_CARRIER_ROUTE: &str = "MemberCarrierRoute";
+    pub const MEMBER_CITY: &str = "MemberCity";
+    pub const MEMBER_COUNTRY: &str = "MemberCountry";
+    pub const MEMBER_COUNTY_OR_PARISH: &str = "MemberCountyOrParish";
+    pub const MEMBER_DESIGNATION: &str = "MemberDesignation";
+    pub const MEMBER_DIRECT_PHONE: &str = "MemberDirectPhone";
+    pub const MEMBER_EMAIL: &str = "MemberEmail";
+    pub const MEMBER_FAX: &str = "MemberFax";
+    pub const MEMBER_FIRST_NAME: &str = "MemberFirstName";
+    pub const MEMBER_FULL_NAME: &str = "MemberFullName";
+    pub const MEMBER_HOME_PHONE: &str = "MemberHomePhone";
+    pub const MEMBER_IS_ASSISTANT_TO: &str = "MemberIsAssistantTo";
+    pub const MEMBER_KEY: &str = "MemberKey";
+    pub const MEMBER_LANGUAGES: &str = "MemberLanguages";
+    pub const MEMBER_LAST_NAME: &str = "MemberLastName";
+    pub const MEMBER_LOGIN_ID: &str = "MemberLoginId";
+    pub const MEMBER_MIDDLE_NAME: &str = "MemberMiddleName";
+    pub const MEMBER_MLS_ACCESS_YN: &str = "MemberMlsAccessYN";
+    pub const MEMBER_MLS_ID: &str = "MemberMlsId";
+    pub const MEMBER_MLS_SECURITY_CLASS: &str = "MemberMlsSecurityClass";
+    pub const MEMBER_MOBILE_PHONE: &str = "MemberMobilePhone";
+    pub const MEMBER_NAME_PREFIX: &str = "MemberNamePrefix";
+    pub const MEMBER_NAME_SUFFIX: &str = "MemberNameSuffix";
+    pub const MEMBER_NATIONAL_ASSOCIATION_ID: &str = "MemberNationalAssociationId";
+    pub const MEMBER_NICKNAME: &str = "MemberNickname";
+    pub const MEMBER_OFFICE_PHONE: &str = "MemberOfficePhone";
+    pub const MEMBER_OFFICE_PHONE_EXT: &str = "MemberOfficePhoneExt";
+    pub const MEMBER_OTHER_PHONE_TYPE: &str = "MemberOtherPhoneType";
+    pub const MEMBER_PAGER: &str = "MemberPager";
+    pub const MEMBER_PASSWORD: &str = "MemberPassword";
+    pub const MEMBER_PHONE_TTYTDD: &str = "MemberPhoneTTYTDD";
+    pub const MEMBER_POSTAL_CODE: &str = "MemberPostalCode";
+    pub const MEMBER_POSTAL_CODE_PLUS4: &str = "MemberPostalCodePlus4";
+    pub const MEMBER_PREFERRED_PHONE: &str = "MemberPreferredPhone";
+    pub const MEMBER_PREFERRED_PHONE_EXT: &str = "MemberPreferredPhoneExt";
+    pub const MEMBER_STATE_LICENSE: &str = "MemberStateLicense";
+    pub const MEMBER_STATE_LICENSE_STATE: &str = "MemberStateLicenseState";
+    pub const MEMBER_STATE_OR_PROVINCE: &str = "MemberStateOrProvince";
+    pub const MEMBER_STATUS: &str = "MemberStatus";
+    pub const MEMBER_TOLL_FREE_PHONE: &str = "MemberTollFreePhone";
+    pub const MEMBER_TYPE: &str = "MemberType";
+    pub const MEMBER_VOICE_MAIL: &str = "MemberVoiceMail";
+    pub const MEMBER_VOICE_MAIL_EXT: &str = "MemberVoiceMailExt";
+    pub const MODIFICATION_TIMESTAMP: &str = "ModificationTimestamp";
+    pub const OFFICE_KEY: &str = "OfficeKey";
+    pub const OFFICE_MLS_ID: &str = "OfficeMlsId";
+    pub const OFFICE_NAME: &str = "OfficeName";
+    pub const ORIGINAL_ENTRY_TIMESTAMP: &str = "OriginalEntryTimestamp";
+    pub const ORIGINATING_SYSTEM_ID: &str = "OriginatingSystemID";
+    pub const ORIGINATING_SYSTEM_MEMBER_KEY: &str = "OriginatingSystemMemberKey";
+    pub const ORIGINATING_SYSTEM_NAME: &str = "OriginatingSystemName";
+    pub const SOCIAL_MEDIA_TYPE: &str = "SocialMediaType";
+    pub const SOURCE_SYSTEM_ID: &str = "SourceSystemID";
+    pub const SOURCE_SYSTEM_MEMBER_KEY: &str = "SourceSystemMemberKey";
+    pub const SOURCE_SYSTEM_NAME: &str = "SourceSystemName";
+    pub const SYNDICATE_TO: &str = "SyndicateTo";
+
+    pub const ALL_FIELDS: &[&str] = &[
+        JOB_TITLE,
+        LAST_LOGIN_TIMESTAMP,
+        MEMBER_AOR,
+        MEMBER_AOR_MLS_ID,
+        MEMBER_AO_RKEY,
+        MEMBER_ADDRESS1,
+        MEMBER_ADDRESS2,
+        MEMBER_ASSOCIATION_COMMENTS,
+        MEMBER_CARRIER_ROUTE,
+        MEMBER_CITY,
+        MEMBER_COUNTRY,
+        MEMBER_COUNTY_OR_PARISH,
+        MEMBER_DESIGNATION,
+        MEMBER_DIRECT_PHONE,
+        MEMBER_EMAIL,
+        MEMBER_FAX,
+        MEMBER_FIRST_NAME,
+        MEMBER_FULL_NAME,
+        MEMBER_HOME_PHONE,
+        MEMBER_IS_ASSISTANT_TO,
+        MEMBER_KEY,
+        MEMBER_LANGUAGES,
+        MEMBER_LAST_NAME,
+        MEMBER_LOGIN_ID,
+        MEMBER_MIDDLE_NAME,
+        MEMBER_MLS_ACCESS_YN,
+        MEMBER_MLS_ID,
+        MEMBER_MLS_SECURITY_CLASS,
+        MEMBER_MOBILE_PHONE,
+        MEMBER_NAME_PREFIX,
+        MEMBER_NAME_SUFFIX,
+        MEMBER_NATIONAL_ASSOCIATION_ID,
+        MEMBER_NICKNAME,
+        MEMBER_OFFICE_PHONE,
+        MEMBER_OFFICE_PHONE_EXT,
+        MEMBER_OTHER_PHONE_TYPE,
+        MEMBER_PAGER,
+        MEMBER_PASSWORD,
+        MEMBER_PHONE_TTYTDD,
+        MEMBER_POSTAL_CODE,
+        MEMBER_POSTAL_CODE_PLUS4,
+        MEMBER_PREFERRED_PHONE,
+        MEMBER_PREFERRED_PHONE_EXT,
+        MEMBER_STATE_LICENSE,
+        MEMBER_STATE_LICENSE_STATE,
+        MEMBER_STATE_OR_PROVINCE,
+        MEMBER_STATUS,
+        MEMBER_TOLL_FREE_PHONE,
+        MEMBER_TYPE,
+        MEMBER_VOICE_MAIL,
+        MEMBER_VOICE_MAIL_EXT,
+        MODIFICATION_TIMESTAMP,
+        OFFICE_KEY,
+        OFFICE_MLS_ID,
+        OFFICE_NAME,
+        ORIGINAL_ENTRY_TIMESTAMP,
+        ORIGINATING_SYSTEM_ID,
+        ORIGINATING_SYSTEM_MEMBER_KEY,
+        ORIGINATING_SYSTEM_NAME,
+        SOCIAL_MEDIA_TYPE,
+        SOURCE_SYSTEM_ID,
+        SOURCE_SYSTEM_MEMBER_KEY,
+        SOURCE_SYSTEM_NAME,
+        SYNDICATE_TO,
+    ];
+}
+
+pub mod office {
+    pub const FRANCHISE_AFFILIATION: &str = "FranchiseAffiliation";
+    pub const IDX_OFFICE_PARTICIPATION_YN: &str = "IDXOfficeParticipationYN";
+    pub const MAIN_OFFICE_KEY: &str = "MainOfficeKey";
+    pub const MAIN_OFFICE_MLS_ID: &str = "MainOfficeMlsId";
+    pub const MODIFICATION_TIMESTAMP: &str = "ModificationTimestamp";
+    pub const OFFICE_AOR: &str = "OfficeAOR";
+    pub const OFFICE_AOR_MLS_ID: &str = "OfficeAORMlsId";
+    pub const OFFICE_AO_RKEY: &str = "OfficeAORkey";
+    pub const OFFICE_ADDRESS1: &str = "OfficeAddress1";
+    pub const OFFICE_ADDRESS2: &str = "OfficeAddress2";
+    pub const OFFICE_ASSOCIATION_COMMENTS: &str = "OfficeAssociationComments";
+    pub const OFFICE_BRANCH_TYPE: &str = "OfficeBranchType";
+    pub const OFFICE_BROKER_KEY: &str = "OfficeBrokerKey";
+    pub const OFFICE_BROKER_MLS_ID: &str = "OfficeBrokerMlsId";
+    pub const OFFICE_CITY: &str = "OfficeCity";
+    pub const OFFICE_CORPORATE_LICENSE: &str = "OfficeCorporateLicense";
+    pub const OFFICE_COUNTY_OR_PARISH: &str = "OfficeCountyOrParish";
+    pub const OFFICE_EMAIL: &str = "OfficeEmail";
+    pub const OFFICE_FAX: &str = "OfficeFax";
+    pub const OFFICE_KEY: &str = "OfficeKey";
+    pub const OFFICE_MANAGER_KEY: &str = "OfficeManagerKey";
+    pub const OFFICE_MANAGER_MLS_ID: &str = "OfficeManagerMlsId";
+    pub const OFFICE_MLS_ID: &str = "OfficeMlsId";
+    pub const OFFICE_NAME: &str = "OfficeName";
+    pub const OFFICE_NATIONAL_ASSOCIATION_ID: &str = "OfficeNationalAssociationId";
+    pub const OFFICE_PHONE: &str = "OfficePhone";
+    pub const OFFICE_PHONE_EXT: &str = "OfficePhoneExt";
+    pub const OFFICE_POSTAL_CODE: &str = "OfficePostalCode";
+    pub const OFFICE_POSTAL_CODE_PLUS4: &str = "OfficePostalCodePlus4";
+    pub const OFFICE_STATE_OR_PROVINCE: &str = "OfficeStateOrProvince";
+    pub const OFFICE_STATUS: &str = "OfficeStatus";
+    pub const OFFICE_TYPE: &str = "OfficeType";
+    pub const ORIGINAL_ENTRY_TIMESTAMP: &str = "OriginalEntryTimestamp";
+    pub const ORIGINATING_SYSTEM_ID: &str = "OriginatingSystemID";
+    pub const ORIGINATING_SYSTEM_NAME: &str = "OriginatingSystemName";
+    pub const ORIGINATING_SYSTEM_OFFICE_KEY: &str = "OriginatingSystemOfficeKey";
+    pub const SOCIAL_MEDIA_TYPE: &str = "SocialMediaType";
+    pub const SOURCE_SYSTEM_ID: &str = "SourceSystemID";
+    pub const SOURCE_SYSTEM_NAME: &str = "SourceSystemName";
+    pub const SOURCE_SYSTEM_OFFICE_KEY: &str = "SourceSystemOfficeKey";
+    pub const SYNDICATE_AGENT_OPTION: &str = "SyndicateAgentOption";
+    pub const SYNDICATE_TO: &str = "SyndicateTo";
+
+    pub const ALL_FIELDS: &[&str] = &[
+        FRANCHISE_AFFILIATION,
+        IDX_OFFICE_PARTICIPATION_YN,
+        MAIN_OFFICE_KEY,
+        MAIN_OFFICE_MLS_ID,
+        MODIFICATION_TIMESTAMP,
+        OFFICE_AOR,
+        OFFICE_AOR_MLS_ID,
+        OFFICE_AO_RKEY,
+        OFFICE_ADDRESS1,
+        OFFICE_ADDRESS2,
+        OFFICE_ASSOCIATION_COMMENTS,
+        OFFICE_BRANCH_TYPE,
+        OFFICE_BROKER_KEY,
+        OFFICE_BROKER_MLS_ID,
+        OFFICE_CITY,
+        OFFICE_CORPORATE_LICENSE,
+        OFFICE_COUNTY_OR_PARISH,
+        OFFICE_EMAIL,
+        OFFICE_FAX,
+        OFFICE_KEY,
+        OFFICE_MANAGER_KEY,
+        OFFICE_MANAGER_MLS_ID,
+        OFFICE_MLS_ID,
+        OFFICE_NAME,
+        OFFICE_NATIONAL_ASSOCIATION_ID,
+        OFFICE_PHONE,
+        OFFICE_PHONE_EXT,
+        OFFICE_POSTAL_CODE,
+        OFFICE_POSTAL_CODE_PLUS4,
+        OFFICE_STATE_OR_PROVINCE,
+        OFFICE_STATUS,
+        OFFICE_TYPE,
+        ORIGINAL_ENTRY_TIMESTAMP,
+        ORIGINATING_SYSTEM_ID,
+        ORIGINATING_SYSTEM_NAME,
+        ORIGINATING_SYSTEM_OFFICE_KEY,
+        SOCIAL_MEDIA_TYPE,
+        SOURCE_SYSTEM_ID,
+        SOURCE_SYSTEM_NAME,
+        SOURCE_SYSTEM_OFFICE_KEY,
+        SYNDICATE_AGENT_OPTION,
+        SYNDICATE_TO,
+    ];
+}
+
+pub mod media {
+    pub const CHANGED_BY_MEMBER_ID: &str = "ChangedByMemberID";
+    pub const CHANGED_BY_MEMBER_KEY: &str = "ChangedByMemberKey";
+    pub const CLASS_NAME: &str = "ClassName";
+    pub const IMAGE_HEIGHT: &str = "ImageHeight";
+    pub const IMAGE_OF: &str = "ImageOf";
+    pub const IMAGE_SIZE_DESCRIPTION: &str = "ImageSizeDescription";
+    pub const IMAGE_WIDTH: &str = "ImageWidth";
+    pub const LONG_DESCRIPTION: &str = "LongDescription";
+    pub const MEDIA_CATEGORY: &str = "MediaCategory";
+    pub const MEDIA_HTML: &str = "MediaHTML";
+    pub const MEDIA_KEY: &str = "MediaKey";
+    pub const MEDIA_MODIFICATION_TIMESTAMP: &str = "MediaModificationTimestamp";
+    pub const MEDIA_OBJECT_ID: &str = "MediaObjectID";
+    pub const MEDIA_STATUS: &str = "MediaStatus";
+    pub const MEDIA_TYPE: &str = "MediaType";
+    pub const MEDIA_URL: &str = "MediaURL";
+    pub const MODIFICATION_TIMESTAMP: &str = "ModificationTimestamp";
+    pub const ORDER: &str = "Order";
+    pub const ORIGINATING_SYSTEM_ID: &str = "OriginatingSystemID";
+    pub const ORIGINATING_SYSTEM_MEDIA_KEY: &str = "OriginatingSystemMediaKey";
+    pub const ORIGINATING_SYSTEM_NAME: &str = "OriginatingSystemName";
+    pub const PERMISSION: &str = "Permission";
+    pub const PREFERRED_PHOTO_YN: &str = "PreferredPhotoYN";
+    pub const RESOURCE_NAME: &str = "ResourceName";
+    pub const RESOURCE_RECORD_ID: &str = "ResourceRecordID";
+    pub const RESOURCE_RECORD_KEY: &str = "ResourceRecordKey";
+    pub const SHORT_DESCRIPTION: &str = "ShortDescription";
+    pub const SOURCE_SYSTEM_ID: &str = "SourceSystemID";
+    pub const SOURCE_SYSTEM_MEDIA_KEY: &str = "SourceSystemMediaKey";
+    pub const SOURCE_SYSTEM_NAME: &str = "SourceSystemName";
+
+    pub const ALL_FIELDS: &[&str] = &[
+        CHANGED_BY_MEMBER_ID,
+        CHANGED_BY_MEMBER_KEY,
+        CLASS_NAME,
+        IMAGE_HEIGHT,
+        IMAGE_OF,
+        IMAGE_SIZE_DESCRIPTION,
+        IMAGE_WIDTH,
+        LONG_DESCRIPTION,
+        MEDIA_CATEGORY,
+        MEDIA_HTML,
+        MEDIA_KEY,
+        MEDIA_MODIFICATION_TIMESTAMP,
+        MEDIA_OBJECT_ID,
+        MEDIA_STATUS,
+        MEDIA_TYPE,
+        MEDIA_URL,
+        MODIFICATION_TIMESTAMP,
+        ORDER,
+        ORIGINATING_SYSTEM_ID,
+        ORIGINATING_SYSTEM_MEDIA_KEY,
+        ORIGINATING_SYSTEM_NAME,
+        PERMISSION,
+        PREFERRED_PHOTO_YN,
+        RESOURCE_NAME,
+        RESOURCE_RECORD_ID,
+        RESOURCE_RECORD_KEY,
+        SHORT_DESCRIPTION,
+        SOURCE_SYSTEM_ID,
+        SOURCE_SYSTEM_MEDIA_KEY,
+        SOURCE_SYSTEM_NAME,
+    ];
+}
+
+pub mod open_house {
+    pub const APPOINTMENT_REQUIRED_YN: &str = "AppointmentRequiredYN";
+    pub const LISTING_ID: &str = "ListingId";
+    pub const LISTING_KEY: &str = "ListingKey";
+    pub const MODIFICATION_TIMESTAMP: &str = "ModificationTimestamp";
+    pub const OPEN_HOUSE_ATTENDED_BY: &str = "OpenHouseAttendedBy";
+    pub const OPEN_HOUSE_DATE: &str = "OpenHouseDate";
+    pub const OPEN_HOUSE_END_TIME: &str = "OpenHouseEndTime";
+    pub const OPEN_HOUSE_ID: &str = "OpenHouseId";
+    pub const OPEN_HOUSE_KEY: &str = "OpenHouseKey";
+    pub const OPEN_HOUSE_REMARKS: &str = "OpenHouseRemarks";
+    pub const OPEN_HOUSE_START_TIME: &str = "OpenHouseStartTime";
+    pub const OPEN_HOUSE_STATUS: &str = "OpenHouseStatus";
+    pub const OPEN_HOUSE_TYPE: &str = "OpenHouseType";
+    pub const ORIGINAL_ENTRY_TIMESTAMP: &str = "OriginalEntryTimestamp";
+    pub const ORIGINATING_SYSTEM_ID: &str = "OriginatingSystemID";
+    pub const ORIGINATING_SYSTEM_KEY: &str = "OriginatingSystemKey";
+    pub const ORIGINATING_SYSTEM_NAME: &str = "OriginatingSystemName";
+    pub const REFRESHMENTS: &str = "Refreshments";
+    pub const SHOWING_AGENT_FIRST_NAME: &str = "ShowingAgentFirstName";
+    pub const SHOWING_AGENT_KEY: &str = "ShowingAgentKey";
+    pub const SHOWING_AGENT_LAST_NAME: &str = "ShowingAgentLastName";
+    pub const SHOWING_AGENT_MLS_ID: &str = "ShowingAgentMlsID";
+    pub const SOURCE_SYSTEM_ID: &str = "SourceSystemID";
+    pub const SOURCE_SYSTEM_KEY: &str = "SourceSystemKey";
+    pub const SOURCE_SYSTEM_NAME: &str = "SourceSystemName";
+
+    pub const ALL_FIELDS: &[&str] = &[
+        APPOINTMENT_REQUIRED_YN,
+        LISTING_ID,
+        LISTING_KEY,
+        MODIFICATION_TIMESTAMP,
+        OPEN_HOUSE_ATTENDED_BY,
+        OPEN_HOUSE_DATE,
+        OPEN_HOUSE_END_TIME,
+        OPEN_HOUSE_ID,
+        OPEN_HOUSE_KEY,
+        OPEN_HOUSE_REMARKS,
+        OPEN_HOUSE_START_TIME,
+        OPEN_HOUSE_STATUS,
+        OPEN_HOUSE_TYPE,
+        ORIGINAL_ENTRY_TIMESTAMP,
+        ORIGINATING_SYSTEM_ID,
+        ORIGINATING_SYSTEM_KEY,
+        ORIGINATING_SYSTEM_NAME,
+        REFRESHMENTS,
+        SHOWING_AGENT_FIRST_NAME,
+        SHOWING_AGENT_KEY,
+        SHOWING_AGENT_LAST_NAME,
+        SHOWING_AGENT_MLS_ID,
+        SOURCE_SYSTEM_ID,
+        SOURCE_SYSTEM_KEY,
+        SOURCE_SYSTEM_NAME,
+    ];
+}
@@ -0,0 +1,636 @@
+//! OData filters derived from annotated structs.
+//!
+//! Web handlers typically receive search criteria as a struct and assemble a
+//! filter by hand, one `if let Some(..)` per field. `#[derive(ResoFilter)]`
+//! generates that code from field annotations instead:
+//!
+//! ```
+//! use reso_examples::filter::ResoFilter;
+//!
+//! #[derive(ResoFilter)]
+//! struct ListingCriteria {
+//!     #[filter(field = "City")]
+//!     city: Option<String>,
+//!     #[filter(field = "ListPrice", op = "ge")]
+//!     min_price: Option<i64>,
+//!     #[filter(field = "ListPrice", op = "le")]
+//!     max_price: Option<i64>,
+//!     // Fields without `#[filter]` are ignored
+//!     page: u32,
+//! }
+//!
+//! let criteria = ListingCriteria {
+//!     city: Some("Austin".into()),
+//!     min_price: Some(300_000),
+//!     max_price: None,
+//!     page: 1,
+//! };
+//! assert_eq!(
+//!     criteria.to_filter().as_deref(),
+//!     Some("City eq 'Austin' and ListPrice ge 300000")
+//! );
+//! ```
+//!
+//! `op` defaults to `eq` and may be any [`Operator`] name: `eq`, `ne`, `gt`,
+//! `ge`, `lt`, `le`, `contains`, `startswith`, or `in` (for `Vec` fields).
+//! `Option` fields that are `None` are left out; other fields always apply.
+//!
+//! For filters assembled at runtime, [`FilterBuilder`] combines typed
+//! comparisons with `and`, `or`, and `not`, escaping every value.
+
+use crate::search::quote;
+use crate::validate::is_keyword;
+use chrono::{DateTime, NaiveDate, Utc};
+use reso_client::ResoError;
+use std::fmt;
+use std::ops::Not;
+use std::str::FromStr;
+
+pub use reso_filter_derive::ResoFilter;
+
+/// A comparison between a field and a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// `contains(Field,'value')`
+    Contains,
+    /// `startswith(Field,'value')`
+    StartsWith,
+    /// `Field in ('a','b')`; the value should be a list
+    In,
+}
+
+impl Operator {
+    /// Every operator, in declaration order.
+    pub const ALL: [Operator; 9] = [
+        Operator::Eq,
+        Operator::Ne,
+        Operator::Gt,
+        Operator::Ge,
+        Operator::Lt,
+        Operator::Le,
+        Operator::Contains,
+        Operator::StartsWith,
+        Operator::In,
+    ];
+
+    /// The operator's name in OData syntax, e.g. `ge` or `startswith`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Eq => "eq",
+            Operator::Ne => "ne",
+            Operator::Gt => "gt",
+            Operator::Ge => "ge",
+            Operator::Lt => "lt",
+            Operator::Le => "le",
+            Operator::Contains => "contains",
+            Operator::StartsWith => "startswith",
+            Operator::In => "in",
+        }
+    }
+
+    /// Renders a comparison of `field` against `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::filter::Operator;
+    ///
+    /// assert_eq!(Operator::Ge.render("ListPrice", &300_000), "ListPrice ge 300000");
+    /// assert_eq!(Operator::Contains.render("City", "Aus"), "contains(City,'Aus')");
+    /// assert_eq!(
+    ///     Operator::In.render("City", &["Austin", "Dallas"]),
+    ///     "City in ('Austin','Dallas')"
+    /// );
+    /// ```
+    pub fn render<V: FilterValue + ?Sized>(&self, field: &str, value: &V) -> String {
+        match self {
+            Operator::Contains | Operator::StartsWith => {
+                format!("{}({},{})", self.as_str(), field, value.to_odata())
+            }
+            _ => format!("{} {} {}", field, self.as_str(), value.to_odata()),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Operator {
+    type Err = ResoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Operator::ALL
+            .into_iter()
+            .find(|op| op.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ResoError::InvalidQuery(format!("Unknown filter operator '{}'", s)))
+    }
+}
+
+/// A filter expression built from typed comparisons.
+///
+/// String values are quoted and escaped, so user input cannot change the
+/// structure of the filter, and field names must be plain OData property
+/// paths. Combining filters adds the parentheses OData's precedence rules
+/// require (`not` binds tighter than `and`, which binds tighter than `or`).
+/// Write `!filter` for `not`.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::build_query;
+/// use reso_examples::filter::FilterBuilder;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let city = "Austin' or ListPrice gt 0 or City eq '";
+/// let filter = FilterBuilder::eq("City", city)
+///     .and(FilterBuilder::gt("ListPrice", &500_000).or(FilterBuilder::contains("PublicRemarks", "pool")))
+///     .and(!FilterBuilder::in_list("StandardStatus", &["Closed", "Expired"]))
+///     .build()?;
+///
+/// assert!(filter.starts_with("City eq 'Austin'' or ListPrice gt 0 or City eq ''' and ("));
+/// assert!(filter.ends_with("and not (StandardStatus in ('Closed','Expired'))"));
+/// let query = build_query("Property", Some(&filter), Some(10))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterBuilder {
+    expression: String,
+    kind: ExpressionKind,
+    /// The first invalid part, reported by [`FilterBuilder::build`]
+    error: Option<String>,
+}
+
+/// The top-level operator of an expression, deciding where parentheses go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpressionKind {
+    Term,
+    And,
+    Or,
+}
+
+impl FilterBuilder {
+    /// A comparison of `field` against `value`.
+    pub fn compare<V: FilterValue + ?Sized>(field: &str, op: Operator, value: &V) -> Self {
+        let error = (!is_property_path(field)).then(|| format!("Invalid field name '{}'", field));
+        Self {
+            expression: op.render(field, value),
+            kind: ExpressionKind::Term,
+            error,
+        }
+    }
+
+    pub fn eq<V: FilterValue + ?Sized>(field: &str, value: &V) -> Self {
+        Self::compare(field, Operator::Eq, value)
+    }
+
+    pub fn ne<V: FilterValue + ?Sized>(field: &str, value: &V) -> Self {
+        Self::compare(field, Operator::Ne, value)
+    }
+
+    pub fn gt<V: FilterValue + ?Sized>(field: &str, value: &V) -> Self {
+        Self::compare(field, Operator::Gt, value)
+    }
+
+    pub fn ge<V: FilterValue + ?Sized>(field: &str, value: &V) -> Self {
+        Self::compare(field, Operator::Ge, value)
+    }
+
+    pub fn lt<V: FilterValue + ?Sized>(field: &str, value: &V) -> Self {
+        Self::compare(field, Operator::Lt, value)
+    }
+
+    pub fn le<V: FilterValue + ?Sized>(field: &str, value: &V) -> Self {
+        Self::compare(field, Operator::Le, value)
+    }
+
+    /// `contains(field,'text')`
+    pub fn contains(field: &str, text: &str) -> Self {
+        Self::compare(field, Operator::Contains, text)
+    }
+
+    /// `startswith(field,'text')`
+    pub fn starts_with(field: &str, text: &str) -> Self {
+        Self::compare(field, Operator::StartsWith, text)
+    }
+
+    /// `field in (...)`. An empty list is an error, since it can never match.
+    pub fn in_list<V: FilterValue>(field: &str, values: &[V]) -> Self {
+        let mut filter = Self::compare(field, Operator::In, values);
+        if values.is_empty() && filter.error.is_none() {
+            filter.error = Some(format!("Empty value list for '{}'", field));
+        }
+        filter
+    }
+
+    /// Both filters must match.
+    pub fn and(self, other: FilterBuilder) -> Self {
+        self.combine(other, ExpressionKind::And)
+    }
+
+    /// Either filter must match.
+    pub fn or(self, other: FilterBuilder) -> Self {
+        self.combine(other, ExpressionKind::Or)
+    }
+
+    fn combine(self, other: FilterBuilder, kind: ExpressionKind) -> Self {
+        let keyword = if kind == ExpressionKind::And { "and" } else { "or" };
+        // An `or` inside an `and` needs parentheses; the reverse does not
+        let operand = |filter: FilterBuilder| {
+            if kind == ExpressionKind::And && filter.kind == ExpressionKind::Or {
+                format!("({})", filter.expression)
+            } else {
+                filter.expression
+            }
+        };
+        let error = self.error.clone().or_else(|| other.error.clone());
+        Self {
+            expression: format!("{} {} {}", operand(self), keyword, operand(other)),
+            kind,
+            error,
+        }
+    }
+
+    /// The filter expression, ready for [`build_query`](crate::build_query).
+    ///
+    /// Returns `ResoError::InvalidQuery` if a field name or value list was
+    /// invalid.
+    pub fn build(&self) -> Result<String, ResoError> {
+        match &self.error {
+            Some(message) => Err(ResoError::InvalidQuery(message.clone())),
+            None => Ok(self.expression.clone()),
+        }
+    }
+}
+
+/// `not (...)`: the filter must not match.
+impl Not for FilterBuilder {
+    type Output = FilterBuilder;
+
+    fn not(self) -> Self::Output {
+        Self {
+            expression: format!("not ({})", self.expression),
+            kind: ExpressionKind::Term,
+            error: self.error,
+        }
+    }
+}
+
+impl fmt::Display for FilterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Whether `name` is a property path such as `ListPrice` or `ListOffice/OfficeName`.
+///
+/// Operators and literals such as `and` or `null` are not, since they would
+/// change how the filter reads.
+fn is_property_path(name: &str) -> bool {
+    name.split('/').all(|segment| {
+        let mut chars = segment.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !is_keyword(segment)
+    })
+}
+
+/// A type that translates into an OData filter expression.
+pub trait ResoFilter {
+    /// The combined filter, or `None` when no criteria apply.
+    fn to_filter(&self) -> Option<String>;
+}
+
+/// A value that can appear on the right-hand side of a filter comparison.
+pub trait FilterValue {
+    /// Renders the value as an OData literal.
+    fn to_odata(&self) -> String;
+}
+
+impl FilterValue for str {
+    fn to_odata(&self) -> String {
+        quote(self)
+    }
+}
+
+impl FilterValue for String {
+    fn to_odata(&self) -> String {
+        quote(self)
+    }
+}
+
+impl<T: FilterValue + ?Sized> FilterValue for &T {
+    fn to_odata(&self) -> String {
+        (**self).to_odata()
+    }
+}
+
+/// Lists render as a parenthesized collection for [`Operator::In`].
+impl<T: FilterValue> FilterValue for [T] {
+    fn to_odata(&self) -> String {
+        let items: Vec<String> = self.iter().map(FilterValue::to_odata).collect();
+        format!("({})", items.join(","))
+    }
+}
+
+impl<T: FilterValue, const N: usize> FilterValue for [T; N] {
+    fn to_odata(&self) -> String {
+        self.as_slice().to_odata()
+    }
+}
+
+impl<T: FilterValue> FilterValue for Vec<T> {
+    fn to_odata(&self) -> String {
+        self.as_slice().to_odata()
+    }
+}
+
+macro_rules! display_filter_value {
+    ($($ty:ty),*) => {
+        $(
+            impl FilterValue for $ty {
+                fn to_odata(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+display_filter_value!(i8, i16, i32, i64, u8, u16, u32, u64, bool);
+
+macro_rules! float_filter_value {
+    ($($ty:ty),*) => {
+        $(
+            /// Non-finite values render as OData's `NaN`, `INF`, and `-INF`.
+            impl FilterValue for $ty {
+                fn to_odata(&self) -> String {
+                    if self.is_nan() {
+                        "NaN".to_string()
+                    } else if self.is_infinite() {
+                        if *self > 0.0 { "INF" } else { "-INF" }.to_string()
+                    } else {
+                        self.to_string()
+                    }
+                }
+            }
+        )*
+    };
+}
+
+float_filter_value!(f32, f64);
+
+/// Dates render as OData `Edm.Date` literals, e.g. `2025-01-31`.
+impl FilterValue for NaiveDate {
+    fn to_odata(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
+/// Timestamps render as `Edm.DateTimeOffset` literals in UTC.
+impl FilterValue for DateTime<Utc> {
+    fn to_odata(&self) -> String {
+        self.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(ResoFilter)]
+    struct Criteria {
+        #[filter(field = "StandardStatus")]
+        status: String,
+        #[filter(field = "City")]
+        city: Option<String>,
+        #[filter(field = "BedroomsTotal", op = "ge")]
+        min_beds: Option<u32>,
+        #[filter(field = "ListingContractDate", op = "gt")]
+        listed_after: Option<NaiveDate>,
+        #[filter(field = "PostalCode", op = "in")]
+        postal_codes: Option<Vec<String>>,
+        #[filter(field = "PublicRemarks", op = "contains")]
+        remarks: Option<String>,
+    }
+
+    #[test]
+    fn test_derived_filter_skips_unset_options() {
+        let criteria = Criteria {
+            status: "Active".into(),
+            city: Some("O'Fallon".into()),
+            min_beds: None,
+            listed_after: NaiveDate::from_ymd_opt(2025, 1, 31),
+            postal_codes: None,
+            remarks: None,
+        };
+        assert_eq!(
+            criteria.to_filter().unwrap(),
+            "StandardStatus eq 'Active' and City eq 'O''Fallon' and ListingContractDate gt 2025-01-31"
+        );
+    }
+
+    #[test]
+    fn test_derived_filter_string_functions_and_in() {
+        let criteria = Criteria {
+            status: "Active".into(),
+            city: None,
+            min_beds: Some(3),
+            listed_after: None,
+            postal_codes: Some(vec!["78701".into(), "78702".into()]),
+            remarks: Some("pool".into()),
+        };
+        assert_eq!(
+            criteria.to_filter().unwrap(),
+            "StandardStatus eq 'Active' and BedroomsTotal ge 3 \
+             and PostalCode in ('78701','78702') and contains(PublicRemarks,'pool')"
+        );
+    }
+
+    #[test]
+    fn test_filter_builder_precedence_and_validation() {
+        let any_city = FilterBuilder::eq("City", "Austin").or(FilterBuilder::eq("City", "Dallas"));
+        let filter = FilterBuilder::ge("BedroomsTotal", &3).and(any_city.clone());
+        assert_eq!(
+            filter.build().unwrap(),
+            "BedroomsTotal ge 3 and (City eq 'Austin' or City eq 'Dallas')"
+        );
+        // `and` binds tighter, so no parentheses are needed under `or`
+        let either = FilterBuilder::eq("A", &1)
+            .and(FilterBuilder::eq("B", &2))
+            .or(FilterBuilder::starts_with("ListOffice/OfficeName", "Acme"));
+        assert_eq!(
+            either.build().unwrap(),
+            "A eq 1 and B eq 2 or startswith(ListOffice/OfficeName,'Acme')"
+        );
+        assert_eq!((!any_city).to_string(), "not (City eq 'Austin' or City eq 'Dallas')");
+
+        let injected = FilterBuilder::eq("City eq 'x' or ListPrice", &1).and(FilterBuilder::eq("City", "Austin"));
+        assert!(matches!(injected.build(), Err(ResoError::InvalidQuery(m)) if m.contains("Invalid field name")));
+        assert!(FilterBuilder::eq("null", &1).build().is_err());
+        let empty: [&str; 0] = [];
+        assert!(FilterBuilder::in_list("City", &empty).build().is_err());
+    }
+
+    #[test]
+    fn test_operator_from_str() {
+        assert_eq!("StartsWith".parse::<Operator>().unwrap(), Operator::StartsWith);
+        assert!("like".parse::<Operator>().is_err());
+    }
+
+    #[test]
+    fn test_non_finite_floats_render_as_odata_keywords() {
+        assert_eq!(Operator::Gt.render("ListPrice", &f64::INFINITY), "ListPrice gt INF");
+        assert_eq!(Operator::Lt.render("ListPrice", &f32::NEG_INFINITY), "ListPrice lt -INF");
+        assert_eq!(Operator::Ne.render("ListPrice", &f64::NAN), "ListPrice ne NaN");
+        assert_eq!(Operator::Eq.render("ListPrice", &2.5), "ListPrice eq 2.5");
+    }
+
+    // The properties below protect the escaping guarantees: whatever values
+    // they are given, builders must emit filters that parse back into the
+    // intended structure, with every value confined to its own literal.
+    mod properties {
+        use super::*;
+        use crate::demo::Parser;
+        use crate::validate::{tokenize, Literal, Token};
+        use proptest::prelude::*;
+        use reso_client::JsonValue;
+        use serde_json::json;
+
+        const FIELDS: [&str; 3] = ["City", "ListOffice/OfficeName", "StandardStatus"];
+
+        fn path(name: &str) -> Token {
+            Token::Path(name.to_string())
+        }
+
+        fn string(value: &str) -> Token {
+            Token::Literal(Literal::String(value.to_string()))
+        }
+
+        /// A random filter, evaluated independently of its rendering.
+        #[derive(Debug, Clone)]
+        enum Node {
+            Eq(usize, String),
+            And(Box<Node>, Box<Node>),
+            Or(Box<Node>, Box<Node>),
+            Not(Box<Node>),
+        }
+
+        impl Node {
+            fn build(&self) -> FilterBuilder {
+                match self {
+                    Node::Eq(field, value) => FilterBuilder::eq(FIELDS[*field], value.as_str()),
+                    Node::And(a, b) => a.build().and(b.build()),
+                    Node::Or(a, b) => a.build().or(b.build()),
+                    Node::Not(a) => !a.build(),
+                }
+            }
+
+            fn matches(&self, record: &[&str; 3]) -> bool {
+                match self {
+                    Node::Eq(field, value) => record[*field] == value,
+                    Node::And(a, b) => a.matches(record) && b.matches(record),
+                    Node::Or(a, b) => a.matches(record) || b.matches(record),
+                    Node::Not(a) => !a.matches(record),
+                }
+            }
+        }
+
+        fn node() -> impl Strategy<Value = Node> {
+            // Mostly values the record can hold, so comparisons are often true
+            let value = prop_oneof![Just("x".to_string()), Just("y".to_string()), any::<String>()];
+            let leaf = (0..FIELDS.len(), value).prop_map(|(field, value)| Node::Eq(field, value));
+            leaf.prop_recursive(5, 32, 2, |inner| {
+                prop_oneof![
+                    (inner.clone(), inner.clone()).prop_map(|(a, b)| Node::And(Box::new(a), Box::new(b))),
+                    (inner.clone(), inner.clone()).prop_map(|(a, b)| Node::Or(Box::new(a), Box::new(b))),
+                    inner.prop_map(|a| Node::Not(Box::new(a))),
+                ]
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn string_values_stay_inside_one_literal(
+                field in "[A-Z][A-Za-z0-9_]{0,15}",
+                value in any::<String>(),
+                op in prop::sample::select(Operator::ALL[..8].to_vec()),
+            ) {
+                prop_assume!(!matches!(field.as_str(), "INF" | "NaN"));
+                let expected = match op {
+                    Operator::Contains | Operator::StartsWith => vec![
+                        path(op.as_str()), Token::Open, path(&field), Token::Comma, string(&value), Token::Close,
+                    ],
+                    _ => vec![path(&field), path(op.as_str()), string(&value)],
+                };
+                let filter = FilterBuilder::compare(&field, op, value.as_str()).build().unwrap();
+                prop_assert_eq!(tokenize(&filter), expected);
+            }
+
+            #[test]
+            fn list_values_stay_inside_their_literals(values in prop::collection::vec(any::<String>(), 1..8)) {
+                let filter = FilterBuilder::in_list("PostalCode", &values).build().unwrap();
+                let mut expected = vec![path("PostalCode"), path("in"), Token::Open];
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        expected.push(Token::Comma);
+                    }
+                    expected.push(string(value));
+                }
+                expected.push(Token::Close);
+                prop_assert_eq!(tokenize(&filter), expected);
+            }
+
+            #[test]
+            fn numbers_render_as_single_literals(int in any::<i64>(), float in any::<f64>()) {
+                let tokens = tokenize(&FilterBuilder::ge("ListPrice", &int).build().unwrap());
+                prop_assert_eq!(
+                    tokens,
+                    vec![path("ListPrice"), path("ge"), Token::Literal(Literal::Integer(int.to_string()))]
+                );
+
+                let rendered = float.to_odata();
+                let tokens = tokenize(&FilterBuilder::lt("ListPrice", &float).build().unwrap());
+                prop_assert_eq!(tokens.len(), 3);
+                if float.is_finite() {
+                    prop_assert!(matches!(&tokens[2], Token::Literal(Literal::Integer(n) | Literal::Decimal(n)) if *n == rendered));
+                    prop_assert_eq!(rendered.parse::<f64>().unwrap(), float);
+                }
+            }
+
+            #[test]
+            fn invalid_field_names_are_rejected(field in any::<String>(), value in any::<String>()) {
+                match FilterBuilder::eq(&field, value.as_str()).build() {
+                    Ok(filter) => prop_assert_eq!(&tokenize(&filter)[..2], &[path(&field), path("eq")]),
+                    Err(e) => prop_assert!(matches!(e, ResoError::InvalidQuery(_))),
+                }
+            }
+
+            #[test]
+            fn combined_filters_keep_their_meaning(
+                node in node(),
+                record in prop::array::uniform3(prop_oneof![Just("x"), Just("y")]),
+            ) {
+                let filter = node.build().build().unwrap();
+                let parsed = Parser::parse(&filter).map_err(|e| TestCaseError::fail(format!("{:?}", e)))?;
+                let json = json!({
+                    "City": record[0],
+                    "ListOffice": {"OfficeName": record[1]},
+                    "StandardStatus": record[2],
+                });
+                prop_assert_eq!(parsed.eval(&json), JsonValue::Bool(node.matches(&record)));
+            }
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Adapters for servers that do not answer in standard OData JSON.
+//!
+//! The helpers in this crate read records from the `value` array of an
+//! OData JSON response. Older or nonstandard servers answer differently:
+//! OData v2 wraps records in `{"d": {"results": [...]}}`, some vendor APIs
+//! return a bare array or their own envelope, and a few still serve
+//! Atom/XML feeds. A [`ResponseFormat`] converts such a body into the
+//! standard envelope, so an [`HttpClient`] configured with
+//! [`response_format`](crate::client::HttpClientBuilder::response_format)
+//! works with every helper unchanged:
+//!
+//! - [`ODataJson`], the default, parses standard responses as they are
+//! - [`JsonEnvelope`] reads records, next link, and count from JSON pointers
+//! - [`Atom`] reads Atom feeds and entries
+//! - [`Detect`] picks one of the above from the body itself
+//!
+//! [`HttpClient`]: crate::client::HttpClient
+
+use reso_client::{JsonValue, ResoError};
+use serde_json::{Map, Number};
+
+/// Converts response bodies into standard OData JSON.
+///
+/// Collections become `{"value": [...]}`, with `@odata.nextLink` and
+/// `@odata.count` when the server sent them; single entities, from key
+/// lookups, become a plain object.
+pub trait ResponseFormat: Send + Sync {
+    /// The `Accept` header sent with data requests.
+    fn accept(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn parse(&self, body: &str) -> Result<JsonValue, ResoError>;
+}
+
+/// Standard OData JSON, parsed as is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ODataJson;
+
+impl ResponseFormat for ODataJson {
+    fn parse(&self, body: &str) -> Result<JsonValue, ResoError> {
+        serde_json::from_str(body).map_err(|e| ResoError::Parse(format!("Failed to parse JSON: {}", e)))
+    }
+}
+
+/// JSON with records somewhere other than a top-level `value` array.
+///
+/// Locations are JSON pointers, e.g. `/d/results`; the empty pointer is the
+/// whole document. A body whose records pointer does not resolve to an
+/// array is passed through, so key lookups still return their entity.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::format::{JsonEnvelope, ResponseFormat};
+///
+/// let format = JsonEnvelope::new("/data/listings").next_link("/paging/next");
+/// let body = r#"{"data": {"listings": [{"ListingKey": "A"}]}, "paging": {"next": "https://x.test/p2"}}"#;
+/// let response = format.parse(body).unwrap();
+/// assert_eq!(response["value"][0]["ListingKey"], "A");
+/// assert_eq!(response["@odata.nextLink"], "https://x.test/p2");
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonEnvelope {
+    records: String,
+    next_link: Option<String>,
+    count: Option<String>,
+}
+
+impl JsonEnvelope {
+    /// Reads records from the array at `records`.
+    pub fn new(records: impl Into<String>) -> Self {
+        Self {
+            records: records.into(),
+            next_link: None,
+            count: None,
+        }
+    }
+
+    /// OData v2 (`{"d": {"results": [...], "__next": ..., "__count": ...}}`).
+    pub fn odata_v2() -> Self {
+        Self::new("/d/results").next_link("/d/__next").count("/d/__count")
+    }
+
+    /// A body that is itself the array of records.
+    pub fn bare_array() -> Self {
+        Self::new("")
+    }
+
+    /// Reads the next link from the string at `pointer`.
+    pub fn next_link(mut self, pointer: impl Into<String>) -> Self {
+        self.next_link = Some(pointer.into());
+        self
+    }
+
+    /// Reads the total count from `pointer`, a number or numeric string.
+    pub fn count(mut self, pointer: impl Into<String>) -> Self {
+        self.count = Some(pointer.into());
+        self
+    }
+}
+
+impl ResponseFormat for JsonEnvelope {
+    fn parse(&self, body: &str) -> Result<JsonValue, ResoError> {
+        let mut json = ODataJson.parse(body)?;
+        if !json.pointer(&self.records).is_some_and(JsonValue::is_array) {
+            // OData v2 key lookups wrap the entity in `d` alone
+            if self.records.starts_with("/d/") && json["d"].is_object() {
+                return Ok(json["d"].take());
+            }
+            return Ok(json);
+        }
+        let records = json.pointer_mut(&self.records).map(JsonValue::take).unwrap_or_default();
+
+        let mut envelope = Map::new();
+        envelope.insert("value".to_string(), records);
+        let next_link = self.next_link.as_deref().and_then(|p| json.pointer(p)).filter(|link| link.is_string());
+        if let Some(link) = next_link {
+            envelope.insert("@odata.nextLink".to_string(), link.clone());
+        }
+        let count = self.count.as_deref().and_then(|p| json.pointer(p)).and_then(|count| {
+            count.as_u64().or_else(|| count.as_str()?.parse().ok())
+        });
+        if let Some(count) = count {
+            envelope.insert("@odata.count".to_string(), count.into());
+        }
+        Ok(JsonValue::Object(envelope))
+    }
+}
+
+/// Atom/XML feeds and entries, as served by OData v2 and v3 servers.
+///
+/// Entry properties are converted by their `m:type`: integers, decimals,
+/// and booleans become JSON numbers and booleans, `m:null="true"` becomes
+/// null, and everything else (dates, enumerations, strings) a string.
+/// Complex properties become objects and collections arrays. Element names
+/// are matched without their namespace, so v2, v3, and v4 Atom all parse.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::format::{Atom, ResponseFormat};
+///
+/// let feed = r#"<feed xmlns="http://www.w3.org/2005/Atom"
+///     xmlns:d="http://schemas.microsoft.com/ado/2007/08/dataservices"
+///     xmlns:m="http://schemas.microsoft.com/ado/2007/08/dataservices/metadata">
+///   <entry><content type="application/xml"><m:properties>
+///     <d:ListingKey>A</d:ListingKey>
+///     <d:ListPrice m:type="Edm.Decimal">450000</d:ListPrice>
+///   </m:properties></content></entry>
+/// </feed>"#;
+/// let response = Atom.parse(feed).unwrap();
+/// assert_eq!(response["value"][0]["ListingKey"], "A");
+/// assert_eq!(response["value"][0]["ListPrice"], 450000.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Atom;
+
+impl ResponseFormat for Atom {
+    fn accept(&self) -> &'static str {
+        "application/atom+xml"
+    }
+
+    fn parse(&self, body: &str) -> Result<JsonValue, ResoError> {
+        let document = roxmltree::Document::parse(body)
+            .map_err(|e| ResoError::Parse(format!("Invalid Atom XML: {}", e)))?;
+        let root = document.root_element();
+        match root.tag_name().name() {
+            "entry" => Ok(atom_entry(root)),
+            "feed" => {
+                let mut envelope = Map::new();
+                let records: Vec<JsonValue> = root
+                    .children()
+                    .filter(|node| is_element(node, "entry"))
+                    .map(atom_entry)
+                    .collect();
+                envelope.insert("value".to_string(), records.into());
+                for child in root.children().filter(|node| node.is_element()) {
+                    match child.tag_name().name() {
+                        "link" if child.attribute("rel") == Some("next") => {
+                            if let Some(href) = child.attribute("href") {
+                                envelope.insert("@odata.nextLink".to_string(), href.into());
+                            }
+                        }
+                        "count" => {
+                            if let Some(count) = child.text().and_then(|text| text.trim().parse::<u64>().ok()) {
+                                envelope.insert("@odata.count".to_string(), count.into());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(JsonValue::Object(envelope))
+            }
+            other => Err(ResoError::Parse(format!(
+                "Expected an Atom feed or entry, found <{}>",
+                other
+            ))),
+        }
+    }
+}
+
+/// Standard JSON, OData v2 JSON, bare arrays, or Atom, told apart by the
+/// body.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Detect;
+
+impl ResponseFormat for Detect {
+    fn accept(&self) -> &'static str {
+        "application/json, application/atom+xml;q=0.9"
+    }
+
+    fn parse(&self, body: &str) -> Result<JsonValue, ResoError> {
+        let trimmed = body.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with('<') {
+            return Atom.parse(trimmed);
+        }
+        let json = ODataJson.parse(trimmed)?;
+        if json.is_array() {
+            return JsonEnvelope::bare_array().parse(trimmed);
+        }
+        if json.get("value").is_none() && json["d"].is_object() {
+            return JsonEnvelope::odata_v2().parse(trimmed);
+        }
+        Ok(json)
+    }
+}
+
+fn is_element(node: &roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+/// The properties of an Atom entry as a JSON object.
+fn atom_entry(entry: roxmltree::Node) -> JsonValue {
+    // Media link entries keep their properties beside `content` instead of
+    // in it. Deeper ones belong to inline (expanded) entries.
+    let properties = entry
+        .children()
+        .filter(|node| is_element(node, "content"))
+        .flat_map(|content| content.children())
+        .chain(entry.children())
+        .find(|node| is_element(node, "properties"));
+    let mut record = Map::new();
+    for property in properties.into_iter().flat_map(|p| p.children()).filter(|n| n.is_element()) {
+        record.insert(property.tag_name().name().to_string(), atom_value(property));
+    }
+    JsonValue::Object(record)
+}
+
+fn atom_value(node: roxmltree::Node) -> JsonValue {
+    // Attributes are matched by local name, whatever their namespace prefix
+    let attribute = |name: &str| node.attributes().find(|a| a.name() == name).map(|a| a.value());
+    if attribute("null") == Some("true") {
+        return JsonValue::Null;
+    }
+    let children: Vec<roxmltree::Node> = node.children().filter(|n| n.is_element()).collect();
+    if !children.is_empty() {
+        return if children.iter().all(|child| child.tag_name().name() == "element") {
+            children.into_iter().map(atom_value).collect::<Vec<_>>().into()
+        } else {
+            children
+                .into_iter()
+                .map(|child| (child.tag_name().name().to_string(), atom_value(child)))
+                .collect::<Map<_, _>>()
+                .into()
+        };
+    }
+
+    let text = node.text().unwrap_or_default();
+    let number = match attribute("type").unwrap_or("Edm.String") {
+        "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" | "Edm.Int64" => {
+            text.trim().parse::<i64>().ok().map(Number::from)
+        }
+        "Edm.Decimal" | "Edm.Double" | "Edm.Single" => text.trim().parse::<f64>().ok().and_then(Number::from_f64),
+        "Edm.Boolean" => return JsonValue::Bool(text.trim() == "true"),
+        _ => None,
+    };
+    number.map_or_else(|| JsonValue::String(text.to_string()), JsonValue::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpClient;
+    use reso_client::ClientConfig;
+    use serde_json::json;
+    use wiremock::matchers::{header, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom"
+      xmlns:d="http://schemas.microsoft.com/ado/2007/08/dataservices"
+      xmlns:m="http://schemas.microsoft.com/ado/2007/08/dataservices/metadata">
+  <m:count>2</m:count>
+  <entry>
+    <id>https://x.test/Property('A')</id>
+    <content type="application/xml">
+      <m:properties>
+        <d:ListingKey>A</d:ListingKey>
+        <d:BedroomsTotal m:type="Edm.Int32">3</d:BedroomsTotal>
+        <d:ListPrice m:type="Edm.Decimal">450000.50</d:ListPrice>
+        <d:PoolPrivateYN m:type="Edm.Boolean">false</d:PoolPrivateYN>
+        <d:ClosePrice m:type="Edm.Decimal" m:null="true" />
+        <d:ModificationTimestamp m:type="Edm.DateTimeOffset">2025-01-02T03:04:05Z</d:ModificationTimestamp>
+        <d:Appliances m:type="Collection(Edm.String)">
+          <d:element>Dishwasher</d:element>
+          <d:element>Range</d:element>
+        </d:Appliances>
+      </m:properties>
+    </content>
+  </entry>
+  <entry>
+    <content type="application/xml"><m:properties><d:ListingKey>B</d:ListingKey></m:properties></content>
+  </entry>
+  <link rel="next" href="https://x.test/Property?$skiptoken=2" />
+</feed>"#;
+
+    #[test]
+    fn test_atom_feed_becomes_odata_json() {
+        let response = Atom.parse(FEED).unwrap();
+        assert_eq!(
+            response["value"][0],
+            json!({
+                "ListingKey": "A",
+                "BedroomsTotal": 3,
+                "ListPrice": 450000.5,
+                "PoolPrivateYN": false,
+                "ClosePrice": null,
+                "ModificationTimestamp": "2025-01-02T03:04:05Z",
+                "Appliances": ["Dishwasher", "Range"]
+            })
+        );
+        assert_eq!(response["value"][1], json!({"ListingKey": "B"}));
+        assert_eq!(response["@odata.nextLink"], "https://x.test/Property?$skiptoken=2");
+        assert_eq!(response["@odata.count"], 2);
+        assert!(matches!(Atom.parse("<html/>"), Err(ResoError::Parse(_))));
+    }
+
+    #[test]
+    fn test_atom_entry_ignores_inline_entries() {
+        let entry = r#"<entry xmlns="http://www.w3.org/2005/Atom"
+      xmlns:d="http://schemas.microsoft.com/ado/2007/08/dataservices"
+      xmlns:m="http://schemas.microsoft.com/ado/2007/08/dataservices/metadata">
+  <link rel="http://schemas.microsoft.com/ado/2007/08/dataservices/related/Media">
+    <m:inline><feed><entry>
+      <content type="application/xml"><m:properties><d:MediaKey>M1</d:MediaKey></m:properties></content>
+    </entry></feed></m:inline>
+  </link>
+  <content type="image/jpeg" src="https://x.test/photo.jpg" />
+  <m:properties><d:ListingKey>A</d:ListingKey></m:properties>
+</entry>"#;
+        assert_eq!(Atom.parse(entry).unwrap(), json!({"ListingKey": "A"}));
+    }
+
+    #[test]
+    fn test_detect_normalizes_nonstandard_json() {
+        let v2 = r#"{"d": {"results": [{"ListingKey": "A"}], "__count": "10", "__next": "https://x.test/p2"}}"#;
+        assert_eq!(
+            Detect.parse(v2).unwrap(),
+            json!({"value": [{"ListingKey": "A"}], "@odata.nextLink": "https://x.test/p2", "@odata.count": 10})
+        );
+        assert_eq!(Detect.parse(r#"{"d": {"ListingKey": "A"}}"#).unwrap(), json!({"ListingKey": "A"}));
+        assert_eq!(Detect.parse(r#"[{"ListingKey": "A"}]"#).unwrap(), json!({"value": [{"ListingKey": "A"}]}));
+        let standard = json!({"value": [], "@odata.count": 0});
+        assert_eq!(Detect.parse(&standard.to_string()).unwrap(), standard);
+        assert_eq!(Detect.parse(FEED).unwrap()["value"][0]["ListingKey"], "A");
+    }
+
+    #[tokio::test]
+    async fn test_client_replicates_atom_feed() {
+        let server = MockServer::start().await;
+        Mock::given(path_regex("^/Property"))
+            .and(header("Accept", "application/atom+xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(FEED))
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .response_format(Atom)
+            .build()
+            .unwrap();
+        let query = crate::build_query("Property", None, Some(2)).unwrap();
+        let response = crate::execute_query(&client, &query).await.unwrap();
+        assert_eq!(response["value"].as_array().unwrap().len(), 2);
+
+        let query = crate::build_replication_query("Property", None).unwrap();
+        let page = crate::execute_replication_query(&client, &query).await.unwrap();
+        assert_eq!(page.records.len(), 2);
+        assert_eq!(page.next_link.as_deref(), Some("https://x.test/Property?$skiptoken=2"));
+    }
+}
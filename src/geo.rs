@@ -0,0 +1,724 @@
+//! Geographic helpers for location-based RESO queries.
+//!
+//! Most RESO servers expose `Latitude` and `Longitude` as plain numeric
+//! fields, so radius searches are expressed as a bounding-box filter on
+//! those fields. The helpers here compute the box and the exact
+//! great-circle distance used to refine and rank results client-side.
+//! Areas drawn on a map are searched the same way: a [`Polygon`] sends its
+//! bounding box and keeps the listings inside its outline.
+//!
+//! Servers with geospatial support can filter on the exact circle instead:
+//! [`build_query_near_geo`] sends `geo.distance` on a geography field, and
+//! [`detect_capabilities`](crate::capabilities::detect_capabilities) reports
+//! whether the server accepts it.
+
+use crate::client::ResoApi;
+use crate::error::is_rejected_query;
+use crate::MAX_PAGE_SIZE;
+use reso_client::{JsonValue, Query, QueryBuilder, ResoError};
+
+/// Mean Earth radius in miles.
+pub const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+/// Meters in a statute mile.
+const METERS_PER_MILE: f64 = 1609.344;
+
+/// A latitude/longitude bounding box in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+impl BoundingBox {
+    /// Computes the smallest box that contains a circle of `radius_miles`
+    /// around the given point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::geo::BoundingBox;
+    ///
+    /// let bbox = BoundingBox::around(30.2672, -97.7431, 5.0);
+    /// assert!(bbox.contains(30.2672, -97.7431));
+    /// ```
+    pub fn around(lat: f64, lon: f64, radius_miles: f64) -> Self {
+        let lat_delta = (radius_miles / EARTH_RADIUS_MILES).to_degrees();
+        // Longitude degrees shrink towards the poles; clamp the cosine so the
+        // box stays finite for points very close to a pole.
+        let cos_lat = lat.to_radians().cos().max(1e-6);
+        let lon_delta = (radius_miles / (EARTH_RADIUS_MILES * cos_lat)).to_degrees();
+
+        Self {
+            min_lat: (lat - lat_delta).max(-90.0),
+            min_lon: (lon - lon_delta).max(-180.0),
+            max_lat: (lat + lat_delta).min(90.0),
+            max_lon: (lon + lon_delta).min(180.0),
+        }
+    }
+
+    /// Returns true if the point lies inside the box (inclusive).
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        lat >= self.min_lat && lat <= self.max_lat && lon >= self.min_lon && lon <= self.max_lon
+    }
+
+    /// Renders the box as an OData filter on the `Latitude`/`Longitude` fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::geo::BoundingBox;
+    ///
+    /// let bbox = BoundingBox { min_lat: 30.0, min_lon: -98.0, max_lat: 31.0, max_lon: -97.0 };
+    /// assert_eq!(
+    ///     bbox.to_filter(),
+    ///     "Latitude ge 30 and Latitude le 31 and Longitude ge -98 and Longitude le -97"
+    /// );
+    /// ```
+    pub fn to_filter(&self) -> String {
+        format!(
+            "Latitude ge {} and Latitude le {} and Longitude ge {} and Longitude le {}",
+            self.min_lat, self.max_lat, self.min_lon, self.max_lon
+        )
+    }
+}
+
+/// Builds a query for records inside a bounding box, e.g. the visible area
+/// of a map.
+///
+/// Returns `ResoError::InvalidQuery` for coordinates out of range or a
+/// box whose minimum exceeds its maximum.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::geo::build_query_in_bbox;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let query = build_query_in_bbox("Property", 30.2, -97.8, 30.3, -97.7, Some("StandardStatus eq 'Active'"), Some(100))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn build_query_in_bbox(
+    resource: &str,
+    min_lat: f64,
+    min_lon: f64,
+    max_lat: f64,
+    max_lon: f64,
+    filter: Option<&str>,
+    top: Option<u32>,
+) -> Result<Query, ResoError> {
+    let bbox = BoundingBox { min_lat, min_lon, max_lat, max_lon };
+    for (lat, lon) in [(min_lat, min_lon), (max_lat, max_lon)] {
+        check_point(lat, lon)?;
+    }
+    if min_lat > max_lat || min_lon > max_lon {
+        return Err(ResoError::InvalidQuery(format!(
+            "Invalid bounding box: ({}, {}) is not below and west of ({}, {})",
+            min_lat, min_lon, max_lat, max_lon
+        )));
+    }
+    location_query(resource, &bbox.to_filter(), filter, top)
+}
+
+/// Builds a Property query for records within about `radius_miles` of a
+/// point, as a `Latitude`/`Longitude` bounding box that every server
+/// understands.
+///
+/// The box includes its corners, which lie outside the circle; drop them
+/// with [`filter_by_distance`], or use [`build_query_near_geo`] on servers
+/// with `geo.distance`.
+pub fn build_query_near(
+    lat: f64,
+    lon: f64,
+    radius_miles: f64,
+    filter: Option<&str>,
+    top: Option<u32>,
+) -> Result<Query, ResoError> {
+    check_point(lat, lon)?;
+    check_radius(radius_miles)?;
+    location_query("Property", &BoundingBox::around(lat, lon, radius_miles).to_filter(), filter, top)
+}
+
+/// Like [`build_query_near`], with an exact `geo.distance` filter on a
+/// geography field such as `Coordinates` (see [`distance_filter`]).
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::geo::build_query_near_geo;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let query = build_query_near_geo("Coordinates", 30.2672, -97.7431, 2.0, None, Some(50))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn build_query_near_geo(
+    field: &str,
+    lat: f64,
+    lon: f64,
+    radius_miles: f64,
+    filter: Option<&str>,
+    top: Option<u32>,
+) -> Result<Query, ResoError> {
+    check_point(lat, lon)?;
+    check_radius(radius_miles)?;
+    location_query("Property", &distance_filter(field, lat, lon, radius_miles), filter, top)
+}
+
+/// Renders a `geo.distance` filter selecting points of a geography field
+/// within `radius_miles` of a point. The distance is compared in meters,
+/// the unit of geography distances.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::geo::distance_filter;
+///
+/// assert_eq!(
+///     distance_filter("Coordinates", 30.25, -97.75, 1.0),
+///     "geo.distance(Coordinates,geography'SRID=4326;POINT(-97.75 30.25)') le 1609.344"
+/// );
+/// ```
+pub fn distance_filter(field: &str, lat: f64, lon: f64, radius_miles: f64) -> String {
+    format!(
+        "geo.distance({},geography'SRID=4326;POINT({} {})') le {}",
+        field,
+        lon,
+        lat,
+        radius_miles * METERS_PER_MILE
+    )
+}
+
+fn check_point(lat: f64, lon: f64) -> Result<(), ResoError> {
+    if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+        Ok(())
+    } else {
+        Err(ResoError::InvalidQuery(format!("Invalid coordinates ({}, {})", lat, lon)))
+    }
+}
+
+fn check_radius(radius_miles: f64) -> Result<(), ResoError> {
+    if radius_miles.is_finite() && radius_miles >= 0.0 {
+        Ok(())
+    } else {
+        Err(ResoError::InvalidQuery(format!("Invalid radius {} miles", radius_miles)))
+    }
+}
+
+/// A query combining a location filter with the caller's filter.
+fn location_query(resource: &str, location: &str, filter: Option<&str>, top: Option<u32>) -> Result<Query, ResoError> {
+    let combined = match filter {
+        Some(expr) => format!("({}) and {}", expr, location),
+        None => location.to_string(),
+    };
+    let mut builder = QueryBuilder::new(resource).filter(combined);
+    if let Some(n) = top {
+        builder = builder.top(n);
+    }
+    builder.build()
+}
+
+/// Great-circle distance between two points in miles, using the haversine formula.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::geo::haversine_miles;
+///
+/// // Austin to Dallas is roughly 182 miles as the crow flies
+/// let miles = haversine_miles(30.2672, -97.7431, 32.7767, -96.7970);
+/// assert!((miles - 182.0).abs() < 2.0);
+/// ```
+pub fn haversine_miles(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_MILES * a.sqrt().asin()
+}
+
+/// Reads the `Latitude`/`Longitude` fields of a record, if both are present.
+pub fn record_coordinates(record: &JsonValue) -> Option<(f64, f64)> {
+    let lat = record["Latitude"].as_f64()?;
+    let lon = record["Longitude"].as_f64()?;
+    Some((lat, lon))
+}
+
+/// A listing returned by [`find_nearby`] with its distance from the search point.
+#[derive(Debug, Clone)]
+pub struct NearbyListing {
+    /// The listing as returned by the server
+    pub record: JsonValue,
+    /// Exact great-circle distance from the search point in miles
+    pub distance_miles: f64,
+}
+
+/// Finds listings within a radius of a point, nearest first.
+///
+/// Servers without `geo.distance` support can still narrow results with a
+/// bounding-box filter on `Latitude`/`Longitude`. This function sends that box
+/// (combined with the optional filter), then drops records outside the exact
+/// radius and sorts the remainder by distance client-side.
+///
+/// At most 200 candidates are fetched, so very dense areas should be
+/// narrowed with an additional filter.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `lat` - Latitude of the search point
+/// * `lon` - Longitude of the search point
+/// * `radius_miles` - Search radius in miles
+/// * `filter` - Optional OData filter expression combined with the bounding box
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, geo::find_nearby};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let nearby = find_nearby(&client, 30.2672, -97.7431, 2.0, Some("StandardStatus eq 'Active'")).await?;
+///
+///     for listing in nearby {
+///         println!("{} - {:.2} mi", listing.record["ListingKey"], listing.distance_miles);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn find_nearby(
+    client: &impl ResoApi,
+    lat: f64,
+    lon: f64,
+    radius_miles: f64,
+    filter: Option<&str>,
+) -> Result<Vec<NearbyListing>, ResoError> {
+    let query = build_query_near(lat, lon, radius_miles, filter, Some(MAX_PAGE_SIZE))?;
+    let response = client.execute(&query).await?;
+
+    let records = response["value"].as_array().cloned().unwrap_or_default();
+    Ok(filter_by_distance(records, lat, lon, radius_miles))
+}
+
+/// Keeps records within `radius_miles` of the point, sorted nearest first.
+///
+/// Records without coordinates are dropped.
+pub fn filter_by_distance(
+    records: Vec<JsonValue>,
+    lat: f64,
+    lon: f64,
+    radius_miles: f64,
+) -> Vec<NearbyListing> {
+    let mut nearby: Vec<NearbyListing> = records
+        .into_iter()
+        .filter_map(|record| {
+            let (rec_lat, rec_lon) = record_coordinates(&record)?;
+            let distance_miles = haversine_miles(lat, lon, rec_lat, rec_lon);
+            (distance_miles <= radius_miles).then_some(NearbyListing {
+                record,
+                distance_miles,
+            })
+        })
+        .collect();
+
+    nearby.sort_by(|a, b| a.distance_miles.total_cmp(&b.distance_miles));
+    nearby
+}
+
+/// A closed area, e.g. a shape drawn on a map, in decimal degrees.
+///
+/// Vertices are `(latitude, longitude)` pairs; the ring closes itself, so
+/// the last vertex need not repeat the first. Containment is computed on a
+/// flat projection, which is accurate for neighborhood- and city-sized
+/// areas; shapes crossing the antimeridian are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<(f64, f64)>,
+}
+
+impl Polygon {
+    /// Builds a polygon from at least three distinct vertices.
+    ///
+    /// Returns `ResoError::InvalidQuery` for too few vertices or
+    /// coordinates outside the valid latitude and longitude ranges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::geo::Polygon;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let downtown = Polygon::new(vec![(30.26, -97.75), (30.26, -97.73), (30.28, -97.73), (30.28, -97.75)])?;
+    /// assert!(downtown.contains(30.27, -97.74));
+    /// assert!(!downtown.contains(30.29, -97.74));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(mut vertices: Vec<(f64, f64)>) -> Result<Self, ResoError> {
+        if let Some(&(lat, lon)) = vertices
+            .iter()
+            .find(|(lat, lon)| !(-90.0..=90.0).contains(lat) || !(-180.0..=180.0).contains(lon))
+        {
+            return Err(ResoError::InvalidQuery(format!("Invalid coordinates ({}, {})", lat, lon)));
+        }
+        vertices.dedup();
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        if vertices.len() < 3 {
+            return Err(ResoError::InvalidQuery(
+                "A polygon needs at least three distinct points".to_string(),
+            ));
+        }
+        Ok(Self { vertices })
+    }
+
+    /// The vertices as `(latitude, longitude)` pairs, without the closing vertex.
+    pub fn vertices(&self) -> &[(f64, f64)] {
+        &self.vertices
+    }
+
+    /// The smallest box containing the polygon.
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.vertices.iter().fold(
+            BoundingBox {
+                min_lat: f64::INFINITY,
+                min_lon: f64::INFINITY,
+                max_lat: f64::NEG_INFINITY,
+                max_lon: f64::NEG_INFINITY,
+            },
+            |bbox, &(lat, lon)| BoundingBox {
+                min_lat: bbox.min_lat.min(lat),
+                min_lon: bbox.min_lon.min(lon),
+                max_lat: bbox.max_lat.max(lat),
+                max_lon: bbox.max_lon.max(lon),
+            },
+        )
+    }
+
+    /// Returns true if the point lies inside the polygon.
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        // Count crossings of a ray cast east from the point
+        let mut inside = false;
+        let mut previous = self.vertices[self.vertices.len() - 1];
+        for &current in &self.vertices {
+            let ((lat1, lon1), (lat2, lon2)) = (previous, current);
+            if (lat1 > lat) != (lat2 > lat) && lon < lon1 + (lat - lat1) / (lat2 - lat1) * (lon2 - lon1) {
+                inside = !inside;
+            }
+            previous = current;
+        }
+        inside
+    }
+
+    /// Renders a `geo.intersects` filter for servers that expose a
+    /// geography field, e.g. `Coordinates`.
+    ///
+    /// Few RESO servers support geospatial functions; [`find_in_polygon`]
+    /// works everywhere by filtering on the bounding box instead, and
+    /// [`find_in_polygon_geo`] tries this filter before falling back to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::geo::Polygon;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let area = Polygon::new(vec![(30.0, -98.0), (30.0, -97.0), (31.0, -97.0)])?;
+    /// assert_eq!(
+    ///     area.to_geo_filter("Coordinates"),
+    ///     "geo.intersects(Coordinates,geography'SRID=4326;POLYGON((-98 30,-97 30,-97 31,-98 30))')"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_geo_filter(&self, field: &str) -> String {
+        // Well-known text lists longitude first and repeats the first vertex
+        let ring: Vec<String> = self
+            .vertices
+            .iter()
+            .chain(self.vertices.first())
+            .map(|(lat, lon)| format!("{} {}", lon, lat))
+            .collect();
+        format!(
+            "geo.intersects({},geography'SRID=4326;POLYGON(({}))')",
+            field,
+            ring.join(",")
+        )
+    }
+}
+
+/// Renders a `geo.intersects` filter for the polygon through `points`,
+/// given as `(latitude, longitude)` pairs.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::geo::polygon_filter;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let filter = polygon_filter("Coordinates", &[(30.26, -97.75), (30.26, -97.73), (30.28, -97.74)])?;
+/// assert!(filter.starts_with("geo.intersects(Coordinates,"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn polygon_filter(field: &str, points: &[(f64, f64)]) -> Result<String, ResoError> {
+    Ok(Polygon::new(points.to_vec())?.to_geo_filter(field))
+}
+
+/// Finds listings inside a polygon, e.g. one drawn on a map.
+///
+/// Like [`find_nearby`], this sends the polygon's bounding box (combined
+/// with the optional filter) as a `Latitude`/`Longitude` filter, then keeps
+/// the records inside the polygon itself. At most 200 candidates are
+/// fetched from the box.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `polygon` - The area to search
+/// * `filter` - Optional OData filter expression combined with the bounding box
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, geo::{find_in_polygon, Polygon}};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let area = Polygon::new(vec![(30.26, -97.75), (30.26, -97.73), (30.28, -97.74)])?;
+///     let listings = find_in_polygon(&client, &area, Some("StandardStatus eq 'Active'")).await?;
+///     println!("{} listings in the area", listings.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn find_in_polygon(
+    client: &impl ResoApi,
+    polygon: &Polygon,
+    filter: Option<&str>,
+) -> Result<Vec<JsonValue>, ResoError> {
+    let bbox_filter = polygon.bounding_box().to_filter();
+    let query = location_query("Property", &bbox_filter, filter, Some(MAX_PAGE_SIZE))?;
+    let response = client.execute(&query).await?;
+
+    let records = response["value"].as_array().cloned().unwrap_or_default();
+    Ok(records
+        .into_iter()
+        .filter(|record| record_coordinates(record).is_some_and(|(lat, lon)| polygon.contains(lat, lon)))
+        .collect())
+}
+
+/// Like [`find_in_polygon`], letting the server match the polygon with
+/// `geo.intersects` on a geography field such as `Coordinates`.
+///
+/// Servers without geospatial support reject the filter or ignore it; either
+/// way the search is repeated as [`find_in_polygon`], and the records are
+/// checked against the polygon client-side. Other failures, such as a
+/// rejected token or a rate limit, are returned.
+pub async fn find_in_polygon_geo(
+    client: &impl ResoApi,
+    polygon: &Polygon,
+    field: &str,
+    filter: Option<&str>,
+) -> Result<Vec<JsonValue>, ResoError> {
+    let query = location_query("Property", &polygon.to_geo_filter(field), filter, Some(MAX_PAGE_SIZE))?;
+
+    // A server that ignored the filter may answer with listings outside the
+    // polygon, so only an answer that is entirely inside is trusted
+    match client.execute(&query).await {
+        Ok(response) => {
+            if let Some(records) = response["value"].as_array() {
+                let inside = |record: &JsonValue| {
+                    record_coordinates(record).is_none_or(|(lat, lon)| polygon.contains(lat, lon))
+                };
+                if records.iter().all(inside) {
+                    return Ok(records.clone());
+                }
+            }
+        }
+        Err(e) if is_rejected_query(&e) => {}
+        Err(e) => return Err(e),
+    }
+    find_in_polygon(client, polygon, filter).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_haversine_zero_distance() {
+        assert_eq!(haversine_miles(30.0, -97.0, 30.0, -97.0), 0.0);
+    }
+
+    #[test]
+    fn test_bounding_box_covers_radius() {
+        let (lat, lon) = (30.2672, -97.7431);
+        let bbox = BoundingBox::around(lat, lon, 10.0);
+
+        // Points 10 miles due north and due east must fall inside the box
+        let north = lat + (10.0 / EARTH_RADIUS_MILES).to_degrees();
+        assert!(bbox.contains(north - 1e-9, lon));
+        assert!((haversine_miles(lat, lon, lat, bbox.max_lon) - 10.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_polygon_contains_and_validation() {
+        // An L shape: the notch at the top right is outside
+        let shape = Polygon::new(vec![
+            (30.0, -98.0),
+            (30.0, -97.0),
+            (30.5, -97.0),
+            (30.5, -97.5),
+            (31.0, -97.5),
+            (31.0, -98.0),
+            (30.0, -98.0),
+        ])
+        .unwrap();
+        assert_eq!(shape.vertices().len(), 6);
+        assert!(shape.contains(30.25, -97.25));
+        assert!(shape.contains(30.75, -97.75));
+        assert!(!shape.contains(30.75, -97.25));
+        assert_eq!(
+            shape.bounding_box(),
+            BoundingBox { min_lat: 30.0, min_lon: -98.0, max_lat: 31.0, max_lon: -97.0 }
+        );
+
+        assert!(Polygon::new(vec![(30.0, -98.0), (30.0, -98.0), (31.0, -97.0)]).is_err());
+        assert!(Polygon::new(vec![(30.0, -98.0), (91.0, -97.0), (31.0, -97.0)]).is_err());
+    }
+
+    #[test]
+    fn test_location_query_builders() {
+        let query = build_query_in_bbox("Property", 30.0, -98.0, 31.0, -97.0, Some("ListPrice gt 0"), Some(10)).unwrap();
+        let odata = query.to_odata_string();
+        assert!(odata.contains("$top=10"), "{}", odata);
+
+        assert!(matches!(
+            build_query_in_bbox("Property", 31.0, -98.0, 30.0, -97.0, None, None),
+            Err(ResoError::InvalidQuery(_))
+        ));
+        assert!(build_query_in_bbox("Property", 30.0, -181.0, 31.0, -97.0, None, None).is_err());
+        assert!(build_query_near(95.0, -97.0, 1.0, None, None).is_err());
+        assert!(matches!(build_query_near(30.0, -97.0, -1.0, None, None), Err(ResoError::InvalidQuery(_))));
+        assert!(matches!(
+            build_query_near_geo("Coordinates", 30.0, -97.0, f64::NAN, None, None),
+            Err(ResoError::InvalidQuery(_))
+        ));
+        assert!(build_query_near_geo("Coordinates", 30.0, -97.0, 0.5, None, Some(5)).is_ok());
+        assert_eq!(
+            distance_filter("Coordinates", 30.0, -97.0, 0.5),
+            "geo.distance(Coordinates,geography'SRID=4326;POINT(-97 30)') le 804.672"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_polygon_search_falls_back_without_geo_support() {
+        use reso_client::ClientConfig;
+        use serde_json::json;
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param(
+                "$filter",
+                "Latitude ge 30 and Latitude le 31 and Longitude ge -98 and Longitude le -97",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [
+                {"ListingKey": "in", "Latitude": 30.2, "Longitude": -97.2},
+                {"ListingKey": "out", "Latitude": 30.8, "Longitude": -97.9},
+            ]})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": {"code": "400", "message": "Unknown function geo.intersects"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let triangle = Polygon::new(vec![(30.0, -98.0), (30.0, -97.0), (31.0, -97.0)]).unwrap();
+        let records = find_in_polygon_geo(&client, &triangle, "Coordinates", None).await.unwrap();
+
+        let keys: Vec<&str> = records.iter().filter_map(|r| r["ListingKey"].as_str()).collect();
+        assert_eq!(keys, ["in"]);
+
+        // Failures other than the filter being turned down are not retried
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let rejected = find_in_polygon_geo(&client, &triangle, "Coordinates", None).await;
+        assert!(matches!(rejected, Err(ResoError::Unauthorized { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_find_nearby_rejects_invalid_point_and_radius() {
+        use reso_client::ClientConfig;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let invalid = [(f64::NAN, -97.0, 1.0), (30.0, 200.0, 1.0), (30.0, -97.0, -1.0), (30.0, -97.0, f64::INFINITY)];
+        for (lat, lon, radius) in invalid {
+            let result = find_nearby(&client, lat, lon, radius, None).await;
+            assert!(matches!(result, Err(ResoError::InvalidQuery(_))), "({}, {}, {})", lat, lon, radius);
+        }
+    }
+
+    #[test]
+    fn test_polygon_filter_needs_three_points() {
+        assert!(polygon_filter("Coordinates", &[(30.0, -98.0), (31.0, -97.0)]).is_err());
+        assert_eq!(
+            polygon_filter("Coordinates", &[(30.0, -98.0), (30.0, -97.0), (31.0, -97.0)]).unwrap(),
+            "geo.intersects(Coordinates,geography'SRID=4326;POLYGON((-98 30,-97 30,-97 31,-98 30))')"
+        );
+    }
+
+    #[test]
+    fn test_record_coordinates() {
+        let record = serde_json::json!({"Latitude": 30.1, "Longitude": -97.2});
+        assert_eq!(record_coordinates(&record), Some((30.1, -97.2)));
+        assert_eq!(record_coordinates(&serde_json::json!({"Latitude": 30.1})), None);
+    }
+
+    #[test]
+    fn test_filter_by_distance_sorts_and_drops() {
+        let records = vec![
+            serde_json::json!({"ListingKey": "b", "Latitude": 30.02, "Longitude": -97.0}),
+            serde_json::json!({"ListingKey": "a", "Latitude": 30.01, "Longitude": -97.0}),
+            serde_json::json!({"ListingKey": "corner", "Latitude": 30.019, "Longitude": -96.978}),
+            serde_json::json!({"ListingKey": "no-coords"}),
+        ];
+
+        let nearby = filter_by_distance(records, 30.0, -97.0, 1.5);
+        let keys: Vec<_> = nearby
+            .iter()
+            .map(|n| n.record["ListingKey"].as_str().unwrap())
+            .collect();
+        assert_eq!(keys, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,612 @@
+//! Geocoding support for listings that lack coordinates.
+//!
+//! Many MLS feeds omit `Latitude`/`Longitude`, which breaks radius and map
+//! searches. The [`Geocoder`] trait abstracts over geocoding services, with
+//! adapters for OpenStreetMap Nominatim and the Google Geocoding API, and
+//! [`fill_missing_coordinates`] applies a geocoder to a batch of records as
+//! part of a sync pipeline. Geocoders that support reverse lookups can also
+//! backfill blank locality fields via [`fill_missing_locality`].
+
+use crate::rate_limit::{RateLimit, RateLimiter};
+use async_trait::async_trait;
+use reso_client::{JsonValue, ResoError};
+
+/// A geographic point in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Administrative areas derived from a point by reverse geocoding.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Locality {
+    pub neighborhood: Option<String>,
+    pub city: Option<String>,
+    pub county: Option<String>,
+}
+
+/// A service that converts free-form addresses into coordinates.
+///
+/// Implementations return `Ok(None)` when the address could not be matched
+/// and reserve errors for transport or service failures.
+#[async_trait]
+pub trait Geocoder: Send + Sync {
+    /// Looks up the coordinates of an address.
+    async fn geocode(&self, address: &str) -> Result<Option<Coordinates>, ResoError>;
+
+    /// Looks up the administrative areas containing a point.
+    ///
+    /// The default implementation reports no match, for services that only
+    /// support forward geocoding.
+    async fn reverse_geocode(&self, _coords: Coordinates) -> Result<Option<Locality>, ResoError> {
+        Ok(None)
+    }
+}
+
+/// Geocoder backed by an OpenStreetMap Nominatim server.
+///
+/// The public instance at `nominatim.openstreetmap.org` requires an
+/// identifying User-Agent and allows at most one request per second, which
+/// the geocoder enforces; clones share the budget. Large backfills should
+/// point at a self-hosted server via [`NominatimGeocoder::with_base_url`]
+/// and lift the limit with [`NominatimGeocoder::with_rate_limit`].
+#[derive(Debug, Clone)]
+pub struct NominatimGeocoder {
+    http: reqwest::Client,
+    base_url: String,
+    user_agent: String,
+    limiter: RateLimiter,
+}
+
+impl NominatimGeocoder {
+    /// Creates a geocoder for the public Nominatim instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - Identifies your application, as required by the Nominatim usage policy
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: "https://nominatim.openstreetmap.org".to_string(),
+            user_agent: user_agent.into(),
+            limiter: RateLimiter::new(RateLimit {
+                requests_per_second: Some(1.0),
+                max_concurrent: 1,
+                ..RateLimit::default()
+            }),
+        }
+    }
+
+    /// Points the geocoder at a different Nominatim server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Replaces the one-request-per-second budget, e.g. for a self-hosted
+    /// server.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.limiter = RateLimiter::new(limit);
+        self
+    }
+}
+
+#[async_trait]
+impl Geocoder for NominatimGeocoder {
+    async fn geocode(&self, address: &str) -> Result<Option<Coordinates>, ResoError> {
+        let body = self
+            .limiter
+            .run(|| async move {
+                let response = self
+                    .http
+                    .get(format!("{}/search", self.base_url))
+                    .query(&[("q", address), ("format", "jsonv2"), ("limit", "1")])
+                    .header("User-Agent", &self.user_agent)
+                    .send()
+                    .await
+                    .map_err(|e| ResoError::Network(e.to_string()))?;
+                read_json(response).await
+            })
+            .await?;
+        Ok(parse_nominatim_response(&body))
+    }
+
+    async fn reverse_geocode(&self, coords: Coordinates) -> Result<Option<Locality>, ResoError> {
+        let body = self
+            .limiter
+            .run(|| async move {
+                let response = self
+                    .http
+                    .get(format!("{}/reverse", self.base_url))
+                    .query(&[
+                        ("lat", coords.latitude.to_string()),
+                        ("lon", coords.longitude.to_string()),
+                        ("format", "jsonv2".to_string()),
+                    ])
+                    .header("User-Agent", &self.user_agent)
+                    .send()
+                    .await
+                    .map_err(|e| ResoError::Network(e.to_string()))?;
+                read_json(response).await
+            })
+            .await?;
+        Ok(parse_nominatim_reverse_response(&body))
+    }
+}
+
+/// Geocoder backed by the Google Geocoding API.
+#[derive(Debug, Clone)]
+pub struct GoogleGeocoder {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl GoogleGeocoder {
+    /// Creates a geocoder using the given API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: "https://maps.googleapis.com/maps/api/geocode/json".to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Creates a geocoder from the `GOOGLE_MAPS_API_KEY` environment variable.
+    pub fn from_env() -> Result<Self, ResoError> {
+        let api_key = std::env::var("GOOGLE_MAPS_API_KEY")
+            .map_err(|_| ResoError::Config("GOOGLE_MAPS_API_KEY not set".into()))?;
+        Ok(Self::new(api_key))
+    }
+}
+
+#[async_trait]
+impl Geocoder for GoogleGeocoder {
+    async fn geocode(&self, address: &str) -> Result<Option<Coordinates>, ResoError> {
+        let response = self
+            .http
+            .get(&self.base_url)
+            .query(&[("address", address), ("key", self.api_key.as_str())])
+            .send()
+            .await
+            .map_err(|e| ResoError::Network(e.to_string()))?;
+
+        let body = read_json(response).await?;
+        parse_google_response(&body)
+    }
+
+    async fn reverse_geocode(&self, coords: Coordinates) -> Result<Option<Locality>, ResoError> {
+        let latlng = format!("{},{}", coords.latitude, coords.longitude);
+        let response = self
+            .http
+            .get(&self.base_url)
+            .query(&[("latlng", latlng.as_str()), ("key", self.api_key.as_str())])
+            .send()
+            .await
+            .map_err(|e| ResoError::Network(e.to_string()))?;
+
+        let body = read_json(response).await?;
+        parse_google_reverse_response(&body)
+    }
+}
+
+async fn read_json(response: reqwest::Response) -> Result<JsonValue, ResoError> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(ResoError::ODataError {
+            message: format!("Geocoding request failed: {}", body),
+            status_code: status.as_u16(),
+        });
+    }
+
+    response
+        .json::<JsonValue>()
+        .await
+        .map_err(|e| ResoError::Parse(format!("Failed to parse geocoder response: {}", e)))
+}
+
+/// Extracts the first match from a Nominatim `jsonv2` search response.
+///
+/// Nominatim encodes coordinates as strings.
+pub fn parse_nominatim_response(body: &JsonValue) -> Option<Coordinates> {
+    let first = body.as_array()?.first()?;
+    let latitude = first["lat"].as_str()?.parse().ok()?;
+    let longitude = first["lon"].as_str()?.parse().ok()?;
+    Some(Coordinates {
+        latitude,
+        longitude,
+    })
+}
+
+/// Checks the `status` of a Google Geocoding API response.
+///
+/// Returns `Ok(false)` for `ZERO_RESULTS` and an error for any status other than `OK`.
+fn google_status_ok(body: &JsonValue) -> Result<bool, ResoError> {
+    match body["status"].as_str() {
+        Some("OK") => Ok(true),
+        Some("ZERO_RESULTS") => Ok(false),
+        status => Err(ResoError::ODataError {
+            message: format!(
+                "Google geocoding failed: {} {}",
+                status.unwrap_or("unknown status"),
+                body["error_message"].as_str().unwrap_or("")
+            ),
+            status_code: 200,
+        }),
+    }
+}
+
+/// Extracts the first match from a Google Geocoding API response.
+///
+/// `ZERO_RESULTS` is treated as "no match"; any other non-`OK` status is an error.
+pub fn parse_google_response(body: &JsonValue) -> Result<Option<Coordinates>, ResoError> {
+    if !google_status_ok(body)? {
+        return Ok(None);
+    }
+
+    let location = &body["results"][0]["geometry"]["location"];
+    Ok(location["lat"].as_f64().zip(location["lng"].as_f64()).map(
+        |(latitude, longitude)| Coordinates {
+            latitude,
+            longitude,
+        },
+    ))
+}
+
+/// Extracts the locality from a Nominatim `jsonv2` reverse response.
+///
+/// Nominatim's address keys vary by region, so the first populated key of
+/// each kind is used (e.g. `neighbourhood`, then `suburb`, then `quarter`).
+pub fn parse_nominatim_reverse_response(body: &JsonValue) -> Option<Locality> {
+    let address = body.get("address")?;
+    let first_of = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| address[*key].as_str())
+            .map(|s| s.to_string())
+    };
+
+    let locality = Locality {
+        neighborhood: first_of(&["neighbourhood", "suburb", "quarter"]),
+        city: first_of(&["city", "town", "village", "hamlet"]),
+        county: first_of(&["county"]),
+    };
+    (locality != Locality::default()).then_some(locality)
+}
+
+/// Extracts the locality from a Google Geocoding API reverse response.
+pub fn parse_google_reverse_response(body: &JsonValue) -> Result<Option<Locality>, ResoError> {
+    if !google_status_ok(body)? {
+        return Ok(None);
+    }
+
+    let components = body["results"][0]["address_components"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let component_of = |kind: &str| {
+        components
+            .iter()
+            .find(|c| {
+                c["types"]
+                    .as_array()
+                    .is_some_and(|types| types.iter().any(|t| t == kind))
+            })
+            .and_then(|c| c["long_name"].as_str())
+            .map(|s| s.to_string())
+    };
+
+    let locality = Locality {
+        neighborhood: component_of("neighborhood"),
+        city: component_of("locality"),
+        county: component_of("administrative_area_level_2"),
+    };
+    Ok((locality != Locality::default()).then_some(locality))
+}
+
+/// Builds a geocodable address from a Property record.
+///
+/// Prefers `UnparsedAddress` and falls back to the street components,
+/// appending city, state, and postal code when available.
+pub fn record_address(record: &JsonValue) -> Option<String> {
+    let street = match record["UnparsedAddress"].as_str() {
+        Some(address) if !address.trim().is_empty() => address.trim().to_string(),
+        _ => ["StreetNumber", "StreetDirPrefix", "StreetName", "StreetSuffix"]
+            .iter()
+            .filter_map(|field| record[*field].as_str())
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    if street.is_empty() {
+        return None;
+    }
+
+    let mut parts = vec![street.clone()];
+    for field in ["City", "StateOrProvince", "PostalCode"] {
+        if let Some(value) = record[field].as_str() {
+            // UnparsedAddress frequently already includes the locality
+            if !value.is_empty() && !street.contains(value) {
+                parts.push(value.to_string());
+            }
+        }
+    }
+
+    Some(parts.join(", "))
+}
+
+/// Counts reported by [`fill_missing_coordinates`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GeocodeStats {
+    /// Records that were missing coordinates and were filled in
+    pub geocoded: usize,
+    /// Records that already had coordinates
+    pub skipped: usize,
+    /// Records the geocoder could not match, or that had no usable address
+    pub unmatched: usize,
+}
+
+/// Fills in missing `Latitude`/`Longitude` on a batch of Property records.
+///
+/// Intended as a pipeline step applied to each batch during replication,
+/// before records are stored. Records that already have coordinates are
+/// left untouched. Requests are issued sequentially to respect geocoder
+/// rate limits.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, build_replication_query, execute_replication_query};
+/// use reso_examples::geocode::{fill_missing_coordinates, NominatimGeocoder};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let geocoder = NominatimGeocoder::new("my-app/1.0 (ops@example.com)");
+///
+///     let query = build_replication_query("Property", None)?;
+///     let mut response = execute_replication_query(&client, &query).await?;
+///     let stats = fill_missing_coordinates(&geocoder, &mut response.records).await?;
+///     println!("Geocoded {} records", stats.geocoded);
+///     Ok(())
+/// }
+/// ```
+pub async fn fill_missing_coordinates<G: Geocoder + ?Sized>(
+    geocoder: &G,
+    records: &mut [JsonValue],
+) -> Result<GeocodeStats, ResoError> {
+    let mut stats = GeocodeStats::default();
+
+    for record in records.iter_mut() {
+        if crate::geo::record_coordinates(record).is_some() {
+            stats.skipped += 1;
+            continue;
+        }
+
+        let Some(address) = record_address(record) else {
+            stats.unmatched += 1;
+            continue;
+        };
+
+        match geocoder.geocode(&address).await? {
+            Some(coords) => {
+                record["Latitude"] = coords.latitude.into();
+                record["Longitude"] = coords.longitude.into();
+                stats.geocoded += 1;
+            }
+            None => stats.unmatched += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Field that receives the reverse-geocoded neighborhood.
+///
+/// The Data Dictionary has no dedicated neighborhood field on Property;
+/// `SubdivisionName` is the closest standard field and is what most
+/// search UIs display as the neighborhood.
+pub const NEIGHBORHOOD_FIELD: &str = "SubdivisionName";
+
+/// Field that receives the reverse-geocoded county.
+pub const COUNTY_FIELD: &str = "CountyOrParish";
+
+/// Fills blank neighborhood and county fields on a batch of Property records.
+///
+/// Only records that have coordinates and are missing at least one of
+/// [`NEIGHBORHOOD_FIELD`] or [`COUNTY_FIELD`] are looked up, and existing
+/// values are never overwritten. Run this after [`fill_missing_coordinates`]
+/// so freshly geocoded records are enriched too.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::geocode::{fill_missing_locality, NominatimGeocoder};
+///
+/// # async fn example(records: &mut [serde_json::Value]) -> Result<(), Box<dyn std::error::Error>> {
+/// let geocoder = NominatimGeocoder::new("my-app/1.0 (ops@example.com)");
+/// let stats = fill_missing_locality(&geocoder, records).await?;
+/// println!("Enriched {} records", stats.geocoded);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fill_missing_locality<G: Geocoder + ?Sized>(
+    geocoder: &G,
+    records: &mut [JsonValue],
+) -> Result<GeocodeStats, ResoError> {
+    let mut stats = GeocodeStats::default();
+
+    for record in records.iter_mut() {
+        let is_blank = |field: &str| record[field].as_str().is_none_or(|s| s.trim().is_empty());
+        if !is_blank(NEIGHBORHOOD_FIELD) && !is_blank(COUNTY_FIELD) {
+            stats.skipped += 1;
+            continue;
+        }
+
+        let Some((latitude, longitude)) = crate::geo::record_coordinates(record) else {
+            stats.unmatched += 1;
+            continue;
+        };
+
+        let coords = Coordinates {
+            latitude,
+            longitude,
+        };
+        match geocoder.reverse_geocode(coords).await? {
+            Some(locality) => {
+                for (field, value) in [
+                    (NEIGHBORHOOD_FIELD, locality.neighborhood),
+                    (COUNTY_FIELD, locality.county),
+                ] {
+                    let blank = record[field].as_str().is_none_or(|s| s.trim().is_empty());
+                    if let (true, Some(value)) = (blank, value) {
+                        record[field] = value.into();
+                    }
+                }
+                stats.geocoded += 1;
+            }
+            None => stats.unmatched += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct FixedGeocoder;
+
+    #[async_trait]
+    impl Geocoder for FixedGeocoder {
+        async fn geocode(&self, address: &str) -> Result<Option<Coordinates>, ResoError> {
+            Ok(address.starts_with("100").then_some(Coordinates {
+                latitude: 30.0,
+                longitude: -97.0,
+            }))
+        }
+
+        async fn reverse_geocode(&self, _coords: Coordinates) -> Result<Option<Locality>, ResoError> {
+            Ok(Some(Locality {
+                neighborhood: Some("Downtown".to_string()),
+                city: Some("Austin".to_string()),
+                county: Some("Travis".to_string()),
+            }))
+        }
+    }
+
+    #[test]
+    fn test_parse_nominatim_response() {
+        let body = json!([{"lat": "30.2672", "lon": "-97.7431"}]);
+        assert_eq!(
+            parse_nominatim_response(&body),
+            Some(Coordinates {
+                latitude: 30.2672,
+                longitude: -97.7431
+            })
+        );
+        assert_eq!(parse_nominatim_response(&json!([])), None);
+    }
+
+    #[test]
+    fn test_parse_google_response() {
+        let ok = json!({"status": "OK", "results": [{"geometry": {"location": {"lat": 30.1, "lng": -97.2}}}]});
+        assert!(parse_google_response(&ok).unwrap().is_some());
+        assert_eq!(parse_google_response(&json!({"status": "ZERO_RESULTS"})).unwrap(), None);
+        assert!(parse_google_response(&json!({"status": "REQUEST_DENIED"})).is_err());
+    }
+
+    #[test]
+    fn test_parse_nominatim_reverse_response() {
+        let body = json!({"address": {"suburb": "Hyde Park", "city": "Austin", "county": "Travis County"}});
+        let locality = parse_nominatim_reverse_response(&body).unwrap();
+        assert_eq!(locality.neighborhood.as_deref(), Some("Hyde Park"));
+        assert_eq!(locality.county.as_deref(), Some("Travis County"));
+        assert_eq!(parse_nominatim_reverse_response(&json!({"error": "Unable to geocode"})), None);
+    }
+
+    #[test]
+    fn test_parse_google_reverse_response() {
+        let body = json!({"status": "OK", "results": [{"address_components": [
+            {"long_name": "Clarksville", "types": ["neighborhood", "political"]},
+            {"long_name": "Travis County", "types": ["administrative_area_level_2", "political"]}
+        ]}]});
+        let locality = parse_google_reverse_response(&body).unwrap().unwrap();
+        assert_eq!(locality.neighborhood.as_deref(), Some("Clarksville"));
+        assert_eq!(locality.county.as_deref(), Some("Travis County"));
+        assert_eq!(locality.city, None);
+    }
+
+    #[tokio::test]
+    async fn test_fill_missing_locality_keeps_existing_values() {
+        let mut records = vec![
+            json!({"Latitude": 30.0, "Longitude": -97.0, "CountyOrParish": "Williamson"}),
+            json!({"Latitude": 30.0, "Longitude": -97.0, "SubdivisionName": "Zilker", "CountyOrParish": "Travis"}),
+            json!({"SubdivisionName": ""}),
+        ];
+
+        let stats = fill_missing_locality(&FixedGeocoder, &mut records).await.unwrap();
+
+        assert_eq!(stats, GeocodeStats { geocoded: 1, skipped: 1, unmatched: 1 });
+        assert_eq!(records[0]["SubdivisionName"], "Downtown");
+        assert_eq!(records[0]["CountyOrParish"], "Williamson");
+    }
+
+    #[test]
+    fn test_record_address() {
+        let record = json!({
+            "StreetNumber": "100", "StreetName": "Congress", "StreetSuffix": "Ave",
+            "City": "Austin", "StateOrProvince": "TX", "PostalCode": "78701"
+        });
+        assert_eq!(
+            record_address(&record).unwrap(),
+            "100 Congress Ave, Austin, TX, 78701"
+        );
+        assert_eq!(record_address(&json!({"City": "Austin"})), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_nominatim_waits_a_second_between_requests() {
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        // Lookups run through the limiter; driving it directly keeps real
+        // sockets, and the timers behind them, away from the paused clock
+        let geocoder = NominatimGeocoder::new("reso-examples-test");
+        let clone = geocoder.clone();
+        let started = Instant::now();
+        let mut starts = Vec::new();
+        for limiter in [&geocoder.limiter, &clone.limiter, &geocoder.limiter] {
+            starts.push(limiter.run(|| async { Ok(started.elapsed()) }).await.unwrap());
+        }
+        assert_eq!(starts, [Duration::ZERO, Duration::from_secs(1), Duration::from_secs(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_fill_missing_coordinates() {
+        let mut records = vec![
+            json!({"UnparsedAddress": "100 Main St"}),
+            json!({"UnparsedAddress": "200 Main St"}),
+            json!({"Latitude": 31.0, "Longitude": -98.0}),
+        ];
+
+        let stats = fill_missing_coordinates(&FixedGeocoder, &mut records)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            stats,
+            GeocodeStats {
+                geocoded: 1,
+                skipped: 1,
+                unmatched: 1
+            }
+        );
+        assert_eq!(records[0]["Latitude"], 30.0);
+        assert!(records[1].get("Latitude").is_none());
+    }
+}
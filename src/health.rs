@@ -0,0 +1,127 @@
+//! Checking that the RESO server is reachable and accepting the token.
+//!
+//! Orchestrators probe a service's health endpoint every few seconds. A
+//! [`HealthChecker`] answers those probes with a cheap one-record query and
+//! reuses the result for a while, so probing does not eat into the feed's
+//! request quota.
+
+use crate::client::ResoApi;
+use reso_client::{QueryBuilder, ResoError};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// The result of a health check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// The server answered the probe query successfully
+    pub healthy: bool,
+    /// How long the probe took, in milliseconds
+    pub latency_ms: u64,
+    /// Why the probe failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Sends a one-record query for `resource` and reports whether it succeeded.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource to probe, e.g. "Property"
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::health::check_health;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let report = check_health(&client, "Property").await;
+///     println!("healthy: {} ({} ms)", report.healthy, report.latency_ms);
+///     Ok(())
+/// }
+/// ```
+pub async fn check_health(client: &impl ResoApi, resource: &str) -> HealthReport {
+    let started = Instant::now();
+    let result: Result<_, ResoError> = async {
+        let query = QueryBuilder::new(resource).top(1).build()?;
+        client.execute(&query).await
+    }
+    .await;
+    HealthReport {
+        healthy: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+/// Runs [`check_health`] at most once per `max_age`, answering other calls
+/// with the last report.
+#[derive(Debug)]
+pub struct HealthChecker {
+    resource: String,
+    max_age: Duration,
+    last: Mutex<Option<(Instant, HealthReport)>>,
+}
+
+impl HealthChecker {
+    pub fn new(resource: &str, max_age: Duration) -> Self {
+        Self {
+            resource: resource.to_string(),
+            max_age,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// The latest report, probing the server if it is older than `max_age`.
+    /// Concurrent callers wait for a single probe.
+    pub async fn check(&self, client: &impl ResoApi) -> HealthReport {
+        let mut last = self.last.lock().await;
+        if let Some((checked, report)) = last.as_ref() {
+            if checked.elapsed() < self.max_age {
+                return report.clone();
+            }
+        }
+        let report = check_health(client, &self.resource).await;
+        *last = Some((Instant::now(), report.clone()));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reso_client::ClientConfig;
+    use serde_json::json;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_checker_reuses_recent_report() {
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+
+        let checker = HealthChecker::new("Property", Duration::from_secs(60));
+        let first = checker.check(&client).await;
+        let second = checker.check(&client).await;
+
+        assert!(!first.healthy);
+        assert!(first.error.is_some());
+        assert_eq!(first, second);
+        assert!(serde_json::to_value(&first).unwrap()["error"].is_string());
+        assert_eq!(
+            serde_json::to_value(HealthReport { healthy: true, latency_ms: 5, error: None }).unwrap(),
+            json!({"healthy": true, "latency_ms": 5})
+        );
+    }
+}
@@ -1,14 +1,126 @@
 //! Core library for interacting with RESO Web API using the reso_client library.
 //!
 //! This library provides convenient functions for:
-//! - Creating and configuring RESO API clients
-//! - Fetching metadata from RESO servers
-//! - Building and executing queries
+//! - Creating and configuring RESO API clients, from the environment or in
+//!   code, including clients with request and response hooks, custom token
+//!   providers, user agents and proxies, OAuth2 client-credentials
+//!   authentication, adapters for Atom/XML and nonstandard JSON responses,
+//!   and pools of clients for several datasets
+//! - Fetching metadata from RESO servers, parsing it into a schema, and
+//!   validating queries against it before they are sent
+//! - Generating field name constants, record structs, and SQL migrations
+//!   from metadata
+//! - Building and executing queries, asynchronously or (with the `blocking`
+//!   feature) synchronously, into JSON or typed records such as
+//!   `models::Property`
+//! - Describing listing searches in real-estate terms
+//!   by city, price, beds, assigned schools, or zoning, and running one
+//!   search across several MLS datasets with merged results, and saving
+//!   searches as JSON to replay later
+//! - Normalizing vendor `MlsStatus` values onto `StandardStatus`, and rolling
+//!   property types up into categories such as house, condo, and land
+//! - Deriving OData filters from annotated structs, or composing them with a typed builder
 //! - Handling common use cases
+//! - Finding comparable properties and other location-based searches
+//! - Geocoding listings that are missing coordinates
+//! - Listing photos in display order with captions and alt text,
+//!   downloading media files within bandwidth and concurrency limits, and
+//!   keeping photos in step with replicated listings
+//! - schema.org `RealEstateListing` structured data and readable URL slugs
+//!   for listing pages
+//! - Caching query results, with a background warmer for popular searches
+//! - Health checks and Prometheus request metrics for deployed services
+//! - Enriching replicated records through pluggable lookups
+//! - Checkpointed replication into JSONL, SQLite, or Parquet sinks, with a
+//!   durable queue of failed runs to retry later, sharded replication of
+//!   large datasets over concurrent streams, and record-at-a-time parsing of
+//!   pages too large to hold in memory
+//! - Manifests of exported files with record counts and SHA-256 checksums
+//! - Compacting snapshot and delta JSONL files, and cross-referencing
+//!   listings duplicated across MLS datasets
+//! - Measuring how often each field changes across a sync history
+//! - Reconciling stored record counts with the server's, and auditing
+//!   sampled records for drift
+//! - Cancelling in-flight requests and replication runs, retrying requests
+//!   that fail for transient reasons, and keeping within vendor rate limits
+//! - Scheduling sync jobs, each on its own interval, with Slack, email, or
+//!   custom alerts when a run fails or its record count changes sharply
+//! - Publishing sync progress for monitoring, and stamping records with
+//!   their provenance
+//! - Redacting or pseudonymizing sensitive fields in public-facing exports,
+//!   and scanning free-text fields for email addresses and phone numbers
+//! - Detecting server capabilities and vendor quirks, and classifying
+//!   failed requests by cause with hints for fixing them
+//! - An offline client serving a bundled sample dataset, for trying the
+//!   examples without credentials
 
+pub mod aggregate;
+pub mod audit;
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cancel;
+pub mod capabilities;
+pub mod churn;
+pub mod client;
+pub mod codegen;
+pub mod compact;
+pub mod comps;
+pub mod demo;
+pub mod dictionary;
+pub mod download;
+pub mod duplicates;
+pub mod enrich;
+pub mod error;
+pub mod export;
+pub mod federated;
+pub mod fields;
+pub mod filter;
+pub mod format;
+pub mod geo;
+pub mod geocode;
+pub mod health;
+pub mod listing_status;
+pub mod manifest;
+pub mod media;
+pub mod media_sync;
+pub mod metadata;
+pub mod metrics;
+pub mod migrations;
+pub mod models;
+pub mod pii;
+pub mod pool;
+pub mod provenance;
+pub mod query_string;
+pub mod rate_limit;
+pub mod reconcile;
+pub mod redact;
+pub mod result_cache;
+pub mod retry;
+pub mod retry_queue;
+pub mod saved_search;
+pub mod schema_org;
+pub mod schedule;
+pub mod school_zoning;
+pub mod search;
+pub mod slug;
+pub mod status;
+pub mod streaming;
+pub mod sync;
+pub mod taxonomy;
+pub mod validate;
+
+// Lets `#[derive(ResoFilter)]` output name this crate from inside it
+extern crate self as reso_examples;
+
+use client::ResoApi;
+use futures::stream::{self, Stream, TryStreamExt};
 use reso_client::{ResoClient, QueryBuilder, Query, ResoError, JsonValue, ReplicationQueryBuilder, ReplicationQuery, ReplicationResponse};
 use std::result::Result;
 
+/// Largest page a standard (non-replication) query may request.
+pub(crate) const MAX_PAGE_SIZE: u32 = 200;
+
 /// Creates a ResoClient from environment variables.
 ///
 /// # Environment Variables
@@ -33,7 +145,109 @@ use std::result::Result;
 /// }
 /// ```
 pub fn create_client() -> Result<ResoClient, ResoError> {
-    ResoClient::from_env()
+    let token = std::env::var("RESO_TOKEN").map_err(|_| ResoError::Config("RESO_TOKEN is not set".to_string()))?;
+    ResoClient::with_config(config_from_env(token)?)
+}
+
+/// Reads `RESO_BASE_URL`, `RESO_DATASET_ID`, and `RESO_TIMEOUT` into a
+/// configuration that authenticates with `token`.
+fn config_from_env(token: String) -> Result<reso_client::ClientConfig, ResoError> {
+    let base_url = std::env::var("RESO_BASE_URL")
+        .map_err(|_| ResoError::Config("RESO_BASE_URL is not set".to_string()))?;
+    let mut config = reso_client::ClientConfig::new(base_url, token);
+    if let Ok(dataset_id) = std::env::var("RESO_DATASET_ID") {
+        config = config.with_dataset_id(dataset_id);
+    }
+    if let Ok(timeout) = std::env::var("RESO_TIMEOUT") {
+        let seconds = timeout
+            .trim()
+            .parse()
+            .map_err(|_| ResoError::Config(format!("Invalid RESO_TIMEOUT: '{}'", timeout)))?;
+        config = config.with_timeout(std::time::Duration::from_secs(seconds));
+    }
+    Ok(config)
+}
+
+/// Creates a client from a configuration built in code rather than read
+/// from the environment.
+///
+/// For more settings (a token provider, user agent, proxy, TLS, or hooks)
+/// use [`client::HttpClient::builder`], which takes the same configuration.
+/// Multi-tenant servers can hold one client per MLS.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use reso_client::ClientConfig;
+/// use reso_examples::auth::{ClientCredentials, OAuthTokens};
+/// use reso_examples::client::HttpClient;
+/// use reso_examples::{count_records, create_client_with};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let actris = create_client_with(
+///         ClientConfig::new("https://api.bridgedataoutput.com/api/v2/OData", "bridge_token")
+///             .with_dataset_id("actris_ref")
+///             .with_timeout(Duration::from_secs(60)),
+///     )?;
+///
+///     let credentials = ClientCredentials::new("https://auth.example.com/token", "id", "secret");
+///     let trestle = HttpClient::builder(ClientConfig::new("https://api-trestle.corelogic.com/trestle/odata", ""))
+///         .token_provider(OAuthTokens::new(credentials)?)
+///         .user_agent("listing-portal/2.1")
+///         .proxy("http://proxy.internal:3128")
+///         .build()?;
+///
+///     println!("ACTRIS: {}", count_records(&actris, "Property", None).await?);
+///     println!("Trestle: {}", count_records(&trestle, "Property", None).await?);
+///     Ok(())
+/// }
+/// ```
+pub fn create_client_with(config: reso_client::ClientConfig) -> Result<client::HttpClient, ResoError> {
+    client::HttpClient::builder(config).build()
+}
+
+/// Creates a client that authenticates with the OAuth2 client-credentials
+/// grant, from environment variables.
+///
+/// Access tokens are fetched from the token endpoint on first use and
+/// replaced shortly before they expire (see [`auth::OAuthTokens`]).
+///
+/// # Environment Variables
+///
+/// Required:
+/// - `RESO_BASE_URL`: Base API URL
+/// - `RESO_TOKEN_URL`: OAuth2 token endpoint
+/// - `RESO_CLIENT_ID` and `RESO_CLIENT_SECRET`: Client credentials
+///
+/// Optional:
+/// - `RESO_SCOPE`: Scope to request (e.g., "api" for Trestle)
+/// - `RESO_DATASET_ID`: Dataset identifier (e.g., "actris_ref")
+/// - `RESO_TIMEOUT`: Timeout in seconds (default: 30)
+/// - The TLS and DNS settings read by [`client::HttpClient::from_env`]
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{count_records, create_client_oauth};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client_oauth()?;
+///     println!("{} listings", count_records(&client, "Property", None).await?);
+///     Ok(())
+/// }
+/// ```
+pub fn create_client_oauth() -> Result<client::HttpClient, ResoError> {
+    // The token is never sent; requests carry the OAuth access token
+    let config = config_from_env(String::new())?;
+    let tokens = auth::OAuthTokens::new(auth::ClientCredentials::from_env()?)?;
+    client::HttpClient::builder(config)
+        .oauth(tokens)
+        .tls_from_env()?
+        .dns_from_env()?
+        .build()
 }
 
 /// Fetches the metadata XML document from the RESO server.
@@ -43,7 +257,7 @@ pub fn create_client() -> Result<ResoClient, ResoError> {
 ///
 /// # Arguments
 ///
-/// * `client` - A reference to a configured ResoClient
+/// * `client` - A reference to a configured client, such as a ResoClient
 ///
 /// # Returns
 ///
@@ -62,7 +276,7 @@ pub fn create_client() -> Result<ResoClient, ResoError> {
 ///     Ok(())
 /// }
 /// ```
-pub async fn fetch_metadata(client: &ResoClient) -> Result<String, ResoError> {
+pub async fn fetch_metadata(client: &impl ResoApi) -> Result<String, ResoError> {
     client.fetch_metadata().await
 }
 
@@ -151,7 +365,7 @@ pub fn build_query_with_select(
 ///
 /// # Arguments
 ///
-/// * `client` - A reference to a configured ResoClient
+/// * `client` - A reference to a configured client, such as a ResoClient
 /// * `query` - A reference to the query to execute
 ///
 /// # Returns
@@ -175,15 +389,218 @@ pub fn build_query_with_select(
 ///     Ok(())
 /// }
 /// ```
-pub async fn execute_query(client: &ResoClient, query: &Query) -> Result<JsonValue, ResoError> {
+pub async fn execute_query(client: &impl ResoApi, query: &Query) -> Result<JsonValue, ResoError> {
     client.execute(query).await
 }
 
+/// Executes a query and deserializes the records of its `value` array,
+/// e.g. into [`models::Property`].
+///
+/// A record that does not fit the type fails the whole call, naming the
+/// record's position in the response.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `query` - A reference to the query to execute
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::models::Property;
+/// use reso_examples::{build_query, create_client, execute_query_typed};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_query("Property", Some("City eq 'Austin'"), Some(10))?;
+///     let listings = execute_query_typed::<Property>(&client, &query).await?;
+///
+///     for listing in listings {
+///         println!("{} {:?}", listing.listing_key, listing.list_price);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn execute_query_typed<T: serde::de::DeserializeOwned>(
+    client: &impl ResoApi,
+    query: &Query,
+) -> Result<Vec<T>, ResoError> {
+    let mut response = client.execute(query).await?;
+    let records = match response["value"].take() {
+        JsonValue::Array(records) => records,
+        _ => return Err(ResoError::Parse("Response has no 'value' array".to_string())),
+    };
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            serde_json::from_value(record).map_err(|e| {
+                ResoError::Parse(format!(
+                    "Record {} is not a valid {}: {}",
+                    i,
+                    std::any::type_name::<T>().rsplit("::").next().unwrap_or_default(),
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Options for [`execute_query_with`].
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteOptions {
+    /// Run a cheap `$top=0&$count=true` query first to learn how many
+    /// records the search matches
+    pub preflight_count: bool,
+    /// With `preflight_count`, skip the search when more records than this
+    /// would match
+    pub max_expected: Option<u64>,
+}
+
+/// The result of [`execute_query_with`].
+#[derive(Debug, Clone)]
+pub struct QueryOutcome {
+    /// Records matching the query's filter, if a preflight ran
+    pub expected_total: Option<u64>,
+    /// The query's response, or `None` if the preflight total exceeded
+    /// `max_expected`
+    pub response: Option<JsonValue>,
+}
+
+/// Executes a query, optionally checking how many records it matches first.
+///
+/// The preflight lets applications warn before launching a search that
+/// would match hundreds of thousands of records, and lets them page through
+/// the results knowing the total.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `query` - A reference to the query to execute
+/// * `options` - Whether to run a preflight count, and the largest total to proceed with
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{build_query, create_client, execute_query_with, ExecuteOptions};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_query("Property", Some("StandardStatus eq 'Active'"), Some(100))?;
+///     let options = ExecuteOptions {
+///         preflight_count: true,
+///         max_expected: Some(10_000),
+///     };
+///
+///     let outcome = execute_query_with(&client, &query, &options).await?;
+///     match outcome.response {
+///         Some(response) => println!("{}", response),
+///         None => println!("{:?} listings match; narrow the search", outcome.expected_total),
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn execute_query_with(
+    client: &impl ResoApi,
+    query: &Query,
+    options: &ExecuteOptions,
+) -> Result<QueryOutcome, ResoError> {
+    let expected_total = if options.preflight_count {
+        Some(preflight_count(client, query).await?)
+    } else {
+        None
+    };
+
+    if let (Some(total), Some(max)) = (expected_total, options.max_expected) {
+        if total > max {
+            return Ok(QueryOutcome {
+                expected_total,
+                response: None,
+            });
+        }
+    }
+
+    Ok(QueryOutcome {
+        expected_total,
+        response: Some(client.execute(query).await?),
+    })
+}
+
+/// Executes a query, retrying transient failures with exponential backoff.
+///
+/// Network errors, rate limiting, and 5xx responses are retried according
+/// to `policy`; other errors are returned at once (see
+/// [`retry::is_retryable`]).
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `query` - A reference to the query to execute
+/// * `policy` - How many retries to make and how long to wait between them
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::retry::RetryPolicy;
+/// use reso_examples::{build_query, create_client, execute_query_with_retry};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_query("Property", Some("City eq 'Austin'"), Some(10))?;
+///     let response = execute_query_with_retry(&client, &query, RetryPolicy::default()).await?;
+///     println!("{}", response);
+///     Ok(())
+/// }
+/// ```
+pub async fn execute_query_with_retry(
+    client: &impl ResoApi,
+    query: &Query,
+    policy: retry::RetryPolicy,
+) -> Result<JsonValue, ResoError> {
+    retry::with_retry(&policy, || client.execute(query)).await
+}
+
+/// Counts the records a collection query matches, using
+/// `$top=0&$count=true` so no records are transferred.
+///
+/// The query's `$filter` and `$apply` are kept; its other options
+/// (`$select`, `$orderby`, `$top`, ...) do not change the count and are
+/// left out. Key lookups have nothing to count and are rejected, as are
+/// `$search` queries, whose search terms cannot be carried over.
+pub async fn preflight_count(client: &impl ResoApi, query: &Query) -> Result<u64, ResoError> {
+    let request = query_string::ODataRequest::from_query(query)?;
+    if request.path.contains('(') {
+        return Err(ResoError::InvalidQuery(
+            "Key lookups cannot be counted".to_string(),
+        ));
+    }
+    if request.option("$search").is_some() {
+        return Err(ResoError::InvalidQuery(
+            "$search queries cannot be counted ahead".to_string(),
+        ));
+    }
+
+    let mut builder = QueryBuilder::new(request.resource()).top(0).with_count();
+    if let Some(filter) = request.option("$filter") {
+        builder = builder.filter(filter);
+    }
+    if let Some(apply) = request.option("$apply") {
+        builder = builder.apply(apply);
+    }
+    let response = client.execute(&builder.build()?).await?;
+    response["@odata.count"]
+        .as_u64()
+        .ok_or_else(|| ResoError::Parse("Server did not return @odata.count".to_string()))
+}
+
 /// Executes a count-only query to get the total number of matching records.
 ///
 /// # Arguments
 ///
-/// * `client` - A reference to a configured ResoClient
+/// * `client` - A reference to a configured client, such as a ResoClient
 /// * `resource` - The resource name (e.g., "Property", "Member", "Office")
 /// * `filter` - Optional OData filter expression
 ///
@@ -205,7 +622,7 @@ pub async fn execute_query(client: &ResoClient, query: &Query) -> Result<JsonVal
 /// }
 /// ```
 pub async fn count_records(
-    client: &ResoClient,
+    client: &impl ResoApi,
     resource: &str,
     filter: Option<&str>,
 ) -> Result<u64, ResoError> {
@@ -219,6 +636,87 @@ pub async fn count_records(
     client.execute_count(&query).await
 }
 
+/// Fetches records matching a filter along with how many records match in
+/// total, for result pages such as "Showing 10 of 2,431".
+///
+/// The total comes from `$count=true` on the query itself. Servers that
+/// reject that option, or ignore it, are asked with a separate
+/// [`count_records`] request.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression
+/// * `fields` - Fields to select; empty selects all fields
+/// * `top` - Optional maximum number of records to return
+///
+/// # Returns
+///
+/// Returns the records and the number of records available.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, fetch_with_total};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let (records, total) = fetch_with_total(
+///         &client,
+///         "Property",
+///         Some("City eq 'Austin'"),
+///         &["ListingKey", "ListPrice"],
+///         Some(10),
+///     )
+///     .await?;
+///     println!("Showing {} of {} listings", records.len(), total);
+///     Ok(())
+/// }
+/// ```
+pub async fn fetch_with_total(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    fields: &[&str],
+    top: Option<u32>,
+) -> Result<(Vec<JsonValue>, u64), ResoError> {
+    let build = |with_count: bool| {
+        let mut builder = QueryBuilder::new(resource);
+        if let Some(filter_expr) = filter {
+            builder = builder.filter(filter_expr);
+        }
+        if !fields.is_empty() {
+            builder = builder.select(fields);
+        }
+        if let Some(n) = top {
+            builder = builder.top(n);
+        }
+        if with_count {
+            builder = builder.with_count();
+        }
+        builder.build()
+    };
+
+    // Servers without `$count=true` reject the option as a bad request, or
+    // say it is unsupported; other failures would fail again without it
+    let mut response = match client.execute(&build(true)?).await {
+        Ok(response) => response,
+        Err(e) if error::is_rejected_query(&e) => client.execute(&build(false)?).await?,
+        Err(e) => return Err(e),
+    };
+    let records = match response["value"].take() {
+        JsonValue::Array(records) => records,
+        _ => Vec::new(),
+    };
+    let total = match response["@odata.count"].as_u64() {
+        Some(total) => total,
+        None => count_records(client, resource, filter).await?,
+    };
+    Ok((records, total))
+}
+
 /// Loads environment variables from a .env file.
 ///
 /// This should be called at the start of your application before
@@ -401,6 +899,86 @@ pub fn build_query_with_pagination(
     builder.build()
 }
 
+/// Streams every record a query matches, fetching `page_size` records per
+/// request with `$skip`/`$top`.
+///
+/// Paging stops at the first empty page, or at a short page without an
+/// `@odata.nextLink`. Servers that cap pages below `page_size` announce
+/// more records with that link, so paging continues from the number of
+/// records actually received. An error ends the stream after it is yielded.
+///
+/// `$skip` paging can skip or repeat records that change while the stream
+/// is read, and many servers limit how far `$skip` may go; use
+/// replication for full-feed downloads.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression
+/// * `fields` - Field names to select; an empty slice selects all fields
+/// * `page_size` - Records per request
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::TryStreamExt;
+/// use reso_examples::{create_client, paginate_query};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let records = paginate_query(
+///         &client,
+///         "Property",
+///         Some("City eq 'Austin'"),
+///         &["ListingKey", "ListPrice"],
+///         200,
+///     );
+///     futures::pin_mut!(records);
+///     while let Some(record) = records.try_next().await? {
+///         println!("{} {}", record["ListingKey"], record["ListPrice"]);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn paginate_query<'a, C: ResoApi>(
+    client: &'a C,
+    resource: &'a str,
+    filter: Option<&'a str>,
+    fields: &'a [&'a str],
+    page_size: u32,
+) -> impl Stream<Item = Result<JsonValue, ResoError>> + 'a {
+    stream::try_unfold(Some(0u32), move |skip| async move {
+        let Some(skip) = skip else {
+            return Ok(None);
+        };
+        if page_size == 0 {
+            return Err(ResoError::InvalidQuery("page_size must be at least 1".to_string()));
+        }
+
+        let mut builder = QueryBuilder::new(resource).skip(skip).top(page_size);
+        if let Some(filter_expr) = filter {
+            builder = builder.filter(filter_expr);
+        }
+        if !fields.is_empty() {
+            builder = builder.select(fields);
+        }
+        let mut response = client.execute(&builder.build()?).await?;
+
+        let records = match response["value"].take() {
+            JsonValue::Array(records) => records,
+            _ => Vec::new(),
+        };
+        let more = !records.is_empty()
+            && (records.len() >= page_size as usize || response.get("@odata.nextLink").is_some());
+        let next = more.then(|| skip + records.len() as u32);
+        Ok(Some((records, next)))
+    })
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
+
 /// Builds a query with expanded related entities.
 ///
 /// # Arguments
@@ -483,7 +1061,7 @@ pub fn build_replication_query(
 ///
 /// # Arguments
 ///
-/// * `client` - A reference to a configured ResoClient
+/// * `client` - A reference to a configured client, such as a ResoClient
 /// * `query` - A reference to the replication query to execute
 ///
 /// # Returns
@@ -509,7 +1087,7 @@ pub fn build_replication_query(
 /// }
 /// ```
 pub async fn execute_replication_query(
-    client: &ResoClient,
+    client: &impl ResoApi,
     query: &ReplicationQuery,
 ) -> Result<ReplicationResponse, ResoError> {
     client.execute_replication(query).await
@@ -589,4 +1167,223 @@ mod tests {
         let query = build_replication_query("Property", Some("StandardStatus eq 'Active'"));
         assert!(query.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_preflight_skips_searches_over_the_limit() {
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param("$top", "0"))
+            .and(query_param("$count", "true"))
+            .and(query_param("$filter", "City eq 'Austin'"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "@odata.count": 500000,
+                "value": []
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client::HttpClient::builder(reso_client::ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let query = build_query_with_select("Property", Some("City eq 'Austin'"), &["ListingKey"], Some(10)).unwrap();
+        let options = ExecuteOptions {
+            preflight_count: true,
+            max_expected: Some(10_000),
+        };
+
+        let outcome = execute_query_with(&client, &query, &options).await.unwrap();
+        assert_eq!(outcome.expected_total, Some(500_000));
+        assert!(outcome.response.is_none());
+
+        let by_key = build_query_by_key("Property", "12345", None).unwrap();
+        assert!(preflight_count(&client, &by_key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_count_keeps_apply() {
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param("$top", "0"))
+            .and(query_param("$apply", "groupby((City))"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "@odata.count": 12,
+                "value": []
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client::HttpClient::builder(reso_client::ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let query = QueryBuilder::new("Property").apply("groupby((City))").top(5).build().unwrap();
+        assert_eq!(preflight_count(&client, &query).await.unwrap(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_with_total_counts_separately_when_count_is_ignored() {
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param("$count", "true"))
+            .and(query_param("$filter", "City eq 'Austin'"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "@odata.count": 2431,
+                "value": [{"ListingKey": "A1"}, {"ListingKey": "A2"}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // This server ignores $count=true
+        Mock::given(path("/Property"))
+            .and(query_param("$filter", "City eq 'Dallas'"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "value": [{"ListingKey": "D1"}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/$count"))
+            .and(query_param("$filter", "City eq 'Dallas'"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("87"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client::HttpClient::builder(reso_client::ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let (records, total) = fetch_with_total(&client, "Property", Some("City eq 'Austin'"), &["ListingKey"], Some(2))
+            .await
+            .unwrap();
+        assert_eq!((records.len(), total), (2, 2431));
+
+        let (records, total) = fetch_with_total(&client, "Property", Some("City eq 'Dallas'"), &[], Some(2))
+            .await
+            .unwrap();
+        assert_eq!(records[0]["ListingKey"], "D1");
+        assert_eq!(total, 87);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_with_total_retries_without_count_only_when_unsupported() {
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param("$filter", "City eq 'Austin'"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property"))
+            .and(query_param("$count", "true"))
+            .and(query_param("$filter", "City eq 'Dallas'"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("$count is not supported"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property"))
+            .and(query_param("$filter", "City eq 'Dallas'"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": []})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/$count"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("0"))
+            .mount(&server)
+            .await;
+
+        let client = client::HttpClient::builder(reso_client::ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let rejected = fetch_with_total(&client, "Property", Some("City eq 'Austin'"), &[], None).await;
+        assert!(matches!(rejected, Err(ResoError::Unauthorized { .. })));
+        let (records, total) = fetch_with_total(&client, "Property", Some("City eq 'Dallas'"), &[], None)
+            .await
+            .unwrap();
+        assert_eq!((records.len(), total), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_execute_query_with_retry_recovers_from_server_errors() {
+        use std::time::Duration;
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": []})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client::HttpClient::builder(reso_client::ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let query = build_query("Property", None, Some(10)).unwrap();
+        let policy = retry::RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+        };
+
+        let response = execute_query_with_retry(&client, &query, policy).await.unwrap();
+        assert!(response["value"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_paginate_query_follows_short_pages_with_next_link() {
+        use futures::TryStreamExt;
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let page = |keys: &[&str], more: bool| {
+            let mut body = serde_json::json!({
+                "value": keys.iter().map(|k| serde_json::json!({"ListingKey": k})).collect::<Vec<_>>()
+            });
+            if more {
+                body["@odata.nextLink"] = "https://api.example.com/Property?$skip=next".into();
+            }
+            ResponseTemplate::new(200).set_body_json(body)
+        };
+        // Full page, then a page capped by the server, then a short last page
+        for (skip, keys, more) in [("0", &["A", "B"][..], false), ("2", &["C"], true), ("3", &["D"], false)] {
+            Mock::given(path("/Property"))
+                .and(query_param("$skip", skip))
+                .and(query_param("$top", "2"))
+                .and(query_param("$select", "ListingKey"))
+                .respond_with(page(keys, more))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = client::HttpClient::builder(reso_client::ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let records: Vec<JsonValue> = paginate_query(&client, "Property", None, &["ListingKey"], 2)
+            .try_collect()
+            .await
+            .unwrap();
+        let keys: Vec<&str> = records.iter().filter_map(|r| r["ListingKey"].as_str()).collect();
+        assert_eq!(keys, ["A", "B", "C", "D"]);
+    }
 }
@@ -0,0 +1,282 @@
+//! Normalizing vendor listing statuses onto `StandardStatus`.
+//!
+//! `StandardStatus` is the Data Dictionary's fixed list of statuses, but
+//! many feeds leave it empty or fill it loosely and put the MLS's own
+//! vocabulary in `MlsStatus`: "Active Option Contract", "Contingent",
+//! "Sold", "Temp Off Market". A filter on raw statuses written for one feed
+//! silently misses listings in another. A [`StatusMapping`] translates
+//! `MlsStatus` values into [`StandardStatus`] through a table that ships
+//! with common vendor values and can be extended or loaded from a file.
+
+use reso_client::{JsonValue, ResoError};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The Data Dictionary `StandardStatus` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StandardStatus {
+    Active,
+    ActiveUnderContract,
+    Canceled,
+    Closed,
+    ComingSoon,
+    Delete,
+    Expired,
+    Hold,
+    Incomplete,
+    Pending,
+    Withdrawn,
+}
+
+impl StandardStatus {
+    pub const ALL: [StandardStatus; 11] = [
+        StandardStatus::Active,
+        StandardStatus::ActiveUnderContract,
+        StandardStatus::Canceled,
+        StandardStatus::Closed,
+        StandardStatus::ComingSoon,
+        StandardStatus::Delete,
+        StandardStatus::Expired,
+        StandardStatus::Hold,
+        StandardStatus::Incomplete,
+        StandardStatus::Pending,
+        StandardStatus::Withdrawn,
+    ];
+
+    /// The value as written in [`STANDARD_STATUSES`](crate::search::STANDARD_STATUSES),
+    /// e.g. "Active Under Contract".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StandardStatus::Active => "Active",
+            StandardStatus::ActiveUnderContract => "Active Under Contract",
+            StandardStatus::Canceled => "Canceled",
+            StandardStatus::Closed => "Closed",
+            StandardStatus::ComingSoon => "Coming Soon",
+            StandardStatus::Delete => "Delete",
+            StandardStatus::Expired => "Expired",
+            StandardStatus::Hold => "Hold",
+            StandardStatus::Incomplete => "Incomplete",
+            StandardStatus::Pending => "Pending",
+            StandardStatus::Withdrawn => "Withdrawn",
+        }
+    }
+
+    /// Whether the listing can still be bought or leased: active, under
+    /// contract, or coming soon.
+    pub fn is_on_market(&self) -> bool {
+        matches!(
+            self,
+            StandardStatus::Active | StandardStatus::ActiveUnderContract | StandardStatus::ComingSoon
+        )
+    }
+}
+
+/// Parses a value in any spelling the Data Dictionary uses ("Active Under
+/// Contract", "ActiveUnderContract"), ignoring case.
+impl FromStr for StandardStatus {
+    type Err = ResoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = table_key(s);
+        StandardStatus::ALL
+            .into_iter()
+            .find(|status| table_key(status.as_str()) == wanted)
+            .ok_or_else(|| ResoError::InvalidQuery(format!("Unknown StandardStatus '{}'", s)))
+    }
+}
+
+impl fmt::Display for StandardStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `MlsStatus` values common across vendors, beyond the standard values themselves.
+const DEFAULT_TABLE: &[(&str, StandardStatus)] = &[
+    ("A", StandardStatus::Active),
+    ("Act", StandardStatus::Active),
+    ("New", StandardStatus::Active),
+    ("Back On Market", StandardStatus::Active),
+    ("Price Change", StandardStatus::Active),
+    ("Active Option Contract", StandardStatus::ActiveUnderContract),
+    ("Active Contingent", StandardStatus::ActiveUnderContract),
+    ("Active Kick Out", StandardStatus::ActiveUnderContract),
+    ("Backup Offer", StandardStatus::ActiveUnderContract),
+    ("Contingent", StandardStatus::ActiveUnderContract),
+    ("Under Contract", StandardStatus::ActiveUnderContract),
+    ("P", StandardStatus::Pending),
+    ("Pending Sale", StandardStatus::Pending),
+    ("Sale Pending", StandardStatus::Pending),
+    ("Under Agreement", StandardStatus::Pending),
+    ("S", StandardStatus::Closed),
+    ("Sold", StandardStatus::Closed),
+    ("Leased", StandardStatus::Closed),
+    ("Rented", StandardStatus::Closed),
+    ("Cancelled", StandardStatus::Canceled),
+    ("X", StandardStatus::Expired),
+    ("Temp Off Market", StandardStatus::Hold),
+    ("Temporarily Off Market", StandardStatus::Hold),
+    ("Off Market", StandardStatus::Withdrawn),
+    ("Coming Soon No Show", StandardStatus::ComingSoon),
+];
+
+/// A table from `MlsStatus` values to [`StandardStatus`].
+///
+/// Lookups ignore case, spaces, and punctuation, so "Active-Option
+/// Contract" matches an entry for "Active Option Contract".
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::listing_status::{StandardStatus, StatusMapping};
+/// use serde_json::json;
+///
+/// let mapping = StatusMapping::default().map("Active Kick Out", StandardStatus::Active);
+///
+/// let mut record = json!({"ListingKey": "A", "MlsStatus": "Sold", "StandardStatus": null});
+/// mapping.apply(&mut record);
+/// assert_eq!(record["StandardStatus"], "Closed");
+///
+/// let record = json!({"MlsStatus": "ACTIVE-KICK OUT"});
+/// assert_eq!(mapping.normalize(&record), Some(StandardStatus::Active));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatusMapping {
+    table: HashMap<String, (String, StandardStatus)>,
+}
+
+impl Default for StatusMapping {
+    /// The built-in table of common vendor values.
+    fn default() -> Self {
+        DEFAULT_TABLE
+            .iter()
+            .fold(Self::new(), |mapping, (mls_status, standard)| mapping.map(mls_status, *standard))
+    }
+}
+
+impl StatusMapping {
+    /// An empty table; records are normalized only from values that are
+    /// already standard.
+    pub fn new() -> Self {
+        Self { table: HashMap::new() }
+    }
+
+    /// Maps `mls_status` onto `standard`, replacing any earlier entry.
+    pub fn map(mut self, mls_status: &str, standard: StandardStatus) -> Self {
+        self.table
+            .insert(table_key(mls_status), (mls_status.to_string(), standard));
+        self
+    }
+
+    /// Adds entries from a JSON object of `MlsStatus` to `StandardStatus`
+    /// values, e.g. `{"Active Option Contract": "Active Under Contract"}`.
+    pub fn extend_from_json(mut self, json: &str) -> Result<Self, ResoError> {
+        let entries: HashMap<String, String> = serde_json::from_str(json)
+            .map_err(|e| ResoError::Parse(format!("Invalid status mapping: {}", e)))?;
+        for (mls_status, standard) in entries {
+            self = self.map(&mls_status, standard.parse()?);
+        }
+        Ok(self)
+    }
+
+    /// The built-in table extended with the JSON object in `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            ResoError::Config(format!("Cannot read status mapping {}: {}", path.display(), e))
+        })?;
+        Self::default().extend_from_json(&json)
+    }
+
+    /// The `StandardStatus` for a Property record.
+    ///
+    /// A table entry for the record's `MlsStatus` wins; otherwise a valid
+    /// `StandardStatus` on the record is kept, and failing that an
+    /// `MlsStatus` that is itself a standard value is used.
+    pub fn normalize(&self, record: &JsonValue) -> Option<StandardStatus> {
+        let mls_status = record["MlsStatus"].as_str();
+        mls_status
+            .and_then(|status| self.table.get(&table_key(status)))
+            .map(|(_, standard)| *standard)
+            .or_else(|| record["StandardStatus"].as_str()?.parse().ok())
+            .or_else(|| mls_status?.parse().ok())
+    }
+
+    /// Sets `StandardStatus` on a record to its normalized value, leaving
+    /// records that cannot be normalized unchanged.
+    pub fn apply(&self, record: &mut JsonValue) {
+        if let Some(standard) = self.normalize(record) {
+            if let Some(fields) = record.as_object_mut() {
+                fields.insert("StandardStatus".to_string(), standard.as_str().into());
+            }
+        }
+    }
+
+    /// The `MlsStatus` values in the table that map onto `standard`, sorted,
+    /// for filtering feeds that only carry `MlsStatus`, e.g. with
+    /// [`FilterBuilder::in_list`](crate::filter::FilterBuilder::in_list).
+    pub fn mls_statuses_for(&self, standard: StandardStatus) -> Vec<&str> {
+        let mut statuses: Vec<&str> = self
+            .table
+            .values()
+            .filter(|(_, mapped)| *mapped == standard)
+            .map(|(mls_status, _)| mls_status.as_str())
+            .collect();
+        statuses.sort_unstable();
+        statuses
+    }
+}
+
+/// Lowercase letters and digits only: "Active-Option Contract" becomes
+/// "activeoptioncontract".
+pub(crate) fn table_key(status: &str) -> String {
+    status
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_precedence_and_json_table() {
+        let mapping = StatusMapping::default()
+            .extend_from_json(r#"{"Contingent": "Pending", "Option Pending": "ActiveUnderContract"}"#)
+            .unwrap();
+
+        // The table overrides both the vendor's StandardStatus and the default entry
+        let record = json!({"MlsStatus": "contingent", "StandardStatus": "Active"});
+        assert_eq!(mapping.normalize(&record), Some(StandardStatus::Pending));
+        // Unmapped MlsStatus: keep a valid StandardStatus, else parse MlsStatus itself
+        let record = json!({"MlsStatus": "Broker Special", "StandardStatus": "ComingSoon"});
+        assert_eq!(mapping.normalize(&record), Some(StandardStatus::ComingSoon));
+        let record = json!({"MlsStatus": "active under contract"});
+        assert_eq!(mapping.normalize(&record), Some(StandardStatus::ActiveUnderContract));
+        assert_eq!(mapping.normalize(&json!({"MlsStatus": "Broker Special"})), None);
+
+        let mut record = json!({"MlsStatus": "Broker Special", "StandardStatus": "Active"});
+        StatusMapping::new().apply(&mut record);
+        assert_eq!(record["StandardStatus"], "Active");
+
+        assert_eq!(
+            mapping.mls_statuses_for(StandardStatus::ActiveUnderContract),
+            [
+                "Active Contingent",
+                "Active Kick Out",
+                "Active Option Contract",
+                "Backup Offer",
+                "Option Pending",
+                "Under Contract"
+            ]
+        );
+        assert!(StatusMapping::new()
+            .extend_from_json(r#"{"Sold": "Sold"}"#)
+            .is_err());
+    }
+}
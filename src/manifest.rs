@@ -0,0 +1,294 @@
+//! Manifests describing exported files.
+//!
+//! Downstream loaders pick up exports on their own schedule, and need to
+//! know which files are complete, whether they arrived intact, and which
+//! they have already loaded. [`ManifestSink`] writes a JSON manifest next
+//! to a file export when a run finishes:
+//!
+//! ```json
+//! {
+//!   "resource": "Property",
+//!   "filter": "City eq 'Austin'",
+//!   "query_hash": "9f1c2a7e5d3b4c60",
+//!   "generated_at": "2025-01-02T03:04:05Z",
+//!   "records_written": 1000,
+//!   "files": [
+//!     {"path": "part-00000.parquet", "records": 1000, "bytes": 48213, "sha256": "5e88…"}
+//!   ]
+//! }
+//! ```
+//!
+//! Paths are relative to the manifest's directory. A loader that keeps the
+//! checksums it has processed can skip files whose checksum it has seen, and
+//! [`ExportManifest::verify`] finds files that are missing or have changed.
+
+use crate::export::{OutputFile, RecordSink, StoredCounts};
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use crate::provenance::query_hash;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reso_client::{JsonValue, ResoError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A manifest of the files an export run wrote.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub resource: String,
+    pub filter: Option<String>,
+    /// Hash of the resource and filter, as in [`provenance`](crate::provenance)
+    pub query_hash: String,
+    pub generated_at: DateTime<Utc>,
+    /// Records written by the run; a file appended to across runs holds more
+    pub records_written: u64,
+    pub files: Vec<ManifestFile>,
+}
+
+/// One exported file in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path relative to the manifest's directory
+    pub path: String,
+    /// Records in the file
+    pub records: u64,
+    pub bytes: u64,
+    /// SHA-256 of the file's contents, as lowercase hex
+    pub sha256: String,
+}
+
+impl ManifestFile {
+    /// Describes the file at `path`, listed as `name`, reading it to compute
+    /// its checksum. `records` of `None` counts the file's lines.
+    pub fn describe(path: &Path, name: &str, records: Option<u64>) -> Result<Self, ResoError> {
+        let (bytes, lines, sha256) = hash_file(path)?;
+        Ok(Self {
+            path: name.to_string(),
+            records: records.unwrap_or(lines),
+            bytes,
+            sha256,
+        })
+    }
+}
+
+impl ExportManifest {
+    /// Builds a manifest for `files`, with paths relative to `dir`.
+    pub fn build(
+        resource: &str,
+        filter: Option<&str>,
+        records_written: u64,
+        files: &[OutputFile],
+        dir: &Path,
+    ) -> Result<Self, ResoError> {
+        let files = files
+            .iter()
+            .map(|file| {
+                let name = file.path.strip_prefix(dir).unwrap_or(&file.path);
+                ManifestFile::describe(&file.path, &name.to_string_lossy(), file.records)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            resource: resource.to_string(),
+            filter: filter.map(str::to_string),
+            query_hash: query_hash(resource, filter),
+            generated_at: Utc::now(),
+            records_written,
+            files,
+        })
+    }
+
+    /// Reads a manifest written by [`ExportManifest::write`].
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ResoError::Config(format!("Cannot read manifest {}: {}", path.display(), e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| ResoError::Parse(format!("Invalid manifest {}: {}", path.display(), e)))
+    }
+
+    /// Writes the manifest, replacing the file atomically so a loader never
+    /// reads a partial one.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), ResoError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).map_err(|e| ResoError::Parse(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| ResoError::Config(format!("Cannot write manifest {}: {}", path.display(), e)))
+    }
+
+    /// The files, relative to `dir`, that are missing or whose size or
+    /// checksum differs from the manifest's. An empty list means the export
+    /// is intact.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use reso_examples::manifest::ExportManifest;
+    /// use std::path::Path;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let manifest = ExportManifest::read("property/manifest.json")?;
+    /// let damaged = manifest.verify(Path::new("property"))?;
+    /// if !damaged.is_empty() {
+    ///     eprintln!("Not loading; changed or missing: {}", damaged.join(", "));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify(&self, dir: &Path) -> Result<Vec<String>, ResoError> {
+        let mut damaged = Vec::new();
+        for file in &self.files {
+            let path = dir.join(&file.path);
+            let intact = match std::fs::metadata(&path) {
+                Ok(metadata) if metadata.len() == file.bytes => hash_file(&path)?.2 == file.sha256,
+                _ => false,
+            };
+            if !intact {
+                damaged.push(file.path.clone());
+            }
+        }
+        Ok(damaged)
+    }
+}
+
+/// Reads a file once, returning its size, line count, and SHA-256.
+fn hash_file(path: &Path) -> Result<(u64, u64, String), ResoError> {
+    let failed = |e: std::io::Error| ResoError::Config(format!("Cannot read {}: {}", path.display(), e));
+    let mut file = std::fs::File::open(path).map_err(failed)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let (mut bytes, mut lines) = (0u64, 0u64);
+    loop {
+        let read = file.read(&mut buffer).map_err(failed)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes += read as u64;
+        lines += buffer[..read].iter().filter(|&&b| b == b'\n').count() as u64;
+    }
+    let sha256 = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+    Ok((bytes, lines, sha256))
+}
+
+/// Wraps a file sink, writing an [`ExportManifest`] of its files when the
+/// run is finalized.
+///
+/// Runs that fail before finishing leave the previous manifest in place,
+/// so a loader only ever sees complete exports.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::manifest::ManifestSink;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let sink = JsonlSink::append("property.jsonl")?;
+/// let sink = ManifestSink::new(sink, "property.jsonl.manifest.json", "Property", None);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ManifestSink<S> {
+    inner: S,
+    path: PathBuf,
+    resource: String,
+    filter: Option<String>,
+    records: u64,
+}
+
+impl<S: RecordSink> ManifestSink<S> {
+    /// # Arguments
+    ///
+    /// * `inner` - The sink writing the files
+    /// * `path` - Where to write the manifest; file paths are listed relative to its directory
+    /// * `resource` - The resource being exported
+    /// * `filter` - The filter the export was replicated with
+    pub fn new(inner: S, path: impl AsRef<Path>, resource: &str, filter: Option<&str>) -> Self {
+        Self {
+            inner,
+            path: path.as_ref().to_path_buf(),
+            resource: resource.to_string(),
+            filter: filter.map(str::to_string),
+            records: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink> RecordSink for ManifestSink<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        self.inner.write_batch(records).await?;
+        self.records += records.len() as u64;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.inner.flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.inner.finalize().await?;
+        let dir = self.path.parent().unwrap_or(Path::new(""));
+        let manifest = ExportManifest::build(
+            &self.resource,
+            self.filter.as_deref(),
+            self.records,
+            &self.inner.output_files(),
+            dir,
+        )?;
+        manifest.write(&self.path)
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        self.inner.evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        self.inner.count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.inner.output_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{Partition, PartitionedSink};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_manifest_lists_partition_files_and_detects_changes() {
+        let root = std::env::temp_dir().join(format!("reso-manifest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let partitioned = PartitionedSink::jsonl(&root, vec![Partition::Field("City".into())]);
+        let manifest_path = root.join("manifest.json");
+        let mut sink = ManifestSink::new(partitioned, &manifest_path, "Property", Some("ListPrice gt 0"));
+
+        sink.write_batch(&[
+            json!({"ListingKey": "A", "City": "Austin"}),
+            json!({"ListingKey": "B", "City": "Austin"}),
+            json!({"ListingKey": "C", "City": "Dallas"}),
+        ])
+        .await
+        .unwrap();
+        sink.finalize().await.unwrap();
+
+        let manifest = ExportManifest::read(&manifest_path).unwrap();
+        assert_eq!(manifest.records_written, 3);
+        assert_eq!(manifest.query_hash, query_hash("Property", Some("ListPrice gt 0")));
+        let files: Vec<(&str, u64)> = manifest.files.iter().map(|f| (f.path.as_str(), f.records)).collect();
+        assert_eq!(files, [("City=Austin/part.jsonl", 2), ("City=Dallas/part.jsonl", 1)]);
+        assert_eq!(manifest.files[1].sha256.len(), 64);
+        assert!(manifest.verify(&root).unwrap().is_empty());
+
+        std::fs::write(root.join("City=Dallas/part.jsonl"), "{\"ListingKey\":\"X\"}\n").unwrap();
+        assert_eq!(manifest.verify(&root).unwrap(), ["City=Dallas/part.jsonl"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,427 @@
+//! Listing photos with their captions, in display order.
+//!
+//! Media records carry more than a URL: `ShortDescription` and
+//! `LongDescription` hold the agent's captions, `ImageOf` labels what a
+//! photo shows ("Kitchen", "Front of Structure"), and `Order` gives the
+//! sequence the listing agent chose. [`ListingMedia`] gathers these so a
+//! website can render an accessible gallery, with alt text for every image.
+//!
+//! [`fetch_media_for_listing`] queries the Media resource for one listing,
+//! [`fetch_first_photos`] finds thumbnails for a page of listings in one
+//! request, and [`download_media`] saves its files through a
+//! [`Downloader`], which bounds concurrency and bandwidth.
+
+use crate::client::ResoApi;
+use crate::download::Downloader;
+use crate::filter::FilterBuilder;
+use futures::future::try_join_all;
+use reso_client::{JsonValue, Query, QueryBuilder, ResoError};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Media records requested per listing; more than any listing carries.
+const MEDIA_PER_LISTING: u32 = 200;
+
+/// Media records requested per listing by [`first_photos_query`], so a
+/// document or tour ordered before the first photo does not hide it.
+const FIRST_PHOTO_CANDIDATES: u32 = 4;
+
+/// File name used by [`download_media`] when no template is given.
+pub const DEFAULT_FILE_NAME: &str = "{listing_key}/{index}.{ext}";
+
+/// A photo or other media file of a listing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListingMedia {
+    pub media_key: Option<String>,
+    pub url: String,
+    /// Position chosen by the listing agent, lowest first
+    pub order: Option<i64>,
+    /// `MediaCategory`, e.g. "Photo", "Document", "Video"
+    pub category: Option<String>,
+    /// Short caption, `ShortDescription`
+    pub caption: Option<String>,
+    /// Longer description, `LongDescription`
+    pub description: Option<String>,
+    /// What the photo shows, `ImageOf`, e.g. "Kitchen"
+    pub label: Option<String>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    /// The listing's primary photo, `PreferredPhotoYN`
+    pub preferred: bool,
+}
+
+impl ListingMedia {
+    /// Reads a Media record, or `None` if it has no `MediaURL`.
+    pub fn from_record(record: &JsonValue) -> Option<Self> {
+        let text = |field: &str| {
+            record[field]
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Some(Self {
+            media_key: text("MediaKey"),
+            url: text("MediaURL")?,
+            order: record["Order"].as_i64(),
+            category: text("MediaCategory"),
+            caption: text("ShortDescription"),
+            description: text("LongDescription"),
+            label: text("ImageOf"),
+            width: record["ImageWidth"].as_u64(),
+            height: record["ImageHeight"].as_u64(),
+            preferred: record["PreferredPhotoYN"].as_bool().unwrap_or(false),
+        })
+    }
+
+    /// Whether this is a photo rather than a document, video, or tour link.
+    /// Media without a category are assumed to be photos.
+    pub fn is_photo(&self) -> bool {
+        self.category
+            .as_deref()
+            .is_none_or(|category| category.eq_ignore_ascii_case("photo"))
+    }
+
+    /// Text for the image's `alt` attribute: the caption, else the
+    /// description, else the label, else `fallback` (e.g. "Photo 3 of 24").
+    pub fn alt_text(&self, fallback: &str) -> String {
+        self.caption
+            .as_deref()
+            .or(self.description.as_deref())
+            .or(self.label.as_deref())
+            .unwrap_or(fallback)
+            .to_string()
+    }
+}
+
+/// Reads Media records into display order.
+///
+/// Media are sorted by `Order`, with unordered items last in their original
+/// sequence. Records without a `MediaURL` are skipped.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::media::listing_media;
+/// use serde_json::json;
+///
+/// let records = [
+///     json!({"MediaURL": "https://cdn.example.com/2.jpg", "Order": 2, "ImageOf": "Kitchen"}),
+///     json!({"MediaURL": "https://cdn.example.com/1.jpg", "Order": 1, "ShortDescription": "Front yard"}),
+/// ];
+/// let media = listing_media(&records);
+///
+/// assert_eq!(media[0].alt_text("Photo 1"), "Front yard");
+/// assert_eq!(media[1].alt_text("Photo 2"), "Kitchen");
+/// ```
+pub fn listing_media(records: &[JsonValue]) -> Vec<ListingMedia> {
+    let mut media: Vec<ListingMedia> = records.iter().filter_map(ListingMedia::from_record).collect();
+    media.sort_by_key(|m| (m.order.is_none(), m.order));
+    media
+}
+
+/// Reads the Media expanded into a Property record (`$expand=Media`), in
+/// display order.
+pub fn expanded_media(listing: &JsonValue) -> Vec<ListingMedia> {
+    listing["Media"]
+        .as_array()
+        .map(|records| listing_media(records))
+        .unwrap_or_default()
+}
+
+/// Fetches the media of one listing from the Media resource, in display
+/// order.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::media::fetch_media_for_listing;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     for (i, photo) in fetch_media_for_listing(&client, "3yd-ACTRIS-5419987").await?.iter().enumerate() {
+///         println!("{} {}", photo.url, photo.alt_text(&format!("Photo {}", i + 1)));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn fetch_media_for_listing(client: &impl ResoApi, listing_key: &str) -> Result<Vec<ListingMedia>, ResoError> {
+    let filter = FilterBuilder::eq("ResourceRecordKey", listing_key).build()?;
+    let query = QueryBuilder::new("Media")
+        .filter(filter)
+        .order_by("Order", "asc")
+        .top(MEDIA_PER_LISTING)
+        .build()?;
+    let response = client.execute(&query).await?;
+    let records = response["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+    Ok(listing_media(records))
+}
+
+/// The query for the leading media of several listings, such as a page of
+/// search results.
+///
+/// Media are sorted by `Order` across all the listings, so every listing's
+/// first items come before anyone's later ones and a small `$top` covers
+/// them all. Media without an `Order` are left out, since they sort first
+/// and would use up the `$top`. Returns `ResoError::InvalidQuery` when
+/// `listing_keys` is empty.
+pub fn first_photos_query(listing_keys: &[&str]) -> Result<Query, ResoError> {
+    let filter = FilterBuilder::eq("ResourceName", "Property")
+        .and(FilterBuilder::in_list("ResourceRecordKey", listing_keys))
+        .build()?;
+    QueryBuilder::new("Media")
+        .filter(format!("{} and Order ne null", filter))
+        .order_by("Order", "asc")
+        .top(listing_keys.len() as u32 * FIRST_PHOTO_CANDIDATES)
+        .build()
+}
+
+/// The first photo of each listing among `records`, the response to a
+/// [`first_photos_query`], keyed by `ResourceRecordKey`.
+pub fn first_photos(records: &[JsonValue]) -> HashMap<String, ListingMedia> {
+    let mut by_listing: HashMap<&str, Vec<JsonValue>> = HashMap::new();
+    for record in records {
+        if let Some(key) = record["ResourceRecordKey"].as_str() {
+            by_listing.entry(key).or_default().push(record.clone());
+        }
+    }
+    by_listing
+        .into_iter()
+        .filter_map(|(key, records)| {
+            let photo = listing_media(&records).into_iter().find(ListingMedia::is_photo)?;
+            Some((key.to_string(), photo))
+        })
+        .collect()
+}
+
+/// Fetches the first photo of each listing in one Media request, for
+/// thumbnails. Listings without photos are missing from the map.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::media::fetch_first_photos;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let photos = fetch_first_photos(&client, &["3yd-ACTRIS-5419987", "3yd-ACTRIS-5419988"]).await?;
+///     for (key, photo) in &photos {
+///         println!("{}: {}", key, photo.url);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn fetch_first_photos(
+    client: &impl ResoApi,
+    listing_keys: &[&str],
+) -> Result<HashMap<String, ListingMedia>, ResoError> {
+    if listing_keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let response = client.execute(&first_photos_query(listing_keys)?).await?;
+    let records = response["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+    Ok(first_photos(records))
+}
+
+/// Downloads a listing's media into `dest_dir`, returning each file's path
+/// in the order of `media`.
+///
+/// Files are named by `template` (see [`media_file_name`]), e.g.
+/// [`DEFAULT_FILE_NAME`]; missing directories are created. Downloads run
+/// concurrently within the `downloader`'s limits, and the first failure is
+/// returned.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::download::{DownloadLimits, Downloader};
+/// use reso_examples::media::{download_media, fetch_media_for_listing, DEFAULT_FILE_NAME};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let downloader = Downloader::new(DownloadLimits::default());
+///
+///     let key = "3yd-ACTRIS-5419987";
+///     let photos: Vec<_> = fetch_media_for_listing(&client, key).await?
+///         .into_iter()
+///         .filter(|m| m.is_photo())
+///         .collect();
+///     let paths = download_media(&downloader, key, &photos, "photos", DEFAULT_FILE_NAME).await?;
+///     println!("Saved {} photos", paths.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn download_media(
+    downloader: &Downloader,
+    listing_key: &str,
+    media: &[ListingMedia],
+    dest_dir: impl AsRef<Path>,
+    template: &str,
+) -> Result<Vec<PathBuf>, ResoError> {
+    let dest_dir = dest_dir.as_ref();
+    try_join_all(media.iter().enumerate().map(|(i, item)| async move {
+        let dest = dest_dir.join(media_file_name(template, listing_key, i + 1, item));
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| ResoError::Config(format!("Cannot create {}: {}", parent.display(), e)))?;
+        }
+        downloader.download(&item.url, &dest).await?;
+        Ok::<_, ResoError>(dest)
+    }))
+    .await
+}
+
+/// Fills in a file name template for one media item.
+///
+/// Placeholders are `{listing_key}`, `{media_key}` (the index if missing),
+/// `{index}` (position in the list, from 1, two digits), `{order}` (the
+/// `Order` field, or the index), `{url_hash}` (the first 16 hex digits of
+/// the URL's SHA-256), and `{ext}` (from the URL, `jpg` if it has none).
+/// Names built from `{media_key}` or `{url_hash}` stay with their file
+/// when the listing's photos are reordered. Substituted values are reduced to letters, digits, `-`, and `_`,
+/// so a key cannot reach outside the destination directory.
+///
+/// ```
+/// use reso_examples::media::{media_file_name, ListingMedia};
+/// use serde_json::json;
+///
+/// let photo = ListingMedia::from_record(&json!({"MediaURL": "https://cdn.example.com/a/b.JPEG?w=800", "Order": 3})).unwrap();
+/// assert_eq!(media_file_name("{listing_key}/{index}.{ext}", "A1", 2, &photo), "A1/02.jpeg");
+/// assert_eq!(media_file_name("{listing_key}-{order}.{ext}", "../x", 2, &photo), "___x-3.jpeg");
+/// assert_eq!(media_file_name("{url_hash}", "A1", 2, &photo).len(), 16);
+/// ```
+pub fn media_file_name(template: &str, listing_key: &str, index: usize, media: &ListingMedia) -> String {
+    let index_text = format!("{:02}", index);
+    let order = media.order.map_or_else(|| index_text.clone(), |order| order.to_string());
+    template
+        .replace("{listing_key}", &file_safe(listing_key))
+        .replace("{media_key}", &file_safe(media.media_key.as_deref().unwrap_or(&index_text)))
+        .replace("{index}", &index_text)
+        .replace("{order}", &file_safe(&order))
+        .replace("{url_hash}", &url_hash(&media.url))
+        .replace("{ext}", &extension(&media.url))
+}
+
+fn file_safe(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn url_hash(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// The extension of the URL's last path segment, lower-cased.
+fn extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| (1..=5).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_else(|| "jpg".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_expanded_media_order_and_alt_text() {
+        let listing = json!({
+            "ListingKey": "A",
+            "Media": [
+                {"MediaKey": "m3", "MediaURL": "https://cdn.example.com/3.jpg"},
+                {"MediaKey": "m2", "MediaURL": "https://cdn.example.com/2.jpg", "Order": 2,
+                 "LongDescription": "Updated kitchen with quartz counters", "ImageOf": "Kitchen"},
+                {"MediaKey": "doc", "MediaURL": "https://cdn.example.com/plat.pdf", "Order": 0,
+                 "MediaCategory": "Document", "ShortDescription": "Plat"},
+                {"MediaKey": "m1", "MediaURL": " ", "Order": 1},
+            ]
+        });
+
+        let media = expanded_media(&listing);
+        let keys: Vec<&str> = media.iter().filter_map(|m| m.media_key.as_deref()).collect();
+        assert_eq!(keys, ["doc", "m2", "m3"]);
+        assert!(!media[0].is_photo() && media[1].is_photo() && media[2].is_photo());
+        assert_eq!(media[1].alt_text("Photo 2"), "Updated kitchen with quartz counters");
+        assert_eq!(media[2].alt_text("Photo 3"), "Photo 3");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_first_photos_skips_other_media() {
+        let client = crate::demo::DemoClient::new();
+        let photos = fetch_first_photos(&client, &["DEMO1001", "DEMO1002", "NOPE"]).await.unwrap();
+        assert_eq!(photos.len(), 2);
+        assert!(photos.values().all(|photo| photo.order == Some(1)));
+        assert_eq!(photos["DEMO1001"].label.as_deref(), Some("Front of Structure"));
+        assert!(fetch_first_photos(&client, &[]).await.unwrap().is_empty());
+
+        let records = [
+            json!({"ResourceRecordKey": "A", "MediaURL": "https://cdn.example.com/tour", "Order": 0, "MediaCategory": "Virtual Tour"}),
+            json!({"ResourceRecordKey": "A", "MediaURL": "https://cdn.example.com/a1.jpg", "Order": 1}),
+        ];
+        assert_eq!(first_photos(&records)["A"].url, "https://cdn.example.com/a1.jpg");
+
+        let request = crate::query_string::ODataRequest::from_query(&first_photos_query(&["A", "B"]).unwrap()).unwrap();
+        let filter = request.option("$filter").unwrap();
+        assert!(filter.contains("ResourceRecordKey in ('A','B') and Order ne null"), "{}", filter);
+        assert!(matches!(first_photos_query(&[]), Err(ResoError::InvalidQuery(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_download_listing_media() {
+        use crate::download::DownloadLimits;
+        use reso_client::ClientConfig;
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let records = json!({"value": [
+            {"MediaKey": "m2", "MediaURL": format!("{}/cdn/2.png", server.uri()), "Order": 2},
+            {"MediaKey": "m1", "MediaURL": format!("{}/cdn/1.jpg", server.uri()), "Order": 1},
+        ]});
+        Mock::given(path("/Media"))
+            .and(query_param("$filter", "ResourceRecordKey eq 'A1'"))
+            .and(query_param("$orderby", "Order asc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(records))
+            .mount(&server)
+            .await;
+        for (file, size) in [("1.jpg", 10), ("2.png", 20)] {
+            Mock::given(path(format!("/cdn/{}", file)))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; size]))
+                .mount(&server)
+                .await;
+        }
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let media = fetch_media_for_listing(&client, "A1").await.unwrap();
+        assert_eq!(media[0].media_key.as_deref(), Some("m1"));
+
+        let dir = std::env::temp_dir().join(format!("reso-media-{}", std::process::id()));
+        let downloader = Downloader::new(DownloadLimits::default());
+        let paths = download_media(&downloader, "A1", &media, &dir, "{listing_key}/{index}-{media_key}.{ext}")
+            .await
+            .unwrap();
+        assert_eq!(paths, [dir.join("A1/01-m1.jpg"), dir.join("A1/02-m2.png")]);
+        assert_eq!(std::fs::read(&paths[1]).unwrap().len(), 20);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
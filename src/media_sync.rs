@@ -0,0 +1,441 @@
+//! Keeping listing photos in step with replicated Property records.
+//!
+//! A [`MediaSyncSink`] wraps the sink a Property replication writes to. For
+//! every batch it fetches the Media of the batch's listings
+//! (`ResourceName eq 'Property' and ResourceRecordKey in (...)`), optionally
+//! downloads the files, and records them in a [`MediaManifest`] mapping each
+//! `ListingKey` to its media and their local paths. A listing's entry is
+//! replaced whenever the listing is replicated again, so photos follow the
+//! listing's changes, and downloaded files whose media are gone are deleted.
+//!
+//! The manifest is saved whenever the sink is flushed, which replication
+//! does before advancing its checkpoint.
+
+use crate::client::ResoApi;
+use crate::download::Downloader;
+use crate::export::{OutputFile, RecordSink, StoredCounts};
+use crate::filter::FilterBuilder;
+use crate::media::{listing_media, media_file_name, ListingMedia};
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use crate::paginate_query;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::TryStreamExt;
+use reso_client::{JsonValue, ResoError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Listings whose media are requested together.
+const KEYS_PER_REQUEST: usize = 50;
+
+/// Media records per request.
+const MEDIA_PAGE_SIZE: u32 = 200;
+
+/// File name template for [`MediaSyncSink::download_to`], naming each file
+/// by a hash of its URL so a reordered photo keeps its file.
+pub const SYNC_FILE_NAME: &str = "{listing_key}/{url_hash}.{ext}";
+
+/// Media of each replicated listing, keyed by `ListingKey`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaManifest {
+    pub listings: BTreeMap<String, Vec<MediaFile>>,
+}
+
+/// One media item of a listing in a [`MediaManifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaFile {
+    pub media_key: Option<String>,
+    pub url: String,
+    pub order: Option<i64>,
+    /// Where the file was downloaded, if it was
+    pub path: Option<PathBuf>,
+}
+
+impl MediaManifest {
+    /// Reads a manifest, or starts an empty one if `path` does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| ResoError::Parse(format!("Invalid media manifest {}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(ResoError::Config(format!("Cannot read {}: {}", path.display(), e))),
+        }
+    }
+
+    /// Writes the manifest, replacing `path` only once it is complete.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ResoError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).map_err(|e| ResoError::Parse(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| ResoError::Config(format!("Cannot write media manifest {}: {}", path.display(), e)))
+    }
+
+    /// Local paths of a listing's downloaded media, in display order.
+    pub fn paths(&self, listing_key: &str) -> Vec<&Path> {
+        self.listings
+            .get(listing_key)
+            .map(|files| files.iter().filter_map(|file| file.path.as_deref()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Where and how a [`MediaSyncSink`] saves media files.
+struct MediaDownloads {
+    downloader: Downloader,
+    dest_dir: PathBuf,
+    template: String,
+}
+
+impl MediaDownloads {
+    /// Downloads the media of one listing, reusing files already fetched
+    /// from the same URL and deleting the listing's other files.
+    /// `previous` lists the files the manifest had for the listing.
+    async fn download(
+        &self,
+        listing_key: &str,
+        media: &[ListingMedia],
+        previous: &[MediaFile],
+    ) -> Result<Vec<Option<PathBuf>>, ResoError> {
+        // Decide every file's path before downloading, so no download can
+        // overwrite a kept file or another download
+        let kept: Vec<Option<PathBuf>> = media
+            .iter()
+            .map(|item| {
+                previous
+                    .iter()
+                    .find(|file| file.url == item.url)
+                    .and_then(|file| file.path.clone())
+                    .filter(|path| path.exists())
+            })
+            .collect();
+        let mut taken: HashSet<PathBuf> = kept.iter().flatten().cloned().collect();
+        let mut planned = Vec::with_capacity(media.len());
+        for (i, (item, kept)) in media.iter().zip(kept).enumerate() {
+            if let Some(path) = kept {
+                planned.push((path, false));
+                continue;
+            }
+            let dest = self.dest_dir.join(media_file_name(&self.template, listing_key, i + 1, item));
+            if !taken.insert(dest.clone()) {
+                return Err(ResoError::Config(format!(
+                    "File name template '{}' gives {} to two media of listing {}; include {{media_key}} or {{url_hash}}",
+                    self.template,
+                    dest.display(),
+                    listing_key
+                )));
+            }
+            planned.push((dest, true));
+        }
+
+        let paths = try_join_all(media.iter().zip(&planned).map(|(item, (dest, fetch))| async move {
+            if *fetch {
+                if let Some(parent) = dest.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| ResoError::Config(format!("Cannot create {}: {}", parent.display(), e)))?;
+                }
+                self.downloader.download(&item.url, dest).await?;
+            }
+            Ok::<_, ResoError>(Some(dest.clone()))
+        }))
+        .await?;
+
+        for path in previous.iter().filter_map(|file| file.path.as_ref()) {
+            if taken.contains(path) {
+                continue;
+            }
+            match tokio::fs::remove_file(path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(ResoError::Config(format!("Cannot delete {}: {}", path.display(), e))),
+            }
+        }
+        Ok(paths)
+    }
+}
+
+/// Media of the listings, keyed by `ListingKey`.
+async fn fetch_media(client: &impl ResoApi, keys: &[&str]) -> Result<HashMap<String, Vec<JsonValue>>, ResoError> {
+    let mut by_listing: HashMap<String, Vec<JsonValue>> = HashMap::new();
+    for chunk in keys.chunks(KEYS_PER_REQUEST) {
+        let filter = FilterBuilder::eq("ResourceName", "Property")
+            .and(FilterBuilder::in_list("ResourceRecordKey", chunk))
+            .build()?;
+        let records: Vec<JsonValue> = paginate_query(client, "Media", Some(filter.as_str()), &[], MEDIA_PAGE_SIZE)
+            .try_collect()
+            .await?;
+        for record in records {
+            if let Some(key) = record["ResourceRecordKey"].as_str() {
+                by_listing.entry(key.to_string()).or_default().push(record);
+            }
+        }
+    }
+    Ok(by_listing)
+}
+
+/// Fetches, and optionally downloads, the media of each batch of listings
+/// before passing the batch on to another sink.
+///
+/// Files already downloaded from the same URL are kept rather than fetched
+/// again, and files of media the listing no longer has are deleted.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::download::{DownloadLimits, Downloader};
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::media_sync::{MediaSyncSink, SYNC_FILE_NAME};
+/// use reso_examples::sync::{replicate_all, Checkpoint};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let mut sink = MediaSyncSink::new(JsonlSink::append("property.jsonl")?, create_client()?, "media.json")?
+///         .download_to(Downloader::new(DownloadLimits::default()), "photos", SYNC_FILE_NAME);
+///     let mut checkpoint = Checkpoint::load("property.checkpoint.json", "Property")?;
+///
+///     replicate_all(&client, "Property", None, &mut sink, &mut checkpoint).await?;
+///     println!("Photos of {} listings", sink.manifest().listings.len());
+///     Ok(())
+/// }
+/// ```
+pub struct MediaSyncSink<C, S> {
+    inner: S,
+    client: C,
+    manifest_path: PathBuf,
+    manifest: MediaManifest,
+    downloads: Option<MediaDownloads>,
+    /// Only photos are kept, unless all media were asked for
+    photos_only: bool,
+}
+
+impl<C: ResoApi, S: RecordSink> MediaSyncSink<C, S> {
+    /// Wraps `inner`, fetching media with `client` and recording them in the
+    /// manifest at `manifest_path`, which is loaded if it exists.
+    pub fn new(inner: S, client: C, manifest_path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let manifest_path = manifest_path.as_ref().to_path_buf();
+        Ok(Self {
+            inner,
+            client,
+            manifest: MediaManifest::load(&manifest_path)?,
+            manifest_path,
+            downloads: None,
+            photos_only: true,
+        })
+    }
+
+    /// Also downloads the files into `dest_dir`, named by `template` as in
+    /// [`media_file_name`], e.g. [`SYNC_FILE_NAME`].
+    ///
+    /// Templates naming files by position, such as
+    /// [`DEFAULT_FILE_NAME`](crate::media::DEFAULT_FILE_NAME), can give a new
+    /// photo the name of a file that is being kept; the batch then fails
+    /// rather than overwrite it.
+    pub fn download_to(mut self, downloader: Downloader, dest_dir: impl AsRef<Path>, template: &str) -> Self {
+        self.downloads = Some(MediaDownloads {
+            downloader,
+            dest_dir: dest_dir.as_ref().to_path_buf(),
+            template: template.to_string(),
+        });
+        self
+    }
+
+    /// Keeps documents, videos, and tour links as well as photos.
+    pub fn all_media(mut self) -> Self {
+        self.photos_only = false;
+        self
+    }
+
+    pub fn manifest(&self) -> &MediaManifest {
+        &self.manifest
+    }
+}
+
+#[async_trait]
+impl<C: ResoApi, S: RecordSink> RecordSink for MediaSyncSink<C, S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let keys: Vec<&str> = records.iter().filter_map(|r| r["ListingKey"].as_str()).collect();
+        let mut by_listing = fetch_media(&self.client, &keys).await?;
+
+        for key in keys {
+            let mut media = listing_media(&by_listing.remove(key).unwrap_or_default());
+            if self.photos_only {
+                media.retain(ListingMedia::is_photo);
+            }
+            let previous = self.manifest.listings.get(key).map(Vec::as_slice).unwrap_or_default();
+            let paths = match &self.downloads {
+                Some(downloads) => downloads.download(key, &media, previous).await?,
+                None => vec![None; media.len()],
+            };
+            let files = media
+                .into_iter()
+                .zip(paths)
+                .map(|(item, path)| MediaFile {
+                    media_key: item.media_key,
+                    url: item.url,
+                    order: item.order,
+                    path,
+                })
+                .collect();
+            self.manifest.listings.insert(key.to_string(), files);
+        }
+        self.inner.write_batch(records).await
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.inner.flush().await?;
+        self.manifest.save(&self.manifest_path)
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.inner.finalize().await?;
+        self.manifest.save(&self.manifest_path)
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        self.inner.evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        self.inner.count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.inner.output_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpClient;
+    use crate::download::DownloadLimits;
+    use crate::media::DEFAULT_FILE_NAME;
+    use reso_client::ClientConfig;
+    use serde_json::json;
+    use wiremock::matchers::{path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Default)]
+    struct VecSink(Vec<JsonValue>);
+
+    #[async_trait]
+    impl RecordSink for VecSink {
+        async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+            self.0.extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_record_and_download_listing_media() {
+        let server = MockServer::start().await;
+        let url = |file: &str| format!("{}/cdn/{}", server.uri(), file);
+        Mock::given(path("/Media"))
+            .and(query_param(
+                "$filter",
+                "ResourceName eq 'Property' and ResourceRecordKey in ('A','B')",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [
+                {"MediaKey": "a2", "ResourceRecordKey": "A", "MediaURL": url("a2.jpg"), "Order": 2},
+                {"MediaKey": "a1", "ResourceRecordKey": "A", "MediaURL": url("a1.jpg"), "Order": 1},
+                {"MediaKey": "plat", "ResourceRecordKey": "A", "MediaURL": url("plat.pdf"), "MediaCategory": "Document"},
+            ]})))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(path("/cdn/a1.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![1u8; 5]))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/cdn/a2.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![2u8; 5]))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("reso-media-sync-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest_path = dir.join("media.json");
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "token")).build().unwrap();
+        let mut sink = MediaSyncSink::new(VecSink::default(), client, &manifest_path)
+            .unwrap()
+            .download_to(Downloader::new(DownloadLimits::default()), dir.join("photos"), DEFAULT_FILE_NAME);
+
+        let batch = [json!({"ListingKey": "A"}), json!({"ListingKey": "B"})];
+        sink.write_batch(&batch).await.unwrap();
+        sink.finalize().await.unwrap();
+
+        let manifest = MediaManifest::load(&manifest_path).unwrap();
+        let photos = dir.join("photos");
+        assert_eq!(manifest.paths("A"), [photos.join("A/01.jpg"), photos.join("A/02.jpg")]);
+        assert_eq!(manifest.listings["A"][0].media_key.as_deref(), Some("a1"));
+        assert!(manifest.listings["B"].is_empty());
+        assert_eq!(sink.inner.0.len(), 2);
+
+        // Replicating the listing again keeps the files it already has
+        sink.write_batch(&batch).await.unwrap();
+        assert_eq!(sink.manifest(), &manifest);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_removed_media_files_are_deleted() {
+        let server = MockServer::start().await;
+        let url = |file: &str| format!("{}/cdn/{}", server.uri(), file);
+        Mock::given(path("/Media"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [
+                {"MediaKey": "a1", "ResourceRecordKey": "A", "MediaURL": url("a1.jpg"), "Order": 1},
+                {"MediaKey": "a2", "ResourceRecordKey": "A", "MediaURL": url("a2.jpg"), "Order": 2},
+            ]})))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Media"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [
+                {"MediaKey": "a2", "ResourceRecordKey": "A", "MediaURL": url("a2.jpg"), "Order": 1},
+            ]})))
+            .mount(&server)
+            .await;
+        Mock::given(path("/cdn/a1.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![1u8; 5]))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/cdn/a2.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![2u8; 5]))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("reso-media-sync-removed-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "token")).build().unwrap();
+        let mut sink = MediaSyncSink::new(VecSink::default(), client, dir.join("media.json"))
+            .unwrap()
+            .download_to(Downloader::new(DownloadLimits::default()), dir.join("photos"), SYNC_FILE_NAME);
+
+        let batch = [json!({"ListingKey": "A"})];
+        sink.write_batch(&batch).await.unwrap();
+        let before: Vec<PathBuf> = sink.manifest().paths("A").into_iter().map(Path::to_path_buf).collect();
+        assert_eq!(before.len(), 2);
+
+        // a1 was removed and a2 moved to the front; a2's file is kept
+        sink.write_batch(&batch).await.unwrap();
+        assert_eq!(sink.manifest().paths("A"), [before[1].as_path()]);
+        assert!(!before[0].exists());
+        assert!(before[1].exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
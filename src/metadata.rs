@@ -0,0 +1,407 @@
+//! Parsing of the OData `$metadata` document into a structured schema.
+//!
+//! [`fetch_metadata`](crate::fetch_metadata) returns the raw CSDL XML. This
+//! module turns it into plain Rust structs describing the resources a server
+//! exposes, their key fields, the fields available on each, and the
+//! navigation properties that can be expanded.
+
+use reso_client::ResoError;
+
+/// Structured view of a server's metadata document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResoSchema {
+    /// Entity types declared across all schemas in the document
+    pub entity_types: Vec<EntityType>,
+    /// Entity sets declared in the entity container
+    pub entity_sets: Vec<EntitySet>,
+}
+
+/// An OData entity type, e.g. `Property` or `Member`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityType {
+    /// Unqualified type name
+    pub name: String,
+    /// Namespace of the schema that declares the type
+    pub namespace: String,
+    /// Names of the key fields, in declaration order
+    pub key: Vec<String>,
+    /// Structural fields of the type
+    pub fields: Vec<Field>,
+    /// Related entities reachable with `$expand`, e.g. `Media` or `ListAgent`
+    pub navigation: Vec<NavigationProperty>,
+}
+
+/// A structural property of an entity type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    /// The declared type, e.g. `Edm.String` or `Collection(Edm.String)`
+    pub edm_type: String,
+    /// Whether the field may be null (OData defaults to true when unspecified)
+    pub nullable: bool,
+}
+
+/// A navigation property of an entity type, linking to related entities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationProperty {
+    pub name: String,
+    /// Qualified name of the target entity type, e.g. `org.reso.metadata.Media`
+    pub target_type: String,
+    /// Whether the property holds many entities (`Collection(...)`)
+    pub collection: bool,
+}
+
+/// An entity set exposed by the service, i.e. a queryable resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntitySet {
+    pub name: String,
+    /// Qualified name of the entity type, e.g. `org.reso.metadata.Property`
+    pub entity_type: String,
+}
+
+impl EntityType {
+    /// Namespace-qualified type name.
+    pub fn qualified_name(&self) -> String {
+        if self.namespace.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.namespace, self.name)
+        }
+    }
+
+    /// Looks up a field by name.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Looks up a navigation property by name.
+    pub fn navigation_property(&self, name: &str) -> Option<&NavigationProperty> {
+        self.navigation.iter().find(|n| n.name == name)
+    }
+}
+
+/// A queryable resource with its entity type resolved.
+#[derive(Debug, Clone, Copy)]
+pub struct Resource<'a> {
+    pub name: &'a str,
+    pub entity_type: &'a EntityType,
+}
+
+impl ResoSchema {
+    /// Looks up an entity type by unqualified or qualified name.
+    pub fn entity_type(&self, name: &str) -> Option<&EntityType> {
+        self.entity_types
+            .iter()
+            .find(|t| t.name == name || t.qualified_name() == name)
+    }
+
+    /// Lists the queryable resources of the service.
+    ///
+    /// Resources come from the entity sets in the container. Some servers
+    /// publish entity types without a container, in which case every entity
+    /// type is treated as a resource of the same name.
+    pub fn resources(&self) -> Vec<Resource<'_>> {
+        if self.entity_sets.is_empty() {
+            return self
+                .entity_types
+                .iter()
+                .map(|t| Resource {
+                    name: &t.name,
+                    entity_type: t,
+                })
+                .collect();
+        }
+
+        self.entity_sets
+            .iter()
+            .filter_map(|set| {
+                self.entity_type(&set.entity_type).map(|t| Resource {
+                    name: &set.name,
+                    entity_type: t,
+                })
+            })
+            .collect()
+    }
+
+    /// Finds the entity type behind a resource name.
+    pub fn resource(&self, name: &str) -> Option<&EntityType> {
+        self.resources()
+            .into_iter()
+            .find(|r| r.name == name)
+            .map(|r| r.entity_type)
+    }
+
+    /// Checks a `$select` list against the fields the resource defines.
+    ///
+    /// Some vendors reject a whole request over one unknown field, so
+    /// selecting fields from another server's schema or a renamed local
+    /// field fails outright. Checking against the (cached) metadata first
+    /// either drops those fields or reports all of them at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+    /// * `fields` - The fields to select
+    /// * `policy` - Whether to drop unknown fields or fail on them
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use reso_examples::metadata::{parse_metadata, UnknownFieldPolicy};
+    /// use reso_examples::build_query_with_select;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let schema = parse_metadata(&std::fs::read_to_string(".reso_cache/metadata.xml")?)?;
+    /// let select = schema.check_select("Property", &["ListingKey", "X_Renamed"], UnknownFieldPolicy::Drop)?;
+    /// if !select.dropped.is_empty() {
+    ///     eprintln!("Not selecting unknown fields: {}", select.dropped.join(", "));
+    /// }
+    /// let query = build_query_with_select("Property", None, &select.fields, Some(10))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_select<'f>(
+        &self,
+        resource: &str,
+        fields: &[&'f str],
+        policy: UnknownFieldPolicy,
+    ) -> Result<CheckedSelect<'f>, ResoError> {
+        let entity_type = self.resource(resource).ok_or_else(|| {
+            ResoError::InvalidQuery(format!("Resource '{}' is not in the server's metadata", resource))
+        })?;
+        let (fields, dropped): (Vec<&str>, Vec<&str>) = fields
+            .iter()
+            .partition(|name| entity_type.field(name).is_some());
+
+        if policy == UnknownFieldPolicy::Error && !dropped.is_empty() {
+            return Err(ResoError::InvalidQuery(format!(
+                "{} does not define {}",
+                resource,
+                dropped.join(", ")
+            )));
+        }
+        Ok(CheckedSelect { fields, dropped })
+    }
+}
+
+/// What [`ResoSchema::check_select`] does with fields the resource does not define.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownFieldPolicy {
+    /// Leave them out of the select list
+    Drop,
+    /// Fail with [`ResoError::InvalidQuery`] naming them
+    Error,
+}
+
+impl std::str::FromStr for UnknownFieldPolicy {
+    type Err = ResoError;
+
+    /// Parses `drop` or `error`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop" => Ok(UnknownFieldPolicy::Drop),
+            "error" => Ok(UnknownFieldPolicy::Error),
+            other => Err(ResoError::Config(format!(
+                "Unknown field policy '{}' (expected drop or error)",
+                other
+            ))),
+        }
+    }
+}
+
+/// The result of [`ResoSchema::check_select`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckedSelect<'f> {
+    /// Fields the resource defines, in the order given
+    pub fields: Vec<&'f str>,
+    /// Fields left out because the resource does not define them
+    pub dropped: Vec<&'f str>,
+}
+
+/// Parses an OData CSDL `$metadata` document.
+///
+/// # Arguments
+///
+/// * `xml` - The metadata XML as returned by [`fetch_metadata`](crate::fetch_metadata)
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, fetch_metadata, metadata::parse_metadata};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let xml = fetch_metadata(&client).await?;
+///     let schema = parse_metadata(&xml)?;
+///
+///     for resource in schema.resources() {
+///         println!("{} ({} fields)", resource.name, resource.entity_type.fields.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn parse_metadata(xml: &str) -> Result<ResoSchema, ResoError> {
+    let document = roxmltree::Document::parse(xml)
+        .map_err(|e| ResoError::Parse(format!("Invalid metadata XML: {}", e)))?;
+
+    let mut schema = ResoSchema::default();
+
+    for schema_node in document.descendants().filter(|n| is_element(n, "Schema")) {
+        let namespace = schema_node.attribute("Namespace").unwrap_or_default();
+
+        for child in schema_node.children().filter(|n| n.is_element()) {
+            match child.tag_name().name() {
+                "EntityType" => schema.entity_types.push(parse_entity_type(child, namespace)),
+                "EntityContainer" => {
+                    for set in child.children().filter(|n| is_element(n, "EntitySet")) {
+                        if let (Some(name), Some(entity_type)) =
+                            (set.attribute("Name"), set.attribute("EntityType"))
+                        {
+                            schema.entity_sets.push(EntitySet {
+                                name: name.to_string(),
+                                entity_type: entity_type.to_string(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(schema)
+}
+
+fn is_element(node: &roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn parse_entity_type(node: roxmltree::Node, namespace: &str) -> EntityType {
+    let mut entity_type = EntityType {
+        name: node.attribute("Name").unwrap_or_default().to_string(),
+        namespace: namespace.to_string(),
+        ..Default::default()
+    };
+
+    for child in node.children().filter(|n| n.is_element()) {
+        match child.tag_name().name() {
+            "Key" => {
+                entity_type.key = child
+                    .children()
+                    .filter(|n| is_element(n, "PropertyRef"))
+                    .filter_map(|n| n.attribute("Name"))
+                    .map(|s| s.to_string())
+                    .collect();
+            }
+            "Property" => {
+                if let Some(name) = child.attribute("Name") {
+                    entity_type.fields.push(Field {
+                        name: name.to_string(),
+                        edm_type: child.attribute("Type").unwrap_or("Edm.String").to_string(),
+                        nullable: child.attribute("Nullable") != Some("false"),
+                    });
+                }
+            }
+            "NavigationProperty" => {
+                if let (Some(name), Some(target)) = (child.attribute("Name"), child.attribute("Type")) {
+                    let element = target
+                        .strip_prefix("Collection(")
+                        .and_then(|t| t.strip_suffix(')'));
+                    entity_type.navigation.push(NavigationProperty {
+                        name: name.to_string(),
+                        target_type: element.unwrap_or(target).to_string(),
+                        collection: element.is_some(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    entity_type
+}
+
+#[cfg(test)]
+pub(crate) const SAMPLE_METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<edmx:Edmx Version="4.0" xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx">
+  <edmx:DataServices>
+    <Schema Namespace="org.reso.metadata" xmlns="http://docs.oasis-open.org/odata/ns/edm">
+      <EntityType Name="Property">
+        <Key><PropertyRef Name="ListingKey"/></Key>
+        <Property Name="ListingKey" Type="Edm.String" Nullable="false" MaxLength="255"/>
+        <Property Name="ListPrice" Type="Edm.Decimal" Precision="14" Scale="2"/>
+        <Property Name="City" Type="Edm.String"/>
+        <Property Name="X_VendorFlag" Type="Edm.Boolean"/>
+        <NavigationProperty Name="ListAgent" Type="org.reso.metadata.Member"/>
+        <NavigationProperty Name="Media" Type="Collection(org.reso.metadata.Media)"/>
+      </EntityType>
+      <EntityType Name="Member">
+        <Key><PropertyRef Name="MemberKey"/></Key>
+        <Property Name="MemberKey" Type="Edm.String" Nullable="false"/>
+        <Property Name="MemberFullName" Type="Edm.String"/>
+      </EntityType>
+      <EntityContainer Name="Default">
+        <EntitySet Name="Property" EntityType="org.reso.metadata.Property"/>
+        <EntitySet Name="Member" EntityType="org.reso.metadata.Member"/>
+      </EntityContainer>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata() {
+        let schema = parse_metadata(SAMPLE_METADATA).unwrap();
+
+        assert_eq!(schema.entity_types.len(), 2);
+        let property = schema.resource("Property").unwrap();
+        assert_eq!(property.key, vec!["ListingKey"]);
+        assert_eq!(property.fields.len(), 4);
+        assert_eq!(property.qualified_name(), "org.reso.metadata.Property");
+
+        let key = property.field("ListingKey").unwrap();
+        assert!(!key.nullable);
+        assert!(property.field("ListPrice").unwrap().nullable);
+        assert_eq!(property.field("ListPrice").unwrap().edm_type, "Edm.Decimal");
+
+        let media = property.navigation_property("Media").unwrap();
+        assert_eq!(media.target_type, "org.reso.metadata.Media");
+        assert!(media.collection);
+        let agent = property.navigation_property("ListAgent").unwrap();
+        assert!(!agent.collection);
+        assert_eq!(schema.entity_type(&agent.target_type).unwrap().key, ["MemberKey"]);
+    }
+
+    #[test]
+    fn test_resources_without_container() {
+        let xml = r#"<Edmx><DataServices><Schema Namespace="ns">
+            <EntityType Name="Office"><Key><PropertyRef Name="OfficeKey"/></Key>
+            <Property Name="OfficeKey" Type="Edm.String"/></EntityType>
+        </Schema></DataServices></Edmx>"#;
+        let schema = parse_metadata(xml).unwrap();
+        let names: Vec<_> = schema.resources().iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["Office"]);
+    }
+
+    #[test]
+    fn test_check_select_drops_or_rejects_unknown_fields() {
+        let schema = parse_metadata(SAMPLE_METADATA).unwrap();
+        let fields = ["ListingKey", "LivingArea", "City"];
+
+        let checked = schema.check_select("Property", &fields, UnknownFieldPolicy::Drop).unwrap();
+        assert_eq!(checked.fields, ["ListingKey", "City"]);
+        assert_eq!(checked.dropped, ["LivingArea"]);
+
+        let err = schema.check_select("Property", &fields, UnknownFieldPolicy::Error).unwrap_err();
+        assert!(err.to_string().contains("LivingArea"));
+    }
+
+    #[test]
+    fn test_parse_metadata_invalid_xml() {
+        assert!(parse_metadata("<Edmx>").is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! Request metrics in the Prometheus text format.
+//!
+//! [`RequestMetrics`] is a [`ResponseHook`] for [`HttpClient`]: add it to a
+//! client and it counts every response by resource and HTTP status and
+//! records how long the server took to answer. [`RequestMetrics::render`]
+//! produces the Prometheus exposition format, ready to serve from a
+//! `/metrics` endpoint.
+//!
+//! Requests that fail before a response arrives (connection errors,
+//! timeouts) are not seen by response hooks and so are not counted.
+//!
+//! [`HttpClient`]: crate::client::HttpClient
+
+use crate::client::{ResponseHook, ResponseParts};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Counts of RESO responses and their latencies.
+///
+/// Clones share the same counts, so one clone can be handed to the client
+/// while another renders the metrics.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_client::ClientConfig;
+/// use reso_examples::client::HttpClient;
+/// use reso_examples::count_records;
+/// use reso_examples::metrics::RequestMetrics;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let metrics = RequestMetrics::default();
+///     let client = HttpClient::builder(ClientConfig::from_env()?)
+///         .response_hook(metrics.clone())
+///         .build()?;
+///
+///     count_records(&client, "Property", None).await?;
+///     print!("{}", metrics.render());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetrics {
+    inner: Arc<Mutex<Counts>>,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    /// Responses by (resource, status)
+    responses: BTreeMap<(String, u16), u64>,
+    /// Responses per latency bucket, parallel to `LATENCY_BUCKETS`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+    latency_count: u64,
+}
+
+impl RequestMetrics {
+    /// Records one response.
+    pub fn record(&self, response: &ResponseParts) {
+        let seconds = response.elapsed.as_secs_f64();
+        let mut counts = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        *counts
+            .responses
+            .entry((resource_label(&response.url), response.status.as_u16()))
+            .or_default() += 1;
+        for (bucket, bound) in counts.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        counts.latency_sum += seconds;
+        counts.latency_count += 1;
+    }
+
+    /// Total responses recorded with `status`.
+    pub fn responses_with_status(&self, status: u16) -> u64 {
+        let counts = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        counts
+            .responses
+            .iter()
+            .filter(|((_, s), _)| *s == status)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let counts = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        out.push_str("# HELP reso_requests_total RESO Web API responses, by resource and HTTP status.\n");
+        out.push_str("# TYPE reso_requests_total counter\n");
+        for ((resource, status), n) in &counts.responses {
+            let _ = writeln!(
+                out,
+                "reso_requests_total{{resource=\"{}\",status=\"{}\"}} {}",
+                escape_label(resource),
+                status,
+                n
+            );
+        }
+
+        out.push_str("# HELP reso_request_duration_seconds Time until RESO Web API response headers arrived.\n");
+        out.push_str("# TYPE reso_request_duration_seconds histogram\n");
+        for (bound, n) in LATENCY_BUCKETS.iter().zip(counts.buckets) {
+            let _ = writeln!(out, "reso_request_duration_seconds_bucket{{le=\"{}\"}} {}", bound, n);
+        }
+        let _ = writeln!(
+            out,
+            "reso_request_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            counts.latency_count
+        );
+        let _ = writeln!(out, "reso_request_duration_seconds_sum {}", counts.latency_sum);
+        let _ = writeln!(out, "reso_request_duration_seconds_count {}", counts.latency_count);
+        out
+    }
+}
+
+#[async_trait]
+impl ResponseHook for RequestMetrics {
+    async fn on_response(&self, response: &ResponseParts) {
+        self.record(response);
+    }
+}
+
+/// The resource a request URL addresses: `Property` for `.../Property?$top=1`,
+/// `.../Property('A1')`, and `.../Property/$count`, or `$metadata`.
+fn resource_label(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.rsplit('/').filter(|s| !s.is_empty());
+    let last = segments.next().unwrap_or_default();
+    let segment = if last == "$count" { segments.next().unwrap_or_default() } else { last };
+    segment.split('(').next().unwrap_or_default().to_string()
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    fn response(url: &str, status: u16, millis: u64) -> ResponseParts {
+        ResponseParts {
+            url: url.to_string(),
+            status: StatusCode::from_u16(status).unwrap(),
+            headers: HeaderMap::new(),
+            elapsed: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_render_counts_and_histogram() {
+        let metrics = RequestMetrics::default();
+        metrics.record(&response("https://api.example.com/OData/Property?$top=1", 200, 80));
+        metrics.record(&response("https://api.example.com/OData/Property('A1')", 200, 300));
+        metrics.record(&response("https://api.example.com/OData/Property/$count", 429, 50));
+        metrics.record(&response("https://api.example.com/OData/$metadata", 200, 1200));
+
+        let text = metrics.render();
+        assert!(text.contains("reso_requests_total{resource=\"Property\",status=\"200\"} 2\n"));
+        assert!(text.contains("reso_requests_total{resource=\"Property\",status=\"429\"} 1\n"));
+        assert!(text.contains("reso_requests_total{resource=\"$metadata\",status=\"200\"} 1\n"));
+        assert!(text.contains("reso_request_duration_seconds_bucket{le=\"0.1\"} 2\n"));
+        assert!(text.contains("reso_request_duration_seconds_bucket{le=\"0.5\"} 3\n"));
+        assert!(text.contains("reso_request_duration_seconds_bucket{le=\"+Inf\"} 4\n"));
+        assert!(text.contains("reso_request_duration_seconds_count 4\n"));
+        assert_eq!(metrics.responses_with_status(429), 1);
+    }
+}
@@ -0,0 +1,444 @@
+//! SQL migrations for the database sinks, generated from metadata.
+//!
+//! The sinks in [`export`](crate::export) store each record as JSON next to
+//! its key and `ModificationTimestamp`. The migrations generated here create
+//! that same table and add one generated column per metadata field, computed
+//! from the stored JSON, so the data can be queried like an ordinary typed
+//! table while the sinks keep writing whole records. When the server's
+//! metadata changes, diffing the old and new schema yields `ALTER TABLE`
+//! migrations for the added fields.
+//!
+//! Migration files follow the naming of sqlx (`<version>_<name>.sql`) or
+//! refinery (`V<version>__<name>.sql`).
+
+use crate::codegen::to_snake_case;
+use crate::metadata::{EntityType, Field, ResoSchema};
+use reso_client::ResoError;
+use std::path::{Path, PathBuf};
+
+/// Columns managed by the sinks, which metadata fields must not shadow.
+const SINK_COLUMNS: &[&str] = &["key", "modification_timestamp", "data"];
+
+/// The database a migration is written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Sqlite,
+    Postgres,
+}
+
+impl std::str::FromStr for SqlDialect {
+    type Err = ResoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sqlite" => Ok(SqlDialect::Sqlite),
+            "postgres" | "postgresql" => Ok(SqlDialect::Postgres),
+            other => Err(ResoError::Config(format!(
+                "Unknown SQL dialect '{}' (expected sqlite or postgres)",
+                other
+            ))),
+        }
+    }
+}
+
+/// File naming convention of the migration tool in use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MigrationFormat {
+    /// `20250101120000_create_property.sql`
+    #[default]
+    Sqlx,
+    /// `V20250101120000__create_property.sql`
+    Refinery,
+}
+
+impl std::str::FromStr for MigrationFormat {
+    type Err = ResoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sqlx" => Ok(MigrationFormat::Sqlx),
+            "refinery" => Ok(MigrationFormat::Refinery),
+            other => Err(ResoError::Config(format!(
+                "Unknown migration format '{}' (expected sqlx or refinery)",
+                other
+            ))),
+        }
+    }
+}
+
+/// One migration file's worth of SQL for a resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub version: u64,
+    /// Short description used in the file name, e.g. `create_property`
+    pub name: String,
+    pub sql: String,
+}
+
+impl Migration {
+    pub fn file_name(&self, format: MigrationFormat) -> String {
+        match format {
+            MigrationFormat::Sqlx => format!("{}_{}.sql", self.version, self.name),
+            MigrationFormat::Refinery => format!("V{}__{}.sql", self.version, self.name),
+        }
+    }
+}
+
+/// Generates the migrations that bring a database from `previous` to
+/// `current`.
+///
+/// Resources missing from `previous` (or all of them, when there is no
+/// previous schema) get a `CREATE TABLE` migration; resources with new
+/// fields get an `ALTER TABLE` migration. Removed fields and changed types
+/// are noted in comments rather than dropped, since the stored JSON still
+/// holds them. Versions count up from `version`.
+///
+/// # Arguments
+///
+/// * `previous` - The schema the existing migrations were generated from
+/// * `current` - The server's current schema
+/// * `resources` - Resources to include; empty means all
+/// * `dialect` - The target database
+/// * `version` - Version of the first generated migration
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::{create_client, fetch_metadata};
+/// use reso_examples::metadata::parse_metadata;
+/// use reso_examples::migrations::{generate_migrations, MigrationFormat, SqlDialect};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let schema = parse_metadata(&fetch_metadata(&client).await?)?;
+///
+///     let migrations = generate_migrations(None, &schema, &["Property"], SqlDialect::Sqlite, 1);
+///     for migration in migrations {
+///         std::fs::write(migration.file_name(MigrationFormat::Sqlx), migration.sql)?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn generate_migrations(
+    previous: Option<&ResoSchema>,
+    current: &ResoSchema,
+    resources: &[&str],
+    dialect: SqlDialect,
+    version: u64,
+) -> Vec<Migration> {
+    let mut migrations = Vec::new();
+
+    for resource in current.resources() {
+        if !resources.is_empty() && !resources.contains(&resource.name) {
+            continue;
+        }
+        let table = to_snake_case(resource.name);
+        let (name, sql) = match previous.and_then(|p| p.resource(resource.name)) {
+            None => (
+                format!("create_{}", table),
+                create_table_sql(resource.name, resource.entity_type, dialect),
+            ),
+            Some(old) => match alter_table_sql(resource.name, old, resource.entity_type, dialect) {
+                Some(sql) => (format!("alter_{}", table), sql),
+                None => continue,
+            },
+        };
+        migrations.push(Migration {
+            version: version + migrations.len() as u64,
+            name,
+            sql,
+        });
+    }
+
+    migrations
+}
+
+/// The `CREATE TABLE` statement for a resource's sink table.
+pub fn create_table_sql(table: &str, entity_type: &EntityType, dialect: SqlDialect) -> String {
+    let data_type = match dialect {
+        SqlDialect::Sqlite => "TEXT",
+        SqlDialect::Postgres => "JSONB",
+    };
+    let mut columns = vec![
+        "    key TEXT PRIMARY KEY".to_string(),
+        "    modification_timestamp TEXT".to_string(),
+        format!("    data {} NOT NULL", data_type),
+    ];
+    columns.extend(
+        mapped_fields(entity_type).map(|field| format!("    {}", column_definition(field, dialect))),
+    );
+
+    format!(
+        "-- {} fields from {}\nCREATE TABLE IF NOT EXISTS {} (\n{}\n);\n",
+        entity_type.fields.len(),
+        entity_type.qualified_name(),
+        quote_identifier(table),
+        columns.join(",\n")
+    )
+}
+
+/// The `ALTER TABLE` statements for fields added between two versions of
+/// an entity type, or `None` if nothing changed.
+pub fn alter_table_sql(
+    table: &str,
+    old: &EntityType,
+    new: &EntityType,
+    dialect: SqlDialect,
+) -> Option<String> {
+    let mut sql = String::new();
+
+    for field in mapped_fields(new) {
+        match old.field(&field.name) {
+            None => sql.push_str(&format!(
+                "ALTER TABLE {} ADD COLUMN {};\n",
+                quote_identifier(table),
+                column_definition(field, dialect)
+            )),
+            Some(previous) if previous.edm_type != field.edm_type => sql.push_str(&format!(
+                "-- {} changed type from {} to {}; recreate the column if its SQL type matters\n",
+                field.name, previous.edm_type, field.edm_type
+            )),
+            Some(_) => {}
+        }
+    }
+    for field in &old.fields {
+        if new.field(&field.name).is_none() {
+            sql.push_str(&format!(
+                "-- {} was removed from the metadata; its column is kept\n",
+                field.name
+            ));
+        }
+    }
+
+    (!sql.is_empty()).then_some(sql)
+}
+
+/// Differences between a sink table and the current metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaChanges {
+    pub table: String,
+    /// Columns added for new metadata fields
+    pub added: Vec<String>,
+    /// Columns whose field is no longer in the metadata; they are kept
+    pub removed: Vec<String>,
+}
+
+impl SchemaChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl std::fmt::Display for SchemaChanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "{}: schema unchanged", self.table);
+        }
+        write!(f, "{}: {} columns added", self.table, self.added.len())?;
+        if !self.added.is_empty() {
+            write!(f, " ({})", self.added.join(", "))?;
+        }
+        if !self.removed.is_empty() {
+            write!(
+                f,
+                "; {} fields no longer in metadata ({})",
+                self.removed.len(),
+                self.removed.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares a table's columns with an entity type, returning the changes
+/// and the `ALTER TABLE` statements that add the missing columns.
+///
+/// Names are compared case-insensitively, as SQLite does.
+#[cfg(any(feature = "db-sqlite", feature = "db-postgres"))]
+pub(crate) fn plan_schema_changes(
+    table: &str,
+    columns: &[String],
+    entity_type: &EntityType,
+    dialect: SqlDialect,
+) -> (SchemaChanges, Vec<String>) {
+    let has_column = |name: &str| columns.iter().any(|c| c.eq_ignore_ascii_case(name));
+    let mut changes = SchemaChanges {
+        table: table.to_string(),
+        ..Default::default()
+    };
+    let mut statements = Vec::new();
+
+    for field in mapped_fields(entity_type) {
+        if !has_column(&field.name) {
+            statements.push(format!(
+                "ALTER TABLE {} ADD COLUMN {}",
+                quote_identifier(table),
+                column_definition(field, dialect)
+            ));
+            changes.added.push(field.name.clone());
+        }
+    }
+    for column in columns {
+        let is_field = entity_type.fields.iter().any(|f| f.name.eq_ignore_ascii_case(column));
+        if !is_field && !SINK_COLUMNS.contains(&column.as_str()) {
+            changes.removed.push(column.clone());
+        }
+    }
+
+    (changes, statements)
+}
+
+/// Writes migrations into `dir`, returning the paths written.
+pub fn write_migrations(
+    dir: impl AsRef<Path>,
+    migrations: &[Migration],
+    format: MigrationFormat,
+) -> Result<Vec<PathBuf>, ResoError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)
+        .map_err(|e| ResoError::Config(format!("Cannot create {}: {}", dir.display(), e)))?;
+
+    migrations
+        .iter()
+        .map(|migration| {
+            let path = dir.join(migration.file_name(format));
+            std::fs::write(&path, &migration.sql)
+                .map_err(|e| ResoError::Config(format!("Cannot write {}: {}", path.display(), e)))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+fn mapped_fields(entity_type: &EntityType) -> impl Iterator<Item = &Field> {
+    entity_type
+        .fields
+        .iter()
+        .filter(|f| !SINK_COLUMNS.contains(&f.name.to_ascii_lowercase().as_str()))
+}
+
+/// A generated column reading the field from the stored JSON record.
+///
+/// SQLite columns are virtual, which `ALTER TABLE ADD COLUMN` allows.
+/// PostgreSQL columns are stored; dates stay text there because casting
+/// text to a date depends on session settings, which generated columns
+/// may not.
+fn column_definition(field: &Field, dialect: SqlDialect) -> String {
+    let name = quote_identifier(&field.name);
+    let path = field.name.replace('\'', "''");
+    let collection = field.edm_type.starts_with("Collection(");
+
+    match dialect {
+        SqlDialect::Sqlite => {
+            let sql_type = match field.edm_type.as_str() {
+                _ if collection => "TEXT",
+                "Edm.Boolean" | "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32"
+                | "Edm.Int64" => "INTEGER",
+                "Edm.Decimal" | "Edm.Double" | "Edm.Single" => "REAL",
+                _ => "TEXT",
+            };
+            format!(
+                "{} {} GENERATED ALWAYS AS (json_extract(data, '$.\"{}\"')) VIRTUAL",
+                name, sql_type, path
+            )
+        }
+        SqlDialect::Postgres => {
+            let expression = match field.edm_type.as_str() {
+                _ if collection => format!("data -> '{}'", path),
+                "Edm.Boolean" => format!("(data ->> '{}')::boolean", path),
+                "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" => {
+                    format!("(data ->> '{}')::integer", path)
+                }
+                "Edm.Int64" => format!("(data ->> '{}')::bigint", path),
+                "Edm.Decimal" => format!("(data ->> '{}')::numeric", path),
+                "Edm.Double" | "Edm.Single" => format!("(data ->> '{}')::double precision", path),
+                _ => format!("data ->> '{}'", path),
+            };
+            let sql_type = match field.edm_type.as_str() {
+                _ if collection => "JSONB",
+                "Edm.Boolean" => "BOOLEAN",
+                "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" => "INTEGER",
+                "Edm.Int64" => "BIGINT",
+                "Edm.Decimal" => "NUMERIC",
+                "Edm.Double" | "Edm.Single" => "DOUBLE PRECISION",
+                _ => "TEXT",
+            };
+            format!("{} {} GENERATED ALWAYS AS ({}) STORED", name, sql_type, expression)
+        }
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property(fields: &[(&str, &str)]) -> ResoSchema {
+        ResoSchema {
+            entity_types: vec![EntityType {
+                name: "Property".to_string(),
+                namespace: "org.reso.metadata".to_string(),
+                key: vec!["ListingKey".to_string()],
+                fields: fields
+                    .iter()
+                    .map(|(name, edm_type)| Field {
+                        name: name.to_string(),
+                        edm_type: edm_type.to_string(),
+                        nullable: true,
+                    })
+                    .collect(),
+                navigation: Vec::new(),
+            }],
+            entity_sets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_create_then_alter() {
+        let v1 = property(&[("ListingKey", "Edm.String"), ("ListPrice", "Edm.Decimal")]);
+        let v2 = property(&[
+            ("ListingKey", "Edm.String"),
+            ("ListPrice", "Edm.Decimal"),
+            ("BedroomsTotal", "Edm.Int32"),
+        ]);
+
+        let created = generate_migrations(None, &v1, &[], SqlDialect::Sqlite, 7);
+        assert_eq!(created[0].file_name(MigrationFormat::Sqlx), "7_create_property.sql");
+        assert!(created[0].sql.contains("data TEXT NOT NULL"));
+        assert!(created[0]
+            .sql
+            .contains("\"ListPrice\" REAL GENERATED ALWAYS AS (json_extract(data, '$.\"ListPrice\"')) VIRTUAL"));
+
+        let altered = generate_migrations(Some(&v1), &v2, &[], SqlDialect::Postgres, 8);
+        assert_eq!(altered[0].file_name(MigrationFormat::Refinery), "V8__alter_property.sql");
+        assert_eq!(
+            altered[0].sql,
+            "ALTER TABLE \"Property\" ADD COLUMN \"BedroomsTotal\" INTEGER \
+             GENERATED ALWAYS AS ((data ->> 'BedroomsTotal')::integer) STORED;\n"
+        );
+
+        assert!(generate_migrations(Some(&v2), &v2, &[], SqlDialect::Sqlite, 9).is_empty());
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    #[test]
+    fn test_generated_sqlite_migration_runs() {
+        let schema = property(&[("ListingKey", "Edm.String"), ("ListPrice", "Edm.Decimal")]);
+        let sql = &generate_migrations(None, &schema, &[], SqlDialect::Sqlite, 1)[0].sql;
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(sql).unwrap();
+        conn.execute(
+            "INSERT INTO \"Property\" (key, data) VALUES ('A', '{\"ListPrice\": 250000.5}')",
+            [],
+        )
+        .unwrap();
+        let price: f64 = conn
+            .query_row("SELECT \"ListPrice\" FROM \"Property\"", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(price, 250000.5);
+    }
+}
@@ -0,0 +1,181 @@
+//! Typed records for common RESO resources.
+//!
+//! Indexing a `JsonValue` by field name compiles whatever the spelling, and
+//! every value comes back as JSON to be converted by hand. [`Property`]
+//! names the commonly used Data Dictionary 2.0 fields with Rust types, and
+//! [`execute_query_typed`](crate::execute_query_typed) deserializes query
+//! results into it, or into any other `Deserialize` type.
+//!
+//! All fields but `ListingKey` are optional, since servers omit fields that
+//! are empty or not in `$select`. Fields without a typed counterpart are
+//! kept in [`Property::other`], so a record survives a round trip intact.
+
+use crate::listing_status::StandardStatus;
+use chrono::{DateTime, NaiveDate, Utc};
+use reso_client::JsonValue;
+use serde::{Deserialize, Serialize};
+
+/// A Property record with the commonly used Data Dictionary 2.0 fields.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::models::Property;
+/// use reso_examples::{build_query, create_client, execute_query_typed};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_query("Property", Some("City eq 'Austin' and StandardStatus eq 'Active'"), Some(10))?;
+///
+///     for property in execute_query_typed::<Property>(&client, &query).await? {
+///         println!(
+///             "{}: {} beds, ${}",
+///             property.listing_key,
+///             property.bedrooms_total.unwrap_or(0),
+///             property.list_price.unwrap_or_default()
+///         );
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Property {
+    pub listing_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standard_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mls_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_sub_type: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_list_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub close_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_contract_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub close_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_on_market: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bedrooms_total: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bathrooms_total_integer: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bathrooms_full: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bathrooms_half: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub living_area: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lot_size_acres: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lot_size_square_feet: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year_built: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stories_total: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub garage_spaces: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unparsed_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_suffix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_or_province: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub county_or_parish: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_remarks: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub photos_count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_agent_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_office_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub originating_system_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modification_timestamp: Option<DateTime<Utc>>,
+
+    /// Fields not covered above, including OData annotations, as returned
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, JsonValue>,
+}
+
+impl Property {
+    /// The `StandardStatus`, parsed. `None` when it is missing or a value
+    /// outside the Data Dictionary's list; see
+    /// [`StatusMapping`](crate::listing_status::StatusMapping) for feeds
+    /// that put their own vocabulary in `MlsStatus`.
+    pub fn status(&self) -> Option<StandardStatus> {
+        self.standard_status.as_deref()?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_property_reads_typed_fields_and_keeps_the_rest() {
+        let record = json!({
+            "@odata.id": "Property('A1')",
+            "ListingKey": "A1",
+            "ListPrice": 525000,
+            "StandardStatus": "Active Under Contract",
+            "BedroomsTotal": 3,
+            "City": "Austin",
+            "Latitude": null,
+            "ListingContractDate": "2025-01-02",
+            "ModificationTimestamp": "2025-01-02T10:00:00-06:00",
+            "FireplaceYN": true
+        });
+        let property: Property = serde_json::from_value(record).unwrap();
+
+        assert_eq!(property.list_price, Some(525000.0));
+        assert_eq!(property.bedrooms_total, Some(3));
+        assert_eq!(property.latitude, None);
+        assert_eq!(property.status(), Some(StandardStatus::ActiveUnderContract));
+        assert_eq!(property.listing_contract_date, NaiveDate::from_ymd_opt(2025, 1, 2));
+        assert_eq!(
+            property.modification_timestamp.unwrap().to_rfc3339(),
+            "2025-01-02T16:00:00+00:00"
+        );
+        assert_eq!(property.other["FireplaceYN"], json!(true));
+        assert_eq!(property.other["@odata.id"], json!("Property('A1')"));
+        assert!(!property.other.contains_key("City"));
+
+        let round_trip = serde_json::to_value(&property).unwrap();
+        assert_eq!(round_trip["FireplaceYN"], json!(true));
+        assert_eq!(round_trip["ListingKey"], json!("A1"));
+        assert!(round_trip.get("Latitude").is_none());
+    }
+}
@@ -0,0 +1,277 @@
+//! Scanning replicated records for personal information in free text.
+//!
+//! MLS rules usually forbid contact details in public remarks, yet agents
+//! routinely type "call Jane at 512-555-0147" into `PublicRemarks`. Before
+//! a dataset is published, [`scan_records`] looks through its free-text
+//! fields for email addresses and phone numbers and reports where they are.
+//!
+//! Detection is heuristic: it finds addresses and North American phone
+//! numbers written in the usual ways, not ones spelled out to dodge filters.
+//! Reported values are masked so the report itself can be shared.
+
+use crate::compact::LatestRecords;
+use reso_client::{JsonValue, ResoError};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Free-text fields shown to the public, scanned by default.
+pub const PUBLIC_TEXT_FIELDS: &[&str] = &["PublicRemarks", "SyndicationRemarks", "Directions"];
+
+/// The kind of personal information found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PiiKind {
+    Email,
+    Phone,
+}
+
+impl std::fmt::Display for PiiKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PiiKind::Email => "email",
+            PiiKind::Phone => "phone",
+        })
+    }
+}
+
+/// A likely email address or phone number in a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiiFinding {
+    pub key: String,
+    pub field: String,
+    pub kind: PiiKind,
+    /// The value with most characters masked, e.g. `j***@example.com` or `***-***-0147`
+    pub masked: String,
+}
+
+/// The result of [`scan_records`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PiiReport {
+    /// Records scanned
+    pub scanned: usize,
+    pub findings: Vec<PiiFinding>,
+}
+
+impl PiiReport {
+    /// Returns true if nothing was found.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Number of findings per field and kind.
+    pub fn counts(&self) -> BTreeMap<(&str, PiiKind), usize> {
+        let mut counts = BTreeMap::new();
+        for finding in &self.findings {
+            *counts.entry((finding.field.as_str(), finding.kind)).or_default() += 1;
+        }
+        counts
+    }
+}
+
+impl std::fmt::Display for PiiReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Scanned {} records: {} likely PII values",
+            self.scanned,
+            self.findings.len()
+        )?;
+        for ((field, kind), count) in self.counts() {
+            write!(f, "\n  {} {}: {}", field, kind, count)?;
+        }
+        for finding in &self.findings {
+            write!(
+                f,
+                "\n  {} {} {}: {}",
+                finding.key, finding.field, finding.kind, finding.masked
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Scans the text fields of records for email addresses and phone numbers.
+///
+/// # Arguments
+///
+/// * `records` - The records to scan, e.g. a replicated JSONL store
+/// * `key_field` - Field identifying each record in the report, e.g. "ListingKey"
+/// * `fields` - Text fields to scan, e.g. [`PUBLIC_TEXT_FIELDS`]
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::pii::{scan_records, PiiKind, PUBLIC_TEXT_FIELDS};
+/// use serde_json::json;
+///
+/// let records = [json!({
+///     "ListingKey": "A",
+///     "PublicRemarks": "Great yard! Call (512) 555-0147 or email jane@example.com"
+/// })];
+/// let report = scan_records(&records, "ListingKey", PUBLIC_TEXT_FIELDS);
+///
+/// let kinds: Vec<PiiKind> = report.findings.iter().map(|f| f.kind).collect();
+/// assert_eq!(kinds, [PiiKind::Email, PiiKind::Phone]);
+/// assert_eq!(report.findings[1].masked, "***-***-0147");
+/// ```
+pub fn scan_records<'a>(
+    records: impl IntoIterator<Item = &'a JsonValue>,
+    key_field: &str,
+    fields: &[&str],
+) -> PiiReport {
+    let mut report = PiiReport::default();
+    for record in records {
+        report.scanned += 1;
+        for field in fields {
+            let Some(text) = record[*field].as_str() else {
+                continue;
+            };
+            for (kind, masked) in find_pii(text) {
+                report.findings.push(PiiFinding {
+                    key: match &record[key_field] {
+                        JsonValue::String(s) => s.clone(),
+                        other => other.to_string(),
+                    },
+                    field: field.to_string(),
+                    kind,
+                    masked,
+                });
+            }
+        }
+    }
+    report
+}
+
+/// Scans the newest version of each record in a JSONL store.
+pub fn scan_jsonl(path: impl AsRef<Path>, key_field: &str, fields: &[&str]) -> Result<PiiReport, ResoError> {
+    let mut latest = LatestRecords::default();
+    latest.read_file(path.as_ref(), key_field)?;
+    Ok(scan_records(latest.records(), key_field, fields))
+}
+
+/// Finds likely email addresses and phone numbers in a piece of text,
+/// returning each one masked, emails first.
+pub fn find_pii(text: &str) -> Vec<(PiiKind, String)> {
+    let mut found: Vec<(PiiKind, String)> = emails(text)
+        .into_iter()
+        .map(|email| (PiiKind::Email, email))
+        .collect();
+    found.extend(phones(text).into_iter().map(|phone| (PiiKind::Phone, phone)));
+    found
+}
+
+/// Masked addresses of the form `local@domain.tld`.
+fn emails(text: &str) -> Vec<String> {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+
+    let mut found = Vec::new();
+    for (at, _) in text.match_indices('@') {
+        let local_start = text[..at]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_local(c))
+            .last()
+            .map_or(at, |(i, _)| i);
+        let local = &text[local_start..at];
+        let domain_len = text[at + 1..].find(|c: char| !is_domain(c)).unwrap_or(text.len() - at - 1);
+        // Sentence punctuation after the address is not part of it
+        let domain = text[at + 1..at + 1 + domain_len].trim_end_matches(['.', '-']);
+
+        let tld = domain.rsplit('.').next().unwrap_or_default();
+        let valid_domain = domain.contains('.') && tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic());
+        if let (Some(first), true) = (local.chars().next(), valid_domain) {
+            found.push(format!("{}***@{}", first, domain));
+        }
+    }
+    found
+}
+
+/// Masked North American numbers such as `512-555-0147`, `(512) 555 0147`,
+/// `512.555.0147`, `5125550147`, and `+1 512 555 0147`.
+fn phones(text: &str) -> Vec<String> {
+    // Runs of digits joined by separators, split into digit groups
+    let mut runs: Vec<Vec<&str>> = Vec::new();
+    let mut groups: Vec<&str> = Vec::new();
+    let mut start = None;
+    let mut separators = 0;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        if c.is_ascii_digit() {
+            start.get_or_insert(i);
+            separators = 0;
+            continue;
+        }
+        if let Some(s) = start.take() {
+            groups.push(&text[s..i]);
+        }
+        let is_separator = " -.()+".contains(c) && i < text.len();
+        // Up to two in a row, as in "(512) 555"
+        separators += 1;
+        if !is_separator || separators > 2 {
+            if !groups.is_empty() {
+                runs.push(std::mem::take(&mut groups));
+            }
+            separators = 0;
+        }
+    }
+
+    let mut found = Vec::new();
+    for groups in runs {
+        let mut i = 0;
+        while i < groups.len() {
+            // Windows of 3 (512 555 0147), 4 (1 512 555 0147), or 1 (5125550147) groups
+            let matched = [3, 4, 1]
+                .into_iter()
+                .find(|&n| groups.get(i..i + n).is_some_and(is_phone));
+            match matched {
+                Some(n) => {
+                    let digits: String = groups[i..i + n].concat();
+                    found.push(format!("***-***-{}", &digits[digits.len() - 4..]));
+                    i += n;
+                }
+                None => i += 1,
+            }
+        }
+    }
+    found
+}
+
+/// Whether digit groups spell a 10-digit number, optionally after a leading 1.
+fn is_phone(groups: &[&str]) -> bool {
+    let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+    let digits: String = groups.concat();
+    let number = match lengths.as_slice() {
+        [3, 3, 4] | [10] => digits.as_str(),
+        [1, 3, 3, 4] | [11] if digits.starts_with('1') => &digits[1..],
+        _ => return false,
+    };
+    // Area codes and exchanges never start with 0 or 1
+    let bytes = number.as_bytes();
+    bytes[0] >= b'2' && bytes[3] >= b'2'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_pii_formats_and_false_positives() {
+        let phones = |text: &str| -> Vec<String> {
+            find_pii(text)
+                .into_iter()
+                .filter(|(kind, _)| *kind == PiiKind::Phone)
+                .map(|(_, masked)| masked)
+                .collect()
+        };
+        assert_eq!(phones("Call 512-555-0147."), ["***-***-0147"]);
+        assert_eq!(phones("(512) 555-0148 or 512.555.0149"), ["***-***-0148", "***-***-0149"]);
+        assert_eq!(phones("+1 512 555 0150, 5125550151"), ["***-***-0150", "***-***-0151"]);
+        // Prices, years, square footage, and lot ids are not phone numbers
+        assert!(phones("Offered at $1,250,000. Built 2005, 2400 sq ft, 3 beds, parcel 123-456-7890").is_empty());
+        assert!(phones("Rooms 2005 2006 2007").is_empty());
+
+        assert_eq!(
+            find_pii("Email jane.doe+mls@realty.example.com. Not me@home or @handle"),
+            [(PiiKind::Email, "j***@realty.example.com".to_string())]
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! One client per MLS dataset, for services that front several feeds.
+//!
+//! Vendors such as Bridge serve each MLS as a dataset under a shared base
+//! URL (`.../OData/{dataset}/Property`), and a brokerage with access to
+//! several MLSs usually wants one deployment for all of them. A
+//! [`ClientPool`] holds a client per dataset, looked up by name, so a web
+//! service can route `/mls/{dataset}/...` to the right feed.
+//!
+//! Aggregators whose feeds come from different vendors, each with its own
+//! URL and credentials, describe them in a TOML file instead and load it
+//! with [`ClientPool::from_toml_file`].
+
+use crate::auth::{ClientCredentials, OAuthTokens};
+use crate::client::{HttpClient, HttpClientBuilder, ResoApi};
+use reso_client::{ClientConfig, ResoError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Another name for [`ClientPool`], for code that thinks of it as a
+/// registry of named MLS clients.
+pub type ClientRegistry<C = HttpClient> = ClientPool<C>;
+
+/// Clients keyed by dataset name.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::build_query;
+/// use reso_examples::pool::ClientPool;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // RESO_DATASETS=actris_ref,har
+///     let pool = ClientPool::from_env_with(Ok)?;
+///     for dataset in pool.datasets() {
+///         println!("{}", dataset);
+///     }
+///
+///     let client = pool.get("har").ok_or("unknown dataset")?;
+///     let query = build_query("Property", Some("City eq 'Houston'"), Some(10))?;
+///     let response = reso_examples::execute_query(&*client, &query).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ClientPool<C = HttpClient> {
+    clients: BTreeMap<String, Arc<C>>,
+}
+
+impl<C> Default for ClientPool<C> {
+    fn default() -> Self {
+        Self {
+            clients: BTreeMap::new(),
+        }
+    }
+}
+
+impl<C: ResoApi> ClientPool<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the client for `dataset`.
+    pub fn insert(&mut self, dataset: &str, client: C) {
+        self.clients.insert(dataset.to_string(), Arc::new(client));
+    }
+
+    /// The client for `dataset`, if the pool serves it.
+    pub fn get(&self, dataset: &str) -> Option<Arc<C>> {
+        self.clients.get(dataset).cloned()
+    }
+
+    /// The datasets served, in name order.
+    pub fn datasets(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+}
+
+impl ClientPool<HttpClient> {
+    /// Builds one [`HttpClient`] per dataset from a shared configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Settings shared by every dataset; its `dataset_id` is replaced
+    /// * `datasets` - Dataset names, e.g. `["actris_ref", "har"]`
+    /// * `configure` - Applied to each client's builder, e.g. to add a metrics
+    ///   hook; pass `Ok` to leave the builders as they are
+    pub fn from_config(
+        base: &ClientConfig,
+        datasets: &[&str],
+        configure: impl Fn(HttpClientBuilder) -> Result<HttpClientBuilder, ResoError>,
+    ) -> Result<Self, ResoError> {
+        let mut pool = Self::new();
+        for dataset in datasets {
+            let config = base.clone().with_dataset_id(*dataset);
+            pool.insert(dataset, configure(HttpClient::builder(config))?.build()?);
+        }
+        Ok(pool)
+    }
+
+    /// Builds a client for each dataset in `RESO_DATASETS` (comma-separated).
+    ///
+    /// Every client starts from `ClientConfig::from_env()` plus the TLS and
+    /// DNS settings [`HttpClient::from_env`] reads. Feeds hosted elsewhere
+    /// or with their own credentials override them with
+    /// `RESO_DATASET_<NAME>_BASE_URL` and `RESO_DATASET_<NAME>_TOKEN`, where
+    /// `<NAME>` is the dataset name in upper case with other characters
+    /// replaced by `_`. The pool is empty when `RESO_DATASETS` is unset.
+    pub fn from_env_with(
+        configure: impl Fn(HttpClientBuilder) -> Result<HttpClientBuilder, ResoError>,
+    ) -> Result<Self, ResoError> {
+        let datasets = std::env::var("RESO_DATASETS").unwrap_or_default();
+        let datasets: Vec<&str> = datasets.split(',').map(str::trim).filter(|d| !d.is_empty()).collect();
+        if datasets.is_empty() {
+            return Ok(Self::new());
+        }
+
+        let base = ClientConfig::from_env()?;
+        let mut pool = Self::new();
+        for dataset in datasets {
+            let prefix = format!("RESO_DATASET_{}", env_name(dataset));
+            let mut config = base.clone().with_dataset_id(dataset);
+            if let Ok(base_url) = std::env::var(format!("{}_BASE_URL", prefix)) {
+                config.base_url = base_url;
+            }
+            if let Ok(token) = std::env::var(format!("{}_TOKEN", prefix)) {
+                config.token = token;
+            }
+            let builder = HttpClient::builder(config).tls_from_env()?.dns_from_env()?;
+            pool.insert(dataset, configure(builder)?.build()?);
+        }
+        Ok(pool)
+    }
+}
+
+impl ClientPool<HttpClient> {
+    /// Builds one client per table of a TOML registry file, named by the
+    /// table (see [`ClientSettings`]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use reso_examples::count_records;
+    /// use reso_examples::pool::ClientRegistry;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     // [actris]
+    ///     // base_url = "https://api.bridgedataoutput.com/api/v2/OData"
+    ///     // dataset_id = "actris_ref"
+    ///     // token_env = "ACTRIS_TOKEN"
+    ///     //
+    ///     // [stellar]
+    ///     // base_url = "https://api-trestle.corelogic.com/trestle/odata"
+    ///     // token_url = "https://api-trestle.corelogic.com/trestle/oidc/connect/token"
+    ///     // client_id = "stellar-client"
+    ///     // client_secret_env = "STELLAR_SECRET"
+    ///     // scope = "api"
+    ///     let registry = ClientRegistry::from_toml_file("mls.toml", Ok)?;
+    ///
+    ///     let actris = registry.get("actris").ok_or("actris is not configured")?;
+    ///     println!("{} listings", count_records(&*actris, "Property", None).await?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_toml_file(
+        path: impl AsRef<Path>,
+        configure: impl Fn(HttpClientBuilder) -> Result<HttpClientBuilder, ResoError>,
+    ) -> Result<Self, ResoError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ResoError::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::from_toml_str(&contents, configure)
+    }
+
+    /// Like [`from_toml_file`](Self::from_toml_file), from the file's contents.
+    pub fn from_toml_str(
+        contents: &str,
+        configure: impl Fn(HttpClientBuilder) -> Result<HttpClientBuilder, ResoError>,
+    ) -> Result<Self, ResoError> {
+        Self::from_toml_str_with_env(contents, |name| std::env::var(name).ok(), configure)
+    }
+
+    /// Like [`from_toml_str`](Self::from_toml_str), looking up the `*_env`
+    /// variables with `env`.
+    fn from_toml_str_with_env(
+        contents: &str,
+        env: impl Fn(&str) -> Option<String>,
+        configure: impl Fn(HttpClientBuilder) -> Result<HttpClientBuilder, ResoError>,
+    ) -> Result<Self, ResoError> {
+        let entries: BTreeMap<String, ClientSettings> = toml::from_str(contents)
+            .map_err(|e| ResoError::Config(format!("Invalid client registry: {}", e)))?;
+        let mut pool = Self::new();
+        for (name, settings) in entries {
+            let builder = settings
+                .builder_with_env(&env)
+                .map_err(|e| ResoError::Config(format!("Client '{}': {}", name, config_message(e))))?;
+            pool.insert(&name, configure(builder)?.build()?);
+        }
+        Ok(pool)
+    }
+}
+
+/// One client in a registry file.
+///
+/// Secrets may be written inline (`token`, `client_secret`) or, to keep
+/// them out of the file, named by environment variable (`token_env`,
+/// `client_secret_env`). Setting `token_url` switches the client to the
+/// OAuth2 client-credentials grant.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientSettings {
+    pub base_url: String,
+    pub dataset_id: Option<String>,
+    pub token: Option<String>,
+    pub token_env: Option<String>,
+    pub token_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub client_secret_env: Option<String>,
+    pub scope: Option<String>,
+    /// Request timeout in seconds
+    pub timeout: Option<u64>,
+    pub user_agent: Option<String>,
+    pub proxy: Option<String>,
+}
+
+impl ClientSettings {
+    /// A client builder with these settings applied.
+    pub fn builder(&self) -> Result<HttpClientBuilder, ResoError> {
+        self.builder_with_env(|name| std::env::var(name).ok())
+    }
+
+    /// Like [`builder`](Self::builder), looking up the `*_env` variables
+    /// with `env`.
+    fn builder_with_env(&self, env: impl Fn(&str) -> Option<String>) -> Result<HttpClientBuilder, ResoError> {
+        let secret = |inline: &Option<String>, var: &Option<String>, name: &str| match (inline, var) {
+            (Some(value), _) => Ok(Some(value.clone())),
+            (None, Some(var)) => env(var)
+                .map(Some)
+                .ok_or_else(|| ResoError::Config(format!("{} names {}, which is not set", name, var))),
+            (None, None) => Ok(None),
+        };
+
+        let token = secret(&self.token, &self.token_env, "token_env")?;
+        let mut config = ClientConfig::new(self.base_url.clone(), token.clone().unwrap_or_default());
+        if let Some(dataset_id) = &self.dataset_id {
+            config = config.with_dataset_id(dataset_id.clone());
+        }
+        if let Some(seconds) = self.timeout {
+            config = config.with_timeout(Duration::from_secs(seconds));
+        }
+
+        let mut builder = HttpClient::builder(config);
+        if let Some(token_url) = &self.token_url {
+            let client_id = self
+                .client_id
+                .clone()
+                .ok_or_else(|| ResoError::Config("token_url is set without client_id".to_string()))?;
+            let client_secret = secret(&self.client_secret, &self.client_secret_env, "client_secret_env")?
+                .ok_or_else(|| ResoError::Config("token_url is set without a client secret".to_string()))?;
+            let mut credentials = ClientCredentials::new(token_url.clone(), client_id, client_secret);
+            if let Some(scope) = &self.scope {
+                credentials = credentials.with_scope(scope.clone());
+            }
+            builder = builder.oauth(OAuthTokens::new(credentials)?);
+        } else if token.is_none() {
+            return Err(ResoError::Config(
+                "needs a token, token_env, or token_url".to_string(),
+            ));
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent.clone());
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        Ok(builder)
+    }
+}
+
+fn config_message(error: ResoError) -> String {
+    match error {
+        ResoError::Config(message) => message,
+        other => other.to_string(),
+    }
+}
+
+/// `actris-ref` becomes `ACTRIS_REF`.
+fn env_name(dataset: &str) -> String {
+    dataset
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_pool_routes_to_dataset() {
+        let server = MockServer::start().await;
+        for dataset in ["actris_ref", "har"] {
+            Mock::given(path(format!("/{}/Property", dataset)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [{"ListingKey": dataset}]})))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let pool = ClientPool::from_config(&ClientConfig::new(server.uri(), "token"), &["har", "actris_ref"], Ok)
+            .unwrap();
+        assert_eq!(pool.datasets().collect::<Vec<_>>(), ["actris_ref", "har"]);
+        assert!(pool.get("crmls").is_none());
+
+        let query = crate::build_query("Property", None, Some(1)).unwrap();
+        for dataset in ["actris_ref", "har"] {
+            let client = pool.get(dataset).unwrap();
+            let response = crate::execute_query(&*client, &query).await.unwrap();
+            assert_eq!(response["value"][0]["ListingKey"], dataset);
+        }
+        assert_eq!(env_name("actris-ref"), "ACTRIS_REF");
+    }
+
+    #[tokio::test]
+    async fn test_registry_from_toml() {
+        let server = MockServer::start().await;
+        Mock::given(path("/actris_ref/Property/$count"))
+            .and(header("Authorization", "Bearer actris-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("12"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/$count"))
+            .and(header("Authorization", "Bearer inline-token"))
+            .and(header("User-Agent", "aggregator/1.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("34"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let toml = format!(
+            r#"
+            [actris]
+            base_url = "{uri}"
+            dataset_id = "actris_ref"
+            token_env = "ACTRIS_TOKEN"
+
+            [stellar]
+            base_url = "{uri}"
+            token = "inline-token"
+            timeout = 10
+            user_agent = "aggregator/1.0"
+            "#,
+            uri = server.uri()
+        );
+        let env = |name: &str| (name == "ACTRIS_TOKEN").then(|| "actris-token".to_string());
+        let registry = ClientRegistry::from_toml_str_with_env(&toml, env, Ok).unwrap();
+        assert_eq!(registry.datasets().collect::<Vec<_>>(), ["actris", "stellar"]);
+        let actris = registry.get("actris").unwrap();
+        assert_eq!(crate::count_records(&*actris, "Property", None).await.unwrap(), 12);
+        let stellar = registry.get("stellar").unwrap();
+        assert_eq!(crate::count_records(&*stellar, "Property", None).await.unwrap(), 34);
+
+        let missing = ClientRegistry::from_toml_str_with_env("[har]\nbase_url = \"https://example.com\"\n", env, Ok);
+        match missing {
+            Err(ResoError::Config(message)) => assert!(message.contains("'har'"), "{}", message),
+            other => panic!("unexpected result: {:?}", other.map(|pool| pool.len())),
+        }
+        let misspelled = ClientRegistry::from_toml_str("[har]\nbase_url = \"x\"\ntokn = \"t\"\n", Ok);
+        assert!(matches!(misspelled, Err(ResoError::Config(_))));
+    }
+}
@@ -0,0 +1,164 @@
+//! Provenance stamps on replicated records.
+//!
+//! Warehouses fed by several MLS feeds need to trace each row back to where
+//! it came from. [`ProvenanceSink`] adds a `_provenance` object to every
+//! record before handing it to another sink:
+//!
+//! ```json
+//! "_provenance": {
+//!     "dataset_id": "actris_ref",
+//!     "originating_system": "ACTRIS",
+//!     "fetched_at": "2025-01-02T03:04:05.678Z",
+//!     "query_hash": "9f1c2a7e5d3b4c60"
+//! }
+//! ```
+//!
+//! `originating_system` is copied from the record's `OriginatingSystemName`,
+//! and `query_hash` identifies the resource and filter the record was
+//! replicated with, so rows from differently filtered syncs can be told apart.
+
+use crate::export::{OutputFile, RecordSink, StoredCounts};
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use async_trait::async_trait;
+use chrono::{SecondsFormat, Utc};
+use reso_client::{JsonValue, ResoError};
+use serde_json::json;
+
+/// Field holding the provenance object.
+pub const PROVENANCE_FIELD: &str = "_provenance";
+
+/// Where a set of replicated records came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provenance {
+    pub dataset_id: Option<String>,
+    /// Stable hash of the resource and filter, see [`query_hash`]
+    pub query_hash: String,
+}
+
+impl Provenance {
+    pub fn new(dataset_id: Option<&str>, resource: &str, filter: Option<&str>) -> Self {
+        Self {
+            dataset_id: dataset_id.map(str::to_string),
+            query_hash: query_hash(resource, filter),
+        }
+    }
+
+    /// Adds the provenance object to a record, fetched at `fetched_at`.
+    pub fn stamp(&self, record: &mut JsonValue, fetched_at: &str) {
+        let Some(fields) = record.as_object_mut() else {
+            return;
+        };
+        let originating_system = fields.get("OriginatingSystemName").cloned().unwrap_or(JsonValue::Null);
+        fields.insert(
+            PROVENANCE_FIELD.to_string(),
+            json!({
+                "dataset_id": self.dataset_id,
+                "originating_system": originating_system,
+                "fetched_at": fetched_at,
+                "query_hash": self.query_hash,
+            }),
+        );
+    }
+}
+
+/// A hash of a resource and filter that stays the same across runs and
+/// Rust versions (64-bit FNV-1a, as 16 hex digits).
+pub fn query_hash(resource: &str, filter: Option<&str>) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let input = format!("{}\n{}", resource, filter.unwrap_or_default());
+    for byte in input.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Wraps a sink, stamping [`Provenance`] onto every record it writes.
+///
+/// All records of a batch share the time the batch reached the sink as
+/// their `fetched_at`.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::provenance::{Provenance, ProvenanceSink};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let provenance = Provenance::new(Some("actris_ref"), "Property", None);
+/// let sink = ProvenanceSink::new(JsonlSink::append("property.jsonl")?, provenance);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ProvenanceSink<S> {
+    inner: S,
+    provenance: Provenance,
+}
+
+impl<S: RecordSink> ProvenanceSink<S> {
+    pub fn new(inner: S, provenance: Provenance) -> Self {
+        Self { inner, provenance }
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink> RecordSink for ProvenanceSink<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let fetched_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        let stamped: Vec<JsonValue> = records
+            .iter()
+            .map(|record| {
+                let mut record = record.clone();
+                self.provenance.stamp(&mut record, &fetched_at);
+                record
+            })
+            .collect();
+        self.inner.write_batch(&stamped).await
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.inner.flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.inner.finalize().await
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        self.inner.evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        self.inner.count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.inner.output_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stamp_adds_provenance() {
+        let provenance = Provenance::new(Some("actris_ref"), "Property", Some("City eq 'Austin'"));
+        let mut record = json!({"ListingKey": "A", "OriginatingSystemName": "ACTRIS"});
+        provenance.stamp(&mut record, "2025-01-02T03:04:05.000Z");
+
+        assert_eq!(
+            record[PROVENANCE_FIELD],
+            json!({
+                "dataset_id": "actris_ref",
+                "originating_system": "ACTRIS",
+                "fetched_at": "2025-01-02T03:04:05.000Z",
+                "query_hash": query_hash("Property", Some("City eq 'Austin'")),
+            })
+        );
+        assert_eq!(query_hash("Property", None), query_hash("Property", None));
+        assert_ne!(query_hash("Property", None), query_hash("Member", None));
+        assert_eq!(query_hash("", None).len(), 16);
+    }
+}
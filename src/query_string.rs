@@ -0,0 +1,142 @@
+//! Reading the path and options back out of a rendered query.
+//!
+//! `reso_client::Query` exposes its options only through
+//! `to_odata_string()`, e.g. `Property?$filter=City eq 'Austin'&$top=10`.
+//! [`ODataRequest`] splits such a string into its resource path and query
+//! options. Values are percent-decoded as RFC 3986 specifies, so a `+` (as
+//! in a `+05:00` offset) stays a plus sign instead of becoming a space the
+//! way form decoding would have it.
+
+use reso_client::{Query, ResoError};
+
+/// The resource path and query options of an OData request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ODataRequest {
+    /// Path without a leading slash, e.g. `Property('A')` or `Property/$count`
+    pub path: String,
+    /// Query options in the order given, percent-decoded
+    pub options: Vec<(String, String)>,
+}
+
+impl ODataRequest {
+    /// Splits a request path with an optional query string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::query_string::ODataRequest;
+    ///
+    /// let request = ODataRequest::parse("Property?$filter=ModificationTimestamp gt 2025-01-01T00:00:00+05:00").unwrap();
+    /// assert_eq!(request.resource(), "Property");
+    /// assert_eq!(request.option("$filter"), Some("ModificationTimestamp gt 2025-01-01T00:00:00+05:00"));
+    /// ```
+    pub fn parse(odata: &str) -> Result<Self, ResoError> {
+        let (path, query) = odata.split_once('?').unwrap_or((odata, ""));
+        let path = percent_decode(path.trim_start_matches('/'))?;
+        if path.is_empty() {
+            return Err(ResoError::InvalidQuery(format!("Cannot parse query '{}': no resource", odata)));
+        }
+        let options = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                Ok((percent_decode(name)?, percent_decode(value)?))
+            })
+            .collect::<Result<_, ResoError>>()?;
+        Ok(Self { path, options })
+    }
+
+    /// Splits a query as rendered by `to_odata_string()`.
+    pub fn from_query(query: &Query) -> Result<Self, ResoError> {
+        Self::parse(&query.to_odata_string())
+    }
+
+    /// The resource the path starts with, e.g. `Property` for
+    /// `Property('A')/Media`.
+    pub fn resource(&self) -> &str {
+        self.path.split(['(', '/']).next().unwrap_or(&self.path)
+    }
+
+    /// The value of the first option named `name`.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    /// The path and options as a request string, the inverse of
+    /// [`parse`](Self::parse).
+    pub fn to_odata_string(&self) -> String {
+        let options: Vec<String> = self
+            .options
+            .iter()
+            .map(|(name, value)| format!("{}={}", percent_encode(name), percent_encode(value)))
+            .collect();
+        match options.is_empty() {
+            true => percent_encode(&self.path),
+            false => format!("{}?{}", percent_encode(&self.path), options.join("&")),
+        }
+    }
+}
+
+/// Escapes everything but unreserved characters and the punctuation OData
+/// paths and expressions use, so `&`, `=`, `+`, `%`, and spaces survive
+/// [`percent_decode`].
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~$'(),:/*;@!".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Decodes `%XX` escapes, leaving `+` and malformed escapes as they are.
+pub fn percent_decode(text: &str) -> Result<String, ResoError> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded)
+        .map_err(|_| ResoError::InvalidQuery(format!("Cannot parse query '{}': invalid UTF-8 escape", text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_plus_and_decodes_escapes() {
+        let request = ODataRequest::parse("/Property('A%2FB')/Media?$filter=Name eq 'C++'&$select=MediaURL,Order&$count").unwrap();
+        assert_eq!(request.path, "Property('A/B')/Media");
+        assert_eq!(request.resource(), "Property");
+        assert_eq!(request.option("$filter"), Some("Name eq 'C++'"));
+        assert_eq!(request.option("$select"), Some("MediaURL,Order"));
+        assert_eq!(request.option("$count"), Some(""));
+        assert_eq!(request.option("$top"), None);
+
+        assert_eq!(ODataRequest::parse(&request.to_odata_string()).unwrap(), request);
+        assert_eq!(percent_encode("City eq 'A&B+C'"), "City%20eq%20'A%26B%2BC'");
+
+        assert_eq!(percent_decode("100%25 %2b5%").unwrap(), "100% +5%");
+        assert!(matches!(percent_decode("%FF"), Err(ResoError::InvalidQuery(_))));
+        assert!(matches!(ODataRequest::parse("?$top=1"), Err(ResoError::InvalidQuery(_))));
+    }
+}
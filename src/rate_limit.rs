@@ -0,0 +1,393 @@
+//! Client-side request budgets that keep within vendor quotas.
+//!
+//! Vendors such as Bridge and Trestle throttle aggressively, and clients
+//! that keep hammering after a 429 risk having their token suspended. A
+//! [`RateLimitedClient`] wraps any [`ResoApi`] and spaces its requests to a
+//! requests-per-second budget with a cap on requests in flight. When the
+//! server answers 429 it pauses every request sharing the [`RateLimiter`]
+//! and then repeats the throttled one.
+//!
+//! The pause honors the server's `Retry-After` header, which
+//! [`HttpClient`] errors carry (see [`retry_after`]); clients that do not
+//! expose headers, such as `ResoClient`, pause for
+//! [`RateLimit::default_pause`]. Adding the limiter to an `HttpClient` as a
+//! response hook also pauses after a 503 with `Retry-After`.
+//!
+//! The `reso` CLI paces its requests with [`RateLimit::from_env`].
+//!
+//! [`HttpClient`]: crate::client::HttpClient
+
+use crate::client::{ResoApi, ResponseHook, ResponseParts};
+use crate::retry::retry_after;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::RETRY_AFTER;
+use reso_client::{JsonValue, Query, ReplicationQuery, ReplicationResponse, ResoError};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+
+/// Budget enforced by a [`RateLimiter`].
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    /// Requests started per second, unlimited if `None`
+    pub requests_per_second: Option<f64>,
+    /// Requests allowed in flight at once
+    pub max_concurrent: usize,
+    /// Pause after a 429 that did not come with a `Retry-After` header
+    pub default_pause: Duration,
+    /// Times a throttled request is repeated before its 429 is returned
+    pub max_throttled_retries: u32,
+}
+
+impl Default for RateLimit {
+    /// 2 requests per second, 2 at a time, pausing 60s after a 429
+    /// without `Retry-After`.
+    fn default() -> Self {
+        Self {
+            requests_per_second: Some(2.0),
+            max_concurrent: 2,
+            default_pause: Duration::from_secs(60),
+            max_throttled_retries: 3,
+        }
+    }
+}
+
+impl RateLimit {
+    /// The default budget, with the request rate and concurrency
+    /// overridden by `RESO_REQUESTS_PER_SECOND` (0 for unlimited) and
+    /// `RESO_MAX_CONCURRENT` when set.
+    pub fn from_env() -> Result<Self, ResoError> {
+        let mut limit = Self::default();
+        if let Some(rate) = env_number::<f64>("RESO_REQUESTS_PER_SECOND")? {
+            limit.requests_per_second = Some(rate).filter(|rate| *rate > 0.0);
+        }
+        if let Some(max) = env_number::<usize>("RESO_MAX_CONCURRENT")? {
+            limit.max_concurrent = max;
+        }
+        Ok(limit)
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Result<Option<T>, ResoError> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| ResoError::Config(format!("Invalid {}: '{}'", name, value.trim()))),
+        _ => Ok(None),
+    }
+}
+
+/// Shared request budget and throttling state.
+///
+/// Clones share the same budget, so one clone can be added to an
+/// `HttpClient` as a response hook while another paces its requests.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    permits: Arc<Semaphore>,
+    pacing: Arc<Mutex<Pacing>>,
+}
+
+#[derive(Debug)]
+struct Pacing {
+    /// Earliest start of the next request
+    next_start: Instant,
+    /// End of a pause requested by the server
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(limit.max_concurrent.max(1))),
+            pacing: Arc::new(Mutex::new(Pacing {
+                next_start: Instant::now(),
+                paused_until: None,
+            })),
+            limit,
+        }
+    }
+
+    /// Holds back every request for at least `wait`. Shorter pauses than
+    /// one already in effect are ignored.
+    pub fn pause_for(&self, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut pacing = self.pacing.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(pacing.paused_until, Some(current) if current >= until) {
+            pacing.paused_until = Some(until);
+        }
+    }
+
+    /// Time left in the current pause, zero when requests may proceed.
+    pub fn remaining_pause(&self) -> Duration {
+        let pacing = self.pacing.lock().unwrap_or_else(|e| e.into_inner());
+        pacing
+            .paused_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Runs `request` within the budget, pausing and repeating it when the
+    /// server answers 429.
+    ///
+    /// `request` is called once per attempt, so it must build a fresh
+    /// future each time.
+    pub async fn run<T, F, Fut>(&self, mut request: F) -> Result<T, ResoError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ResoError>>,
+    {
+        let mut throttled = 0;
+        loop {
+            let result = {
+                let _permit = self.permits.acquire().await.expect("rate limiter semaphore is never closed");
+                self.wait_turn().await;
+                request().await
+            };
+            match result {
+                Err(e @ ResoError::RateLimited { .. }) if throttled < self.limit.max_throttled_retries => {
+                    match retry_after(&e) {
+                        Some(wait) => self.pause_for(wait),
+                        // A Retry-After seen by the response hook may have set the pause already
+                        None if self.remaining_pause().is_zero() => self.pause_for(self.limit.default_pause),
+                        None => {}
+                    }
+                    throttled += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sleeps until the current pause is over and this request's slot in
+    /// the per-second budget has come.
+    async fn wait_turn(&self) {
+        loop {
+            let wait = {
+                let mut pacing = self.pacing.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let ready = match pacing.paused_until {
+                    Some(until) if until > pacing.next_start => until,
+                    _ => pacing.next_start,
+                };
+                if ready <= now {
+                    pacing.paused_until = None;
+                    if let Some(rate) = self.limit.requests_per_second.filter(|rate| *rate > 0.0) {
+                        pacing.next_start = now + Duration::from_secs_f64(1.0 / rate);
+                    }
+                    return;
+                }
+                ready - now
+            };
+            // Re-checked afterwards, since a 429 elsewhere may extend the pause
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ResponseHook for RateLimiter {
+    /// Pauses for the `Retry-After` of 429 and 503 responses.
+    async fn on_response(&self, response: &ResponseParts) {
+        if !matches!(response.status.as_u16(), 429 | 503) {
+            return;
+        }
+        let wait = response
+            .headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, Utc::now()));
+        if let Some(wait) = wait {
+            self.pause_for(wait);
+        }
+    }
+}
+
+/// Reads a `Retry-After` value, either a number of seconds or an HTTP date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// A client whose requests are paced by a [`RateLimiter`].
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_client::ClientConfig;
+/// use reso_examples::client::HttpClient;
+/// use reso_examples::count_records;
+/// use reso_examples::rate_limit::{RateLimit, RateLimitedClient, RateLimiter};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let limiter = RateLimiter::new(RateLimit {
+///         requests_per_second: Some(1.0),
+///         ..Default::default()
+///     });
+///     // The hook lets 429 pauses follow the server's Retry-After
+///     let http = HttpClient::builder(ClientConfig::from_env()?)
+///         .response_hook(limiter.clone())
+///         .build()?;
+///     let client = RateLimitedClient::with_limiter(http, limiter);
+///
+///     let count = count_records(&client, "Property", None).await?;
+///     println!("{} listings", count);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimitedClient<C> {
+    inner: C,
+    limiter: RateLimiter,
+}
+
+impl<C: ResoApi> RateLimitedClient<C> {
+    /// Wraps `inner` with a budget of its own.
+    pub fn new(inner: C, limit: RateLimit) -> Self {
+        Self::with_limiter(inner, RateLimiter::new(limit))
+    }
+
+    /// Wraps `inner` with a budget that may be shared with other clients
+    /// of the same server.
+    pub fn with_limiter(inner: C, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<C: ResoApi> ResoApi for RateLimitedClient<C> {
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    async fn execute(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        self.limiter.run(|| self.inner.execute(query)).await
+    }
+
+    async fn execute_by_key(&self, query: &Query) -> Result<JsonValue, ResoError> {
+        self.limiter.run(|| self.inner.execute_by_key(query)).await
+    }
+
+    async fn execute_count(&self, query: &Query) -> Result<u64, ResoError> {
+        self.limiter.run(|| self.inner.execute_count(query)).await
+    }
+
+    async fn fetch_metadata(&self) -> Result<String, ResoError> {
+        self.limiter.run(|| self.inner.fetch_metadata()).await
+    }
+
+    async fn execute_replication(
+        &self,
+        query: &ReplicationQuery,
+    ) -> Result<ReplicationResponse, ResoError> {
+        self.limiter.run(|| self.inner.execute_replication(query)).await
+    }
+
+    async fn execute_next_link(&self, next_link: &str) -> Result<ReplicationResponse, ResoError> {
+        self.limiter.run(|| self.inner.execute_next_link(next_link)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::note_retry_after;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 27, 30).unwrap();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    /// Answers counts after `throttled` 429s asking to retry in 1s, as
+    /// `HttpClient` reports them.
+    struct Throttled(Mutex<u32>);
+
+    #[async_trait]
+    impl ResoApi for Throttled {
+        fn base_url(&self) -> &str {
+            "https://api.example.com"
+        }
+
+        async fn execute(&self, _query: &Query) -> Result<JsonValue, ResoError> {
+            Err(ResoError::Config("not used in this test".into()))
+        }
+
+        async fn execute_by_key(&self, _query: &Query) -> Result<JsonValue, ResoError> {
+            Err(ResoError::Config("not used in this test".into()))
+        }
+
+        async fn execute_count(&self, _query: &Query) -> Result<u64, ResoError> {
+            let mut throttled = self.0.lock().unwrap();
+            if *throttled == 0 {
+                return Ok(42);
+            }
+            *throttled -= 1;
+            let error = ResoError::RateLimited {
+                message: "Too many requests".into(),
+                status_code: 429,
+            };
+            Err(note_retry_after(error, Duration::from_secs(1)))
+        }
+
+        async fn fetch_metadata(&self) -> Result<String, ResoError> {
+            Err(ResoError::Config("not used in this test".into()))
+        }
+
+        async fn execute_replication(&self, _query: &ReplicationQuery) -> Result<ReplicationResponse, ResoError> {
+            Err(ResoError::Config("not used in this test".into()))
+        }
+
+        async fn execute_next_link(&self, _next_link: &str) -> Result<ReplicationResponse, ResoError> {
+            Err(ResoError::Config("not used in this test".into()))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pauses_for_retry_after_and_repeats_request() {
+        // The long default pause would show if Retry-After were ignored
+        let client = RateLimitedClient::new(
+            Throttled(Mutex::new(1)),
+            RateLimit {
+                requests_per_second: Some(20.0),
+                max_concurrent: 1,
+                default_pause: Duration::from_secs(30),
+                max_throttled_retries: 1,
+            },
+        );
+
+        let started = Instant::now();
+        assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 42);
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_secs(2), "{:?}", elapsed);
+
+        // The next request waits only for its per-second slot
+        let started = Instant::now();
+        assert_eq!(crate::count_records(&client, "Property", None).await.unwrap(), 42);
+        assert!(started.elapsed() <= Duration::from_millis(60), "{:?}", started.elapsed());
+    }
+}
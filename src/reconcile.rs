@@ -0,0 +1,175 @@
+//! Checking a replicated copy against the server's counts.
+//!
+//! A replication run that ends without errors can still leave gaps: records
+//! that changed while the run was paging, vendor-side replication bugs, or a
+//! sink that silently dropped rows. Comparing the number of stored records
+//! with the server's `$count` for the same filter, overall and per value of
+//! a field such as `City`, catches those gaps right after the run.
+
+use crate::aggregate::{count_by, GroupCount};
+use crate::client::ResoApi;
+use crate::count_records;
+use crate::export::StoredCounts;
+use reso_client::{JsonValue, ResoError};
+
+/// A group whose stored count differs from the server's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupDiscrepancy {
+    /// The field value, `Null` for records without one
+    pub value: JsonValue,
+    pub server: u64,
+    pub stored: u64,
+}
+
+/// The result of [`reconcile_counts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconciliation {
+    pub resource: String,
+    pub server_total: u64,
+    pub stored_total: u64,
+    /// Field the sub-counts were grouped by, if any
+    pub group_by: Option<String>,
+    /// Groups whose counts differ, largest difference first
+    pub discrepancies: Vec<GroupDiscrepancy>,
+}
+
+impl Reconciliation {
+    /// Returns true if every count matched.
+    pub fn is_consistent(&self) -> bool {
+        self.server_total == self.stored_total && self.discrepancies.is_empty()
+    }
+}
+
+impl std::fmt::Display for Reconciliation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_consistent() {
+            return write!(
+                f,
+                "{}: {} records stored, matching the server",
+                self.resource, self.stored_total
+            );
+        }
+        write!(
+            f,
+            "{}: {} records stored, server reports {} ({:+})",
+            self.resource,
+            self.stored_total,
+            self.server_total,
+            self.stored_total as i64 - self.server_total as i64
+        )?;
+        for d in &self.discrepancies {
+            write!(
+                f,
+                "\n  {} = {}: {} stored, {} on server",
+                self.group_by.as_deref().unwrap_or("group"),
+                d.value,
+                d.stored,
+                d.server
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares stored record counts with the server's counts for the same filter.
+///
+/// Pass the filter the data was replicated with, without the incremental
+/// `ModificationTimestamp` window, since the store holds the results of
+/// every run. Stored counts come from [`RecordSink::count_stored`](crate::export::RecordSink::count_stored).
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression
+/// * `stored` - Counts from the local store, grouped by `group_by` if given
+/// * `group_by` - Field to compare sub-counts for, e.g. "City"
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::export::{RecordSink, SqliteSink};
+/// use reso_examples::reconcile::reconcile_counts;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let mut sink = SqliteSink::open("mls.sqlite", "Property", "ListingKey")?;
+///
+///     if let Some(stored) = sink.count_stored(Some("City")).await? {
+///         let report = reconcile_counts(&client, "Property", None, &stored, Some("City")).await?;
+///         println!("{}", report);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn reconcile_counts(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    stored: &StoredCounts,
+    group_by: Option<&str>,
+) -> Result<Reconciliation, ResoError> {
+    let server_total = count_records(client, resource, filter).await?;
+    let discrepancies = match group_by {
+        Some(field) => compare_groups(&count_by(client, resource, filter, field).await?, &stored.groups),
+        None => Vec::new(),
+    };
+
+    Ok(Reconciliation {
+        resource: resource.to_string(),
+        server_total,
+        stored_total: stored.total,
+        group_by: group_by.map(str::to_string),
+        discrepancies,
+    })
+}
+
+fn compare_groups(server: &[GroupCount], stored: &[GroupCount]) -> Vec<GroupDiscrepancy> {
+    let count_of = |groups: &[GroupCount], value: &JsonValue| {
+        groups
+            .iter()
+            .find(|g| &g.value == value)
+            .map_or(0, |g| g.count)
+    };
+
+    let mut discrepancies: Vec<GroupDiscrepancy> = server
+        .iter()
+        .chain(stored.iter().filter(|g| !server.iter().any(|s| s.value == g.value)))
+        .map(|g| GroupDiscrepancy {
+            value: g.value.clone(),
+            server: count_of(server, &g.value),
+            stored: count_of(stored, &g.value),
+        })
+        .filter(|d| d.server != d.stored)
+        .collect();
+    discrepancies.sort_by_key(|d| std::cmp::Reverse(d.server.abs_diff(d.stored)));
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn group(value: JsonValue, count: u64) -> GroupCount {
+        GroupCount { value, count }
+    }
+
+    #[test]
+    fn test_compare_groups_reports_mismatches_both_ways() {
+        let server = [group(json!("Austin"), 10), group(json!("Dallas"), 5), group(json!("Waco"), 1)];
+        let stored = [group(json!("Austin"), 10), group(json!("Dallas"), 2), group(JsonValue::Null, 1)];
+
+        let discrepancies = compare_groups(&server, &stored);
+        assert_eq!(
+            discrepancies,
+            vec![
+                GroupDiscrepancy { value: json!("Dallas"), server: 5, stored: 2 },
+                GroupDiscrepancy { value: json!("Waco"), server: 1, stored: 0 },
+                GroupDiscrepancy { value: JsonValue::Null, server: 0, stored: 1 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Field-level redaction for public-facing exports.
+//!
+//! The same sync often feeds an internal warehouse and a public website.
+//! A [`Redaction`] drops or pseudonymizes sensitive fields — owner names,
+//! private remarks, agent phone numbers — so a compliant export can be
+//! written from the same run by wrapping its sink in a [`RedactingSink`].
+//!
+//! Hashed fields are replaced with an HMAC-SHA256 of their value under a
+//! secret key. The same value always hashes the same way, so redacted
+//! exports can still be joined and deduplicated on those fields, while
+//! nobody without the key can guess values by hashing candidates.
+
+use crate::export::{OutputFile, RecordSink, StoredCounts};
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use async_trait::async_trait;
+use hmac::{Hmac, KeyInit, Mac};
+use reso_client::{JsonValue, ResoError};
+use sha2::Sha256;
+
+/// Data Dictionary fields commonly kept out of public exports.
+pub const SENSITIVE_FIELDS: &[&str] = &[
+    "OwnerName",
+    "OwnerPhone",
+    "PrivateRemarks",
+    "ShowingInstructions",
+    "ShowingContactPhone",
+    "LockBoxLocation",
+    "ListAgentDirectPhone",
+    "ListAgentMobilePhone",
+    "ListAgentEmail",
+    "CoListAgentDirectPhone",
+    "CoListAgentMobilePhone",
+    "CoListAgentEmail",
+];
+
+/// What happens to a redacted field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactAction {
+    /// Remove the field
+    Drop,
+    /// Replace a non-null value with its keyed hash as lowercase hex
+    Hash,
+}
+
+/// A set of fields to drop or hash.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::redact::Redaction;
+/// use serde_json::json;
+///
+/// let redaction = Redaction::new(b"secret key")
+///     .drop("PrivateRemarks")
+///     .hash("OwnerName");
+///
+/// let mut record = json!({"ListingKey": "A", "PrivateRemarks": "Gate code 1234", "OwnerName": "J. Smith"});
+/// redaction.apply(&mut record);
+/// assert!(record.get("PrivateRemarks").is_none());
+/// assert_eq!(record["OwnerName"].as_str().unwrap().len(), 64);
+/// ```
+#[derive(Clone)]
+pub struct Redaction {
+    rules: Vec<(String, RedactAction)>,
+    key: Vec<u8>,
+}
+
+impl Redaction {
+    /// Starts an empty redaction; `hash_key` keys the hashes of hashed fields.
+    pub fn new(hash_key: &[u8]) -> Self {
+        Self {
+            rules: Vec::new(),
+            key: hash_key.to_vec(),
+        }
+    }
+
+    /// Drops every field in [`SENSITIVE_FIELDS`].
+    pub fn sensitive_fields(hash_key: &[u8]) -> Self {
+        SENSITIVE_FIELDS
+            .iter()
+            .fold(Self::new(hash_key), |redaction, field| redaction.drop(field))
+    }
+
+    /// Removes `field` from every record.
+    pub fn drop(self, field: &str) -> Self {
+        self.rule(field, RedactAction::Drop)
+    }
+
+    /// Replaces `field` with its keyed hash.
+    pub fn hash(self, field: &str) -> Self {
+        self.rule(field, RedactAction::Hash)
+    }
+
+    /// Sets the action for a field, replacing any earlier one.
+    pub fn rule(mut self, field: &str, action: RedactAction) -> Self {
+        self.rules.retain(|(name, _)| name != field);
+        self.rules.push((field.to_string(), action));
+        self
+    }
+
+    /// Whether no fields are redacted.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Redacts a record in place.
+    pub fn apply(&self, record: &mut JsonValue) {
+        let Some(fields) = record.as_object_mut() else {
+            return;
+        };
+        for (field, action) in &self.rules {
+            match action {
+                RedactAction::Drop => {
+                    fields.remove(field);
+                }
+                RedactAction::Hash => {
+                    if let Some(value) = fields.get_mut(field).filter(|v| !v.is_null()) {
+                        *value = JsonValue::String(self.hash_value(value));
+                    }
+                }
+            }
+        }
+    }
+
+    fn hash_value(&self, value: &JsonValue) -> String {
+        let text = match value {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(text.as_bytes());
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Redaction {
+    /// Lists the rules without revealing the key.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Redaction").field("rules", &self.rules).finish_non_exhaustive()
+    }
+}
+
+/// Wraps a sink, redacting every record before it is written.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::redact::{RedactingSink, Redaction};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let key = std::env::var("RESO_REDACTION_KEY")?;
+/// let redaction = Redaction::sensitive_fields(key.as_bytes()).hash("OwnerName");
+/// let sink = RedactingSink::new(JsonlSink::append("public/property.jsonl")?, redaction);
+/// # Ok(())
+/// # }
+/// ```
+pub struct RedactingSink<S> {
+    inner: S,
+    redaction: Redaction,
+}
+
+impl<S: RecordSink> RedactingSink<S> {
+    pub fn new(inner: S, redaction: Redaction) -> Self {
+        Self { inner, redaction }
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink> RecordSink for RedactingSink<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        let redacted: Vec<JsonValue> = records
+            .iter()
+            .map(|record| {
+                let mut record = record.clone();
+                self.redaction.apply(&mut record);
+                record
+            })
+            .collect();
+        self.inner.write_batch(&redacted).await
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.inner.flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.inner.finalize().await
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        self.inner.evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        self.inner.count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.inner.output_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hash_is_keyed_and_stable() {
+        let redaction = Redaction::new(b"key").hash("OwnerName").hash("OwnerPhone");
+        let mut a = json!({"OwnerName": "J. Smith", "OwnerPhone": null});
+        let mut b = json!({"OwnerName": "J. Smith"});
+        redaction.apply(&mut a);
+        redaction.apply(&mut b);
+
+        assert_eq!(a["OwnerName"], b["OwnerName"]);
+        assert_ne!(a["OwnerName"], "J. Smith");
+        assert!(a["OwnerPhone"].is_null());
+
+        let mut c = json!({"OwnerName": "J. Smith"});
+        Redaction::new(b"other key").hash("OwnerName").apply(&mut c);
+        assert_ne!(a["OwnerName"], c["OwnerName"]);
+    }
+}
@@ -0,0 +1,257 @@
+//! Caching query results for interactive services.
+//!
+//! A search page sends the same handful of queries over and over: the
+//! biggest cities, the usual price bands. A [`ResultCache`] keeps recent
+//! responses keyed by their OData query, and [`run_cache_warmer`] refreshes
+//! a list of popular queries in the background so visitors rarely wait on
+//! the feed. The warmer spaces its requests out and backs off when the
+//! server rate limits it, so warming never competes with live traffic for
+//! the vendor's quota.
+
+use crate::cancel::CancellationToken;
+use crate::client::ResoApi;
+use reso_client::{JsonValue, Query, ResoError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Recent query responses, each kept for a fixed time.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::result_cache::{execute_cached, ResultCache};
+/// use reso_examples::{build_query, create_client};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let cache = ResultCache::new(Duration::from_secs(600), 1000);
+///
+///     let query = build_query("Property", Some("City eq 'Austin'"), Some(10))?;
+///     let first = execute_cached(&client, &cache, &query).await?;
+///     // Served from the cache for the next ten minutes
+///     let second = execute_cached(&client, &cache, &query).await?;
+///     assert_eq!(first, second);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ResultCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, JsonValue)>>,
+}
+
+impl ResultCache {
+    /// Keeps up to `max_entries` responses for `ttl` each; when full, the
+    /// oldest response makes room for a new one.
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries: max_entries.max(1),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached response to `query`, unless it has expired.
+    pub fn get(&self, query: &Query) -> Option<JsonValue> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(&query.to_odata_string())
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    /// Stores the response to `query`.
+    pub fn insert(&self, query: &Query, response: JsonValue) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let key = query.to_odata_string();
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            entries.retain(|_, (stored, _)| stored.elapsed() < self.ttl);
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (stored, _))| *stored)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (Instant::now(), response));
+    }
+
+    /// Number of responses held, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Executes a query, answering from the cache when it holds a fresh response.
+pub async fn execute_cached(
+    client: &impl ResoApi,
+    cache: &ResultCache,
+    query: &Query,
+) -> Result<JsonValue, ResoError> {
+    if let Some(response) = cache.get(query) {
+        return Ok(response);
+    }
+    let response = crate::execute_query(client, query).await?;
+    cache.insert(query, response.clone());
+    Ok(response)
+}
+
+/// The outcome of one [`warm_cache`] pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarmStats {
+    /// Queries whose responses were refreshed
+    pub refreshed: usize,
+    /// Queries that failed for reasons other than rate limiting
+    pub failed: usize,
+    /// The server rate limited the pass, which stopped early
+    pub rate_limited: bool,
+}
+
+/// Re-runs each query and stores its response, waiting `spacing` between
+/// requests.
+///
+/// Stops at the first rate-limit response, leaving the remaining entries to
+/// the next pass; other failures are counted and skipped.
+pub async fn warm_cache(
+    client: &impl ResoApi,
+    cache: &ResultCache,
+    queries: &[Query],
+    spacing: Duration,
+) -> WarmStats {
+    let mut stats = WarmStats::default();
+    for (i, query) in queries.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(spacing).await;
+        }
+        match crate::execute_query(client, query).await {
+            Ok(response) => {
+                cache.insert(query, response);
+                stats.refreshed += 1;
+            }
+            Err(ResoError::RateLimited { .. }) => {
+                stats.rate_limited = true;
+                break;
+            }
+            Err(_) => stats.failed += 1,
+        }
+    }
+    stats
+}
+
+/// Warms the cache every `interval` until `token` is cancelled.
+///
+/// Choose an `interval` shorter than the cache's TTL so popular entries are
+/// replaced before they expire. After a rate-limited pass the next one
+/// waits twice as long, up to eight intervals.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::cancel::CancellationToken;
+/// use reso_examples::result_cache::{run_cache_warmer, ResultCache};
+/// use reso_examples::{build_query, create_client};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = Arc::new(create_client()?);
+///     let cache = Arc::new(ResultCache::new(Duration::from_secs(600), 1000));
+///     let popular = vec![
+///         build_query("Property", Some("City eq 'Austin'"), Some(10))?,
+///         build_query("Property", Some("City eq 'Dallas'"), Some(10))?,
+///     ];
+///
+///     let token = CancellationToken::new();
+///     let (client, cache, warmer) = (client.clone(), cache.clone(), token.clone());
+///     tokio::spawn(async move {
+///         let every = Duration::from_secs(300);
+///         let spacing = Duration::from_secs(2);
+///         run_cache_warmer(&*client, &cache, &popular, every, spacing, &warmer).await;
+///     });
+///     Ok(())
+/// }
+/// ```
+pub async fn run_cache_warmer(
+    client: &impl ResoApi,
+    cache: &ResultCache,
+    queries: &[Query],
+    interval: Duration,
+    spacing: Duration,
+    token: &CancellationToken,
+) {
+    let mut wait = interval;
+    loop {
+        let stats = tokio::select! {
+            _ = token.cancelled() => return,
+            stats = warm_cache(client, cache, queries, spacing) => stats,
+        };
+        wait = if stats.rate_limited { (wait * 2).min(interval * 8) } else { interval };
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = tokio::time::sleep(wait) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reso_client::{ClientConfig, QueryBuilder};
+    use serde_json::json;
+    use wiremock::matchers::{path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_warm_cache_stops_when_rate_limited() {
+        let server = MockServer::start().await;
+        Mock::given(path("/Property"))
+            .and(query_param("$filter", "City eq 'Austin'"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [{"ListingKey": "A"}]})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property"))
+            .and(query_param("$filter", "City eq 'Dallas'"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&server)
+            .await;
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let query = |city: &str| {
+            QueryBuilder::new("Property")
+                .filter(format!("City eq '{}'", city))
+                .build()
+                .unwrap()
+        };
+        let queries = [query("Austin"), query("Dallas"), query("Houston")];
+        let cache = ResultCache::new(Duration::from_secs(60), 10);
+
+        let stats = warm_cache(&client, &cache, &queries, Duration::ZERO).await;
+        assert_eq!(
+            stats,
+            WarmStats {
+                refreshed: 1,
+                failed: 0,
+                rate_limited: true
+            }
+        );
+        assert_eq!(cache.len(), 1);
+        // Served from the cache: the Austin mock expects a single request
+        let cached = execute_cached(&client, &cache, &queries[0]).await.unwrap();
+        assert_eq!(cached["value"][0]["ListingKey"], "A");
+    }
+}
@@ -0,0 +1,249 @@
+//! Retrying requests that fail for transient reasons.
+//!
+//! Long replication runs and scheduled jobs issue thousands of requests, and
+//! a single 503 or dropped connection should not end them. [`with_retry`]
+//! repeats a request with exponential backoff and jitter while its errors
+//! are [retryable](is_retryable); [`execute_query_with_retry`](crate::execute_query_with_retry)
+//! applies it to a query.
+//!
+//! Errors that another attempt cannot fix (bad credentials, invalid
+//! queries, unparseable responses, cancellation) are returned immediately.
+//!
+//! When a 429 or 503 from [`HttpClient`](crate::client::HttpClient) carries
+//! a `Retry-After` header, the retry waits that long instead of backing off
+//! (see [`retry_after`]).
+
+use crate::cancel::is_cancelled;
+use reso_client::ResoError;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How often and how long to wait before repeating a failed request.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub base_delay: Duration,
+    /// Upper bound on a single delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Up to 5 retries, waiting about 0.5s, 1s, 2s, 4s, and 8s.
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 0).
+    ///
+    /// The exponential delay is capped at `max_delay`, and a random half of
+    /// it is kept ("equal jitter") so clients that failed together do not
+    /// retry together.
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let half = exponential / 2;
+        let jitter = RandomState::new().build_hasher().finish() % (half.as_millis() as u64 + 1);
+        half + Duration::from_millis(jitter)
+    }
+
+    /// Delay before retry number `retry` of a request that failed with
+    /// `error`: the server's [`retry_after`] when it sent one, otherwise
+    /// [`delay_for`](Self::delay_for).
+    ///
+    /// Returns `None` when the server asks for a longer wait than
+    /// `max_delay`; the error should be returned rather than retried.
+    pub fn delay_after(&self, retry: u32, error: &ResoError) -> Option<Duration> {
+        match retry_after(error) {
+            Some(wait) if wait > self.max_delay => None,
+            Some(wait) => Some(wait),
+            None => Some(self.delay_for(retry)),
+        }
+    }
+}
+
+/// Marks the wait a server asked for at the end of an error message.
+const RETRY_AFTER_NOTE: &str = " (retry after ";
+
+/// How long the server asked to wait before retrying a 429 or 503 response,
+/// from its `Retry-After` header.
+///
+/// Only errors from [`HttpClient`](crate::client::HttpClient) carry it;
+/// `ResoClient` does not expose response headers.
+pub fn retry_after(error: &ResoError) -> Option<Duration> {
+    match error {
+        ResoError::RateLimited { message, .. } | ResoError::ServerError { message, .. } => {
+            split_retry_after(message).map(|(_, wait)| wait)
+        }
+        _ => None,
+    }
+}
+
+/// Splits the note added by [`note_retry_after`] off an error message.
+pub(crate) fn split_retry_after(message: &str) -> Option<(&str, Duration)> {
+    let (text, seconds) = message.strip_suffix("s)")?.rsplit_once(RETRY_AFTER_NOTE)?;
+    Some((text, Duration::from_secs(seconds.parse().ok()?)))
+}
+
+/// Notes `wait`, rounded up to whole seconds, on a 429 or 503 error.
+pub(crate) fn note_retry_after(error: ResoError, wait: Duration) -> ResoError {
+    let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    let note = |message: String| format!("{}{}{}s)", message, RETRY_AFTER_NOTE, seconds);
+    match error {
+        ResoError::RateLimited { message, status_code } => ResoError::RateLimited {
+            message: note(message),
+            status_code,
+        },
+        ResoError::ServerError { message, status_code } if status_code == 503 => ResoError::ServerError {
+            message: note(message),
+            status_code,
+        },
+        other => other,
+    }
+}
+
+/// Returns true if a request that failed with `error` may succeed when
+/// repeated.
+///
+/// Network errors (timeouts, refused or reset connections), rate limiting,
+/// 408 Request Timeout, and server errors other than 501 Not Implemented are
+/// retryable. Configuration, authentication, not-found, query, and parse
+/// errors are fatal, as are cancelled requests.
+pub fn is_retryable(error: &ResoError) -> bool {
+    match error {
+        ResoError::Network(_) => !is_cancelled(error),
+        ResoError::RateLimited { .. } => true,
+        ResoError::ServerError { status_code, .. } => *status_code != 501,
+        ResoError::ODataError { status_code, .. } => *status_code == 408,
+        ResoError::Config(_)
+        | ResoError::Unauthorized { .. }
+        | ResoError::Forbidden { .. }
+        | ResoError::NotFound { .. }
+        | ResoError::InvalidQuery(_)
+        | ResoError::Parse(_) => false,
+    }
+}
+
+/// Runs `request` until it succeeds, fails with a fatal error, or
+/// `policy.max_retries` retries have failed; the last error is returned.
+///
+/// Each retry waits [`RetryPolicy::delay_after`]; a `Retry-After` longer
+/// than `max_delay` ends the retries early.
+///
+/// `request` is called once per attempt, so it must build a fresh future
+/// each time.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::retry::{with_retry, RetryPolicy};
+/// use reso_examples::{build_replication_query, create_client, execute_replication_query};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let query = build_replication_query("Property", None)?;
+///
+///     let response = with_retry(&RetryPolicy::default(), || {
+///         execute_replication_query(&client, &query)
+///     })
+///     .await?;
+///     println!("Retrieved {} records", response.records.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn with_retry<T, F, Fut>(policy: &RetryPolicy, mut request: F) -> Result<T, ResoError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ResoError>>,
+{
+    let mut retry = 0;
+    loop {
+        match request().await {
+            Err(e) if retry < policy.max_retries && is_retryable(&e) => {
+                let Some(delay) = policy.delay_after(retry, &e) else {
+                    return Err(e);
+                };
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_errors() {
+        let server = |status_code| ResoError::ServerError {
+            message: "failed".into(),
+            status_code,
+        };
+        assert!(is_retryable(&ResoError::Network("Request timed out".into())));
+        assert!(is_retryable(&server(503)));
+        assert!(!is_retryable(&server(501)));
+        assert!(!is_retryable(&ResoError::Unauthorized {
+            message: "bad token".into(),
+            status_code: 401,
+        }));
+        assert!(!is_retryable(&ResoError::InvalidQuery("bad filter".into())));
+    }
+
+    #[test]
+    fn test_delay_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        for (retry, full) in [(0, 100), (3, 800), (4, 1000), (9, 1000)] {
+            let delay = policy.delay_for(retry).as_millis();
+            assert!(delay >= full / 2 && delay <= full, "retry {}: {}ms", retry, delay);
+        }
+    }
+
+    #[test]
+    fn test_retry_after_overrides_backoff() {
+        let policy = RetryPolicy::default();
+        let limited = |wait| {
+            let error = ResoError::RateLimited {
+                message: "Too many requests".into(),
+                status_code: 429,
+            };
+            note_retry_after(error, wait)
+        };
+        let error = limited(Duration::from_millis(2500));
+        assert_eq!(retry_after(&error), Some(Duration::from_secs(3)));
+        assert_eq!(policy.delay_after(0, &error), Some(Duration::from_secs(3)));
+        assert_eq!(policy.delay_after(0, &limited(Duration::from_secs(3600))), None);
+
+        let unavailable = ResoError::ServerError {
+            message: "Service unavailable".into(),
+            status_code: 503,
+        };
+        assert_eq!(retry_after(&unavailable), None);
+        assert!(policy.delay_after(0, &unavailable).unwrap() <= Duration::from_millis(500));
+    }
+}
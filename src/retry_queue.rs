@@ -0,0 +1,414 @@
+//! A durable record of replication runs that failed part-way.
+//!
+//! When a request fails after some batches were written, the run's remaining
+//! work (the next link and the filter of the run) is appended to a
+//! [`RetryQueue`] file, and the checkpoint starts the same window over so the
+//! next scheduled run is not stuck behind a failing page. The high-water
+//! mark never moves past the failed page. [`retry_failed`] fetches the
+//! remaining records sooner, saving its place in the queue after each page;
+//! until it succeeds the gap stays listed in the queue.
+//!
+//! Only [upstream failures](is_upstream_failure) are queued. A run that
+//! failed writing to its destination is resumed from its checkpoint instead.
+
+use crate::cancel::is_cancelled;
+use crate::client::ResoApi;
+use crate::export::RecordSink;
+use crate::sync::{replication_filter, Checkpoint};
+use chrono::Utc;
+use reso_client::{ReplicationQueryBuilder, ReplicationResponse, ResoError};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Default queue file used by `reso replicate` and `reso retry-failed`.
+pub const DEFAULT_RETRY_QUEUE: &str = "reso_failed.jsonl";
+
+/// The unfinished part of a failed replication run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedBatch {
+    pub resource: String,
+    /// Replication filter of the failed run, including its `since` window
+    pub filter: Option<String>,
+    /// Next link the run stopped at; may have expired by the time of a retry
+    pub next_link: Option<String>,
+    /// The error that stopped the run, or the latest retry
+    pub error: String,
+    /// When the run first failed (RFC 3339)
+    pub failed_at: String,
+    /// Retries attempted so far
+    pub attempts: u32,
+}
+
+impl FailedBatch {
+    /// Describes the remaining work of an interrupted run.
+    ///
+    /// `filter` is the caller's filter; the checkpoint's `since` window is
+    /// added to it.
+    pub fn from_checkpoint(checkpoint: &Checkpoint, filter: Option<&str>, error: &ResoError) -> Self {
+        Self {
+            resource: checkpoint.resource.clone(),
+            filter: replication_filter(filter, checkpoint.since.as_deref()),
+            next_link: checkpoint.next_link.clone(),
+            error: error.to_string(),
+            failed_at: Utc::now().to_rfc3339(),
+            attempts: 0,
+        }
+    }
+}
+
+/// Returns true if `error` came from a request to the server, rather than
+/// from the sink, the local configuration, or cancellation.
+///
+/// Sinks report I/O and encoding problems as `Config` and `Parse` errors,
+/// which are never queued.
+pub fn is_upstream_failure(error: &ResoError) -> bool {
+    match error {
+        ResoError::Network(_) => !is_cancelled(error),
+        ResoError::Unauthorized { .. }
+        | ResoError::Forbidden { .. }
+        | ResoError::NotFound { .. }
+        | ResoError::RateLimited { .. }
+        | ResoError::ServerError { .. }
+        | ResoError::ODataError { .. } => true,
+        ResoError::Config(_) | ResoError::InvalidQuery(_) | ResoError::Parse(_) => false,
+    }
+}
+
+/// A newline-delimited JSON file of [`FailedBatch`] entries.
+#[derive(Debug, Clone)]
+pub struct RetryQueue {
+    path: PathBuf,
+}
+
+impl RetryQueue {
+    /// Uses the queue at `path`. The file is created on the first push.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads all entries; a missing file is an empty queue.
+    pub fn entries(&self) -> Result<Vec<FailedBatch>, ResoError> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(self.io_error("read", e)),
+        };
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| {
+                    ResoError::Parse(format!("Invalid entry in {}: {}", self.path.display(), e))
+                })
+            })
+            .collect()
+    }
+
+    /// Appends an entry, syncing it to disk before returning.
+    pub fn push(&self, entry: &FailedBatch) -> Result<(), ResoError> {
+        let line = serde_json::to_string(entry).map_err(|e| ResoError::Parse(e.to_string()))?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| self.io_error("open", e))?;
+        writeln!(file, "{}", line)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| self.io_error("write", e))
+    }
+
+    /// Replaces the queue's contents atomically.
+    pub fn replace(&self, entries: &[FailedBatch]) -> Result<(), ResoError> {
+        let mut contents = String::new();
+        for entry in entries {
+            contents.push_str(&serde_json::to_string(entry).map_err(|e| ResoError::Parse(e.to_string()))?);
+            contents.push('\n');
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| self.io_error("write", e))
+    }
+
+    fn io_error(&self, action: &str, e: std::io::Error) -> ResoError {
+        ResoError::Config(format!("Cannot {} retry queue {}: {}", action, self.path.display(), e))
+    }
+}
+
+/// Counts reported by [`retry_failed`].
+#[derive(Debug, Clone, Default)]
+pub struct RetryStats {
+    /// Entries that were retried and removed from the queue
+    pub recovered: usize,
+    /// Entries that failed again and stay queued
+    pub still_failing: usize,
+    /// Records written to the sink
+    pub records: u64,
+}
+
+/// Retries the queued failures of one resource, writing into `sink`.
+///
+/// Each entry resumes from its next link. Replication links usually expire
+/// after a while, so if the link is rejected the entry's whole filter window
+/// is replicated again instead; upserting sinks absorb the overlap.
+/// After each page is written the entry's next link is updated in the queue,
+/// so a retry that fails part-way resumes where it stopped rather than
+/// writing the same records again. Recovered entries are removed from the
+/// queue, the others keep their place with the new error and attempt count.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `queue` - The queue the failures were recorded in
+/// * `resource` - Only entries for this resource are retried
+/// * `sink` - Destination for the recovered records
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::retry_queue::{retry_failed, RetryQueue, DEFAULT_RETRY_QUEUE};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let mut sink = JsonlSink::append("property.jsonl")?;
+///     let queue = RetryQueue::new(DEFAULT_RETRY_QUEUE);
+///
+///     let stats = retry_failed(&client, &queue, "Property", &mut sink).await?;
+///     println!("Recovered {} gaps, {} still failing", stats.recovered, stats.still_failing);
+///     Ok(())
+/// }
+/// ```
+pub async fn retry_failed<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    queue: &RetryQueue,
+    resource: &str,
+    sink: &mut S,
+) -> Result<RetryStats, ResoError> {
+    let mut stats = RetryStats::default();
+    let mut entries = queue.entries()?;
+    let mut recovered = vec![false; entries.len()];
+
+    for index in 0..entries.len() {
+        if entries[index].resource != resource {
+            continue;
+        }
+        let mut progress = Progress {
+            queue,
+            entries: &mut entries,
+            index,
+            records: 0,
+        };
+        let result = retry_entry(client, &mut progress, sink).await;
+        stats.records += progress.records;
+        match result {
+            Ok(()) => {
+                stats.recovered += 1;
+                recovered[index] = true;
+            }
+            Err(e) => {
+                let entry = &mut entries[index];
+                entry.attempts += 1;
+                entry.error = e.to_string();
+                stats.still_failing += 1;
+            }
+        }
+    }
+
+    sink.finalize().await?;
+    let remaining: Vec<FailedBatch> = entries
+        .into_iter()
+        .zip(recovered)
+        .filter(|(_, recovered)| !recovered)
+        .map(|(entry, _)| entry)
+        .collect();
+    queue.replace(&remaining)?;
+    Ok(stats)
+}
+
+/// The queue entry being retried, and how far the retry has got.
+struct Progress<'a> {
+    queue: &'a RetryQueue,
+    entries: &'a mut Vec<FailedBatch>,
+    index: usize,
+    records: u64,
+}
+
+impl Progress<'_> {
+    fn entry(&self) -> &FailedBatch {
+        &self.entries[self.index]
+    }
+
+    /// Moves the entry past a page written to the sink and saves the queue.
+    fn advance(&mut self, records: u64, next_link: Option<String>) -> Result<(), ResoError> {
+        self.records += records;
+        self.entries[self.index].next_link = next_link;
+        self.queue.replace(&self.entries[..])
+    }
+}
+
+async fn retry_entry<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    progress: &mut Progress<'_>,
+    sink: &mut S,
+) -> Result<(), ResoError> {
+    let entry = progress.entry();
+    let resumed = match &entry.next_link {
+        Some(link) => match client.execute_next_link(link).await {
+            Ok(response) => Some(response),
+            // Network errors are worth another retry of the same link later;
+            // a rejected link has most likely expired
+            Err(ResoError::Network(message)) => return Err(ResoError::Network(message)),
+            Err(_) => None,
+        },
+        None => None,
+    };
+
+    let first = match resumed {
+        Some(response) => response,
+        None => {
+            let mut builder = ReplicationQueryBuilder::new(&entry.resource);
+            if let Some(filter) = &entry.filter {
+                builder = builder.filter(filter);
+            }
+            client.execute_replication(&builder.build()?).await?
+        }
+    };
+    drain(client, first, sink, progress).await
+}
+
+/// Writes a replication response and all pages after it into a sink,
+/// recording each page in the queue once it is flushed.
+async fn drain<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    mut response: ReplicationResponse,
+    sink: &mut S,
+    progress: &mut Progress<'_>,
+) -> Result<(), ResoError> {
+    loop {
+        sink.write_batch(&response.records).await?;
+        sink.flush().await?;
+        let next_link = response.next_link.take().filter(|_| !response.records.is_empty());
+        progress.advance(response.records.len() as u64, next_link.clone())?;
+
+        match next_link {
+            Some(link) => response = client.execute_next_link(&link).await?,
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use reso_client::{ClientConfig, JsonValue};
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Default)]
+    struct VecSink(Vec<JsonValue>);
+
+    #[async_trait]
+    impl RecordSink for VecSink {
+        async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+            self.0.extend_from_slice(records);
+            Ok(())
+        }
+    }
+
+    fn entry(resource: &str, next_link: Option<String>) -> FailedBatch {
+        FailedBatch {
+            resource: resource.to_string(),
+            filter: Some("ModificationTimestamp gt 2025-01-01T00:00:00Z".to_string()),
+            next_link,
+            error: "Server error".to_string(),
+            failed_at: "2025-01-02T00:00:00Z".to_string(),
+            attempts: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_link_falls_back_to_filter_window() {
+        let server = MockServer::start().await;
+        Mock::given(path("/expired"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/replication"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "value": [{"ListingKey": "A"}, {"ListingKey": "B"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("reso-retry-queue-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let queue = RetryQueue::new(dir.join("failed.jsonl"));
+        queue.push(&entry("Property", Some(format!("{}/expired", server.uri())))).unwrap();
+        queue.push(&entry("Member", None)).unwrap();
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let mut sink = VecSink::default();
+        let stats = retry_failed(&client, &queue, "Property", &mut sink).await.unwrap();
+
+        assert_eq!((stats.recovered, stats.still_failing, stats.records), (1, 0, 2));
+        assert_eq!(sink.0.len(), 2);
+        assert_eq!(queue.entries().unwrap(), vec![entry("Member", None)]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_retry_keeps_its_place() {
+        let server = MockServer::start().await;
+        Mock::given(path("/page1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "value": [{"ListingKey": "A"}, {"ListingKey": "B"}],
+                "@odata.nextLink": format!("{}/page2", server.uri())
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(path("/page2"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("reso-retry-progress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let queue = RetryQueue::new(dir.join("failed.jsonl"));
+        queue.push(&entry("Property", Some(format!("{}/page1", server.uri())))).unwrap();
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let mut sink = VecSink::default();
+        let stats = retry_failed(&client, &queue, "Property", &mut sink).await.unwrap();
+
+        assert_eq!((stats.recovered, stats.still_failing, stats.records), (0, 1, 2));
+        let entries = queue.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].next_link, Some(format!("{}/page2", server.uri())));
+        assert_eq!(entries[0].attempts, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sink_errors_are_not_upstream_failures() {
+        assert!(is_upstream_failure(&ResoError::Network("connection reset".into())));
+        assert!(!is_upstream_failure(&ResoError::Config("Cannot write property.jsonl".into())));
+        assert!(!is_upstream_failure(&ResoError::Parse("Invalid record".into())));
+    }
+}
@@ -0,0 +1,173 @@
+//! Searches stored as data, for saving and replaying later.
+//!
+//! A [`SavedSearch`] holds the parts of a query (resource, filter clauses,
+//! selected fields, sort order, and limit) rather than a rendered URL, so a
+//! web app or CLI can store users' searches as JSON, show or edit their
+//! parts, and run them again with [`SavedSearch::to_query`].
+
+use crate::search::{stable_order_by, PropertySearch};
+use reso_client::{Query, QueryBuilder, ResoError};
+use serde::{Deserialize, Serialize};
+
+/// A search definition that serializes to and from JSON.
+///
+/// Missing fields take their defaults when deserializing, so
+/// `{"filters": ["City eq 'Austin'"]}` is a complete `Property` search.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::saved_search::SavedSearch;
+/// use reso_examples::search::PropertySearch;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let saved = SavedSearch::from(PropertySearch::new().city("Austin").min_beds(3).cheapest_first().top(20));
+/// let json = serde_json::to_string(&saved)?;
+///
+/// // Later, e.g. when a user re-runs the search
+/// let restored: SavedSearch = serde_json::from_str(&json)?;
+/// assert_eq!(restored, saved);
+/// let query = restored.to_query()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSearch {
+    /// Resource searched, e.g. "Property"
+    pub resource: String,
+    /// Filter clauses, all of which must match
+    pub filters: Vec<String>,
+    /// Fields to return; empty returns all fields
+    pub select: Vec<String>,
+    pub order_by: Option<SortOrder>,
+    /// Maximum number of results
+    pub top: Option<u32>,
+}
+
+/// The sort order of a [`SavedSearch`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortOrder {
+    pub field: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+impl Default for SavedSearch {
+    fn default() -> Self {
+        Self {
+            resource: "Property".to_string(),
+            filters: Vec::new(),
+            select: Vec::new(),
+            order_by: None,
+            top: None,
+        }
+    }
+}
+
+impl SavedSearch {
+    /// An empty search of `resource`.
+    pub fn new(resource: &str) -> Self {
+        Self {
+            resource: resource.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The filter clauses joined with `and`, each parenthesized when there
+    /// is more than one so `or` clauses keep their meaning.
+    pub fn filter(&self) -> Option<String> {
+        match self.filters.as_slice() {
+            [] => None,
+            [single] => Some(single.clone()),
+            clauses => Some(
+                clauses
+                    .iter()
+                    .map(|c| format!("({})", c))
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+            ),
+        }
+    }
+
+    /// Builds the query to run the search.
+    ///
+    /// Returns `ResoError::InvalidQuery` when the resource or a sort field
+    /// is empty.
+    pub fn to_query(&self) -> Result<Query, ResoError> {
+        if self.resource.trim().is_empty() {
+            return Err(ResoError::InvalidQuery("Saved search has no resource".to_string()));
+        }
+        let mut builder = QueryBuilder::new(&self.resource);
+        if let Some(filter) = self.filter() {
+            builder = builder.filter(filter);
+        }
+        if !self.select.is_empty() {
+            let fields: Vec<&str> = self.select.iter().map(String::as_str).collect();
+            builder = builder.select(&fields);
+        }
+        if let Some(order) = &self.order_by {
+            if order.field.trim().is_empty() {
+                return Err(ResoError::InvalidQuery("Saved search sorts on an empty field".to_string()));
+            }
+            let direction = if order.descending { "desc" } else { "asc" };
+            // Sorted as PropertySearch sorts, with its ListingKey tiebreaker
+            builder = if self.resource == "Property" {
+                stable_order_by(builder, &order.field, direction)
+            } else {
+                builder.order_by(&order.field, direction)
+            };
+        }
+        if let Some(n) = self.top {
+            builder = builder.top(n);
+        }
+        builder.build()
+    }
+}
+
+/// Captures a [`PropertySearch`]. Its `skip`, a paging position rather than
+/// part of the search, is not kept.
+impl From<PropertySearch> for SavedSearch {
+    fn from(search: PropertySearch) -> Self {
+        Self {
+            resource: "Property".to_string(),
+            filters: search.filters,
+            select: search.select,
+            order_by: search.order_by.map(|(field, direction)| SortOrder {
+                field,
+                descending: direction.eq_ignore_ascii_case("desc"),
+            }),
+            top: search.top,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_search_round_trips_and_replays() {
+        let search = PropertySearch::new().city("Austin").min_beds(3).cheapest_first().top(20);
+        let expected = search.clone().build().unwrap().to_odata_string();
+
+        let saved = SavedSearch::from(search);
+        let json = serde_json::to_value(&saved).unwrap();
+        assert_eq!(json["order_by"], serde_json::json!({"field": "ListPrice", "descending": false}));
+        let restored: SavedSearch = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, saved);
+
+        // Two clauses from PropertySearch are parenthesized once saved
+        let mut replayed = restored.clone();
+        replayed.filters = vec![restored.filters.join(" and ")];
+        assert_eq!(replayed.to_query().unwrap().to_odata_string(), expected);
+
+        let minimal: SavedSearch = serde_json::from_str(r#"{"filters": ["City eq 'Austin' or City eq 'Round Rock'", "ListPrice gt 0"]}"#).unwrap();
+        assert_eq!(minimal.resource, "Property");
+        assert_eq!(
+            minimal.filter().as_deref(),
+            Some("(City eq 'Austin' or City eq 'Round Rock') and (ListPrice gt 0)")
+        );
+        assert!(SavedSearch::new(" ").to_query().is_err());
+    }
+}
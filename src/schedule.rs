@@ -0,0 +1,558 @@
+//! Running sync jobs on a schedule, with alerts when they go wrong.
+//!
+//! A [`Scheduler`] runs its jobs one after another every interval, or each
+//! job on its own interval when added with
+//! [`job_every`](Scheduler::job_every). When a
+//! job fails, or a run writes far more or fewer records than the one before
+//! it, the scheduler raises an [`Alert`] and passes it to every
+//! [`AlertHook`]: a closure, a [`SlackNotifier`], an [`EmailNotifier`], or
+//! any other implementation. Alerts are delivered after each job so an
+//! overnight failure is reported before the remaining jobs finish.
+//!
+//! Cancelling the scheduler's token (see
+//! [`cancel_on_ctrl_c`](crate::cancel::cancel_on_ctrl_c)) stops a running
+//! [`ReplicationJob`] between batches with its checkpoint saved, so the next
+//! start resumes where it left off.
+
+use crate::cancel::{is_cancelled, CancellationToken};
+use crate::client::ResoApi;
+use crate::export::RecordSink;
+use crate::sync::{replicate_all_cancellable, Checkpoint, ReplicationStats};
+use async_trait::async_trait;
+use reso_client::ResoError;
+use std::fmt;
+use std::future::Future;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::time::Instant;
+
+/// Default factor by which a run's record count may differ from the
+/// previous run's before [`Alert::CountDeviation`] is raised.
+pub const DEFAULT_MAX_DEVIATION: f64 = 10.0;
+
+/// A unit of work the scheduler runs.
+///
+/// [`ReplicationJob`] replicates a resource into a sink; closures returning
+/// a future of [`ReplicationStats`] are jobs too.
+#[async_trait]
+pub trait SyncJob: Send {
+    async fn run(&mut self, token: &CancellationToken) -> Result<ReplicationStats, ResoError>;
+}
+
+#[async_trait]
+impl<F, Fut> SyncJob for F
+where
+    F: FnMut() -> Fut + Send,
+    Fut: Future<Output = Result<ReplicationStats, ResoError>> + Send,
+{
+    async fn run(&mut self, _token: &CancellationToken) -> Result<ReplicationStats, ResoError> {
+        self().await
+    }
+}
+
+/// A job that replicates a resource with
+/// [`replicate_all_cancellable`], resuming from its checkpoint.
+pub struct ReplicationJob<C, S> {
+    client: C,
+    resource: String,
+    filter: Option<String>,
+    sink: S,
+    checkpoint: Checkpoint,
+}
+
+impl<C: ResoApi, S: RecordSink> ReplicationJob<C, S> {
+    pub fn new(client: C, resource: &str, filter: Option<&str>, sink: S, checkpoint: Checkpoint) -> Self {
+        Self {
+            client,
+            resource: resource.to_string(),
+            filter: filter.map(str::to_string),
+            sink,
+            checkpoint,
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ResoApi, S: RecordSink> SyncJob for ReplicationJob<C, S> {
+    async fn run(&mut self, token: &CancellationToken) -> Result<ReplicationStats, ResoError> {
+        replicate_all_cancellable(
+            &self.client,
+            &self.resource,
+            self.filter.as_deref(),
+            &mut self.sink,
+            &mut self.checkpoint,
+            token,
+        )
+        .await
+    }
+}
+
+/// Something an operator should look at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alert {
+    /// A scheduled run returned an error
+    Failed { job: String, error: String },
+    /// A run's record count differs from the previous run's by more than
+    /// the scheduler's maximum deviation
+    CountDeviation { job: String, records: u64, previous: u64 },
+}
+
+impl Alert {
+    /// The job the alert is about.
+    pub fn job(&self) -> &str {
+        match self {
+            Alert::Failed { job, .. } | Alert::CountDeviation { job, .. } => job,
+        }
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::Failed { job, error } => write!(f, "Sync job '{}' failed: {}", job, error),
+            Alert::CountDeviation { job, records, previous } => write!(
+                f,
+                "Sync job '{}' wrote {} records; the previous run wrote {}",
+                job, records, previous
+            ),
+        }
+    }
+}
+
+/// Receives alerts raised by a [`Scheduler`].
+///
+/// Closures taking an `&Alert` are hooks, for logging or custom delivery.
+#[async_trait]
+pub trait AlertHook: Send + Sync {
+    async fn notify(&self, alert: &Alert) -> Result<(), ResoError>;
+}
+
+#[async_trait]
+impl<F: Fn(&Alert) + Send + Sync> AlertHook for F {
+    async fn notify(&self, alert: &Alert) -> Result<(), ResoError> {
+        self(alert);
+        Ok(())
+    }
+}
+
+/// How long [`SlackNotifier`] waits for the webhook before giving up, so a
+/// hung Slack request cannot stall the jobs after it.
+const SLACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts alerts to a Slack channel through an incoming webhook.
+#[derive(Debug, Clone)]
+pub struct SlackNotifier {
+    http: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    /// # Arguments
+    ///
+    /// * `webhook_url` - The channel's incoming webhook URL, e.g. from `SLACK_WEBHOOK_URL`
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            webhook_url: webhook_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertHook for SlackNotifier {
+    async fn notify(&self, alert: &Alert) -> Result<(), ResoError> {
+        let response = self
+            .http
+            .post(&self.webhook_url)
+            .timeout(SLACK_TIMEOUT)
+            .json(&serde_json::json!({ "text": alert.to_string() }))
+            .send()
+            .await
+            .map_err(|e| ResoError::Network(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ResoError::ServerError {
+                message: format!("Slack webhook rejected the alert: {}", body),
+                status_code: status.as_u16(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Emails alerts through the local `sendmail` program.
+///
+/// Any MTA that provides the `sendmail -t` interface works, including
+/// Postfix, Exim, and msmtp.
+#[derive(Debug, Clone)]
+pub struct EmailNotifier {
+    from: String,
+    to: Vec<String>,
+    sendmail: String,
+}
+
+impl EmailNotifier {
+    pub fn new(from: impl Into<String>, to: &[&str]) -> Self {
+        Self {
+            from: from.into(),
+            to: to.iter().map(|address| address.to_string()).collect(),
+            sendmail: "sendmail".to_string(),
+        }
+    }
+
+    /// Uses another `sendmail`-compatible program, e.g. `/usr/bin/msmtp`.
+    pub fn sendmail(mut self, program: impl Into<String>) -> Self {
+        self.sendmail = program.into();
+        self
+    }
+
+    /// The message handed to `sendmail -t`, headers included.
+    pub fn message(&self, alert: &Alert) -> String {
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: RESO sync alert: {}\r\n\r\n{}\r\n",
+            self.from,
+            self.to.join(", "),
+            alert.job(),
+            alert
+        )
+    }
+}
+
+#[async_trait]
+impl AlertHook for EmailNotifier {
+    async fn notify(&self, alert: &Alert) -> Result<(), ResoError> {
+        let failed = |e: std::io::Error| ResoError::Config(format!("Cannot run {}: {}", self.sendmail, e));
+        let mut child = tokio::process::Command::new(&self.sendmail)
+            .arg("-t")
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(failed)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(self.message(alert).as_bytes()).await.map_err(failed)?;
+        }
+        let status = child.wait().await.map_err(failed)?;
+        if !status.success() {
+            return Err(ResoError::Config(format!("{} exited with {}", self.sendmail, status)));
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of one job in a scheduler round.
+#[derive(Debug)]
+pub struct JobRun {
+    pub job: String,
+    pub result: Result<ReplicationStats, ResoError>,
+    /// Alerts raised for this run, already passed to the hooks
+    pub alerts: Vec<Alert>,
+}
+
+struct ScheduledJob {
+    name: String,
+    job: Box<dyn SyncJob>,
+    /// The job's own interval, if not the scheduler's
+    every: Option<Duration>,
+    next_run: Instant,
+    /// Records written by the last successful run; kept in memory only
+    last_records: Option<u64>,
+}
+
+/// Runs sync jobs every interval and alerts on failures and unusual counts.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::cancel::CancellationToken;
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::schedule::{Alert, ReplicationJob, Scheduler, SlackNotifier};
+/// use reso_examples::sync::Checkpoint;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let job = ReplicationJob::new(
+///         create_client()?,
+///         "Property",
+///         None,
+///         JsonlSink::append("property.jsonl")?,
+///         Checkpoint::load("property.checkpoint.json", "Property")?,
+///     );
+///     let mut scheduler = Scheduler::new(Duration::from_secs(3600))
+///         .job("property", job)
+///         .on_alert(SlackNotifier::new(std::env::var("SLACK_WEBHOOK_URL")?))
+///         .on_alert(|alert: &Alert| eprintln!("{}", alert));
+///
+///     scheduler.run(&CancellationToken::new()).await;
+///     Ok(())
+/// }
+/// ```
+pub struct Scheduler {
+    interval: Duration,
+    jobs: Vec<ScheduledJob>,
+    hooks: Vec<Box<dyn AlertHook>>,
+    max_deviation: f64,
+}
+
+impl Scheduler {
+    /// A scheduler that starts a round of jobs every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            jobs: Vec::new(),
+            hooks: Vec::new(),
+            max_deviation: DEFAULT_MAX_DEVIATION,
+        }
+    }
+
+    /// Adds a job, run after the jobs added before it.
+    pub fn job(self, name: &str, job: impl SyncJob + 'static) -> Self {
+        self.add(name, None, job)
+    }
+
+    /// Adds a job that runs every `every` instead of every scheduler
+    /// interval, e.g. listings every 15 minutes and offices once a day.
+    pub fn job_every(self, name: &str, every: Duration, job: impl SyncJob + 'static) -> Self {
+        self.add(name, Some(every), job)
+    }
+
+    fn add(mut self, name: &str, every: Option<Duration>, job: impl SyncJob + 'static) -> Self {
+        self.jobs.push(ScheduledJob {
+            name: name.to_string(),
+            job: Box::new(job),
+            every,
+            next_run: Instant::now(),
+            last_records: None,
+        });
+        self
+    }
+
+    /// Adds a hook that receives every alert.
+    pub fn on_alert(mut self, hook: impl AlertHook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Alerts when a run writes more than `factor` times as many records
+    /// as the previous successful run, or fewer than 1/`factor` as many.
+    ///
+    /// Previous counts are kept in memory, so the first run of each job
+    /// after the scheduler starts is never compared.
+    pub fn max_deviation(mut self, factor: f64) -> Self {
+        self.max_deviation = factor;
+        self
+    }
+
+    /// Runs every job once and delivers the resulting alerts.
+    ///
+    /// A hook that fails to deliver an alert is reported on stderr and does
+    /// not stop the other hooks or jobs. A job stopped by cancelling `token`
+    /// raises no alert, and the jobs after it are not started.
+    pub async fn run_once(&mut self, token: &CancellationToken) -> Vec<JobRun> {
+        let mut runs = Vec::with_capacity(self.jobs.len());
+        for index in 0..self.jobs.len() {
+            if token.is_cancelled() {
+                break;
+            }
+            runs.push(self.run_job(index, token).await);
+        }
+        runs
+    }
+
+    async fn run_job(&mut self, index: usize, token: &CancellationToken) -> JobRun {
+        let scheduled = &mut self.jobs[index];
+        let result = scheduled.job.run(token).await;
+        let mut alerts = Vec::new();
+        match &result {
+            Ok(stats) => {
+                if let Some(previous) = scheduled.last_records {
+                    if deviates(stats.records, previous, self.max_deviation) {
+                        alerts.push(Alert::CountDeviation {
+                            job: scheduled.name.clone(),
+                            records: stats.records,
+                            previous,
+                        });
+                    }
+                }
+                scheduled.last_records = Some(stats.records);
+            }
+            Err(e) if is_cancelled(e) => {}
+            Err(e) => alerts.push(Alert::Failed {
+                job: scheduled.name.clone(),
+                error: e.to_string(),
+            }),
+        }
+
+        for alert in &alerts {
+            for hook in &self.hooks {
+                if let Err(e) = hook.notify(alert).await {
+                    eprintln!("Alert for '{}' not delivered: {}", alert.job(), e);
+                }
+            }
+        }
+        JobRun {
+            job: scheduled.name.clone(),
+            result,
+            alerts,
+        }
+    }
+
+    /// Runs each job on its interval until `token` is cancelled.
+    ///
+    /// Every job first runs immediately. Jobs due at the same time run one
+    /// after another, and a run that overruns its interval delays the job's
+    /// next run rather than overlapping it. Cancelling stops the running
+    /// job, if any, and returns without starting another.
+    pub async fn run(&mut self, token: &CancellationToken) {
+        let start = Instant::now();
+        for scheduled in &mut self.jobs {
+            scheduled.next_run = start;
+        }
+        loop {
+            let Some(due) = self.jobs.iter().map(|j| j.next_run).min() else {
+                token.cancelled().await;
+                return;
+            };
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => return,
+                _ = tokio::time::sleep_until(due) => {}
+            }
+            let now = Instant::now();
+            for index in 0..self.jobs.len() {
+                if token.is_cancelled() {
+                    return;
+                }
+                if self.jobs[index].next_run > now {
+                    continue;
+                }
+                let started = Instant::now();
+                self.run_job(index, token).await;
+                let scheduled = &mut self.jobs[index];
+                scheduled.next_run = next_run(started, Instant::now(), scheduled.every.unwrap_or(self.interval));
+            }
+        }
+    }
+}
+
+/// When a job that started at `started` and finished at `finished` runs
+/// next: one interval after it started, or after it finished if it overran.
+fn next_run(started: Instant, finished: Instant, every: Duration) -> Instant {
+    let next = started + every;
+    if next > finished {
+        next
+    } else {
+        finished + every
+    }
+}
+
+/// Whether `records` is more than `factor` times `previous` or less than
+/// 1/`factor` of it. A run after an empty run never deviates, since an
+/// incremental sync often finds nothing new.
+fn deviates(records: u64, previous: u64, factor: f64) -> bool {
+    if previous == 0 {
+        return false;
+    }
+    let ratio = records as f64 / previous as f64;
+    ratio > factor || ratio < 1.0 / factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_scheduler_alerts_on_failure_and_count_deviation() {
+        let counts = Arc::new(Mutex::new(vec![Ok(100), Ok(120), Ok(3), Err("Server Error (503)")].into_iter()));
+        let job = {
+            let counts = counts.clone();
+            move || {
+                let next = counts.lock().unwrap().next().unwrap();
+                async move {
+                    next.map(|records| ReplicationStats {
+                        records,
+                        ..Default::default()
+                    })
+                    .map_err(|e| ResoError::Network(e.to_string()))
+                }
+            }
+        };
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let mut scheduler = Scheduler::new(Duration::from_secs(60))
+            .job("property", job)
+            .on_alert(move |alert: &Alert| sink.lock().unwrap().push(alert.clone()));
+
+        let token = CancellationToken::new();
+        for _ in 0..3 {
+            scheduler.run_once(&token).await;
+        }
+        let runs = scheduler.run_once(&token).await;
+        assert!(runs[0].result.is_err());
+
+        // A cancelled round starts no jobs
+        token.cancel();
+        assert!(scheduler.run_once(&token).await.is_empty());
+
+        let received = received.lock().unwrap();
+        assert_eq!(
+            received[0],
+            Alert::CountDeviation {
+                job: "property".into(),
+                records: 3,
+                previous: 120
+            }
+        );
+        assert!(matches!(&received[1], Alert::Failed { job, error } if job == "property" && error.contains("503")));
+        assert_eq!(received.len(), 2);
+
+        let email = EmailNotifier::new("sync@example.com", &["ops@example.com", "oncall@example.com"]);
+        let message = email.message(&received[0]);
+        assert!(message.starts_with("From: sync@example.com\r\nTo: ops@example.com, oncall@example.com\r\n"));
+        assert!(message.contains("Subject: RESO sync alert: property\r\n\r\nSync job 'property' wrote 3 records"));
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_on_their_own_intervals_until_cancelled() {
+        let token = CancellationToken::new();
+        let fast_runs = Arc::new(Mutex::new(0));
+        let slow_runs = Arc::new(Mutex::new(0));
+        let fast = {
+            let (runs, token) = (fast_runs.clone(), token.clone());
+            move || {
+                let mut runs = runs.lock().unwrap();
+                *runs += 1;
+                if *runs == 3 {
+                    token.cancel();
+                }
+                async { Ok::<_, ResoError>(ReplicationStats::default()) }
+            }
+        };
+        let slow = {
+            let runs = slow_runs.clone();
+            move || {
+                *runs.lock().unwrap() += 1;
+                async { Ok::<_, ResoError>(ReplicationStats::default()) }
+            }
+        };
+        let alerts = Arc::new(Mutex::new(0));
+        let alerted = alerts.clone();
+        let mut scheduler = Scheduler::new(Duration::from_secs(3600))
+            .job("offices", slow)
+            .job_every("property", Duration::from_millis(10), fast)
+            .on_alert(move |_: &Alert| *alerted.lock().unwrap() += 1);
+
+        tokio::time::timeout(Duration::from_secs(5), scheduler.run(&token)).await.unwrap();
+        assert_eq!(*fast_runs.lock().unwrap(), 3);
+        assert_eq!(*slow_runs.lock().unwrap(), 1);
+        assert_eq!(*alerts.lock().unwrap(), 0);
+
+        let start = Instant::now();
+        let every = Duration::from_secs(60);
+        assert_eq!(next_run(start, start + Duration::from_secs(5), every), start + every);
+        assert_eq!(next_run(start, start + Duration::from_secs(90), every), start + Duration::from_secs(150));
+    }
+}
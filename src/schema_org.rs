@@ -0,0 +1,202 @@
+//! schema.org structured data for listing pages.
+//!
+//! Search engines read JSON-LD embedded in a page to show rich results:
+//! price, address, and photos straight in the search listing.
+//! [`real_estate_listing`] maps a Property record onto a schema.org
+//! `RealEstateListing` whose `offers` hold an `Offer` and whose `about`
+//! describes the home itself, and [`json_ld_script`] embeds it in HTML.
+
+use crate::fields::property as field;
+use crate::media::ListingMedia;
+use reso_client::JsonValue;
+use serde_json::{json, Map};
+
+/// Builds a schema.org `RealEstateListing` for a Property record.
+///
+/// Fields missing from the record are left out rather than emitted as
+/// nulls. Prices are assumed to be in US dollars.
+///
+/// # Arguments
+///
+/// * `property` - A Property record
+/// * `page_url` - The canonical URL of the listing page
+/// * `photos` - Photos in display order, e.g. from [`listing_media`](crate::media::listing_media)
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::schema_org::real_estate_listing;
+/// use serde_json::json;
+///
+/// let property = json!({
+///     "ListingKey": "A1",
+///     "ListPrice": 450000,
+///     "StandardStatus": "Active",
+///     "City": "Austin",
+///     "BedroomsTotal": 3
+/// });
+/// let listing = real_estate_listing(&property, "https://example.com/property/A1", &[]);
+///
+/// assert_eq!(listing["@type"], "RealEstateListing");
+/// assert_eq!(listing["offers"]["price"], 450000);
+/// assert_eq!(listing["offers"]["availability"], "https://schema.org/InStock");
+/// assert_eq!(listing["about"]["numberOfBedrooms"], 3);
+/// ```
+pub fn real_estate_listing(property: &JsonValue, page_url: &str, photos: &[ListingMedia]) -> JsonValue {
+    let text = |name: &str| property[name].as_str().filter(|s| !s.is_empty());
+
+    let mut address = Map::new();
+    address.insert("@type".into(), "PostalAddress".into());
+    insert(&mut address, "streetAddress", street_address(property));
+    insert(&mut address, "addressLocality", text(field::CITY));
+    insert(&mut address, "addressRegion", text(field::STATE_OR_PROVINCE));
+    insert(&mut address, "postalCode", text(field::POSTAL_CODE));
+    insert(&mut address, "addressCountry", text(field::COUNTRY));
+
+    let mut home = Map::new();
+    home.insert("@type".into(), accommodation_type(property).into());
+    home.insert("address".into(), address.into());
+    if let (Some(latitude), Some(longitude)) = (
+        property[field::LATITUDE].as_f64(),
+        property[field::LONGITUDE].as_f64(),
+    ) {
+        home.insert(
+            "geo".into(),
+            json!({"@type": "GeoCoordinates", "latitude": latitude, "longitude": longitude}),
+        );
+    }
+    insert(&mut home, "numberOfBedrooms", property[field::BEDROOMS_TOTAL].as_i64());
+    insert(
+        &mut home,
+        "numberOfBathroomsTotal",
+        property[field::BATHROOMS_TOTAL_INTEGER].as_i64(),
+    );
+    if let Some(area) = property[field::LIVING_AREA].as_f64() {
+        // FTK is the UN/CEFACT code for square feet
+        home.insert(
+            "floorSize".into(),
+            json!({"@type": "QuantitativeValue", "value": area, "unitCode": "FTK"}),
+        );
+    }
+    insert(&mut home, "yearBuilt", property[field::YEAR_BUILT].as_i64());
+
+    let mut offer = Map::new();
+    offer.insert("@type".into(), "Offer".into());
+    if let Some(price) = property[field::LIST_PRICE].as_number() {
+        offer.insert("price".into(), price.clone().into());
+        offer.insert("priceCurrency".into(), "USD".into());
+    }
+    insert(&mut offer, "availability", text(field::STANDARD_STATUS).map(availability));
+    if text(field::PROPERTY_TYPE).is_some_and(|t| t.ends_with("Lease")) {
+        offer.insert("businessFunction".into(), "http://purl.org/goodrelations/v1#LeaseOut".into());
+    }
+
+    let mut listing = Map::new();
+    listing.insert("@context".into(), "https://schema.org".into());
+    listing.insert("@type".into(), "RealEstateListing".into());
+    listing.insert("url".into(), page_url.into());
+    insert(
+        &mut listing,
+        "name",
+        text(field::UNPARSED_ADDRESS).map(str::to_string).or_else(|| street_address(property)),
+    );
+    insert(&mut listing, "description", text(field::PUBLIC_REMARKS));
+    insert(&mut listing, "identifier", text(field::LISTING_ID).or(text(field::LISTING_KEY)));
+    insert(&mut listing, "datePosted", text(field::LISTING_CONTRACT_DATE));
+    insert(&mut listing, "dateModified", text(field::MODIFICATION_TIMESTAMP));
+    if !photos.is_empty() {
+        listing.insert(
+            "image".into(),
+            photos.iter().map(|photo| JsonValue::from(photo.url.as_str())).collect(),
+        );
+    }
+    listing.insert("offers".into(), offer.into());
+    listing.insert("about".into(), home.into());
+    listing.into()
+}
+
+/// Renders structured data as a `<script type="application/ld+json">`
+/// element, escaping `<` so text in the data cannot close the script early.
+pub fn json_ld_script(data: &JsonValue) -> String {
+    format!(
+        r#"<script type="application/ld+json">{}</script>"#,
+        data.to_string().replace('<', "\\u003c")
+    )
+}
+
+fn insert<V: Into<JsonValue>>(map: &mut Map<String, JsonValue>, key: &str, value: Option<V>) {
+    if let Some(value) = value {
+        map.insert(key.to_string(), value.into());
+    }
+}
+
+pub(crate) fn street_address(property: &JsonValue) -> Option<String> {
+    let street = [field::STREET_NUMBER, field::STREET_NAME, field::STREET_SUFFIX, field::UNIT_NUMBER]
+        .iter()
+        .filter_map(|name| property[*name].as_str())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!street.is_empty()).then_some(street)
+}
+
+/// The schema.org type for the home, from `PropertySubType`.
+fn accommodation_type(property: &JsonValue) -> &'static str {
+    match property[field::PROPERTY_SUB_TYPE].as_str().unwrap_or_default() {
+        "SingleFamilyResidence" => "SingleFamilyResidence",
+        "Townhouse" | "ManufacturedHome" | "MobileHome" | "Cabin" => "House",
+        "Condominium" | "Apartment" | "StockCooperative" => "Apartment",
+        _ => "Accommodation",
+    }
+}
+
+/// Maps a `StandardStatus` onto a schema.org `ItemAvailability`.
+fn availability(status: &str) -> &'static str {
+    match status {
+        "Active" | "ComingSoon" => "https://schema.org/InStock",
+        "ActiveUnderContract" | "Pending" => "https://schema.org/LimitedAvailability",
+        "Closed" => "https://schema.org/SoldOut",
+        _ => "https://schema.org/Discontinued",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing_omits_missing_fields_and_script_is_escaped() {
+        let property = json!({
+            "StreetNumber": "100",
+            "StreetName": "Main",
+            "StreetSuffix": "St",
+            "City": "Austin",
+            "PropertyType": "ResidentialLease",
+            "PropertySubType": "Condominium",
+            "StandardStatus": "Pending",
+            "Latitude": 30.27,
+            "Longitude": -97.74,
+            "LivingArea": 950.0,
+            "PublicRemarks": "Views! </script><script>alert(1)</script>",
+        });
+        let listing = real_estate_listing(&property, "https://example.com/property/A1", &[]);
+
+        assert_eq!(listing["name"], "100 Main St");
+        assert_eq!(listing["about"]["@type"], "Apartment");
+        assert_eq!(listing["about"]["address"]["streetAddress"], "100 Main St");
+        assert_eq!(listing["about"]["geo"]["latitude"], 30.27);
+        assert_eq!(listing["about"]["floorSize"]["unitCode"], "FTK");
+        assert_eq!(listing["offers"]["availability"], "https://schema.org/LimitedAvailability");
+        assert!(listing["offers"]["businessFunction"].is_string());
+        for absent in ["image", "datePosted", "identifier"] {
+            assert!(listing.get(absent).is_none(), "{}", absent);
+        }
+        assert!(listing["offers"].get("price").is_none());
+        assert!(listing["about"].get("numberOfBedrooms").is_none());
+
+        let script = json_ld_script(&listing);
+        assert_eq!(script.matches("</script>").count(), 1);
+        let body = &script[script.find('>').unwrap() + 1..script.rfind("</script>").unwrap()];
+        assert_eq!(serde_json::from_str::<JsonValue>(body).unwrap(), listing);
+    }
+}
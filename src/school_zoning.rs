@@ -0,0 +1,142 @@
+//! Assigned schools and zoning of a listing.
+//!
+//! Buyers search by school as often as by city, but feeds fill
+//! `ElementarySchool`, `MiddleOrJuniorSchool`, `HighSchool`, and `Zoning`
+//! however the listing agent typed them: "LAMAR  MIDDLE", "lamar middle ",
+//! "sf-3". [`Schools`] and [`Zoning`] read these fields with consistent
+//! spacing and capitalization for display, and [`normalize_name`] applies
+//! the same rules to other text. Searches compare these fields ignoring case
+//! instead (see [`PropertySearch::high_school`](crate::search::PropertySearch::high_school)),
+//! since no one spelling matches every feed.
+
+use crate::fields::property as field;
+use reso_client::JsonValue;
+
+/// The schools a listing is assigned to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schools {
+    /// `ElementarySchool`
+    pub elementary: Option<String>,
+    /// `MiddleOrJuniorSchool`
+    pub middle: Option<String>,
+    /// `HighSchool`
+    pub high: Option<String>,
+}
+
+impl Schools {
+    /// Reads the school fields of a Property record, normalized with
+    /// [`normalize_name`]. Blank fields are `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::school_zoning::Schools;
+    /// use serde_json::json;
+    ///
+    /// let listing = json!({"ElementarySchool": " BARTON  HILLS ", "HighSchool": "austin"});
+    /// let schools = Schools::from_record(&listing);
+    ///
+    /// assert_eq!(schools.elementary.as_deref(), Some("Barton Hills"));
+    /// assert_eq!(schools.middle, None);
+    /// assert_eq!(schools.high.as_deref(), Some("Austin"));
+    /// ```
+    pub fn from_record(record: &JsonValue) -> Self {
+        Self {
+            elementary: name_field(record, field::ELEMENTARY_SCHOOL),
+            middle: name_field(record, field::MIDDLE_OR_JUNIOR_SCHOOL),
+            high: name_field(record, field::HIGH_SCHOOL),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elementary.is_none() && self.middle.is_none() && self.high.is_none()
+    }
+}
+
+/// A listing's zoning designation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zoning {
+    /// `Zoning`, e.g. "SF-3"
+    pub code: String,
+    /// `ZoningDescription`, e.g. "Family Residence"
+    pub description: Option<String>,
+}
+
+impl Zoning {
+    /// Reads the zoning fields of a Property record, or `None` if it has
+    /// no `Zoning`.
+    pub fn from_record(record: &JsonValue) -> Option<Self> {
+        Some(Self {
+            code: name_field(record, field::ZONING)?,
+            description: name_field(record, field::ZONING_DESCRIPTION),
+        })
+    }
+}
+
+/// Trims a name, collapses runs of whitespace, and title-cases each word.
+///
+/// Letters after a hyphen, apostrophe, or other punctuation start a new
+/// word ("o'henry" becomes "O'Henry"). Words containing a digit are
+/// treated as codes and upper-cased, so zoning such as "sf-3" becomes
+/// "SF-3". Returns `None` for blank input.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::school_zoning::normalize_name;
+///
+/// assert_eq!(normalize_name("  LAMAR   middle ").as_deref(), Some("Lamar Middle"));
+/// assert_eq!(normalize_name("r-1").as_deref(), Some("R-1"));
+/// assert_eq!(normalize_name("   "), None);
+/// ```
+pub fn normalize_name(value: &str) -> Option<String> {
+    let words: Vec<String> = value
+        .split_whitespace()
+        .map(|word| {
+            if word.chars().any(|c| c.is_ascii_digit()) {
+                return word.to_uppercase();
+            }
+            let mut word_start = true;
+            word.chars()
+                .map(|c| {
+                    let cased = if word_start { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() };
+                    word_start = !c.is_alphanumeric();
+                    cased
+                })
+                .collect()
+        })
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+fn name_field(record: &JsonValue, field: &str) -> Option<String> {
+    record[field].as_str().and_then(normalize_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schools_and_zoning_are_normalized() {
+        let listing = json!({
+            "ElementarySchool": "o'henry  ",
+            "MiddleOrJuniorSchool": "",
+            "HighSchool": "MCCALLUM",
+            "Zoning": " sf-3 ",
+            "ZoningDescription": "single-family   residence"
+        });
+        let schools = Schools::from_record(&listing);
+        assert_eq!(schools.elementary.as_deref(), Some("O'Henry"));
+        assert_eq!(schools.middle, None);
+        assert_eq!(schools.high.as_deref(), Some("Mccallum"));
+
+        let zoning = Zoning::from_record(&listing).unwrap();
+        assert_eq!(zoning.code, "SF-3");
+        assert_eq!(zoning.description.as_deref(), Some("Single-Family Residence"));
+
+        assert!(Schools::from_record(&json!({"City": "Austin"})).is_empty());
+        assert!(Zoning::from_record(&json!({"Zoning": null})).is_none());
+    }
+}
@@ -0,0 +1,836 @@
+//! Listing search in real-estate terms.
+//!
+//! [`PropertySearch`] lets applications describe a search the way a buyer
+//! would ("active homes in Austin between $300k and $500k with 3+ beds")
+//! and compiles it into an OData [`Query`] against the `Property` resource.
+//! [`SearchParams`] is the same search as submitted by a web form or query
+//! string, so any web framework can reuse the translation.
+
+use crate::filter::{FilterValue, Operator};
+use crate::geo::BoundingBox;
+use reso_client::{Query, QueryBuilder, ResoError};
+use serde::{Deserialize, Serialize};
+
+/// Number of results returned when a search does not specify a limit.
+pub const DEFAULT_LIMIT: u32 = 10;
+
+/// Largest number of results a [`SearchParams`] search may request.
+pub const MAX_LIMIT: u32 = 100;
+
+/// Deepest result a [`SearchParams`] search may page to by default; many
+/// servers refuse larger `$skip` values.
+pub const MAX_OFFSET: u32 = 10_000;
+
+/// Fields a [`SearchParams`] search may sort on, by `sort_by` value.
+pub const SORT_FIELDS: &[(&str, &str)] = &[
+    ("price", "ListPrice"),
+    ("beds", "BedroomsTotal"),
+    ("date", "ListingContractDate"),
+];
+
+/// Sort key that [`PropertySearch`] adds after the chosen one, and sorts on
+/// when none is chosen, so results with equal sort values page in the same
+/// order on every request.
+pub const TIEBREAK_FIELD: &str = "ListingKey";
+
+/// `StandardStatus` values accepted by [`SearchParams`].
+pub const STANDARD_STATUSES: &[&str] = &[
+    "Active",
+    "Active Under Contract",
+    "Canceled",
+    "Closed",
+    "Coming Soon",
+    "Delete",
+    "Expired",
+    "Hold",
+    "Incomplete",
+    "Pending",
+    "Withdrawn",
+];
+
+/// A fluent builder for `Property` searches.
+///
+/// Each criterion adds one condition; all conditions must match.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::search::PropertySearch;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let query = PropertySearch::new()
+///     .city("Austin")
+///     .price_range(300_000, 500_000)
+///     .min_beds(3)
+///     .active()
+///     .top(25)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PropertySearch {
+    pub(crate) filters: Vec<String>,
+    pub(crate) select: Vec<String>,
+    pub(crate) order_by: Option<(String, String)>,
+    pub(crate) top: Option<u32>,
+    pub(crate) skip: Option<u32>,
+}
+
+impl PropertySearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listings in a city.
+    pub fn city(self, city: &str) -> Self {
+        self.string_eq("City", city)
+    }
+
+    /// Listings in a state or province, e.g. "TX".
+    pub fn state(self, state: &str) -> Self {
+        self.string_eq("StateOrProvince", state)
+    }
+
+    /// Listings in a postal code.
+    pub fn postal_code(self, postal_code: &str) -> Self {
+        self.string_eq("PostalCode", postal_code)
+    }
+
+    /// Listings of a property type, e.g. "Residential".
+    pub fn property_type(self, property_type: &str) -> Self {
+        self.string_eq("PropertyType", property_type)
+    }
+
+    /// Listings with a `StandardStatus`, e.g. "Active Under Contract".
+    pub fn status(self, status: &str) -> Self {
+        self.string_eq("StandardStatus", status)
+    }
+
+    /// Listings assigned to an elementary school.
+    ///
+    /// School names and zoning codes are compared ignoring case, since feeds
+    /// capitalize them inconsistently:
+    ///
+    /// ```
+    /// use reso_examples::search::PropertySearch;
+    ///
+    /// let search = PropertySearch::new().elementary_school("Barton Hills");
+    /// assert_eq!(search.to_filter().as_deref(), Some("tolower(ElementarySchool) eq 'barton hills'"));
+    /// ```
+    pub fn elementary_school(self, school: &str) -> Self {
+        self.string_eq_ignore_case("ElementarySchool", school)
+    }
+
+    /// Listings assigned to a middle or junior high school.
+    pub fn middle_school(self, school: &str) -> Self {
+        self.string_eq_ignore_case("MiddleOrJuniorSchool", school)
+    }
+
+    /// Listings assigned to a high school.
+    pub fn high_school(self, school: &str) -> Self {
+        self.string_eq_ignore_case("HighSchool", school)
+    }
+
+    /// Listings with a `Zoning` code, e.g. "SF-3".
+    pub fn zoning(self, zoning: &str) -> Self {
+        self.string_eq_ignore_case("Zoning", zoning)
+    }
+
+    /// Listings currently for sale.
+    pub fn active(self) -> Self {
+        self.status("Active")
+    }
+
+    /// Listings under contract.
+    pub fn pending(self) -> Self {
+        self.status("Pending")
+    }
+
+    /// Sold listings.
+    pub fn closed(self) -> Self {
+        self.status("Closed")
+    }
+
+    /// Listings priced between `min` and `max`, inclusive.
+    pub fn price_range(self, min: u64, max: u64) -> Self {
+        self.min_price(min).max_price(max)
+    }
+
+    pub fn min_price(self, price: u64) -> Self {
+        self.compare("ListPrice", Operator::Ge, &price)
+    }
+
+    pub fn max_price(self, price: u64) -> Self {
+        self.compare("ListPrice", Operator::Le, &price)
+    }
+
+    pub fn min_beds(self, beds: u32) -> Self {
+        self.compare("BedroomsTotal", Operator::Ge, &beds)
+    }
+
+    pub fn max_beds(self, beds: u32) -> Self {
+        self.compare("BedroomsTotal", Operator::Le, &beds)
+    }
+
+    pub fn min_baths(self, baths: u32) -> Self {
+        self.compare("BathroomsTotalInteger", Operator::Ge, &baths)
+    }
+
+    /// Listings with at least this much `LivingArea`, in square feet.
+    pub fn min_living_area(self, square_feet: u32) -> Self {
+        self.compare("LivingArea", Operator::Ge, &square_feet)
+    }
+
+    /// Listings in any of several cities.
+    pub fn cities(self, cities: &[&str]) -> Self {
+        self.compare("City", Operator::In, cities)
+    }
+
+    /// Listings whose `PublicRemarks` mention `text`, e.g. "pool".
+    pub fn remarks_contain(self, text: &str) -> Self {
+        self.compare("PublicRemarks", Operator::Contains, text)
+    }
+
+    /// Newly built listings (`NewConstructionYN`), or with `false`, resales.
+    pub fn new_construction(self, yes: bool) -> Self {
+        self.flag("NewConstructionYN", yes)
+    }
+
+    /// Waterfront listings (`WaterfrontYN`), or with `false`, the rest.
+    pub fn waterfront(self, yes: bool) -> Self {
+        self.flag("WaterfrontYN", yes)
+    }
+
+    /// Listings with a private pool (`PoolPrivateYN`), or with `false`, without one.
+    pub fn private_pool(self, yes: bool) -> Self {
+        self.flag("PoolPrivateYN", yes)
+    }
+
+    /// Listings where a Yes/No field is `yes`.
+    ///
+    /// Feeds often leave these fields empty rather than `false`, so a
+    /// `false` search also matches listings where the field is null.
+    ///
+    /// ```
+    /// use reso_examples::search::PropertySearch;
+    ///
+    /// let search = PropertySearch::new().flag("NewConstructionYN", true).flag("WaterfrontYN", false);
+    /// assert_eq!(
+    ///     search.to_filter().as_deref(),
+    ///     Some("NewConstructionYN eq true and (WaterfrontYN eq false or WaterfrontYN eq null)")
+    /// );
+    /// ```
+    pub fn flag(self, field: &str, yes: bool) -> Self {
+        if yes {
+            self.compare(field, Operator::Eq, &true)
+        } else {
+            self.condition(format!("({} eq false or {} eq null)", field, field))
+        }
+    }
+
+    /// Listings inside the bounding box around a point.
+    ///
+    /// Like [`find_nearby`](crate::geo::find_nearby), this is a box rather
+    /// than a circle; use [`filter_by_distance`](crate::geo::filter_by_distance)
+    /// on the results for an exact radius.
+    pub fn near(self, lat: f64, lon: f64, radius_miles: f64) -> Self {
+        self.condition(BoundingBox::around(lat, lon, radius_miles).to_filter())
+    }
+
+    /// Compares any field, for criteria without a dedicated method.
+    ///
+    /// ```
+    /// use reso_examples::filter::Operator;
+    /// use reso_examples::search::PropertySearch;
+    ///
+    /// let search = PropertySearch::new().compare("StreetName", Operator::StartsWith, "Congress");
+    /// assert_eq!(search.to_filter().as_deref(), Some("startswith(StreetName,'Congress')"));
+    /// ```
+    pub fn compare<V: FilterValue + ?Sized>(self, field: &str, op: Operator, value: &V) -> Self {
+        self.condition(op.render(field, value))
+    }
+
+    /// Adds a raw OData condition for criteria the builder does not cover.
+    pub fn condition(mut self, expression: impl Into<String>) -> Self {
+        self.filters.push(expression.into());
+        self
+    }
+
+    /// Limits the fields returned.
+    pub fn select(mut self, fields: &[&str]) -> Self {
+        self.select = fields.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    /// Cheapest listings first.
+    pub fn cheapest_first(self) -> Self {
+        self.order_by("ListPrice", "asc")
+    }
+
+    /// Most recently listed first.
+    pub fn newest_first(self) -> Self {
+        self.order_by("ListingContractDate", "desc")
+    }
+
+    pub fn order_by(mut self, field: &str, direction: &str) -> Self {
+        self.order_by = Some((field.to_string(), direction.to_string()));
+        self
+    }
+
+    pub fn top(mut self, n: u32) -> Self {
+        self.top = Some(n);
+        self
+    }
+
+    pub fn skip(mut self, n: u32) -> Self {
+        self.skip = Some(n);
+        self
+    }
+
+    /// The combined filter expression, or `None` when no criteria were given.
+    pub fn to_filter(&self) -> Option<String> {
+        if self.filters.is_empty() {
+            None
+        } else {
+            Some(self.filters.join(" and "))
+        }
+    }
+
+    /// Compiles the search into a `Property` query.
+    ///
+    /// A sort on any field other than [`TIEBREAK_FIELD`] is followed by one
+    /// on it, e.g. `$orderby=ListPrice desc,ListingKey asc`.
+    pub fn build(self) -> Result<Query, ResoError> {
+        let mut builder = QueryBuilder::new("Property");
+
+        if let Some(filter) = self.to_filter() {
+            builder = builder.filter(filter);
+        }
+
+        if !self.select.is_empty() {
+            let fields: Vec<&str> = self.select.iter().map(String::as_str).collect();
+            builder = builder.select(&fields);
+        }
+
+        if let Some((field, direction)) = &self.order_by {
+            builder = stable_order_by(builder, field, direction);
+        }
+
+        if let Some(n) = self.top {
+            builder = builder.top(n);
+        }
+
+        if let Some(n) = self.skip {
+            builder = builder.skip(n);
+        }
+
+        builder.build()
+    }
+
+    fn string_eq(self, field: &str, value: &str) -> Self {
+        self.compare(field, Operator::Eq, value)
+    }
+
+    fn string_eq_ignore_case(self, field: &str, value: &str) -> Self {
+        self.compare(&format!("tolower({})", field), Operator::Eq, value.to_lowercase().as_str())
+    }
+}
+
+/// Sorts by `field`, then by [`TIEBREAK_FIELD`] ascending.
+///
+/// `QueryBuilder::order_by` takes a single key, so the tiebreaker is
+/// appended to the direction.
+///
+/// ```
+/// use reso_client::QueryBuilder;
+/// use reso_examples::search::stable_order_by;
+///
+/// let query = stable_order_by(QueryBuilder::new("Property"), "ListPrice", "desc").build().unwrap();
+/// assert_eq!(
+///     query.to_odata_string(),
+///     QueryBuilder::new("Property").order_by("ListPrice", "desc,ListingKey asc").build().unwrap().to_odata_string()
+/// );
+/// ```
+pub fn stable_order_by(builder: QueryBuilder, field: &str, direction: &str) -> QueryBuilder {
+    if field == TIEBREAK_FIELD {
+        return builder.order_by(field, direction);
+    }
+    builder.order_by(field, &format!("{},{} asc", direction, TIEBREAK_FIELD))
+}
+
+/// Listing search criteria as submitted by a search form or query string.
+///
+/// Every field is a string and an empty string means "any", matching how
+/// HTML forms submit blank inputs. [`SearchParams::to_search`] validates the
+/// values and translates them into a [`PropertySearch`].
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::search::SearchParams;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let params: SearchParams = serde_json::from_str(r#"{"city": "Austin", "min_beds": "3"}"#)?;
+/// assert_eq!(
+///     params.to_filter()?.as_deref(),
+///     Some("City eq 'Austin' and BedroomsTotal ge 3")
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchParams {
+    /// City name
+    pub city: String,
+    /// State or province code (e.g., TX, CA)
+    pub state: String,
+    /// `StandardStatus`, one of [`STANDARD_STATUSES`]
+    pub status: String,
+    /// Minimum listing price in whole dollars
+    pub min_price: String,
+    /// Maximum listing price in whole dollars
+    pub max_price: String,
+    /// Minimum number of bedrooms
+    pub min_beds: String,
+    /// Maximum number of bedrooms
+    pub max_beds: String,
+    /// Minimum number of bathrooms
+    pub min_baths: String,
+    /// Property type (Residential, Commercial, Land, ...)
+    pub property_type: String,
+    /// Assigned elementary school
+    pub elementary_school: String,
+    /// Assigned middle or junior high school
+    pub middle_school: String,
+    /// Assigned high school
+    pub high_school: String,
+    /// Zoning code, e.g. SF-3
+    pub zoning: String,
+    /// Maximum number of results (default [`DEFAULT_LIMIT`], at most [`MAX_LIMIT`])
+    pub limit: String,
+    /// Page of results, counting from 1, each `limit` results long
+    pub page: String,
+    /// Sort field, a key of [`SORT_FIELDS`]; empty sorts by [`TIEBREAK_FIELD`]
+    pub sort_by: String,
+    /// Sort direction, `asc` (the default) or `desc`
+    pub sort_dir: String,
+}
+
+impl SearchParams {
+    /// Validates the parameters and builds the equivalent search.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ResoError::InvalidQuery` naming the offending parameter when
+    /// a number does not parse, a range is inverted, or the status is not a
+    /// standard value.
+    pub fn to_search(&self) -> Result<PropertySearch, ResoError> {
+        let mut search = PropertySearch::new();
+
+        if let Some(city) = non_empty(&self.city) {
+            search = search.city(city);
+        }
+        if let Some(state) = non_empty(&self.state) {
+            search = search.state(state);
+        }
+        if let Some(status) = non_empty(&self.status) {
+            let status = STANDARD_STATUSES
+                .iter()
+                .find(|s| s.eq_ignore_ascii_case(status))
+                .ok_or_else(|| {
+                    ResoError::InvalidQuery(format!(
+                        "status: '{}' is not one of {}",
+                        status,
+                        STANDARD_STATUSES.join(", ")
+                    ))
+                })?;
+            search = search.status(status);
+        }
+
+        let min_price = parse_number::<u64>("min_price", &self.min_price)?;
+        let max_price = parse_number::<u64>("max_price", &self.max_price)?;
+        check_range("price", min_price, max_price)?;
+        if let Some(price) = min_price {
+            search = search.min_price(price);
+        }
+        if let Some(price) = max_price {
+            search = search.max_price(price);
+        }
+
+        let min_beds = parse_number::<u32>("min_beds", &self.min_beds)?;
+        let max_beds = parse_number::<u32>("max_beds", &self.max_beds)?;
+        check_range("beds", min_beds, max_beds)?;
+        if let Some(beds) = min_beds {
+            search = search.min_beds(beds);
+        }
+        if let Some(beds) = max_beds {
+            search = search.max_beds(beds);
+        }
+
+        if let Some(baths) = parse_number::<u32>("min_baths", &self.min_baths)? {
+            search = search.min_baths(baths);
+        }
+        if let Some(property_type) = non_empty(&self.property_type) {
+            search = search.property_type(property_type);
+        }
+        if let Some(school) = non_empty(&self.elementary_school) {
+            search = search.elementary_school(school);
+        }
+        if let Some(school) = non_empty(&self.middle_school) {
+            search = search.middle_school(school);
+        }
+        if let Some(school) = non_empty(&self.high_school) {
+            search = search.high_school(school);
+        }
+        if let Some(zoning) = non_empty(&self.zoning) {
+            search = search.zoning(zoning);
+        }
+
+        let (field, direction) = self.order_by()?.unwrap_or((TIEBREAK_FIELD, "asc"));
+        let search = search.top(self.limit()?).order_by(field, direction);
+        match self.offset()? {
+            0 => Ok(search),
+            offset => Ok(search.skip(offset)),
+        }
+    }
+
+    /// The OData filter for these parameters, or `None` when all are empty.
+    pub fn to_filter(&self) -> Result<Option<String>, ResoError> {
+        Ok(self.to_search()?.to_filter())
+    }
+
+    /// The requested result limit, capped at [`MAX_LIMIT`].
+    pub fn limit(&self) -> Result<u32, ResoError> {
+        let limit = parse_number::<u32>("limit", &self.limit)?.unwrap_or(DEFAULT_LIMIT);
+        if limit == 0 {
+            return Err(ResoError::InvalidQuery("limit: must be at least 1".to_string()));
+        }
+        Ok(limit.min(MAX_LIMIT))
+    }
+
+    /// The requested page, counting from 1.
+    pub fn page(&self) -> Result<u32, ResoError> {
+        match parse_number::<u32>("page", &self.page)? {
+            Some(0) => Err(ResoError::InvalidQuery("page: must be at least 1".to_string())),
+            page => Ok(page.unwrap_or(1)),
+        }
+    }
+
+    /// The sort field and direction, or `None` when `sort_by` is empty.
+    ///
+    /// Only the fields in [`SORT_FIELDS`] are accepted, so a query string
+    /// cannot order by an unindexed or nonexistent field.
+    pub fn order_by(&self) -> Result<Option<(&'static str, &'static str)>, ResoError> {
+        let Some(sort_by) = non_empty(&self.sort_by) else {
+            return Ok(None);
+        };
+        let (_, field) = SORT_FIELDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(sort_by))
+            .ok_or_else(|| {
+                let names: Vec<&str> = SORT_FIELDS.iter().map(|(name, _)| *name).collect();
+                ResoError::InvalidQuery(format!("sort_by: '{}' is not one of {}", sort_by, names.join(", ")))
+            })?;
+        let direction = match non_empty(&self.sort_dir).map(str::to_ascii_lowercase).as_deref() {
+            None | Some("asc") => "asc",
+            Some("desc") => "desc",
+            Some(_) => {
+                return Err(ResoError::InvalidQuery(format!(
+                    "sort_dir: '{}' is not asc or desc",
+                    self.sort_dir
+                )))
+            }
+        };
+        Ok(Some((field, direction)))
+    }
+
+    /// How many results come before the requested page.
+    pub fn offset(&self) -> Result<u32, ResoError> {
+        (self.page()? - 1)
+            .checked_mul(self.limit()?)
+            .ok_or_else(|| ResoError::InvalidQuery("page: too large".to_string()))
+    }
+
+    /// Rejects searches that exceed `limits`, before any request is made.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reso_examples::search::{SearchLimits, SearchParams};
+    ///
+    /// let limits = SearchLimits {
+    ///     max_limit: 25,
+    ///     max_filter_terms: 3,
+    ///     ..Default::default()
+    /// };
+    /// let params = SearchParams {
+    ///     city: "Austin".into(),
+    ///     limit: "50".into(),
+    ///     ..Default::default()
+    /// };
+    /// assert!(params.check_limits(&limits).is_err());
+    /// ```
+    pub fn check_limits(&self, limits: &SearchLimits) -> Result<(), ResoError> {
+        let values = [
+            ("city", &self.city),
+            ("state", &self.state),
+            ("status", &self.status),
+            ("min_price", &self.min_price),
+            ("max_price", &self.max_price),
+            ("min_beds", &self.min_beds),
+            ("max_beds", &self.max_beds),
+            ("min_baths", &self.min_baths),
+            ("property_type", &self.property_type),
+            ("elementary_school", &self.elementary_school),
+            ("middle_school", &self.middle_school),
+            ("high_school", &self.high_school),
+            ("zoning", &self.zoning),
+            ("limit", &self.limit),
+            ("page", &self.page),
+            ("sort_by", &self.sort_by),
+            ("sort_dir", &self.sort_dir),
+        ];
+        if let Some((name, _)) = values.iter().find(|(_, v)| v.len() > limits.max_value_length) {
+            return Err(ResoError::InvalidQuery(format!(
+                "{}: longer than {} characters",
+                name, limits.max_value_length
+            )));
+        }
+
+        // Each non-empty criterion becomes one condition of the filter
+        let terms = values
+            .iter()
+            .filter(|(name, value)| !matches!(*name, "limit" | "page" | "sort_by" | "sort_dir") && non_empty(value).is_some())
+            .count();
+        if terms > limits.max_filter_terms {
+            return Err(ResoError::InvalidQuery(format!(
+                "search has {} conditions, at most {} are allowed",
+                terms, limits.max_filter_terms
+            )));
+        }
+
+        let requested = parse_number::<u32>("limit", &self.limit)?.unwrap_or(DEFAULT_LIMIT);
+        if requested > limits.max_limit {
+            return Err(ResoError::InvalidQuery(format!(
+                "limit: at most {} results may be requested",
+                limits.max_limit
+            )));
+        }
+        if self.offset()? > limits.max_offset {
+            return Err(ResoError::InvalidQuery(format!(
+                "page: results beyond the first {} cannot be paged to; narrow the search",
+                limits.max_offset
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Caps on how expensive a [`SearchParams`] search may be, for services
+/// exposed to the public in front of a metered feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Largest `limit` accepted; larger values are rejected rather than capped
+    pub max_limit: u32,
+    /// Most conditions the generated filter may combine
+    pub max_filter_terms: usize,
+    /// Longest value accepted for any parameter, in bytes
+    pub max_value_length: usize,
+    /// Most results a search may page past
+    pub max_offset: u32,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            max_limit: MAX_LIMIT,
+            max_filter_terms: 10,
+            max_value_length: 100,
+            max_offset: MAX_OFFSET,
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    let value = value.trim();
+    (!value.is_empty()).then_some(value)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<Option<T>, ResoError> {
+    non_empty(value)
+        .map(|v| {
+            v.parse().map_err(|_| {
+                ResoError::InvalidQuery(format!("{}: '{}' is not a whole number", name, v))
+            })
+        })
+        .transpose()
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    name: &str,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), ResoError> {
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err(ResoError::InvalidQuery(format!(
+            "{}: minimum {} is greater than maximum {}",
+            name, min, max
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Renders an OData string literal, doubling embedded single quotes.
+pub(crate) fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_filter_combines_criteria() {
+        let search = PropertySearch::new()
+            .city("Austin")
+            .price_range(300_000, 500_000)
+            .min_beds(3)
+            .active();
+        assert_eq!(
+            search.to_filter().unwrap(),
+            "City eq 'Austin' and ListPrice ge 300000 and ListPrice le 500000 \
+             and BedroomsTotal ge 3 and StandardStatus eq 'Active'"
+        );
+        assert!(PropertySearch::new().to_filter().is_none());
+    }
+
+    #[test]
+    fn test_flag_shortcuts_treat_null_as_no() {
+        let search = PropertySearch::new().waterfront(true).private_pool(false);
+        assert_eq!(
+            search.to_filter().unwrap(),
+            "WaterfrontYN eq true and (PoolPrivateYN eq false or PoolPrivateYN eq null)"
+        );
+    }
+
+    #[test]
+    fn test_string_values_are_escaped() {
+        let search = PropertySearch::new().city("Coeur d'Alene");
+        assert_eq!(search.to_filter().unwrap(), "City eq 'Coeur d''Alene'");
+    }
+
+    #[test]
+    fn test_search_params_validation() {
+        let params = SearchParams {
+            status: "active".into(),
+            min_price: "100000".into(),
+            limit: "500".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            params.to_filter().unwrap().unwrap(),
+            "StandardStatus eq 'Active' and ListPrice ge 100000"
+        );
+        assert_eq!(params.limit().unwrap(), MAX_LIMIT);
+
+        let bad_number = SearchParams {
+            min_beds: "three".into(),
+            ..Default::default()
+        };
+        assert!(matches!(bad_number.to_filter(), Err(ResoError::InvalidQuery(m)) if m.contains("min_beds")));
+
+        let inverted = SearchParams {
+            min_price: "500".into(),
+            max_price: "100".into(),
+            ..Default::default()
+        };
+        assert!(inverted.to_filter().is_err());
+        assert_eq!(SearchParams::default().to_filter().unwrap(), None);
+
+        let sorted = SearchParams {
+            sort_by: "Price".into(),
+            sort_dir: "DESC".into(),
+            ..Default::default()
+        };
+        assert_eq!(sorted.order_by().unwrap(), Some(("ListPrice", "desc")));
+        assert_eq!(
+            sorted.to_search().unwrap().build().unwrap().to_odata_string(),
+            QueryBuilder::new("Property")
+                .order_by("ListPrice", "desc,ListingKey asc")
+                .top(DEFAULT_LIMIT)
+                .build()
+                .unwrap()
+                .to_odata_string()
+        );
+        assert_eq!(
+            SearchParams::default().to_search().unwrap().build().unwrap().to_odata_string(),
+            QueryBuilder::new("Property").order_by("ListingKey", "asc").top(DEFAULT_LIMIT).build().unwrap().to_odata_string()
+        );
+        let unlisted = SearchParams {
+            sort_by: "PrivateRemarks".into(),
+            ..Default::default()
+        };
+        assert!(matches!(unlisted.order_by(), Err(ResoError::InvalidQuery(m)) if m.starts_with("sort_by")));
+        assert!(SearchParams { sort_dir: "up".into(), ..sorted }.order_by().is_err());
+        assert_eq!(SearchParams::default().order_by().unwrap(), None);
+    }
+
+    #[test]
+    fn test_search_params_match_school_and_zoning_ignoring_case() {
+        let params = SearchParams {
+            high_school: "  McCallum ".into(),
+            zoning: "sf-3".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            params.to_filter().unwrap().unwrap(),
+            "tolower(HighSchool) eq 'mccallum' and tolower(Zoning) eq 'sf-3'"
+        );
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let limits = SearchLimits {
+            max_limit: 25,
+            max_filter_terms: 2,
+            max_value_length: 20,
+            max_offset: 100,
+        };
+        let within = SearchParams {
+            city: "Austin".into(),
+            min_price: "100000".into(),
+            limit: "25".into(),
+            ..Default::default()
+        };
+        assert!(within.check_limits(&limits).is_ok());
+
+        let too_many_terms = SearchParams {
+            min_beds: "3".into(),
+            ..within.clone()
+        };
+        assert!(matches!(too_many_terms.check_limits(&limits), Err(ResoError::InvalidQuery(m)) if m.contains("conditions")));
+        let long_value = SearchParams {
+            city: "x".repeat(21),
+            ..within.clone()
+        };
+        assert!(matches!(long_value.check_limits(&limits), Err(ResoError::InvalidQuery(m)) if m.starts_with("city")));
+        let large_limit = SearchParams {
+            limit: "26".into(),
+            ..within.clone()
+        };
+        assert!(large_limit.check_limits(&limits).is_err());
+
+        // Page 5 of 25 starts after result 100, page 6 beyond the cap
+        let page = |page: &str| SearchParams {
+            page: page.into(),
+            ..within.clone()
+        };
+        assert_eq!(page("5").offset().unwrap(), 100);
+        assert!(page("5").check_limits(&limits).is_ok());
+        assert!(matches!(page("6").check_limits(&limits), Err(ResoError::InvalidQuery(m)) if m.starts_with("page")));
+        assert!(page("0").offset().is_err());
+        assert_eq!(within.offset().unwrap(), 0);
+    }
+}
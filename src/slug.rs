@@ -0,0 +1,137 @@
+//! Readable URL slugs for listing pages.
+//!
+//! A slug such as `123-main-st-austin-tx-78701-ACT1234` tells people and
+//! search engines where a listing is before they open it, while still
+//! carrying the `ListingKey` needed to load it. [`listing_slug`] builds the
+//! slug from the address fields and [`parse_listing_slug`] recovers the key,
+//! so the address part can change without breaking old links.
+
+use crate::fields::property as field;
+use crate::schema_org::street_address;
+use reso_client::JsonValue;
+
+/// Builds the slug for a Property record: its street, city, state, and
+/// postal code in lowercase words joined by hyphens, followed by the
+/// `ListingKey`.
+///
+/// The street comes from the street fields, falling back to the part of
+/// `UnparsedAddress` before the first comma. Characters other than ASCII
+/// letters and digits separate words; apostrophes are dropped. The key
+/// keeps its case, and any hyphens in it are doubled so
+/// [`parse_listing_slug`] can tell where it starts. Returns `None` for a
+/// record without a `ListingKey`.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::slug::{listing_slug, parse_listing_slug};
+/// use serde_json::json;
+///
+/// let listing = json!({
+///     "ListingKey": "ACT-1234",
+///     "StreetNumber": "123",
+///     "StreetName": "Main",
+///     "StreetSuffix": "St.",
+///     "City": "Austin",
+///     "StateOrProvince": "TX",
+///     "PostalCode": "78701"
+/// });
+/// let slug = listing_slug(&listing).unwrap();
+///
+/// assert_eq!(slug, "123-main-st-austin-tx-78701-ACT--1234");
+/// assert_eq!(parse_listing_slug(&slug).as_deref(), Some("ACT-1234"));
+/// ```
+pub fn listing_slug(record: &JsonValue) -> Option<String> {
+    let key = record[field::LISTING_KEY].as_str().filter(|key| !key.is_empty())?;
+    let street = street_address(record).or_else(|| {
+        let unparsed = record[field::UNPARSED_ADDRESS].as_str()?;
+        unparsed.split(',').next().map(str::to_string)
+    });
+    let address: Vec<String> = [
+        street.as_deref(),
+        record[field::CITY].as_str(),
+        record[field::STATE_OR_PROVINCE].as_str(),
+        record[field::POSTAL_CODE].as_str(),
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(slug_words)
+    .collect();
+
+    let key = key.replace('-', "--");
+    Some(if address.is_empty() {
+        key
+    } else {
+        format!("{}-{}", address.join("-"), key)
+    })
+}
+
+/// Recovers the `ListingKey` from a slug made by [`listing_slug`].
+///
+/// Only the key is read, so a slug whose address part is out of date, or
+/// missing altogether, still finds the listing. Returns `None` if the slug
+/// has no key.
+pub fn parse_listing_slug(slug: &str) -> Option<String> {
+    // Hyphens in the key are doubled and the address never has two in a
+    // row, so the separator is the first hyphen of the last odd-length run
+    let bytes = slug.as_bytes();
+    let mut key_start = 0;
+    let mut i = bytes.len();
+    while i > 0 {
+        if bytes[i - 1] != b'-' {
+            i -= 1;
+            continue;
+        }
+        let run_end = i;
+        while i > 0 && bytes[i - 1] == b'-' {
+            i -= 1;
+        }
+        if (run_end - i) % 2 == 1 {
+            key_start = i + 1;
+            break;
+        }
+    }
+    let key = slug[key_start..].replace("--", "-");
+    (!key.is_empty()).then_some(key)
+}
+
+/// Lowercase ASCII words of `value`, e.g. "O'Neil Rd." becomes `["oneil", "rd"]`.
+fn slug_words(value: &str) -> Vec<String> {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '’'))
+        .collect::<String>()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_slug_round_trips_awkward_keys() {
+        let listing = json!({
+            "ListingKey": "-3yd-ACT-",
+            "UnparsedAddress": "  1200 O'Neil  Rd. #4B, Round Rock, TX 78664",
+            "City": "Round Rock",
+            "StateOrProvince": "TX",
+            "PostalCode": "78664"
+        });
+        let slug = listing_slug(&listing).unwrap();
+        assert_eq!(slug, "1200-oneil-rd-4b-round-rock-tx-78664---3yd--ACT--");
+        assert_eq!(parse_listing_slug(&slug).as_deref(), Some("-3yd-ACT-"));
+
+        // A stale or missing address part still resolves to the key
+        assert_eq!(parse_listing_slug("old-address-A1").as_deref(), Some("A1"));
+        assert_eq!(parse_listing_slug("A1").as_deref(), Some("A1"));
+        assert_eq!(listing_slug(&json!({"ListingKey": "A-1"})).as_deref(), Some("A--1"));
+        assert_eq!(parse_listing_slug("A--1").as_deref(), Some("A-1"));
+
+        assert_eq!(listing_slug(&json!({"City": "Austin"})), None);
+        assert_eq!(parse_listing_slug("main-st-"), None);
+    }
+}
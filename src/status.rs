@@ -0,0 +1,349 @@
+//! Shared sync status for monitoring.
+//!
+//! Sync runs record their progress in a small JSON status file: per resource,
+//! the run state, record and batch counts, throughput, errors, and any
+//! rate-limit pause. Monitoring tools such as `reso dashboard` poll the file.
+//! Writes replace the file atomically so readers never see a partial update.
+//!
+//! Each finished run is also appended to a per-resource ledger in the same
+//! file, so [`StatusFile::list_runs`] can answer "did last night's sync
+//! finish?" after later runs have reset the live counters.
+
+use crate::export::{OutputFile, RecordSink, StoredCounts};
+use crate::metadata::EntityType;
+use crate::migrations::SchemaChanges;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reso_client::{JsonValue, ResoError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Default status file location.
+pub const DEFAULT_STATUS_FILE: &str = ".reso_status.json";
+
+/// Runs kept in each resource's ledger; older runs are dropped.
+pub const MAX_RUNS: usize = 100;
+
+/// State of a resource's most recent sync run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyncState {
+    #[default]
+    Idle,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of one resource's sync.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceStatus {
+    pub state: SyncState,
+    /// Records written by the current (or last) run
+    pub records: u64,
+    /// Batches written by the current (or last) run
+    pub batches: u64,
+    /// Errors recorded across runs
+    pub errors: u64,
+    pub last_error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Time until which the server asked us to back off
+    pub rate_limited_until: Option<DateTime<Utc>>,
+    /// Checkpoint position when the current (or last) run started
+    #[serde(default)]
+    pub checkpoint: Option<String>,
+    /// Finished runs, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runs: Vec<SyncRun>,
+}
+
+/// A finished sync run in the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncRun {
+    pub resource: String,
+    /// [`SyncState::Completed`] or [`SyncState::Failed`]
+    pub state: SyncState,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: DateTime<Utc>,
+    pub records: u64,
+    pub batches: u64,
+    /// The error that ended a failed run
+    pub error: Option<String>,
+    /// Checkpoint position when the run started
+    pub checkpoint_before: Option<String>,
+    /// Checkpoint position when the run finished
+    pub checkpoint_after: Option<String>,
+}
+
+impl ResourceStatus {
+    /// Average records per second since the run started.
+    pub fn records_per_sec(&self) -> f64 {
+        match (self.started_at, self.updated_at) {
+            (Some(start), Some(end)) => {
+                let secs = (end - start).num_milliseconds() as f64 / 1000.0;
+                if secs > 0.0 {
+                    self.records as f64 / secs
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Returns true if a rate-limit pause is in effect at `now`.
+    pub fn is_rate_limited(&self, now: DateTime<Utc>) -> bool {
+        self.rate_limited_until.is_some_and(|until| until > now)
+    }
+
+    /// Adds the run that just finished to the ledger.
+    fn record_run(&mut self, resource: &str, error: Option<String>, checkpoint: Option<&str>) {
+        self.runs.push(SyncRun {
+            resource: resource.to_string(),
+            state: self.state,
+            started_at: self.started_at,
+            finished_at: Utc::now(),
+            records: self.records,
+            batches: self.batches,
+            error,
+            checkpoint_before: self.checkpoint.clone(),
+            checkpoint_after: checkpoint.map(str::to_string),
+        });
+        if self.runs.len() > MAX_RUNS {
+            self.runs.drain(..self.runs.len() - MAX_RUNS);
+        }
+    }
+}
+
+/// A status file holding the progress of every resource.
+#[derive(Debug, Clone)]
+pub struct StatusFile {
+    path: PathBuf,
+}
+
+impl StatusFile {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads all resource statuses; a missing file means nothing has run.
+    pub fn read(&self) -> Result<BTreeMap<String, ResourceStatus>, ResoError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| {
+                ResoError::Parse(format!("Invalid status file {}: {}", self.path.display(), e))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(ResoError::Config(format!(
+                "Cannot read status file {}: {}",
+                self.path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Applies a change to one resource's status and writes the file.
+    pub fn update(
+        &self,
+        resource: &str,
+        change: impl FnOnce(&mut ResourceStatus),
+    ) -> Result<(), ResoError> {
+        let mut statuses = self.read()?;
+        let status = statuses.entry(resource.to_string()).or_default();
+        change(status);
+        status.updated_at = Some(Utc::now());
+
+        let json = serde_json::to_string_pretty(&statuses).map_err(|e| ResoError::Parse(e.to_string()))?;
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| {
+                ResoError::Config(format!(
+                    "Cannot write status file {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+
+    /// Marks a run as started from `checkpoint`, resetting its counters.
+    pub fn start(&self, resource: &str, checkpoint: Option<&str>) -> Result<(), ResoError> {
+        self.update(resource, |s| {
+            s.state = SyncState::Running;
+            s.records = 0;
+            s.batches = 0;
+            s.started_at = Some(Utc::now());
+            s.checkpoint = checkpoint.map(str::to_string);
+        })
+    }
+
+    /// Marks a run as completed at `checkpoint` and adds it to the ledger.
+    pub fn complete(&self, resource: &str, checkpoint: Option<&str>) -> Result<(), ResoError> {
+        self.update(resource, |s| {
+            s.state = SyncState::Completed;
+            s.record_run(resource, None, checkpoint);
+        })
+    }
+
+    /// Marks a run as failed at `checkpoint`, recording the error and any
+    /// rate-limit pause, and adds it to the ledger.
+    pub fn fail(&self, resource: &str, error: &ResoError, checkpoint: Option<&str>) -> Result<(), ResoError> {
+        let message = error.to_string();
+        let rate_limited = matches!(error, ResoError::RateLimited { .. });
+        self.update(resource, |s| {
+            s.state = SyncState::Failed;
+            s.errors += 1;
+            s.last_error = Some(message.clone());
+            if rate_limited {
+                s.rate_limited_until = Some(Utc::now() + chrono::Duration::seconds(60));
+            }
+            s.record_run(resource, Some(message), checkpoint);
+        })
+    }
+
+    /// Finished runs of every resource, most recent first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use reso_examples::status::{StatusFile, SyncState, DEFAULT_STATUS_FILE};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let status = StatusFile::new(DEFAULT_STATUS_FILE);
+    /// let last = status.list_runs()?.into_iter().find(|run| run.resource == "Property");
+    /// match last {
+    ///     Some(run) if run.state == SyncState::Completed => println!("Finished at {}", run.finished_at),
+    ///     Some(run) => println!("Failed: {}", run.error.unwrap_or_default()),
+    ///     None => println!("Never run"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_runs(&self) -> Result<Vec<SyncRun>, ResoError> {
+        let mut runs: Vec<SyncRun> = self
+            .read()?
+            .into_values()
+            .flat_map(|s| s.runs.into_iter().rev())
+            .collect();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.finished_at));
+        Ok(runs)
+    }
+}
+
+/// A sink wrapper that records each written batch in a status file.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::status::{StatusFile, StatusSink};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let status = StatusFile::new(".reso_status.json");
+/// let sink = StatusSink::new(JsonlSink::append("property.jsonl")?, status, "Property");
+/// # Ok(())
+/// # }
+/// ```
+pub struct StatusSink<S> {
+    inner: S,
+    status: StatusFile,
+    resource: String,
+}
+
+impl<S: RecordSink> StatusSink<S> {
+    pub fn new(inner: S, status: StatusFile, resource: &str) -> Self {
+        Self {
+            inner,
+            status,
+            resource: resource.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: RecordSink> RecordSink for StatusSink<S> {
+    async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+        self.inner.write_batch(records).await?;
+        let count = records.len() as u64;
+        self.status.update(&self.resource, |s| {
+            s.records += count;
+            s.batches += 1;
+            s.rate_limited_until = None;
+        })
+    }
+
+    async fn flush(&mut self) -> Result<(), ResoError> {
+        self.inner.flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<(), ResoError> {
+        self.inner.finalize().await
+    }
+
+    async fn evolve_schema(&mut self, entity_type: &EntityType) -> Result<SchemaChanges, ResoError> {
+        self.inner.evolve_schema(entity_type).await
+    }
+
+    async fn count_stored(&mut self, group_by: Option<&str>) -> Result<Option<StoredCounts>, ResoError> {
+        self.inner.count_stored(group_by).await
+    }
+
+    fn output_files(&self) -> Vec<OutputFile> {
+        self.inner.output_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct NullSink;
+
+    #[async_trait]
+    impl RecordSink for NullSink {
+        async fn write_batch(&mut self, _records: &[JsonValue]) -> Result<(), ResoError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_sink_records_progress() {
+        let path = std::env::temp_dir().join(format!("reso-status-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let status = StatusFile::new(&path);
+
+        status.start("Property", Some("2025-01-01T00:00:00Z")).unwrap();
+        let mut sink = StatusSink::new(NullSink, status.clone(), "Property");
+        sink.write_batch(&[json!({}), json!({})]).await.unwrap();
+        sink.write_batch(&[json!({})]).await.unwrap();
+        status
+            .fail("Property", &ResoError::Network("connection reset".into()), None)
+            .unwrap();
+
+        let statuses = status.read().unwrap();
+        let property = &statuses["Property"];
+        assert_eq!(property.state, SyncState::Failed);
+        assert_eq!((property.records, property.batches, property.errors), (3, 2, 1));
+        assert!(property.last_error.as_deref().unwrap().contains("connection reset"));
+
+        // A later successful run resets the counters but the ledger keeps both
+        status.start("Property", Some("2025-01-01T00:00:00Z")).unwrap();
+        sink.write_batch(&[json!({})]).await.unwrap();
+        status.complete("Property", Some("2025-01-02T00:00:00Z")).unwrap();
+        let runs = status.list_runs().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!((runs[0].state, runs[0].records), (SyncState::Completed, 1));
+        assert_eq!(runs[0].checkpoint_before.as_deref(), Some("2025-01-01T00:00:00Z"));
+        assert_eq!(runs[0].checkpoint_after.as_deref(), Some("2025-01-02T00:00:00Z"));
+        assert_eq!((runs[1].state, runs[1].records, runs[1].batches), (SyncState::Failed, 3, 2));
+        assert!(runs[1].error.as_deref().unwrap().contains("connection reset"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,292 @@
+//! Record-at-a-time parsing of large replication responses.
+//!
+//! A replication batch of 10,000 listings can be tens of megabytes, and
+//! parsing it into one `JsonValue` holds the whole body and every record in
+//! memory at once. A [`RecordStream`] instead parses the `value` array as
+//! the body arrives and hands records over in small batches, so memory stays
+//! bounded by the batch size rather than the page size.
+//! [`HttpClient::stream_replication`](crate::client::HttpClient::stream_replication)
+//! starts one, and [`replicate_streaming`](crate::sync::replicate_streaming)
+//! drives them into a sink.
+//!
+//! The body must be OData JSON; a client's
+//! [`response_format`](crate::client::HttpClientBuilder::response_format)
+//! does not apply.
+
+use reso_client::{JsonValue, ResoError};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::io::{self, BufReader, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Body chunks buffered between the network and the parser.
+const CHUNK_BUFFER: usize = 8;
+
+/// Records buffered between the parser and the consumer.
+const RECORD_BUFFER: usize = 256;
+
+/// Records of one replication response, parsed as its body arrives.
+///
+/// Dropping the stream before the end cancels the rest of the download.
+pub struct RecordStream {
+    records: mpsc::Receiver<JsonValue>,
+    parser: Option<JoinHandle<Result<Option<String>, ResoError>>>,
+    next_link: Option<String>,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl RecordStream {
+    /// Starts parsing `response`, whose headers name `next_link` as the
+    /// next page (if any). An `@odata.nextLink` in the body takes precedence.
+    pub(crate) fn new(mut response: reqwest::Response, next_link: Option<String>) -> Self {
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let counter = bytes_read.clone();
+        let (chunk_tx, chunk_rx) = mpsc::channel(CHUNK_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let chunk = match response.chunk().await {
+                    Ok(Some(chunk)) => {
+                        counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                        Ok(chunk.to_vec())
+                    }
+                    Ok(None) => return,
+                    Err(e) => Err(e.to_string()),
+                };
+                let failed = chunk.is_err();
+                // Stops downloading once the parser has given up
+                if chunk_tx.send(chunk).await.is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        let (record_tx, records) = mpsc::channel(RECORD_BUFFER);
+        let parser = tokio::task::spawn_blocking(move || {
+            let reader = BufReader::new(ChunkReader {
+                chunks: chunk_rx,
+                current: Vec::new(),
+                position: 0,
+            });
+            parse_records(reader, |record| record_tx.blocking_send(record).is_ok())
+        });
+
+        Self {
+            records,
+            parser: Some(parser),
+            next_link,
+            bytes_read,
+        }
+    }
+
+    /// Up to `max` more records, or `None` once the response is exhausted.
+    ///
+    /// Waits until `max` records have arrived or the body ends, so every
+    /// batch but the last is full.
+    pub async fn next_batch(&mut self, max: usize) -> Result<Option<Vec<JsonValue>>, ResoError> {
+        let mut batch = Vec::new();
+        while batch.len() < max.max(1) {
+            match self.records.recv().await {
+                Some(record) => batch.push(record),
+                None => break,
+            }
+        }
+        if batch.is_empty() {
+            self.finish().await?;
+            return Ok(None);
+        }
+        Ok(Some(batch))
+    }
+
+    /// The link to the next page, known once [`next_batch`](Self::next_batch)
+    /// has returned `None`, since `@odata.nextLink` may follow the records.
+    pub fn next_link(&self) -> Option<&str> {
+        self.next_link.as_deref()
+    }
+
+    /// Bytes of the response body received so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the parser, surfacing any error it stopped on.
+    async fn finish(&mut self) -> Result<(), ResoError> {
+        if let Some(parser) = self.parser.take() {
+            let body_link = parser
+                .await
+                .map_err(|e| ResoError::Parse(format!("Replication parser failed: {}", e)))??;
+            if body_link.is_some() {
+                self.next_link = body_link;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RecordStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordStream")
+            .field("next_link", &self.next_link)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses an OData response from `reader`, passing each element of its
+/// `value` array to `on_record` and returning its `@odata.nextLink`.
+///
+/// Stops early, with an error, when `on_record` returns false.
+pub(crate) fn parse_records(
+    reader: impl Read,
+    mut on_record: impl FnMut(JsonValue) -> bool,
+) -> Result<Option<String>, ResoError> {
+    let mut next_link = None;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let envelope = Envelope {
+        on_record: &mut on_record,
+        next_link: &mut next_link,
+    };
+    envelope
+        .deserialize(&mut deserializer)
+        .and_then(|()| deserializer.end())
+        .map_err(|e| match e.is_io() {
+            true => ResoError::Network(format!("Failed to read replication response: {}", e)),
+            false => ResoError::Parse(format!("Invalid replication response: {}", e)),
+        })?;
+    Ok(next_link)
+}
+
+/// The response object: `value` is streamed, `@odata.nextLink` kept, and
+/// everything else skipped.
+struct Envelope<'a, F> {
+    on_record: &'a mut F,
+    next_link: &'a mut Option<String>,
+}
+
+impl<'de, F: FnMut(JsonValue) -> bool> DeserializeSeed<'de> for Envelope<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(JsonValue) -> bool> Visitor<'de> for Envelope<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an OData response object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "value" => map.next_value_seed(Records {
+                    on_record: &mut *self.on_record,
+                })?,
+                "@odata.nextLink" => *self.next_link = map.next_value()?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `value` array, handed over one record at a time.
+struct Records<'a, F> {
+    on_record: &'a mut F,
+}
+
+impl<'de, F: FnMut(JsonValue) -> bool> DeserializeSeed<'de> for Records<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(JsonValue) -> bool> Visitor<'de> for Records<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an array of records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(record) = seq.next_element::<JsonValue>()? {
+            if !(self.on_record)(record) {
+                return Err(de::Error::custom("record stream closed"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Blocking reader over body chunks sent from the download task.
+struct ChunkReader {
+    chunks: mpsc::Receiver<Result<Vec<u8>, String>>,
+    current: Vec<u8>,
+    position: usize,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.current.len() {
+            match self.chunks.blocking_recv() {
+                Some(Ok(chunk)) => {
+                    self.current = chunk;
+                    self.position = 0;
+                }
+                Some(Err(message)) => return Err(io::Error::other(message)),
+                None => return Ok(0),
+            }
+        }
+        let count = buf.len().min(self.current.len() - self.position);
+        buf[..count].copy_from_slice(&self.current[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_records_streams_value_and_finds_next_link() {
+        let body = json!({
+            "@odata.context": "$metadata#Property",
+            "value": [
+                {"ListingKey": "A", "Rooms": [{"RoomType": "Kitchen"}]},
+                {"ListingKey": "B", "Rooms": []}
+            ],
+            "@odata.nextLink": "https://example.com/Property/replication?next=abc"
+        })
+        .to_string();
+
+        let mut keys = Vec::new();
+        let next_link = parse_records(body.as_bytes(), |record| {
+            keys.push(record["ListingKey"].as_str().unwrap().to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(keys, ["A", "B"]);
+        assert_eq!(next_link.as_deref(), Some("https://example.com/Property/replication?next=abc"));
+
+        // A consumer that stops early ends the parse
+        let mut seen = 0;
+        assert!(parse_records(body.as_bytes(), |_| {
+            seen += 1;
+            false
+        })
+        .is_err());
+        assert_eq!(seen, 1);
+
+        let truncated = &body[..body.len() / 2];
+        assert!(matches!(parse_records(truncated.as_bytes(), |_| true), Err(ResoError::Parse(_))));
+    }
+}
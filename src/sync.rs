@@ -0,0 +1,1270 @@
+//! Checkpointed replication of a resource into a [`RecordSink`].
+//!
+//! [`replicate_all`] follows replication `next` links until the dataset is
+//! exhausted, writing each batch to a sink. Progress is recorded in a
+//! [`Checkpoint`]: while a run is in progress it holds the next link so an
+//! interrupted run can resume, and once a run completes it holds the highest
+//! `ModificationTimestamp` seen so the next run only fetches changes.
+//!
+//! [`incremental_sync`] is the same pattern without a sink or checkpoint
+//! file, for callers that keep their own high-water mark and want the
+//! changed records in memory.
+
+use crate::cancel::{cancellable, CancellationToken};
+use crate::client::{is_timeout, HttpClient, ResoApi};
+use crate::export::RecordSink;
+use crate::filter::Operator;
+use crate::query_string::percent_decode;
+use crate::retry::{is_retryable, RetryPolicy};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::future::try_join_all;
+use reso_client::{JsonValue, ReplicationQueryBuilder, ReplicationResponse, ResoError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// Replication progress for one resource.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Resource the checkpoint belongs to
+    pub resource: String,
+    /// High-water `ModificationTimestamp` of the last completed run
+    pub since: Option<String>,
+    /// Highest `ModificationTimestamp` seen by the run in progress
+    pub high_water: Option<String>,
+    /// Next link of the run in progress, used to resume it
+    pub next_link: Option<String>,
+    /// Records written by the run in progress
+    pub records: u64,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Checkpoint {
+    /// Creates an in-memory checkpoint that is never persisted.
+    pub fn new(resource: &str) -> Self {
+        Self {
+            resource: resource.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Loads a checkpoint from a JSON file, or starts a new one if the file
+    /// does not exist. Later calls to [`save`](Self::save) write to `path`.
+    pub fn load(path: impl AsRef<Path>, resource: &str) -> Result<Self, ResoError> {
+        let path = path.as_ref();
+        let mut checkpoint = match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                let checkpoint: Checkpoint = serde_json::from_str(&contents)
+                    .map_err(|e| ResoError::Parse(format!("Invalid checkpoint {}: {}", path.display(), e)))?;
+                if checkpoint.resource != resource {
+                    return Err(ResoError::Config(format!(
+                        "Checkpoint {} belongs to resource '{}', not '{}'",
+                        path.display(),
+                        checkpoint.resource,
+                        resource
+                    )));
+                }
+                checkpoint
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Checkpoint::new(resource),
+            Err(e) => {
+                return Err(ResoError::Config(format!(
+                    "Cannot read checkpoint {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        checkpoint.path = Some(path.to_path_buf());
+        Ok(checkpoint)
+    }
+
+    /// Writes the checkpoint to the file it was loaded from, if any.
+    ///
+    /// The file is replaced atomically so a crash never leaves it truncated.
+    pub fn save(&self) -> Result<(), ResoError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(self).map_err(|e| ResoError::Parse(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| ResoError::Config(format!("Cannot write checkpoint {}: {}", path.display(), e)))
+    }
+
+    /// The high-water mark of the last completed run, for passing to
+    /// [`incremental_sync`]; `None` before the first run.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use chrono::DateTime;
+    /// use reso_examples::create_client;
+    /// use reso_examples::sync::{incremental_sync, Checkpoint};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let client = create_client()?;
+    ///     let mut checkpoint = Checkpoint::load("member.checkpoint.json", "Member")?;
+    ///
+    ///     let since = checkpoint.since_timestamp()?.unwrap_or(DateTime::UNIX_EPOCH);
+    ///     let changes = incremental_sync(&client, "Member", since, &[]).await?;
+    ///     // ... store changes.records ...
+    ///     checkpoint.record_sync(&changes);
+    ///     checkpoint.save()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn since_timestamp(&self) -> Result<Option<DateTime<Utc>>, ResoError> {
+        self.since
+            .as_deref()
+            .map(|since| {
+                modification_time(since)
+                    .ok_or_else(|| ResoError::Parse(format!("Invalid checkpoint timestamp '{}'", since)))
+            })
+            .transpose()
+    }
+
+    /// Records a completed [`incremental_sync`], whose high-water mark
+    /// becomes the start of the next run. Any interrupted
+    /// [`replicate_all`] run is abandoned.
+    ///
+    /// Save the checkpoint only after the records are stored, so a crash in
+    /// between fetches them again rather than losing them.
+    pub fn record_sync(&mut self, sync: &IncrementalSync) {
+        self.complete();
+        self.since = Some(sync.high_water.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true));
+    }
+
+    /// Returns true if a run was interrupted and can be resumed.
+    pub fn in_progress(&self) -> bool {
+        self.next_link.is_some()
+    }
+
+    /// Gives up on the run in progress without moving the high-water mark.
+    ///
+    /// Replication pages are not ordered by `ModificationTimestamp`, so the
+    /// pages that were never fetched may hold records older than any seen so
+    /// far. The next run therefore replicates the same window again from its
+    /// first page, with a fresh next link; the records already written are
+    /// fetched again.
+    pub fn restart(&mut self) {
+        self.high_water = None;
+        self.next_link = None;
+        self.records = 0;
+    }
+
+    fn observe(&mut self, records: &[JsonValue]) {
+        for timestamp in records
+            .iter()
+            .filter_map(|r| r["ModificationTimestamp"].as_str())
+        {
+            if self.high_water.as_deref().is_none_or(|hw| timestamp > hw) {
+                self.high_water = Some(timestamp.to_string());
+            }
+        }
+        self.records += records.len() as u64;
+    }
+
+    fn complete(&mut self) {
+        if self.high_water.is_some() {
+            self.since = self.high_water.take();
+        }
+        self.next_link = None;
+        self.records = 0;
+    }
+}
+
+/// Throughput of a [`replicate_all`] call, for capacity planning.
+///
+/// Displays as a one-line summary, e.g. "12000 records in 6 batches over
+/// 42.0s (285.7 records/s, 18.4 MB, 1 retry)".
+#[derive(Debug, Clone, Default)]
+pub struct ReplicationStats {
+    /// Records written to the sink during this call
+    pub records: u64,
+    /// Batches fetched during this call
+    pub batches: u64,
+    /// Whether the call resumed an interrupted run
+    pub resumed: bool,
+    /// Wall-clock time of the call, including sink writes
+    pub duration: Duration,
+    /// Pages requested again, with a smaller page size after a timeout or
+    /// oversized response, or after a transient failure (see
+    /// [`retry`](crate::retry))
+    pub retries: u64,
+    /// Size of the records written, as compact JSON; for
+    /// [`replicate_streaming`], the response bytes received
+    pub bytes: u64,
+}
+
+impl ReplicationStats {
+    /// Average records written per second, or 0 for an instant run.
+    pub fn records_per_second(&self) -> f64 {
+        let seconds = self.duration.as_secs_f64();
+        if seconds > 0.0 {
+            self.records as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for ReplicationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} records in {} {} over {:.1}s ({:.1} records/s, {:.1} MB, {} {})",
+            self.records,
+            self.batches,
+            if self.batches == 1 { "batch" } else { "batches" },
+            self.duration.as_secs_f64(),
+            self.records_per_second(),
+            self.bytes as f64 / 1_000_000.0,
+            self.retries,
+            if self.retries == 1 { "retry" } else { "retries" }
+        )
+    }
+}
+
+/// Builds the replication filter, adding `ModificationTimestamp gt <since>`
+/// to the caller's filter when a high-water mark is known.
+pub fn replication_filter(filter: Option<&str>, since: Option<&str>) -> Option<String> {
+    let since = since.map(|ts| format!("ModificationTimestamp gt {}", ts));
+    match (filter, since) {
+        (Some(f), Some(s)) => Some(format!("({}) and {}", f, s)),
+        (Some(f), None) => Some(f.to_string()),
+        (None, s) => s,
+    }
+}
+
+/// Replicates a resource into a sink, resuming from and updating a checkpoint.
+///
+/// If the checkpoint holds a next link from an interrupted run, replication
+/// resumes from it. Otherwise a new run starts, limited to records modified
+/// after the checkpoint's `since` timestamp. The checkpoint is saved after
+/// every batch, once the sink has flushed it.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `filter` - Optional OData filter expression
+/// * `sink` - Destination for the replicated records
+/// * `checkpoint` - Progress from previous runs, updated in place
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::sync::{replicate_all, Checkpoint};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let mut sink = JsonlSink::append("property.jsonl")?;
+///     let mut checkpoint = Checkpoint::load("property.checkpoint.json", "Property")?;
+///
+///     let stats = replicate_all(&client, "Property", None, &mut sink, &mut checkpoint).await?;
+///     // e.g. "Replicated 12000 records in 6 batches over 42.0s (285.7 records/s, 18.4 MB, 0 retries)"
+///     println!("Replicated {}", stats);
+///     Ok(())
+/// }
+/// ```
+pub async fn replicate_all<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    sink: &mut S,
+    checkpoint: &mut Checkpoint,
+) -> Result<ReplicationStats, ResoError> {
+    let never = CancellationToken::new();
+    replicate_all_cancellable(client, resource, filter, sink, checkpoint, &never).await
+}
+
+/// Like [`replicate_all`], but stops when `token` is cancelled.
+///
+/// Cancellation interrupts the pending replication request, never a sink
+/// write, so the checkpoint always reflects the last batch written and the
+/// run can be resumed later. A cancelled run returns an error for which
+/// [`cancel::is_cancelled`](crate::cancel::is_cancelled) is true.
+pub async fn replicate_all_cancellable<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    sink: &mut S,
+    checkpoint: &mut Checkpoint,
+    token: &CancellationToken,
+) -> Result<ReplicationStats, ResoError> {
+    let mut page_size = AdaptivePageSize::default();
+    replicate_all_paged(client, resource, filter, sink, checkpoint, token, &mut page_size).await
+}
+
+/// Like [`replicate_all_cancellable`], with control over the page size.
+///
+/// [`replicate_all`] and [`replicate_all_cancellable`] use
+/// [`AdaptivePageSize::default`]; pass `AdaptivePageSize::new(n, n)` for a
+/// fixed size. The page size is left where the run ended, so a caller
+/// replicating several resources from one server can carry it over.
+pub async fn replicate_all_paged<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    resource: &str,
+    filter: Option<&str>,
+    sink: &mut S,
+    checkpoint: &mut Checkpoint,
+    token: &CancellationToken,
+    page_size: &mut AdaptivePageSize,
+) -> Result<ReplicationStats, ResoError> {
+    let started = Instant::now();
+    let mut stats = ReplicationStats {
+        resumed: checkpoint.in_progress(),
+        ..Default::default()
+    };
+
+    let first = match checkpoint.next_link.clone() {
+        Some(link) => Page::Next(link),
+        None => Page::First {
+            filter: replication_filter(filter, checkpoint.since.as_deref()),
+            select: Vec::new(),
+        },
+    };
+    let mut response = fetch_page(client, resource, &first, token, page_size, &mut stats.retries).await?;
+
+    loop {
+        sink.write_batch(&response.records).await?;
+        sink.flush().await?;
+
+        stats.batches += 1;
+        stats.records += response.records.len() as u64;
+        stats.bytes += response
+            .records
+            .iter()
+            .map(|record| record.to_string().len() as u64)
+            .sum::<u64>();
+        checkpoint.observe(&response.records);
+        checkpoint.next_link = response.next_link.clone();
+        checkpoint.save()?;
+
+        match response.next_link.take() {
+            Some(link) if !response.records.is_empty() => {
+                response =
+                    fetch_page(client, resource, &Page::Next(link), token, page_size, &mut stats.retries).await?;
+            }
+            _ => break,
+        }
+    }
+
+    sink.finalize().await?;
+    checkpoint.complete();
+    checkpoint.save()?;
+    stats.duration = started.elapsed();
+    Ok(stats)
+}
+
+/// Like [`replicate_all`], parsing each response as it arrives and writing
+/// it to the sink `batch_size` records at a time, for servers whose pages
+/// are too large to hold in memory (see [`streaming`](crate::streaming)).
+///
+/// Requests use the server's page size. The checkpoint is saved after each
+/// page, so a run interrupted partway through a page writes that page's
+/// earlier records again when it resumes; sinks keyed on `ListingKey`
+/// absorb the repeats.
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::client::HttpClient;
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::sync::{replicate_streaming, Checkpoint};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = HttpClient::from_env()?;
+///     let mut sink = JsonlSink::append("property.jsonl")?;
+///     let mut checkpoint = Checkpoint::load("property.checkpoint.json", "Property")?;
+///
+///     let stats = replicate_streaming(&client, "Property", None, &mut sink, &mut checkpoint, 500).await?;
+///     println!("Replicated {}", stats);
+///     Ok(())
+/// }
+/// ```
+pub async fn replicate_streaming<S: RecordSink + ?Sized>(
+    client: &HttpClient,
+    resource: &str,
+    filter: Option<&str>,
+    sink: &mut S,
+    checkpoint: &mut Checkpoint,
+    batch_size: usize,
+) -> Result<ReplicationStats, ResoError> {
+    let started = Instant::now();
+    let mut stats = ReplicationStats {
+        resumed: checkpoint.in_progress(),
+        ..Default::default()
+    };
+
+    let mut page = match checkpoint.next_link.clone() {
+        Some(link) => client.stream_next_link(&link).await?,
+        None => {
+            let mut builder = ReplicationQueryBuilder::new(resource);
+            if let Some(expr) = replication_filter(filter, checkpoint.since.as_deref()) {
+                builder = builder.filter(expr);
+            }
+            client.stream_replication(&builder.build()?).await?
+        }
+    };
+
+    loop {
+        let mut page_records = 0;
+        while let Some(records) = page.next_batch(batch_size).await? {
+            sink.write_batch(&records).await?;
+            page_records += records.len();
+            stats.records += records.len() as u64;
+            checkpoint.observe(&records);
+        }
+        sink.flush().await?;
+        stats.bytes += page.bytes_read();
+
+        stats.batches += 1;
+        checkpoint.next_link = page.next_link().map(str::to_string);
+        checkpoint.save()?;
+
+        match checkpoint.next_link.clone() {
+            Some(link) if page_records > 0 => page = client.stream_next_link(&link).await?,
+            _ => break,
+        }
+    }
+
+    sink.finalize().await?;
+    checkpoint.complete();
+    checkpoint.save()?;
+    stats.duration = started.elapsed();
+    Ok(stats)
+}
+
+/// A slice of a resource replicated by [`replicate_parallel`], selected by
+/// a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard {
+    /// OData filter selecting the shard's records
+    pub filter: String,
+}
+
+impl Shard {
+    /// First characters of lower-case hexadecimal keys.
+    ///
+    /// Many servers compare strings ignoring case, where `startswith(Key,'a')`
+    /// also matches keys starting with `A`, so only one case is listed; use
+    /// [`HEX_PREFIXES_UPPER`](Self::HEX_PREFIXES_UPPER) for upper-case keys.
+    pub const HEX_PREFIXES: [&'static str; 16] = [
+        "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "a", "b", "c", "d", "e", "f",
+    ];
+
+    /// First characters of upper-case hexadecimal keys.
+    pub const HEX_PREFIXES_UPPER: [&'static str; 16] = [
+        "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "A", "B", "C", "D", "E", "F",
+    ];
+
+    pub fn new(filter: impl Into<String>) -> Self {
+        Self { filter: filter.into() }
+    }
+
+    /// One shard per key prefix, e.g. `startswith(ListingKey,'3')`.
+    ///
+    /// Records whose key starts with none of the prefixes are not
+    /// replicated, so the prefixes must cover every key the server issues;
+    /// [`HEX_PREFIXES`](Self::HEX_PREFIXES) covers lower-case hexadecimal
+    /// keys.
+    pub fn key_prefixes(field: &str, prefixes: &[&str]) -> Vec<Shard> {
+        prefixes
+            .iter()
+            .map(|prefix| Shard::new(Operator::StartsWith.render(field, *prefix)))
+            .collect()
+    }
+
+    /// `count` equal windows of a timestamp field between `start` and `end`.
+    ///
+    /// The first window has no lower bound and the last no upper bound, so
+    /// records outside the range, including those modified while the run is
+    /// in progress, still fall in a shard. Records with no value in `field`
+    /// are not replicated.
+    pub fn time_windows(field: &str, start: DateTime<Utc>, end: DateTime<Utc>, count: u32) -> Vec<Shard> {
+        let count = count.max(1);
+        let step = (end - start) / count as i32;
+        let bounds: Vec<String> = (1..count)
+            .map(|i| (start + step * i as i32).to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .collect();
+        if bounds.is_empty() {
+            return vec![Shard::new(format!("{} ne null", field))];
+        }
+        let mut shards = vec![Shard::new(format!("{} lt {}", field, bounds[0]))];
+        shards.extend(
+            bounds
+                .windows(2)
+                .map(|w| Shard::new(format!("{} ge {} and {} lt {}", field, w[0], field, w[1]))),
+        );
+        shards.push(Shard::new(format!("{} ge {}", field, bounds[bounds.len() - 1])));
+        shards
+    }
+}
+
+/// Replicates a resource as several shards fetched concurrently, writing
+/// every batch into one sink.
+///
+/// Each shard follows its own replication next links, with at most
+/// `concurrency` shards in flight at once; batches from different shards
+/// reach the sink interleaved. There is no checkpoint, so an interrupted
+/// run starts over: use it for initial loads of large datasets, and
+/// [`replicate_all`] for the incremental runs that follow. Shards should
+/// not overlap, or their shared records are written twice.
+///
+/// The returned stats add up the shards' records, batches, retries, and
+/// bytes; `duration` is the wall-clock time of the whole call. If a shard
+/// fails, the others stop, the sink is finalized with the batches already
+/// written, and the shard's error is returned.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `shards` - Non-overlapping slices of the resource, see [`Shard`]
+/// * `concurrency` - Shards replicated at once (at least 1)
+/// * `sink` - Destination for the replicated records
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::create_client;
+/// use reso_examples::export::JsonlSink;
+/// use reso_examples::sync::{replicate_parallel, Shard};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let mut sink = JsonlSink::append("property.jsonl")?;
+///     let shards = Shard::key_prefixes("ListingKey", &Shard::HEX_PREFIXES);
+///
+///     let stats = replicate_parallel(&client, "Property", &shards, 4, &mut sink).await?;
+///     println!("Replicated {}", stats);
+///     Ok(())
+/// }
+/// ```
+pub async fn replicate_parallel<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    resource: &str,
+    shards: &[Shard],
+    concurrency: usize,
+    sink: &mut S,
+) -> Result<ReplicationStats, ResoError> {
+    let started = Instant::now();
+    let permits = Semaphore::new(concurrency.max(1));
+    let shared = tokio::sync::Mutex::new(sink);
+
+    let result = try_join_all(shards.iter().map(|shard| async {
+        let _permit = permits.acquire().await.expect("shard semaphore is never closed");
+        replicate_shard(client, resource, shard, &shared).await
+    }))
+    .await;
+    let sink = shared.into_inner();
+    let shard_stats = match result {
+        Ok(shard_stats) => shard_stats,
+        Err(e) => {
+            // Close what the other shards wrote; the shard's error is the one
+            // worth reporting
+            let _ = sink.finalize().await;
+            return Err(e);
+        }
+    };
+    sink.finalize().await?;
+
+    let mut stats = ReplicationStats::default();
+    for shard in shard_stats {
+        stats.records += shard.records;
+        stats.batches += shard.batches;
+        stats.retries += shard.retries;
+        stats.bytes += shard.bytes;
+    }
+    stats.duration = started.elapsed();
+    Ok(stats)
+}
+
+/// Follows one shard's next links, writing each batch to the shared sink.
+async fn replicate_shard<S: RecordSink + ?Sized>(
+    client: &impl ResoApi,
+    resource: &str,
+    shard: &Shard,
+    sink: &tokio::sync::Mutex<&mut S>,
+) -> Result<ReplicationStats, ResoError> {
+    let never = CancellationToken::new();
+    let mut page_size = AdaptivePageSize::default();
+    let mut stats = ReplicationStats::default();
+    let mut page = Page::First {
+        filter: Some(shard.filter.clone()),
+        select: Vec::new(),
+    };
+    loop {
+        let mut response = fetch_page(client, resource, &page, &never, &mut page_size, &mut stats.retries).await?;
+        {
+            let mut sink = sink.lock().await;
+            sink.write_batch(&response.records).await?;
+            sink.flush().await?;
+        }
+
+        stats.batches += 1;
+        stats.records += response.records.len() as u64;
+        stats.bytes += response
+            .records
+            .iter()
+            .map(|record| record.to_string().len() as u64)
+            .sum::<u64>();
+
+        match response.next_link.take() {
+            Some(link) if !response.records.is_empty() => page = Page::Next(link),
+            _ => return Ok(stats),
+        }
+    }
+}
+
+/// Records fetched by [`incremental_sync`].
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalSync {
+    /// Records modified after `since`, in the order the server returned them
+    pub records: Vec<JsonValue>,
+    /// The latest `ModificationTimestamp` among the records, or `since` when
+    /// none changed; pass it as `since` to the next call
+    pub high_water: DateTime<Utc>,
+}
+
+/// Fetches every record of a resource modified after `since`.
+///
+/// Builds the `ModificationTimestamp gt <since>` filter, follows replication
+/// next links until the changes are exhausted, and reports the new
+/// high-water mark. `since` is sent with whole seconds, so records modified
+/// within the same second as the previous high-water mark may be fetched
+/// again; none are missed.
+///
+/// # Arguments
+///
+/// * `client` - A reference to a configured client, such as a ResoClient
+/// * `resource` - The resource name (e.g., "Property", "Member", "Office")
+/// * `since` - The high-water mark from the previous call
+/// * `fields` - Fields to select, or empty for all; `ModificationTimestamp`
+///   is added when missing, since the high-water mark is read from it
+///
+/// # Example
+///
+/// ```no_run
+/// use chrono::{TimeZone, Utc};
+/// use reso_examples::create_client;
+/// use reso_examples::sync::incremental_sync;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = create_client()?;
+///     let mut since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+///
+///     let changes = incremental_sync(&client, "Property", since, &["ListingKey", "ListPrice"]).await?;
+///     println!("{} listings changed", changes.records.len());
+///     since = changes.high_water;
+///     Ok(())
+/// }
+/// ```
+pub async fn incremental_sync(
+    client: &impl ResoApi,
+    resource: &str,
+    since: DateTime<Utc>,
+    fields: &[&str],
+) -> Result<IncrementalSync, ResoError> {
+    let mut select: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+    if !select.is_empty() && !fields.contains(&"ModificationTimestamp") {
+        select.push("ModificationTimestamp".to_string());
+    }
+    let since_literal = since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let first = Page::First {
+        filter: replication_filter(None, Some(&since_literal)),
+        select,
+    };
+
+    let never = CancellationToken::new();
+    let mut page_size = AdaptivePageSize::default();
+    let mut retries = 0;
+    let mut sync = IncrementalSync {
+        records: Vec::new(),
+        high_water: since,
+    };
+    let mut response = fetch_page(client, resource, &first, &never, &mut page_size, &mut retries).await?;
+    loop {
+        for record in &response.records {
+            if let Some(modified) = record["ModificationTimestamp"].as_str().and_then(modification_time) {
+                sync.high_water = sync.high_water.max(modified);
+            }
+        }
+        let exhausted = response.records.is_empty();
+        sync.records.append(&mut response.records);
+
+        match response.next_link.take() {
+            Some(link) if !exhausted => {
+                let next = Page::Next(link);
+                response = fetch_page(client, resource, &next, &never, &mut page_size, &mut retries).await?;
+            }
+            _ => break,
+        }
+    }
+    Ok(sync)
+}
+
+/// Parses a `ModificationTimestamp`. Some servers send timestamps without
+/// an offset, e.g. `2025-01-02T12:00:00`; those are read as UTC.
+fn modification_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(ts) => Some(ts.with_timezone(&Utc)),
+        Err(_) => NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|ts| ts.and_utc()),
+    }
+}
+
+/// Replication page size that adapts to what a server can serve.
+///
+/// Runs start at the largest page size, since fewer round trips make for
+/// the fastest replication. A timeout, `408 Request Timeout`,
+/// `413 Payload Too Large`, or `504 Gateway Timeout` halves the size and
+/// the page is requested again; after a streak of successful pages the size
+/// doubles back towards the maximum. Page sizes are applied through the
+/// `$top` of the first request and of next links, which get one added once
+/// the size is below the maximum.
+///
+/// Timeouts are recognised in errors from [`HttpClient`](crate::client::HttpClient)
+/// (see [`is_timeout`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptivePageSize {
+    min: u32,
+    max: u32,
+    current: u32,
+    successes: u32,
+}
+
+impl AdaptivePageSize {
+    /// Successful pages needed before the size grows again.
+    const GROW_AFTER: u32 = 5;
+
+    /// Starts at `max` and never goes below `min`.
+    pub fn new(min: u32, max: u32) -> Self {
+        let min = min.clamp(1, max.max(1));
+        Self {
+            min,
+            max: max.max(min),
+            current: max.max(min),
+            successes: 0,
+        }
+    }
+
+    /// The page size to request next.
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// Halves the page size. Returns false if it is already at the minimum.
+    pub fn step_down(&mut self) -> bool {
+        self.successes = 0;
+        if self.current == self.min {
+            return false;
+        }
+        self.current = (self.current / 2).max(self.min);
+        true
+    }
+
+    /// Records a successful page, growing the size after a streak of them.
+    pub fn record_success(&mut self) {
+        self.successes += 1;
+        if self.successes >= Self::GROW_AFTER && self.current < self.max {
+            self.current = self.current.saturating_mul(2).min(self.max);
+            self.successes = 0;
+        }
+    }
+
+    /// Whether an error suggests the page was too large to serve in time.
+    pub fn is_page_too_large(error: &ResoError) -> bool {
+        match error {
+            ResoError::ODataError { status_code, .. } | ResoError::ServerError { status_code, .. } => {
+                matches!(status_code, 408 | 413 | 504)
+            }
+            _ => is_timeout(error),
+        }
+    }
+
+    /// Whether the size is below the maximum after a step down.
+    pub fn is_reduced(&self) -> bool {
+        self.current < self.max
+    }
+}
+
+impl Default for AdaptivePageSize {
+    /// Between 100 and 2000 records, the replication endpoint's maximum.
+    fn default() -> Self {
+        Self::new(100, 2000)
+    }
+}
+
+/// A replication request: the first of a run, with its filter and
+/// selected fields, or a next link.
+enum Page {
+    First {
+        filter: Option<String>,
+        select: Vec<String>,
+    },
+    Next(String),
+}
+
+/// Fetches one page, halving the page size while the page is too large and
+/// retrying transient failures as [`RetryPolicy::default`] allows.
+async fn fetch_page(
+    client: &impl ResoApi,
+    resource: &str,
+    page: &Page,
+    token: &CancellationToken,
+    page_size: &mut AdaptivePageSize,
+    retries: &mut u64,
+) -> Result<ReplicationResponse, ResoError> {
+    let policy = RetryPolicy::default();
+    let mut attempt = 0;
+    loop {
+        let result = match page {
+            Page::First { filter, select } => {
+                let mut builder = ReplicationQueryBuilder::new(resource).top(page_size.current());
+                if let Some(expr) = filter {
+                    builder = builder.filter(expr);
+                }
+                if !select.is_empty() {
+                    let fields: Vec<&str> = select.iter().map(String::as_str).collect();
+                    builder = builder.select(&fields);
+                }
+                cancellable(token, client.execute_replication(&builder.build()?)).await
+            }
+            Page::Next(link) => {
+                let link = with_top(link, page_size.current(), page_size.is_reduced());
+                cancellable(token, client.execute_next_link(&link)).await
+            }
+        };
+
+        match result {
+            Ok(response) => {
+                page_size.record_success();
+                return Ok(response);
+            }
+            Err(e) if AdaptivePageSize::is_page_too_large(&e) && page_size.step_down() => *retries += 1,
+            Err(e) if attempt < policy.max_retries && is_retryable(&e) => {
+                let Some(delay) = policy.delay_after(attempt, &e) else {
+                    return Err(e);
+                };
+                cancellable(token, async {
+                    tokio::time::sleep(delay).await;
+                    Ok(())
+                })
+                .await?;
+                attempt += 1;
+                *retries += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Replaces the `$top` value of a next link, leaving its other query
+/// options exactly as the server wrote them. A link without `$top` gets
+/// one only when `add` is set; otherwise the server's page size applies.
+fn with_top(link: &str, top: u32, add: bool) -> String {
+    let (base, query) = link.split_once('?').unwrap_or((link, ""));
+    let mut found = false;
+    let mut pairs: Vec<String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let name = pair.split_once('=').map_or(pair, |(name, _)| name);
+            if percent_decode(name).is_ok_and(|name| name == "$top") {
+                found = true;
+                format!("{}={}", name, top)
+            } else {
+                pair.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        if !add {
+            return link.to_string();
+        }
+        pairs.push(format!("$top={}", top));
+    }
+    format!("{}?{}", base, pairs.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_replication_filter() {
+        assert_eq!(replication_filter(None, None), None);
+        assert_eq!(
+            replication_filter(Some("City eq 'Austin'"), Some("2025-01-01T00:00:00Z")).unwrap(),
+            "(City eq 'Austin') and ModificationTimestamp gt 2025-01-01T00:00:00Z"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replicate_all_reports_throughput() {
+        use async_trait::async_trait;
+        use reso_client::ClientConfig;
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(Default)]
+        struct VecSink(Vec<JsonValue>);
+
+        #[async_trait]
+        impl RecordSink for VecSink {
+            async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+                self.0.extend_from_slice(records);
+                Ok(())
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property/replication"))
+            .and(query_param("$top", "2000"))
+            .respond_with(ResponseTemplate::new(504))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/replication"))
+            .and(query_param("$top", "1000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [{"ListingKey": "A"}, {"ListingKey": "BB"}]})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let mut sink = VecSink::default();
+        let mut checkpoint = Checkpoint::new("Property");
+        let stats = replicate_all(&client, "Property", None, &mut sink, &mut checkpoint).await.unwrap();
+
+        assert_eq!((stats.records, stats.batches, stats.retries), (2, 1, 1));
+        assert_eq!(stats.bytes, r#"{"ListingKey":"A"}{"ListingKey":"BB"}"#.len() as u64);
+        assert!(stats.duration > Duration::ZERO);
+        let summary = ReplicationStats {
+            duration: Duration::from_secs(4),
+            ..stats
+        };
+        assert_eq!(summary.to_string(), "2 records in 1 batch over 4.0s (0.5 records/s, 0.0 MB, 1 retry)");
+    }
+
+    #[tokio::test]
+    async fn test_replicate_all_waits_out_rate_limit() {
+        use async_trait::async_trait;
+        use reso_client::ClientConfig;
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct CountSink(u64);
+
+        #[async_trait]
+        impl RecordSink for CountSink {
+            async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+                self.0 += records.len() as u64;
+                Ok(())
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property/replication"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/replication"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [{"ListingKey": "A"}]})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let mut sink = CountSink(0);
+        let mut checkpoint = Checkpoint::new("Property");
+        let stats = replicate_all(&client, "Property", None, &mut sink, &mut checkpoint).await.unwrap();
+
+        assert_eq!((sink.0, stats.retries), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_replicate_streaming_follows_next_links() {
+        use async_trait::async_trait;
+        use reso_client::ClientConfig;
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(Default)]
+        struct VecSink(Vec<Vec<JsonValue>>);
+
+        #[async_trait]
+        impl RecordSink for VecSink {
+            async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+                self.0.push(records.to_vec());
+                Ok(())
+            }
+        }
+
+        let server = MockServer::start().await;
+        let next = format!("{}/Property/replication?page=2", server.uri());
+        let first: Vec<JsonValue> = (0..5).map(|i| json!({"ListingKey": format!("K{}", i)})).collect();
+        let bodies = [
+            json!({"value": first, "@odata.nextLink": next}),
+            json!({"value": [{"ListingKey": "K5"}]}),
+            json!({"value": []}),
+        ];
+        // The body's next link wins over the header; without one, the
+        // header's rel="next" target is followed
+        Mock::given(path("/Property/replication"))
+            .and(query_param("page", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&bodies[2]))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/replication"))
+            .and(query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", format!(r#"<{}/Property/replication?page=3>; rel="next""#, server.uri()).as_str())
+                    .set_body_json(&bodies[1]),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/replication"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", r#"<https://elsewhere.test/replication>; rel="next""#)
+                    .set_body_json(&bodies[0]),
+            )
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::builder(ClientConfig::new(server.uri(), "token")).build().unwrap();
+        let mut sink = VecSink::default();
+        let mut checkpoint = Checkpoint::new("Property");
+        let stats = replicate_streaming(&client, "Property", None, &mut sink, &mut checkpoint, 2).await.unwrap();
+
+        assert_eq!((stats.records, stats.batches), (6, 3));
+        assert_eq!(stats.bytes, bodies.iter().map(|body| body.to_string().len() as u64).sum::<u64>());
+        let sizes: Vec<usize> = sink.0.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [2, 2, 1, 1]);
+        assert!(!checkpoint.in_progress());
+    }
+
+    #[test]
+    fn test_time_window_shards_cover_the_whole_range() {
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap();
+        let filters: Vec<String> = Shard::time_windows("ModificationTimestamp", start, end, 3)
+            .into_iter()
+            .map(|shard| shard.filter)
+            .collect();
+        assert_eq!(
+            filters,
+            [
+                "ModificationTimestamp lt 2024-01-02T00:00:00Z",
+                "ModificationTimestamp ge 2024-01-02T00:00:00Z and ModificationTimestamp lt 2024-01-03T00:00:00Z",
+                "ModificationTimestamp ge 2024-01-03T00:00:00Z",
+            ]
+        );
+        assert_eq!(
+            Shard::key_prefixes("ListingKey", &["A"]),
+            [Shard::new("startswith(ListingKey,'A')")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replicate_parallel_merges_shards_into_one_sink() {
+        use crate::demo::DemoClient;
+        use async_trait::async_trait;
+
+        #[derive(Default)]
+        struct VecSink(Vec<JsonValue>, bool);
+
+        #[async_trait]
+        impl RecordSink for VecSink {
+            async fn write_batch(&mut self, records: &[JsonValue]) -> Result<(), ResoError> {
+                self.0.extend_from_slice(records);
+                Ok(())
+            }
+
+            async fn finalize(&mut self) -> Result<(), ResoError> {
+                self.1 = true;
+                Ok(())
+            }
+        }
+
+        // DEMO1001 through DEMO1060
+        let prefixes = ["DEMO100", "DEMO101", "DEMO102", "DEMO103", "DEMO104", "DEMO105", "DEMO106"];
+        let shards = Shard::key_prefixes("ListingKey", &prefixes);
+        let mut sink = VecSink::default();
+        let stats = replicate_parallel(&DemoClient::new(), "Property", &shards, 3, &mut sink)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.records, 60);
+        assert_eq!(stats.batches, 7);
+        let mut keys: Vec<&str> = sink.0.iter().map(|r| r["ListingKey"].as_str().unwrap()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), 60);
+
+        // A failing shard still leaves the sink finalized
+        let mut shards = Shard::key_prefixes("ListingKey", &prefixes[..1]);
+        shards.push(Shard::new("length(City) gt 5"));
+        let mut sink = VecSink::default();
+        assert!(replicate_parallel(&DemoClient::new(), "Property", &shards, 1, &mut sink).await.is_err());
+        assert!(sink.1);
+    }
+
+    #[test]
+    fn test_adaptive_page_size_steps_down_and_recovers() {
+        let mut size = AdaptivePageSize::new(100, 2000);
+        assert!(AdaptivePageSize::is_page_too_large(&ResoError::ODataError {
+            message: "Payload Too Large".into(),
+            status_code: 413,
+        }));
+        assert!(!AdaptivePageSize::is_page_too_large(&ResoError::ServerError {
+            message: "Internal".into(),
+            status_code: 500,
+        }));
+
+        while size.step_down() {}
+        assert_eq!(size.current(), 100);
+        for _ in 0..AdaptivePageSize::GROW_AFTER {
+            size.record_success();
+        }
+        assert_eq!(size.current(), 200);
+
+        assert!(AdaptivePageSize::is_page_too_large(&ResoError::ODataError {
+            message: "Request Timeout".into(),
+            status_code: 408,
+        }));
+        assert!(!AdaptivePageSize::is_page_too_large(&ResoError::Network("connection reset; timed out".into())));
+
+        assert_eq!(
+            with_top("https://api.example.com/Property/replication?$skiptoken=a%26b&%24top=2000", 500, false),
+            "https://api.example.com/Property/replication?$skiptoken=a%26b&%24top=500"
+        );
+        let next = "https://api.example.com/next?token=x+y";
+        assert_eq!(with_top(next, 500, false), next);
+        assert_eq!(with_top(next, 500, true), "https://api.example.com/next?token=x+y&$top=500");
+        assert_eq!(with_top("https://api.example.com/next", 500, true), "https://api.example.com/next?$top=500");
+    }
+
+    #[tokio::test]
+    async fn test_incremental_sync_follows_next_links_and_advances_high_water() {
+        use chrono::TimeZone;
+        use reso_client::ClientConfig;
+        use wiremock::matchers::{path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/Property/replication"))
+            .and(query_param("$filter", "ModificationTimestamp gt 2025-01-01T00:00:00Z"))
+            .and(query_param("$select", "ListingKey,ModificationTimestamp"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("next", format!("{}/Property/replication?$skiptoken=2", server.uri()).as_str())
+                    .set_body_json(json!({"value": [
+                        {"ListingKey": "A", "ModificationTimestamp": "2025-01-03T00:00:00Z"},
+                        {"ListingKey": "B", "ModificationTimestamp": "2025-01-02T12:00:00.250+00:00"}
+                    ]})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/Property/replication"))
+            .and(query_param("$skiptoken", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"value": [
+                {"ListingKey": "C", "ModificationTimestamp": "2025-01-04T08:30:00-06:00"}
+            ]})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = crate::client::HttpClient::builder(ClientConfig::new(server.uri(), "token"))
+            .build()
+            .unwrap();
+        let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let sync = incremental_sync(&client, "Property", since, &["ListingKey"]).await.unwrap();
+
+        let keys: Vec<_> = sync.records.iter().map(|r| r["ListingKey"].as_str().unwrap()).collect();
+        assert_eq!(keys, ["A", "B", "C"]);
+        assert_eq!(sync.high_water, Utc.with_ymd_and_hms(2025, 1, 4, 14, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_modification_time_reads_timestamps_without_offset_as_utc() {
+        use chrono::TimeZone;
+
+        let noon = Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap();
+        assert_eq!(modification_time("2025-01-02T12:00:00"), Some(noon));
+        assert_eq!(modification_time("2025-01-02T06:00:00-06:00"), Some(noon));
+        assert_eq!(
+            modification_time("2025-01-02T12:00:00.250"),
+            Some(noon + chrono::Duration::milliseconds(250))
+        );
+        assert_eq!(modification_time("2025-01-02"), None);
+    }
+
+    #[test]
+    fn test_checkpoint_tracks_high_water_and_round_trips() {
+        let dir = std::env::temp_dir().join(format!("reso-checkpoint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("property.json");
+        let _ = std::fs::remove_file(&path);
+
+        let mut checkpoint = Checkpoint::load(&path, "Property").unwrap();
+        checkpoint.observe(&[
+            json!({"ModificationTimestamp": "2025-01-02T00:00:00Z"}),
+            json!({"ModificationTimestamp": "2025-01-03T00:00:00Z"}),
+            json!({}),
+        ]);
+        checkpoint.next_link = Some("https://example.com/next".to_string());
+        checkpoint.save().unwrap();
+
+        let mut loaded = Checkpoint::load(&path, "Property").unwrap();
+        assert!(loaded.in_progress());
+        assert_eq!(loaded.records, 3);
+
+        loaded.complete();
+        assert_eq!(loaded.since.as_deref(), Some("2025-01-03T00:00:00Z"));
+        assert!(!loaded.in_progress());
+        assert!(Checkpoint::load(&path, "Member").is_err());
+
+        let high_water = DateTime::parse_from_rfc3339("2025-01-04T12:00:00.5Z").unwrap().with_timezone(&Utc);
+        loaded.record_sync(&IncrementalSync {
+            records: Vec::new(),
+            high_water,
+        });
+        loaded.save().unwrap();
+        let reloaded = Checkpoint::load(&path, "Property").unwrap();
+        assert_eq!(reloaded.since.as_deref(), Some("2025-01-04T12:00:00.500Z"));
+        assert_eq!(reloaded.since_timestamp().unwrap(), Some(high_water));
+        assert_eq!(Checkpoint::new("Property").since_timestamp().unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
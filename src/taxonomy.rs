@@ -0,0 +1,262 @@
+//! Rolling property types up into a few categories for search and analytics.
+//!
+//! `PropertyType` and `PropertySubType` together describe a listing in more
+//! detail than a search UI wants: a buyer picks "condo", not
+//! `Residential`/`StockCooperative`. A [`Taxonomy`] maps type and subtype
+//! combinations onto a [`PropertyCategory`] through a table that ships with
+//! the Data Dictionary values and can be extended or loaded from a file.
+
+use crate::filter::FilterBuilder;
+use crate::listing_status::table_key;
+use reso_client::{JsonValue, ResoError};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Record field that [`Taxonomy::apply`] writes the category to.
+pub const CATEGORY_FIELD: &str = "X_PropertyCategory";
+
+/// A canonical kind of property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyCategory {
+    House,
+    Condo,
+    Townhome,
+    Land,
+    Multifamily,
+}
+
+impl PropertyCategory {
+    pub const ALL: [PropertyCategory; 5] = [
+        PropertyCategory::House,
+        PropertyCategory::Condo,
+        PropertyCategory::Townhome,
+        PropertyCategory::Land,
+        PropertyCategory::Multifamily,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertyCategory::House => "house",
+            PropertyCategory::Condo => "condo",
+            PropertyCategory::Townhome => "townhome",
+            PropertyCategory::Land => "land",
+            PropertyCategory::Multifamily => "multifamily",
+        }
+    }
+}
+
+impl FromStr for PropertyCategory {
+    type Err = ResoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PropertyCategory::ALL
+            .into_iter()
+            .find(|category| category.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ResoError::InvalidQuery(format!("Unknown property category '{}'", s)))
+    }
+}
+
+impl fmt::Display for PropertyCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Built-in rules: `PropertyType`, `PropertySubType` (`None` for every
+/// subtype), and category.
+const DEFAULT_RULES: &[(&str, Option<&str>, PropertyCategory)] = &[
+    ("Residential", Some("SingleFamilyResidence"), PropertyCategory::House),
+    ("Residential", Some("ManufacturedHome"), PropertyCategory::House),
+    ("Residential", Some("ManufacturedOnLand"), PropertyCategory::House),
+    ("Residential", Some("MobileHome"), PropertyCategory::House),
+    ("Residential", Some("Cabin"), PropertyCategory::House),
+    ("Residential", Some("Condominium"), PropertyCategory::Condo),
+    ("Residential", Some("StockCooperative"), PropertyCategory::Condo),
+    ("Residential", Some("Apartment"), PropertyCategory::Condo),
+    ("Residential", Some("Townhouse"), PropertyCategory::Townhome),
+    ("Residential", Some("Duplex"), PropertyCategory::Multifamily),
+    ("Residential", Some("Triplex"), PropertyCategory::Multifamily),
+    ("Residential", Some("Quadruplex"), PropertyCategory::Multifamily),
+    ("ResidentialLease", Some("SingleFamilyResidence"), PropertyCategory::House),
+    ("ResidentialLease", Some("Condominium"), PropertyCategory::Condo),
+    ("ResidentialLease", Some("Apartment"), PropertyCategory::Condo),
+    ("ResidentialLease", Some("Townhouse"), PropertyCategory::Townhome),
+    ("ResidentialIncome", None, PropertyCategory::Multifamily),
+    ("Land", None, PropertyCategory::Land),
+    ("Farm", None, PropertyCategory::Land),
+];
+
+/// A table from `PropertyType`/`PropertySubType` combinations to
+/// [`PropertyCategory`].
+///
+/// A rule for a type and subtype beats a rule for the whole type.
+/// Lookups ignore case, spaces, and punctuation, so "Single Family
+/// Residence" matches a rule for `SingleFamilyResidence`. Combinations
+/// without a rule, such as commercial listings, have no category.
+///
+/// # Example
+///
+/// ```
+/// use reso_examples::taxonomy::{PropertyCategory, Taxonomy};
+/// use serde_json::json;
+///
+/// let taxonomy = Taxonomy::default().rule("Residential", Some("Patio Home"), PropertyCategory::House);
+///
+/// let listing = json!({"PropertyType": "Residential", "PropertySubType": "Stock Cooperative"});
+/// assert_eq!(taxonomy.categorize(&listing), Some(PropertyCategory::Condo));
+/// let listing = json!({"PropertyType": "Farm", "PropertySubType": "Ranch"});
+/// assert_eq!(taxonomy.categorize(&listing), Some(PropertyCategory::Land));
+/// let listing = json!({"PropertyType": "Commercial"});
+/// assert_eq!(taxonomy.categorize(&listing), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Taxonomy {
+    /// Rules by normalized (type, subtype)
+    rules: BTreeMap<(String, Option<String>), Rule>,
+}
+
+/// A rule with the type and subtype names as given.
+#[derive(Debug, Clone)]
+struct Rule {
+    property_type: String,
+    sub_type: Option<String>,
+    category: PropertyCategory,
+}
+
+impl Default for Taxonomy {
+    /// The built-in rules for Data Dictionary types and subtypes.
+    fn default() -> Self {
+        DEFAULT_RULES
+            .iter()
+            .fold(Self::new(), |taxonomy, (property_type, sub_type, category)| {
+                taxonomy.rule(property_type, *sub_type, *category)
+            })
+    }
+}
+
+impl Taxonomy {
+    /// A taxonomy without rules.
+    pub fn new() -> Self {
+        Self { rules: BTreeMap::new() }
+    }
+
+    /// Maps `property_type` with `sub_type` (or any subtype, for `None`)
+    /// onto `category`, replacing any earlier rule for the combination.
+    pub fn rule(mut self, property_type: &str, sub_type: Option<&str>, category: PropertyCategory) -> Self {
+        self.rules.insert(
+            (table_key(property_type), sub_type.map(table_key)),
+            Rule {
+                property_type: property_type.to_string(),
+                sub_type: sub_type.map(str::to_string),
+                category,
+            },
+        );
+        self
+    }
+
+    /// Adds rules from a JSON object keyed by `PropertyType` or
+    /// `PropertyType/PropertySubType`, e.g.
+    /// `{"Residential/Patio Home": "house", "Farm": "land"}`.
+    pub fn extend_from_json(mut self, json: &str) -> Result<Self, ResoError> {
+        let entries: HashMap<String, String> = serde_json::from_str(json)
+            .map_err(|e| ResoError::Parse(format!("Invalid taxonomy: {}", e)))?;
+        for (combination, category) in entries {
+            let (property_type, sub_type) = match combination.split_once('/') {
+                Some((property_type, sub_type)) => (property_type, Some(sub_type)),
+                None => (combination.as_str(), None),
+            };
+            self = self.rule(property_type, sub_type, category.parse()?);
+        }
+        Ok(self)
+    }
+
+    /// The built-in rules extended with the JSON object in `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResoError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| ResoError::Config(format!("Cannot read taxonomy {}: {}", path.display(), e)))?;
+        Self::default().extend_from_json(&json)
+    }
+
+    /// The category of a Property record, from its `PropertyType` and
+    /// `PropertySubType`.
+    pub fn categorize(&self, record: &JsonValue) -> Option<PropertyCategory> {
+        let property_type = table_key(record["PropertyType"].as_str()?);
+        let sub_type = record["PropertySubType"].as_str().map(table_key);
+        sub_type
+            .and_then(|sub_type| self.rules.get(&(property_type.clone(), Some(sub_type))))
+            .or_else(|| self.rules.get(&(property_type, None)))
+            .map(|rule| rule.category)
+    }
+
+    /// Sets [`CATEGORY_FIELD`] on a record to its category, or removes it
+    /// when the record has none.
+    pub fn apply(&self, record: &mut JsonValue) {
+        let category = self.categorize(record);
+        if let Some(fields) = record.as_object_mut() {
+            match category {
+                Some(category) => fields.insert(CATEGORY_FIELD.to_string(), category.as_str().into()),
+                None => fields.remove(CATEGORY_FIELD),
+            };
+        }
+    }
+
+    /// A filter matching the type and subtype combinations of `category`,
+    /// for searching a feed by category, or `None` if no rule produces it.
+    ///
+    /// The filter uses the names as written in the rules, so it only
+    /// matches feeds that spell the values the same way.
+    pub fn filter_for(&self, category: PropertyCategory) -> Option<FilterBuilder> {
+        self.rules
+            .values()
+            .filter(|rule| rule.category == category)
+            .map(|rule| {
+                let type_filter = FilterBuilder::eq("PropertyType", rule.property_type.as_str());
+                match &rule.sub_type {
+                    Some(sub_type) => type_filter.and(FilterBuilder::eq("PropertySubType", sub_type.as_str())),
+                    None => type_filter,
+                }
+            })
+            .reduce(FilterBuilder::or)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_subtype_rules_win_and_filter_for_category() {
+        let taxonomy = Taxonomy::default()
+            .extend_from_json(r#"{"Residential": "house", "ResidentialIncome/Duplex": "townhome"}"#)
+            .unwrap();
+
+        let mut listing = json!({"PropertyType": "Residential", "PropertySubType": "Townhouse"});
+        taxonomy.apply(&mut listing);
+        assert_eq!(listing[CATEGORY_FIELD], "townhome");
+        // Falls back to the type-wide rule for unknown subtypes
+        let listing = json!({"PropertyType": "Residential", "PropertySubType": "Patio Home"});
+        assert_eq!(taxonomy.categorize(&listing), Some(PropertyCategory::House));
+        let listing = json!({"PropertyType": "Residential Income", "PropertySubType": "duplex"});
+        assert_eq!(taxonomy.categorize(&listing), Some(PropertyCategory::Townhome));
+
+        let mut commercial = json!({"PropertyType": "Commercial", CATEGORY_FIELD: "house"});
+        taxonomy.apply(&mut commercial);
+        assert!(commercial.get(CATEGORY_FIELD).is_none());
+
+        let condo = Taxonomy::new()
+            .rule("Residential", Some("Condominium"), PropertyCategory::Condo)
+            .rule("Residential", Some("Townhouse"), PropertyCategory::Townhome)
+            .rule("ResidentialLease", Some("Condominium"), PropertyCategory::Condo);
+        assert_eq!(
+            condo.filter_for(PropertyCategory::Condo).unwrap().build().unwrap(),
+            "PropertyType eq 'Residential' and PropertySubType eq 'Condominium' \
+             or PropertyType eq 'ResidentialLease' and PropertySubType eq 'Condominium'"
+        );
+        assert!(condo.filter_for(PropertyCategory::Land).is_none());
+        assert!(Taxonomy::new().extend_from_json(r#"{"Land": "acreage"}"#).is_err());
+    }
+}
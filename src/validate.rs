@@ -0,0 +1,577 @@
+//! Checking queries against a server's metadata before sending them.
+//!
+//! A misspelled field (`ListPice`) or a string compared with a number
+//! usually comes back from the server as a bare 400, sometimes only after
+//! a slow round trip. [`validate_query`] catches these locally from a
+//! parsed (and typically cached) [`ResoSchema`]: the resource must exist,
+//! selected, expanded, sorted, and filtered fields must be defined, sort
+//! fields must be sortable, and filter values must fit the field's type.
+
+use crate::metadata::{EntityType, Field, ResoSchema};
+use crate::query_string::ODataRequest;
+use reso_client::{Query, ResoError};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Everything wrong with a query, as found by [`validate_query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub problems: Vec<QueryProblem>,
+}
+
+/// One way a query does not fit the metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryProblem {
+    /// The rendered query could not be split into a path and options
+    Unparseable(String),
+    /// The server does not expose the resource
+    UnknownResource(String),
+    /// A field or navigation property the resource does not define
+    UnknownField {
+        /// The query option naming it, e.g. `$filter`
+        option: &'static str,
+        field: String,
+        /// A defined name within two edits, e.g. `ListPrice` for `ListPice`
+        suggestion: Option<String>,
+    },
+    /// An `$orderby` field whose type cannot be sorted, such as a collection
+    NotSortable { field: String, edm_type: String },
+    /// A `$filter` value of the wrong kind for the field, e.g. `ListPrice eq 'cheap'`
+    TypeMismatch {
+        field: String,
+        edm_type: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for QueryProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryProblem::Unparseable(message) => f.write_str(message),
+            QueryProblem::UnknownResource(resource) => {
+                write!(f, "resource '{}' is not in the server's metadata", resource)
+            }
+            QueryProblem::UnknownField {
+                option,
+                field,
+                suggestion,
+            } => {
+                write!(f, "{}: unknown field '{}'", option, field)?;
+                match suggestion {
+                    Some(suggestion) => write!(f, " (did you mean '{}'?)", suggestion),
+                    None => Ok(()),
+                }
+            }
+            QueryProblem::NotSortable { field, edm_type } => {
+                write!(f, "$orderby: '{}' ({}) cannot be sorted", field, edm_type)
+            }
+            QueryProblem::TypeMismatch { field, edm_type, value } => {
+                write!(f, "$filter: {} is not a valid {} for '{}'", value, edm_type, field)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = self.problems.iter().map(ToString::to_string).collect();
+        f.write_str(&problems.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Lets callers that return `ResoError` use `?` on [`validate_query`].
+impl From<ValidationError> for ResoError {
+    fn from(error: ValidationError) -> Self {
+        ResoError::InvalidQuery(error.to_string())
+    }
+}
+
+/// Checks a query against the server's metadata without sending it.
+///
+/// Reports every problem found rather than stopping at the first. Filter
+/// checks are best effort: fields inside function calls are checked for
+/// existence only, and lambda range variables (`any(m: m/...)`) are not
+/// resolved.
+///
+/// # Arguments
+///
+/// * `schema` - The server's metadata, from [`parse_metadata`](crate::metadata::parse_metadata)
+/// * `query` - The query to check
+///
+/// # Example
+///
+/// ```no_run
+/// use reso_examples::build_query;
+/// use reso_examples::metadata::parse_metadata;
+/// use reso_examples::validate::validate_query;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let schema = parse_metadata(&std::fs::read_to_string(".reso_cache/metadata.xml")?)?;
+/// let query = build_query("Property", Some("ListPice gt 500000"), Some(10))?;
+///
+/// if let Err(e) = validate_query(&schema, &query) {
+///     // $filter: unknown field 'ListPice' (did you mean 'ListPrice'?)
+///     eprintln!("{}", e);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn validate_query(schema: &ResoSchema, query: &Query) -> Result<(), ValidationError> {
+    let request = ODataRequest::from_query(query).map_err(|e| {
+        let message = match e {
+            ResoError::InvalidQuery(message) => message,
+            other => other.to_string(),
+        };
+        ValidationError {
+            problems: vec![QueryProblem::Unparseable(message)],
+        }
+    })?;
+    let resource = request.resource();
+
+    let Some(entity_type) = schema.resource(resource) else {
+        return Err(ValidationError {
+            problems: vec![QueryProblem::UnknownResource(resource.to_string())],
+        });
+    };
+
+    let mut checker = Checker {
+        schema,
+        entity_type,
+        problems: Vec::new(),
+    };
+    for (option, value) in &request.options {
+        match option.as_str() {
+            "$select" => checker.check_select(value),
+            "$expand" => checker.check_expand(value),
+            "$orderby" => checker.check_order_by(value),
+            "$filter" => checker.check_filter(value),
+            _ => {}
+        }
+    }
+
+    if checker.problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError {
+            problems: checker.problems,
+        })
+    }
+}
+
+struct Checker<'a> {
+    schema: &'a ResoSchema,
+    entity_type: &'a EntityType,
+    problems: Vec<QueryProblem>,
+}
+
+/// What a property path names.
+enum Target<'a> {
+    Field(&'a Field),
+    Navigation,
+    /// Through a navigation property whose type the metadata does not declare
+    Unchecked,
+}
+
+impl<'a> Checker<'a> {
+    fn check_select(&mut self, select: &str) {
+        for field in list_items(select) {
+            self.resolve("$select", field);
+        }
+    }
+
+    fn check_expand(&mut self, expand: &str) {
+        for item in list_items(expand) {
+            // Nested options, e.g. `Media($select=MediaURL)`, are not checked
+            let name = item.split('(').next().unwrap_or(item).trim();
+            if self.entity_type.navigation_property(name).is_none() {
+                let candidates = self.entity_type.navigation.iter().map(|n| n.name.as_str());
+                self.problems.push(QueryProblem::UnknownField {
+                    option: "$expand",
+                    field: name.to_string(),
+                    suggestion: suggest(name, candidates),
+                });
+            }
+        }
+    }
+
+    fn check_order_by(&mut self, order_by: &str) {
+        for item in list_items(order_by) {
+            let path = item.split_whitespace().next().unwrap_or_default();
+            match self.resolve("$orderby", path) {
+                Some(Target::Field(field)) if !is_sortable(&field.edm_type) => {
+                    self.problems.push(QueryProblem::NotSortable {
+                        field: path.to_string(),
+                        edm_type: field.edm_type.clone(),
+                    })
+                }
+                Some(Target::Navigation) => self.problems.push(QueryProblem::NotSortable {
+                    field: path.to_string(),
+                    edm_type: "navigation property".to_string(),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_filter(&mut self, filter: &str) {
+        let tokens = tokenize(filter);
+        let mut range_variables = HashSet::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            let Token::Path(path) = token else { continue };
+            let next = tokens.get(i + 1);
+            if let Some(Token::Colon) = next {
+                range_variables.insert(path.as_str());
+                continue;
+            }
+            let first = path.split('/').next().unwrap_or_default();
+            if range_variables.contains(first) || first.starts_with('$') || is_keyword(path) {
+                continue;
+            }
+            if let Some(Token::Open) = next {
+                // A function call, or a lambda on a collection: `Media/any(...)`
+                if let Some((collection, "any" | "all")) = path.rsplit_once('/') {
+                    self.resolve("$filter", collection);
+                }
+                continue;
+            }
+
+            let Some(Target::Field(field)) = self.resolve("$filter", path) else {
+                continue;
+            };
+            for value in compared_values(&tokens, i) {
+                if !value.fits(&field.edm_type) {
+                    self.problems.push(QueryProblem::TypeMismatch {
+                        field: path.clone(),
+                        edm_type: field.edm_type.clone(),
+                        value: value.text(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Resolves a property path, recording a problem if it names nothing.
+    fn resolve(&mut self, option: &'static str, path: &str) -> Option<Target<'a>> {
+        let mut entity_type = self.entity_type;
+        let mut segments = path.split('/').peekable();
+
+        while let Some(segment) = segments.next() {
+            let last = segments.peek().is_none();
+            if let Some(field) = entity_type.field(segment) {
+                // Complex-typed fields are not described by the schema model
+                return Some(if last { Target::Field(field) } else { Target::Unchecked });
+            }
+            let Some(navigation) = entity_type.navigation_property(segment) else {
+                let candidates = entity_type
+                    .fields
+                    .iter()
+                    .map(|f| f.name.as_str())
+                    .chain(entity_type.navigation.iter().map(|n| n.name.as_str()));
+                self.problems.push(QueryProblem::UnknownField {
+                    option,
+                    field: path.to_string(),
+                    suggestion: suggest(segment, candidates),
+                });
+                return None;
+            };
+            if last {
+                return Some(Target::Navigation);
+            }
+            match self.schema.entity_type(&navigation.target_type) {
+                Some(target) => entity_type = target,
+                None => return Some(Target::Unchecked),
+            }
+        }
+        None
+    }
+}
+
+fn list_items(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+fn is_sortable(edm_type: &str) -> bool {
+    !(edm_type.starts_with("Collection(")
+        || edm_type == "Edm.Stream"
+        || edm_type.starts_with("Edm.Geography")
+        || edm_type.starts_with("Edm.Geometry"))
+}
+
+/// Operators, literals, and `$orderby` directions that look like property names.
+pub(crate) fn is_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "and" | "or" | "not" | "eq" | "ne" | "gt" | "ge" | "lt" | "le" | "in" | "has" | "add" | "sub" | "mul"
+            | "div" | "mod" | "true" | "false" | "null" | "INF" | "NaN"
+    )
+}
+
+/// The closest of `candidates` to `name` within two edits, ignoring case.
+fn suggest<'c>(name: &str, candidates: impl Iterator<Item = &'c str>) -> Option<String> {
+    candidates
+        .map(|candidate| (edit_distance(&name.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    /// A name or property path, e.g. `ListOffice/OfficeName`
+    Path(String),
+    Literal(Literal),
+    Open,
+    Close,
+    Comma,
+    Colon,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Literal {
+    String(String),
+    /// A literal with a type prefix, e.g. `Namespace.Enum'Value'`
+    Typed(String),
+    Integer(String),
+    Decimal(String),
+    Date(String),
+    DateTimeOffset(String),
+    Boolean(String),
+    Null,
+}
+
+impl Literal {
+    /// Whether the literal may be compared with a field of `edm_type`.
+    fn fits(&self, edm_type: &str) -> bool {
+        match (self, edm_type) {
+            (Literal::Null, _) | (Literal::Typed(_), _) => true,
+            (Literal::String(_), "Edm.String" | "Edm.Guid") => true,
+            (Literal::Integer(_), "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" | "Edm.Int64") => true,
+            (Literal::Integer(_) | Literal::Decimal(_), "Edm.Decimal" | "Edm.Double" | "Edm.Single") => true,
+            (Literal::Boolean(_), "Edm.Boolean") => true,
+            (Literal::Date(_), "Edm.Date" | "Edm.DateTimeOffset") => true,
+            (Literal::DateTimeOffset(_), "Edm.DateTimeOffset") => true,
+            // Enumerations are commonly filtered with plain strings
+            (Literal::String(_), other) => !other.starts_with("Edm."),
+            (_, other) => !other.starts_with("Edm.") && !other.starts_with("Collection("),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Literal::String(s) => format!("'{}'", s),
+            Literal::Typed(s)
+            | Literal::Integer(s)
+            | Literal::Decimal(s)
+            | Literal::Date(s)
+            | Literal::DateTimeOffset(s)
+            | Literal::Boolean(s) => s.clone(),
+            Literal::Null => "null".to_string(),
+        }
+    }
+}
+
+/// The literals the path at `index` is compared with: `Field op value`,
+/// `Field in (values)`, or `function(Field,value)`.
+fn compared_values(tokens: &[Token], index: usize) -> Vec<&Literal> {
+    let literal = |i: usize| match tokens.get(i) {
+        Some(Token::Literal(literal)) => Some(literal),
+        _ => None,
+    };
+    match tokens.get(index + 1) {
+        Some(Token::Path(op)) if matches!(op.as_str(), "eq" | "ne" | "gt" | "ge" | "lt" | "le") => {
+            literal(index + 2).into_iter().collect()
+        }
+        Some(Token::Path(op)) if op == "in" && tokens.get(index + 2) == Some(&Token::Open) => tokens
+            [index + 3..]
+            .iter()
+            .take_while(|token| **token != Token::Close)
+            .filter_map(|token| match token {
+                Token::Literal(literal) => Some(literal),
+                _ => None,
+            })
+            .collect(),
+        Some(Token::Comma) if index >= 2 && tokens[index - 1] == Token::Open => {
+            literal(index + 2).into_iter().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Splits a filter into names, literals, and punctuation.
+pub(crate) fn tokenize(filter: &str) -> Vec<Token> {
+    let chars: Vec<char> = filter.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // Reads a quoted string starting at `start`, returning its content and the index after it
+    let quoted = |start: usize| {
+        let mut content = String::new();
+        let mut i = start + 1;
+        while i < chars.len() {
+            if chars[i] == '\'' {
+                if chars.get(i + 1) == Some(&'\'') {
+                    content.push('\'');
+                    i += 2;
+                    continue;
+                }
+                return (content, i + 1);
+            }
+            content.push(chars[i]);
+            i += 1;
+        }
+        (content, i)
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            ':' => tokens.push(Token::Colon),
+            '\'' => {
+                let (content, end) = quoted(i);
+                tokens.push(Token::Literal(Literal::String(content)));
+                i = end;
+                continue;
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '-' | '+' | ':' | '.')) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Literal(number_or_date(text)));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '.' | '/')) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if chars.get(i) == Some(&'\'') {
+                    let (content, end) = quoted(i);
+                    tokens.push(Token::Literal(Literal::Typed(format!("{}'{}'", word, content))));
+                    i = end;
+                    continue;
+                }
+                tokens.push(match word.as_str() {
+                    "true" | "false" => Token::Literal(Literal::Boolean(word)),
+                    "null" => Token::Literal(Literal::Null),
+                    _ => Token::Path(word),
+                });
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    tokens
+}
+
+fn number_or_date(text: String) -> Literal {
+    let digits = text.trim_start_matches('-');
+    if digits.contains('T') {
+        Literal::DateTimeOffset(text)
+    } else if digits.contains('-') {
+        Literal::Date(text)
+    } else if digits.contains(['.', 'e', 'E']) {
+        Literal::Decimal(text)
+    } else {
+        Literal::Integer(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{parse_metadata, SAMPLE_METADATA};
+    use reso_client::QueryBuilder;
+
+    #[test]
+    fn test_validate_query_reports_every_problem() {
+        let schema = parse_metadata(SAMPLE_METADATA).unwrap();
+
+        let valid = QueryBuilder::new("Property")
+            .filter("ListPrice ge 300000 and (City eq 'O''Fallon' or X_VendorFlag eq true) and ListAgent/MemberFullName ne null")
+            .select(&["ListingKey", "City"])
+            .expand(&["Media"])
+            .order_by("ListPrice", "desc")
+            .top(10)
+            .build()
+            .unwrap();
+        assert_eq!(validate_query(&schema, &valid), Ok(()));
+        let functions = QueryBuilder::new("Property")
+            .filter("contains(City,'Aus') and Media/any(m: m/Order eq 1) and City in ('Austin','Dallas')")
+            .build()
+            .unwrap();
+        assert_eq!(validate_query(&schema, &functions), Ok(()));
+
+        let invalid = QueryBuilder::new("Property")
+            .filter("ListPice gt 500000 and City eq 12 and ListPrice in (1,'two')")
+            .select(&["ListingKey", "Cty"])
+            .expand(&["Photos"])
+            .order_by("Media", "asc")
+            .build()
+            .unwrap();
+        let error = validate_query(&schema, &invalid).unwrap_err();
+        assert_eq!(
+            error.problems,
+            [
+                QueryProblem::UnknownField {
+                    option: "$filter",
+                    field: "ListPice".into(),
+                    suggestion: Some("ListPrice".into())
+                },
+                QueryProblem::TypeMismatch {
+                    field: "City".into(),
+                    edm_type: "Edm.String".into(),
+                    value: "12".into()
+                },
+                QueryProblem::TypeMismatch {
+                    field: "ListPrice".into(),
+                    edm_type: "Edm.Decimal".into(),
+                    value: "'two'".into()
+                },
+                QueryProblem::UnknownField {
+                    option: "$select",
+                    field: "Cty".into(),
+                    suggestion: Some("City".into())
+                },
+                QueryProblem::UnknownField {
+                    option: "$expand",
+                    field: "Photos".into(),
+                    suggestion: None
+                },
+                QueryProblem::NotSortable {
+                    field: "Media".into(),
+                    edm_type: "navigation property".into()
+                },
+            ]
+        );
+        assert!(error.to_string().starts_with("$filter: unknown field 'ListPice' (did you mean 'ListPrice'?)"));
+
+        let unknown = QueryBuilder::new("Listing").build().unwrap();
+        let error: ResoError = validate_query(&schema, &unknown).unwrap_err().into();
+        assert!(matches!(error, ResoError::InvalidQuery(m) if m.contains("'Listing'")));
+    }
+}